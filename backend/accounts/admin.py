@@ -5,7 +5,7 @@ from django.urls import reverse
 from unfold.admin import ModelAdmin
 from unfold.decorators import display
 
-from .models import User, Profile, UserSession, Notification
+from .models import User, Profile, UserSession, Notification, SyncedEntity
 
 
 @admin.register(User)
@@ -148,4 +148,23 @@ class NotificationAdmin(ModelAdmin):
     @display(description="Read")
     def read_status(self, obj):
         # Check if notification has been read (you can add a read_at field to model)
-        return '✓' if hasattr(obj, 'read_at') and obj.read_at else '✗'
\ No newline at end of file
+        return '✓' if hasattr(obj, 'read_at') and obj.read_at else '✗'
+
+@admin.register(SyncedEntity)
+class SyncedEntityAdmin(ModelAdmin):
+    list_display = ['user', 'entity_type', 'entity_uuid', 'version', 'deleted', 'source_device', 'updated_at']
+    list_filter = ['entity_type', 'deleted', 'updated_at']
+    search_fields = ['user__email', 'entity_uuid']
+    readonly_fields = ['created_at', 'updated_at']
+    
+    fieldsets = (
+        ('User', {
+            'fields': ('user', 'source_device')
+        }),
+        ('Entity', {
+            'fields': ('entity_type', 'entity_uuid', 'version', 'deleted', 'data')
+        }),
+        ('Timestamps', {
+            'fields': ('created_at', 'updated_at')
+        }),
+    )
@@ -0,0 +1,36 @@
+# Generated by Django 6.0.1 on 2026-10-16 09:12
+
+import django.db.models.deletion
+from django.conf import settings
+from django.db import migrations, models
+
+
+class Migration(migrations.Migration):
+
+    dependencies = [
+        ('accounts', '0001_initial'),
+    ]
+
+    operations = [
+        migrations.CreateModel(
+            name='SyncedEntity',
+            fields=[
+                ('id', models.BigAutoField(auto_created=True, primary_key=True, serialize=False, verbose_name='ID')),
+                ('entity_type', models.CharField(max_length=50)),
+                ('entity_uuid', models.CharField(max_length=64)),
+                ('version', models.IntegerField(default=0)),
+                ('data', models.JSONField(default=dict)),
+                ('deleted', models.BooleanField(default=False)),
+                ('source_device', models.CharField(blank=True, max_length=64)),
+                ('created_at', models.DateTimeField(auto_now_add=True)),
+                ('updated_at', models.DateTimeField(auto_now=True)),
+                ('user', models.ForeignKey(on_delete=django.db.models.deletion.CASCADE, related_name='synced_entities', to=settings.AUTH_USER_MODEL)),
+            ],
+            options={
+                'db_table': 'synced_entities',
+                'ordering': ['updated_at'],
+                'indexes': [models.Index(fields=['user', 'updated_at'], name='synced_entity_user_updated')],
+                'unique_together': {('user', 'entity_type', 'entity_uuid')},
+            },
+        ),
+    ]
@@ -104,4 +104,27 @@ class Notification(models.Model):
         ordering = ['-created_at']
     
     def __str__(self):
-        return f"{self.user.email} - {self.title}"
\ No newline at end of file
+        return f"{self.user.email} - {self.title}"
+
+class SyncedEntity(models.Model):
+    """Latest copy of a row pushed by a desktop client"""
+    user = models.ForeignKey(User, on_delete=models.CASCADE, related_name='synced_entities')
+    entity_type = models.CharField(max_length=50)
+    entity_uuid = models.CharField(max_length=64)
+    version = models.IntegerField(default=0)
+    data = models.JSONField(default=dict)
+    deleted = models.BooleanField(default=False)
+    source_device = models.CharField(max_length=64, blank=True)
+    created_at = models.DateTimeField(auto_now_add=True)
+    updated_at = models.DateTimeField(auto_now=True)
+    
+    class Meta:
+        db_table = 'synced_entities'
+        ordering = ['updated_at']
+        unique_together = ('user', 'entity_type', 'entity_uuid')
+        indexes = [
+            models.Index(fields=['user', 'updated_at'], name='synced_entity_user_updated'),
+        ]
+    
+    def __str__(self):
+        return f"{self.user.email} - {self.entity_type} {self.entity_uuid} v{self.version}"
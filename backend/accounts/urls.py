@@ -38,6 +38,7 @@ urlpatterns = [
     path('account/export/', views.ExportAccountDataView.as_view(), name='export_account_data'),
     path('account/sessions/', views.ActiveSessionsView.as_view(), name='active_sessions'),
     path('account/cache/clear/', views.ClearLocalCacheView.as_view(), name='clear_cache'),
+    path('account/device/deprovision/', views.DeviceDeprovisionView.as_view(), name='deprovision_device'),
     path('account/delete/', views.DeleteAccountView.as_view(), name='delete_account'),
     
     # Notifications
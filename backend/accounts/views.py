@@ -12,6 +12,7 @@ from django.http import JsonResponse, HttpResponse
 from django.contrib.auth.tokens import default_token_generator
 from django.utils.http import urlsafe_base64_encode, urlsafe_base64_decode
 from django.utils.encoding import force_bytes, force_str
+from django.utils.dateparse import parse_datetime
 from django.core.mail import send_mail
 from django.conf import settings
 from django.db.models import Count, Q
@@ -27,7 +28,7 @@ from .serializers import (
     ChangePasswordSerializer, UserSessionSerializer,
     NotificationSerializer, SecuritySettingsSerializer
 )
-from .models import User, Profile, UserSession, Notification
+from .models import User, Profile, UserSession, Notification, SyncedEntity
 from projects.models import Project, ProjectMembership
 from workspaces.models import Workspace, WorkspaceMembership
 from django.db import connection
@@ -657,6 +658,42 @@ class ClearLocalCacheView(APIView):
         
         return Response({'message': 'Cache clear signal sent'})
 
+class DeviceDeprovisionView(APIView):
+    """Record that a desktop device wiped its local copy of the user's data"""
+    permission_classes = [IsAuthenticated]
+    
+    def post(self, request):
+        device_name = request.data.get('device_name') or request.META.get('HTTP_USER_AGENT', 'Unknown')
+        workspace_uuid = request.data.get('workspace_uuid')
+        targets = request.data.get('targets') or []
+        verified = bool(request.data.get('verified', False))
+        
+        logger.warning(
+            f"Device deprovisioned by {request.user.email}: {device_name} "
+            f"(scope: {workspace_uuid or 'all'}, verified: {verified})"
+        )
+        
+        try:
+            AuditLog.objects.create(
+                user=request.user,
+                action='device_deprovisioned',
+                resource_type='user',
+                resource_id=request.user.id,
+                details={
+                    'timestamp': timezone.now().isoformat(),
+                    'device_name': device_name,
+                    'workspace_uuid': workspace_uuid,
+                    'targets': targets,
+                    'verified': verified,
+                },
+                ip_address=getattr(request, 'audit_ip', None),
+                user_agent=getattr(request, 'audit_user_agent', '')
+            )
+        except Exception as e:
+            logger.error(f"Failed to create audit log: {e}")
+        
+        return Response({'message': 'Device deprovisioning recorded'})
+
 class DeleteAccountView(APIView):
     """Delete user account"""
     permission_classes = [IsAuthenticated]
@@ -794,26 +831,64 @@ def health_check(request):
 @api_view(['POST'])
 @permission_classes([IsAuthenticated])
 def sync_metadata(request):
-    """Sync lightweight metadata from desktop client"""
+    """Sync lightweight metadata from desktop client
+    
+    Applies the pushed `items` and answers each one in `results`, so the
+    client only drops what was actually stored. A request carrying `since`
+    is a pull: `changes` lists what other devices stored after that time.
+    """
     try:
         sync_data = request.data
         user = request.user
+        device_id = str(sync_data.get('device_id') or '')[:64]
+        now = timezone.now()
         
         # Update last sync timestamp
         user.last_sync = timezone.now()
         user.offline_grace_expires = timezone.now() + timedelta(days=7)
         user.save(update_fields=['last_sync', 'offline_grace_expires'])
         
-        # Process sync queue (project metadata, published artifacts, etc.)
-        # This will be expanded as we build projects/workspaces
-        
-        logger.info(f"Metadata sync completed for: {user.email}")
+        results = [
+            _apply_sync_item(user, device_id, item)
+            for item in sync_data.get('items') or []
+        ]
         
-        return Response({
+        response = {
             'status': 'synced',
-            'timestamp': timezone.now().isoformat(),
-            'next_sync_recommended': (timezone.now() + timedelta(hours=1)).isoformat()
-        })
+            'results': results,
+            'timestamp': now.isoformat(),
+            'next_sync_recommended': (now + timedelta(hours=1)).isoformat()
+        }
+        
+        if 'since' in sync_data:
+            raw_since = sync_data.get('since')
+            since = parse_datetime(raw_since) if raw_since else None
+            if raw_since and since is None:
+                return Response({'error': 'Invalid since timestamp'}, status=status.HTTP_400_BAD_REQUEST)
+            
+            # Deleted rows aren't sent; the client can't apply a delete yet
+            changed = SyncedEntity.objects.filter(user=user, deleted=False, updated_at__lte=now)
+            if since:
+                changed = changed.filter(updated_at__gt=since)
+            if device_id:
+                changed = changed.exclude(source_device=device_id)
+            
+            # Oldest first, so the client can stop its cursor at a change
+            # it failed to apply
+            response['changes'] = [
+                {
+                    'entity_type': entity.entity_type,
+                    'uuid': entity.entity_uuid,
+                    'version': entity.version,
+                    'data': entity.data,
+                    'updated_at': entity.updated_at.isoformat(),
+                }
+                for entity in changed.order_by('updated_at', 'id')
+            ]
+        
+        logger.info(f"Metadata sync completed for: {user.email} ({len(results)} items)")
+        
+        return Response(response)
     except Exception as e:
         logger.error(f"Sync failed for {request.user.email}: {str(e)}")
         return Response({
@@ -822,6 +897,55 @@ def sync_metadata(request):
         }, status=status.HTTP_500_INTERNAL_SERVER_ERROR)
 
 
+def _apply_sync_item(user, device_id, item):
+    """Store one pushed item, returning the client's per-item result"""
+    item_id = item.get('id') if isinstance(item, dict) else None
+    if item_id is None:
+        return {'id': None, 'ok': False, 'error': 'Item has no id'}
+    
+    entity_type = item.get('entity_type')
+    entity_uuid = item.get('entity_uuid')
+    action = item.get('action')
+    payload = item.get('payload')
+    if not entity_type or not entity_uuid:
+        return {'id': item_id, 'ok': False, 'error': 'entity_type and entity_uuid are required'}
+    if action not in ('create', 'update', 'delete'):
+        return {'id': item_id, 'ok': False, 'error': f"Unknown action: {action}"}
+    if action != 'delete' and not isinstance(payload, dict):
+        return {'id': item_id, 'ok': False, 'error': 'payload must be an object'}
+    
+    try:
+        with transaction.atomic():
+            entity, _ = SyncedEntity.objects.select_for_update().get_or_create(
+                user=user,
+                entity_type=entity_type,
+                entity_uuid=entity_uuid,
+            )
+            
+            if action == 'delete':
+                entity.deleted = True
+            elif item.get('changed_fields'):
+                # A delta only carries the fields that changed
+                entity.data = {**(entity.data or {}), **payload}
+                entity.deleted = False
+            else:
+                entity.data = payload
+                entity.deleted = False
+            
+            # Keep the client's version when it is ahead, so both sides agree
+            client_version = payload.get('version') if isinstance(payload, dict) else None
+            if not isinstance(client_version, int):
+                client_version = 0
+            entity.version = max(entity.version + 1, client_version)
+            entity.source_device = device_id
+            entity.save()
+        
+        return {'id': item_id, 'ok': True, 'server_version': entity.version}
+    except Exception as e:
+        logger.warning(f"Sync item {item_id} rejected for {user.email}: {str(e)}")
+        return {'id': item_id, 'ok': False, 'error': str(e)}
+
+
 
 
 @api_view(['GET'])
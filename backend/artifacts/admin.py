@@ -0,0 +1,34 @@
+from django.contrib import admin
+from unfold.admin import ModelAdmin
+from unfold.decorators import display
+
+from .models import ArtifactUpload
+
+
+@admin.register(ArtifactUpload)
+class ArtifactUploadAdmin(ModelAdmin):
+    list_display = ['file_name', 'user', 'entity_type', 'progress_display', 'completed_at', 'created_at']
+    list_filter = ['entity_type', 'created_at']
+    search_fields = ['user__email', 'file_name', 'entity_uuid']
+    readonly_fields = ['uuid', 'created_at', 'updated_at', 'completed_at']
+    
+    fieldsets = (
+        ('Upload', {
+            'fields': ('uuid', 'user', 'file_name', 'file')
+        }),
+        ('Entity', {
+            'fields': ('entity_type', 'entity_uuid')
+        }),
+        ('Progress', {
+            'fields': ('total_bytes', 'bytes_received', 'chunks_received')
+        }),
+        ('Timestamps', {
+            'fields': ('created_at', 'updated_at', 'completed_at')
+        }),
+    )
+    
+    @display(description="Progress")
+    def progress_display(self, obj):
+        if not obj.total_bytes:
+            return '—'
+        return f"{obj.bytes_received * 100 // obj.total_bytes}%"
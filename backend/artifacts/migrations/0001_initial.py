@@ -0,0 +1,39 @@
+# Generated by Django 6.0.1 on 2026-10-16 09:40
+
+import django.db.models.deletion
+from django.conf import settings
+from django.db import migrations, models
+
+
+class Migration(migrations.Migration):
+
+    initial = True
+
+    dependencies = [
+        migrations.swappable_dependency(settings.AUTH_USER_MODEL),
+    ]
+
+    operations = [
+        migrations.CreateModel(
+            name='ArtifactUpload',
+            fields=[
+                ('id', models.BigAutoField(auto_created=True, primary_key=True, serialize=False, verbose_name='ID')),
+                ('uuid', models.UUIDField(unique=True)),
+                ('entity_type', models.CharField(max_length=50)),
+                ('entity_uuid', models.CharField(max_length=64)),
+                ('file_name', models.CharField(max_length=255)),
+                ('file', models.FileField(blank=True, upload_to='artifact_uploads/')),
+                ('total_bytes', models.BigIntegerField()),
+                ('bytes_received', models.BigIntegerField(default=0)),
+                ('chunks_received', models.IntegerField(default=0)),
+                ('created_at', models.DateTimeField(auto_now_add=True)),
+                ('updated_at', models.DateTimeField(auto_now=True)),
+                ('completed_at', models.DateTimeField(blank=True, null=True)),
+                ('user', models.ForeignKey(on_delete=django.db.models.deletion.CASCADE, related_name='artifact_uploads', to=settings.AUTH_USER_MODEL)),
+            ],
+            options={
+                'db_table': 'artifact_uploads',
+                'ordering': ['-created_at'],
+            },
+        ),
+    ]
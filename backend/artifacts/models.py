@@ -0,0 +1,37 @@
+from django.db import models
+from django.conf import settings
+
+
+class ArtifactUpload(models.Model):
+    """File uploaded from a desktop client in resumable chunks"""
+    uuid = models.UUIDField(unique=True)
+    user = models.ForeignKey(
+        settings.AUTH_USER_MODEL,
+        on_delete=models.CASCADE,
+        related_name='artifact_uploads'
+    )
+    
+    # What the file belongs to on the client
+    entity_type = models.CharField(max_length=50)
+    entity_uuid = models.CharField(max_length=64)
+    file_name = models.CharField(max_length=255)
+    file = models.FileField(upload_to='artifact_uploads/', blank=True)
+    
+    total_bytes = models.BigIntegerField()
+    bytes_received = models.BigIntegerField(default=0)
+    chunks_received = models.IntegerField(default=0)
+    
+    created_at = models.DateTimeField(auto_now_add=True)
+    updated_at = models.DateTimeField(auto_now=True)
+    completed_at = models.DateTimeField(null=True, blank=True)
+    
+    class Meta:
+        db_table = 'artifact_uploads'
+        ordering = ['-created_at']
+    
+    def __str__(self):
+        return f"{self.file_name} ({self.bytes_received}/{self.total_bytes} bytes)"
+    
+    @property
+    def is_complete(self):
+        return self.completed_at is not None
@@ -0,0 +1,7 @@
+from django.urls import path
+from . import views
+
+urlpatterns = [
+    # Resumable uploads from the desktop client, one chunk per PUT
+    path('uploads/<uuid:upload_uuid>/', views.UploadChunkView.as_view(), name='upload_chunk'),
+]
@@ -0,0 +1,96 @@
+from rest_framework import status
+from rest_framework.response import Response
+from rest_framework.views import APIView
+from rest_framework.permissions import IsAuthenticated
+from django.conf import settings
+from django.db import transaction
+from django.utils import timezone
+from django.utils.text import get_valid_filename
+import logging
+import re
+
+from .models import ArtifactUpload
+
+logger = logging.getLogger(__name__)
+
+CONTENT_RANGE = re.compile(r'^bytes (\d+)-(\d+)/(\d+)$')
+
+
+def _parse_content_range(header):
+    """Returns (start, end, total) from a `bytes start-end/total` header"""
+    match = CONTENT_RANGE.match(header.strip())
+    if not match:
+        return None
+    start, end, total = (int(group) for group in match.groups())
+    if start > end or end >= total:
+        return None
+    return start, end, total
+
+
+def _upload_state(upload):
+    return {
+        'uuid': str(upload.uuid),
+        'bytes_received': upload.bytes_received,
+        'total_bytes': upload.total_bytes,
+        'completed': upload.is_complete,
+    }
+
+
+class UploadChunkView(APIView):
+    """
+    Receives one chunk of a resumable upload
+    The first chunk creates the upload; later ones must continue where the
+    stored bytes end, so an interrupted client resumes from its last
+    acknowledged chunk. Resending a stored chunk is harmless.
+    """
+    permission_classes = [IsAuthenticated]
+    
+    def put(self, request, upload_uuid):
+        content_range = _parse_content_range(request.headers.get('Content-Range', ''))
+        if content_range is None:
+            return Response({'error': 'A valid Content-Range header is required'}, status=status.HTTP_400_BAD_REQUEST)
+        start, end, total = content_range
+        
+        chunk = request.body
+        if len(chunk) != end - start + 1:
+            return Response({'error': 'Chunk size does not match Content-Range'}, status=status.HTTP_400_BAD_REQUEST)
+        
+        with transaction.atomic():
+            upload, created = ArtifactUpload.objects.select_for_update().get_or_create(
+                uuid=upload_uuid,
+                defaults={
+                    'user': request.user,
+                    'entity_type': request.headers.get('X-Entity-Type', ''),
+                    'entity_uuid': request.headers.get('X-Entity-Uuid', ''),
+                    'file_name': get_valid_filename(request.headers.get('X-File-Name', '')) or str(upload_uuid),
+                    'total_bytes': total,
+                }
+            )
+            
+            if upload.user_id != request.user.id:
+                return Response({'error': 'Upload not found'}, status=status.HTTP_404_NOT_FOUND)
+            if total != upload.total_bytes:
+                return Response({'error': 'Total size changed mid-upload'}, status=status.HTTP_400_BAD_REQUEST)
+            if upload.is_complete:
+                return Response(_upload_state(upload))
+            if start > upload.bytes_received:
+                # A chunk went missing; the client has to resend from here
+                return Response(_upload_state(upload), status=status.HTTP_409_CONFLICT)
+            
+            relative_path = f"artifact_uploads/{request.user.id}/{upload.uuid}/{upload.file_name}"
+            path = settings.MEDIA_ROOT / relative_path
+            path.parent.mkdir(parents=True, exist_ok=True)
+            with open(path, 'r+b' if path.exists() else 'wb') as file:
+                file.seek(start)
+                file.write(chunk)
+            
+            if end + 1 > upload.bytes_received:
+                upload.bytes_received = end + 1
+                upload.chunks_received += 1
+            upload.file.name = relative_path
+            if upload.bytes_received >= upload.total_bytes:
+                upload.completed_at = timezone.now()
+                logger.info(f"Artifact upload completed: {upload.file_name} ({upload.total_bytes} bytes) by {request.user.email}")
+            upload.save()
+        
+        return Response(_upload_state(upload), status=status.HTTP_201_CREATED if created else status.HTTP_200_OK)
@@ -42,6 +42,7 @@ INSTALLED_APPS = [
     'projects',
     'audit',
     'workspaces',
+    'artifacts',
 ]
 
 MIDDLEWARE = [
@@ -21,6 +21,7 @@ urlpatterns = [
     path('api/audit/', include('audit.urls')),
     path('api/workspaces/', include('workspaces.urls')),
     path('api/projects/', include('projects.urls')),
+    path('api/artifacts/', include('artifacts.urls')),
     
     # JWT token refresh
     path('api/token/refresh/', TokenRefreshView.as_view(), name='token_refresh'),
@@ -1,9 +1,59 @@
 from fastapi import APIRouter
 from datetime import datetime
+from importlib import metadata
+import importlib.util
+import platform
+import sys
 import psutil
 
 router = APIRouter()
 
+SERVER_PACKAGES = ["fastapi", "uvicorn", "pydantic", "starlette"]
+SCIENTIFIC_PACKAGES = [
+    "duckdb", "numpy", "pandas", "polars", "pyarrow", "scipy",
+    "scikit-learn", "statsmodels", "torch", "cupy",
+]
+
+# Kept across requests so cpu_percent() measures since the previous call
+_engine_process = psutil.Process()
+_app_processes = {}
+
+
+def _engine_disk_write_bytes():
+    # io_counters() isn't available on macOS
+    try:
+        return _engine_process.io_counters().write_bytes
+    except (AttributeError, psutil.Error):
+        return None
+
+
+def _app_usage():
+    """CPU and memory of the desktop app: the engine's parent and all of its
+    children (this engine, other project engines, their workers)"""
+    try:
+        parent = _engine_process.parent()
+        if parent is None:
+            return None, None
+        processes = [parent] + parent.children(recursive=True)
+    except psutil.Error:
+        return None, None
+
+    cpu_percent = 0.0
+    memory_mb = 0.0
+    live = {}
+    for process in processes:
+        process = _app_processes.get(process.pid, process)
+        try:
+            cpu_percent += process.cpu_percent(interval=None)
+            memory_mb += process.memory_info().rss / (1024**2)
+        except psutil.Error:
+            continue
+        live[process.pid] = process
+
+    _app_processes.clear()
+    _app_processes.update(live)
+    return cpu_percent, memory_mb
+
 @router.get("")
 async def health_check():
     """Simple health check endpoint for Tauri to detect if engine is ready"""
@@ -21,6 +71,7 @@ async def detailed_status():
         cpu_percent = psutil.cpu_percent(interval=0.1)
         memory = psutil.virtual_memory()
         disk = psutil.disk_usage('/')
+        app_cpu_percent, app_memory_mb = _app_usage()
         
         return {
             "status": "healthy",
@@ -34,6 +85,11 @@ async def detailed_status():
                 "memory_total_gb": memory.total / (1024**3),
                 "disk_available_gb": disk.free / (1024**3),
                 "disk_total_gb": disk.total / (1024**3),
+                "engine_cpu_percent": _engine_process.cpu_percent(interval=None),
+                "engine_memory_mb": _engine_process.memory_info().rss / (1024**2),
+                "engine_disk_write_bytes": _engine_disk_write_bytes(),
+                "app_cpu_percent": app_cpu_percent,
+                "app_memory_mb": app_memory_mb,
             }
         }
     except Exception as e:
@@ -43,4 +99,64 @@ async def detailed_status():
             "timestamp": datetime.utcnow().isoformat(),
             "mode": "embedded",
             "error": str(e)
-        }
\ No newline at end of file
+        }
+
+
+@router.get("/gpu")
+async def gpu_status():
+    """GPU acceleration available to Python libraries in this engine"""
+    info = {
+        "cuda_available": False,
+        "mps_available": False,
+        "cuda_version": None,
+        "devices": [],
+        "frameworks": [],
+    }
+
+    if importlib.util.find_spec("torch") is not None:
+        try:
+            import torch
+
+            info["frameworks"].append("torch")
+            if torch.cuda.is_available():
+                info["cuda_available"] = True
+                info["cuda_version"] = torch.version.cuda
+                for index in range(torch.cuda.device_count()):
+                    props = torch.cuda.get_device_properties(index)
+                    info["devices"].append({
+                        "name": props.name,
+                        "vram_total_mb": props.total_memory // (1024 ** 2),
+                    })
+            mps = getattr(torch.backends, "mps", None)
+            if mps is not None and mps.is_available():
+                info["mps_available"] = True
+        except Exception as e:
+            info["error"] = str(e)
+
+    if importlib.util.find_spec("cupy") is not None:
+        info["frameworks"].append("cupy")
+
+    return info
+
+
+def _package_versions(names):
+    versions = {}
+    for name in names:
+        try:
+            versions[name] = metadata.version(name)
+        except metadata.PackageNotFoundError:
+            continue
+    return versions
+
+
+@router.get("/info")
+async def engine_info():
+    """Interpreter and package versions for the diagnostics screen"""
+    return {
+        "python_version": platform.python_version(),
+        "python_implementation": platform.python_implementation(),
+        "python_executable": sys.executable,
+        "platform": platform.platform(),
+        "server_packages": _package_versions(SERVER_PACKAGES),
+        "scientific_packages": _package_versions(SCIENTIFIC_PACKAGES),
+    }
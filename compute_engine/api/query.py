@@ -0,0 +1,54 @@
+"""
+Query API
+Executes SQL against the local DuckDB database
+"""
+from fastapi import APIRouter, HTTPException
+from pydantic import BaseModel
+from typing import Optional
+import logging
+
+from core.database import duckdb_manager
+from api.sessions import session_cursor
+
+router = APIRouter()
+logger = logging.getLogger(__name__)
+
+
+class QueryRequest(BaseModel):
+    sql: str
+    limit: int = 1000
+    # Run in a notebook session's cursor, seeing its temp tables and settings
+    session_id: Optional[str] = None
+
+
+@router.post("/execute")
+async def execute_query(request: QueryRequest):
+    """
+    Execute a SQL statement and return up to `limit` rows
+    """
+    try:
+        if request.session_id:
+            cursor = session_cursor(request.session_id).execute(request.sql)
+        else:
+            cursor = duckdb_manager.execute(request.sql)
+        columns = [column[0] for column in cursor.description] if cursor.description else []
+        rows = cursor.fetchmany(request.limit) if columns else []
+        truncated = bool(columns) and len(rows) == request.limit and cursor.fetchone() is not None
+
+        return {
+            "columns": columns,
+            "rows": [[_to_json(value) for value in row] for row in rows],
+            "row_count": len(rows),
+            "truncated": truncated,
+        }
+    except HTTPException:
+        raise
+    except Exception as e:
+        logger.error(f"Query failed: {e}")
+        raise HTTPException(status_code=400, detail=str(e))
+
+
+def _to_json(value):
+    if value is None or isinstance(value, (bool, int, float, str)):
+        return value
+    return str(value)
@@ -0,0 +1,321 @@
+"""
+Sessions API
+Isolated execution contexts for notebooks. Each session has its own Python
+namespace and DuckDB cursor (temp tables, SET variables), so the desktop
+app can keep sessions warm and hand them from one notebook to the next.
+
+Namespaces can be snapshotted to disk and restored by a later engine
+process, so an engine restart (or crash) doesn't cost users their loaded
+dataframes. Only picklable variables survive; DuckDB temp tables don't.
+"""
+from contextlib import redirect_stderr, redirect_stdout
+from datetime import datetime
+from fastapi import APIRouter, HTTPException
+from pydantic import BaseModel
+from typing import Dict, Optional
+import ast
+import io
+import logging
+import os
+import pickle
+import threading
+import time
+import traceback
+import types
+import uuid
+
+from core.config import settings
+from core.database import duckdb_manager
+
+router = APIRouter()
+logger = logging.getLogger(__name__)
+
+# Imported into every fresh namespace; loading them is most of a session's
+# startup cost
+PRELOADED_MODULES = (("pandas", "pd"), ("numpy", "np"))
+
+SNAPSHOT_DIR = settings.data_dir / "sessions"
+
+# Sessions that ran code since their last snapshot are saved this often, so
+# a crashed engine loses at most this much work
+CHECKPOINT_INTERVAL_SECONDS = 60
+
+
+class Session:
+    def __init__(self, session_id: Optional[str] = None):
+        self.id = session_id or str(uuid.uuid4())
+        self.created_at = datetime.now().isoformat()
+        self.lock = threading.Lock()
+        self.cursor = None
+        self.namespace: dict = {}
+        self.executions = 0
+        self.dirty = False
+        self.reset()
+
+    def reset(self):
+        if self.cursor is not None:
+            try:
+                self.cursor.close()
+            except Exception as e:
+                logger.warning(f"Session {self.id}: failed to close cursor: {e}")
+
+        self.cursor = duckdb_manager.get_connection().cursor()
+        self.namespace = {"__name__": "__main__", "con": self.cursor}
+        for module, alias in PRELOADED_MODULES:
+            try:
+                self.namespace[alias] = __import__(module)
+            except ImportError:
+                pass
+        self.executions = 0
+
+    def close(self):
+        try:
+            self.cursor.close()
+        except Exception:
+            pass
+
+    def info(self):
+        return {"id": self.id, "created_at": self.created_at, "executions": self.executions}
+
+    def user_variables(self):
+        """Names the user defined, leaving out the connection and imports."""
+        return {
+            name: value
+            for name, value in self.namespace.items()
+            if not name.startswith("__") and name != "con" and not isinstance(value, types.ModuleType)
+        }
+
+
+def _snapshot_path(session_id: str):
+    return SNAPSHOT_DIR / f"{session_id}.pkl"
+
+
+def _remove_snapshot(session_id: str):
+    try:
+        _snapshot_path(session_id).unlink(missing_ok=True)
+    except OSError as e:
+        logger.warning(f"Session {session_id}: failed to remove snapshot: {e}")
+
+
+def _save(session: Session):
+    """Pickles the session's variables one by one, skipping those that can't be."""
+    variables, skipped = {}, []
+    with session.lock:
+        for name, value in session.user_variables().items():
+            try:
+                variables[name] = pickle.dumps(value, protocol=pickle.HIGHEST_PROTOCOL)
+            except Exception:
+                skipped.append(name)
+        modules = [
+            (name, value.__name__)
+            for name, value in session.namespace.items()
+            if isinstance(value, types.ModuleType)
+        ]
+        session.dirty = False
+
+    snapshot = {
+        "id": session.id,
+        "created_at": session.created_at,
+        "saved_at": datetime.now().isoformat(),
+        "executions": session.executions,
+        "modules": modules,
+        "variables": variables,
+    }
+    SNAPSHOT_DIR.mkdir(parents=True, exist_ok=True)
+    path = _snapshot_path(session.id)
+    temp = path.with_suffix(".tmp")
+    with open(temp, "wb") as f:
+        pickle.dump(snapshot, f, protocol=pickle.HIGHEST_PROTOCOL)
+    os.replace(temp, path)
+
+    return {"id": session.id, "variables": sorted(variables), "skipped": sorted(skipped)}
+
+
+def _restore(path) -> Optional[dict]:
+    with open(path, "rb") as f:
+        snapshot = pickle.load(f)
+
+    with _sessions_lock:
+        if snapshot["id"] in _sessions:
+            return None
+
+    session = Session(snapshot["id"])
+    session.created_at = snapshot["created_at"]
+    session.executions = snapshot["executions"]
+
+    for name, module in snapshot["modules"]:
+        try:
+            session.namespace[name] = __import__(module, fromlist=["_"])
+        except ImportError:
+            pass
+
+    restored, skipped = [], []
+    for name, data in snapshot["variables"].items():
+        try:
+            session.namespace[name] = pickle.loads(data)
+            restored.append(name)
+        except Exception:
+            skipped.append(name)
+
+    with _sessions_lock:
+        _sessions[session.id] = session
+    logger.info(f"Session {session.id} restored with {len(restored)} variables")
+
+    return {
+        "id": session.id,
+        "saved_at": snapshot["saved_at"],
+        "variables": sorted(restored),
+        "skipped": sorted(skipped),
+    }
+
+
+def _checkpoint_loop():
+    while True:
+        time.sleep(CHECKPOINT_INTERVAL_SECONDS)
+        with _sessions_lock:
+            dirty = [session for session in _sessions.values() if session.dirty]
+        for session in dirty:
+            try:
+                _save(session)
+            except Exception as e:
+                logger.warning(f"Session {session.id}: checkpoint failed: {e}")
+
+
+def start_checkpoints():
+    threading.Thread(target=_checkpoint_loop, name="session-checkpoints", daemon=True).start()
+
+
+_sessions: Dict[str, Session] = {}
+_sessions_lock = threading.Lock()
+
+
+def _get(session_id: str) -> Session:
+    with _sessions_lock:
+        session = _sessions.get(session_id)
+    if session is None:
+        raise HTTPException(status_code=404, detail=f"Session {session_id} not found")
+    return session
+
+
+class ExecuteRequest(BaseModel):
+    code: str
+
+
+# Plain defs throughout, so imports and user code run in the threadpool
+
+@router.post("")
+def create_session():
+    session = Session()
+    with _sessions_lock:
+        _sessions[session.id] = session
+    logger.info(f"Session {session.id} created")
+    return session.info()
+
+
+@router.get("")
+def list_sessions():
+    with _sessions_lock:
+        return [session.info() for session in _sessions.values()]
+
+
+@router.post("/save")
+def save_sessions():
+    """Snapshots every session that has run code, e.g. before a restart."""
+    with _sessions_lock:
+        sessions = [session for session in _sessions.values() if session.executions > 0]
+
+    saved = []
+    for session in sessions:
+        try:
+            saved.append(_save(session))
+        except Exception as e:
+            logger.error(f"Session {session.id}: snapshot failed: {e}")
+    logger.info(f"Saved {len(saved)} session snapshots")
+    return {"sessions": saved}
+
+
+@router.post("/restore")
+def restore_sessions():
+    """Recreates sessions from their snapshots under their old IDs."""
+    restored = []
+    if SNAPSHOT_DIR.is_dir():
+        for path in sorted(SNAPSHOT_DIR.glob("*.pkl")):
+            try:
+                result = _restore(path)
+            except Exception as e:
+                logger.error(f"Failed to restore session snapshot {path.name}: {e}")
+                continue
+            if result is not None:
+                restored.append(result)
+    return {"sessions": restored}
+
+
+@router.post("/{session_id}/reset")
+def reset_session(session_id: str):
+    session = _get(session_id)
+    with session.lock:
+        session.reset()
+        session.dirty = False
+    _remove_snapshot(session_id)
+    return session.info()
+
+
+@router.delete("/{session_id}")
+def delete_session(session_id: str):
+    with _sessions_lock:
+        session = _sessions.pop(session_id, None)
+    if session is None:
+        raise HTTPException(status_code=404, detail=f"Session {session_id} not found")
+    session.close()
+    _remove_snapshot(session_id)
+    logger.info(f"Session {session_id} closed")
+    return {"id": session_id, "deleted": True}
+
+
+@router.post("/{session_id}/execute")
+def execute(session_id: str, request: ExecuteRequest):
+    """
+    Runs code in the session's namespace. Like a notebook cell, the value of
+    a trailing expression is returned as `result`.
+    """
+    session = _get(session_id)
+    stdout, stderr = io.StringIO(), io.StringIO()
+    result, error = None, None
+
+    with session.lock:
+        session.executions += 1
+        session.dirty = True
+        try:
+            tree = ast.parse(request.code, mode="exec")
+            tail = None
+            if tree.body and isinstance(tree.body[-1], ast.Expr):
+                tail = ast.Expression(tree.body.pop().value)
+
+            with redirect_stdout(stdout), redirect_stderr(stderr):
+                exec(compile(tree, "<cell>", "exec"), session.namespace)
+                if tail is not None:
+                    value = eval(compile(tail, "<cell>", "eval"), session.namespace)
+                    if value is not None:
+                        result = repr(value)
+        except Exception:
+            error = traceback.format_exc()
+
+    return {
+        "stdout": stdout.getvalue(),
+        "stderr": stderr.getvalue(),
+        "result": result,
+        "error": error,
+    }
+
+
+def session_cursor(session_id: str):
+    """The DuckDB cursor of a session, for queries run inside it."""
+    return _get(session_id).cursor
+
+
+def close_all():
+    with _sessions_lock:
+        sessions = list(_sessions.values())
+        _sessions.clear()
+    for session in sessions:
+        session.close()
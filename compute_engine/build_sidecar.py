@@ -0,0 +1,69 @@
+"""
+Builds the compute engine into a single executable with PyInstaller and
+places it where Tauri expects the `novem-engine` sidecar:
+novem-desktop/src-tauri/binaries/novem-engine-<target triple>[.exe]
+
+Run with the engine's virtual environment (PyInstaller is in
+requirements.txt):  python build_sidecar.py [--target <triple>]
+"""
+import argparse
+import shutil
+import subprocess
+import sys
+from pathlib import Path
+
+ENGINE_DIR = Path(__file__).resolve().parent
+BINARIES_DIR = ENGINE_DIR.parent / "novem-desktop" / "src-tauri" / "binaries"
+NAME = "novem-engine"
+
+# Loaded by name at runtime, so PyInstaller's import analysis misses them
+HIDDEN_IMPORTS = ["pandas", "pyarrow", "numpy", "duckdb"]
+COLLECT_SUBMODULES = ["uvicorn", "api", "core", "models", "services"]
+
+
+def host_target() -> str:
+    """Target triple of the Rust toolchain, which names sidecar binaries."""
+    output = subprocess.run(["rustc", "-vV"], capture_output=True, text=True, check=True).stdout
+    for line in output.splitlines():
+        if line.startswith("host:"):
+            return line.split(":", 1)[1].strip()
+    sys.exit("Could not determine the target triple from `rustc -vV`")
+
+
+def main():
+    parser = argparse.ArgumentParser(description="Build the compute engine sidecar")
+    parser.add_argument("--target", help="Target triple (defaults to the rustc host)")
+    args = parser.parse_args()
+
+    target = args.target or host_target()
+    build_dir = ENGINE_DIR / "build"
+
+    command = [
+        sys.executable, "-m", "PyInstaller",
+        "--onefile",
+        "--noconfirm",
+        "--clean",
+        "--name", NAME,
+        "--distpath", str(build_dir / "dist"),
+        "--workpath", str(build_dir / "work"),
+        "--specpath", str(build_dir),
+    ]
+    for module in HIDDEN_IMPORTS:
+        command += ["--hidden-import", module]
+    for package in COLLECT_SUBMODULES:
+        command += ["--collect-submodules", package]
+    command.append(str(ENGINE_DIR / "main.py"))
+
+    print(f"Building {NAME} for {target}...")
+    subprocess.run(command, cwd=ENGINE_DIR, check=True)
+
+    suffix = ".exe" if "windows" in target else ""
+    built = build_dir / "dist" / f"{NAME}{suffix}"
+    BINARIES_DIR.mkdir(parents=True, exist_ok=True)
+    destination = BINARIES_DIR / f"{NAME}-{target}{suffix}"
+    shutil.copy2(built, destination)
+    print(f"Sidecar written to {destination}")
+
+
+if __name__ == "__main__":
+    main()
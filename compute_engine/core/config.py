@@ -42,6 +42,8 @@ class Settings(BaseSettings):
     
     # Security
     access_token_expire_minutes: int = 60
+    # Shared secret set by the desktop app; unset when run standalone
+    auth_token: Optional[str] = None
     
     class Config:
         env_file = ".env"
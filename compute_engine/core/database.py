@@ -35,6 +35,8 @@ class DuckDBManager:
             # Configure DuckDB settings
             self.conn.execute(f"SET memory_limit='{settings.max_memory_gb}GB'")
             self.conn.execute(f"SET threads={settings.max_cpu_cores}")
+            # Spill to the engine's own temp dir so project engines don't share scratch space
+            self.conn.execute(f"SET temp_directory='{settings.temp_dir / 'duckdb'}'")
             
             # Enable useful extensions
             try:
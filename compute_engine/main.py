@@ -3,17 +3,32 @@ NOVEM Local Compute Engine - Embedded Mode
 FastAPI-based analytical processor running inside Tauri desktop app
 """
 from contextlib import asynccontextmanager
-from fastapi import FastAPI, HTTPException
+from fastapi import FastAPI, HTTPException, Request
 from fastapi.middleware.cors import CORSMiddleware
 from fastapi.responses import JSONResponse
+from pydantic import BaseModel
+import asyncio
+import hmac
 import logging
+import signal
 import sys
 import os
 
 from core.config import settings
 
+# uvicorn's level below DEBUG
+TRACE_LOG_LEVEL = 5
+LOG_LEVELS = {
+    "critical": logging.CRITICAL,
+    "error": logging.ERROR,
+    "warning": logging.WARNING,
+    "info": logging.INFO,
+    "debug": logging.DEBUG,
+    "trace": TRACE_LOG_LEVEL,
+}
+
 logging.basicConfig(
-    level=logging.INFO,
+    level=LOG_LEVELS.get(settings.log_level.lower(), logging.INFO),
     format='%(asctime)s - %(name)s - %(levelname)s - %(message)s',
     handlers=[
         logging.StreamHandler(sys.stdout),
@@ -29,6 +44,7 @@ async def lifespan(app: FastAPI):
     logger.info("Starting NOVEM Compute Engine (Embedded Mode)...")
     logger.info(f"Working directory: {os.getcwd()}")
     logger.info(f"Data directory: {settings.data_dir}")
+    logger.info(f"Temp directory: {settings.temp_dir}")
     logger.info(f"Max memory: {settings.max_memory_gb}GB")
     logger.info(f"Max CPU cores: {settings.max_cpu_cores}")
     
@@ -48,6 +64,7 @@ async def lifespan(app: FastAPI):
                 duckdb_manager._connect()
             logger.info("DuckDB ready")
             initialized["duckdb"] = True
+            sessions.start_checkpoints()
         except Exception as e:
             logger.warning(f"DuckDB initialization warning: {e}")
         
@@ -94,6 +111,7 @@ async def lifespan(app: FastAPI):
         
         if initialized["duckdb"]:
             try:
+                sessions.close_all()
                 duckdb_manager.close()
                 logger.info("DuckDB closed")
             except Exception as e:
@@ -132,11 +150,33 @@ app.add_middleware(
     allow_headers=["*"],
 )
 
-from api import health, auth, sync
+
+ENGINE_TOKEN_HEADER = "x-novem-engine-token"
+
+
+@app.middleware("http")
+async def require_engine_token(request: Request, call_next):
+    """Only the desktop app that spawned this engine may call it.
+
+    Anything on localhost can reach the port, so requests must carry the
+    token the app passed in COMPUTE_ENGINE_AUTH_TOKEN. CORS preflights carry
+    no custom headers and are let through.
+    """
+    if settings.auth_token and request.method != "OPTIONS":
+        supplied = request.headers.get(ENGINE_TOKEN_HEADER, "")
+        if not hmac.compare_digest(supplied.encode(), settings.auth_token.encode()):
+            return JSONResponse(status_code=401, content={"detail": "Missing or invalid engine token"})
+    return await call_next(request)
+
+
+from api import health, auth, sync, query, sessions, connections
 
 app.include_router(health.router, prefix="/health", tags=["Health"])
 app.include_router(auth.router, prefix="/auth", tags=["Authentication"])
 app.include_router(sync.router, prefix="/sync", tags=["Sync"])
+app.include_router(query.router, prefix="/query", tags=["Query"])
+app.include_router(sessions.router, prefix="/sessions", tags=["Sessions"])
+app.include_router(connections.router, prefix="/connections", tags=["Connections"])
 
 
 @app.get("/")
@@ -152,6 +192,73 @@ async def root():
     }
 
 
+@app.get("/heartbeat")
+async def heartbeat():
+    """Keepalive for the desktop app's supervisor, polled every second or so.
+
+    Deliberately does no work: an answer only shows the event loop is
+    responsive. Database and resource checks stay in /health.
+    """
+    return {"ok": True}
+
+
+class LogLevelRequest(BaseModel):
+    level: str
+
+
+@app.post("/control/log-level")
+async def set_log_level(request: LogLevelRequest):
+    """Switch logging verbosity without a restart, e.g. to capture debug logs for support."""
+    level = LOG_LEVELS.get(request.level.lower())
+    if level is None:
+        raise HTTPException(status_code=400, detail=f"Unknown log level: {request.level}")
+
+    logging.getLogger().setLevel(level)
+    for name in ("uvicorn", "uvicorn.error", "uvicorn.access"):
+        logging.getLogger(name).setLevel(level)
+
+    logger.warning(f"Log level set to {request.level.lower()}")
+    return {"level": request.level.lower()}
+
+
+@app.post("/shutdown")
+async def shutdown():
+    """Graceful shutdown requested by the desktop app.
+
+    Raises SIGINT shortly after responding so uvicorn runs the lifespan
+    shutdown (closing DuckDB/SQLite) instead of being killed mid-write.
+    """
+    logger.info("Shutdown requested by desktop app")
+    asyncio.get_running_loop().call_later(0.2, signal.raise_signal, signal.SIGINT)
+    return {"status": "shutting_down"}
+
+
+@app.post("/warmup")
+def warmup():
+    """Preloads the heavy analytical libraries before the desktop app marks
+    the engine ready, so the first user query doesn't pay for the imports.
+
+    A plain def so the imports run in the threadpool, not on the event loop.
+    """
+    loaded = []
+    for module in ("pandas", "pyarrow", "numpy"):
+        try:
+            __import__(module)
+            loaded.append(module)
+        except ImportError:
+            logger.info(f"Warm-up: {module} not installed, skipping")
+
+    try:
+        from core.database import duckdb_manager
+        duckdb_manager.execute("SELECT 1").fetchall()
+        loaded.append("duckdb")
+    except Exception as e:
+        logger.warning(f"Warm-up: DuckDB not available: {e}")
+
+    logger.info(f"Warm-up complete: {', '.join(loaded)}")
+    return {"status": "warm", "loaded": loaded}
+
+
 @app.exception_handler(HTTPException)
 async def http_exception_handler(request, exc):
     logger.error(f"HTTP error: {exc.detail}")
@@ -171,16 +278,27 @@ async def general_exception_handler(request, exc):
 
 
 if __name__ == "__main__":
+    # Also the entry point of the frozen sidecar binary, which the desktop
+    # app launches with the same flags it passes to uvicorn
+    import argparse
     import uvicorn
+
+    parser = argparse.ArgumentParser(description="NOVEM Compute Engine")
+    parser.add_argument("--host", default=settings.host)
+    parser.add_argument("--port", type=int, default=settings.port)
+    parser.add_argument("--log-level", default="info")
+    args, unknown = parser.parse_known_args()
+    if unknown:
+        logger.warning(f"Ignoring unsupported arguments: {' '.join(unknown)}")
     
-    logger.info(f"Starting embedded server on {settings.host}:{settings.port}")
+    logger.info(f"Starting embedded server on {args.host}:{args.port}")
     
     try:
         uvicorn.run(
             app,
-            host=settings.host,
-            port=settings.port,
-            log_level="info",
+            host=args.host,
+            port=args.port,
+            log_level=args.log_level,
             access_log=True
         )
     except KeyboardInterrupt:
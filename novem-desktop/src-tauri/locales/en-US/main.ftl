@@ -0,0 +1,151 @@
+# Messages for errors and notifications produced by the desktop shell.
+# Arguments are passed from Rust with the tr! macro.
+
+## Commands
+
+database-not-initialized = Database not initialized
+file-unreadable = Cannot read { $path }: { $error }
+not-a-file = { $path } is not a file
+dataset-not-found = Dataset { $uuid } not found
+recipe-not-found = Recipe { $uuid } not found
+recipe-name-empty = Recipe name must not be empty
+refresh-not-scheduled = Dataset { $uuid } has no refresh schedule
+refresh-already-running = Dataset { $uuid } is already being refreshed
+refresh-interval-invalid = Refresh interval must be at least { $min } seconds
+activity-retention-invalid = Activity log retention must be at least one day
+setting-unknown = Unknown setting: { $key }
+setting-invalid = Invalid value for { $key }: { $reason }
+import-not-a-bundle = Not a NOVEM data export
+import-newer-version = This export was made by a newer version of NOVEM (format { $version }); update the app to import it
+export-write-failed = Failed to write export to { $path }: { $reason }
+import-read-failed = Failed to read export { $path }: { $reason }
+page-cursor-invalid = Invalid page cursor
+comment-empty = Comments cannot be empty
+comment-too-long = Comments are limited to { $max } characters
+comment-target-not-found = No { $entity_type } { $uuid } to comment on
+project-not-found = Project { $id } not found
+attachment-not-a-file = Not a file: { $path }
+template-name-empty = Template name must not be empty
+project-name-empty = Project name must not be empty
+template-not-found = Template { $uuid } not found
+workspace-already-archived = Workspace { $name } is already archived
+workspace-not-archived = Workspace { $name } is not archived
+conflict-not-found = Conflict { $id } not found
+conflict-already-resolved = Conflict { $id } has already been resolved
+workspace-name-empty = Workspace name must not be empty
+workspace-not-signed-in = Sign in to create a workspace
+account-uuid-invalid = { $uuid } is not a valid account ID
+account-switch-busy = Wait for uploads and dataset refreshes to finish before switching accounts
+refresh-source-invalid = Invalid refresh source: { $source }
+query-not-found = Query { $id } not found
+query-not-rerunnable = Cannot re-run '{ $kind }' queries
+tag-empty = Tag must not be empty
+locale-unsupported = Unsupported locale: { $locale }
+watchdog-sustained-negative = The sustained period must not be negative
+workspace-not-found = Workspace { $uuid } not found
+deprovision-not-signed-in = Not signed in; the backend was not told about this deprovisioning
+guest-mode-blocked = '{ $command }' is not available in read-only guest mode
+guest-mode-locked = Guest mode was started from the command line and can't be turned off
+secret-not-found = No secret named { $name }
+secret-undecryptable = Secret { $name } cannot be decrypted with this device’s vault key
+secret-name-invalid = Secret names may only contain letters, digits, dots, dashes and underscores
+secret-vault-unavailable = The OS keyring is unavailable, so secrets cannot be stored: { $reason }
+connection-name-empty = Connection name must not be empty
+connection-not-found = Connection { $uuid } not found
+schedule-cron-invalid = Invalid cron expression: { $cron }
+schedule-action-invalid = Unknown or incomplete scheduled action '{ $action }'
+schedule-not-found = Schedule { $uuid } not found
+schedule-name-empty = A schedule needs a name
+share-entity-unsupported = Cannot share a { $entity_type }
+share-permission-invalid = Unknown share permission '{ $permission }'
+share-target-not-found = No { $entity_type } { $uuid } to share
+share-expiry-invalid = A share link must expire in the future
+sync-offline = Can't sync while offline; changes stay queued until the server is reachable
+
+## Engines
+
+engine-env-invalid = Invalid environment variable: '{ $name }'
+engine-dir-not-found = Could not find the compute_engine directory
+python-not-found = No Python { $minimum } or newer was found. Install one or create a virtual environment in compute_engine
+engine-max-concurrent-invalid = An engine must run at least 1 request at a time
+engine-method-invalid = Invalid HTTP method: { $method }
+engine-unreachable = Compute engine unreachable: { $error }
+engine-status-error = Compute engine returned status { $status }: { $body }
+engine-busy = Engine busy: { $queued } requests already waiting (limit { $limit }), try again shortly
+engine-request-timeout = The compute engine did not answer within { $seconds } seconds
+engine-request-timeout-invalid = The request timeout must be at least 1 second
+engine-profile-not-found = Engine profile { $id } not found
+engine-profile-name-empty = Profile name must not be empty
+engine-profile-working-dir-invalid = { $path } is not a directory
+engine-readiness-invalid = Startup timeout, warm-up timeout and poll interval must be greater than zero
+engine-shutdown-invalid = Shutdown timeout and grace period must each be at most { $max } seconds
+engine-heartbeat-invalid = Heartbeat interval must be at least 100 ms, the timeout no longer than the interval, and at least one miss allowed
+engine-start-timeout = The compute engine did not become ready within { $seconds } seconds; check the engine logs for errors
+engine-port-in-use = Port { $port } is in use by { $owner } and no other port is free
+engine-startup-missing-module = The compute engine could not start: Python package "{ $module }" is not installed. Install the engine dependencies and try again
+engine-startup-syntax-error = The compute engine could not start because of a syntax error at { $location }
+engine-startup-permission-denied = The compute engine could not start: a file or directory it needs is not accessible
+engine-startup-exited = The compute engine exited during startup; see the error output for details
+engine-update-url-missing = No engine bundle URL is configured
+engine-update-url-invalid = Invalid engine bundle URL: { $url }
+engine-update-key-invalid = The engine bundle public key is not a valid minisign key
+engine-update-key-missing = No engine bundle public key is configured; unsigned bundles are not installed
+engine-update-checksum-mismatch = Engine bundle checksum mismatch (expected { $expected }, got { $actual })
+engine-update-signature-invalid = The engine bundle signature is missing or invalid
+engine-update-invalid-bundle = Not a valid engine bundle: { $error }
+engine-update-dev-checkout = The compute engine runs from a development checkout and can't be updated in place
+proxy-body-too-large = Engine response is larger than the { $limit } byte limit
+proxy-spill-not-found = Spilled response { $handle } not found
+proxy-limits-invalid = The spill threshold must be greater than zero and no larger than the maximum response size
+session-pool-config-invalid = The session limit must be at least 1 and no smaller than the number of warm sessions
+session-pool-exhausted = All { $max } engine sessions are in use; close a notebook and try again
+session-engine-restarted = The compute engine restarted while the session was being created; try again
+backend-unreachable = Backend unreachable: { $error }
+backend-status-error = Backend returned status { $status }
+resources-unavailable = Could not read system resources: { $error }
+engine-not-running = The compute engine is not running; check the engine logs for errors
+
+## Tunnels
+
+tunnel-unreachable = Cannot reach { $host }: { $error }
+tunnel-auth-failed = SSH authentication failed for { $user }@{ $host }
+tunnel-host-unknown = { $host } is not in known_hosts; connect once with ssh to verify its key, or trust it explicitly
+tunnel-host-key-changed = The host key for { $host } has changed; refusing to connect
+
+## Resource alerts
+
+metric-engine-cpu = Compute engine CPU
+metric-engine-memory = Compute engine memory
+metric-engine-disk-write = Compute engine disk writes
+metric-app-cpu = NOVEM CPU
+metric-app-memory = NOVEM memory
+alert-raised = { $metric } has been above { $threshold } for { $seconds } seconds (now { $value })
+alert-cleared = { $metric } is back under { $threshold } (peaked at { $peak })
+
+## Startup recovery
+
+recovery-sample-removed = Removed unregistered sample { $uuid }
+recovery-sample-kept = Sample { $uuid } was already registered
+recovery-transform-removed = Removed unregistered recipe output { $uuid }
+recovery-transform-kept = Recipe output { $uuid } was already registered
+recovery-append-discarded = Discarded partial append to dataset { $uuid }
+recovery-append-finished = Finished append to dataset { $uuid } ({ $rows } rows)
+recovery-clone-cleaned = Cleaned up staged copies ({ $moved } already committed)
+recovery-clone-moved = Moved { $moved } committed dataset copies into place
+recovery-clone-removed = Removed copies of an uncommitted clone
+recovery-engine-update-restored = Restored the compute engine after an interrupted update
+recovery-engine-update-kept = Finished an interrupted compute engine update
+recovery-attachment-removed = Removed unregistered attachment { $uuid }
+recovery-attachment-kept = Attachment { $uuid } was already registered
+
+## Notifications
+
+notify-engine-crashed = The compute engine crashed
+notify-engine-degraded = The compute engine is not responding normally
+notify-engine-project = Engine for project { $id }
+notify-refresh-failed = Dataset refresh failed
+notify-job-finished = { $kind } job finished
+notify-job-failed = { $kind } job failed
+notify-sync-conflict = Sync conflict needs to be resolved
+notify-sync-conflict-body = Both this device and the server changed { $entity } { $uuid }
+notify-schedule-failed = Scheduled task '{ $name }' failed
@@ -0,0 +1,150 @@
+# Mensajes de errores y notificaciones generados por la aplicación de escritorio.
+
+## Commands
+
+database-not-initialized = La base de datos no está inicializada
+file-unreadable = No se puede leer { $path }: { $error }
+not-a-file = { $path } no es un archivo
+dataset-not-found = No se encontró el conjunto de datos { $uuid }
+recipe-not-found = No se encontró la receta { $uuid }
+recipe-name-empty = El nombre de la receta no puede estar vacío
+refresh-not-scheduled = El conjunto de datos { $uuid } no tiene una actualización programada
+refresh-already-running = El conjunto de datos { $uuid } ya se está actualizando
+refresh-interval-invalid = El intervalo de actualización debe ser de al menos { $min } segundos
+activity-retention-invalid = La retención del registro de actividad debe ser de al menos un día
+setting-unknown = Ajuste desconocido: { $key }
+setting-invalid = Valor no válido para { $key }: { $reason }
+import-not-a-bundle = No es una exportación de datos de NOVEM
+import-newer-version = Esta exportación se creó con una versión más reciente de NOVEM (formato { $version }); actualiza la aplicación para importarla
+export-write-failed = No se pudo escribir la exportación en { $path }: { $reason }
+import-read-failed = No se pudo leer la exportación { $path }: { $reason }
+page-cursor-invalid = Cursor de página no válido
+comment-empty = El comentario no puede estar vacío
+comment-too-long = Los comentarios admiten como máximo { $max } caracteres
+comment-target-not-found = No existe { $entity_type } { $uuid } para comentar
+project-not-found = No se encontró el proyecto { $id }
+attachment-not-a-file = No es un archivo: { $path }
+template-name-empty = El nombre de la plantilla no puede estar vacío
+project-name-empty = El nombre del proyecto no puede estar vacío
+template-not-found = No se encontró la plantilla { $uuid }
+workspace-already-archived = El espacio de trabajo { $name } ya está archivado
+workspace-not-archived = El espacio de trabajo { $name } no está archivado
+conflict-not-found = No se encontró el conflicto { $id }
+conflict-already-resolved = El conflicto { $id } ya está resuelto
+workspace-name-empty = El nombre del espacio de trabajo no puede estar vacío
+workspace-not-signed-in = Inicia sesión para crear un espacio de trabajo
+account-uuid-invalid = { $uuid } no es un identificador de cuenta válido
+account-switch-busy = Espera a que terminen las subidas y actualizaciones de conjuntos de datos antes de cambiar de cuenta
+refresh-source-invalid = Origen de actualización no válido: { $source }
+query-not-found = No se encontró la consulta { $id }
+query-not-rerunnable = Las consultas '{ $kind }' no se pueden volver a ejecutar
+tag-empty = La etiqueta no puede estar vacía
+locale-unsupported = Idioma no disponible: { $locale }
+watchdog-sustained-negative = El periodo sostenido no puede ser negativo
+workspace-not-found = No se encontró el espacio de trabajo { $uuid }
+deprovision-not-signed-in = No hay sesión iniciada; no se informó al servidor de este desaprovisionamiento
+guest-mode-blocked = '{ $command }' no está disponible en el modo invitado de solo lectura
+guest-mode-locked = El modo invitado se inició desde la línea de comandos y no se puede desactivar
+secret-not-found = No hay ningún secreto llamado { $name }
+secret-undecryptable = El secreto { $name } no se puede descifrar con la clave del almacén de este dispositivo
+secret-name-invalid = Los nombres de secretos solo pueden contener letras, dígitos, puntos, guiones y guiones bajos
+secret-vault-unavailable = El llavero del sistema no está disponible, no se pueden guardar secretos: { $reason }
+connection-name-empty = El nombre de la conexión no puede estar vacío
+connection-not-found = No se encontró la conexión { $uuid }
+schedule-cron-invalid = Expresión cron no válida: { $cron }
+schedule-action-invalid = Acción programada desconocida o incompleta: '{ $action }'
+schedule-not-found = No se encontró la programación { $uuid }
+schedule-name-empty = La programación necesita un nombre
+share-entity-unsupported = No se puede compartir un elemento de tipo { $entity_type }
+share-permission-invalid = Permiso de uso compartido desconocido: '{ $permission }'
+share-target-not-found = No hay { $entity_type } { $uuid } para compartir
+share-expiry-invalid = Un enlace compartido debe caducar en el futuro
+sync-offline = No se puede sincronizar sin conexión; los cambios quedan en cola hasta que el servidor esté disponible
+
+## Engines
+
+engine-env-invalid = Variable de entorno no válida: '{ $name }'
+engine-dir-not-found = No se encontró el directorio compute_engine
+python-not-found = No se encontró Python { $minimum } o posterior. Instala uno o crea un entorno virtual en compute_engine
+engine-max-concurrent-invalid = Un motor debe ejecutar al menos 1 solicitud a la vez
+engine-method-invalid = Método HTTP no válido: { $method }
+engine-unreachable = No se puede contactar con el motor de cómputo: { $error }
+engine-status-error = El motor de cómputo devolvió el estado { $status }: { $body }
+engine-busy = Motor ocupado: ya hay { $queued } solicitudes en espera (límite { $limit }), inténtalo de nuevo en breve
+engine-request-timeout = El motor de cálculo no respondió en { $seconds } segundos
+engine-request-timeout-invalid = El tiempo de espera de las solicitudes debe ser de al menos 1 segundo
+engine-profile-not-found = No se encontró el perfil de motor { $id }
+engine-profile-name-empty = El nombre del perfil no puede estar vacío
+engine-profile-working-dir-invalid = { $path } no es un directorio
+engine-readiness-invalid = Los tiempos de espera de arranque y de precalentamiento y el intervalo de sondeo deben ser mayores que cero
+engine-shutdown-invalid = El tiempo de apagado y el periodo de gracia deben ser como máximo de { $max } segundos cada uno
+engine-heartbeat-invalid = El intervalo del latido debe ser de al menos 100 ms, el tiempo de espera no mayor que el intervalo y se debe permitir al menos un fallo
+engine-start-timeout = El motor de cómputo no estuvo listo en { $seconds } segundos; revisa sus registros para ver los errores
+engine-port-in-use = El puerto { $port } está en uso por { $owner } y no hay otro puerto libre
+engine-startup-missing-module = El motor de cálculo no pudo iniciarse: el paquete de Python "{ $module }" no está instalado. Instala las dependencias del motor e inténtalo de nuevo
+engine-startup-syntax-error = El motor de cálculo no pudo iniciarse por un error de sintaxis en { $location }
+engine-startup-permission-denied = El motor de cálculo no pudo iniciarse: no se puede acceder a un archivo o directorio que necesita
+engine-startup-exited = El motor de cálculo se cerró durante el inicio; consulta la salida de error para más detalles
+engine-update-url-missing = No hay ninguna URL de paquete del motor configurada
+engine-update-url-invalid = URL de paquete del motor no válida: { $url }
+engine-update-key-invalid = La clave pública del paquete del motor no es una clave minisign válida
+engine-update-key-missing = No hay ninguna clave pública configurada para los paquetes del motor; los paquetes sin firmar no se instalan
+engine-update-checksum-mismatch = La suma de comprobación del paquete del motor no coincide (se esperaba { $expected }, se obtuvo { $actual })
+engine-update-signature-invalid = Falta la firma del paquete del motor o no es válida
+engine-update-invalid-bundle = No es un paquete del motor válido: { $error }
+engine-update-dev-checkout = El motor de cálculo se ejecuta desde una copia de desarrollo y no se puede actualizar en su sitio
+proxy-body-too-large = La respuesta del motor supera el límite de { $limit } bytes
+proxy-spill-not-found = No se encontró la respuesta volcada { $handle }
+proxy-limits-invalid = El umbral de volcado debe ser mayor que cero y no superar el tamaño máximo de respuesta
+session-pool-config-invalid = El límite de sesiones debe ser al menos 1 y no menor que el número de sesiones preparadas
+session-pool-exhausted = Las { $max } sesiones del motor están en uso; cierra un cuaderno e inténtalo de nuevo
+session-engine-restarted = El motor de cálculo se reinició mientras se creaba la sesión; inténtalo de nuevo
+backend-unreachable = No se puede contactar con el servidor: { $error }
+backend-status-error = El servidor devolvió el estado { $status }
+resources-unavailable = No se pudieron leer los recursos del sistema: { $error }
+engine-not-running = El motor de cálculo no está en ejecución; revisa sus registros para ver el error
+
+## Tunnels
+
+tunnel-unreachable = No se puede conectar con { $host }: { $error }
+tunnel-auth-failed = Falló la autenticación SSH de { $user }@{ $host }
+tunnel-host-unknown = { $host } no está en known_hosts; conéctate una vez con ssh para verificar su clave o confía en él explícitamente
+tunnel-host-key-changed = La clave de { $host } ha cambiado; se rechaza la conexión
+
+## Resource alerts
+
+metric-engine-cpu = CPU del motor de cómputo
+metric-engine-memory = Memoria del motor de cómputo
+metric-engine-disk-write = Escritura en disco del motor de cómputo
+metric-app-cpu = CPU de NOVEM
+metric-app-memory = Memoria de NOVEM
+alert-raised = { $metric } lleva { $seconds } segundos por encima de { $threshold } (ahora { $value })
+alert-cleared = { $metric } ha vuelto por debajo de { $threshold } (máximo { $peak })
+
+## Startup recovery
+
+recovery-sample-removed = Se eliminó la muestra no registrada { $uuid }
+recovery-sample-kept = La muestra { $uuid } ya estaba registrada
+recovery-transform-removed = Se eliminó el resultado de receta no registrado { $uuid }
+recovery-transform-kept = El resultado de receta { $uuid } ya estaba registrado
+recovery-append-discarded = Se descartó la adición incompleta al conjunto de datos { $uuid }
+recovery-append-finished = Se completó la adición al conjunto de datos { $uuid } ({ $rows } filas)
+recovery-clone-cleaned = Se limpiaron las copias preparadas ({ $moved } ya confirmadas)
+recovery-clone-moved = Se movieron { $moved } copias confirmadas de conjuntos de datos
+recovery-clone-removed = Se eliminaron las copias de una clonación no confirmada
+recovery-engine-update-restored = Se restauró el motor de cálculo tras una actualización interrumpida
+recovery-engine-update-kept = Se completó una actualización interrumpida del motor de cálculo
+recovery-attachment-removed = Se eliminó el adjunto no registrado { $uuid }
+recovery-attachment-kept = El adjunto { $uuid } ya estaba registrado
+
+## Notifications
+
+notify-engine-crashed = El motor de cálculo se ha bloqueado
+notify-engine-degraded = El motor de cálculo no responde con normalidad
+notify-engine-project = Motor del proyecto { $id }
+notify-refresh-failed = Falló la actualización del conjunto de datos
+notify-job-finished = Tarea { $kind } terminada
+notify-job-failed = Falló la tarea { $kind }
+notify-sync-conflict = Hay un conflicto de sincronización por resolver
+notify-sync-conflict-body = Este dispositivo y el servidor modificaron { $entity } { $uuid }
+notify-schedule-failed = Falló la tarea programada '{ $name }'
@@ -0,0 +1,150 @@
+# Messages d'erreur et notifications produits par l'application de bureau.
+
+## Commands
+
+database-not-initialized = La base de données n'est pas initialisée
+file-unreadable = Impossible de lire { $path } : { $error }
+not-a-file = { $path } n'est pas un fichier
+dataset-not-found = Jeu de données { $uuid } introuvable
+recipe-not-found = Recette { $uuid } introuvable
+recipe-name-empty = Le nom de la recette ne peut pas être vide
+refresh-not-scheduled = Le jeu de données { $uuid } n'a pas d'actualisation planifiée
+refresh-already-running = Le jeu de données { $uuid } est déjà en cours d'actualisation
+refresh-interval-invalid = L'intervalle d'actualisation doit être d'au moins { $min } secondes
+activity-retention-invalid = La conservation du journal d'activité doit être d'au moins un jour
+setting-unknown = Paramètre inconnu : { $key }
+setting-invalid = Valeur invalide pour { $key } : { $reason }
+import-not-a-bundle = Ce n'est pas une exportation de données NOVEM
+import-newer-version = Cette exportation provient d'une version plus récente de NOVEM (format { $version }) ; mettez l'application à jour pour l'importer
+export-write-failed = Impossible d'écrire l'exportation dans { $path } : { $reason }
+import-read-failed = Impossible de lire l'exportation { $path } : { $reason }
+page-cursor-invalid = Curseur de page invalide
+comment-empty = Le commentaire ne peut pas être vide
+comment-too-long = Les commentaires sont limités à { $max } caractères
+comment-target-not-found = Aucun { $entity_type } { $uuid } à commenter
+project-not-found = Projet { $id } introuvable
+attachment-not-a-file = Ce n'est pas un fichier : { $path }
+template-name-empty = Le nom du modèle ne peut pas être vide
+project-name-empty = Le nom du projet ne peut pas être vide
+template-not-found = Modèle { $uuid } introuvable
+workspace-already-archived = L'espace de travail { $name } est déjà archivé
+workspace-not-archived = L'espace de travail { $name } n'est pas archivé
+conflict-not-found = Conflit { $id } introuvable
+conflict-already-resolved = Le conflit { $id } est déjà résolu
+workspace-name-empty = Le nom de l'espace de travail ne peut pas être vide
+workspace-not-signed-in = Connectez-vous pour créer un espace de travail
+account-uuid-invalid = { $uuid } n'est pas un identifiant de compte valide
+account-switch-busy = Attendez la fin des envois et des actualisations de jeux de données avant de changer de compte
+refresh-source-invalid = Source d'actualisation invalide : { $source }
+query-not-found = Requête { $id } introuvable
+query-not-rerunnable = Les requêtes « { $kind } » ne peuvent pas être relancées
+tag-empty = L'étiquette ne peut pas être vide
+locale-unsupported = Langue non prise en charge : { $locale }
+watchdog-sustained-negative = La durée soutenue ne peut pas être négative
+workspace-not-found = Espace de travail { $uuid } introuvable
+deprovision-not-signed-in = Non connecté ; le serveur n’a pas été informé de ce déprovisionnement
+guest-mode-blocked = '{ $command }' n'est pas disponible en mode invité en lecture seule
+guest-mode-locked = Le mode invité a été lancé depuis la ligne de commande et ne peut pas être désactivé
+secret-not-found = Aucun secret nommé { $name }
+secret-undecryptable = Le secret { $name } ne peut pas être déchiffré avec la clé du coffre de cet appareil
+secret-name-invalid = Les noms de secrets ne peuvent contenir que des lettres, chiffres, points, tirets et tirets bas
+secret-vault-unavailable = Le trousseau du système est indisponible, les secrets ne peuvent pas être enregistrés : { $reason }
+connection-name-empty = Le nom de la connexion ne peut pas être vide
+connection-not-found = Connexion { $uuid } introuvable
+schedule-cron-invalid = Expression cron invalide : { $cron }
+schedule-action-invalid = Action planifiée inconnue ou incomplète : « { $action } »
+schedule-not-found = Planification { $uuid } introuvable
+schedule-name-empty = Une planification doit avoir un nom
+share-entity-unsupported = Impossible de partager un élément de type { $entity_type }
+share-permission-invalid = Permission de partage inconnue : « { $permission } »
+share-target-not-found = Aucun élément { $entity_type } { $uuid } à partager
+share-expiry-invalid = Un lien de partage doit expirer dans le futur
+sync-offline = Synchronisation impossible hors ligne ; les modifications restent en attente jusqu'à ce que le serveur soit joignable
+
+## Engines
+
+engine-env-invalid = Variable d'environnement invalide : « { $name } »
+engine-dir-not-found = Répertoire compute_engine introuvable
+python-not-found = Aucun Python { $minimum } ou plus récent trouvé. Installez-en un ou créez un environnement virtuel dans compute_engine
+engine-max-concurrent-invalid = Un moteur doit exécuter au moins 1 requête à la fois
+engine-method-invalid = Méthode HTTP invalide : { $method }
+engine-unreachable = Moteur de calcul injoignable : { $error }
+engine-status-error = Le moteur de calcul a renvoyé le statut { $status } : { $body }
+engine-busy = Moteur occupé : { $queued } requêtes déjà en attente (limite { $limit }), réessayez dans un instant
+engine-request-timeout = Le moteur de calcul n'a pas répondu dans les { $seconds } secondes
+engine-request-timeout-invalid = Le délai des requêtes doit être d'au moins 1 seconde
+engine-profile-not-found = Profil de moteur { $id } introuvable
+engine-profile-name-empty = Le nom du profil ne peut pas être vide
+engine-profile-working-dir-invalid = { $path } n'est pas un répertoire
+engine-readiness-invalid = Les délais de démarrage et de préchauffage et l'intervalle de sondage doivent être supérieurs à zéro
+engine-shutdown-invalid = Le délai d’arrêt et la période de grâce ne peuvent pas dépasser { $max } secondes chacun
+engine-heartbeat-invalid = L’intervalle du battement doit être d’au moins 100 ms, le délai ne peut pas dépasser l’intervalle et au moins un échec doit être toléré
+engine-start-timeout = Le moteur de calcul n'était pas prêt après { $seconds } secondes ; consultez ses journaux pour voir les erreurs
+engine-port-in-use = Le port { $port } est utilisé par { $owner } et aucun autre port n'est libre
+engine-startup-missing-module = Le moteur de calcul n'a pas pu démarrer : le paquet Python « { $module } » n'est pas installé. Installez les dépendances du moteur puis réessayez
+engine-startup-syntax-error = Le moteur de calcul n'a pas pu démarrer à cause d'une erreur de syntaxe en { $location }
+engine-startup-permission-denied = Le moteur de calcul n'a pas pu démarrer : un fichier ou dossier nécessaire est inaccessible
+engine-startup-exited = Le moteur de calcul s'est arrêté pendant le démarrage ; consultez la sortie d'erreur pour plus de détails
+engine-update-url-missing = Aucune URL de paquet du moteur n'est configurée
+engine-update-url-invalid = URL de paquet du moteur invalide : { $url }
+engine-update-key-invalid = La clé publique du paquet du moteur n'est pas une clé minisign valide
+engine-update-key-missing = Aucune clé publique n'est configurée pour les paquets du moteur ; les paquets non signés ne sont pas installés
+engine-update-checksum-mismatch = La somme de contrôle du paquet du moteur ne correspond pas (attendu { $expected }, obtenu { $actual })
+engine-update-signature-invalid = La signature du paquet du moteur est absente ou invalide
+engine-update-invalid-bundle = Paquet du moteur invalide : { $error }
+engine-update-dev-checkout = Le moteur de calcul s'exécute depuis une copie de développement et ne peut pas être mis à jour sur place
+proxy-body-too-large = La réponse du moteur dépasse la limite de { $limit } octets
+proxy-spill-not-found = Réponse déversée { $handle } introuvable
+proxy-limits-invalid = Le seuil de déversement doit être supérieur à zéro et ne pas dépasser la taille maximale de réponse
+session-pool-config-invalid = La limite de sessions doit être d'au moins 1 et ne pas être inférieure au nombre de sessions préchauffées
+session-pool-exhausted = Les { $max } sessions du moteur sont utilisées ; fermez un notebook et réessayez
+session-engine-restarted = Le moteur de calcul a redémarré pendant la création de la session ; réessayez
+backend-unreachable = Serveur injoignable : { $error }
+backend-status-error = Le serveur a renvoyé le statut { $status }
+resources-unavailable = Impossible de lire les ressources système : { $error }
+engine-not-running = Le moteur de calcul n'est pas démarré ; consultez ses journaux pour connaître l'erreur
+
+## Tunnels
+
+tunnel-unreachable = Impossible de joindre { $host } : { $error }
+tunnel-auth-failed = Échec de l'authentification SSH pour { $user }@{ $host }
+tunnel-host-unknown = { $host } n'est pas dans known_hosts ; connectez-vous une fois avec ssh pour vérifier sa clé, ou faites-lui confiance explicitement
+tunnel-host-key-changed = La clé d'hôte de { $host } a changé ; connexion refusée
+
+## Resource alerts
+
+metric-engine-cpu = CPU du moteur de calcul
+metric-engine-memory = Mémoire du moteur de calcul
+metric-engine-disk-write = Écritures disque du moteur de calcul
+metric-app-cpu = CPU de NOVEM
+metric-app-memory = Mémoire de NOVEM
+alert-raised = { $metric } dépasse { $threshold } depuis { $seconds } secondes (actuellement { $value })
+alert-cleared = { $metric } est repassé sous { $threshold } (pic à { $peak })
+
+## Startup recovery
+
+recovery-sample-removed = Échantillon non enregistré { $uuid } supprimé
+recovery-sample-kept = L'échantillon { $uuid } était déjà enregistré
+recovery-transform-removed = Résultat de recette non enregistré { $uuid } supprimé
+recovery-transform-kept = Le résultat de recette { $uuid } était déjà enregistré
+recovery-append-discarded = Ajout partiel au jeu de données { $uuid } annulé
+recovery-append-finished = Ajout au jeu de données { $uuid } terminé ({ $rows } lignes)
+recovery-clone-cleaned = Copies préparées nettoyées ({ $moved } déjà validées)
+recovery-clone-moved = { $moved } copies validées de jeux de données mises en place
+recovery-clone-removed = Copies d'un clonage non validé supprimées
+recovery-engine-update-restored = Moteur de calcul restauré après une mise à jour interrompue
+recovery-engine-update-kept = Mise à jour interrompue du moteur de calcul terminée
+recovery-attachment-removed = Pièce jointe non enregistrée { $uuid } supprimée
+recovery-attachment-kept = La pièce jointe { $uuid } était déjà enregistrée
+
+## Notifications
+
+notify-engine-crashed = Le moteur de calcul s'est arrêté brutalement
+notify-engine-degraded = Le moteur de calcul ne répond pas normalement
+notify-engine-project = Moteur du projet { $id }
+notify-refresh-failed = Échec de l'actualisation du jeu de données
+notify-job-finished = Tâche { $kind } terminée
+notify-job-failed = Échec de la tâche { $kind }
+notify-sync-conflict = Un conflit de synchronisation doit être résolu
+notify-sync-conflict-body = Cet appareil et le serveur ont tous deux modifié { $entity } { $uuid }
+notify-schedule-failed = Échec de la tâche planifiée « { $name } »
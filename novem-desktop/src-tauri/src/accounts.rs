@@ -0,0 +1,162 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::i18n::tr;
+use crate::timestamps;
+
+/// Registry of the accounts used on this device, at the root of app data.
+/// It can't live in a database: it decides which database to open.
+const ACCOUNTS_FILE: &str = "accounts.json";
+
+/// Each account's database and files live in a folder named after its UUID.
+const ACCOUNTS_DIR: &str = "accounts";
+
+/// An account that has signed in on this device and has data here.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LocalAccount {
+    pub uuid: String,
+    pub email: Option<String>,
+    pub display_name: Option<String>,
+    pub last_used_at: String,
+    /// Whether its database is the one open now
+    #[serde(default, skip_deserializing)]
+    pub active: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Registry {
+    active: Option<String>,
+    accounts: Vec<LocalAccount>,
+}
+
+fn load(app_dir: &Path) -> Result<Registry> {
+    let path = app_dir.join(ACCOUNTS_FILE);
+    match std::fs::read_to_string(&path) {
+        Ok(json) => serde_json::from_str(&json).context(format!("Failed to parse {:?}", path)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Registry::default()),
+        Err(e) => Err(e).context(format!("Failed to read {:?}", path)),
+    }
+}
+
+/// Written aside and renamed over, so a crash never leaves half a registry.
+fn save(app_dir: &Path, registry: &Registry) -> Result<()> {
+    let path = app_dir.join(ACCOUNTS_FILE);
+    let temp = app_dir.join(format!("{}.tmp", ACCOUNTS_FILE));
+    std::fs::write(&temp, serde_json::to_string_pretty(registry)?).context(format!("Failed to write {:?}", temp))?;
+    std::fs::rename(&temp, &path).context(format!("Failed to replace {:?}", path))?;
+    Ok(())
+}
+
+/// Canonical form of an account UUID, which also makes it safe as a folder
+/// name.
+pub fn normalize_uuid(uuid: &str) -> Result<String> {
+    uuid::Uuid::parse_str(uuid.trim())
+        .map(|uuid| uuid.hyphenated().to_string())
+        .map_err(|_| anyhow::anyhow!(tr!("account-uuid-invalid", uuid = uuid)))
+}
+
+/// Where an account keeps its database and files. Without an account this
+/// is app data itself, which also holds everything from before accounts
+/// were separated.
+pub fn data_dir(app_dir: &Path, account: Option<&str>) -> PathBuf {
+    match account {
+        Some(uuid) => app_dir.join(ACCOUNTS_DIR).join(uuid),
+        None => app_dir.to_path_buf(),
+    }
+}
+
+/// The account whose data was open last, to reopen at startup.
+pub fn active(app_dir: &Path) -> Result<Option<String>> {
+    Ok(load(app_dir)?.active)
+}
+
+/// Every account with data on this device, most recently used first.
+pub fn list(app_dir: &Path) -> Result<Vec<LocalAccount>> {
+    let registry = load(app_dir)?;
+    let mut accounts = registry.accounts;
+    for account in &mut accounts {
+        account.active = registry.active.as_deref() == Some(account.uuid.as_str());
+    }
+    accounts.sort_by(|a, b| b.last_used_at.cmp(&a.last_used_at));
+    Ok(accounts)
+}
+
+/// Records `uuid` as the open account, adding it on first use. `None`
+/// records that no account is open. Details left out keep what was known.
+pub fn set_active(
+    app_dir: &Path,
+    uuid: Option<&str>,
+    email: Option<String>,
+    display_name: Option<String>,
+) -> Result<Option<LocalAccount>> {
+    let mut registry = load(app_dir)?;
+    registry.active = uuid.map(str::to_string);
+
+    let account = match uuid {
+        Some(uuid) => {
+            let index = match registry.accounts.iter().position(|account| account.uuid == uuid) {
+                Some(index) => index,
+                None => {
+                    registry.accounts.push(LocalAccount {
+                        uuid: uuid.to_string(),
+                        email: None,
+                        display_name: None,
+                        last_used_at: String::new(),
+                        active: false,
+                    });
+                    registry.accounts.len() - 1
+                }
+            };
+            let account = &mut registry.accounts[index];
+            account.email = email.or(account.email.take());
+            account.display_name = display_name.or(account.display_name.take());
+            account.last_used_at = timestamps::now();
+            Some(LocalAccount { active: true, ..account.clone() })
+        }
+        None => None,
+    };
+
+    save(app_dir, &registry)?;
+    Ok(account)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_registry() {
+        let dir = std::env::temp_dir().join("test_novem_accounts");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        assert!(list(&dir).unwrap().is_empty());
+        assert_eq!(active(&dir).unwrap(), None);
+
+        let ana = normalize_uuid(" 6F9619FF-8B86-D011-B42D-00C04FC964FF ").unwrap();
+        assert_eq!(ana, "6f9619ff-8b86-d011-b42d-00c04fc964ff");
+        assert!(normalize_uuid("../other").is_err());
+        assert_eq!(data_dir(&dir, Some(&ana)), dir.join("accounts").join(&ana));
+        assert_eq!(data_dir(&dir, None), dir);
+
+        set_active(&dir, Some(&ana), Some("ana@example.com".to_string()), None).unwrap();
+        let ben = uuid::Uuid::new_v4().to_string();
+        set_active(&dir, Some(&ben), None, Some("Ben".to_string())).unwrap();
+
+        let accounts = list(&dir).unwrap();
+        assert_eq!(accounts.len(), 2);
+        assert_eq!(active(&dir).unwrap().as_deref(), Some(ben.as_str()));
+        assert!(accounts.iter().find(|a| a.uuid == ben).unwrap().active);
+
+        // Switching back keeps the details given before
+        let account = set_active(&dir, Some(&ana), None, None).unwrap().unwrap();
+        assert_eq!(account.email.as_deref(), Some("ana@example.com"));
+        assert!(account.active);
+
+        assert_eq!(set_active(&dir, None, None, None).unwrap(), None);
+        assert!(list(&dir).unwrap().iter().all(|a| !a.active));
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
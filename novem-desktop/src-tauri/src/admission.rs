@@ -0,0 +1,237 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Notify;
+
+use crate::i18n::tr;
+
+pub const DEFAULT_MAX_CONCURRENT: usize = 4;
+pub const DEFAULT_MAX_QUEUED: usize = 32;
+pub const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 300;
+
+/// Setting key holding the admission limits as JSON.
+pub const ADMISSION_SETTING: &str = "engine.admission";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdmissionConfig {
+    /// Requests an engine works on at once
+    pub max_concurrent: usize,
+    /// Requests allowed to wait; beyond this new requests are rejected
+    pub max_queued: usize,
+    /// How long an admitted request may take unless the caller asks for
+    /// its own timeout; time spent queued doesn't count
+    pub request_timeout_secs: u64,
+}
+
+impl Default for AdmissionConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: DEFAULT_MAX_CONCURRENT,
+            max_queued: DEFAULT_MAX_QUEUED,
+            request_timeout_secs: DEFAULT_REQUEST_TIMEOUT_SECS,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum AdmissionError {
+    EngineBusy { queued: usize, limit: usize },
+}
+
+impl std::fmt::Display for AdmissionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AdmissionError::EngineBusy { queued, limit } => {
+                f.write_str(&tr!("engine-busy", queued = *queued, limit = *limit))
+            }
+        }
+    }
+}
+
+impl std::error::Error for AdmissionError {}
+
+/// Emitted as `engine-queue-position` while a request waits; `position` 0
+/// means it has been admitted.
+#[derive(Debug, Clone, Serialize)]
+pub struct QueuePosition {
+    pub request_id: String,
+    pub project_id: Option<i64>,
+    pub position: usize,
+    pub queued: usize,
+}
+
+/// Snapshot of one engine's admission queue.
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueStatus {
+    pub project_id: Option<i64>,
+    pub running: usize,
+    pub queued: usize,
+    pub max_concurrent: usize,
+    pub max_queued: usize,
+    pub running_requests: Vec<String>,
+}
+
+struct Queue {
+    config: AdmissionConfig,
+    /// Ticket and request ID of each admitted request
+    running: Vec<(u64, String)>,
+    waiting: VecDeque<u64>,
+    next_ticket: u64,
+}
+
+/// FIFO admission for one engine: at most `max_concurrent` requests run,
+/// up to `max_queued` wait in order, and the rest are shed immediately.
+pub struct AdmissionController {
+    queue: Mutex<Queue>,
+    changed: Notify,
+    project_id: Option<i64>,
+    app: AppHandle,
+}
+
+/// Held while a request runs; frees its slot when dropped.
+pub struct AdmissionPermit {
+    controller: Arc<AdmissionController>,
+    ticket: u64,
+}
+
+impl Drop for AdmissionPermit {
+    fn drop(&mut self) {
+        self.controller.queue.lock().unwrap().running.retain(|(ticket, _)| *ticket != self.ticket);
+        self.controller.changed.notify_waiters();
+    }
+}
+
+/// Removes an abandoned ticket (e.g. the caller went away) from the queue.
+struct Ticket<'a> {
+    controller: &'a AdmissionController,
+    id: u64,
+    admitted: bool,
+}
+
+impl Drop for Ticket<'_> {
+    fn drop(&mut self) {
+        if !self.admitted {
+            self.controller.queue.lock().unwrap().waiting.retain(|id| *id != self.id);
+            self.controller.changed.notify_waiters();
+        }
+    }
+}
+
+impl AdmissionController {
+    pub fn new(app: AppHandle, project_id: Option<i64>, config: AdmissionConfig) -> Self {
+        Self {
+            queue: Mutex::new(Queue {
+                config,
+                running: Vec::new(),
+                waiting: VecDeque::new(),
+                next_ticket: 0,
+            }),
+            changed: Notify::new(),
+            project_id,
+            app,
+        }
+    }
+
+    pub fn set_config(&self, config: AdmissionConfig) {
+        self.queue.lock().unwrap().config = config;
+        // A higher limit may admit waiting requests right away
+        self.changed.notify_waiters();
+    }
+
+    /// Request IDs currently holding a slot on this engine.
+    pub fn running_requests(&self) -> Vec<String> {
+        self.queue
+            .lock()
+            .unwrap()
+            .running
+            .iter()
+            .map(|(_, request_id)| request_id.clone())
+            .collect()
+    }
+
+    pub fn status(&self) -> QueueStatus {
+        let queue = self.queue.lock().unwrap();
+        QueueStatus {
+            project_id: self.project_id,
+            running: queue.running.len(),
+            queued: queue.waiting.len(),
+            max_concurrent: queue.config.max_concurrent,
+            max_queued: queue.config.max_queued,
+            running_requests: queue.running.iter().map(|(_, request_id)| request_id.clone()).collect(),
+        }
+    }
+
+    fn emit_position(&self, request_id: &str, position: usize, queued: usize) {
+        let _ = self.app.emit(
+            "engine-queue-position",
+            QueuePosition {
+                request_id: request_id.to_string(),
+                project_id: self.project_id,
+                position,
+                queued,
+            },
+        );
+    }
+
+    /// Waits for a slot on this engine, or fails fast when the queue is full.
+    pub async fn acquire(self: &Arc<Self>, request_id: &str) -> Result<AdmissionPermit, AdmissionError> {
+        let id = {
+            let mut queue = self.queue.lock().unwrap();
+
+            let id = queue.next_ticket;
+            queue.next_ticket += 1;
+
+            if queue.waiting.is_empty() && queue.running.len() < queue.config.max_concurrent.max(1) {
+                queue.running.push((id, request_id.to_string()));
+                return Ok(AdmissionPermit { controller: Arc::clone(self), ticket: id });
+            }
+
+            if queue.waiting.len() >= queue.config.max_queued {
+                return Err(AdmissionError::EngineBusy {
+                    queued: queue.waiting.len(),
+                    limit: queue.config.max_queued,
+                });
+            }
+
+            queue.waiting.push_back(id);
+            id
+        };
+
+        let mut ticket = Ticket { controller: self, id, admitted: false };
+        let mut last_position = None;
+
+        loop {
+            // Registered before checking so a release in between isn't missed
+            let changed = self.changed.notified();
+
+            let (position, queued) = {
+                let mut queue = self.queue.lock().unwrap();
+                let position = queue.waiting.iter().position(|t| *t == id).unwrap_or(0);
+
+                if position == 0 && queue.running.len() < queue.config.max_concurrent.max(1) {
+                    queue.waiting.pop_front();
+                    queue.running.push((id, request_id.to_string()));
+                    ticket.admitted = true;
+                    let queued = queue.waiting.len();
+                    drop(queue);
+
+                    self.emit_position(request_id, 0, queued);
+                    // Everyone behind moved up one place
+                    self.changed.notify_waiters();
+                    return Ok(AdmissionPermit { controller: Arc::clone(self), ticket: id });
+                }
+
+                (position + 1, queue.waiting.len())
+            };
+
+            if last_position != Some(position) {
+                last_position = Some(position);
+                self.emit_position(request_id, position, queued);
+            }
+
+            changed.await;
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::i18n::tr;
+
+const COPY_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Extensions the UI previews or opens differently; anything else is
+/// stored as `application/octet-stream`.
+const MIME_TYPES: &[(&str, &str)] = &[
+    ("csv", "text/csv"),
+    ("tsv", "text/tab-separated-values"),
+    ("txt", "text/plain"),
+    ("md", "text/markdown"),
+    ("json", "application/json"),
+    ("parquet", "application/vnd.apache.parquet"),
+    ("pdf", "application/pdf"),
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("svg", "image/svg+xml"),
+    ("html", "text/html"),
+    ("xlsx", "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+    ("zip", "application/zip"),
+    ("ipynb", "application/x-ipynb+json"),
+];
+
+/// A file copied into managed storage.
+#[derive(Debug, Clone)]
+pub struct StoredFile {
+    pub path: PathBuf,
+    /// SHA-256 of the contents, lowercase hex
+    pub checksum: String,
+    pub size_bytes: i64,
+}
+
+/// Where an attachment's copy lives. The original extension is kept so
+/// the file opens in the right application.
+pub fn managed_attachment_path(data_dir: &Path, uuid: &str, source: &Path) -> PathBuf {
+    let file_name = match source.extension() {
+        Some(ext) => format!("{}.{}", uuid, ext.to_string_lossy().to_lowercase()),
+        None => uuid.to_string(),
+    };
+    data_dir.join("attachments").join(file_name)
+}
+
+pub fn mime_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    MIME_TYPES
+        .iter()
+        .find(|(ext, _)| *ext == extension)
+        .map(|(_, mime)| *mime)
+        .unwrap_or("application/octet-stream")
+}
+
+/// Copies `source` to `dest`, hashing it on the way. The copy is written
+/// next to `dest` and renamed into place, so `dest` never holds a partial
+/// file.
+pub fn copy_into_storage(source: &Path, dest: &Path) -> Result<StoredFile> {
+    if !source.is_file() {
+        anyhow::bail!(tr!("attachment-not-a-file", path = source.display().to_string()));
+    }
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent).context(format!("Failed to create {:?}", parent))?;
+    }
+
+    let partial = dest.with_extension("partial");
+    let stored = write_copy(source, &partial).and_then(|stored| {
+        std::fs::rename(&partial, dest).context(format!("Failed to move attachment to {:?}", dest))?;
+        Ok(StoredFile { path: dest.to_path_buf(), ..stored })
+    });
+    if stored.is_err() {
+        let _ = std::fs::remove_file(&partial);
+    }
+    stored
+}
+
+fn write_copy(source: &Path, dest: &Path) -> Result<StoredFile> {
+    let mut input = File::open(source).context(format!("Failed to open {:?}", source))?;
+    let mut output = File::create(dest).context(format!("Failed to create {:?}", dest))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; COPY_BUFFER_SIZE];
+    let mut size_bytes = 0i64;
+
+    loop {
+        let read = input.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        output.write_all(&buffer[..read])?;
+        size_bytes += read as i64;
+    }
+    output.sync_all()?;
+
+    Ok(StoredFile {
+        path: dest.to_path_buf(),
+        checksum: hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect(),
+        size_bytes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_copy_into_storage() {
+        let dir = std::env::temp_dir().join("test_novem_attachments");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let source = dir.join("Notes.MD");
+        std::fs::write(&source, "abc").unwrap();
+        assert_eq!(mime_type(&source), "text/markdown");
+        assert_eq!(mime_type(Path::new("model.bin")), "application/octet-stream");
+
+        let dest = managed_attachment_path(&dir, "a1", &source);
+        assert_eq!(dest, dir.join("attachments").join("a1.md"));
+
+        let stored = copy_into_storage(&source, &dest).unwrap();
+        assert_eq!(stored.size_bytes, 3);
+        assert_eq!(stored.checksum, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(std::fs::read_to_string(&dest).unwrap(), "abc");
+        assert!(!dest.with_extension("partial").exists());
+
+        assert!(copy_into_storage(&dir, &dir.join("attachments").join("a2")).is_err());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
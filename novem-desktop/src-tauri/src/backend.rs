@@ -0,0 +1,36 @@
+use reqwest::RequestBuilder;
+
+pub const DEFAULT_BACKEND_URL: &str = "http://localhost:8000";
+
+/// Connection details for the NOVEM cloud backend.
+///
+/// The frontend owns the login flow and hands the current access token to
+/// Rust so background work (uploads, sync) can talk to the backend on its own.
+#[derive(Clone)]
+pub struct BackendSession {
+    pub base_url: String,
+    pub access_token: Option<String>,
+    /// Signed-in user, credited with actions in the activity log
+    pub user_id: Option<i64>,
+}
+
+impl BackendSession {
+    pub fn new() -> Self {
+        Self {
+            base_url: DEFAULT_BACKEND_URL.to_string(),
+            access_token: None,
+            user_id: None,
+        }
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url.trim_end_matches('/'), path)
+    }
+
+    pub fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.access_token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use tauri::State;
+
+use crate::database::{ActivityFilter, ActivityPage, ACTIVITY_RETENTION_SETTING};
+use crate::i18n::tr;
+use crate::AppState;
+
+/// The activity feed, newest first. Pass a page's `next_cursor` back as
+/// `before_id` for the next one.
+#[tauri::command]
+pub async fn get_activity_log(
+    state: State<'_, AppState>,
+    filter: Option<ActivityFilter>,
+) -> Result<ActivityPage, String> {
+    let filter = filter.unwrap_or_default();
+    state
+        .with_db_async(move |db| db.get_activity_log(&filter)).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_activity_retention(state: State<'_, AppState>) -> Result<i64, String> {
+    state
+        .with_db_async(|db| db.activity_retention_days()).await
+        .map_err(|e| e.to_string())
+}
+
+/// How many days of activity are kept. Persisted; older entries are
+/// dropped now and on every launch.
+#[tauri::command]
+pub async fn set_activity_retention(state: State<'_, AppState>, days: i64) -> Result<usize, String> {
+    if days < 1 {
+        return Err(tr!("activity-retention-invalid"));
+    }
+
+    let pruned = state
+        .with_db_async(move |db| {
+            db.set_setting(ACTIVITY_RETENTION_SETTING, &days.to_string())?;
+            db.prune_activity_log(days)
+        }).await
+        .map_err(|e| e.to_string())?;
+
+    println!("[NOVEM] Activity log kept for {} days ({} entries pruned)", days, pruned);
+    Ok(pruned)
+}
@@ -0,0 +1,122 @@
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use crate::attachments;
+use crate::database::{Attachment, NewActivity, NewAttachment};
+use crate::recovery::{self, Operation};
+use crate::AppState;
+
+/// Copies a file into the app's managed storage and attaches it to a
+/// project. The original is left untouched.
+#[tauri::command]
+pub async fn add_attachment(
+    state: State<'_, AppState>,
+    project_id: i64,
+    path: String,
+) -> Result<Attachment, String> {
+    let source = PathBuf::from(&path);
+    let uuid = uuid::Uuid::new_v4().to_string();
+    let dest = attachments::managed_attachment_path(&state.data_dir(), &uuid, &source);
+
+    let operation = Operation::AddAttachment {
+        attachment_uuid: uuid.clone(),
+        file_path: dest.to_string_lossy().to_string(),
+    };
+    let journal = recovery::begin(&state, &operation);
+
+    let result = store_and_register(&state, project_id, &source, uuid, &dest).await;
+    if result.is_ok() {
+        recovery::finish(&state, journal, "completed");
+    } else {
+        if let Err(e) = state.with_db_async(move |db| recovery::roll_back(db, &operation)).await {
+            eprintln!("[ERROR] Failed to clean up attachment {:?}: {}", dest, e);
+        }
+        recovery::finish(&state, journal, "rolled_back");
+    }
+    let attachment = result?;
+
+    println!(
+        "[NOVEM] Attached {} to project {} ({} bytes)",
+        attachment.name, project_id, attachment.size_bytes
+    );
+    state
+        .log_activity(
+            NewActivity::new("create", "attachment", Some(&attachment.uuid)).after(serde_json::json!({
+                "project_id": project_id,
+                "name": attachment.name,
+                "size_bytes": attachment.size_bytes,
+            })),
+        )
+        .await;
+    Ok(attachment)
+}
+
+async fn store_and_register(
+    state: &AppState,
+    project_id: i64,
+    source: &Path,
+    uuid: String,
+    dest: &Path,
+) -> Result<Attachment, String> {
+    let stored = {
+        let source = source.to_path_buf();
+        let dest = dest.to_path_buf();
+        tauri::async_runtime::spawn_blocking(move || attachments::copy_into_storage(&source, &dest))
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| e.to_string())?
+    };
+
+    let attachment = NewAttachment {
+        uuid,
+        project_id,
+        name: source
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        file_path: stored.path.to_string_lossy().to_string(),
+        checksum: stored.checksum,
+        mime_type: attachments::mime_type(source).to_string(),
+        size_bytes: stored.size_bytes,
+    };
+    state
+        .with_db_async(move |db| db.insert_attachment(&attachment)).await
+        .map_err(|e| e.to_string())
+}
+
+/// A project's attachments, newest first.
+#[tauri::command]
+pub async fn list_attachments(state: State<'_, AppState>, project_id: i64) -> Result<Vec<Attachment>, String> {
+    state
+        .with_db_async(move |db| db.list_attachments(project_id)).await
+        .map_err(|e| e.to_string())
+}
+
+/// Detaches a file and deletes its managed copy.
+#[tauri::command]
+pub async fn remove_attachment(state: State<'_, AppState>, uuid: String) -> Result<(), String> {
+    let attachment = state
+        .with_db_async({
+            let uuid = uuid.clone();
+            move |db| db.delete_attachment(&uuid)
+        }).await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Attachment {} not found", uuid))?;
+
+    if let Err(e) = std::fs::remove_file(&attachment.file_path) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            eprintln!("[WARNING] Failed to remove attachment file {}: {}", attachment.file_path, e);
+        }
+    }
+
+    state
+        .log_activity(
+            NewActivity::new("delete", "attachment", Some(&attachment.uuid)).before(serde_json::json!({
+                "project_id": attachment.project_id,
+                "name": attachment.name,
+                "checksum": attachment.checksum,
+            })),
+        )
+        .await;
+    Ok(())
+}
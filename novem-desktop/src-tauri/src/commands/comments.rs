@@ -0,0 +1,93 @@
+use tauri::State;
+
+use crate::database::{Comment, CommentTarget, NewActivity};
+use crate::AppState;
+
+/// Comments on a project or notebook cell, oldest first. Resolved ones are
+/// left out unless `include_resolved` is set.
+#[tauri::command]
+pub async fn list_comments(
+    state: State<'_, AppState>,
+    target: CommentTarget,
+    include_resolved: Option<bool>,
+) -> Result<Vec<Comment>, String> {
+    state
+        .with_db_async(move |db| db.list_comments(&target, include_resolved.unwrap_or(false))).await
+        .map_err(|e| e.to_string())
+}
+
+/// Adds a comment as the signed-in user. Works offline; it is queued for
+/// sync like any other local change.
+#[tauri::command]
+pub async fn create_comment(
+    state: State<'_, AppState>,
+    target: CommentTarget,
+    body: String,
+) -> Result<Comment, String> {
+    let author_id = state.backend.lock()
+        .map_err(|e| format!("Failed to lock backend session: {}", e))?
+        .user_id;
+
+    let comment = state
+        .with_db_async(move |db| db.create_comment(&target, author_id, &body)).await
+        .map_err(|e| e.to_string())?;
+
+    state
+        .log_activity(
+            NewActivity::new("create", "comment", Some(&comment.uuid)).after(serde_json::json!({
+                "entity_type": comment.entity_type,
+                "entity_uuid": comment.entity_uuid,
+            })),
+        )
+        .await;
+    Ok(comment)
+}
+
+/// Edits a comment's text, resolves or reopens it, or both.
+#[tauri::command]
+pub async fn update_comment(
+    state: State<'_, AppState>,
+    uuid: String,
+    body: Option<String>,
+    resolved: Option<bool>,
+) -> Result<Comment, String> {
+    let comment = state
+        .with_db_async({
+            let uuid = uuid.clone();
+            move |db| db.update_comment(&uuid, body.as_deref(), resolved)
+        }).await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Comment {} not found", uuid))?;
+
+    let action = match resolved {
+        Some(true) => "resolve",
+        Some(false) => "reopen",
+        None => "update",
+    };
+    state
+        .log_activity(NewActivity::new(action, "comment", Some(&comment.uuid)))
+        .await;
+    Ok(comment)
+}
+
+#[tauri::command]
+pub async fn delete_comment(state: State<'_, AppState>, uuid: String) -> Result<(), String> {
+    let comment = state
+        .with_db_async({
+            let uuid = uuid.clone();
+            move |db| db.delete_comment(&uuid)
+        }).await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Comment {} not found", uuid))?;
+
+    state
+        .log_activity(
+            NewActivity::new("delete", "comment", Some(&comment.uuid)).before(serde_json::json!({
+                "entity_type": comment.entity_type,
+                "entity_uuid": comment.entity_uuid,
+                "body": comment.body,
+            })),
+        )
+        .await;
+    Ok(())
+}
@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::State;
+
+use crate::database::{DataConnection, NewActivity, NewDataConnection};
+use crate::i18n::tr;
+use crate::proxy;
+use crate::secrets::{self, VaultKey};
+use crate::AppState;
+
+/// How long the engine may spend reaching a data source.
+const TEST_TIMEOUT_SECS: u64 = 15;
+
+/// Outcome of `test_connection`, as reported by the compute engine.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionTest {
+    pub ok: bool,
+    /// 'login' when the credentials were tried, 'network' when only the
+    /// host was reached (no driver installed in the engine)
+    pub checked: Option<String>,
+    pub latency_ms: u64,
+    pub message: Option<String>,
+}
+
+/// Registers an external data source. Credentials are referenced by the
+/// name of a secret stored with `store_secret`, never passed in directly.
+#[tauri::command]
+pub async fn create_connection(
+    state: State<'_, AppState>,
+    connection: NewDataConnection,
+) -> Result<DataConnection, String> {
+    if connection.name.trim().is_empty() {
+        return Err(tr!("connection-name-empty"));
+    }
+
+    let uuid = uuid::Uuid::new_v4().to_string();
+    let connection = state
+        .with_db_async(move |db| {
+            if let Some(secret_name) = &connection.secret_name {
+                if db.get_sealed_secret(secret_name)?.is_none() {
+                    return Err(anyhow::anyhow!(tr!("secret-not-found", name = secret_name.as_str())));
+                }
+            }
+            db.create_connection(&uuid, &connection)
+        }).await
+        .map_err(|e| e.to_string())?;
+
+    state
+        .log_activity(NewActivity::new("create", "connection", Some(&connection.uuid)).after(serde_json::json!({
+            "name": connection.name,
+            "kind": connection.kind,
+            "host": connection.host,
+        })))
+        .await;
+    Ok(connection)
+}
+
+/// Connections usable in a workspace, including those shared by all.
+#[tauri::command]
+pub async fn list_connections(
+    state: State<'_, AppState>,
+    workspace_id: Option<i64>,
+) -> Result<Vec<DataConnection>, String> {
+    state
+        .with_db_async(move |db| db.list_connections(workspace_id)).await
+        .map_err(|e| e.to_string())
+}
+
+/// Has the shared compute engine reach the data source with its stored
+/// credentials, and records the outcome on the connection.
+#[tauri::command]
+pub async fn test_connection(state: State<'_, AppState>, uuid: String) -> Result<ConnectionTest, String> {
+    let (connection, secret) = {
+        let uuid = uuid.clone();
+        state
+            .with_db_async(move |db| {
+                let connection = db
+                    .get_connection(&uuid)?
+                    .ok_or_else(|| anyhow::anyhow!(tr!("connection-not-found", uuid = uuid.as_str())))?;
+                let secret = match &connection.secret_name {
+                    Some(name) => Some(secrets::reveal(db, VaultKey::get()?, name)?),
+                    None => None,
+                };
+                Ok((connection, secret))
+            }).await
+            .map_err(|e| e.to_string())?
+    };
+
+    let options: serde_json::Value = serde_json::from_str(&connection.options).unwrap_or_else(|_| serde_json::json!({}));
+    let body = serde_json::json!({
+        "kind": connection.kind,
+        "host": connection.host,
+        "port": connection.port,
+        "database": connection.database,
+        "username": connection.username,
+        "secret": secret,
+        "options": options,
+        "timeout_secs": TEST_TIMEOUT_SECS,
+    });
+
+    let base_url = state.engines.base_url(None).map_err(|e| e.to_string())?;
+    let response = state
+        .http
+        .engine()
+        .post(format!("{}/connections/test", base_url))
+        .timeout(Duration::from_secs(TEST_TIMEOUT_SECS + 5))
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| tr!("engine-unreachable", error = e.to_string()))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = proxy::read_error_body(response).await;
+        return Err(tr!("engine-status-error", status = status.as_u16(), body = body));
+    }
+    let test: ConnectionTest = response.json().await.map_err(|e| e.to_string())?;
+
+    let recorded = test.clone();
+    state
+        .with_db_async(move |db| db.record_connection_test(&uuid, recorded.ok, recorded.message.as_deref())).await
+        .map_err(|e| e.to_string())?;
+
+    if !test.ok {
+        eprintln!("[WARNING] Connection '{}' failed its test", connection.name);
+    }
+    Ok(test)
+}
+
+#[tauri::command]
+pub async fn delete_connection(state: State<'_, AppState>, uuid: String) -> Result<bool, String> {
+    let deleted = {
+        let uuid = uuid.clone();
+        state
+            .with_db_async(move |db| db.delete_connection(&uuid)).await
+            .map_err(|e| e.to_string())?
+    };
+
+    if deleted {
+        state
+            .log_activity(NewActivity::new("delete", "connection", Some(&uuid)))
+            .await;
+    }
+    Ok(deleted)
+}
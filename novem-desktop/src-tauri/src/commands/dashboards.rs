@@ -0,0 +1,52 @@
+use tauri::State;
+
+use crate::dashboards::{self, LayoutSpec, PublishedDashboard};
+use crate::database::{Dashboard, NewActivity};
+use crate::AppState;
+
+#[tauri::command]
+pub async fn publish_dashboard(
+    state: State<'_, AppState>,
+    notebook_uuid: String,
+    layout_spec: Option<LayoutSpec>,
+) -> Result<PublishedDashboard, String> {
+    let artifacts_dir = state.artifacts_dir();
+
+    let published = state
+        .with_db_async(move |db| dashboards::publish(db, &artifacts_dir, &notebook_uuid, layout_spec)).await
+        .map_err(|e| e.to_string())?;
+
+    state
+        .log_activity(
+            NewActivity::new("publish", "dashboard", Some(&published.dashboard.uuid))
+                .after(serde_json::json!({ "notebook_uuid": published.dashboard.notebook_uuid })),
+        )
+        .await;
+    Ok(published)
+}
+
+#[tauri::command]
+pub async fn get_dashboard(
+    state: State<'_, AppState>,
+    uuid: String,
+) -> Result<Option<PublishedDashboard>, String> {
+    let artifacts_dir = state.artifacts_dir();
+
+    let dashboard = state
+        .with_db_async(move |db| db.get_dashboard(&uuid)).await
+        .map_err(|e| e.to_string())?;
+
+    Ok(dashboard.map(|dashboard| PublishedDashboard {
+        render_path: dashboards::render_path(&artifacts_dir, &dashboard.uuid)
+            .to_string_lossy()
+            .to_string(),
+        dashboard,
+    }))
+}
+
+#[tauri::command]
+pub async fn list_dashboards(state: State<'_, AppState>) -> Result<Vec<Dashboard>, String> {
+    state
+        .with_db_async(move |db| db.list_dashboards()).await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,662 @@
+use std::path::Path;
+use std::time::Instant;
+use tauri::{AppHandle, State};
+
+use crate::database::{
+    Dataset, DatasetColumn, DatasetLineage, DatasetRecipe, DatasetRefresh, NewActivity, NewDataset, NewQueryHistory,
+};
+use crate::datasets::recipes::{self, RecipeStep};
+use crate::datasets::sampling::{self, SampleMethod, SampleSpec};
+use crate::datasets::stats::{self, ColumnSketch, ColumnStats};
+use crate::datasets::{self, DatasetFormat};
+use crate::i18n::tr;
+use crate::recovery::{self, Operation};
+use crate::refresh::{self, DatasetFreshness, RefreshSource};
+use crate::resources;
+use crate::{queries, AppState};
+
+#[tauri::command]
+pub async fn register_dataset(
+    state: State<'_, AppState>,
+    project_id: i64,
+    name: String,
+    file_path: String,
+) -> Result<Dataset, String> {
+    let path = Path::new(&file_path);
+    let format = DatasetFormat::from_path(path).map_err(|e| e.to_string())?;
+    let metadata = std::fs::metadata(path)
+        .map_err(|e| tr!("file-unreadable", path = file_path.as_str(), error = e.to_string()))?;
+
+    let dataset = NewDataset {
+        uuid: uuid::Uuid::new_v4().to_string(),
+        project_id,
+        name,
+        file_path,
+        format: format.as_str().to_string(),
+        row_count: None,
+        size_bytes: metadata.len() as i64,
+        parent_uuid: None,
+    };
+
+    let dataset = state
+        .with_db_async(move |db| db.create_dataset(&dataset)).await
+        .map_err(|e| e.to_string())?;
+
+    state
+        .log_activity(NewActivity::new("create", "dataset", Some(&dataset.uuid)).after(serde_json::json!({
+            "name": dataset.name,
+            "file_path": dataset.file_path,
+        })))
+        .await;
+    Ok(dataset)
+}
+
+#[tauri::command]
+pub async fn list_datasets(state: State<'_, AppState>, project_id: i64) -> Result<Vec<Dataset>, String> {
+    state
+        .with_db_async(move |db| db.list_datasets(project_id)).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_dataset_lineage(
+    state: State<'_, AppState>,
+    dataset_uuid: String,
+) -> Result<Vec<DatasetLineage>, String> {
+    state
+        .with_db_async(move |db| db.get_dataset_lineage(&dataset_uuid)).await
+        .map_err(|e| e.to_string())
+}
+
+/// Samples a dataset natively and registers the result as a derived dataset.
+/// The sample becomes the parent's default for exploration.
+#[tauri::command]
+pub async fn create_sample(
+    state: State<'_, AppState>,
+    dataset_uuid: String,
+    method: SampleMethod,
+    size: Option<u64>,
+    fraction: Option<f64>,
+    seed: Option<u64>,
+    stratify_by: Option<String>,
+) -> Result<Dataset, String> {
+    let spec = SampleSpec { method, size, fraction, seed, stratify_by };
+    let sample = sample_dataset(&state, &dataset_uuid, spec).await?;
+
+    state
+        .log_activity(
+            NewActivity::new("sample", "dataset", Some(&sample.uuid))
+                .before(serde_json::json!({ "uuid": dataset_uuid }))
+                .after(&sample.name),
+        )
+        .await;
+    Ok(sample)
+}
+
+/// Creates a sample and records it in the query history so it can be re-run.
+pub(crate) async fn sample_dataset(state: &AppState, dataset_uuid: &str, spec: SampleSpec) -> Result<Dataset, String> {
+    let source = find_dataset(state, dataset_uuid).await?;
+
+    let started = Instant::now();
+    let result = write_sample(state, &source, &spec).await;
+
+    let amount = match (spec.size, spec.fraction) {
+        (Some(size), _) => format!("{} rows", size),
+        (None, Some(fraction)) => format!("{}%", fraction * 100.0),
+        (None, None) => "?".to_string(),
+    };
+    let query_text = match &spec.stratify_by {
+        Some(column) => format!("Stratified sample of {} from '{}' by {}", amount, source.name, column),
+        None => format!("Random sample of {} from '{}'", amount, source.name),
+    };
+
+    queries::record(
+        state,
+        NewQueryHistory {
+            kind: "sample".to_string(),
+            query_text,
+            target: Some(source.uuid.clone()),
+            project_id: Some(source.project_id),
+            params: serde_json::json!({ "dataset_uuid": source.uuid, "spec": spec }).to_string(),
+            duration_ms: started.elapsed().as_millis() as i64,
+            row_count: result.as_ref().ok().and_then(|dataset| dataset.row_count),
+            error_message: result.as_ref().err().cloned(),
+        },
+    );
+
+    result
+}
+
+/// Writes and registers a sample under a journal entry, so a crash in
+/// between never leaves an orphaned sample file.
+async fn write_sample(state: &AppState, source: &Dataset, spec: &SampleSpec) -> Result<Dataset, String> {
+    let sample_uuid = uuid::Uuid::new_v4().to_string();
+    let dest = datasets::managed_dataset_path(&state.data_dir(), &sample_uuid);
+
+    let operation = Operation::Sample {
+        dataset_uuid: sample_uuid.clone(),
+        file_path: dest.to_string_lossy().to_string(),
+    };
+    let journal = recovery::begin(state, &operation);
+
+    let result = write_and_register_sample(state, source, spec, sample_uuid, &dest).await;
+    if result.is_ok() {
+        recovery::finish(state, journal, "completed");
+    } else {
+        if let Err(e) = state.with_db_async(move |db| recovery::roll_back(db, &operation)).await {
+            eprintln!("[ERROR] Failed to clean up sample {:?}: {}", dest, e);
+        }
+        recovery::finish(state, journal, "rolled_back");
+    }
+    result
+}
+
+async fn write_and_register_sample(
+    state: &AppState,
+    source: &Dataset,
+    spec: &SampleSpec,
+    sample_uuid: String,
+    dest: &Path,
+) -> Result<Dataset, String> {
+    let format = DatasetFormat::parse(&source.format).map_err(|e| e.to_string())?;
+
+    let outcome = {
+        let source_path = source.file_path.clone();
+        let dest = dest.to_path_buf();
+        let spec = spec.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            sampling::sample_file(Path::new(&source_path), format, &dest, &spec)
+        })
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?
+    };
+
+    let size_bytes = std::fs::metadata(dest).map(|m| m.len() as i64).unwrap_or(0);
+    let params = serde_json::json!({
+        "method": spec.method,
+        "size": spec.size,
+        "fraction": spec.fraction,
+        "seed": outcome.seed,
+        "stratify_by": spec.stratify_by,
+        "source_rows": outcome.source_rows,
+    });
+
+    let sample = NewDataset {
+        uuid: sample_uuid,
+        project_id: source.project_id,
+        name: format!("{} (sample)", source.name),
+        file_path: dest.to_string_lossy().to_string(),
+        format: DatasetFormat::Parquet.as_str().to_string(),
+        row_count: Some(outcome.sample_rows as i64),
+        size_bytes,
+        parent_uuid: Some(source.uuid.clone()),
+    };
+
+    let source_uuid = source.uuid.clone();
+    state
+        .with_db_async(move |db| {
+            let dataset = db.create_dataset(&sample)?;
+            db.add_dataset_lineage(&dataset.uuid, &source_uuid, "sample", &params.to_string())?;
+            db.set_default_sample(&source_uuid, Some(&dataset.uuid))?;
+            Ok(dataset)
+        }).await
+        .map_err(|e| e.to_string())
+}
+
+/// Stores a recipe for a dataset, replacing the dataset's recipe of the
+/// same name. The steps are checked against the dataset's columns first.
+#[tauri::command]
+pub async fn save_recipe(
+    state: State<'_, AppState>,
+    dataset_uuid: String,
+    name: String,
+    steps: Vec<RecipeStep>,
+) -> Result<DatasetRecipe, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(tr!("recipe-name-empty"));
+    }
+
+    let dataset = find_dataset(&state, &dataset_uuid).await?;
+    let format = DatasetFormat::parse(&dataset.format).map_err(|e| e.to_string())?;
+    let checked = steps.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let schema = datasets::read_schema(Path::new(&dataset.file_path), format)?;
+        recipes::output_schema(&schema, &checked)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| format!("{:#}", e))?;
+
+    let steps_json = serde_json::to_string(&steps).map_err(|e| e.to_string())?;
+    let recipe = state
+        .with_db_async(move |db| db.save_recipe(&dataset_uuid, &name, &steps_json)).await
+        .map_err(|e| e.to_string())?;
+
+    state
+        .log_activity(NewActivity::new("save", "recipe", Some(&recipe.uuid)).after(serde_json::json!({
+            "name": recipe.name,
+            "steps": recipe.steps,
+        })))
+        .await;
+    Ok(recipe)
+}
+
+#[tauri::command]
+pub async fn list_recipes(state: State<'_, AppState>, dataset_uuid: String) -> Result<Vec<DatasetRecipe>, String> {
+    state
+        .with_db_async(move |db| db.list_recipes(&dataset_uuid)).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_recipe(state: State<'_, AppState>, recipe_uuid: String) -> Result<(), String> {
+    let uuid = recipe_uuid.clone();
+    let deleted = state
+        .with_db_async(move |db| {
+            let recipe = db.get_recipe(&uuid)?;
+            Ok(if db.delete_recipe(&uuid)? { recipe } else { None })
+        }).await
+        .map_err(|e| e.to_string())?;
+    let Some(recipe) = deleted else {
+        return Err(tr!("recipe-not-found", uuid = recipe_uuid.as_str()));
+    };
+
+    state
+        .log_activity(NewActivity::new("delete", "recipe", Some(&recipe_uuid)).before(serde_json::json!({
+            "name": recipe.name,
+            "steps": recipe.steps,
+        })))
+        .await;
+    Ok(())
+}
+
+/// Runs a stored recipe over a dataset (usually the one it was saved for)
+/// and registers the output as a derived dataset with lineage.
+#[tauri::command]
+pub async fn apply_recipe(
+    state: State<'_, AppState>,
+    dataset_uuid: String,
+    recipe_uuid: String,
+) -> Result<Dataset, String> {
+    let uuid = recipe_uuid.clone();
+    let recipe = state
+        .with_db_async(move |db| db.get_recipe(&uuid)).await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| tr!("recipe-not-found", uuid = recipe_uuid.as_str()))?;
+
+    let output = transform_dataset(&state, &dataset_uuid, &recipe).await?;
+
+    state
+        .log_activity(
+            NewActivity::new("transform", "dataset", Some(&output.uuid))
+                .before(serde_json::json!({ "uuid": dataset_uuid, "recipe_uuid": recipe_uuid }))
+                .after(&output.name),
+        )
+        .await;
+    Ok(output)
+}
+
+/// Applies a recipe and records it in the query history so it can be re-run.
+pub(crate) async fn transform_dataset(state: &AppState, dataset_uuid: &str, recipe: &DatasetRecipe) -> Result<Dataset, String> {
+    let source = find_dataset(state, dataset_uuid).await?;
+    let steps: Vec<RecipeStep> = serde_json::from_value(recipe.steps.clone()).map_err(|e| e.to_string())?;
+
+    let started = Instant::now();
+    let result = write_transform(state, &source, recipe, &steps).await;
+
+    queries::record(
+        state,
+        NewQueryHistory {
+            kind: "recipe".to_string(),
+            query_text: format!("Recipe '{}' ({} steps) on '{}'", recipe.name, steps.len(), source.name),
+            target: Some(source.uuid.clone()),
+            project_id: Some(source.project_id),
+            params: serde_json::json!({ "dataset_uuid": source.uuid, "recipe": recipe }).to_string(),
+            duration_ms: started.elapsed().as_millis() as i64,
+            row_count: result.as_ref().ok().and_then(|dataset| dataset.row_count),
+            error_message: result.as_ref().err().cloned(),
+        },
+    );
+
+    result
+}
+
+/// Writes and registers the recipe output under a journal entry, as
+/// `write_sample` does.
+async fn write_transform(
+    state: &AppState,
+    source: &Dataset,
+    recipe: &DatasetRecipe,
+    steps: &[RecipeStep],
+) -> Result<Dataset, String> {
+    let output_uuid = uuid::Uuid::new_v4().to_string();
+    let dest = datasets::managed_dataset_path(&state.data_dir(), &output_uuid);
+
+    let operation = Operation::Transform {
+        dataset_uuid: output_uuid.clone(),
+        file_path: dest.to_string_lossy().to_string(),
+    };
+    let journal = recovery::begin(state, &operation);
+
+    let result = write_and_register_transform(state, source, recipe, steps, output_uuid, &dest).await;
+    if result.is_ok() {
+        recovery::finish(state, journal, "completed");
+    } else {
+        if let Err(e) = state.with_db_async(move |db| recovery::roll_back(db, &operation)).await {
+            eprintln!("[ERROR] Failed to clean up recipe output {:?}: {}", dest, e);
+        }
+        recovery::finish(state, journal, "rolled_back");
+    }
+    result
+}
+
+async fn write_and_register_transform(
+    state: &AppState,
+    source: &Dataset,
+    recipe: &DatasetRecipe,
+    steps: &[RecipeStep],
+    output_uuid: String,
+    dest: &Path,
+) -> Result<Dataset, String> {
+    let format = DatasetFormat::parse(&source.format).map_err(|e| e.to_string())?;
+
+    let outcome = {
+        let source_path = source.file_path.clone();
+        let dest = dest.to_path_buf();
+        let steps = steps.to_vec();
+        tauri::async_runtime::spawn_blocking(move || {
+            recipes::apply_file(Path::new(&source_path), format, &dest, &steps)
+        })
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("{:#}", e))?
+    };
+
+    let size_bytes = std::fs::metadata(dest).map(|m| m.len() as i64).unwrap_or(0);
+    let params = serde_json::json!({
+        "recipe_uuid": recipe.uuid,
+        "recipe_name": recipe.name,
+        "steps": steps,
+        "source_rows": outcome.source_rows,
+    });
+
+    let output = NewDataset {
+        uuid: output_uuid,
+        project_id: source.project_id,
+        name: format!("{} ({})", source.name, recipe.name),
+        file_path: dest.to_string_lossy().to_string(),
+        format: DatasetFormat::Parquet.as_str().to_string(),
+        row_count: Some(outcome.output_rows as i64),
+        size_bytes,
+        parent_uuid: Some(source.uuid.clone()),
+    };
+
+    let source_uuid = source.uuid.clone();
+    state
+        .with_db_async(move |db| {
+            let dataset = db.create_dataset(&output)?;
+            db.add_dataset_lineage(&dataset.uuid, &source_uuid, "recipe", &params.to_string())?;
+            Ok(dataset)
+        }).await
+        .map_err(|e| e.to_string())
+}
+
+/// Re-imports a dataset from `source` every `interval` ("30m", "6h", "1d").
+/// Refreshed data is stored as the dataset's managed Parquet file; the
+/// first refresh runs right away.
+#[tauri::command]
+pub async fn set_dataset_refresh(
+    state: State<'_, AppState>,
+    dataset_uuid: String,
+    source: RefreshSource,
+    interval: String,
+) -> Result<DatasetRefresh, String> {
+    let interval_secs = resources::parse_duration(&interval).map_err(|e| e.to_string())?;
+    if interval_secs < refresh::MIN_INTERVAL_SECS {
+        return Err(tr!("refresh-interval-invalid", min = refresh::MIN_INTERVAL_SECS));
+    }
+    source.validate().map_err(|e| e.to_string())?;
+    find_dataset(&state, &dataset_uuid).await?;
+
+    let source_json = serde_json::to_string(&source).map_err(|e| e.to_string())?;
+    let uuid = dataset_uuid.clone();
+    let schedule = state
+        .with_db_async(move |db| db.set_dataset_refresh(&uuid, &source_json, interval_secs)).await
+        .map_err(|e| e.to_string())?;
+
+    state
+        .log_activity(
+            NewActivity::new("schedule_refresh", "dataset", Some(&dataset_uuid))
+                .after(serde_json::json!({ "interval_secs": interval_secs })),
+        )
+        .await;
+    Ok(schedule)
+}
+
+/// Stops refreshing a dataset; its current data is kept.
+#[tauri::command]
+pub async fn remove_dataset_refresh(state: State<'_, AppState>, dataset_uuid: String) -> Result<bool, String> {
+    let uuid = dataset_uuid.clone();
+    let removed = state
+        .with_db_async(move |db| db.delete_dataset_refresh(&uuid)).await
+        .map_err(|e| e.to_string())?;
+
+    if removed {
+        state
+            .log_activity(NewActivity::new("unschedule_refresh", "dataset", Some(&dataset_uuid)))
+            .await;
+    }
+    Ok(removed)
+}
+
+/// Refreshes a scheduled dataset now instead of waiting for its next run.
+#[tauri::command]
+pub async fn refresh_dataset(app: AppHandle, dataset_uuid: String) -> Result<Dataset, String> {
+    refresh::run_refresh(&app, &dataset_uuid)
+        .await
+        .map_err(|e| format!("{:#}", e))
+}
+
+#[tauri::command]
+pub async fn get_dataset_freshness(state: State<'_, AppState>, dataset_uuid: String) -> Result<DatasetFreshness, String> {
+    let dataset = find_dataset(&state, &dataset_uuid).await?;
+    refresh::freshness(&state, &dataset).map_err(|e| e.to_string())
+}
+
+async fn find_dataset(state: &AppState, uuid: &str) -> Result<Dataset, String> {
+    let owned = uuid.to_string();
+    state
+        .with_db_async(move |db| db.get_dataset(&owned)).await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| tr!("dataset-not-found", uuid = uuid))
+}
+
+/// Loads the stored column sketches, profiling the file once if the dataset
+/// has never been profiled.
+async fn load_sketches(state: &AppState, dataset: &Dataset) -> Result<Vec<ColumnSketch>, String> {
+    let dataset_uuid = dataset.uuid.clone();
+    let records = state
+        .with_db_async(move |db| db.get_column_stats(&dataset_uuid)).await
+        .map_err(|e| e.to_string())?;
+
+    if !records.is_empty() {
+        return records
+            .iter()
+            .map(ColumnSketch::from_record)
+            .collect::<anyhow::Result<Vec<_>>>()
+            .map_err(|e| e.to_string());
+    }
+
+    println!("[NOVEM] Profiling dataset {}", dataset.uuid);
+
+    let format = DatasetFormat::parse(&dataset.format).map_err(|e| e.to_string())?;
+    let path = dataset.file_path.clone();
+    let (sketches, _) = tauri::async_runtime::spawn_blocking(move || {
+        let path = Path::new(&path);
+        let schema = datasets::read_schema(path, format)?;
+        stats::profile_file(path, format, &schema)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())?;
+
+    save_sketches(state, &dataset.uuid, &sketches).await?;
+    Ok(sketches)
+}
+
+async fn save_sketches(state: &AppState, dataset_uuid: &str, sketches: &[ColumnSketch]) -> Result<(), String> {
+    let records = sketches
+        .iter()
+        .enumerate()
+        .map(|(position, sketch)| sketch.to_record(position))
+        .collect::<anyhow::Result<Vec<_>>>()
+        .map_err(|e| e.to_string())?;
+    let columns: Vec<_> = sketches
+        .iter()
+        .enumerate()
+        .map(|(position, sketch)| sketch.to_column(position))
+        .collect();
+
+    state
+        .with_db_async({
+            let dataset_uuid = dataset_uuid.to_string();
+            move |db| {
+                db.atomically(|db| {
+                    db.save_column_stats(&dataset_uuid, &records)?;
+                    db.save_dataset_columns(&dataset_uuid, &columns)
+                })
+            }
+        }).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_column_stats(
+    state: State<'_, AppState>,
+    dataset_uuid: String,
+) -> Result<Vec<ColumnStats>, String> {
+    let dataset = find_dataset(&state, &dataset_uuid).await?;
+    let sketches = load_sketches(&state, &dataset).await?;
+
+    Ok(sketches.iter().map(ColumnSketch::stats).collect())
+}
+
+/// Column names, types and summary stats for column pickers. Served from
+/// the stored schema; a dataset never profiled is profiled once first.
+#[tauri::command]
+pub async fn get_dataset_schema(
+    state: State<'_, AppState>,
+    dataset_uuid: String,
+) -> Result<Vec<DatasetColumn>, String> {
+    let uuid = dataset_uuid.clone();
+    let columns = state
+        .with_db_async(move |db| db.get_dataset_columns(&uuid)).await
+        .map_err(|e| e.to_string())?;
+    if !columns.is_empty() {
+        return Ok(columns);
+    }
+
+    let dataset = find_dataset(&state, &dataset_uuid).await?;
+    let sketches = load_sketches(&state, &dataset).await?;
+
+    // Profiled before this table existed: derive the schema from the sketches
+    let columns: Vec<_> = sketches
+        .iter()
+        .enumerate()
+        .map(|(position, sketch)| sketch.to_column(position))
+        .collect();
+    let saved = columns.clone();
+    state
+        .with_db_async(move |db| db.save_dataset_columns(&dataset.uuid, &saved)).await
+        .map_err(|e| e.to_string())?;
+
+    Ok(columns)
+}
+
+/// Appends the rows of another CSV/Parquet file to a dataset, updating its
+/// column sketches from the new rows only.
+#[tauri::command]
+pub async fn append_to_dataset(
+    state: State<'_, AppState>,
+    dataset_uuid: String,
+    file_path: String,
+) -> Result<Dataset, String> {
+    let dataset = find_dataset(&state, &dataset_uuid).await?;
+    let format = DatasetFormat::parse(&dataset.format).map_err(|e| e.to_string())?;
+    let source_format = DatasetFormat::from_path(Path::new(&file_path)).map_err(|e| e.to_string())?;
+    let mut sketches = load_sketches(&state, &dataset).await?;
+
+    let operation = Operation::Append {
+        dataset_uuid: dataset.uuid.clone(),
+        file_path: dataset.file_path.clone(),
+        format: dataset.format.clone(),
+        original_len: std::fs::metadata(&dataset.file_path).map(|m| m.len()).unwrap_or(0),
+    };
+    let journal = recovery::begin(&state, &operation);
+
+    let target = dataset.file_path.clone();
+    let written = tauri::async_runtime::spawn_blocking(move || {
+        let target = Path::new(&target);
+        let schema = datasets::read_schema(target, format)?;
+
+        let batches = datasets::open_batches(Path::new(&file_path), source_format, None)?.map(|batch| {
+            let batch = datasets::conform_batch(&batch?, &schema)?;
+            for (sketch, column) in sketches.iter_mut().zip(batch.columns()) {
+                sketch.update(column.as_ref())?;
+            }
+            Ok(batch)
+        });
+
+        let appended = datasets::append_batches(target, format, schema.clone(), batches)?;
+        Ok::<_, anyhow::Error>((sketches, appended))
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|result| result.map_err(|e| e.to_string()));
+
+    let (sketches, appended) = match written {
+        Ok(written) => written,
+        Err(e) => {
+            // Don't leave a half-written row (CSV) or scratch file (Parquet)
+            if let Err(e) = state.with_db_async(move |db| recovery::roll_back(db, &operation)).await {
+                eprintln!("[ERROR] Failed to roll back append to {}: {}", dataset.uuid, e);
+            }
+            recovery::finish(&state, journal, "rolled_back");
+            return Err(e);
+        }
+    };
+    recovery::advance(&state, journal, "written", &operation);
+
+    let row_count = sketches
+        .first()
+        .map(|sketch| sketch.count + sketch.null_count)
+        .unwrap_or(0) as i64;
+    let size_bytes = std::fs::metadata(&dataset.file_path)
+        .map(|m| m.len() as i64)
+        .unwrap_or(dataset.size_bytes);
+
+    save_sketches(&state, &dataset.uuid, &sketches).await?;
+
+    println!("[NOVEM] Appended {} rows to dataset {}", appended, dataset.uuid);
+
+    let previous_rows = dataset.row_count;
+    let updated = state
+        .with_db_async(move |db| {
+            db.update_dataset_size(&dataset.uuid, row_count, size_bytes)?;
+            db.get_dataset(&dataset.uuid)?
+                .ok_or_else(|| anyhow::anyhow!("Dataset {} not found", dataset.uuid))
+        }).await
+        .map_err(|e| e.to_string())?;
+
+    recovery::finish(&state, journal, "completed");
+    state
+        .log_activity(
+            NewActivity::new("append", "dataset", Some(&updated.uuid))
+                .before(serde_json::json!({ "row_count": previous_rows }))
+                .after(serde_json::json!({ "row_count": updated.row_count })),
+        )
+        .await;
+    Ok(updated)
+}
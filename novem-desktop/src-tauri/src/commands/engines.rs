@@ -0,0 +1,942 @@
+use reqwest::Method;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::admission::{AdmissionConfig, QueueStatus, ADMISSION_SETTING};
+use crate::database::{EngineProfile, NewActivity, NewEngineProfile, ResultCacheStats};
+use crate::dependencies::{self, DependencyReport, DEPENDENCY_TOOL_SETTING};
+use crate::engine_info::{self, EngineInfo};
+use crate::engine_update::{self, BundleUpdate, BundleUpdateConfig, BUNDLE_UPDATE_SETTING};
+use crate::engine_manager::{EngineInstance, ACTIVE_PROFILE_SETTING, DEV_MODE_SETTING, LAZY_START_SETTING, LOG_LEVEL_SETTING, READINESS_SETTING, SHUTDOWN_SETTING};
+use crate::heartbeat::{HeartbeatConfig, HeartbeatState, HEARTBEAT_SETTING};
+use crate::i18n::tr;
+use crate::interpreters::{self, InterpreterReport};
+use crate::latency::LatencyStats;
+use crate::proxy::{self, ArrayPage, ProxyBody, ProxyLimits, PROXY_LIMITS_SETTING};
+use crate::engine::{EngineLogLevel, EngineStatus, ReadinessProbe, ShutdownPolicy};
+use crate::result_cache::{self, CacheOptions};
+use crate::sessions::{self, SessionLease, SessionPoolConfig, SessionPoolStats, SESSION_POOL_SETTING};
+use crate::tunnels::{TunnelConfig, TunnelInfo};
+use crate::AppState;
+
+/// Environment for the engine serving `project_id`: global variables,
+/// overlaid with the project's workspace variables.
+pub(crate) async fn engine_env_for(state: &AppState, project_id: Option<i64>) -> Result<BTreeMap<String, String>, String> {
+    state
+        .with_db_async(move |db| match project_id {
+            Some(project_id) => db.get_project_engine_env(project_id),
+            None => db.get_engine_env(None),
+        }).await
+        .map_err(|e| e.to_string())
+}
+
+/// Starts (or reuses) the isolated compute engine for a project and returns its port.
+#[tauri::command]
+pub async fn start_project_engine(app: AppHandle, state: State<'_, AppState>, project_id: i64) -> Result<u16, String> {
+    let env = engine_env_for(&state, Some(project_id)).await?;
+    // Waits for the engine to answer, which can take a while
+    tauri::async_runtime::spawn_blocking(move || {
+        app.state::<AppState>().engines.start_project(project_id, env)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn stop_project_engine(app: AppHandle, project_id: i64) -> Result<bool, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        app.state::<AppState>().engines.stop_project(project_id)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+/// Forwards a local port over SSH to a remote engine and routes requests for
+/// `project_id` (the shared engine when omitted) through it. Replaces any
+/// tunnel already open for that engine.
+#[tauri::command]
+pub async fn open_engine_tunnel(
+    app: AppHandle,
+    project_id: Option<i64>,
+    config: TunnelConfig,
+) -> Result<TunnelInfo, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        app.state::<AppState>().engines.tunnels().open(project_id, config)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+/// Closes the tunnel for `project_id`; requests go back to the local engine.
+#[tauri::command]
+pub async fn close_engine_tunnel(state: State<'_, AppState>, project_id: Option<i64>) -> Result<bool, String> {
+    Ok(state.engines.tunnels().close(project_id))
+}
+
+#[tauri::command]
+pub async fn list_engine_tunnels(state: State<'_, AppState>) -> Result<Vec<TunnelInfo>, String> {
+    Ok(state.engines.tunnels().list())
+}
+
+/// Variables defined for a workspace, or the global set when `workspace_id`
+/// is omitted.
+#[tauri::command]
+pub async fn get_engine_env(
+    state: State<'_, AppState>,
+    workspace_id: Option<i64>,
+) -> Result<BTreeMap<String, String>, String> {
+    state
+        .with_db_async(move |db| db.get_engine_env(workspace_id)).await
+        .map_err(|e| e.to_string())
+}
+
+/// Replaces the variables of a workspace (or the global set). Running
+/// engines pick them up on their next restart.
+#[tauri::command]
+pub async fn set_engine_env(
+    state: State<'_, AppState>,
+    workspace_id: Option<i64>,
+    vars: BTreeMap<String, String>,
+) -> Result<(), String> {
+    validate_env(&vars)?;
+
+    // Values are often secrets, so only names are logged
+    let names: Vec<String> = vars.keys().cloned().collect();
+    let previous = state
+        .with_db_async(move |db| {
+            let previous = db.get_engine_env(workspace_id)?;
+            db.set_engine_env(workspace_id, &vars)?;
+            Ok(previous)
+        }).await
+        .map_err(|e| e.to_string())?;
+
+    println!("[NOVEM] Saved {} engine environment variables", names.len());
+    state
+        .log_activity(
+            NewActivity::new("update", "engine_env", None)
+                .before(serde_json::json!({ "workspace_id": workspace_id, "names": previous.keys().collect::<Vec<_>>() }))
+                .after(serde_json::json!({ "workspace_id": workspace_id, "names": names })),
+        )
+        .await;
+    Ok(())
+}
+
+fn validate_env(vars: &BTreeMap<String, String>) -> Result<(), String> {
+    for (name, value) in vars {
+        if name.is_empty() || name.contains('=') || name.contains('\0') || value.contains('\0') {
+            return Err(tr!("engine-env-invalid", name = name.as_str()));
+        }
+    }
+    Ok(())
+}
+
+fn compute_engine_dir(state: &AppState) -> Result<PathBuf, String> {
+    state.engines.compute_engine_dir()
+        .map(|dir| dir.to_path_buf())
+        .ok_or_else(|| tr!("engine-dir-not-found"))
+}
+
+/// Diffs the engine environment's installed packages against requirements.txt.
+#[tauri::command]
+pub async fn verify_engine_dependencies(state: State<'_, AppState>) -> Result<DependencyReport, String> {
+    let dir = compute_engine_dir(&state)?;
+
+    let report = tauri::async_runtime::spawn_blocking(move || dependencies::verify(&dir))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+
+    println!(
+        "[NOVEM] Engine dependencies: {} missing, {} mismatched",
+        report.missing.len(),
+        report.mismatched.len()
+    );
+    Ok(report)
+}
+
+/// Every Python interpreter the engine could run on, with the reason each
+/// rejected one was passed over and the one that would be picked.
+#[tauri::command]
+pub async fn discover_python_interpreters(state: State<'_, AppState>) -> Result<InterpreterReport, String> {
+    // Without a compute_engine directory there is no venv to look in, but
+    // system interpreters are still worth reporting
+    let dir = state.engines.compute_engine_dir().map(|dir| dir.to_path_buf());
+
+    let report = tauri::async_runtime::spawn_blocking(move || interpreters::discover(dir.as_deref()))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    println!(
+        "[NOVEM] Python discovery: {} candidates, selected {:?}",
+        report.candidates.len(),
+        report.selected.as_ref().map(|candidate| &candidate.command)
+    );
+    Ok(report)
+}
+
+/// One-click fix: installs `packages` (typically a report's `install_specs`)
+/// or the whole environment when omitted, with uv or Poetry when the
+/// compute_engine project uses them.
+#[tauri::command]
+pub async fn install_engine_dependencies(
+    state: State<'_, AppState>,
+    packages: Option<Vec<String>>,
+) -> Result<String, String> {
+    let dir = compute_engine_dir(&state)?;
+    let packages = packages.unwrap_or_default();
+
+    let (tool, output) = tauri::async_runtime::spawn_blocking(move || dependencies::install(&dir, &packages))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+
+    let json = serde_json::to_string(&tool).map_err(|e| e.to_string())?;
+    state
+        .with_db_async(move |db| db.set_setting(DEPENDENCY_TOOL_SETTING, &json)).await
+        .map_err(|e| e.to_string())?;
+
+    // Package versions changed; collect them again on next request
+    if let Ok(mut cached) = state.engine_info.lock() {
+        *cached = None;
+    }
+
+    Ok(output)
+}
+
+/// Python, server and scientific package versions plus the compute_engine
+/// revision, for the about/diagnostics screen. Served from the copy
+/// collected at startup unless `refresh` is set.
+#[tauri::command]
+pub async fn get_engine_info(
+    state: State<'_, AppState>,
+    refresh: Option<bool>,
+) -> Result<EngineInfo, String> {
+    if !refresh.unwrap_or(false) {
+        let cached = state.engine_info.lock()
+            .map_err(|e| format!("Failed to lock engine info: {}", e))?
+            .clone();
+        if let Some(mut info) = cached {
+            // The engine may have restarted since the info was collected
+            info.warmup_ms = state.engines.warmup_ms(None);
+            return Ok(info);
+        }
+    }
+
+    let info = engine_info::collect(&state).await.map_err(|e| e.to_string())?;
+
+    *state.engine_info.lock()
+        .map_err(|e| format!("Failed to lock engine info: {}", e))? = Some(info.clone());
+
+    Ok(info)
+}
+
+#[tauri::command]
+pub async fn get_engine_dev_mode(state: State<'_, AppState>) -> Result<bool, String> {
+    Ok(state.engines.dev_mode())
+}
+
+/// Persists dev mode and restarts running engines so uvicorn picks up
+/// `--reload`; from then on Python changes apply without restarting the app.
+#[tauri::command]
+pub async fn set_engine_dev_mode(state: State<'_, AppState>, enabled: bool) -> Result<(), String> {
+    state
+        .with_db_async(move |db| db.set_setting(DEV_MODE_SETTING, if enabled { "true" } else { "false" })).await
+        .map_err(|e| e.to_string())?;
+
+    if state.engines.dev_mode() == enabled {
+        return Ok(());
+    }
+    state.engines.set_dev_mode(enabled);
+    println!("[NOVEM] Engine dev mode {}", if enabled { "enabled" } else { "disabled" });
+
+    restart_running_engines(&state).await
+}
+
+#[tauri::command]
+pub async fn get_engine_lazy_start(state: State<'_, AppState>) -> Result<bool, String> {
+    Ok(state.engines.lazy_start())
+}
+
+/// Persists whether the shared engine starts on the first compute request
+/// instead of during launch. Applies from the next launch.
+#[tauri::command]
+pub async fn set_engine_lazy_start(state: State<'_, AppState>, enabled: bool) -> Result<(), String> {
+    state
+        .with_db_async(move |db| db.set_setting(LAZY_START_SETTING, if enabled { "true" } else { "false" })).await
+        .map_err(|e| e.to_string())?;
+
+    state.engines.set_lazy_start(enabled);
+    Ok(())
+}
+
+fn running_engines(state: &AppState) -> Vec<Option<i64>> {
+    state
+        .engines
+        .list()
+        .into_iter()
+        .filter(|engine| !matches!(engine.status, EngineStatus::Stopped | EngineStatus::NotFound))
+        .map(|engine| engine.project_id)
+        .collect()
+}
+
+/// Restarts every engine that is up so it picks up new launch settings.
+async fn restart_running_engines(state: &AppState) -> Result<(), String> {
+    for project_id in running_engines(state) {
+        let env = engine_env_for(state, project_id).await?;
+        state.engines.restart(project_id, env).map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_engine_readiness_probe(state: State<'_, AppState>) -> Result<ReadinessProbe, String> {
+    Ok(state.engines.readiness_probe())
+}
+
+/// How long and how often startup polls a new engine before giving up.
+/// Persisted; applies the next time an engine starts.
+#[tauri::command]
+pub async fn set_engine_readiness_probe(
+    state: State<'_, AppState>,
+    probe: ReadinessProbe,
+) -> Result<(), String> {
+    if probe.timeout_secs == 0 || probe.poll_interval_ms == 0 || probe.warmup_timeout_secs == 0 {
+        return Err(tr!("engine-readiness-invalid"));
+    }
+
+    let json = serde_json::to_string(&probe).map_err(|e| e.to_string())?;
+    state
+        .with_db_async(move |db| db.set_setting(READINESS_SETTING, &json)).await
+        .map_err(|e| e.to_string())?;
+
+    println!("[NOVEM] Engine readiness probe: {:?}", probe);
+    state.engines.set_readiness_probe(probe);
+    Ok(())
+}
+
+/// Last heartbeat of an engine, for the status bar indicator; changes
+/// arrive as `engine-heartbeat` events.
+#[tauri::command]
+pub async fn get_engine_heartbeat(state: State<'_, AppState>, project_id: Option<i64>) -> Result<HeartbeatState, String> {
+    state.engines.heartbeat(project_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_engine_heartbeat_config(state: State<'_, AppState>) -> Result<HeartbeatConfig, String> {
+    Ok(state.engines.heartbeat_config())
+}
+
+/// Persisted, and applied to running engines from their next beat.
+#[tauri::command]
+pub async fn set_engine_heartbeat_config(state: State<'_, AppState>, config: HeartbeatConfig) -> Result<(), String> {
+    if !config.is_valid() {
+        return Err(tr!("engine-heartbeat-invalid"));
+    }
+
+    let json = serde_json::to_string(&config).map_err(|e| e.to_string())?;
+    state
+        .with_db_async(move |db| db.set_setting(HEARTBEAT_SETTING, &json)).await
+        .map_err(|e| e.to_string())?;
+
+    println!("[NOVEM] Engine heartbeat: {:?}", config);
+    state.engines.set_heartbeat_config(config);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_engine_log_level(state: State<'_, AppState>) -> Result<EngineLogLevel, String> {
+    Ok(state.engines.log_level())
+}
+
+async fn apply_log_level(state: &AppState, project_id: Option<i64>, level: EngineLogLevel) -> anyhow::Result<()> {
+    let base_url = state.engines.base_url(project_id)?;
+    state
+        .http
+        .engine()
+        .post(format!("{}/control/log-level", base_url))
+        .timeout(Duration::from_secs(5))
+        .json(&serde_json::json!({ "level": level }))
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+/// Persisted so support can have users turn on debug logging without
+/// editing files. Running engines switch immediately; one that can't (an
+/// older bundle without the control endpoint) is restarted at the new level.
+#[tauri::command]
+pub async fn set_engine_log_level(state: State<'_, AppState>, level: EngineLogLevel) -> Result<(), String> {
+    let json = serde_json::to_string(&level).map_err(|e| e.to_string())?;
+    state
+        .with_db_async(move |db| db.set_setting(LOG_LEVEL_SETTING, &json)).await
+        .map_err(|e| e.to_string())?;
+
+    println!("[NOVEM] Engine log level: {}", level.as_str());
+    state.engines.set_log_level(level);
+
+    for project_id in running_engines(&state) {
+        if let Err(e) = apply_log_level(&state, project_id, level).await {
+            eprintln!("[WARNING] Engine {:?} rejected the log level change, restarting it: {:#}", project_id, e);
+            let env = engine_env_for(&state, project_id).await?;
+            state.engines.restart(project_id, env).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+/// Upper bound on each shutdown wait, so closing the app can't hang on it.
+const MAX_SHUTDOWN_WAIT_SECS: u64 = 120;
+
+#[tauri::command]
+pub async fn get_engine_shutdown_policy(state: State<'_, AppState>) -> Result<ShutdownPolicy, String> {
+    Ok(state.engines.shutdown_policy())
+}
+
+/// How long stopping engines wait after `/shutdown` and after SIGTERM
+/// before escalating. Persisted; applies to the next stop.
+#[tauri::command]
+pub async fn set_engine_shutdown_policy(
+    state: State<'_, AppState>,
+    policy: ShutdownPolicy,
+) -> Result<(), String> {
+    if policy.shutdown_timeout_secs > MAX_SHUTDOWN_WAIT_SECS || policy.grace_period_secs > MAX_SHUTDOWN_WAIT_SECS {
+        return Err(tr!("engine-shutdown-invalid", max = MAX_SHUTDOWN_WAIT_SECS));
+    }
+
+    let json = serde_json::to_string(&policy).map_err(|e| e.to_string())?;
+    state
+        .with_db_async(move |db| db.set_setting(SHUTDOWN_SETTING, &json)).await
+        .map_err(|e| e.to_string())?;
+
+    println!("[NOVEM] Engine shutdown policy: {:?}", policy);
+    state.engines.set_shutdown_policy(policy);
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+pub struct EngineProfileList {
+    pub active_profile_id: Option<i64>,
+    pub profiles: Vec<EngineProfile>,
+}
+
+#[tauri::command]
+pub async fn list_engine_profiles(state: State<'_, AppState>) -> Result<EngineProfileList, String> {
+    let profiles = state
+        .with_db_async(move |db| db.list_engine_profiles()).await
+        .map_err(|e| e.to_string())?;
+
+    Ok(EngineProfileList {
+        active_profile_id: state.engines.active_profile().map(|profile| profile.id),
+        profiles,
+    })
+}
+
+/// Saves a named launch configuration ("GPU env", "minimal env", ...);
+/// it applies once activated.
+#[tauri::command]
+pub async fn create_engine_profile(
+    state: State<'_, AppState>,
+    mut profile: NewEngineProfile,
+) -> Result<EngineProfile, String> {
+    profile.name = profile.name.trim().to_string();
+    if profile.name.is_empty() {
+        return Err(tr!("engine-profile-name-empty"));
+    }
+    validate_env(&profile.env)?;
+    if let Some(dir) = &profile.working_dir {
+        if !Path::new(dir).is_dir() {
+            return Err(tr!("engine-profile-working-dir-invalid", path = dir.as_str()));
+        }
+    }
+
+    let profile = state
+        .with_db_async(move |db| db.create_engine_profile(&profile)).await
+        .map_err(|e| e.to_string())?;
+
+    println!("[NOVEM] Created engine profile '{}'", profile.name);
+    Ok(profile)
+}
+
+/// Makes engines launch with a profile (`None` restores the defaults) and
+/// restarts running engines with it. The choice persists across restarts.
+#[tauri::command]
+pub async fn activate_engine_profile(
+    state: State<'_, AppState>,
+    profile_id: Option<i64>,
+) -> Result<Option<EngineProfile>, String> {
+    let profile = match profile_id {
+        Some(id) => Some(
+            state
+                .with_db_async(move |db| db.get_engine_profile(id)).await
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| tr!("engine-profile-not-found", id = id))?,
+        ),
+        None => None,
+    };
+
+    state
+        .with_db_async(move |db| match profile_id {
+            Some(id) => db.set_setting(ACTIVE_PROFILE_SETTING, &id.to_string()),
+            None => db.delete_setting(ACTIVE_PROFILE_SETTING),
+        }).await
+        .map_err(|e| e.to_string())?;
+
+    match &profile {
+        Some(profile) => println!("[NOVEM] Activating engine profile '{}'", profile.name),
+        None => println!("[NOVEM] Engine profile cleared, using defaults"),
+    }
+    state.engines.set_active_profile(profile.clone());
+    restart_running_engines(&state).await?;
+
+    Ok(profile)
+}
+
+/// Last resort for an engine that won't start or stop: kills its process
+/// and every subprocess without waiting for a clean shutdown.
+#[tauri::command]
+pub async fn force_kill_engine(state: State<'_, AppState>, project_id: Option<i64>) -> Result<bool, String> {
+    state.engines.force_kill(project_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_engines(state: State<'_, AppState>) -> Result<Vec<EngineInstance>, String> {
+    Ok(state.engines.list())
+}
+
+/// Health-check latency percentiles for one engine, so a slowly degrading
+/// engine shows up before it fails outright.
+#[tauri::command]
+pub async fn get_engine_latency_stats(
+    state: State<'_, AppState>,
+    project_id: Option<i64>,
+) -> Result<LatencyStats, String> {
+    state.engines.latency_stats(project_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_engine_admission(state: State<'_, AppState>) -> Result<AdmissionConfig, String> {
+    Ok(state.engines.admission_config())
+}
+
+/// Changes how many requests each engine runs at once, how many may wait
+/// and how long each may take. Persisted.
+#[tauri::command]
+pub async fn set_engine_admission(
+    state: State<'_, AppState>,
+    config: AdmissionConfig,
+) -> Result<(), String> {
+    if config.max_concurrent == 0 {
+        return Err(tr!("engine-max-concurrent-invalid"));
+    }
+    if config.request_timeout_secs == 0 {
+        return Err(tr!("engine-request-timeout-invalid"));
+    }
+
+    let json = serde_json::to_string(&config).map_err(|e| e.to_string())?;
+    state
+        .with_db_async(move |db| db.set_setting(ADMISSION_SETTING, &json)).await
+        .map_err(|e| e.to_string())?;
+
+    state.engines.set_admission_config(config);
+    Ok(())
+}
+
+/// Requests running and waiting on the engine serving `project_id`.
+#[tauri::command]
+pub async fn get_engine_queue_status(state: State<'_, AppState>, project_id: Option<i64>) -> Result<QueueStatus, String> {
+    Ok(state.engines.queue_status(project_id))
+}
+
+/// Waits out a start underway and fails unless the engine came up.
+async fn wait_for_start(state: &AppState, project_id: Option<i64>) -> Result<(), String> {
+    let timeout = Duration::from_secs(state.engines.readiness_probe().timeout_secs);
+    let started = std::time::Instant::now();
+    while state.engines.status(project_id) == EngineStatus::Starting && started.elapsed() < timeout {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+
+    match state.engines.status(project_id) {
+        EngineStatus::Ready | EngineStatus::Degraded => Ok(()),
+        _ => Err(state
+            .engines
+            .startup_failure(project_id)
+            .map(|diagnosis| diagnosis.message)
+            .unwrap_or_else(|| tr!("engine-not-running"))),
+    }
+}
+
+/// Proxies a request to the compute engine serving `project_id`
+/// (the shared engine when omitted). Requests beyond the engine's
+/// concurrency wait in its queue; `request_id` tags the resulting
+/// `engine-queue-position` events. `timeout_secs` overrides the configured
+/// request timeout, which starts once the request is admitted. With `cache`
+/// set, a stored result of the same request is returned without reaching
+/// the engine, and JSON results are stored for next time.
+#[tauri::command]
+pub async fn call_compute_engine(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    endpoint: String,
+    method: String,
+    data: Option<Value>,
+    project_id: Option<i64>,
+    request_id: Option<String>,
+    timeout_secs: Option<u64>,
+    cache: Option<CacheOptions>,
+) -> Result<Value, String> {
+    let cache_key = match &cache {
+        Some(options) => {
+            let (method, endpoint, data, options) = (method.clone(), endpoint.clone(), data.clone(), options.clone());
+            let cached = state
+                .with_db_async(move |db| {
+                    let key = result_cache::key(db, &method, &endpoint, data.as_ref(), &options)?;
+                    Ok((result_cache::lookup(db, &key)?, key))
+                }).await
+                .map_err(|e| e.to_string())?;
+            match cached {
+                (Some(value), _) => return Ok(value),
+                (None, key) => Some(key),
+            }
+        }
+        None => None,
+    };
+
+    // Progress shows through the usual `engine-status-changed` and
+    // `engine-startup-progress` events. A start begun by a concurrent
+    // request is waited out too.
+    let starting = if project_id.is_none() && state.engines.is_deferred() {
+        let env = engine_env_for(&state, None).await.unwrap_or_else(|e| {
+            eprintln!("[ERROR] Failed to load engine environment: {}", e);
+            Default::default()
+        });
+        // Waits for the engine to answer, which can take a while
+        tauri::async_runtime::spawn_blocking(move || {
+            app.state::<AppState>().engines.start_deferred(|| env)
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+        true
+    } else {
+        state.engines.status(project_id) == EngineStatus::Starting
+    };
+    if starting {
+        wait_for_start(&state, project_id).await?;
+    }
+
+    let base_url = state.engines.base_url(project_id)
+        .map_err(|e| e.to_string())?;
+
+    let request_id = request_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let _permit = state.engines.admission(project_id)
+        .acquire(&request_id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let method = Method::from_bytes(method.to_uppercase().as_bytes())
+        .map_err(|_| tr!("engine-method-invalid", method = method.as_str()))?;
+
+    let timeout_secs = timeout_secs
+        .filter(|secs| *secs > 0)
+        .unwrap_or_else(|| state.engines.admission_config().request_timeout_secs);
+    let url = format!("{}/{}", base_url, endpoint.trim_start_matches('/'));
+    let mut request = state
+        .http
+        .engine()
+        .request(method, &url)
+        .timeout(Duration::from_secs(timeout_secs));
+    if let Some(data) = data {
+        request = request.json(&data);
+    }
+
+    let response = request.send().await.map_err(|e| {
+        if e.is_timeout() {
+            tr!("engine-request-timeout", seconds = timeout_secs)
+        } else {
+            tr!("engine-unreachable", error = e.to_string())
+        }
+    })?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = proxy::read_error_body(response).await;
+        return Err(tr!("engine-status-error", status = status.as_u16(), body = body));
+    }
+
+    let limits = state.proxy_limits.lock().unwrap().clone();
+    let body = proxy::read_body(response, &limits, &state.spill_dir())
+        .await
+        .map_err(|e| format!("Failed to read response: {}", e))?;
+
+    let body = match body {
+        ProxyBody::Inline(body) => body,
+        ProxyBody::Spilled(spilled) => return serde_json::to_value(spilled).map_err(|e| e.to_string()),
+    };
+    if body.is_empty() {
+        return Ok(Value::Null);
+    }
+
+    let value = match serde_json::from_slice::<Value>(&body) {
+        Ok(value) => value,
+        Err(_) => return Ok(Value::String(String::from_utf8_lossy(&body).into_owned())),
+    };
+
+    if let (Some(key), Some(options)) = (cache_key, cache) {
+        let cached = value.clone();
+        let result = state
+            .with_db_async(move |db| result_cache::store(db, &key, &endpoint, &cached, options.ttl_secs())).await;
+        if let Err(e) = result {
+            eprintln!("[WARNING] Failed to cache engine result: {}", e);
+        }
+    }
+    Ok(value)
+}
+
+/// Drops every cached engine result, e.g. when results look stale.
+#[tauri::command]
+pub async fn clear_result_cache(state: State<'_, AppState>) -> Result<usize, String> {
+    state
+        .with_db_async(|db| db.clear_result_cache()).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_result_cache_stats(state: State<'_, AppState>) -> Result<ResultCacheStats, String> {
+    state
+        .with_db_async(|db| db.result_cache_stats()).await
+        .map_err(|e| e.to_string())
+}
+
+/// One page of a spilled response's JSON array: the whole body, or the
+/// array under `field` (such as `rows`) of a top-level object.
+#[tauri::command]
+pub async fn read_spilled_response(
+    state: State<'_, AppState>,
+    handle: String,
+    field: Option<String>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<ArrayPage, String> {
+    let spill_dir = state.spill_dir();
+    tauri::async_runtime::spawn_blocking(move || {
+        proxy::read_page(
+            &spill_dir,
+            &handle,
+            field.as_deref(),
+            offset.unwrap_or(0),
+            limit.unwrap_or(proxy::DEFAULT_PAGE_SIZE),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+/// Deletes a spilled response once the frontend is done with it.
+#[tauri::command]
+pub async fn release_spilled_response(state: State<'_, AppState>, handle: String) -> Result<(), String> {
+    proxy::release(&state.spill_dir(), &handle).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_proxy_limits(state: State<'_, AppState>) -> Result<ProxyLimits, String> {
+    Ok(state.proxy_limits.lock().unwrap().clone())
+}
+
+/// Size limits for engine responses passing through `call_compute_engine`.
+/// Persisted; applies to the next request.
+#[tauri::command]
+pub async fn set_proxy_limits(state: State<'_, AppState>, limits: ProxyLimits) -> Result<(), String> {
+    if limits.spill_threshold_bytes == 0 || limits.max_body_bytes < limits.spill_threshold_bytes {
+        return Err(tr!("proxy-limits-invalid"));
+    }
+
+    let json = serde_json::to_string(&limits).map_err(|e| e.to_string())?;
+    state
+        .with_db_async(move |db| db.set_setting(PROXY_LIMITS_SETTING, &json)).await
+        .map_err(|e| e.to_string())?;
+
+    println!("[NOVEM] Engine response limits: {:?}", limits);
+    *state.proxy_limits.lock().unwrap() = limits;
+    Ok(())
+}
+
+/// Leases a warm session on the engine serving `project_id` to a notebook.
+/// A notebook asking again gets the session it already holds.
+#[tauri::command]
+pub async fn lease_engine_session(
+    app: AppHandle,
+    project_id: Option<i64>,
+    notebook_uuid: String,
+) -> Result<SessionLease, String> {
+    sessions::lease(&app, project_id, &notebook_uuid)
+        .await
+        .map_err(|e| format!("{:#}", e))
+}
+
+/// Returns a closed notebook's session to the pool, its state cleared.
+#[tauri::command]
+pub async fn release_engine_session(
+    app: AppHandle,
+    project_id: Option<i64>,
+    notebook_uuid: String,
+) -> Result<bool, String> {
+    sessions::release(&app, project_id, &notebook_uuid)
+        .await
+        .map_err(|e| format!("{:#}", e))
+}
+
+#[tauri::command]
+pub async fn get_session_pool_stats(state: State<'_, AppState>) -> Result<Vec<SessionPoolStats>, String> {
+    Ok(state.sessions.stats())
+}
+
+#[tauri::command]
+pub async fn get_session_pool_config(state: State<'_, AppState>) -> Result<SessionPoolConfig, String> {
+    Ok(state.sessions.config())
+}
+
+/// Persists the pool sizes and brings running engines' pools in line.
+#[tauri::command]
+pub async fn set_session_pool_config(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    config: SessionPoolConfig,
+) -> Result<(), String> {
+    if !config.is_valid() {
+        return Err(tr!("session-pool-config-invalid"));
+    }
+
+    let json = serde_json::to_string(&config).map_err(|e| e.to_string())?;
+    state
+        .with_db_async(move |db| db.set_setting(SESSION_POOL_SETTING, &json)).await
+        .map_err(|e| e.to_string())?;
+
+    println!("[NOVEM] Engine session pool: {:?}", config);
+    state.sessions.set_config(config);
+
+    for project_id in running_engines(&state) {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move { sessions::refill(&app, project_id).await });
+    }
+    Ok(())
+}
+
+async fn engine_update_config(state: &AppState) -> Result<BundleUpdateConfig, String> {
+    let json = state
+        .with_db_async(move |db| db.get_setting(BUNDLE_UPDATE_SETTING)).await
+        .map_err(|e| e.to_string())?;
+    match json {
+        Some(json) => serde_json::from_str(&json).map_err(|e| e.to_string()),
+        None => Ok(BundleUpdateConfig::default()),
+    }
+}
+
+#[tauri::command]
+pub async fn get_engine_update_config(state: State<'_, AppState>) -> Result<BundleUpdateConfig, String> {
+    engine_update_config(&state).await
+}
+
+/// Where `update_compute_engine` downloads bundles from, and the key they
+/// must be signed with. Persisted.
+#[tauri::command]
+pub async fn set_engine_update_config(state: State<'_, AppState>, config: BundleUpdateConfig) -> Result<(), String> {
+    config.validate().map_err(|e| e.to_string())?;
+
+    let json = serde_json::to_string(&config).map_err(|e| e.to_string())?;
+    state
+        .with_db_async(move |db| db.set_setting(BUNDLE_UPDATE_SETTING, &json)).await
+        .map_err(|e| e.to_string())?;
+
+    println!("[NOVEM] Engine bundle source: {:?}", config.url);
+    Ok(())
+}
+
+/// Downloads a compute_engine bundle (from `url`, or the configured one),
+/// verifies its checksum and signature, replaces the engine sources and
+/// restarts the engines that were running. The engine's virtualenv is kept.
+#[tauri::command]
+pub async fn update_compute_engine(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    url: Option<String>,
+) -> Result<BundleUpdate, String> {
+    let config = engine_update_config(&state).await?;
+    let url = url.or_else(|| config.url.clone()).ok_or_else(|| tr!("engine-update-url-missing"))?;
+    BundleUpdateConfig { url: Some(url.clone()), ..config.clone() }
+        .validate()
+        .map_err(|e| e.to_string())?;
+
+    let engine_dir = compute_engine_dir(&state)?;
+    engine_update::check_updatable(&engine_dir).map_err(|e| e.to_string())?;
+
+    println!("[NOVEM] Downloading compute engine bundle from {}", url);
+    let bundle = engine_update::download(state.http.backend(), &config, &url).await.map_err(|e| format!("{:#}", e))?;
+    let size_bytes = bundle.bytes.len() as u64;
+
+    let staging_dir = {
+        let engine_dir = engine_dir.clone();
+        tauri::async_runtime::spawn_blocking(move || engine_update::stage(&bundle.bytes, &engine_dir))
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| e.to_string())?
+    };
+
+    // Read up front: stopping, swapping and restarting all block
+    let mut running = Vec::new();
+    for project_id in running_engines(&state) {
+        running.push((project_id, engine_env_for(&state, project_id).await?));
+    }
+
+    let swap_dir = engine_dir.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<AppState>();
+        for (project_id, _) in &running {
+            let stopped = match project_id {
+                Some(project_id) => state.engines.stop_project(*project_id).map(|_| ()),
+                None => state.engines.stop_default(),
+            };
+            if let Err(e) = stopped {
+                eprintln!("[ERROR] Failed to stop engine before update: {}", e);
+            }
+        }
+
+        let swapped = engine_update::swap(&state, &swap_dir, &staging_dir).map_err(|e| format!("{:#}", e));
+
+        // Engines come back on whichever sources are in place now
+        for (project_id, env) in running {
+            match project_id {
+                Some(project_id) => {
+                    if let Err(e) = state.engines.start_project(project_id, env) {
+                        eprintln!("[ERROR] Failed to restart engine for project {}: {}", project_id, e);
+                    }
+                }
+                None => state.engines.start_default(env),
+            }
+        }
+        swapped
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    // The engine revision changed
+    if let Ok(mut cached) = state.engine_info.lock() {
+        *cached = None;
+    }
+
+    println!("[NOVEM] Compute engine updated ({} bytes, sha256 {})", size_bytes, bundle.sha256);
+    Ok(BundleUpdate {
+        url,
+        size_bytes,
+        sha256: bundle.sha256,
+        engine_dir: engine_dir.to_string_lossy().to_string(),
+    })
+}
@@ -0,0 +1,23 @@
+use tauri::State;
+
+use crate::database::{FavoriteEntity, NewActivity};
+use crate::AppState;
+
+/// Pins a workspace or project to the top of its list, or unpins it.
+/// Returns whether it is pinned now.
+#[tauri::command]
+pub async fn toggle_favorite(state: State<'_, AppState>, entity: FavoriteEntity) -> Result<bool, String> {
+    let pinned = state
+        .with_db_async({
+            let entity = entity.clone();
+            move |db| db.toggle_favorite(&entity)
+        }).await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("{} {} not found", entity.entity_type(), entity.uuid()))?;
+
+    let action = if pinned { "pin" } else { "unpin" };
+    state
+        .log_activity(NewActivity::new(action, entity.entity_type(), Some(entity.uuid())))
+        .await;
+    Ok(pinned)
+}
@@ -0,0 +1,111 @@
+use tauri::{AppHandle, State};
+
+use crate::database::{Job, NewNotification, NotificationPriority};
+use crate::i18n::tr;
+use crate::notifications;
+use crate::AppState;
+
+/// Records a computation about to be submitted to an engine, so it shows
+/// up in the jobs panel and survives a restart.
+#[tauri::command]
+pub async fn create_job(
+    state: State<'_, AppState>,
+    project_id: Option<i64>,
+    kind: String,
+    params: Option<serde_json::Value>,
+) -> Result<Job, String> {
+    let uuid = uuid::Uuid::new_v4().to_string();
+    let params = params.unwrap_or_else(|| serde_json::json!({})).to_string();
+
+    let job = state
+        .with_db_async(move |db| db.create_job(&uuid, project_id, &kind, &params)).await
+        .map_err(|e| e.to_string())?;
+
+    println!("[NOVEM] Queued {} job {}", job.kind, job.uuid);
+    Ok(job)
+}
+
+#[tauri::command]
+pub async fn list_jobs(
+    state: State<'_, AppState>,
+    project_id: Option<i64>,
+    status: Option<String>,
+) -> Result<Vec<Job>, String> {
+    state
+        .with_db_async(move |db| db.list_jobs(project_id, status.as_deref())).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_job(state: State<'_, AppState>, uuid: String) -> Result<Option<Job>, String> {
+    state
+        .with_db_async(move |db| db.get_job(&uuid)).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn start_job(state: State<'_, AppState>, uuid: String) -> Result<bool, String> {
+    state
+        .with_db_async(move |db| db.start_job(&uuid)).await
+        .map_err(|e| e.to_string())
+}
+
+/// `progress` runs from 0.0 to 1.0.
+#[tauri::command]
+pub async fn update_job_progress(state: State<'_, AppState>, uuid: String, progress: f64) -> Result<bool, String> {
+    state
+        .with_db_async(move |db| db.update_job_progress(&uuid, progress)).await
+        .map_err(|e| e.to_string())
+}
+
+/// Completes a job, or fails it when `error` is given.
+#[tauri::command]
+pub async fn finish_job(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    uuid: String,
+    result_ref: Option<String>,
+    error: Option<String>,
+) -> Result<bool, String> {
+    let job = state
+        .with_db_async({
+            let uuid = uuid.clone();
+            let error = error.clone();
+            move |db| {
+                if !db.finish_job(&uuid, result_ref.as_deref(), error.as_deref())? {
+                    return Ok(None);
+                }
+                db.get_job(&uuid)
+            }
+        }).await
+        .map_err(|e| e.to_string())?;
+
+    let Some(job) = job else {
+        return Ok(false);
+    };
+
+    let kind = job.kind.as_str();
+    let notification = match error {
+        Some(error) => {
+            eprintln!("[WARNING] Job {} failed: {}", uuid, error);
+            NewNotification::new("job", NotificationPriority::High, tr!("notify-job-failed", kind = kind)).body(error)
+        }
+        None => NewNotification::new("job", NotificationPriority::Normal, tr!("notify-job-finished", kind = kind)),
+    };
+    notifications::notify(&app, notification.about("job", &uuid)).await;
+    Ok(true)
+}
+
+#[tauri::command]
+pub async fn cancel_job(state: State<'_, AppState>, uuid: String) -> Result<bool, String> {
+    state
+        .with_db_async(move |db| db.cancel_job(&uuid)).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_job(state: State<'_, AppState>, uuid: String) -> Result<bool, String> {
+    state
+        .with_db_async(move |db| db.delete_job(&uuid)).await
+        .map_err(|e| e.to_string())
+}
@@ -1,6 +1,43 @@
-use tauri::State;
-use crate::{AppState, database::{Workspace, Project}};
+use tauri::{AppHandle, Manager, State};
+use crate::{AppState, database::{DbMaintenanceReport, DbPragmaInfo, EngineMetricPoint, NewActivity, NewWorkspaceMember, Page, PageRequest, ProjectQuery, RecentEntity, Workspace, WorkspaceMember, WorkspaceRole, Project, ResourcePoint, SearchEntityType, SearchFilter, SearchResult, SyncQueue}, gpu::{self, EngineGpuStatus, GpuInfo}, engine::EngineStatus, recovery::RecoveryReport, resources, watchdog::WatchdogConfig};
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use crate::accounts::{self, LocalAccount};
+use crate::connectivity::ConnectivityStatus;
+use crate::deprovision::{self, DeprovisionReport, DeprovisionScope};
+use crate::events::{self, AppEvent, EventBus, EventRecord};
+use crate::guard::{self, AppModeInfo};
+use crate::i18n::{self, tr, LocaleInfo};
+use crate::ports::{self, PortDiagnosis};
+use crate::sessions::{self, SessionRestore};
+use crate::startup_diagnosis::StartupDiagnosis;
+use crate::storage::{self, StorageReport};
+use crate::workspaces::{self, WorkspaceSnapshot};
+
+pub mod activity;
+pub mod attachments;
+pub mod comments;
+pub mod connections;
+pub mod dashboards;
+pub mod datasets;
+pub mod engines;
+pub mod favorites;
+pub mod jobs;
+pub mod notebooks;
+pub mod notifications;
+pub mod portable;
+pub mod projects;
+pub mod queries;
+pub mod recent;
+pub mod schedules;
+pub mod secrets;
+pub mod settings;
+pub mod share_links;
+pub mod sync;
+pub mod templates;
+pub mod transfers;
+pub mod trash;
+pub mod versions;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct HealthResponse {
@@ -19,6 +56,13 @@ pub struct SystemResources {
     pub memory_total_gb: f64,
     pub disk_available_gb: f64,
     pub disk_total_gb: f64,
+    pub engine_cpu_percent: Option<f64>,
+    pub engine_memory_mb: Option<f64>,
+    /// Cumulative bytes written by the engine process
+    pub engine_disk_write_bytes: Option<u64>,
+    /// Desktop app and every process it spawned, engines included
+    pub app_cpu_percent: Option<f64>,
+    pub app_memory_mb: Option<f64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -30,49 +74,85 @@ pub struct DetailedStatus {
     pub resources: Option<SystemResources>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct EngineRestart {
+    pub restarted: bool,
+    /// Why the engine didn't come back up, when `restarted` is false
+    pub diagnosis: Option<StartupDiagnosis>,
+    /// Notebook sessions carried over from before the restart
+    pub restored: Option<SessionRestore>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreatedWorkspace {
+    pub workspace: Workspace,
+    pub first_project: Option<Project>,
+}
+
 // ==================== ENGINE STATUS ====================
 
 #[tauri::command]
-pub async fn get_engine_status(state: State<'_, AppState>) -> Result<bool, String> {
-    let engine = state.python_engine.lock()
-        .map_err(|e| format!("Failed to lock engine: {}", e))?;
-    
-    engine.check_health()
-        .map_err(|e| e.to_string())
+pub async fn get_engine_status(
+    state: State<'_, AppState>,
+    project_id: Option<i64>,
+) -> Result<EngineStatus, String> {
+    Ok(state.engines.status(project_id))
 }
 
 #[tauri::command]
-pub async fn get_engine_port(state: State<'_, AppState>) -> Result<u16, String> {
-    let engine = state.python_engine.lock()
-        .map_err(|e| format!("Failed to lock engine: {}", e))?;
-    
-    Ok(engine.get_port())
+pub async fn get_engine_port(
+    state: State<'_, AppState>,
+    project_id: Option<i64>,
+) -> Result<u16, String> {
+    state.engines.port(project_id)
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn restart_engine(state: State<'_, AppState>) -> Result<bool, String> {
-    let mut engine = state.python_engine.lock()
-        .map_err(|e| format!("Failed to lock engine: {}", e))?;
-    
-    engine.restart()
-        .map_err(|e| e.to_string())?;
-    
-    Ok(true)
+pub async fn restart_engine(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    project_id: Option<i64>,
+) -> Result<EngineRestart, String> {
+    let env = engines::engine_env_for(&state, project_id).await?;
+
+    // A crashed engine can't save; its last checkpoint is restored instead
+    if matches!(state.engines.status(project_id), EngineStatus::Ready | EngineStatus::Degraded) {
+        match sessions::save(&state, project_id).await {
+            Ok(saved) => println!("[NOVEM] Saved {} engine sessions before restart", saved),
+            Err(e) => eprintln!("[WARNING] Failed to save engine sessions before restart: {:#}", e),
+        }
+    }
+
+    let restart_app = app.clone();
+    let restarted = tauri::async_runtime::spawn_blocking(move || {
+        restart_app.state::<AppState>().engines.restart(project_id, env)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    if let Err(e) = restarted {
+        // A failed start explains itself; anything else is a plain error
+        return match state.engines.startup_failure(project_id) {
+            Some(diagnosis) => Ok(EngineRestart { restarted: false, diagnosis: Some(diagnosis), restored: None }),
+            None => Err(e.to_string()),
+        };
+    }
+
+    let restored = sessions::restore(&app, project_id)
+        .await
+        .inspect_err(|e| eprintln!("[WARNING] Failed to restore engine sessions: {:#}", e))
+        .ok();
+
+    Ok(EngineRestart { restarted: true, diagnosis: None, restored })
 }
 
 // ==================== HEALTH CHECKS ====================
 
 #[tauri::command]
-pub async fn check_backend_health() -> Result<HealthResponse, String> {
-    use reqwest::Client;
-    use std::time::Duration;
-    
-    let client = Client::builder()
+pub async fn check_backend_health(state: State<'_, AppState>) -> Result<HealthResponse, String> {
+    match state.http.backend()
+        .get("http://localhost:8000/api/health/")
         .timeout(Duration::from_secs(5))
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-    
-    match client.get("http://localhost:8000/api/health/")
         .send()
         .await
     {
@@ -83,37 +163,37 @@ pub async fn check_backend_health() -> Result<HealthResponse, String> {
                     Err(_) => Ok(HealthResponse {
                         status: "healthy".to_string(),
                         service: Some("novem-backend".to_string()),
-                        timestamp: Some(chrono::Utc::now().to_rfc3339()),
+                        timestamp: Some(crate::timestamps::now()),
                         database: Some("connected".to_string()),
                         mode: None,
                     }),
                 }
             } else {
-                Err(format!("Backend returned status: {}", response.status()))
+                Err(tr!("backend-status-error", status = response.status().as_u16()))
             }
         }
-        Err(e) => Err(format!("Backend unreachable: {}", e)),
+        Err(e) => Err(tr!("backend-unreachable", error = e.to_string())),
     }
 }
 
+/// Whether the backend is reachable, as last probed. Changes arrive as
+/// `connectivity-changed` events.
 #[tauri::command]
-pub async fn check_compute_engine_health(state: State<'_, AppState>) -> Result<HealthResponse, String> {
-    use reqwest::Client;
-    use std::time::Duration;
-    
-    // Get port and drop the lock immediately
-    let port = {
-        let engine = state.python_engine.lock()
-            .map_err(|e| format!("Failed to lock engine: {}", e))?;
-        engine.get_port()
-    }; // Lock is dropped here
+pub async fn get_connectivity_status(state: State<'_, AppState>) -> Result<ConnectivityStatus, String> {
+    Ok(state.connectivity.status())
+}
+
+#[tauri::command]
+pub async fn check_compute_engine_health(
+    state: State<'_, AppState>,
+    project_id: Option<i64>,
+) -> Result<HealthResponse, String> {
+    let port = state.engines.port(project_id)
+        .map_err(|e| e.to_string())?;
     
-    let client = Client::builder()
+    match state.http.engine()
+        .get(format!("http://127.0.0.1:{}/health", port))
         .timeout(Duration::from_secs(5))
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-    
-    match client.get(format!("http://127.0.0.1:{}/health", port))
         .send()
         .await
     {
@@ -124,37 +204,57 @@ pub async fn check_compute_engine_health(state: State<'_, AppState>) -> Result<H
                     Err(_) => Ok(HealthResponse {
                         status: "healthy".to_string(),
                         service: Some("novem-compute-engine".to_string()),
-                        timestamp: Some(chrono::Utc::now().to_rfc3339()),
+                        timestamp: Some(crate::timestamps::now()),
                         database: Some("duckdb".to_string()),
                         mode: Some("embedded".to_string()),
                     }),
                 }
             } else {
-                Err(format!("Compute engine returned status: {}", response.status()))
+                Err(tr!(
+                    "engine-status-error",
+                    status = response.status().as_u16(),
+                    body = response.status().canonical_reason().unwrap_or_default()
+                ))
             }
         }
-        Err(e) => Err(format!("Compute engine unreachable: {}", e)),
+        Err(e) => Err(tr!("engine-unreachable", error = e.to_string())),
     }
 }
 
+/// Who holds each service's port, whether that's a conflict, and free
+/// ports to move to. Engines already move on their own when they start.
 #[tauri::command]
-pub async fn get_system_resources(state: State<'_, AppState>) -> Result<SystemResources, String> {
-    use reqwest::Client;
-    use std::time::Duration;
-    
-    // Get port and drop the lock immediately
-    let port = {
-        let engine = state.python_engine.lock()
-            .map_err(|e| format!("Failed to lock engine: {}", e))?;
-        engine.get_port()
-    }; // Lock is dropped here
+pub async fn diagnose_ports(state: State<'_, AppState>) -> Result<Vec<PortDiagnosis>, String> {
+    let session = state.backend.lock()
+        .map_err(|e| format!("Failed to lock backend session: {}", e))?
+        .clone();
+
+    let backend_responding = matches!(
+        state.http.backend()
+            .get(session.url("/api/health/"))
+            .timeout(Duration::from_secs(2))
+            .send()
+            .await,
+        Ok(response) if response.status().is_success()
+    );
+
+    let engines = state.engines.list();
+    tauri::async_runtime::spawn_blocking(move || ports::diagnose(&session.base_url, backend_responding, &engines))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_system_resources(
+    state: State<'_, AppState>,
+    project_id: Option<i64>,
+) -> Result<SystemResources, String> {
+    let port = state.engines.port(project_id)
+        .map_err(|e| e.to_string())?;
     
-    let client = Client::builder()
+    match state.http.engine()
+        .get(format!("http://127.0.0.1:{}/health/status", port))
         .timeout(Duration::from_secs(5))
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-    
-    match client.get(format!("http://127.0.0.1:{}/health/status", port))
         .send()
         .await
     {
@@ -165,27 +265,145 @@ pub async fn get_system_resources(state: State<'_, AppState>) -> Result<SystemRe
                 
                 detailed.resources.ok_or_else(|| "No resources in response".to_string())
             } else {
-                Err(format!("Failed to get resources: {}", response.status()))
+                Err(tr!("resources-unavailable", error = response.status().to_string()))
             }
         }
-        Err(e) => Err(format!("Request failed: {}", e)),
+        Err(e) => Err(tr!("resources-unavailable", error = e.to_string())),
     }
 }
 
+/// Charting data for the monitoring panel. `range` and `resolution` are
+/// durations like "15m", "1h", "7d"; resolution defaults to ~120 points.
+#[tauri::command]
+pub async fn get_resource_history(
+    state: State<'_, AppState>,
+    range: String,
+    resolution: Option<String>,
+) -> Result<Vec<ResourcePoint>, String> {
+    let range = resources::parse_duration(&range).map_err(|e| e.to_string())?;
+    let resolution = match resolution {
+        Some(resolution) => resources::parse_duration(&resolution).map_err(|e| e.to_string())?,
+        None => (range / 120).max(10),
+    };
+
+    let since = chrono::Utc::now().timestamp() - range;
+    state
+        .with_db_async(move |db| db.get_resource_history(since, resolution)).await
+        .map_err(|e| e.to_string())
+}
+
+/// CPU and memory history of one engine's process tree (the shared engine
+/// when `project_id` is omitted), with the same `range`/`resolution` rules
+/// as `get_resource_history`.
+#[tauri::command]
+pub async fn get_engine_metrics(
+    state: State<'_, AppState>,
+    range: String,
+    project_id: Option<i64>,
+    resolution: Option<String>,
+) -> Result<Vec<EngineMetricPoint>, String> {
+    let range = resources::parse_duration(&range).map_err(|e| e.to_string())?;
+    let resolution = match resolution {
+        Some(resolution) => resources::parse_duration(&resolution).map_err(|e| e.to_string())?,
+        None => (range / 120).max(10),
+    };
+
+    let since = chrono::Utc::now().timestamp() - range;
+    state
+        .with_db_async(move |db| db.get_engine_metrics(project_id, since, resolution)).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_watchdog_config(state: State<'_, AppState>) -> Result<WatchdogConfig, String> {
+    state.watchdog.lock()
+        .map(|config| *config)
+        .map_err(|e| format!("Failed to lock watchdog config: {}", e))
+}
+
+/// Thresholds for `resource-alert` events; takes effect on the next sample.
+#[tauri::command]
+pub async fn set_watchdog_config(
+    state: State<'_, AppState>,
+    config: WatchdogConfig,
+) -> Result<(), String> {
+    if config.sustained_secs < 0 {
+        return Err(tr!("watchdog-sustained-negative"));
+    }
+
+    *state.watchdog.lock()
+        .map_err(|e| format!("Failed to lock watchdog config: {}", e))? = config;
+
+    println!("[NOVEM] Watchdog config updated: {:?}", config);
+    Ok(())
+}
+
+// ==================== GPU ====================
+
+/// GPU capabilities, detected once and cached in `AppState`. Pass
+/// `refresh` to re-detect (e.g. after installing drivers).
+#[tauri::command]
+pub async fn get_gpu_info(
+    state: State<'_, AppState>,
+    refresh: Option<bool>,
+) -> Result<GpuInfo, String> {
+    if !refresh.unwrap_or(false) {
+        let cached = state.gpu_info.lock()
+            .map_err(|e| format!("Failed to lock GPU info: {}", e))?
+            .clone();
+        if let Some(info) = cached {
+            return Ok(info);
+        }
+    }
+
+    let mut info = tauri::async_runtime::spawn_blocking(gpu::detect)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // The engine reports what its Python libraries can use; it's optional
+    if let Ok(port) = state.engines.port(None) {
+        let request = state.http.engine()
+            .get(format!("http://127.0.0.1:{}/health/gpu", port))
+            .timeout(Duration::from_secs(10));
+
+        match request.send().await {
+            Ok(response) if response.status().is_success() => {
+                match response.json::<EngineGpuStatus>().await {
+                    Ok(engine) => info.merge_engine(engine),
+                    Err(e) => eprintln!("[ERROR] Invalid GPU status from compute engine: {}", e),
+                }
+            }
+            Ok(response) => eprintln!("[WARNING] Compute engine GPU check returned {}", response.status()),
+            Err(e) => println!("[NOVEM] GPU check without compute engine: {}", e),
+        }
+    }
+
+    println!(
+        "[NOVEM] GPU: cuda={}, metal={}, devices={}",
+        info.cuda_available,
+        info.metal_available,
+        info.devices.len()
+    );
+
+    *state.gpu_info.lock()
+        .map_err(|e| format!("Failed to lock GPU info: {}", e))? = Some(info.clone());
+
+    Ok(info)
+}
+
 // ==================== DATABASE ====================
 
 #[tauri::command]
 pub async fn get_workspaces(
     state: State<'_, AppState>,
     user_id: i64,
-) -> Result<Vec<Workspace>, String> {
-    let db_guard = state.db.lock()
-        .map_err(|e| format!("Failed to lock database: {}", e))?;
-    
-    let db = db_guard.as_ref()
-        .ok_or("Database not initialized")?;
-    
-    db.get_workspaces(user_id)
+    page: Option<PageRequest>,
+    archived: Option<bool>,
+) -> Result<Page<Workspace>, String> {
+    let page = page.unwrap_or_default();
+    let archived = archived.unwrap_or(false);
+    state
+        .with_db_async(move |db| db.get_workspaces(user_id, archived, &page)).await
         .map_err(|e| e.to_string())
 }
 
@@ -194,18 +412,470 @@ pub async fn get_projects(
     state: State<'_, AppState>,
     workspace_id: i64,
     user_id: i64,
-) -> Result<Vec<Project>, String> {
-    let db_guard = state.db.lock()
-        .map_err(|e| format!("Failed to lock database: {}", e))?;
-    
-    let db = db_guard.as_ref()
-        .ok_or("Database not initialized")?;
-    
-    db.get_projects(workspace_id, user_id)
+    query: Option<ProjectQuery>,
+    page: Option<PageRequest>,
+) -> Result<Page<Project>, String> {
+    let query = query.unwrap_or_default();
+    let page = page.unwrap_or_default();
+    state
+        .with_db_async(move |db| db.get_projects(workspace_id, user_id, &query, &page)).await
+        .map_err(|e| e.to_string())
+}
+
+/// SQLite settings of the local database (journal mode, busy timeout,
+/// size), for the diagnostics panel.
+#[tauri::command]
+pub async fn get_db_pragma_info(state: State<'_, AppState>) -> Result<DbPragmaInfo, String> {
+    state
+        .with_db_async(move |db| db.pragma_info()).await
+        .map_err(|e| e.to_string())
+}
+
+/// Where NOVEM's disk space goes: the database table by table, the data
+/// directories and each project engine's scratch space.
+#[tauri::command]
+pub async fn get_storage_report(state: State<'_, AppState>) -> Result<StorageReport, String> {
+    let (tables, free_bytes) = state
+        .with_db_async(|db| Ok((db.table_usage()?, db.free_bytes()?))).await
+        .map_err(|e| e.to_string())?;
+
+    let data_dir = state.data_dir();
+    tauri::async_runtime::spawn_blocking(move || {
+        storage::report(&data_dir, deprovision::DATABASE_FILE, tables, free_bytes)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+/// Checks the local database's integrity, refreshes query planner
+/// statistics, clears old completed sync entries and releases free space.
+/// May take a while on a large database.
+#[tauri::command]
+pub async fn run_db_maintenance(state: State<'_, AppState>) -> Result<DbMaintenanceReport, String> {
+    let report = state
+        .with_db_async(|db| db.run_maintenance()).await
+        .map_err(|e| e.to_string())?;
+
+    println!(
+        "[NOVEM] Database maintenance: integrity {}, {:?} vacuum, {} pages freed, {} sync entries cleared",
+        if report.integrity_ok { "ok" } else { "FAILED" },
+        report.vacuum,
+        report.pages_freed,
+        report.sync_items_cleared
+    );
+    if !report.integrity_ok {
+        eprintln!("[ERROR] Database integrity check failed: {}", report.integrity_problems.join("; "));
+    }
+    state
+        .log_activity(NewActivity::new("maintenance", "database", None).after(&report))
+        .await;
+    Ok(report)
+}
+
+/// Sync items that ran out of retries, for the sync status panel.
+#[tauri::command]
+pub async fn get_failed_sync_items(state: State<'_, AppState>) -> Result<Vec<SyncQueue>, String> {
+    state
+        .with_db_async(|db| db.get_failed_sync_items()).await
+        .map_err(|e| e.to_string())
+}
+
+/// Gives a permanently failed sync item a fresh set of retries.
+#[tauri::command]
+pub async fn requeue_sync_item(state: State<'_, AppState>, id: i64) -> Result<(), String> {
+    let requeued = state
+        .with_db_async(move |db| db.requeue_sync_item(id)).await
+        .map_err(|e| e.to_string())?;
+    if !requeued {
+        return Err(format!("Sync item {} is not a failed item", id));
+    }
+
+    println!("[NOVEM] Requeued sync item {}", id);
+    Ok(())
+}
+
+// ==================== WORKSPACES ====================
+
+/// Creates a workspace, optionally with a first project, offline. Both rows
+/// and their sync entries are written in one transaction, so a crash never
+/// leaves a workspace queued without the project that was asked for.
+#[tauri::command]
+pub async fn create_workspace_local(
+    state: State<'_, AppState>,
+    name: String,
+    description: Option<String>,
+    first_project: Option<String>,
+) -> Result<CreatedWorkspace, String> {
+    let owner_id = state.backend.lock()
+        .map_err(|e| format!("Failed to lock backend session: {}", e))?
+        .user_id
+        .ok_or_else(|| tr!("workspace-not-signed-in"))?;
+
+    let created = state
+        .with_db_async(move |db| {
+            db.atomically(|db| {
+                let workspace = db.create_workspace(&name, description.as_deref(), owner_id)?;
+                let first_project = first_project
+                    .map(|project_name| db.create_project(workspace.id, &project_name, None, Some(owner_id)))
+                    .transpose()?;
+                Ok(CreatedWorkspace { workspace, first_project })
+            })
+        }).await
+        .map_err(|e| e.to_string())?;
+
+    println!("[NOVEM] Created workspace '{}'", created.workspace.name);
+    state
+        .log_activity(
+            NewActivity::new("create", "workspace", Some(&created.workspace.uuid)).after(&created.workspace.name),
+        )
+        .await;
+    if let Some(project) = &created.first_project {
+        state
+            .log_activity(NewActivity::new("create", "project", Some(&project.uuid)).after(&project.name))
+            .await;
+    }
+    Ok(created)
+}
+
+/// Creates an empty project offline; it is queued for sync and pushed
+/// once the backend is reachable.
+#[tauri::command]
+pub async fn create_project_local(
+    state: State<'_, AppState>,
+    workspace_id: i64,
+    name: String,
+    description: Option<String>,
+) -> Result<Project, String> {
+    let owner_id = state.backend.lock()
+        .map_err(|e| format!("Failed to lock backend session: {}", e))?
+        .user_id;
+
+    let project = state
+        .with_db_async(move |db| db.create_project(workspace_id, &name, description.as_deref(), owner_id)).await
+        .map_err(|e| e.to_string())?;
+
+    println!("[NOVEM] Created project '{}' in workspace {}", project.name, workspace_id);
+    state
+        .log_activity(NewActivity::new("create", "project", Some(&project.uuid)).after(&project.name))
+        .await;
+    Ok(project)
+}
+
+/// Switches the active workspace. Returns immediately; `workspace-activated`
+/// follows once its projects and recent activity are loaded.
+#[tauri::command]
+pub async fn set_active_workspace(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    uuid: String,
+) -> Result<Workspace, String> {
+    let workspace = workspaces::activate(&app, &uuid).map_err(|e| e.to_string())?;
+
+    recent::remember_opened(&state, move |db| db.record_opened(RecentEntity::Workspace, &uuid)).await;
+    Ok(workspace)
+}
+
+/// Moves a finished workspace out of the default listing. Nothing is
+/// deleted; `unarchive_workspace` brings it back.
+#[tauri::command]
+pub async fn archive_workspace(state: State<'_, AppState>, uuid: String) -> Result<(), String> {
+    set_workspace_archived(&state, uuid, true).await
+}
+
+#[tauri::command]
+pub async fn unarchive_workspace(state: State<'_, AppState>, uuid: String) -> Result<(), String> {
+    set_workspace_archived(&state, uuid, false).await
+}
+
+async fn set_workspace_archived(state: &AppState, uuid: String, archived: bool) -> Result<(), String> {
+    state
+        .with_db_async({
+            let uuid = uuid.clone();
+            move |db| db.set_workspace_archived(&uuid, archived)
+        }).await
+        .map_err(|e| e.to_string())?;
+
+    let action = if archived { "archive" } else { "unarchive" };
+    state.log_activity(NewActivity::new(action, "workspace", Some(&uuid))).await;
+    Ok(())
+}
+
+/// The active workspace with its preloaded data, or `None` while it is
+/// still loading (or none was ever chosen).
+#[tauri::command]
+pub async fn get_active_workspace(state: State<'_, AppState>) -> Result<Option<WorkspaceSnapshot>, String> {
+    Ok(state.workspace.snapshot())
+}
+
+#[tauri::command]
+pub async fn list_workspace_members(
+    state: State<'_, AppState>,
+    workspace_id: i64,
+) -> Result<Vec<WorkspaceMember>, String> {
+    state
+        .with_db_async(move |db| db.get_workspace_members(workspace_id)).await
+        .map_err(|e| e.to_string())
+}
+
+/// Caches the backend's member list so collaborators and roles are
+/// available offline.
+#[tauri::command]
+pub async fn cache_workspace_members(
+    state: State<'_, AppState>,
+    workspace_id: i64,
+    members: Vec<NewWorkspaceMember>,
+) -> Result<(), String> {
+    state
+        .with_db_async(move |db| db.replace_workspace_members(workspace_id, &members)).await
+        .map_err(|e| e.to_string())
+}
+
+/// Whether the user may perform an action that needs `role`, judged from
+/// the cached membership. The backend re-checks once the change syncs.
+#[tauri::command]
+pub async fn check_workspace_role(
+    state: State<'_, AppState>,
+    workspace_id: i64,
+    user_id: i64,
+    role: WorkspaceRole,
+) -> Result<bool, String> {
+    state
+        .with_db_async(move |db| db.has_workspace_role(workspace_id, user_id, role)).await
         .map_err(|e| e.to_string())
 }
 
+// ==================== SEARCH ====================
+
+/// Command palette search over workspaces, projects, notebooks, datasets
+/// and saved queries, best match first. `workspace` narrows it to one
+/// workspace's contents; no `entity_types` means all of them.
+#[tauri::command]
+pub async fn global_search(
+    state: State<'_, AppState>,
+    query: String,
+    entity_types: Option<Vec<SearchEntityType>>,
+    workspace: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<SearchResult>, String> {
+    let filter = SearchFilter {
+        entity_types: entity_types.unwrap_or_default(),
+        workspace_uuid: workspace,
+        limit,
+    };
+
+    state
+        .with_db_async(move |db| db.global_search(&query, &filter)).await
+        .map_err(|e| e.to_string())
+}
+
+// ==================== LOCALE ====================
+
+#[tauri::command]
+pub async fn get_locale(state: State<'_, AppState>) -> Result<LocaleInfo, String> {
+    let override_locale = state
+        .with_db_async(move |db| db.get_setting(i18n::LOCALE_SETTING)).await
+        .map_err(|e| e.to_string())?;
+
+    Ok(LocaleInfo {
+        locale: i18n::current_locale(),
+        system_locale: i18n::system_locale(),
+        override_locale,
+        available: i18n::available_locales(),
+    })
+}
+
+/// Overrides the detected locale for messages produced in Rust; `None`
+/// goes back to following the OS.
+#[tauri::command]
+pub async fn set_locale(state: State<'_, AppState>, locale: Option<String>) -> Result<LocaleInfo, String> {
+    if let Some(locale) = &locale {
+        if !i18n::is_available(locale) {
+            return Err(tr!("locale-unsupported", locale = locale.as_str()));
+        }
+    }
+
+    let stored = locale.clone();
+    state
+        .with_db_async(move |db| match &stored {
+            Some(locale) => db.set_setting(i18n::LOCALE_SETTING, locale),
+            None => db.delete_setting(i18n::LOCALE_SETTING),
+        }).await
+        .map_err(|e| e.to_string())?;
+    i18n::set_locale(locale.as_deref());
+
+    get_locale(state).await
+}
+
+// ==================== APP MODE ====================
+
+#[tauri::command]
+pub async fn get_app_mode(state: State<'_, AppState>) -> Result<AppModeInfo, String> {
+    Ok(state.mode.info())
+}
+
+/// Enters or leaves read-only guest mode; remembered for the next launch.
+#[tauri::command]
+pub async fn set_guest_mode(app: AppHandle, state: State<'_, AppState>, guest: bool) -> Result<AppModeInfo, String> {
+    state.mode.set_guest(guest)?;
+    state
+        .with_db_async(move |db| db.set_setting(guard::GUEST_MODE_SETTING, if guest { "true" } else { "false" })).await
+        .map_err(|e| e.to_string())?;
+
+    let mode = state.mode.info();
+    println!("[NOVEM] Guest mode {}", if mode.guest { "enabled" } else { "disabled" });
+    events::publish(&app, AppEvent::ModeChanged(mode));
+    Ok(mode)
+}
+
+// ==================== EVENTS ====================
+
+/// Latest internal events, oldest first, for debugging how subsystems interact.
+#[tauri::command]
+pub async fn get_recent_events(bus: State<'_, EventBus>, limit: Option<usize>) -> Result<Vec<EventRecord>, String> {
+    Ok(bus.recent(limit.unwrap_or(50)))
+}
+
+// ==================== BACKEND SESSION ====================
+
+#[tauri::command]
+pub async fn set_backend_session(
+    state: State<'_, AppState>,
+    access_token: Option<String>,
+    base_url: Option<String>,
+    user_id: Option<i64>,
+) -> Result<(), String> {
+    let mut session = state.backend.lock()
+        .map_err(|e| format!("Failed to lock backend session: {}", e))?;
+
+    session.access_token = access_token;
+    session.user_id = user_id;
+    if let Some(url) = base_url {
+        session.base_url = url;
+    }
+
+    Ok(())
+}
+
+// ==================== ACCOUNTS ====================
+
+/// Accounts with data on this device, most recently used first.
+#[tauri::command]
+pub async fn list_local_accounts(state: State<'_, AppState>) -> Result<Vec<LocalAccount>, String> {
+    accounts::list(&state.app_dir).map_err(|e| format!("{:#}", e))
+}
+
+/// Opens another account's local data, or with no `uuid` the shared data
+/// used while signed out. Project engines are stopped and the backend
+/// session is cleared; the caller signs the account in with
+/// `set_backend_session` afterwards. Engine tuning saved by that account
+/// applies from the next launch.
+#[tauri::command]
+pub async fn switch_account(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    uuid: Option<String>,
+    email: Option<String>,
+    display_name: Option<String>,
+) -> Result<Option<LocalAccount>, String> {
+    let uuid = uuid.as_deref().map(accounts::normalize_uuid).transpose().map_err(|e| e.to_string())?;
+    // Their workers write to whichever database is open when they finish
+    if !state.transfers.is_idle() || !state.refreshes.is_idle() {
+        return Err(tr!("account-switch-busy"));
+    }
+
+    let data_dir = accounts::data_dir(&state.app_dir, uuid.as_deref());
+    std::fs::create_dir_all(&data_dir).map_err(|e| format!("Failed to create {:?}: {}", data_dir, e))?;
+
+    state.engines.stop_projects();
+    state.workspace.forget(None);
+
+    let pool = state.db.clone();
+    let db_path = data_dir.join(deprovision::DATABASE_FILE);
+    tauri::async_runtime::spawn_blocking(move || pool.reopen_at(db_path))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("{:#}", e))?;
+    *state.data_dir.write().map_err(|e| format!("Failed to lock data directory: {}", e))? = data_dir.clone();
+    state.engines.set_data_dir(&data_dir);
+
+    let (locale, backend_url, active_workspace) = state
+        .with_db_async(|db| {
+            Ok((
+                db.get_setting(i18n::LOCALE_SETTING)?,
+                crate::settings::backend_url(db)?,
+                db.get_setting(workspaces::ACTIVE_WORKSPACE_SETTING)?,
+            ))
+        }).await
+        .map_err(|e| e.to_string())?;
+    i18n::set_locale(locale.as_deref());
+    {
+        let mut session = state.backend.lock()
+            .map_err(|e| format!("Failed to lock backend session: {}", e))?;
+        session.access_token = None;
+        session.user_id = None;
+        session.base_url = backend_url;
+    }
+
+    let account = accounts::set_active(&state.app_dir, uuid.as_deref(), email, display_name)
+        .map_err(|e| format!("{:#}", e))?;
+    println!("[NOVEM] Switched to data of {}", uuid.as_deref().unwrap_or("no account"));
+
+    if let Some(workspace) = active_workspace {
+        if let Err(e) = workspaces::activate(&app, &workspace) {
+            eprintln!("[WARNING] Could not reopen workspace {}: {}", workspace, e);
+        }
+    }
+    Ok(account)
+}
+
+// ==================== DEVICE ====================
+
+/// Wipes this device's copy of one workspace, or of everything, and tells
+/// the backend the device was deprovisioned. Nothing is deleted remotely.
+#[tauri::command]
+pub async fn deprovision_device(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    scope: DeprovisionScope,
+) -> Result<DeprovisionReport, String> {
+    // Taken before wiping: clearing credentials signs the device out
+    let session = state.backend.lock()
+        .map_err(|e| format!("Failed to lock backend session: {}", e))?
+        .clone();
+
+    let targets = {
+        let scope = scope.clone();
+        tauri::async_runtime::spawn_blocking(move || deprovision::wipe(&app, &scope))
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| format!("{:#}", e))?
+    };
+
+    let notified = deprovision::notify_backend(state.http.backend(), &session, &scope, &targets).await;
+    if let Err(e) = &notified {
+        eprintln!("[WARNING] Could not report deprovisioning to the backend: {:#}", e);
+    }
+
+    let report = DeprovisionReport::new(scope, targets, notified);
+    println!("[NOVEM] Device deprovisioned (verified: {})", report.verified);
+    let entity_type = if report.workspace_uuid.is_some() { "workspace" } else { "device" };
+    state
+        .log_activity(
+            NewActivity::new("deprovision", entity_type, report.workspace_uuid.as_deref())
+                .after(serde_json::json!({ "verified": report.verified })),
+        )
+        .await;
+    Ok(report)
+}
+
 #[tauri::command]
 pub async fn health_check() -> Result<String, String> {
     Ok("NOVEM Desktop is running".to_string())
-}
\ No newline at end of file
+}
+/// What startup recovery rolled back or resumed; also emitted as
+/// `recovery-report`, which the UI may miss while still loading.
+#[tauri::command]
+pub async fn get_recovery_report(state: State<'_, AppState>) -> Result<RecoveryReport, String> {
+    Ok(state.recovery_report.clone())
+}
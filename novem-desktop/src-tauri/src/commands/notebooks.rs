@@ -0,0 +1,187 @@
+use tauri::State;
+
+use crate::dashboards;
+use super::recent::remember_opened;
+use crate::database::{CellUpdate, Execution, NewActivity, NewCell, NewExecution, Notebook, NotebookCell, RecentEntity};
+use crate::AppState;
+
+/// What the activity log keeps of a cell; outputs are left out.
+fn cell_content(cell: &NotebookCell) -> serde_json::Value {
+    serde_json::json!({
+        "position": cell.position,
+        "cell_type": cell.cell_type,
+        "source": cell.source,
+        "tags": cell.tags,
+    })
+}
+
+#[tauri::command]
+pub async fn list_notebooks(state: State<'_, AppState>, project_id: i64) -> Result<Vec<Notebook>, String> {
+    let notebooks = state
+        .with_db_async(move |db| db.get_project_notebooks(project_id)).await
+        .map_err(|e| e.to_string())?;
+
+    remember_opened(&state, move |db| db.record_opened_project(project_id)).await;
+    Ok(notebooks)
+}
+
+#[tauri::command]
+pub async fn create_notebook(state: State<'_, AppState>, project_id: i64, name: String) -> Result<Notebook, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Notebook name cannot be empty".to_string());
+    }
+
+    let notebook = state
+        .with_db_async(move |db| db.create_notebook(project_id, &name)).await
+        .map_err(|e| e.to_string())?;
+
+    state
+        .log_activity(NewActivity::new("create", "notebook", Some(&notebook.uuid)).after(&notebook.name))
+        .await;
+    Ok(notebook)
+}
+
+/// Cells of a notebook in display order, with their cached outputs.
+#[tauri::command]
+pub async fn list_cells(state: State<'_, AppState>, notebook_uuid: String) -> Result<Vec<NotebookCell>, String> {
+    let cells = {
+        let notebook_uuid = notebook_uuid.clone();
+        state
+            .with_db_async(move |db| match db.get_notebook_by_uuid(&notebook_uuid)? {
+                Some(notebook) => db.get_notebook_cells(notebook.id),
+                None => Err(anyhow::anyhow!("Notebook {} not found", notebook_uuid)),
+            }).await
+            .map_err(|e| e.to_string())?
+    };
+
+    remember_opened(&state, move |db| db.record_opened(RecentEntity::Notebook, &notebook_uuid)).await;
+    Ok(cells)
+}
+
+/// Saves a new cell locally and queues it for sync.
+#[tauri::command]
+pub async fn create_cell(
+    state: State<'_, AppState>,
+    notebook_uuid: String,
+    cell: NewCell,
+) -> Result<NotebookCell, String> {
+    let cell = state
+        .with_db_async(move |db| db.create_cell(&notebook_uuid, &cell)).await
+        .map_err(|e| e.to_string())?;
+
+    state
+        .log_activity(NewActivity::new("create", "notebook_cell", Some(&cell.uuid)).after(cell_content(&cell)))
+        .await;
+    Ok(cell)
+}
+
+/// Saves an edit to a cell locally and queues it for sync.
+#[tauri::command]
+pub async fn update_cell(
+    state: State<'_, AppState>,
+    cell_uuid: String,
+    update: CellUpdate,
+) -> Result<NotebookCell, String> {
+    let uuid = cell_uuid.clone();
+    let (old, cell) = state
+        .with_db_async(move |db| Ok((db.get_cell_by_uuid(&uuid)?, db.update_cell(&uuid, &update)?))).await
+        .map_err(|e| e.to_string())?;
+    let (Some(old), Some(cell)) = (old, cell) else {
+        return Err(format!("Cell {} not found", cell_uuid));
+    };
+
+    state
+        .log_activity(
+            NewActivity::new("update", "notebook_cell", Some(&cell.uuid))
+                .before(cell_content(&old))
+                .after(cell_content(&cell)),
+        )
+        .await;
+    Ok(cell)
+}
+
+/// Stores the outputs of a cell execution. Dashboards published from the
+/// notebook are re-published so they always show the latest results.
+#[tauri::command]
+pub async fn save_cell_outputs(
+    state: State<'_, AppState>,
+    cell_uuid: String,
+    outputs: serde_json::Value,
+) -> Result<bool, String> {
+    let artifacts_dir = state.artifacts_dir();
+
+    state
+        .with_db_async(move |db| {
+            let notebook_uuid = match db.update_cell_outputs(&cell_uuid, &outputs.to_string())? {
+                Some(uuid) => uuid,
+                None => return Ok(false),
+            };
+
+            if let Some(dashboard) = db.get_dashboard_for_notebook(&notebook_uuid)? {
+                if dashboard.auto_republish {
+                    if let Err(e) = dashboards::publish(db, &artifacts_dir, &notebook_uuid, None) {
+                        eprintln!("[NOVEM] Failed to re-publish dashboard {}: {}", dashboard.uuid, e);
+                    }
+                }
+            }
+
+            Ok(true)
+        }).await
+        .map_err(|e| e.to_string())
+}
+
+/// Records a cell run in the notebook's execution history. Without an
+/// explicit `engine_version`, the Python version and source revision of the
+/// running engine are recorded.
+#[tauri::command]
+pub async fn record_execution(
+    state: State<'_, AppState>,
+    mut execution: NewExecution,
+) -> Result<Execution, String> {
+    if execution.engine_version.is_none() {
+        execution.engine_version = state.engine_info.lock()
+            .map_err(|e| format!("Failed to lock engine info: {}", e))?
+            .as_ref()
+            .map(|info| match &info.git_revision {
+                Some(revision) => format!("Python {} @ {}", info.python_version, revision),
+                None => format!("Python {}", info.python_version),
+            });
+    }
+
+    state
+        .with_db_async(move |db| db.record_execution(&execution)).await
+        .map_err(|e| e.to_string())
+}
+
+/// Cell runs of a notebook, newest first; `limit` defaults to 200.
+#[tauri::command]
+pub async fn get_execution_history(
+    state: State<'_, AppState>,
+    notebook_uuid: String,
+    cell_uuid: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<Execution>, String> {
+    let limit = limit.unwrap_or(200).clamp(1, 1000);
+    state
+        .with_db_async(move |db| db.get_execution_history(&notebook_uuid, cell_uuid.as_deref(), limit)).await
+        .map_err(|e| e.to_string())
+}
+
+/// Duplicates a notebook, cells and outputs included, within its project.
+#[tauri::command]
+pub async fn clone_notebook(state: State<'_, AppState>, uuid: String) -> Result<Notebook, String> {
+    let source = uuid.clone();
+    let notebook = state
+        .with_db_async(move |db| db.clone_notebook(&source)).await
+        .map_err(|e| e.to_string())?;
+
+    state
+        .log_activity(
+            NewActivity::new("clone", "notebook", Some(&notebook.uuid))
+                .before(serde_json::json!({ "uuid": uuid }))
+                .after(&notebook.name),
+        )
+        .await;
+    Ok(notebook)
+}
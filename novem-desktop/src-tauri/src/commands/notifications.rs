@@ -0,0 +1,26 @@
+use tauri::State;
+
+use crate::database::Notification;
+use crate::AppState;
+
+/// The notification inbox, newest first.
+#[tauri::command]
+pub async fn get_notifications(
+    state: State<'_, AppState>,
+    unread_only: Option<bool>,
+    limit: Option<i64>,
+) -> Result<Vec<Notification>, String> {
+    let limit = limit.unwrap_or(100).clamp(1, 1000);
+    state
+        .with_db_async(move |db| db.get_notifications(unread_only.unwrap_or(false), limit)).await
+        .map_err(|e| e.to_string())
+}
+
+/// Marks the given notifications read, or the whole inbox when `ids` is
+/// omitted. Returns how many were unread.
+#[tauri::command]
+pub async fn mark_read(state: State<'_, AppState>, ids: Option<Vec<i64>>) -> Result<usize, String> {
+    state
+        .with_db_async(move |db| db.mark_notifications_read(ids.as_deref())).await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,75 @@
+use std::path::PathBuf;
+use tauri::State;
+
+use crate::database::{ImportConflictPolicy, ImportReport, LocalDataBundle, LocalDataCounts, NewActivity};
+use crate::i18n::tr;
+use crate::AppState;
+
+/// Writes this machine's workspaces, projects and datasets to a JSON bundle
+/// at `path`, for `import_local_data` on another machine. Dataset files are
+/// not included.
+#[tauri::command]
+pub async fn export_local_data(state: State<'_, AppState>, path: String) -> Result<LocalDataCounts, String> {
+    let bundle = state
+        .with_db_async(|db| db.export_local_data()).await
+        .map_err(|e| e.to_string())?;
+    let json = serde_json::to_vec_pretty(&bundle).map_err(|e| e.to_string())?;
+
+    // Written beside the target first, so a failed export never leaves a
+    // truncated bundle behind
+    let path = PathBuf::from(path);
+    let temp = path.with_extension("json.partial");
+    if let Err(e) = tokio::fs::write(&temp, &json).await {
+        let _ = tokio::fs::remove_file(&temp).await;
+        return Err(tr!("export-write-failed", path = path.display().to_string(), reason = e.to_string()));
+    }
+    tokio::fs::rename(&temp, &path)
+        .await
+        .map_err(|e| tr!("export-write-failed", path = path.display().to_string(), reason = e.to_string()))?;
+
+    let counts = bundle.counts();
+    println!(
+        "[NOVEM] Exported {} workspaces, {} projects, {} datasets to {:?}",
+        counts.workspaces, counts.projects, counts.datasets, path
+    );
+    Ok(counts)
+}
+
+/// Merges a bundle from `export_local_data` into this machine's data.
+/// Entities already here are matched by UUID and resolved per `policy`
+/// (the most recently updated copy wins by default).
+#[tauri::command]
+pub async fn import_local_data(
+    state: State<'_, AppState>,
+    path: String,
+    policy: Option<ImportConflictPolicy>,
+) -> Result<ImportReport, String> {
+    let policy = policy.unwrap_or_default();
+    let json = tokio::fs::read(&path)
+        .await
+        .map_err(|e| tr!("import-read-failed", path = path.as_str(), reason = e.to_string()))?;
+    let bundle: LocalDataBundle = serde_json::from_slice(&json)
+        .map_err(|e| tr!("import-read-failed", path = path.as_str(), reason = e.to_string()))?;
+    let counts = bundle.counts();
+
+    let report = state
+        .with_db_async(move |db| db.import_local_data(&bundle, policy)).await
+        .map_err(|e| e.to_string())?;
+
+    println!(
+        "[NOVEM] Imported {} workspaces, {} projects, {} datasets from {} ({} conflicts)",
+        report.workspaces.inserted, report.projects.inserted, report.datasets.inserted, path, report.conflicts.len()
+    );
+    for file in &report.missing_files {
+        eprintln!("[WARNING] Imported dataset file not found on this machine: {}", file);
+    }
+
+    state
+        .log_activity(NewActivity::new("import", "local_data", None).after(serde_json::json!({
+            "path": path,
+            "bundle": counts,
+            "conflicts": report.conflicts.len(),
+        })))
+        .await;
+    Ok(report)
+}
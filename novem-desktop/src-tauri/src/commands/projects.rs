@@ -0,0 +1,131 @@
+use std::path::PathBuf;
+use tauri::State;
+
+use crate::database::{BulkResult, CloneOptions, ClonedProject, DatasetCloneMode, NewActivity};
+use crate::i18n::tr;
+use crate::recovery::{self, Operation};
+use crate::AppState;
+
+/// Starts a new project from an existing one ("start from last quarter's
+/// analysis"). Datasets are shared by reference unless `options` asks for
+/// copies; those are staged under a journal entry and only moved into the
+/// managed datasets directory once the clone has committed.
+#[tauri::command]
+pub async fn clone_project(
+    state: State<'_, AppState>,
+    uuid: String,
+    new_name: String,
+    options: Option<CloneOptions>,
+) -> Result<ClonedProject, String> {
+    let options = options.unwrap_or_default();
+    let data_dir = state.data_dir();
+    let datasets_dir = data_dir.join("datasets");
+    let staging_dir = data_dir.join("staging").join(uuid::Uuid::new_v4().to_string());
+
+    let operation = Operation::CloneProject {
+        staging_dir: staging_dir.to_string_lossy().to_string(),
+        datasets_dir: datasets_dir.to_string_lossy().to_string(),
+    };
+    let journal = match options.datasets {
+        DatasetCloneMode::Copy => recovery::begin(&state, &operation),
+        _ => None,
+    };
+
+    let (source_uuid, name) = (uuid.clone(), new_name.clone());
+    let result = state.with_db_async(move |db| {
+        let cloned = db.clone_project(&source_uuid, &name, &options, |dataset, new_uuid| {
+            let source = PathBuf::from(&dataset.file_path);
+            let file_name = match source.extension() {
+                Some(ext) => format!("{}.{}", new_uuid, ext.to_string_lossy()),
+                None => new_uuid.to_string(),
+            };
+
+            std::fs::create_dir_all(&staging_dir)?;
+            std::fs::copy(&source, staging_dir.join(&file_name))
+                .map_err(|e| anyhow::anyhow!("Failed to copy dataset '{}': {}", dataset.name, e))?;
+
+            Ok(datasets_dir.join(file_name).to_string_lossy().to_string())
+        });
+
+        // Committed copies move into place; copies of a failed clone go away
+        std::fs::create_dir_all(&datasets_dir)?;
+        recovery::settle_clone(db, &operation)?;
+        cloned
+    }).await;
+
+    recovery::finish(&state, journal, if result.is_ok() { "completed" } else { "rolled_back" });
+
+    let cloned = result.map_err(|e| e.to_string())?;
+    println!(
+        "[NOVEM] Cloned project {} as '{}' ({} notebooks, {} datasets)",
+        uuid, new_name, cloned.notebooks, cloned.datasets
+    );
+    state
+        .log_activity(
+            NewActivity::new("clone", "project", Some(&cloned.project.uuid))
+                .before(serde_json::json!({ "uuid": uuid }))
+                .after(&cloned.project.name),
+        )
+        .await;
+    Ok(cloned)
+}
+
+/// Moves projects to another workspace. Like the other bulk commands, runs
+/// in one transaction and reports per-project failures instead of aborting.
+#[tauri::command]
+pub async fn bulk_move_projects(
+    state: State<'_, AppState>,
+    ids: Vec<i64>,
+    target_workspace: i64,
+) -> Result<BulkResult, String> {
+    let result = state
+        .with_db_async(move |db| db.bulk_move_projects(&ids, target_workspace)).await
+        .map_err(|e| e.to_string())?;
+
+    state
+        .log_activity(NewActivity::new("move", "project", None).after(serde_json::json!({
+            "project_ids": result.succeeded,
+            "workspace_id": target_workspace,
+        })))
+        .await;
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn bulk_tag(
+    state: State<'_, AppState>,
+    ids: Vec<i64>,
+    tag: String,
+) -> Result<BulkResult, String> {
+    let tag = tag.trim().to_string();
+    if tag.is_empty() {
+        return Err(tr!("tag-empty"));
+    }
+
+    let applied = tag.clone();
+    let result = state
+        .with_db_async(move |db| db.bulk_tag_projects(&ids, &applied)).await
+        .map_err(|e| e.to_string())?;
+
+    state
+        .log_activity(NewActivity::new("tag", "project", None).after(serde_json::json!({
+            "project_ids": result.succeeded,
+            "tag": tag,
+        })))
+        .await;
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn bulk_archive(state: State<'_, AppState>, ids: Vec<i64>) -> Result<BulkResult, String> {
+    let result = state
+        .with_db_async(move |db| db.bulk_archive_projects(&ids)).await
+        .map_err(|e| e.to_string())?;
+
+    state
+        .log_activity(
+            NewActivity::new("archive", "project", None).after(serde_json::json!({ "project_ids": result.succeeded })),
+        )
+        .await;
+    Ok(result)
+}
@@ -0,0 +1,115 @@
+use serde::Serialize;
+use tauri::State;
+
+use crate::database::{Dataset, DatasetRecipe, NewActivity, QueryHistoryEntry, QueryHistoryFilter};
+use crate::datasets::sampling::SampleSpec;
+use crate::i18n::tr;
+use crate::queries::{self, QueryResult};
+use crate::AppState;
+
+/// Result of re-running a history entry, tagged with the entry's kind.
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", content = "result", rename_all = "snake_case")]
+pub enum RerunResult {
+    Sql(QueryResult),
+    Sample(Dataset),
+    Recipe(Dataset),
+}
+
+#[tauri::command]
+pub async fn run_query(
+    state: State<'_, AppState>,
+    sql: String,
+    project_id: Option<i64>,
+    request_id: Option<String>,
+) -> Result<QueryResult, String> {
+    let request_id = request_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    queries::run_sql(&state, &sql, project_id, &request_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_query_history(
+    state: State<'_, AppState>,
+    filters: Option<QueryHistoryFilter>,
+) -> Result<Vec<QueryHistoryEntry>, String> {
+    let filters = filters.unwrap_or_default();
+    state
+        .with_db_async(move |db| db.get_query_history(&filters)).await
+        .map_err(|e| e.to_string())
+}
+
+/// Marks (or with `favorite: false`, unmarks) a history entry as a favorite.
+#[tauri::command]
+pub async fn favorite_query(
+    state: State<'_, AppState>,
+    id: i64,
+    favorite: Option<bool>,
+) -> Result<bool, String> {
+    let favorite = favorite.unwrap_or(true);
+    let updated = state
+        .with_db_async(move |db| db.set_query_favorite(id, favorite)).await
+        .map_err(|e| e.to_string())?;
+
+    if updated {
+        state
+            .log_activity(
+                NewActivity::new("favorite", "query", None).after(serde_json::json!({ "id": id, "favorite": favorite })),
+            )
+            .await;
+    }
+    Ok(updated)
+}
+
+/// Runs a history entry again against its original target. The re-run is
+/// recorded as a new entry.
+#[tauri::command]
+pub async fn rerun_query(state: State<'_, AppState>, id: i64) -> Result<RerunResult, String> {
+    let entry = state
+        .with_db_async(move |db| db.get_query_history_entry(id)).await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| tr!("query-not-found", id = id))?;
+
+    let result = match entry.kind.as_str() {
+        "sql" => queries::run_sql(&state, &entry.query_text, entry.project_id, &uuid::Uuid::new_v4().to_string())
+            .await
+            .map(RerunResult::Sql)
+            .map_err(|e| e.to_string()),
+        "sample" => {
+            let params: serde_json::Value = serde_json::from_str(&entry.params).map_err(|e| e.to_string())?;
+            let dataset_uuid = params["dataset_uuid"]
+                .as_str()
+                .ok_or_else(|| "Sample history entry has no dataset".to_string())?;
+            let spec: SampleSpec = serde_json::from_value(params["spec"].clone()).map_err(|e| e.to_string())?;
+
+            crate::commands::datasets::sample_dataset(&state, dataset_uuid, spec)
+                .await
+                .map(RerunResult::Sample)
+        }
+        "recipe" => {
+            let params: serde_json::Value = serde_json::from_str(&entry.params).map_err(|e| e.to_string())?;
+            let dataset_uuid = params["dataset_uuid"]
+                .as_str()
+                .ok_or_else(|| "Recipe history entry has no dataset".to_string())?;
+            // The recipe as it was run, even if it has been edited since
+            let recipe: DatasetRecipe = serde_json::from_value(params["recipe"].clone()).map_err(|e| e.to_string())?;
+
+            crate::commands::datasets::transform_dataset(&state, dataset_uuid, &recipe)
+                .await
+                .map(RerunResult::Recipe)
+        }
+        other => Err(tr!("query-not-rerunnable", kind = other)),
+    }?;
+
+    if let RerunResult::Sample(dataset) | RerunResult::Recipe(dataset) = &result {
+        state
+            .log_activity(
+                NewActivity::new("rerun", "dataset", Some(&dataset.uuid))
+                    .before(serde_json::json!({ "query_id": id }))
+                    .after(&dataset.name),
+            )
+            .await;
+    }
+    Ok(result)
+}
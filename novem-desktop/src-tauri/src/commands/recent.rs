@@ -0,0 +1,33 @@
+use tauri::State;
+
+use crate::database::{LocalDatabase, RecentItem};
+use crate::AppState;
+
+/// Records that something was opened. Never fails the command opening it.
+pub(crate) async fn remember_opened(
+    state: &AppState,
+    record: impl FnOnce(&LocalDatabase) -> anyhow::Result<()> + Send + 'static,
+) {
+    if let Err(e) = state.with_db_async(record).await {
+        eprintln!("[WARNING] Failed to record recently opened item: {}", e);
+    }
+}
+
+/// What was opened last, most recent first, for "Continue where you left
+/// off".
+#[tauri::command]
+pub async fn get_recent_items(state: State<'_, AppState>, limit: Option<i64>) -> Result<Vec<RecentItem>, String> {
+    state
+        .with_db_async(move |db| db.get_recent_items(limit)).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn clear_recent_items(state: State<'_, AppState>) -> Result<usize, String> {
+    let cleared = state
+        .with_db_async(|db| db.clear_recent_items()).await
+        .map_err(|e| e.to_string())?;
+
+    println!("[NOVEM] Cleared {} recent items", cleared);
+    Ok(cleared)
+}
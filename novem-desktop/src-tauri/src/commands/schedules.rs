@@ -0,0 +1,156 @@
+use serde::Deserialize;
+use tauri::State;
+
+use crate::database::{NewActivity, ScheduledTask};
+use crate::i18n::tr;
+use crate::schedules::{self, ScheduleAction};
+use crate::AppState;
+
+/// Changes to a schedule; omitted fields keep their value.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ScheduleUpdate {
+    pub name: Option<String>,
+    pub cron: Option<String>,
+    /// Replaces the action and its payload
+    pub task: Option<ScheduleAction>,
+    pub enabled: Option<bool>,
+}
+
+/// Checks that what a schedule would act on exists now, rather than
+/// failing at 2am.
+async fn validate_action(state: &AppState, action: &ScheduleAction) -> Result<(), String> {
+    match action.clone() {
+        ScheduleAction::Sync => Ok(()),
+        ScheduleAction::RefreshDataset { dataset_uuid } => state
+            .with_db_async(move |db| {
+                db.get_dataset_refresh(&dataset_uuid)?
+                    .map(|_| ())
+                    .ok_or_else(|| anyhow::anyhow!(tr!("refresh-not-scheduled", uuid = dataset_uuid.as_str())))
+            }).await
+            .map_err(|e| e.to_string()),
+        ScheduleAction::RunQuery { query_id } => {
+            let entry = state
+                .with_db_async(move |db| db.get_query_history_entry(query_id)).await
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| tr!("query-not-found", id = query_id))?;
+            if entry.kind != "sql" {
+                return Err(tr!("query-not-rerunnable", kind = entry.kind.as_str()));
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Schedules a task on a cron expression such as "0 2 * * *" (2am daily,
+/// local time).
+#[tauri::command]
+pub async fn create_schedule(
+    state: State<'_, AppState>,
+    name: String,
+    cron: String,
+    task: ScheduleAction,
+) -> Result<ScheduledTask, String> {
+    if name.trim().is_empty() {
+        return Err(tr!("schedule-name-empty"));
+    }
+    let next_run_at = schedules::next_run(&cron, chrono::Utc::now())
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| tr!("schedule-cron-invalid", cron = cron.as_str()))?;
+    validate_action(&state, &task).await?;
+
+    let (action, payload) = task.to_parts().map_err(|e| e.to_string())?;
+    let uuid = uuid::Uuid::new_v4().to_string();
+    let schedule = state
+        .with_db_async(move |db| {
+            db.create_schedule(&uuid, name.trim(), cron.trim(), &action, &payload.to_string(), &next_run_at)
+        }).await
+        .map_err(|e| e.to_string())?;
+
+    state
+        .log_activity(NewActivity::new("create", "schedule", Some(&schedule.uuid)).after(serde_json::json!({
+            "name": schedule.name,
+            "cron": schedule.cron,
+            "action": schedule.action,
+        })))
+        .await;
+    Ok(schedule)
+}
+
+#[tauri::command]
+pub async fn list_schedules(state: State<'_, AppState>) -> Result<Vec<ScheduledTask>, String> {
+    state
+        .with_db_async(|db| db.list_schedules()).await
+        .map_err(|e| e.to_string())
+}
+
+/// Edits a schedule. Its next run is recomputed from now, and cleared
+/// while it is disabled.
+#[tauri::command]
+pub async fn update_schedule(
+    state: State<'_, AppState>,
+    uuid: String,
+    update: ScheduleUpdate,
+) -> Result<ScheduledTask, String> {
+    let existing = {
+        let uuid = uuid.clone();
+        state
+            .with_db_async(move |db| db.get_schedule(&uuid)).await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| tr!("schedule-not-found", uuid = uuid.as_str()))?
+    };
+
+    let name = update.name.unwrap_or_else(|| existing.name.clone());
+    if name.trim().is_empty() {
+        return Err(tr!("schedule-name-empty"));
+    }
+    let cron = update.cron.unwrap_or_else(|| existing.cron.clone());
+    let enabled = update.enabled.unwrap_or(existing.enabled);
+    let (action, payload) = match &update.task {
+        Some(task) => {
+            validate_action(&state, task).await?;
+            task.to_parts().map_err(|e| e.to_string())?
+        }
+        None => (existing.action.clone(), existing.payload.clone()),
+    };
+
+    let next_run_at = schedules::next_run(&cron, chrono::Utc::now()).map_err(|e| e.to_string())?;
+    let next_run_at = next_run_at.filter(|_| enabled);
+
+    let schedule = {
+        let uuid = uuid.clone();
+        state
+            .with_db_async(move |db| {
+                let payload = payload.to_string();
+                db.update_schedule(&uuid, name.trim(), cron.trim(), &action, &payload, enabled, next_run_at.as_deref())?;
+                db.get_schedule(&uuid)?
+                    .ok_or_else(|| anyhow::anyhow!(tr!("schedule-not-found", uuid = uuid.as_str())))
+            }).await
+            .map_err(|e| e.to_string())?
+    };
+
+    state
+        .log_activity(
+            NewActivity::new("update", "schedule", Some(&uuid))
+                .before(serde_json::json!({ "name": existing.name, "cron": existing.cron, "enabled": existing.enabled }))
+                .after(serde_json::json!({ "name": schedule.name, "cron": schedule.cron, "enabled": schedule.enabled })),
+        )
+        .await;
+    Ok(schedule)
+}
+
+#[tauri::command]
+pub async fn delete_schedule(state: State<'_, AppState>, uuid: String) -> Result<bool, String> {
+    let deleted = {
+        let uuid = uuid.clone();
+        state
+            .with_db_async(move |db| db.delete_schedule(&uuid)).await
+            .map_err(|e| e.to_string())?
+    };
+
+    if deleted {
+        state
+            .log_activity(NewActivity::new("delete", "schedule", Some(&uuid)))
+            .await;
+    }
+    Ok(deleted)
+}
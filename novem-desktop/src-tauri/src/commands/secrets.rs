@@ -0,0 +1,63 @@
+use tauri::State;
+
+use crate::database::{NewActivity, SecretInfo};
+use crate::i18n::tr;
+use crate::secrets::{self, VaultKey};
+use crate::AppState;
+
+/// Names end up in connection configs, so they stay simple identifiers.
+fn check_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 128
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+    if valid {
+        Ok(())
+    } else {
+        Err(tr!("secret-name-invalid"))
+    }
+}
+
+/// Encrypts a credential and stores it under `name`, replacing any
+/// previous value. Refused when the OS keyring is unavailable rather than
+/// storing the value in the clear.
+#[tauri::command]
+pub async fn store_secret(state: State<'_, AppState>, name: String, value: String) -> Result<(), String> {
+    let name = name.trim().to_string();
+    check_name(&name)?;
+
+    let key = VaultKey::get().map_err(|e| tr!("secret-vault-unavailable", reason = format!("{:#}", e)))?;
+    let stored = name.clone();
+    state
+        .with_db_async(move |db| secrets::store(db, key, &stored, &value)).await
+        .map_err(|e| e.to_string())?;
+
+    // The value itself never goes into the activity log
+    state
+        .log_activity(NewActivity::new("store", "secret", Some(&name)))
+        .await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_secret_names(state: State<'_, AppState>) -> Result<Vec<SecretInfo>, String> {
+    state
+        .with_db_async(|db| db.list_secrets()).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_secret(state: State<'_, AppState>, name: String) -> Result<bool, String> {
+    let deleted = {
+        let name = name.clone();
+        state
+            .with_db_async(move |db| db.delete_secret(&name)).await
+            .map_err(|e| e.to_string())?
+    };
+
+    if deleted {
+        state
+            .log_activity(NewActivity::new("delete", "secret", Some(&name)))
+            .await;
+    }
+    Ok(deleted)
+}
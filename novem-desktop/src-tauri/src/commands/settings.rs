@@ -0,0 +1,59 @@
+use serde_json::Value;
+use tauri::State;
+
+use crate::database::NewActivity;
+use crate::settings::{self, SettingValue, BACKEND_URL_SETTING, SYNC_INTERVAL_SETTING};
+use crate::AppState;
+
+#[tauri::command]
+pub async fn get_setting(state: State<'_, AppState>, key: String) -> Result<SettingValue, String> {
+    state
+        .with_db_async(move |db| settings::load(db, &key)).await
+        .map_err(|e| e.to_string())
+}
+
+/// Every known setting with its current value, default and schema.
+#[tauri::command]
+pub async fn get_all_settings(state: State<'_, AppState>) -> Result<Vec<SettingValue>, String> {
+    state
+        .with_db_async(settings::load_all).await
+        .map_err(|e| e.to_string())
+}
+
+/// Validates `value` against the setting's schema and persists it. Passing
+/// `null` resets it to the default.
+#[tauri::command]
+pub async fn set_setting(state: State<'_, AppState>, key: String, value: Value) -> Result<SettingValue, String> {
+    let (before, after) = {
+        let key = key.clone();
+        state
+            .with_db_async(move |db| {
+                let before = settings::load(db, &key)?;
+                let after = settings::store(db, &key, &value)?;
+                Ok((before, after))
+            }).await
+            .map_err(|e| e.to_string())?
+    };
+
+    if key == BACKEND_URL_SETTING {
+        if let Some(url) = after.value.as_str() {
+            let mut session = state.backend.lock()
+                .map_err(|e| format!("Failed to lock backend session: {}", e))?;
+            session.base_url = url.to_string();
+        }
+    }
+    if key == SYNC_INTERVAL_SETTING {
+        state.sync.wake();
+    }
+
+    state
+        .log_activity(
+            NewActivity::new("update", "setting", Some(&key))
+                .before(&before.value)
+                .after(&after.value),
+        )
+        .await;
+
+    println!("[NOVEM] Setting {} = {}", key, after.value);
+    Ok(after)
+}
@@ -0,0 +1,81 @@
+use tauri::State;
+
+use crate::database::{NewActivity, ShareLink};
+use crate::i18n::tr;
+use crate::{resources, timestamps, AppState};
+
+#[tauri::command]
+pub async fn list_share_links(
+    state: State<'_, AppState>,
+    entity_type: String,
+    entity_uuid: String,
+    include_revoked: Option<bool>,
+) -> Result<Vec<ShareLink>, String> {
+    state
+        .with_db_async(move |db| db.list_share_links(&entity_type, &entity_uuid, include_revoked.unwrap_or(false))).await
+        .map_err(|e| e.to_string())
+}
+
+/// Creates a share link as the signed-in user. Works offline: the link is
+/// queued for sync and becomes active once the backend has it.
+/// `expires_in` is a duration such as "7d"; without it the link never
+/// expires.
+#[tauri::command]
+pub async fn create_share_link(
+    state: State<'_, AppState>,
+    entity_type: String,
+    entity_uuid: String,
+    permission: Option<String>,
+    expires_in: Option<String>,
+) -> Result<ShareLink, String> {
+    let expires_at = match expires_in {
+        Some(expires_in) => {
+            let secs = resources::parse_duration(&expires_in).map_err(|e| e.to_string())?;
+            if secs <= 0 {
+                return Err(tr!("share-expiry-invalid"));
+            }
+            Some(timestamps::format(chrono::Utc::now() + chrono::Duration::seconds(secs)))
+        }
+        None => None,
+    };
+    let created_by = state.backend.lock()
+        .map_err(|e| format!("Failed to lock backend session: {}", e))?
+        .user_id;
+    let permission = permission.unwrap_or_else(|| "view".to_string());
+
+    let link = state
+        .with_db_async(move |db| {
+            db.create_share_link(&entity_type, &entity_uuid, &permission, expires_at.as_deref(), created_by)
+        }).await
+        .map_err(|e| e.to_string())?;
+
+    state
+        .log_activity(
+            NewActivity::new("share", &link.entity_type, Some(&link.entity_uuid)).after(serde_json::json!({
+                "link": link.uuid,
+                "permission": link.permission,
+                "expires_at": link.expires_at,
+            })),
+        )
+        .await;
+    Ok(link)
+}
+
+/// Stops a link from working. Revoking while offline takes effect on the
+/// backend at the next sync.
+#[tauri::command]
+pub async fn revoke_share_link(state: State<'_, AppState>, uuid: String) -> Result<Option<ShareLink>, String> {
+    let link = state
+        .with_db_async(move |db| db.revoke_share_link(&uuid)).await
+        .map_err(|e| e.to_string())?;
+
+    if let Some(link) = &link {
+        state
+            .log_activity(
+                NewActivity::new("unshare", &link.entity_type, Some(&link.entity_uuid))
+                    .before(serde_json::json!({ "link": link.uuid, "permission": link.permission })),
+            )
+            .await;
+    }
+    Ok(link)
+}
@@ -0,0 +1,69 @@
+use tauri::{AppHandle, State};
+
+use crate::database::NewActivity;
+use crate::i18n::tr;
+use crate::settings::{self, SYNC_INTERVAL_SETTING};
+use crate::sync::{self, SyncStatus, SyncSummary};
+use crate::AppState;
+
+#[tauri::command]
+pub async fn get_sync_status(state: State<'_, AppState>) -> Result<SyncStatus, String> {
+    sync::status(&state).map_err(|e| e.to_string())
+}
+
+/// Resumes background sync; a pass runs right away.
+#[tauri::command]
+pub async fn start_sync(state: State<'_, AppState>) -> Result<SyncStatus, String> {
+    state.sync.set_enabled(true);
+    println!("[NOVEM] Background sync started");
+    sync::status(&state).map_err(|e| e.to_string())
+}
+
+/// Pauses background sync until `start_sync` or the next launch. Changes
+/// keep queueing meanwhile.
+#[tauri::command]
+pub async fn stop_sync(state: State<'_, AppState>) -> Result<SyncStatus, String> {
+    state.sync.set_enabled(false);
+    println!("[NOVEM] Background sync stopped");
+    sync::status(&state).map_err(|e| e.to_string())
+}
+
+/// Sets how often the queue is pushed, in seconds (5 to 3600).
+#[tauri::command]
+pub async fn set_sync_interval(state: State<'_, AppState>, secs: u64) -> Result<SyncStatus, String> {
+    let (before, after) = state
+        .with_db_async(move |db| {
+            let before = settings::load(db, SYNC_INTERVAL_SETTING)?;
+            let after = settings::store(db, SYNC_INTERVAL_SETTING, &serde_json::json!(secs))?;
+            Ok((before, after))
+        }).await
+        .map_err(|e| e.to_string())?;
+    state.sync.wake();
+
+    state
+        .log_activity(
+            NewActivity::new("update", "setting", Some(SYNC_INTERVAL_SETTING))
+                .before(&before.value)
+                .after(&after.value),
+        )
+        .await;
+
+    println!("[NOVEM] Sync interval set to {}s", secs);
+    sync::status(&state).map_err(|e| e.to_string())
+}
+
+/// "Sync now": runs a full push and pull right away, even while background
+/// sync is stopped, and reports what it did.
+#[tauri::command]
+pub async fn trigger_sync(app: AppHandle, state: State<'_, AppState>) -> Result<SyncSummary, String> {
+    if !state.connectivity.is_online() {
+        return Err(tr!("sync-offline"));
+    }
+    let summary = sync::run_cycle(&app, &state).await.map_err(|e| format!("{:#}", e))?;
+
+    println!(
+        "[NOVEM] Manual sync in {}ms: pushed {}, pulled {}, {} conflicts, {} failed",
+        summary.duration_ms, summary.pushed, summary.pulled, summary.conflicts, summary.failed
+    );
+    Ok(summary)
+}
@@ -0,0 +1,88 @@
+use tauri::State;
+
+use crate::database::{NewActivity, ProjectTemplate, TemplatedProject};
+use crate::AppState;
+
+#[tauri::command]
+pub async fn list_project_templates(state: State<'_, AppState>) -> Result<Vec<ProjectTemplate>, String> {
+    state
+        .with_db_async(|db| db.list_project_templates()).await
+        .map_err(|e| e.to_string())
+}
+
+/// Saves a project's notebooks and dataset references as a template for
+/// new projects.
+#[tauri::command]
+pub async fn save_project_template(
+    state: State<'_, AppState>,
+    project_uuid: String,
+    name: String,
+) -> Result<ProjectTemplate, String> {
+    let template = state
+        .with_db_async({
+            let project_uuid = project_uuid.clone();
+            move |db| db.save_project_template(&project_uuid, &name)
+        }).await
+        .map_err(|e| e.to_string())?;
+
+    state
+        .log_activity(
+            NewActivity::new("create", "project_template", Some(&template.uuid))
+                .before(serde_json::json!({ "project_uuid": project_uuid }))
+                .after(&template.name),
+        )
+        .await;
+    Ok(template)
+}
+
+#[tauri::command]
+pub async fn delete_project_template(state: State<'_, AppState>, uuid: String) -> Result<(), String> {
+    let deleted = state
+        .with_db_async({
+            let uuid = uuid.clone();
+            move |db| db.delete_project_template(&uuid)
+        }).await
+        .map_err(|e| e.to_string())?;
+    if !deleted {
+        return Err(format!("Template {} not found", uuid));
+    }
+
+    state
+        .log_activity(NewActivity::new("delete", "project_template", Some(&uuid)))
+        .await;
+    Ok(())
+}
+
+/// Creates a project from a template, offline; the project, its starter
+/// notebooks and dataset references are queued for sync.
+#[tauri::command]
+pub async fn create_project_from_template(
+    state: State<'_, AppState>,
+    template_uuid: String,
+    workspace_id: i64,
+    name: String,
+) -> Result<TemplatedProject, String> {
+    let owner_id = state.backend.lock()
+        .map_err(|e| format!("Failed to lock backend session: {}", e))?
+        .user_id;
+
+    let created = state
+        .with_db_async({
+            let template_uuid = template_uuid.clone();
+            move |db| db.create_project_from_template(&template_uuid, workspace_id, &name, owner_id)
+        }).await
+        .map_err(|e| e.to_string())?;
+
+    println!(
+        "[NOVEM] Created project '{}' from template {} ({} notebooks, {} datasets)",
+        created.project.name, template_uuid, created.notebooks, created.datasets
+    );
+    state
+        .log_activity(
+            NewActivity::new("create", "project", Some(&created.project.uuid))
+                .before(serde_json::json!({ "template_uuid": template_uuid }))
+                .after(&created.project.name),
+        )
+        .await;
+    Ok(created)
+}
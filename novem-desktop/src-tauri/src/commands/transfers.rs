@@ -0,0 +1,91 @@
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+use crate::database::Transfer;
+use crate::i18n::tr;
+use crate::transfers::{self, DEFAULT_CHUNK_SIZE};
+use crate::AppState;
+
+#[tauri::command]
+pub async fn queue_artifact_upload(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    entity_type: String,
+    entity_uuid: String,
+    file_path: String,
+) -> Result<Transfer, String> {
+    let path = Path::new(&file_path);
+    let metadata = std::fs::metadata(path)
+        .map_err(|e| tr!("file-unreadable", path = file_path.as_str(), error = e.to_string()))?;
+
+    if !metadata.is_file() {
+        return Err(tr!("not-a-file", path = file_path.as_str()));
+    }
+
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| file_path.clone());
+
+    let uuid = uuid::Uuid::new_v4().to_string();
+
+    let transfer = state
+        .with_db_async(move |db| {
+            db.create_transfer(
+                &uuid,
+                &entity_type,
+                &entity_uuid,
+                &file_path,
+                &file_name,
+                metadata.len() as i64,
+                DEFAULT_CHUNK_SIZE,
+            )
+        }).await
+        .map_err(|e| e.to_string())?;
+
+    // Offline, the resume worker starts it once the backend is back
+    if state.connectivity.is_online() {
+        transfers::spawn_transfer(app, transfer.uuid.clone());
+    } else {
+        println!("[NOVEM] Offline; transfer {} queued", transfer.uuid);
+    }
+
+    Ok(transfer)
+}
+
+#[tauri::command]
+pub async fn list_transfers(
+    state: State<'_, AppState>,
+    status: Option<String>,
+) -> Result<Vec<Transfer>, String> {
+    state
+        .with_db_async(move |db| db.list_transfers(status.as_deref())).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn retry_transfer(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    uuid: String,
+) -> Result<bool, String> {
+    let requeued = state
+        .with_db_async({
+            let uuid = uuid.clone();
+            move |db| db.requeue_transfer(&uuid)
+        }).await
+        .map_err(|e| e.to_string())?;
+
+    if requeued && state.connectivity.is_online() {
+        transfers::spawn_transfer(app, uuid);
+    }
+
+    Ok(requeued)
+}
+
+#[tauri::command]
+pub async fn cancel_transfer(state: State<'_, AppState>, uuid: String) -> Result<bool, String> {
+    state
+        .with_db_async(move |db| db.cancel_transfer(&uuid)).await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,181 @@
+use std::path::PathBuf;
+use tauri::State;
+
+use crate::database::{DeleteImpact, NewActivity, PendingDelete, TrashEntity, TrashItem, TrashPurge};
+use crate::resources;
+use crate::AppState;
+
+/// What deleting `entity` would remove, for the confirmation dialog.
+#[tauri::command]
+pub async fn preview_delete(
+    state: State<'_, AppState>,
+    entity: TrashEntity,
+) -> Result<DeleteImpact, String> {
+    state
+        .with_db_async(move |db| db.preview_delete(&entity)).await
+        .map_err(|e| e.to_string())
+}
+
+/// Moves `entity` and everything under it to the trash, then stops the
+/// engines of any projects that went with it. With `permanent` it skips
+/// the trash and can't be restored.
+#[tauri::command]
+pub async fn delete_entity(
+    state: State<'_, AppState>,
+    entity: TrashEntity,
+    permanent: Option<bool>,
+) -> Result<DeleteImpact, String> {
+    let permanent = permanent.unwrap_or(false);
+    let (impact, purge) = state
+        .with_db_async(move |db| {
+            let impact = db.delete_entity(&entity)?;
+            let purge = if permanent { Some(db.purge_entity(&entity)?) } else { None };
+            Ok((impact, purge))
+        }).await
+        .map_err(|e| e.to_string())?;
+
+    println!(
+        "[NOVEM] Deleted {} {}: {} projects, {} notebooks, {} datasets, {} attachments",
+        impact.entity_type, impact.uuid, impact.projects, impact.notebooks, impact.datasets, impact.attachments
+    );
+
+    for project_id in &impact.project_ids {
+        if let Err(e) = state.engines.stop_project(*project_id) {
+            eprintln!("[ERROR] Failed to stop engine for deleted project {}: {}", project_id, e);
+            continue;
+        }
+        if let Err(e) = state.engines.remove_work_dir(*project_id) {
+            eprintln!("[WARNING] {:#}", e);
+        }
+    }
+
+    let action = if purge.is_some() { "purge" } else { "delete" };
+    if let Some(purge) = purge {
+        remove_purged_files(&state, &purge).await;
+    }
+    state
+        .log_activity(NewActivity::new(action, &impact.entity_type, Some(&impact.uuid)).before(serde_json::json!({
+            "name": impact.name,
+            "projects": impact.projects,
+            "notebooks": impact.notebooks,
+            "datasets": impact.datasets,
+            "attachments": impact.attachments,
+        })))
+        .await;
+    Ok(impact)
+}
+
+/// Moves a workspace to the trash with its projects and everything in
+/// them; see `delete_entity`.
+#[tauri::command]
+pub async fn delete_workspace(
+    state: State<'_, AppState>,
+    uuid: String,
+    permanent: Option<bool>,
+) -> Result<DeleteImpact, String> {
+    delete_entity(state, TrashEntity::Workspace(uuid), permanent).await
+}
+
+#[tauri::command]
+pub async fn list_trash(state: State<'_, AppState>) -> Result<Vec<TrashItem>, String> {
+    state
+        .with_db_async(|db| db.list_trash()).await
+        .map_err(|e| e.to_string())
+}
+
+/// Takes `entity` out of the trash along with everything deleted with it.
+#[tauri::command]
+pub async fn restore_entity(state: State<'_, AppState>, entity: TrashEntity) -> Result<TrashItem, String> {
+    let item = state
+        .with_db_async(move |db| db.restore_entity(&entity)).await
+        .map_err(|e| e.to_string())?;
+
+    println!("[NOVEM] Restored '{}' from the trash", item.name);
+    state
+        .log_activity(NewActivity::new("restore", item.entity.entity_type(), Some(item.entity.uuid())).after(&item.name))
+        .await;
+    Ok(item)
+}
+
+/// Permanently removes what has been in the trash longer than `older_than`
+/// ("30m", "6h", "30d"), or everything when omitted, with its local files.
+#[tauri::command]
+pub async fn purge_trash(state: State<'_, AppState>, older_than: Option<String>) -> Result<TrashPurge, String> {
+    let age = match older_than {
+        Some(older_than) => resources::parse_duration(&older_than).map_err(|e| e.to_string())?,
+        None => 0,
+    };
+    let cutoff = crate::timestamps::format(chrono::Utc::now() - chrono::Duration::seconds(age));
+
+    let purge = state
+        .with_db_async(move |db| db.purge_trash(&cutoff)).await
+        .map_err(|e| e.to_string())?;
+
+    remove_purged_files(&state, &purge).await;
+    println!(
+        "[NOVEM] Emptied trash: {} entities, {} rows, {} dataset files, {} attachments",
+        purge.entities, purge.rows_removed, purge.dataset_files.len(), purge.attachment_files.len()
+    );
+    state
+        .log_activity(NewActivity::new("purge", "trash", None).after(serde_json::json!({
+            "older_than_secs": age,
+            "entities": purge.entities,
+        })))
+        .await;
+    Ok(purge)
+}
+
+/// Deletes still to be pushed to the backend, with what each took along.
+#[tauri::command]
+pub async fn get_pending_deletes(state: State<'_, AppState>) -> Result<Vec<PendingDelete>, String> {
+    state
+        .with_db_async(|db| db.pending_deletes()).await
+        .map_err(|e| e.to_string())
+}
+
+/// Called by the sync push once the backend has applied a delete.
+#[tauri::command]
+pub async fn acknowledge_delete(
+    state: State<'_, AppState>,
+    entity_type: String,
+    uuid: String,
+) -> Result<bool, String> {
+    let acknowledged = state
+        .with_db_async({
+            let uuid = uuid.clone();
+            move |db| db.acknowledge_delete(&entity_type, &uuid)
+        }).await
+        .map_err(|e| e.to_string())?;
+
+    if acknowledged {
+        println!("[NOVEM] Backend acknowledged delete of {}", uuid);
+    }
+    Ok(acknowledged)
+}
+
+/// Deletes the purged datasets' and attachments' files. Registered files
+/// outside managed storage belong to the user and are left alone.
+async fn remove_purged_files(state: &AppState, purge: &TrashPurge) {
+    let data_dir = state.data_dir();
+    let files: Vec<PathBuf> = purge
+        .dataset_files
+        .iter()
+        .chain(&purge.attachment_files)
+        .map(PathBuf::from)
+        .filter(|path| path.starts_with(&data_dir))
+        .collect();
+
+    let removed = tauri::async_runtime::spawn_blocking(move || {
+        for path in files {
+            if let Err(e) = std::fs::remove_file(&path) {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    eprintln!("[WARNING] Failed to remove purged file {:?}: {}", path, e);
+                }
+            }
+        }
+    })
+    .await;
+    if let Err(e) = removed {
+        eprintln!("[ERROR] Failed to remove purged files: {}", e);
+    }
+}
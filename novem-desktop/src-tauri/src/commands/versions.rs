@@ -0,0 +1,102 @@
+use tauri::{AppHandle, State};
+
+use crate::database::{Conflict, ConflictChoice, NewActivity, ServerCopyResult, VersionCheck};
+use crate::{settings, sync, AppState};
+
+/// Compares a workspace, project or dataset with the version the backend
+/// reports, before the sync applies or pushes either copy. Concurrent
+/// edits mark the row as a conflict.
+#[tauri::command]
+pub async fn check_server_version(
+    state: State<'_, AppState>,
+    entity_type: String,
+    uuid: String,
+    server_version: i64,
+) -> Result<VersionCheck, String> {
+    let check = state
+        .with_db_async({
+            let (entity_type, uuid) = (entity_type.clone(), uuid.clone());
+            move |db| db.check_server_version(&entity_type, &uuid, server_version)
+        }).await
+        .map_err(|e| e.to_string())?;
+
+    if check == VersionCheck::Conflict {
+        eprintln!(
+            "[WARNING] Sync conflict on {} {}: edited here and on the server (v{})",
+            entity_type, uuid, server_version
+        );
+    }
+    Ok(check)
+}
+
+/// Called by the sync once the backend holds `version` of a row.
+#[tauri::command]
+pub async fn mark_version_synced(
+    state: State<'_, AppState>,
+    entity_type: String,
+    uuid: String,
+    version: i64,
+) -> Result<bool, String> {
+    state
+        .with_db_async(move |db| db.mark_version_synced(&entity_type, &uuid, version)).await
+        .map_err(|e| e.to_string())
+}
+
+/// Offers the backend's copy of a row to the local one during sync. When
+/// both changed, the conflict is settled by the strategy configured for
+/// the entity type, or kept for `resolve_conflict`.
+#[tauri::command]
+pub async fn apply_server_copy(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    entity_type: String,
+    uuid: String,
+    server_version: i64,
+    data: serde_json::Value,
+) -> Result<ServerCopyResult, String> {
+    let result = state
+        .with_db_async(move |db| {
+            let strategy = settings::conflict_strategy(db, &entity_type)?;
+            db.apply_server_copy(&entity_type, &uuid, server_version, &data, strategy)
+        }).await
+        .map_err(|e| e.to_string())?;
+
+    if let Some(conflict) = &result.conflict {
+        sync::report_conflict(&app, conflict).await;
+    }
+    Ok(result)
+}
+
+/// Open sync conflicts, oldest first; with `include_resolved` also the
+/// settled ones.
+#[tauri::command]
+pub async fn list_conflicts(
+    state: State<'_, AppState>,
+    include_resolved: Option<bool>,
+) -> Result<Vec<Conflict>, String> {
+    state
+        .with_db_async(move |db| db.list_conflicts(include_resolved.unwrap_or(false))).await
+        .map_err(|e| e.to_string())
+}
+
+/// Settles a conflict by keeping the local copy, which is pushed again, or
+/// taking the backend's.
+#[tauri::command]
+pub async fn resolve_conflict(
+    state: State<'_, AppState>,
+    id: i64,
+    choice: ConflictChoice,
+) -> Result<Conflict, String> {
+    let conflict = state
+        .with_db_async(move |db| db.resolve_conflict(id, choice)).await
+        .map_err(|e| e.to_string())?;
+
+    state
+        .log_activity(
+            NewActivity::new("resolve_conflict", &conflict.entity_type, Some(&conflict.entity_uuid))
+                .before(&conflict.local_data)
+                .after(serde_json::json!({ "choice": choice, "server": conflict.server_data })),
+        )
+        .await;
+    Ok(conflict)
+}
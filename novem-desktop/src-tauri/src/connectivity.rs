@@ -0,0 +1,143 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::Notify;
+
+use crate::events::{self, AppEvent};
+use crate::{timestamps, AppState};
+
+/// How often the backend is probed while it answers.
+const PROBE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Probed more often while offline, so coming back is noticed quickly.
+const OFFLINE_PROBE_INTERVAL: Duration = Duration::from_secs(10);
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Network changes come in bursts, e.g. an interface going down with all
+/// its addresses; the probe waits for them to settle.
+const NETWORK_SETTLE: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectivityStatus {
+    /// The backend answered the last probe
+    pub online: bool,
+    /// When `online` last changed, or the app started
+    pub since: String,
+    pub checked_at: Option<String>,
+}
+
+/// Whether the backend can be reached, kept current by `start_monitor`.
+/// Starts out online, so nothing waits on the first probe.
+pub struct Connectivity {
+    status: Mutex<ConnectivityStatus>,
+    recheck: Notify,
+}
+
+impl Connectivity {
+    pub fn new() -> Self {
+        Self {
+            status: Mutex::new(ConnectivityStatus { online: true, since: timestamps::now(), checked_at: None }),
+            recheck: Notify::new(),
+        }
+    }
+
+    pub fn is_online(&self) -> bool {
+        self.status.lock().unwrap().online
+    }
+
+    pub fn status(&self) -> ConnectivityStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    /// Probes again now, e.g. after a request to the backend failed to
+    /// connect.
+    pub fn recheck(&self) {
+        self.recheck.notify_one();
+    }
+
+    /// Records a probe, returning the new status if it changed.
+    fn record(&self, online: bool) -> Option<ConnectivityStatus> {
+        let mut status = self.status.lock().unwrap();
+        let now = timestamps::now();
+        status.checked_at = Some(now.clone());
+        if status.online == online {
+            return None;
+        }
+        status.online = online;
+        status.since = now;
+        Some(status.clone())
+    }
+}
+
+/// Whether the backend answers its health check within a few seconds.
+pub async fn probe_backend(state: &AppState) -> bool {
+    let url = match state.backend.lock() {
+        Ok(session) => session.url("/api/health/"),
+        Err(_) => return false,
+    };
+
+    matches!(
+        state.http.backend().get(url).timeout(PROBE_TIMEOUT).send().await,
+        Ok(response) if response.status().is_success()
+    )
+}
+
+/// Background loop probing the backend on an interval and whenever the
+/// OS reports a network change, publishing `ConnectivityChanged` when the
+/// answer flips. Nothing is probed in guest mode.
+pub fn start_monitor(app: AppHandle) {
+    watch_network(app.clone());
+
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+
+        loop {
+            if !state.mode.is_guest() {
+                let online = probe_backend(&state).await;
+                if let Some(status) = state.connectivity.record(online) {
+                    if status.online {
+                        println!("[NOVEM] Backend reachable again");
+                    } else {
+                        eprintln!("[WARNING] Backend unreachable; working offline");
+                    }
+                    events::publish(&app, AppEvent::ConnectivityChanged(status));
+                }
+            }
+
+            let interval = if state.connectivity.is_online() { PROBE_INTERVAL } else { OFFLINE_PROBE_INTERVAL };
+            tokio::select! {
+                _ = tokio::time::sleep(interval) => {}
+                _ = state.connectivity.recheck.notified() => {}
+            }
+        }
+    });
+}
+
+/// Asks for a probe whenever an interface gains or loses an address. Where
+/// the OS can't report that, periodic probes still notice, only later.
+fn watch_network(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut watcher = match if_watch::tokio::IfWatcher::new() {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                eprintln!("[WARNING] Network changes won't be detected: {}", e);
+                return;
+            }
+        };
+
+        loop {
+            match std::future::poll_fn(|cx| watcher.poll_if_event(cx)).await {
+                Ok(_) => {
+                    tokio::time::sleep(NETWORK_SETTLE).await;
+                    app.state::<AppState>().connectivity.recheck();
+                }
+                Err(e) => {
+                    eprintln!("[WARNING] Stopped watching network changes: {}", e);
+                    break;
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,293 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+use crate::database::{Dashboard, LocalDatabase, NotebookCell};
+
+/// Cells carrying this tag have their outputs published to the dashboard.
+pub const DASHBOARD_TAG: &str = "dashboard";
+
+const DEFAULT_COLUMNS: u32 = 12;
+const DEFAULT_WIDGET_HEIGHT: u32 = 4;
+
+const CHART_MIME_TYPES: &[&str] = &[
+    "application/vnd.plotly.v1+json",
+    "application/vnd.vegalite.v5+json",
+    "application/vnd.vegalite.v4+json",
+    "image/svg+xml",
+    "image/png",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayoutSpec {
+    #[serde(default = "default_columns")]
+    pub columns: u32,
+    #[serde(default)]
+    pub items: Vec<LayoutItem>,
+}
+
+fn default_columns() -> u32 {
+    DEFAULT_COLUMNS
+}
+
+impl Default for LayoutSpec {
+    fn default() -> Self {
+        Self {
+            columns: DEFAULT_COLUMNS,
+            items: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayoutItem {
+    pub cell_uuid: String,
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WidgetKind {
+    Chart,
+    Table,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DashboardWidget {
+    pub cell_uuid: String,
+    pub kind: WidgetKind,
+    pub mime_type: String,
+    pub data: Value,
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DashboardDefinition {
+    pub notebook_uuid: String,
+    pub columns: u32,
+    pub widgets: Vec<DashboardWidget>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublishedDashboard {
+    pub dashboard: Dashboard,
+    pub render_path: String,
+}
+
+/// Picks the renderable output of a cell, preferring the most recent
+/// chart over a table.
+fn extract_output(outputs: &Value) -> Option<(WidgetKind, String, Value)> {
+    let outputs = outputs.as_array()?;
+
+    for output in outputs.iter().rev() {
+        let data = match output.get("data").and_then(Value::as_object) {
+            Some(data) => data,
+            None => continue,
+        };
+
+        for mime in CHART_MIME_TYPES {
+            if let Some(value) = data.get(*mime) {
+                return Some((WidgetKind::Chart, mime.to_string(), value.clone()));
+            }
+        }
+
+        if let Some(value) = data.get("application/vnd.dataresource+json") {
+            return Some((WidgetKind::Table, "application/vnd.dataresource+json".to_string(), value.clone()));
+        }
+
+        if let Some(html) = data.get("text/html") {
+            let is_table = match html {
+                Value::String(text) => text.contains("<table"),
+                Value::Array(lines) => lines.iter().any(|l| l.as_str().is_some_and(|l| l.contains("<table"))),
+                _ => false,
+            };
+            if is_table {
+                return Some((WidgetKind::Table, "text/html".to_string(), html.clone()));
+            }
+        }
+    }
+
+    None
+}
+
+fn is_tagged(cell: &NotebookCell) -> bool {
+    serde_json::from_str::<Vec<String>>(&cell.tags)
+        .map(|tags| tags.iter().any(|tag| tag == DASHBOARD_TAG))
+        .unwrap_or(false)
+}
+
+/// Builds a dashboard from the tagged chart/table outputs of a notebook.
+/// Cells without an explicit layout item are placed two per row.
+pub fn build_definition(notebook_uuid: &str, cells: &[NotebookCell], layout: &LayoutSpec) -> DashboardDefinition {
+    let columns = layout.columns.max(1);
+    let auto_width = (columns / 2).max(1);
+    let mut next_auto_slot = 0u32;
+
+    // Auto-placed widgets go below anything positioned explicitly
+    let auto_origin = layout.items.iter().map(|item| item.y + item.h).max().unwrap_or(0);
+
+    let mut widgets = Vec::new();
+
+    for cell in cells.iter().filter(|cell| is_tagged(cell)) {
+        let outputs: Value = serde_json::from_str(&cell.outputs).unwrap_or(Value::Null);
+        let (kind, mime_type, data) = match extract_output(&outputs) {
+            Some(output) => output,
+            None => continue,
+        };
+
+        let (x, y, w, h) = match layout.items.iter().find(|item| item.cell_uuid == cell.uuid) {
+            Some(item) => (item.x, item.y, item.w, item.h),
+            None => {
+                let slot = next_auto_slot;
+                next_auto_slot += 1;
+                let per_row = (columns / auto_width).max(1);
+                (
+                    (slot % per_row) * auto_width,
+                    auto_origin + (slot / per_row) * DEFAULT_WIDGET_HEIGHT,
+                    auto_width,
+                    DEFAULT_WIDGET_HEIGHT,
+                )
+            }
+        };
+
+        widgets.push(DashboardWidget {
+            cell_uuid: cell.uuid.clone(),
+            kind,
+            mime_type,
+            data,
+            x,
+            y,
+            w,
+            h,
+        });
+    }
+
+    DashboardDefinition {
+        notebook_uuid: notebook_uuid.to_string(),
+        columns,
+        widgets,
+    }
+}
+
+/// Local file the frontend loads to render a published dashboard.
+pub fn render_path(artifacts_dir: &Path, dashboard_uuid: &str) -> PathBuf {
+    artifacts_dir.join("dashboards").join(format!("{}.json", dashboard_uuid))
+}
+
+/// Publishes (or re-publishes) the dashboard for a notebook: stores the
+/// definition, writes the render artifact and queues it for sync.
+/// Without a layout, the previously published layout is reused.
+pub fn publish(
+    db: &LocalDatabase,
+    artifacts_dir: &Path,
+    notebook_uuid: &str,
+    layout: Option<LayoutSpec>,
+) -> Result<PublishedDashboard> {
+    let notebook = db
+        .get_notebook_by_uuid(notebook_uuid)?
+        .ok_or_else(|| anyhow::anyhow!("Notebook {} not found", notebook_uuid))?;
+
+    let existing = db.get_dashboard_for_notebook(notebook_uuid)?;
+
+    let layout = match (layout, &existing) {
+        (Some(layout), _) => layout,
+        (None, Some(dashboard)) => serde_json::from_str(&dashboard.layout_spec).unwrap_or_default(),
+        (None, None) => LayoutSpec::default(),
+    };
+
+    let cells = db.get_notebook_cells(notebook.id)?;
+    let definition = build_definition(notebook_uuid, &cells, &layout);
+
+    if definition.widgets.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Notebook has no cells tagged '{}' with chart or table output",
+            DASHBOARD_TAG
+        ));
+    }
+
+    let uuid = existing
+        .as_ref()
+        .map(|dashboard| dashboard.uuid.clone())
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+    let definition_json = serde_json::to_string(&definition)?;
+    let dashboard = db.save_dashboard(
+        &uuid,
+        notebook_uuid,
+        &notebook.name,
+        &serde_json::to_string(&layout)?,
+        &definition_json,
+    )?;
+
+    let path = render_path(artifacts_dir, &dashboard.uuid);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).context("Failed to create dashboard artifact directory")?;
+    }
+    std::fs::write(&path, &definition_json).context(format!("Failed to write {:?}", path))?;
+
+    let payload = serde_json::json!({
+        "uuid": dashboard.uuid,
+        "notebook_uuid": dashboard.notebook_uuid,
+        "name": dashboard.name,
+        "version": dashboard.version,
+        "definition": definition,
+    });
+    let action = if dashboard.version == 1 { "create" } else { "update" };
+    db.add_to_sync_queue("dashboard", &dashboard.uuid, action, &payload.to_string())?;
+
+    println!(
+        "[NOVEM] Published dashboard {} (v{}, {} widgets)",
+        dashboard.uuid,
+        dashboard.version,
+        definition.widgets.len()
+    );
+
+    Ok(PublishedDashboard {
+        dashboard,
+        render_path: path.to_string_lossy().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(uuid: &str, tags: &str, outputs: &str) -> NotebookCell {
+        NotebookCell {
+            id: 0,
+            uuid: uuid.to_string(),
+            notebook_id: 1,
+            position: 0,
+            cell_type: "code".to_string(),
+            source: String::new(),
+            outputs: outputs.to_string(),
+            tags: tags.to_string(),
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_build_definition_uses_tagged_outputs() {
+        let cells = vec![
+            cell("chart", r#"["dashboard"]"#, r#"[{"output_type": "display_data", "data": {"image/png": "iVBOR"}}]"#),
+            cell("untagged", "[]", r#"[{"output_type": "display_data", "data": {"image/png": "iVBOR"}}]"#),
+            cell("table", r#"["dashboard"]"#, r#"[{"output_type": "execute_result", "data": {"text/html": "<table></table>"}}]"#),
+            cell("text", r#"["dashboard"]"#, r#"[{"output_type": "stream", "text": "hello"}]"#),
+        ];
+
+        let definition = build_definition("nb", &cells, &LayoutSpec::default());
+
+        assert_eq!(definition.widgets.len(), 2);
+        assert_eq!(definition.widgets[0].kind, WidgetKind::Chart);
+        assert_eq!(definition.widgets[1].kind, WidgetKind::Table);
+        assert_eq!((definition.widgets[1].x, definition.widgets[1].y), (6, 0));
+    }
+}
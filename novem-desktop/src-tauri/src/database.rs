@@ -1,7 +1,100 @@
 use anyhow::{Context, Result};
-use rusqlite::{params, Connection, OptionalExtension};
+use rand::Rng;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+use crate::timestamps;
+
+mod activity;
+mod attachments;
+mod bulk;
+mod clones;
+mod comments;
+mod conflicts;
+mod connections;
+mod dashboards;
+mod deprovision;
+mod datasets;
+mod encryption;
+mod favorites;
+mod engine_env;
+mod engine_metrics;
+mod engine_profiles;
+mod executions;
+mod jobs;
+mod journal;
+mod maintenance;
+mod members;
+mod migrations;
+mod notebooks;
+mod notifications;
+mod paging;
+mod pool;
+mod portable;
+mod pragmas;
+mod query_history;
+mod recent;
+mod recipes;
+mod refresh;
+mod result_cache;
+mod resources;
+mod schedules;
+mod search;
+mod secrets;
+mod settings;
+mod share_links;
+mod templates;
+mod transfers;
+mod transactions;
+mod trash;
+mod versions;
+mod workspaces;
+
+pub use activity::{ActivityFilter, ActivityPage, NewActivity, ACTIVITY_RETENTION_SETTING};
+pub use attachments::{Attachment, NewAttachment};
+pub use bulk::BulkResult;
+pub use clones::{CloneOptions, ClonedProject, DatasetCloneMode};
+pub use comments::{Comment, CommentTarget};
+pub use conflicts::{Conflict, ConflictChoice, ConflictStrategy, ServerCopyResult};
+pub use connections::{DataConnection, NewDataConnection};
+pub use dashboards::Dashboard;
+pub use deprovision::LocalFootprint;
+pub use encryption::DatabaseKey;
+pub use favorites::FavoriteEntity;
+pub use datasets::{ColumnStatsRecord, Dataset, DatasetColumn, DatasetLineage, NewDataset};
+pub use engine_metrics::{EngineMetric, EngineMetricPoint};
+pub use engine_profiles::{EngineProfile, NewEngineProfile};
+pub use executions::{Execution, NewExecution};
+pub use jobs::Job;
+pub use journal::JournalEntry;
+pub use maintenance::{DbMaintenanceReport, TableUsage};
+pub use members::{NewWorkspaceMember, WorkspaceMember, WorkspaceRole};
+pub use notebooks::{CellUpdate, NewCell, Notebook, NotebookCell};
+pub use notifications::{NewNotification, Notification, NotificationPriority};
+use paging::Cursor;
+pub use paging::{Page, PageRequest, SortDirection};
+pub use pool::DatabasePool;
+pub use portable::{ImportConflictPolicy, ImportReport, LocalDataBundle, LocalDataCounts};
+use pool::PooledConnection;
+pub use pragmas::DbPragmaInfo;
+pub use query_history::{NewQueryHistory, QueryHistoryEntry, QueryHistoryFilter};
+pub use recent::{RecentEntity, RecentItem};
+pub use recipes::DatasetRecipe;
+pub use refresh::DatasetRefresh;
+pub use result_cache::ResultCacheStats;
+pub use resources::{ResourcePoint, ResourceSample};
+pub use schedules::ScheduledTask;
+pub use search::{SearchEntityType, SearchFilter, SearchResult};
+pub use secrets::SecretInfo;
+pub use share_links::ShareLink;
+pub use templates::{ProjectTemplate, TemplatedProject};
+pub use transfers::Transfer;
+pub use trash::{DeleteImpact, PendingDelete, TrashEntity, TrashItem, TrashPurge};
+pub use versions::{is_versioned, VersionCheck};
+pub use workspaces::WorkspaceActivity;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Workspace {
@@ -15,6 +108,12 @@ pub struct Workspace {
     pub is_active: bool,
     pub sync_status: String, // 'synced', 'pending', 'conflict'
     pub last_synced_at: Option<String>,
+    /// Kept on top of lists; local to this device
+    #[serde(default)]
+    pub is_pinned: bool,
+    /// Bumped on every local edit; see `check_server_version`
+    #[serde(default)]
+    pub version: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,6 +129,59 @@ pub struct Project {
     pub is_active: bool,
     pub sync_status: String,
     pub last_synced_at: Option<String>,
+    #[serde(default)]
+    pub is_pinned: bool,
+    #[serde(default)]
+    pub version: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectSort {
+    Name,
+    CreatedAt,
+    #[default]
+    UpdatedAt,
+}
+
+impl ProjectSort {
+    fn column(self) -> &'static str {
+        match self {
+            ProjectSort::Name => "name",
+            ProjectSort::CreatedAt => "created_at",
+            ProjectSort::UpdatedAt => "updated_at",
+        }
+    }
+
+    /// What rows are ordered by; names ignore case.
+    fn expression(self) -> &'static str {
+        match self {
+            ProjectSort::Name => "name COLLATE NOCASE",
+            _ => self.column(),
+        }
+    }
+
+    fn value(self, project: &Project) -> &str {
+        match self {
+            ProjectSort::Name => &project.name,
+            ProjectSort::CreatedAt => &project.created_at,
+            ProjectSort::UpdatedAt => &project.updated_at,
+        }
+    }
+}
+
+/// How `get_projects` sorts and filters. Most recently updated first by
+/// default.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ProjectQuery {
+    pub sort: ProjectSort,
+    pub direction: SortDirection,
+    /// Case-insensitive substring of the name
+    pub name: Option<String>,
+    pub sync_status: Option<String>,
+    /// Whose projects to list; the caller's own when omitted
+    pub owner_id: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,6 +197,25 @@ pub struct User {
     pub created_at: String,
 }
 
+/// Attempts after which a sync item stops being retried.
+pub const MAX_SYNC_RETRIES: i64 = 10;
+/// Status of items that ran out of retries; only requeued by hand.
+pub const SYNC_FAILED_PERMANENT: &str = "failed_permanent";
+/// Wait before retrying an item that failed once; doubles with each
+/// further failure up to `SYNC_RETRY_MAX_DELAY_SECS`.
+pub const SYNC_RETRY_BASE_DELAY_SECS: i64 = 5;
+pub const SYNC_RETRY_MAX_DELAY_SECS: i64 = 3600;
+
+/// Seconds to wait after an item's `retry_count`th failure. Jittered so
+/// items that failed together, like a whole batch, don't retry together;
+/// never less than half the exponential delay, so retries still back off.
+fn sync_retry_delay(retry_count: i64) -> i64 {
+    let doublings = (retry_count - 1).clamp(0, 20) as u32;
+    let ceiling = (SYNC_RETRY_BASE_DELAY_SECS << doublings).min(SYNC_RETRY_MAX_DELAY_SECS);
+    let floor = ceiling / 2;
+    rand::thread_rng().gen_range(floor..=ceiling)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyncQueue {
     pub id: i64,
@@ -52,23 +223,115 @@ pub struct SyncQueue {
     pub entity_uuid: String,
     pub action: String, // 'create', 'update', 'delete'
     pub payload: String, // JSON
-    pub status: String, // 'pending', 'processing', 'completed', 'failed'
+    pub status: String, // 'pending', 'processing', 'completed', 'failed', 'failed_permanent'
     pub retry_count: i64,
     pub created_at: String,
     pub updated_at: String,
     pub error_message: Option<String>,
+    /// Not pushed before this time after a failure; `None` when due now
+    pub next_attempt_at: Option<String>,
+    /// For updates, the fields `payload` carries; `None` when it carries
+    /// the whole entity
+    pub changed_fields: Option<Vec<String>>,
+}
+
+fn sync_item_from_row(row: &rusqlite::Row) -> rusqlite::Result<SyncQueue> {
+    Ok(SyncQueue {
+        id: row.get(0)?,
+        entity_type: row.get(1)?,
+        entity_uuid: row.get(2)?,
+        action: row.get(3)?,
+        payload: row.get(4)?,
+        status: row.get(5)?,
+        retry_count: row.get(6)?,
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+        error_message: row.get(9)?,
+        next_attempt_at: row.get(10)?,
+        // Read as empty, a corrupt list would push the delta as if nothing changed
+        changed_fields: row
+            .get::<_, Option<String>>(11)?
+            .map(|fields| serde_json::from_str(&fields))
+            .transpose()
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(11, rusqlite::types::Type::Text, Box::new(e)))?,
+    })
+}
+
+/// Top-level fields of `after` that differ from `before`, with their new
+/// values; a field `after` dropped comes out as null.
+fn changed_fields(before: &serde_json::Value, after: &serde_json::Value) -> serde_json::Map<String, serde_json::Value> {
+    let empty = serde_json::Map::new();
+    let before = before.as_object().unwrap_or(&empty);
+    let after = after.as_object().unwrap_or(&empty);
+
+    let mut changed: serde_json::Map<_, _> = after
+        .iter()
+        .filter(|(field, value)| before.get(*field) != Some(*value))
+        .map(|(field, value)| (field.clone(), value.clone()))
+        .collect();
+    for field in before.keys().filter(|field| !after.contains_key(*field)) {
+        changed.insert(field.clone(), serde_json::Value::Null);
+    }
+    changed
+}
+
+/// Lays `later` over `earlier`, field by field when both are objects.
+fn overlay(earlier: &mut serde_json::Value, later: serde_json::Value) {
+    match (earlier.as_object_mut(), later) {
+        (Some(fields), serde_json::Value::Object(later)) => fields.extend(later),
+        (_, later) => *earlier = later,
+    }
 }
 
 pub struct LocalDatabase {
-    conn: Connection,
+    conn: DbConnection,
+}
+
+/// A connection of its own, or one borrowed from a `DatabasePool` and
+/// returned when the `LocalDatabase` is dropped.
+enum DbConnection {
+    Owned(Connection),
+    Pooled(PooledConnection),
+}
+
+impl Deref for DbConnection {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        match self {
+            DbConnection::Owned(conn) => conn,
+            DbConnection::Pooled(conn) => conn,
+        }
+    }
+}
+
+/// Opens, unlocks and configures one connection.
+fn open_connection(db_path: &Path, key: Option<&DatabaseKey>) -> rusqlite::Result<Connection> {
+    let conn = Connection::open(db_path)?;
+    if let Some(key) = key {
+        encryption::unlock(&conn, key)?;
+    }
+    // A wrong key only shows once a page is read
+    conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |row| row.get::<_, i64>(0))?;
+    pragmas::configure(&conn)?;
+    Ok(conn)
 }
 
 impl LocalDatabase {
-    pub fn new(db_path: PathBuf) -> Result<Self> {
-        let conn = Connection::open(&db_path)
+    /// Opens the database, encrypted with `key` if one is given, and brings
+    /// its schema up to date; an existing unencrypted file is encrypted
+    /// first.
+    pub fn new(db_path: PathBuf, key: Option<&DatabaseKey>) -> Result<Self> {
+        if let Some(key) = key {
+            encryption::encrypt_plaintext(&db_path, key)
+                .context(format!("Failed to encrypt database at {:?}", db_path))?;
+        }
+
+        let conn = open_connection(&db_path, key)
             .context(format!("Failed to open database at {:?}", db_path))?;
 
-        let db = LocalDatabase { conn };
+        let db = LocalDatabase { conn: DbConnection::Owned(conn) };
+        db.migrate()?;
         db.initialize_schema()?;
         
         Ok(db)
@@ -86,7 +349,7 @@ impl LocalDatabase {
                 last_name TEXT,
                 is_active BOOLEAN NOT NULL DEFAULT 1,
                 last_login TEXT,
-                created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+                created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
             )",
             [],
         )?;
@@ -99,11 +362,15 @@ impl LocalDatabase {
                 name TEXT NOT NULL,
                 description TEXT,
                 owner_id INTEGER NOT NULL,
-                created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
-                updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
                 is_active BOOLEAN NOT NULL DEFAULT 1,
+                deleted_at TEXT,
+                is_pinned BOOLEAN NOT NULL DEFAULT 0,
                 sync_status TEXT NOT NULL DEFAULT 'pending',
                 last_synced_at TEXT,
+                version INTEGER NOT NULL DEFAULT 1,
+                server_version INTEGER,
                 FOREIGN KEY (owner_id) REFERENCES users(id)
             )",
             [],
@@ -118,11 +385,15 @@ impl LocalDatabase {
                 name TEXT NOT NULL,
                 description TEXT,
                 owner_id INTEGER NOT NULL,
-                created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
-                updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
                 is_active BOOLEAN NOT NULL DEFAULT 1,
+                deleted_at TEXT,
+                is_pinned BOOLEAN NOT NULL DEFAULT 0,
                 sync_status TEXT NOT NULL DEFAULT 'pending',
                 last_synced_at TEXT,
+                version INTEGER NOT NULL DEFAULT 1,
+                server_version INTEGER,
                 FOREIGN KEY (workspace_id) REFERENCES workspaces(id),
                 FOREIGN KEY (owner_id) REFERENCES users(id)
             )",
@@ -139,9 +410,614 @@ impl LocalDatabase {
                 payload TEXT NOT NULL,
                 status TEXT NOT NULL DEFAULT 'pending',
                 retry_count INTEGER NOT NULL DEFAULT 0,
-                created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
-                updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
-                error_message TEXT
+                created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                error_message TEXT,
+                next_attempt_at TEXT,
+                changed_fields TEXT
+            )",
+            [],
+        )?;
+
+        // Transfers table (resumable artifact uploads)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS transfers (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                uuid TEXT NOT NULL UNIQUE,
+                entity_type TEXT NOT NULL,
+                entity_uuid TEXT NOT NULL,
+                file_path TEXT NOT NULL,
+                file_name TEXT NOT NULL,
+                total_bytes INTEGER NOT NULL,
+                bytes_sent INTEGER NOT NULL DEFAULT 0,
+                chunk_index INTEGER NOT NULL DEFAULT 0,
+                chunk_size INTEGER NOT NULL,
+                status TEXT NOT NULL DEFAULT 'pending',
+                retry_count INTEGER NOT NULL DEFAULT 0,
+                error_message TEXT,
+                created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+            )",
+            [],
+        )?;
+
+        // Notebooks table
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS notebooks (
+                id INTEGER PRIMARY KEY,
+                uuid TEXT NOT NULL UNIQUE,
+                project_id INTEGER NOT NULL,
+                name TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                is_active BOOLEAN NOT NULL DEFAULT 1,
+                sync_status TEXT NOT NULL DEFAULT 'pending',
+                last_synced_at TEXT,
+                FOREIGN KEY (project_id) REFERENCES projects(id)
+            )",
+            [],
+        )?;
+
+        // Notebook cells table
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS notebook_cells (
+                id INTEGER PRIMARY KEY,
+                uuid TEXT NOT NULL UNIQUE,
+                notebook_id INTEGER NOT NULL,
+                position INTEGER NOT NULL,
+                cell_type TEXT NOT NULL DEFAULT 'code',
+                source TEXT NOT NULL DEFAULT '',
+                outputs TEXT NOT NULL DEFAULT '[]',
+                tags TEXT NOT NULL DEFAULT '[]',
+                created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                FOREIGN KEY (notebook_id) REFERENCES notebooks(id)
+            )",
+            [],
+        )?;
+
+        // Dashboards table (published from notebooks)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS dashboards (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                uuid TEXT NOT NULL UNIQUE,
+                notebook_uuid TEXT NOT NULL UNIQUE,
+                name TEXT NOT NULL,
+                layout_spec TEXT NOT NULL,
+                definition TEXT NOT NULL,
+                version INTEGER NOT NULL DEFAULT 1,
+                auto_republish BOOLEAN NOT NULL DEFAULT 1,
+                published_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                sync_status TEXT NOT NULL DEFAULT 'pending'
+            )",
+            [],
+        )?;
+
+        // Datasets table (local data files registered to a project)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS datasets (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                uuid TEXT NOT NULL UNIQUE,
+                project_id INTEGER NOT NULL,
+                name TEXT NOT NULL,
+                file_path TEXT NOT NULL,
+                format TEXT NOT NULL,
+                row_count INTEGER,
+                size_bytes INTEGER NOT NULL DEFAULT 0,
+                parent_uuid TEXT,
+                default_sample_uuid TEXT,
+                created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                is_active BOOLEAN NOT NULL DEFAULT 1,
+                deleted_at TEXT,
+                sync_status TEXT NOT NULL DEFAULT 'pending',
+                last_synced_at TEXT,
+                version INTEGER NOT NULL DEFAULT 1,
+                server_version INTEGER,
+                FOREIGN KEY (project_id) REFERENCES projects(id)
+            )",
+            [],
+        )?;
+
+        // Dataset lineage table (how derived datasets were produced)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS dataset_lineage (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                dataset_uuid TEXT NOT NULL,
+                parent_uuid TEXT NOT NULL,
+                operation TEXT NOT NULL,
+                params TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+            )",
+            [],
+        )?;
+
+        // Column statistics table (incrementally maintained sketches per column)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS column_stats (
+                dataset_uuid TEXT NOT NULL,
+                column_name TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                sketch TEXT NOT NULL,
+                hll BLOB NOT NULL,
+                updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                PRIMARY KEY (dataset_uuid, column_name)
+            )",
+            [],
+        )?;
+
+        // Dataset columns table (schema summary for column pickers, from the sketches)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS dataset_columns (
+                dataset_uuid TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                name TEXT NOT NULL,
+                dtype TEXT NOT NULL,
+                null_count INTEGER NOT NULL DEFAULT 0,
+                min_value TEXT,
+                max_value TEXT,
+                cardinality INTEGER NOT NULL DEFAULT 0,
+                updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                PRIMARY KEY (dataset_uuid, position)
+            )",
+            [],
+        )?;
+
+        // Engine environment table (NULL workspace_id = applies to all engines)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS engine_env (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                workspace_id INTEGER,
+                name TEXT NOT NULL,
+                value TEXT NOT NULL,
+                updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                FOREIGN KEY (workspace_id) REFERENCES workspaces(id)
+            )",
+            [],
+        )?;
+
+        // Query history table (SQL and transforms run through the desktop app)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS query_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                kind TEXT NOT NULL,
+                query_text TEXT NOT NULL,
+                target TEXT,
+                project_id INTEGER,
+                params TEXT NOT NULL DEFAULT '{}',
+                duration_ms INTEGER NOT NULL,
+                row_count INTEGER,
+                status TEXT NOT NULL,
+                error_message TEXT,
+                is_favorite BOOLEAN NOT NULL DEFAULT 0,
+                executed_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+            )",
+            [],
+        )?;
+
+        // Resource samples table (ring buffer, downsampled by age; resolution 0 = raw)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS resource_samples (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                resolution INTEGER NOT NULL,
+                sampled_at INTEGER NOT NULL,
+                cpu_percent REAL NOT NULL,
+                cpu_peak REAL NOT NULL,
+                memory_percent REAL NOT NULL,
+                memory_used_gb REAL NOT NULL,
+                engine_cpu_percent REAL,
+                engine_memory_mb REAL,
+                sample_count INTEGER NOT NULL DEFAULT 1
+            )",
+            [],
+        )?;
+
+        // Engine metrics table (CPU/memory of each engine's process tree; project_id NULL = shared engine)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS engine_metrics (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                project_id INTEGER,
+                pid INTEGER NOT NULL,
+                sampled_at INTEGER NOT NULL,
+                cpu_percent REAL NOT NULL,
+                memory_mb REAL NOT NULL,
+                process_count INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        // Settings table (app preferences as key/value pairs)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS settings (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL,
+                updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+            )",
+            [],
+        )?;
+
+        // Entity lineage table (projects and notebooks cloned from others)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS entity_lineage (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                entity_type TEXT NOT NULL,
+                entity_uuid TEXT NOT NULL,
+                source_uuid TEXT NOT NULL,
+                operation TEXT NOT NULL,
+                params TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+            )",
+            [],
+        )?;
+
+        // Project tags table
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS project_tags (
+                project_id INTEGER NOT NULL,
+                tag TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                PRIMARY KEY (project_id, tag),
+                FOREIGN KEY (project_id) REFERENCES projects(id)
+            )",
+            [],
+        )?;
+
+        // Attachments table (files copied into app data for a project)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS attachments (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                uuid TEXT UNIQUE NOT NULL,
+                project_id INTEGER NOT NULL,
+                name TEXT NOT NULL,
+                file_path TEXT NOT NULL,
+                checksum TEXT NOT NULL,
+                mime_type TEXT NOT NULL,
+                size_bytes INTEGER NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                deleted_at TEXT,
+                FOREIGN KEY (project_id) REFERENCES projects(id)
+            )",
+            [],
+        )?;
+
+        // Archived workspaces table (hidden from the default listing, otherwise untouched)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS archived_workspaces (
+                workspace_id INTEGER PRIMARY KEY,
+                archived_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                FOREIGN KEY (workspace_id) REFERENCES workspaces(id)
+            )",
+            [],
+        )?;
+
+        // Project templates table (starting points for new projects)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS project_templates (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                uuid TEXT UNIQUE NOT NULL,
+                name TEXT NOT NULL,
+                source_project_uuid TEXT,
+                spec TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+            )",
+            [],
+        )?;
+
+        // Archived projects table (hidden from listings, otherwise untouched)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS archived_projects (
+                project_id INTEGER PRIMARY KEY,
+                archived_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                FOREIGN KEY (project_id) REFERENCES projects(id)
+            )",
+            [],
+        )?;
+
+        // Tombstones table (deleted entities, kept for restore and until the backend acknowledges the delete)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS tombstones (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                entity_type TEXT NOT NULL,
+                entity_uuid TEXT NOT NULL,
+                root_type TEXT NOT NULL,
+                root_uuid TEXT NOT NULL,
+                deleted_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                synced_at TEXT,
+                purged BOOLEAN NOT NULL DEFAULT 0,
+                UNIQUE (entity_type, entity_uuid)
+            )",
+            [],
+        )?;
+
+        // Activity log table (mutating actions, for the activity feed and audits)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS activity_log (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                actor_id INTEGER,
+                action TEXT NOT NULL,
+                entity_type TEXT NOT NULL,
+                entity_uuid TEXT,
+                old_value TEXT,
+                new_value TEXT,
+                created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+            )",
+            [],
+        )?;
+
+        // Recent items table (what was opened last, for the home screen)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS recent_items (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                entity_type TEXT NOT NULL,
+                entity_uuid TEXT NOT NULL,
+                opened_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                open_count INTEGER NOT NULL DEFAULT 1,
+                UNIQUE (entity_type, entity_uuid)
+            )",
+            [],
+        )?;
+
+        // Review comments on projects and notebook cells, kept offline
+        // and synced through sync_queue
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS comments (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                uuid TEXT UNIQUE NOT NULL,
+                entity_type TEXT NOT NULL,
+                entity_uuid TEXT NOT NULL,
+                author_id INTEGER,
+                body TEXT NOT NULL,
+                resolved BOOLEAN NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                sync_status TEXT NOT NULL DEFAULT 'pending'
+            )",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_comments_entity ON comments(entity_type, entity_uuid)",
+            [],
+        )?;
+
+        // Conflicts table (rows edited here and on the backend, with both copies as JSON)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS conflicts (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                entity_type TEXT NOT NULL,
+                entity_uuid TEXT NOT NULL,
+                local_version INTEGER NOT NULL,
+                server_version INTEGER NOT NULL,
+                local_data TEXT NOT NULL,
+                server_data TEXT NOT NULL,
+                strategy TEXT NOT NULL,
+                detected_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                resolved_at TEXT,
+                resolution TEXT
+            )",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_conflicts_entity ON conflicts(entity_uuid)",
+            [],
+        )?;
+
+        // Operation journal table (multi-step operations, for crash recovery)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS operation_journal (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                kind TEXT NOT NULL,
+                step TEXT NOT NULL,
+                state TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'running',
+                started_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+            )",
+            [],
+        )?;
+
+        // Engine profiles table (named interpreter/args/env sets to launch engines with)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS engine_profiles (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE,
+                python_path TEXT,
+                extra_args TEXT NOT NULL DEFAULT '[]',
+                env TEXT NOT NULL DEFAULT '{}',
+                working_dir TEXT,
+                created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+            )",
+            [],
+        )?;
+
+        // Dataset recipes table (declarative transforms stored per dataset)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS dataset_recipes (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                uuid TEXT NOT NULL UNIQUE,
+                dataset_uuid TEXT NOT NULL,
+                name TEXT NOT NULL,
+                steps TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                UNIQUE(dataset_uuid, name)
+            )",
+            [],
+        )?;
+
+        // Dataset refresh schedules table (source to re-import from, interval, last outcome)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS dataset_refresh_schedules (
+                dataset_uuid TEXT PRIMARY KEY,
+                source TEXT NOT NULL,
+                interval_secs INTEGER NOT NULL,
+                next_run_at TEXT NOT NULL,
+                last_started_at TEXT,
+                last_success_at TEXT,
+                last_error TEXT,
+                consecutive_failures INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+            )",
+            [],
+        )?;
+
+        // Jobs table (long-running engine computations, for the jobs panel)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS jobs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                uuid TEXT NOT NULL UNIQUE,
+                project_id INTEGER,
+                kind TEXT NOT NULL,
+                params TEXT NOT NULL DEFAULT '{}',
+                status TEXT NOT NULL DEFAULT 'queued',
+                progress REAL NOT NULL DEFAULT 0,
+                result_ref TEXT,
+                error TEXT,
+                created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                started_at TEXT,
+                finished_at TEXT,
+                FOREIGN KEY (project_id) REFERENCES projects(id)
+            )",
+            [],
+        )?;
+
+        // Executions table (cell run history, for reproducibility audits)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS executions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                uuid TEXT NOT NULL UNIQUE,
+                notebook_uuid TEXT NOT NULL,
+                cell_uuid TEXT NOT NULL,
+                source TEXT NOT NULL,
+                status TEXT NOT NULL,
+                duration_ms INTEGER NOT NULL DEFAULT 0,
+                stdout_ref TEXT,
+                stderr_ref TEXT,
+                engine_version TEXT,
+                started_at TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+            )",
+            [],
+        )?;
+
+        // Result cache table (compressed engine responses, expired by TTL and size)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS result_cache (
+                key TEXT PRIMARY KEY,
+                endpoint TEXT NOT NULL,
+                data BLOB NOT NULL,
+                size_bytes INTEGER NOT NULL,
+                hits INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                last_used_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                expires_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Secrets table (data source credentials, sealed with the vault key)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS secrets (
+                name TEXT PRIMARY KEY,
+                nonce BLOB NOT NULL,
+                ciphertext BLOB NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+            )",
+            [],
+        )?;
+
+        // Connections table (external data sources; credentials stay in the secrets table)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS connections (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                uuid TEXT NOT NULL UNIQUE,
+                workspace_id INTEGER,
+                name TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                host TEXT,
+                port INTEGER,
+                database TEXT,
+                username TEXT,
+                secret_name TEXT,
+                options TEXT NOT NULL DEFAULT '{}',
+                created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                last_tested_at TEXT,
+                last_test_ok INTEGER,
+                last_test_message TEXT,
+                FOREIGN KEY (workspace_id) REFERENCES workspaces(id)
+            )",
+            [],
+        )?;
+
+        // Notifications table (local inbox fed by sync, jobs and the engine supervisor)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS notifications (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                kind TEXT NOT NULL,
+                priority TEXT NOT NULL DEFAULT 'normal',
+                title TEXT NOT NULL,
+                body TEXT,
+                entity_type TEXT,
+                entity_uuid TEXT,
+                is_read INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+            )",
+            [],
+        )?;
+
+        // Schedules table (cron-driven tasks: sync, dataset refresh, saved queries)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS schedules (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                uuid TEXT NOT NULL UNIQUE,
+                name TEXT NOT NULL,
+                cron TEXT NOT NULL,
+                action TEXT NOT NULL,
+                payload TEXT NOT NULL DEFAULT '{}',
+                enabled INTEGER NOT NULL DEFAULT 1,
+                last_run_at TEXT,
+                last_error TEXT,
+                next_run_at TEXT,
+                created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+            )",
+            [],
+        )?;
+
+        // Share links table (tokens granting access to an entity; active once synced)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS share_links (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                uuid TEXT NOT NULL UNIQUE,
+                entity_type TEXT NOT NULL,
+                entity_uuid TEXT NOT NULL,
+                token TEXT NOT NULL UNIQUE,
+                permission TEXT NOT NULL DEFAULT 'view',
+                expires_at TEXT,
+                created_by INTEGER,
+                created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                revoked_at TEXT,
+                sync_status TEXT NOT NULL DEFAULT 'pending'
+            )",
+            [],
+        )?;
+
+        // Workspace members table (cached from the backend for offline role checks)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS workspace_members (
+                workspace_id INTEGER NOT NULL,
+                user_id INTEGER NOT NULL,
+                role TEXT NOT NULL DEFAULT 'member',
+                invited_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                status TEXT NOT NULL DEFAULT 'active',
+                PRIMARY KEY (workspace_id, user_id),
+                FOREIGN KEY (workspace_id) REFERENCES workspaces(id)
             )",
             [],
         )?;
@@ -162,11 +1038,110 @@ impl LocalDatabase {
             [],
         )?;
 
+        // Keyset pagination walks these in order
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_workspaces_owner_listing ON workspaces(owner_id, is_pinned, updated_at, id)",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_projects_workspace_listing ON projects(workspace_id, is_pinned, updated_at, id)",
+            [],
+        )?;
+
         self.conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_sync_queue_status ON sync_queue(status)",
             [],
         )?;
 
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_transfers_status ON transfers(status)",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_jobs_project ON jobs(project_id, created_at)",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_executions_notebook ON executions(notebook_uuid, started_at)",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_notifications_unread ON notifications(is_read, created_at)",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_schedules_due ON schedules(enabled, next_run_at)",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_share_links_entity ON share_links(entity_type, entity_uuid)",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_notebooks_project ON notebooks(project_id)",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_notebook_cells_notebook ON notebook_cells(notebook_id, position)",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_datasets_project ON datasets(project_id)",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_query_history_executed ON query_history(executed_at)",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_resource_samples_time ON resource_samples(resolution, sampled_at)",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_engine_metrics_time ON engine_metrics(project_id, sampled_at)",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_operation_journal_status ON operation_journal(status)",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_tombstones_root ON tombstones(root_uuid)",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_dataset_lineage_dataset ON dataset_lineage(dataset_uuid)",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_activity_log_entity ON activity_log(entity_uuid)",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_activity_log_created ON activity_log(created_at)",
+            [],
+        )?;
+
+        self.initialize_search_index()?;
+        self.conn.execute_batch(versions::VERSION_TRIGGERS)?;
+
         Ok(())
     }
 
@@ -190,8 +1165,8 @@ impl LocalDatabase {
                 &user.first_name,
                 &user.last_name,
                 user.is_active,
-                &user.last_login,
-                &user.created_at,
+                timestamps::normalize_opt(user.last_login.as_deref()),
+                timestamps::normalize(&user.created_at),
             ],
         )?;
         Ok(())
@@ -221,17 +1196,44 @@ impl LocalDatabase {
     }
 
     // Workspace operations
-    pub fn get_workspaces(&self, user_id: i64) -> Result<Vec<Workspace>> {
-        let mut stmt = self.conn.prepare(
+    /// One page of the user's workspaces: pinned ones first, then most
+    /// recently updated.
+    /// Open workspaces, or only archived ones when `archived` is set.
+    pub fn get_workspaces(&self, user_id: i64, archived: bool, page: &PageRequest) -> Result<Page<Workspace>> {
+        let in_archive = if archived { "IN" } else { "NOT IN" };
+        let total = self.conn.query_row(
+            &format!(
+                "SELECT COUNT(*) FROM workspaces WHERE owner_id = ?1 AND is_active = 1
+                   AND id {} (SELECT workspace_id FROM archived_workspaces)",
+                in_archive
+            ),
+            params![user_id],
+            |row| row.get(0),
+        )?;
+
+        let limit = page.limit();
+        let cursor = page.cursor("updated_at")?;
+        let after_cursor = match &cursor {
+            Some(cursor) => format!("AND {}", cursor.condition("updated_at", SortDirection::Desc, 3)),
+            None => String::new(),
+        };
+        let mut stmt = self.conn.prepare(&format!(
             "SELECT id, uuid, name, description, owner_id, created_at, updated_at, 
-                    is_active, sync_status, last_synced_at
+                    is_active, sync_status, last_synced_at, is_pinned, version
              FROM workspaces 
              WHERE owner_id = ?1 AND is_active = 1
-             ORDER BY updated_at DESC"
-        )?;
+               AND id {} (SELECT workspace_id FROM archived_workspaces) {}
+             ORDER BY is_pinned DESC, updated_at DESC, id DESC
+             LIMIT ?2",
+            in_archive, after_cursor
+        ))?;
 
+        let mut values: Vec<SqlValue> = vec![user_id.into(), (limit + 1).into()];
+        if let Some(cursor) = cursor {
+            values.extend(cursor.values());
+        }
         let workspaces = stmt
-            .query_map(params![user_id], |row| {
+            .query_map(params_from_iter(values), |row| {
                 Ok(Workspace {
                     id: row.get(0)?,
                     uuid: row.get(1)?,
@@ -243,15 +1245,30 @@ impl LocalDatabase {
                     is_active: row.get(7)?,
                     sync_status: row.get(8)?,
                     last_synced_at: row.get(9)?,
+                    is_pinned: row.get(10)?,
+                    version: row.get(11)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
 
-        Ok(workspaces)
+        Ok(Page::from_rows(workspaces, limit, total, |workspace| Cursor {
+            sort: "updated_at".to_string(),
+            pinned: workspace.is_pinned,
+            value: workspace.updated_at.clone(),
+            id: workspace.id,
+        }))
     }
 
     pub fn upsert_workspace(&self, workspace: &Workspace) -> Result<()> {
-        self.conn.execute(
+        self.upsert_workspaces(std::slice::from_ref(workspace))?;
+        Ok(())
+    }
+
+    /// Inserts or updates many workspaces in one transaction, e.g. for a
+    /// full sync pull. Returns how many were written.
+    pub fn upsert_workspaces(&self, workspaces: &[Workspace]) -> Result<usize> {
+        let tx = self.begin()?;
+        let mut stmt = tx.prepare(
             "INSERT INTO workspaces (id, uuid, name, description, owner_id, created_at, updated_at, is_active, sync_status, last_synced_at)
              VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
              ON CONFLICT(uuid) DO UPDATE SET
@@ -261,55 +1278,104 @@ impl LocalDatabase {
                 is_active = excluded.is_active,
                 sync_status = excluded.sync_status,
                 last_synced_at = excluded.last_synced_at",
-            params![
+        )?;
+
+        for workspace in workspaces {
+            stmt.execute(params![
                 workspace.id,
                 &workspace.uuid,
                 &workspace.name,
                 &workspace.description,
                 workspace.owner_id,
-                &workspace.created_at,
-                &workspace.updated_at,
+                timestamps::normalize(&workspace.created_at),
+                timestamps::normalize(&workspace.updated_at),
                 workspace.is_active,
                 &workspace.sync_status,
-                &workspace.last_synced_at,
-            ],
-        )?;
-        Ok(())
+                timestamps::normalize_opt(workspace.last_synced_at.as_deref()),
+            ])?;
+        }
+        drop(stmt);
+
+        tx.commit()?;
+        Ok(workspaces.len())
     }
 
     // Project operations
-    pub fn get_projects(&self, workspace_id: i64, user_id: i64) -> Result<Vec<Project>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, uuid, workspace_id, name, description, owner_id, 
-                    created_at, updated_at, is_active, sync_status, last_synced_at
-             FROM projects 
-             WHERE workspace_id = ?1 AND owner_id = ?2 AND is_active = 1
-             ORDER BY updated_at DESC"
+    /// One page of a workspace's projects, pinned ones first, then sorted
+    /// and filtered per `query`. Archived projects are left out.
+    pub fn get_projects(
+        &self,
+        workspace_id: i64,
+        user_id: i64,
+        query: &ProjectQuery,
+        page: &PageRequest,
+    ) -> Result<Page<Project>> {
+        let mut values: Vec<SqlValue> = vec![
+            SqlValue::Integer(workspace_id),
+            SqlValue::Integer(query.owner_id.unwrap_or(user_id)),
+        ];
+        let mut conditions = vec![
+            "workspace_id = ?1".to_string(),
+            "owner_id = ?2".to_string(),
+            "is_active = 1".to_string(),
+            "id NOT IN (SELECT project_id FROM archived_projects)".to_string(),
+        ];
+
+        if let Some(name) = &query.name {
+            values.push(SqlValue::Text(name.clone()));
+            conditions.push(format!("instr(lower(name), lower(?{})) > 0", values.len()));
+        }
+        if let Some(sync_status) = &query.sync_status {
+            values.push(SqlValue::Text(sync_status.clone()));
+            conditions.push(format!("sync_status = ?{}", values.len()));
+        }
+
+        let total = self.conn.query_row(
+            &format!("SELECT COUNT(*) FROM projects WHERE {}", conditions.join(" AND ")),
+            params_from_iter(&values),
+            |row| row.get(0),
         )?;
 
+        let sort = query.sort.column();
+        if let Some(cursor) = page.cursor(sort)? {
+            conditions.push(cursor.condition(query.sort.expression(), query.direction, values.len() + 1));
+            values.extend(cursor.values());
+        }
+
+        let limit = page.limit();
+        values.push(SqlValue::Integer(limit + 1));
+        let sql = format!(
+            "SELECT {} FROM projects WHERE {} ORDER BY is_pinned DESC, {} {dir}, id {dir} LIMIT ?{}",
+            clones::PROJECT_COLUMNS,
+            conditions.join(" AND "),
+            query.sort.expression(),
+            values.len(),
+            dir = query.direction.sql(),
+        );
+
+        let mut stmt = self.conn.prepare(&sql)?;
         let projects = stmt
-            .query_map(params![workspace_id, user_id], |row| {
-                Ok(Project {
-                    id: row.get(0)?,
-                    uuid: row.get(1)?,
-                    workspace_id: row.get(2)?,
-                    name: row.get(3)?,
-                    description: row.get(4)?,
-                    owner_id: row.get(5)?,
-                    created_at: row.get(6)?,
-                    updated_at: row.get(7)?,
-                    is_active: row.get(8)?,
-                    sync_status: row.get(9)?,
-                    last_synced_at: row.get(10)?,
-                })
-            })?
+            .query_map(params_from_iter(values), clones::project_from_row)?
             .collect::<Result<Vec<_>, _>>()?;
 
-        Ok(projects)
+        Ok(Page::from_rows(projects, limit, total, |project| Cursor {
+            sort: sort.to_string(),
+            pinned: project.is_pinned,
+            value: query.sort.value(project).to_string(),
+            id: project.id,
+        }))
     }
 
     pub fn upsert_project(&self, project: &Project) -> Result<()> {
-        self.conn.execute(
+        self.upsert_projects(std::slice::from_ref(project))?;
+        Ok(())
+    }
+
+    /// Inserts or updates many projects in one transaction, like
+    /// `upsert_workspaces`. Returns how many were written.
+    pub fn upsert_projects(&self, projects: &[Project]) -> Result<usize> {
+        let tx = self.begin()?;
+        let mut stmt = tx.prepare(
             "INSERT INTO projects (id, uuid, workspace_id, name, description, owner_id, created_at, updated_at, is_active, sync_status, last_synced_at)
              VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
              ON CONFLICT(uuid) DO UPDATE SET
@@ -319,21 +1385,27 @@ impl LocalDatabase {
                 is_active = excluded.is_active,
                 sync_status = excluded.sync_status,
                 last_synced_at = excluded.last_synced_at",
-            params![
+        )?;
+
+        for project in projects {
+            stmt.execute(params![
                 project.id,
                 &project.uuid,
                 project.workspace_id,
                 &project.name,
                 &project.description,
                 project.owner_id,
-                &project.created_at,
-                &project.updated_at,
+                timestamps::normalize(&project.created_at),
+                timestamps::normalize(&project.updated_at),
                 project.is_active,
                 &project.sync_status,
-                &project.last_synced_at,
-            ],
-        )?;
-        Ok(())
+                timestamps::normalize_opt(project.last_synced_at.as_deref()),
+            ])?;
+        }
+        drop(stmt);
+
+        tx.commit()?;
+        Ok(projects.len())
     }
 
     // Sync queue operations
@@ -346,71 +1418,231 @@ impl LocalDatabase {
         Ok(())
     }
 
-    pub fn get_pending_sync_items(&self) -> Result<Vec<SyncQueue>> {
+    /// Queues an update carrying only the fields that differ between the
+    /// entity's `before` and `after` payloads. Returns `false`, queueing
+    /// nothing, if none do.
+    pub fn queue_update(
+        &self,
+        entity_type: &str,
+        entity_uuid: &str,
+        before: &serde_json::Value,
+        after: &serde_json::Value,
+    ) -> Result<bool> {
+        let changed = changed_fields(before, after);
+        if changed.is_empty() {
+            return Ok(false);
+        }
+        self.queue_delta(entity_type, entity_uuid, serde_json::Value::Object(changed))?;
+        Ok(true)
+    }
+
+    /// Queues an update of just the fields in `fields`.
+    pub fn queue_delta(&self, entity_type: &str, entity_uuid: &str, fields: serde_json::Value) -> Result<()> {
+        let names: Vec<&String> = fields.as_object().map(|fields| fields.keys().collect()).unwrap_or_default();
+        self.conn.execute(
+            "INSERT INTO sync_queue (entity_type, entity_uuid, action, payload, status, changed_fields)
+             VALUES (?1, ?2, 'update', ?3, 'pending', ?4)",
+            params![entity_type, entity_uuid, fields.to_string(), serde_json::to_string(&names)?],
+        )?;
+        Ok(())
+    }
+
+    /// Folds each entity's pending creates and updates into its oldest
+    /// one, so a push sends one delta per entity however often it was
+    /// edited. The merged item keeps the oldest one's retry state, keeping
+    /// the edits in order. Entities with a pending delete or another
+    /// action are left as they are. Returns how many items were folded
+    /// away.
+    pub fn coalesce_sync_queue(&self) -> Result<usize> {
+        let tx = self.begin()?;
+        let mut stmt = self.conn.prepare(
+            "SELECT id, entity_type, entity_uuid, action, payload, changed_fields
+             FROM sync_queue
+             WHERE status = 'pending'
+               AND (entity_type, entity_uuid) IN (
+                   SELECT entity_type, entity_uuid FROM sync_queue
+                   WHERE status = 'pending'
+                   GROUP BY entity_type, entity_uuid
+                   HAVING COUNT(*) > 1 AND SUM(action NOT IN ('create', 'update')) = 0
+               )
+             ORDER BY entity_type, entity_uuid, id",
+        )?;
+        let items = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    (row.get::<_, String>(1)?, row.get::<_, String>(2)?),
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                    row.get::<_, Option<String>>(5)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        drop(stmt);
+
+        let mut folded = 0;
+        let mut items = items.into_iter().peekable();
+        while let Some((id, entity, action, payload, fields)) = items.next() {
+            let mut payload: serde_json::Value = serde_json::from_str(&payload)?;
+            // A create carries the whole entity, so it stays whole
+            let mut fields: Option<Vec<String>> = match action.as_str() {
+                "create" => None,
+                _ => fields.map(|fields| serde_json::from_str(&fields)).transpose()?,
+            };
+
+            while let Some((later_id, _, _, later_payload, later_fields)) = items.next_if(|item| item.1 == entity) {
+                overlay(&mut payload, serde_json::from_str(&later_payload)?);
+                fields = match (fields, later_fields) {
+                    (Some(mut fields), Some(later)) => {
+                        for field in serde_json::from_str::<Vec<String>>(&later)? {
+                            if !fields.contains(&field) {
+                                fields.push(field);
+                            }
+                        }
+                        Some(fields)
+                    }
+                    _ => None,
+                };
+                self.conn.execute("DELETE FROM sync_queue WHERE id = ?1", params![later_id])?;
+                folded += 1;
+            }
+
+            self.conn.execute(
+                "UPDATE sync_queue SET payload = ?2, changed_fields = ?3, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+                 WHERE id = ?1",
+                params![id, payload.to_string(), fields.map(|fields| serde_json::to_string(&fields)).transpose()?],
+            )?;
+        }
+        tx.commit()?;
+
+        Ok(folded)
+    }
+
+    /// Items with `status`, oldest first; with `due_only`, leaving out those
+    /// still backing off from a failure.
+    fn get_sync_items(&self, status: &str, due_only: bool, limit: i64) -> Result<Vec<SyncQueue>> {
         let mut stmt = self.conn.prepare(
             "SELECT id, entity_type, entity_uuid, action, payload, status, retry_count, 
-                    created_at, updated_at, error_message
+                    created_at, updated_at, error_message, next_attempt_at, changed_fields
              FROM sync_queue 
-             WHERE status = 'pending'
+             WHERE status = ?1
+               AND (NOT ?2 OR next_attempt_at IS NULL OR next_attempt_at <= strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
              ORDER BY created_at ASC
-             LIMIT 100"
+             LIMIT ?3"
         )?;
 
         let items = stmt
-            .query_map([], |row| {
-                Ok(SyncQueue {
-                    id: row.get(0)?,
-                    entity_type: row.get(1)?,
-                    entity_uuid: row.get(2)?,
-                    action: row.get(3)?,
-                    payload: row.get(4)?,
-                    status: row.get(5)?,
-                    retry_count: row.get(6)?,
-                    created_at: row.get(7)?,
-                    updated_at: row.get(8)?,
-                    error_message: row.get(9)?,
-                })
-            })?
+            .query_map(params![status, due_only, limit], sync_item_from_row)?
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(items)
     }
 
+    pub fn get_sync_item(&self, id: i64) -> Result<Option<SyncQueue>> {
+        let item = self
+            .conn
+            .query_row(
+                "SELECT id, entity_type, entity_uuid, action, payload, status, retry_count,
+                        created_at, updated_at, error_message, next_attempt_at, changed_fields
+                 FROM sync_queue WHERE id = ?1",
+                params![id],
+                sync_item_from_row,
+            )
+            .optional()?;
+
+        Ok(item)
+    }
+
+    /// Moves items that reached `MAX_SYNC_RETRIES` out of the queue.
+    fn dead_letter_exhausted_sync_items(&self) -> Result<()> {
+        let exhausted = self.conn.execute(
+            "UPDATE sync_queue SET status = ?1, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE status = 'pending' AND retry_count >= ?2",
+            params![SYNC_FAILED_PERMANENT, MAX_SYNC_RETRIES],
+        )?;
+        if exhausted > 0 {
+            eprintln!("[WARNING] {} sync items failed {} times and won't be retried", exhausted, MAX_SYNC_RETRIES);
+        }
+        Ok(())
+    }
+
+    /// The next items to push, leaving out those that ran out of retries
+    /// or aren't due for another attempt yet.
+    pub fn get_pending_sync_items(&self) -> Result<Vec<SyncQueue>> {
+        self.dead_letter_exhausted_sync_items()?;
+        self.get_sync_items("pending", true, 100)
+    }
+
+    /// Items that gave up after `MAX_SYNC_RETRIES` attempts, oldest first.
+    pub fn get_failed_sync_items(&self) -> Result<Vec<SyncQueue>> {
+        self.dead_letter_exhausted_sync_items()?;
+        self.get_sync_items(SYNC_FAILED_PERMANENT, false, i64::MAX)
+    }
+
+    /// Puts a permanently failed item back in the queue with its retries
+    /// reset. Returns `false` if there is no such failed item.
+    pub fn requeue_sync_item(&self, id: i64) -> Result<bool> {
+        let requeued = self.conn.execute(
+            "UPDATE sync_queue
+             SET status = 'pending', retry_count = 0, error_message = NULL, next_attempt_at = NULL,
+                 updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE id = ?1 AND status = ?2",
+            params![id, SYNC_FAILED_PERMANENT],
+        )?;
+        Ok(requeued > 0)
+    }
+
     pub fn update_sync_item_status(&self, id: i64, status: &str, error: Option<&str>) -> Result<()> {
         self.conn.execute(
             "UPDATE sync_queue 
-             SET status = ?1, error_message = ?2, updated_at = CURRENT_TIMESTAMP
+             SET status = ?1, error_message = ?2, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
              WHERE id = ?3",
             params![status, error, id],
         )?;
         Ok(())
     }
 
+    /// Counts a failed attempt and holds the item back for an exponentially
+    /// growing, jittered delay (see `sync_retry_delay`).
     pub fn increment_sync_retry(&self, id: i64) -> Result<()> {
+        let retry_count: Option<i64> = self
+            .conn
+            .query_row("SELECT retry_count FROM sync_queue WHERE id = ?1", params![id], |row| row.get(0))
+            .optional()?;
+        let Some(retry_count) = retry_count else {
+            return Ok(());
+        };
+
+        let delay = format!("+{} seconds", sync_retry_delay(retry_count + 1));
         self.conn.execute(
             "UPDATE sync_queue 
-             SET retry_count = retry_count + 1, updated_at = CURRENT_TIMESTAMP
+             SET retry_count = retry_count + 1,
+                 next_attempt_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now', ?2),
+                 updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
              WHERE id = ?1",
-            params![id],
+            params![id, delay],
         )?;
         Ok(())
     }
 
+    pub fn count_sync_items(&self, status: &str) -> Result<i64> {
+        let count = self.conn.query_row(
+            "SELECT COUNT(*) FROM sync_queue WHERE status = ?1",
+            params![status],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+
     pub fn clear_completed_sync_items(&self) -> Result<usize> {
         let count = self.conn.execute(
-            "DELETE FROM sync_queue WHERE status = 'completed' AND updated_at < datetime('now', '-7 days')",
+            "DELETE FROM sync_queue WHERE status = 'completed' AND updated_at < strftime('%Y-%m-%dT%H:%M:%SZ', 'now', '-7 days')",
             [],
         )?;
         Ok(count)
     }
 }
 
-impl Drop for LocalDatabase {
-    fn drop(&mut self) {
-        // Connection will be closed automatically when dropped
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -421,7 +1653,7 @@ mod tests {
         let temp_dir = std::env::temp_dir();
         let db_path = temp_dir.join("test_novem.db");
         
-        let db = LocalDatabase::new(db_path.clone()).unwrap();
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
         
         // Verify tables exist
         let table_count: i64 = db.conn
@@ -437,4 +1669,173 @@ mod tests {
         // Cleanup
         std::fs::remove_file(db_path).ok();
     }
+
+    #[test]
+    fn test_batch_upserts() {
+        let db_path = std::env::temp_dir().join("test_novem_batch_upserts.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+        db.conn
+            .execute("INSERT INTO users (id, uuid, email, username) VALUES (1, 'u1', 'a@example.com', 'ana')", [])
+            .unwrap();
+
+        let workspace = |id: i64| Workspace {
+            id,
+            uuid: format!("ws{}", id),
+            name: format!("Workspace {}", id),
+            description: None,
+            owner_id: 1,
+            created_at: "2025-01-01T00:00:00Z".to_string(),
+            updated_at: "2025-01-01T00:00:00Z".to_string(),
+            is_active: true,
+            sync_status: "synced".to_string(),
+            last_synced_at: None,
+            is_pinned: false,
+            version: 1,
+        };
+        let mut workspaces: Vec<Workspace> = (1..=300).map(workspace).collect();
+        assert_eq!(db.upsert_workspaces(&workspaces).unwrap(), 300);
+
+        workspaces[0].name = "Renamed".to_string();
+        let projects: Vec<Project> = (1..=3)
+            .map(|id| Project {
+                id,
+                uuid: format!("p{}", id),
+                workspace_id: 1,
+                name: format!("Project {}", id),
+                description: None,
+                owner_id: 1,
+                created_at: "2025-01-01T00:00:00Z".to_string(),
+                updated_at: "2025-01-01T00:00:00Z".to_string(),
+                is_active: true,
+                sync_status: "synced".to_string(),
+                last_synced_at: None,
+                is_pinned: false,
+                version: 1,
+            })
+            .collect();
+        db.upsert_workspaces(&workspaces[..1]).unwrap();
+        assert_eq!(db.upsert_projects(&projects).unwrap(), 3);
+
+        assert_eq!(db.get_workspace_by_uuid("ws1").unwrap().unwrap().name, "Renamed");
+        let count = |table: &str| -> i64 {
+            db.conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0)).unwrap()
+        };
+        assert_eq!((count("workspaces"), count("projects")), (300, 3));
+
+        // A row clashing on its ID rolls back the whole batch
+        let mut fresh = projects[0].clone();
+        fresh.uuid = "p-new".to_string();
+        fresh.id = 99;
+        let mut duplicate = projects[1].clone();
+        duplicate.uuid = "p-dup".to_string();
+        assert!(db.upsert_projects(&[fresh, duplicate]).is_err());
+        assert_eq!(count("projects"), 3);
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+
+    #[test]
+    fn test_exhausted_sync_items_are_dead_lettered() {
+        let db_path = std::env::temp_dir().join("test_novem_sync_retries.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        db.add_to_sync_queue("project", "p1", "update", "{}").unwrap();
+        let id = db.get_pending_sync_items().unwrap()[0].id;
+
+        for _ in 0..MAX_SYNC_RETRIES - 1 {
+            db.increment_sync_retry(id).unwrap();
+        }
+        assert!(db.get_pending_sync_items().unwrap().is_empty()); // backing off
+        assert_eq!(db.count_sync_items("pending").unwrap(), 1);
+        assert!(db.get_failed_sync_items().unwrap().is_empty());
+
+        db.increment_sync_retry(id).unwrap();
+        assert!(db.get_pending_sync_items().unwrap().is_empty());
+        let failed = db.get_failed_sync_items().unwrap();
+        assert_eq!((failed[0].id, failed[0].retry_count), (id, MAX_SYNC_RETRIES));
+
+        assert!(db.requeue_sync_item(id).unwrap());
+        assert!(!db.requeue_sync_item(id).unwrap());
+        assert_eq!(db.get_pending_sync_items().unwrap()[0].retry_count, 0);
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+
+    #[test]
+    fn test_sync_retries_back_off() {
+        let db_path = std::env::temp_dir().join("test_novem_sync_backoff.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        for retry_count in 1..=MAX_SYNC_RETRIES {
+            let ceiling = (SYNC_RETRY_BASE_DELAY_SECS << (retry_count - 1)).min(SYNC_RETRY_MAX_DELAY_SECS);
+            let delay = sync_retry_delay(retry_count);
+            assert!(delay >= ceiling / 2 && delay <= ceiling, "retry {}: {}s", retry_count, delay);
+        }
+        assert!(sync_retry_delay(1000) <= SYNC_RETRY_MAX_DELAY_SECS);
+
+        db.add_to_sync_queue("project", "p1", "update", "{}").unwrap();
+        let id = db.get_pending_sync_items().unwrap()[0].id;
+        db.increment_sync_retry(id).unwrap();
+
+        let item = db.get_sync_item(id).unwrap().unwrap();
+        let next = item.next_attempt_at.unwrap();
+        assert!(next > timestamps::now());
+        assert!(db.get_pending_sync_items().unwrap().is_empty());
+
+        // Due again once the delay has passed
+        db.conn
+            .execute("UPDATE sync_queue SET next_attempt_at = '2000-01-01T00:00:00Z' WHERE id = ?1", params![id])
+            .unwrap();
+        assert_eq!(db.get_pending_sync_items().unwrap()[0].retry_count, 1);
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+
+    #[test]
+    fn test_updates_coalesce_into_deltas() {
+        let db_path = std::env::temp_dir().join("test_novem_sync_deltas.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+        let cell = |source: &str, position: i64| serde_json::json!({ "source": source, "position": position, "tags": [] });
+
+        assert!(!db.queue_update("notebook_cell", "c1", &cell("a", 0), &cell("a", 0)).unwrap());
+        assert!(db.queue_update("notebook_cell", "c1", &cell("a", 0), &cell("b", 0)).unwrap());
+        assert!(db.queue_update("notebook_cell", "c1", &cell("b", 0), &cell("c", 2)).unwrap());
+        db.add_to_sync_queue("comment", "m1", "create", r#"{"body": "hi", "resolved": false}"#).unwrap();
+        db.queue_delta("comment", "m1", serde_json::json!({ "resolved": true })).unwrap();
+        db.queue_delta("workspace", "ws1", serde_json::json!({ "archived": true })).unwrap();
+        db.add_to_sync_queue("workspace", "ws1", "delete", "{}").unwrap();
+
+        assert_eq!(db.coalesce_sync_queue().unwrap(), 2);
+        let items = db.get_pending_sync_items().unwrap();
+        assert_eq!(items.len(), 4);
+
+        let cell = items.iter().find(|item| item.entity_uuid == "c1").unwrap();
+        assert_eq!(cell.action, "update");
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&cell.payload).unwrap(), serde_json::json!({ "source": "c", "position": 2 }));
+        assert_eq!(cell.changed_fields.as_deref(), Some(&["source".to_string(), "position".to_string()][..]));
+
+        let comment = items.iter().find(|item| item.entity_uuid == "m1").unwrap();
+        assert_eq!(comment.action, "create");
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&comment.payload).unwrap(),
+            serde_json::json!({ "body": "hi", "resolved": true })
+        );
+        assert!(comment.changed_fields.is_none());
+
+        // Nothing left to fold
+        assert_eq!(db.coalesce_sync_queue().unwrap(), 0);
+
+        db.conn.execute("UPDATE sync_queue SET changed_fields = 'source' WHERE id = ?1", params![cell.id]).unwrap();
+        assert!(db.get_sync_item(cell.id).is_err());
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
 }
\ No newline at end of file
@@ -0,0 +1,227 @@
+use anyhow::Result;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter, Row};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::LocalDatabase;
+
+/// Setting key holding how many days the activity log is kept.
+pub const ACTIVITY_RETENTION_SETTING: &str = "activity.retention_days";
+
+pub const DEFAULT_ACTIVITY_RETENTION_DAYS: i64 = 365;
+
+const DEFAULT_ACTIVITY_LIMIT: i64 = 50;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ActivityEntry {
+    pub id: i64,
+    /// Backend user ID of whoever was signed in, if anyone
+    pub actor_id: Option<i64>,
+    pub action: String, // 'create', 'update', 'delete', 'restore', ...
+    pub entity_type: String,
+    pub entity_uuid: Option<String>,
+    pub old_value: Option<Value>,
+    pub new_value: Option<Value>,
+    pub created_at: String,
+}
+
+/// One mutating action, as recorded by the command that performed it.
+#[derive(Debug, Clone)]
+pub struct NewActivity {
+    pub action: String,
+    pub entity_type: String,
+    pub entity_uuid: Option<String>,
+    pub old_value: Option<Value>,
+    pub new_value: Option<Value>,
+}
+
+impl NewActivity {
+    pub fn new(action: &str, entity_type: &str, entity_uuid: Option<&str>) -> Self {
+        Self {
+            action: action.to_string(),
+            entity_type: entity_type.to_string(),
+            entity_uuid: entity_uuid.map(str::to_string),
+            old_value: None,
+            new_value: None,
+        }
+    }
+
+    pub fn before(mut self, value: impl Serialize) -> Self {
+        self.old_value = serde_json::to_value(value).ok();
+        self
+    }
+
+    pub fn after(mut self, value: impl Serialize) -> Self {
+        self.new_value = serde_json::to_value(value).ok();
+        self
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ActivityFilter {
+    pub entity_type: Option<String>,
+    pub entity_uuid: Option<String>,
+    pub actor_id: Option<i64>,
+    /// Cursor from the previous page; entries older than this ID
+    pub before_id: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ActivityPage {
+    pub entries: Vec<ActivityEntry>,
+    /// Pass as `before_id` for the next page; `None` on the last one
+    pub next_cursor: Option<i64>,
+}
+
+fn json_column(row: &Row, index: usize) -> rusqlite::Result<Option<Value>> {
+    let text: Option<String> = row.get(index)?;
+    Ok(text.and_then(|text| serde_json::from_str(&text).ok()))
+}
+
+fn activity_from_row(row: &Row) -> rusqlite::Result<ActivityEntry> {
+    Ok(ActivityEntry {
+        id: row.get(0)?,
+        actor_id: row.get(1)?,
+        action: row.get(2)?,
+        entity_type: row.get(3)?,
+        entity_uuid: row.get(4)?,
+        old_value: json_column(row, 5)?,
+        new_value: json_column(row, 6)?,
+        created_at: row.get(7)?,
+    })
+}
+
+impl LocalDatabase {
+    // Activity log operations
+    pub fn log_activity(&self, actor_id: Option<i64>, activity: &NewActivity) -> Result<i64> {
+        self.conn.execute(
+            "INSERT INTO activity_log (actor_id, action, entity_type, entity_uuid, old_value, new_value)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                actor_id,
+                &activity.action,
+                &activity.entity_type,
+                &activity.entity_uuid,
+                activity.old_value.as_ref().map(Value::to_string),
+                activity.new_value.as_ref().map(Value::to_string),
+            ],
+        )?;
+
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Newest first, one page at a time.
+    pub fn get_activity_log(&self, filter: &ActivityFilter) -> Result<ActivityPage> {
+        let mut conditions = Vec::new();
+        let mut values: Vec<SqlValue> = Vec::new();
+
+        if let Some(entity_type) = &filter.entity_type {
+            values.push(SqlValue::Text(entity_type.clone()));
+            conditions.push(format!("entity_type = ?{}", values.len()));
+        }
+        if let Some(entity_uuid) = &filter.entity_uuid {
+            values.push(SqlValue::Text(entity_uuid.clone()));
+            conditions.push(format!("entity_uuid = ?{}", values.len()));
+        }
+        if let Some(actor_id) = filter.actor_id {
+            values.push(SqlValue::Integer(actor_id));
+            conditions.push(format!("actor_id = ?{}", values.len()));
+        }
+        if let Some(before_id) = filter.before_id {
+            values.push(SqlValue::Integer(before_id));
+            conditions.push(format!("id < ?{}", values.len()));
+        }
+
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+
+        // One extra row tells whether another page follows
+        let limit = filter.limit.unwrap_or(DEFAULT_ACTIVITY_LIMIT).max(1);
+        values.push(SqlValue::Integer(limit + 1));
+        let sql = format!(
+            "SELECT id, actor_id, action, entity_type, entity_uuid, old_value, new_value, created_at
+             FROM activity_log {} ORDER BY id DESC LIMIT ?{}",
+            where_clause,
+            values.len()
+        );
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let mut entries = stmt
+            .query_map(params_from_iter(values), activity_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let next_cursor = if entries.len() as i64 > limit {
+            entries.truncate(limit as usize);
+            entries.last().map(|entry| entry.id)
+        } else {
+            None
+        };
+
+        Ok(ActivityPage { entries, next_cursor })
+    }
+
+    /// Drops entries older than `retention_days`. Returns how many.
+    pub fn prune_activity_log(&self, retention_days: i64) -> Result<usize> {
+        let removed = self.conn.execute(
+            "DELETE FROM activity_log
+             WHERE created_at < strftime('%Y-%m-%dT%H:%M:%SZ', 'now', ?1)",
+            params![format!("-{} days", retention_days)],
+        )?;
+        Ok(removed)
+    }
+
+    pub fn activity_retention_days(&self) -> Result<i64> {
+        let days = self
+            .get_setting(ACTIVITY_RETENTION_SETTING)?
+            .and_then(|days| days.parse().ok())
+            .unwrap_or(DEFAULT_ACTIVITY_RETENTION_DAYS);
+        Ok(days)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_activity_log_pages_and_prunes() {
+        let db_path = std::env::temp_dir().join("test_novem_activity.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        for name in ["Draft", "Review", "Final"] {
+            let activity = NewActivity::new("update", "notebook", Some("nb"))
+                .before(serde_json::json!({ "name": "Untitled" }))
+                .after(serde_json::json!({ "name": name }));
+            db.log_activity(Some(7), &activity).unwrap();
+        }
+        db.log_activity(None, &NewActivity::new("purge", "trash", None)).unwrap();
+
+        let filter = ActivityFilter { entity_uuid: Some("nb".to_string()), limit: Some(2), ..Default::default() };
+        let first = db.get_activity_log(&filter).unwrap();
+        assert_eq!(first.entries.len(), 2);
+        assert_eq!(first.entries[0].new_value.as_ref().unwrap()["name"], "Final");
+        assert_eq!(first.entries[0].actor_id, Some(7));
+
+        let second = db
+            .get_activity_log(&ActivityFilter { before_id: first.next_cursor, ..filter })
+            .unwrap();
+        assert_eq!(second.entries.len(), 1);
+        assert_eq!(second.entries[0].old_value.as_ref().unwrap()["name"], "Untitled");
+        assert!(second.next_cursor.is_none());
+
+        db.conn
+            .execute("UPDATE activity_log SET created_at = '2020-01-01T00:00:00Z' WHERE action = 'purge'", [])
+            .unwrap();
+        assert_eq!(db.prune_activity_log(db.activity_retention_days().unwrap()).unwrap(), 1);
+        assert_eq!(db.get_activity_log(&ActivityFilter::default()).unwrap().entries.len(), 3);
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
@@ -0,0 +1,157 @@
+use anyhow::Result;
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+use super::LocalDatabase;
+use crate::i18n::tr;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Attachment {
+    pub id: i64,
+    pub uuid: String,
+    pub project_id: i64,
+    /// File name it was added under
+    pub name: String,
+    /// The managed copy inside app data
+    pub file_path: String,
+    /// SHA-256 of the contents, lowercase hex
+    pub checksum: String,
+    pub mime_type: String,
+    pub size_bytes: i64,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewAttachment {
+    pub uuid: String,
+    pub project_id: i64,
+    pub name: String,
+    pub file_path: String,
+    pub checksum: String,
+    pub mime_type: String,
+    pub size_bytes: i64,
+}
+
+const ATTACHMENT_COLUMNS: &str =
+    "id, uuid, project_id, name, file_path, checksum, mime_type, size_bytes, created_at";
+
+fn attachment_from_row(row: &Row) -> rusqlite::Result<Attachment> {
+    Ok(Attachment {
+        id: row.get(0)?,
+        uuid: row.get(1)?,
+        project_id: row.get(2)?,
+        name: row.get(3)?,
+        file_path: row.get(4)?,
+        checksum: row.get(5)?,
+        mime_type: row.get(6)?,
+        size_bytes: row.get(7)?,
+        created_at: row.get(8)?,
+    })
+}
+
+impl LocalDatabase {
+    // Attachment operations
+    /// Registers an attachment on a project that isn't in the trash.
+    pub fn insert_attachment(&self, attachment: &NewAttachment) -> Result<Attachment> {
+        let inserted = self.conn.execute(
+            "INSERT INTO attachments (uuid, project_id, name, file_path, checksum, mime_type, size_bytes)
+             SELECT ?1, id, ?3, ?4, ?5, ?6, ?7 FROM projects WHERE id = ?2 AND deleted_at IS NULL",
+            params![
+                attachment.uuid,
+                attachment.project_id,
+                attachment.name,
+                attachment.file_path,
+                attachment.checksum,
+                attachment.mime_type,
+                attachment.size_bytes,
+            ],
+        )?;
+        if inserted == 0 {
+            anyhow::bail!(tr!("project-not-found", id = attachment.project_id));
+        }
+
+        self.get_attachment(&attachment.uuid)?
+            .ok_or_else(|| anyhow::anyhow!("Attachment {} missing after insert", attachment.uuid))
+    }
+
+    pub fn get_attachment(&self, uuid: &str) -> Result<Option<Attachment>> {
+        let attachment = self
+            .conn
+            .query_row(
+                &format!("SELECT {} FROM attachments WHERE uuid = ?1", ATTACHMENT_COLUMNS),
+                params![uuid],
+                attachment_from_row,
+            )
+            .optional()?;
+
+        Ok(attachment)
+    }
+
+    /// Newest first, leaving out those in the trash with their project.
+    pub fn list_attachments(&self, project_id: i64) -> Result<Vec<Attachment>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM attachments WHERE project_id = ?1 AND deleted_at IS NULL
+             ORDER BY created_at DESC, id DESC",
+            ATTACHMENT_COLUMNS
+        ))?;
+
+        let attachments = stmt
+            .query_map(params![project_id], attachment_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(attachments)
+    }
+
+    /// Removes the row and returns it, so the caller can delete the file.
+    pub fn delete_attachment(&self, uuid: &str) -> Result<Option<Attachment>> {
+        let Some(attachment) = self.get_attachment(uuid)? else {
+            return Ok(None);
+        };
+
+        self.conn.execute("DELETE FROM attachments WHERE id = ?1", params![attachment.id])?;
+        Ok(Some(attachment))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attachments() {
+        let db_path = std::env::temp_dir().join("test_novem_attachment_rows.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        db.conn
+            .execute_batch(
+                "INSERT INTO users (id, uuid, email, username) VALUES (1, 'u1', 'a@example.com', 'ana');
+                 INSERT INTO workspaces (id, uuid, name, owner_id) VALUES (1, 'ws1', 'Team', 1);
+                 INSERT INTO projects (id, uuid, workspace_id, name, owner_id) VALUES (1, 'p1', 1, 'Sales', 1);",
+            )
+            .unwrap();
+
+        let new = |uuid: &str| NewAttachment {
+            uuid: uuid.to_string(),
+            project_id: 1,
+            name: "brief.pdf".to_string(),
+            file_path: format!("/data/attachments/{}.pdf", uuid),
+            checksum: "00".to_string(),
+            mime_type: "application/pdf".to_string(),
+            size_bytes: 10,
+        };
+        db.insert_attachment(&new("a1")).unwrap();
+        db.insert_attachment(&new("a2")).unwrap();
+        assert!(db.insert_attachment(&NewAttachment { project_id: 9, ..new("a3") }).is_err());
+
+        let listed = db.list_attachments(1).unwrap();
+        assert_eq!(listed.iter().map(|a| a.uuid.as_str()).collect::<Vec<_>>(), vec!["a2", "a1"]);
+
+        assert_eq!(db.delete_attachment("a1").unwrap().unwrap().file_path, "/data/attachments/a1.pdf");
+        assert!(db.delete_attachment("a1").unwrap().is_none());
+        assert_eq!(db.list_attachments(1).unwrap().len(), 1);
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
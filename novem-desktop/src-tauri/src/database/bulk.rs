@@ -0,0 +1,157 @@
+use anyhow::Result;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+
+use super::LocalDatabase;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkFailure {
+    pub id: i64,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BulkResult {
+    pub succeeded: Vec<i64>,
+    pub failed: Vec<BulkFailure>,
+}
+
+fn active_project_uuid(conn: &Connection, project_id: i64) -> Result<String> {
+    conn.query_row(
+        "SELECT uuid FROM projects WHERE id = ?1 AND is_active = 1",
+        params![project_id],
+        |row| row.get(0),
+    )
+    .optional()?
+    .ok_or_else(|| anyhow::anyhow!("Project {} not found", project_id))
+}
+
+fn queue_project_update(conn: &Connection, project_id: i64, payload: serde_json::Value) -> Result<()> {
+    let uuid = active_project_uuid(conn, project_id)?;
+
+    conn.execute(
+        "UPDATE projects SET sync_status = 'pending', updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = ?1",
+        params![project_id],
+    )?;
+    conn.execute(
+        "INSERT INTO sync_queue (entity_type, entity_uuid, action, payload, status)
+         VALUES ('project', ?1, 'update', ?2, 'pending')",
+        params![uuid, payload.to_string()],
+    )?;
+    Ok(())
+}
+
+impl LocalDatabase {
+    // Bulk operations
+
+    /// Applies `op` to every project in one transaction. Each item runs in
+    /// its own savepoint, so a failing item is rolled back and reported
+    /// while the rest still commit.
+    fn bulk_apply(&self, ids: &[i64], mut op: impl FnMut(&Connection, i64) -> Result<()>) -> Result<BulkResult> {
+        let tx = self.begin()?;
+        let mut result = BulkResult::default();
+
+        for &id in ids {
+            let savepoint = self.begin()?;
+            match op(&savepoint, id) {
+                Ok(()) => {
+                    savepoint.commit()?;
+                    result.succeeded.push(id);
+                }
+                Err(e) => result.failed.push(BulkFailure { id, error: e.to_string() }),
+            }
+        }
+
+        tx.commit()?;
+        Ok(result)
+    }
+
+    pub fn bulk_move_projects(&self, ids: &[i64], target_workspace_id: i64) -> Result<BulkResult> {
+        let workspace_uuid: String = self.conn
+            .query_row(
+                "SELECT uuid FROM workspaces WHERE id = ?1 AND is_active = 1",
+                params![target_workspace_id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .ok_or_else(|| anyhow::anyhow!("Workspace {} not found", target_workspace_id))?;
+
+        self.bulk_apply(ids, |conn, id| {
+            conn.execute(
+                "UPDATE projects SET workspace_id = ?1 WHERE id = ?2 AND is_active = 1",
+                params![target_workspace_id, id],
+            )?;
+            queue_project_update(conn, id, serde_json::json!({ "workspace_uuid": workspace_uuid }))
+        })
+    }
+
+    pub fn bulk_tag_projects(&self, ids: &[i64], tag: &str) -> Result<BulkResult> {
+        self.bulk_apply(ids, |conn, id| {
+            active_project_uuid(conn, id)?;
+            conn.execute(
+                "INSERT OR IGNORE INTO project_tags (project_id, tag) VALUES (?1, ?2)",
+                params![id, tag],
+            )?;
+
+            let mut stmt = conn.prepare("SELECT tag FROM project_tags WHERE project_id = ?1 ORDER BY tag")?;
+            let tags = stmt
+                .query_map(params![id], |row| row.get::<_, String>(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+
+            queue_project_update(conn, id, serde_json::json!({ "tags": tags }))
+        })
+    }
+
+    /// Archived projects stay intact but drop out of project listings.
+    pub fn bulk_archive_projects(&self, ids: &[i64]) -> Result<BulkResult> {
+        self.bulk_apply(ids, |conn, id| {
+            active_project_uuid(conn, id)?;
+            let inserted = conn.execute(
+                "INSERT OR IGNORE INTO archived_projects (project_id) VALUES (?1)",
+                params![id],
+            )?;
+            if inserted == 0 {
+                return Err(anyhow::anyhow!("Project {} is already archived", id));
+            }
+
+            queue_project_update(conn, id, serde_json::json!({ "archived": true }))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{PageRequest, ProjectQuery};
+
+    #[test]
+    fn test_bulk_reports_partial_failures() {
+        let db_path = std::env::temp_dir().join("test_novem_bulk.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        db.conn
+            .execute_batch(
+                "INSERT INTO users (id, uuid, email, username) VALUES (1, 'u1', 'a@example.com', 'ana');
+                 INSERT INTO workspaces (id, uuid, name, owner_id) VALUES (1, 'ws1', 'Team', 1);
+                 INSERT INTO workspaces (id, uuid, name, owner_id) VALUES (2, 'ws2', 'Archive', 1);
+                 INSERT INTO projects (id, uuid, workspace_id, name, owner_id) VALUES (1, 'p1', 1, 'Sales', 1);
+                 INSERT INTO projects (id, uuid, workspace_id, name, owner_id) VALUES (2, 'p2', 1, 'Churn', 1);",
+            )
+            .unwrap();
+
+        let moved = db.bulk_move_projects(&[1, 99, 2], 2).unwrap();
+        assert_eq!(moved.succeeded, vec![1, 2]);
+        assert_eq!(moved.failed.len(), 1);
+        assert_eq!(moved.failed[0].id, 99);
+        assert_eq!(db.get_projects(2, 1, &ProjectQuery::default(), &PageRequest::default()).unwrap().total, 2);
+
+        db.bulk_archive_projects(&[1]).unwrap();
+        let archived = db.bulk_archive_projects(&[1, 2]).unwrap();
+        assert_eq!(archived.succeeded, vec![2]);
+        assert!(db.get_projects(2, 1, &ProjectQuery::default(), &PageRequest::default()).unwrap().items.is_empty());
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
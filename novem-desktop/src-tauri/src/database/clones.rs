@@ -0,0 +1,316 @@
+use anyhow::Result;
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+use super::{Dataset, LocalDatabase, NewDataset, Project};
+use super::notebooks::Notebook;
+
+/// How a cloned project gets the original's datasets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DatasetCloneMode {
+    /// Leave datasets behind
+    Skip,
+    /// New dataset entries pointing at the original files
+    #[default]
+    Reference,
+    /// Independent copies of the files
+    Copy,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CloneOptions {
+    #[serde(default = "default_true")]
+    pub include_notebooks: bool,
+    #[serde(default)]
+    pub datasets: DatasetCloneMode,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for CloneOptions {
+    fn default() -> Self {
+        Self {
+            include_notebooks: true,
+            datasets: DatasetCloneMode::default(),
+        }
+    }
+}
+
+pub(super) const PROJECT_COLUMNS: &str =
+    "id, uuid, workspace_id, name, description, owner_id,
+     created_at, updated_at, is_active, sync_status, last_synced_at, is_pinned, version";
+
+pub(super) fn project_from_row(row: &Row) -> rusqlite::Result<Project> {
+    Ok(Project {
+        id: row.get(0)?,
+        uuid: row.get(1)?,
+        workspace_id: row.get(2)?,
+        name: row.get(3)?,
+        description: row.get(4)?,
+        owner_id: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+        is_active: row.get(8)?,
+        sync_status: row.get(9)?,
+        last_synced_at: row.get(10)?,
+        is_pinned: row.get(11)?,
+        version: row.get(12)?,
+    })
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClonedProject {
+    pub project: Project,
+    pub notebooks: usize,
+    pub datasets: usize,
+}
+
+impl LocalDatabase {
+    // Clone operations
+    fn add_entity_lineage(&self, entity_type: &str, entity_uuid: &str, source_uuid: &str, params_json: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO entity_lineage (entity_type, entity_uuid, source_uuid, operation, params)
+             VALUES (?1, ?2, ?3, 'clone', ?4)",
+            params![entity_type, entity_uuid, source_uuid, params_json],
+        )?;
+        Ok(())
+    }
+
+    /// Copies a notebook and its cells into `project_id`; the caller owns the
+    /// transaction.
+    fn copy_notebook(&self, source: &Notebook, project_id: i64, project_uuid: &str, name: &str) -> Result<Notebook> {
+        let uuid = uuid::Uuid::new_v4().to_string();
+
+        self.conn.execute(
+            "INSERT INTO notebooks (uuid, project_id, name) VALUES (?1, ?2, ?3)",
+            params![&uuid, project_id, name],
+        )?;
+        let notebook_id = self.conn.last_insert_rowid();
+
+        for cell in self.get_notebook_cells(source.id)? {
+            self.conn.execute(
+                "INSERT INTO notebook_cells (uuid, notebook_id, position, cell_type, source, outputs, tags)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    uuid::Uuid::new_v4().to_string(),
+                    notebook_id,
+                    cell.position,
+                    &cell.cell_type,
+                    &cell.source,
+                    &cell.outputs,
+                    &cell.tags,
+                ],
+            )?;
+        }
+
+        self.add_entity_lineage("notebook", &uuid, &source.uuid, "{}")?;
+        self.add_to_sync_queue(
+            "notebook",
+            &uuid,
+            "create",
+            &serde_json::json!({
+                "project_uuid": project_uuid,
+                "name": name,
+                "cloned_from": source.uuid,
+            })
+            .to_string(),
+        )?;
+
+        self.get_notebook_by_uuid(&uuid)?
+            .ok_or_else(|| anyhow::anyhow!("Notebook {} missing after insert", uuid))
+    }
+
+    /// Duplicates a notebook (cells and outputs) next to the original.
+    pub fn clone_notebook(&self, uuid: &str) -> Result<Notebook> {
+        let source = self
+            .get_notebook_by_uuid(uuid)?
+            .ok_or_else(|| anyhow::anyhow!("Notebook {} not found", uuid))?;
+
+        let project_uuid: String = self.conn.query_row(
+            "SELECT uuid FROM projects WHERE id = ?1",
+            params![source.project_id],
+            |row| row.get(0),
+        )?;
+
+        let tx = self.begin()?;
+        let notebook = self.copy_notebook(&source, source.project_id, &project_uuid, &format!("{} (copy)", source.name))?;
+        tx.commit()?;
+
+        Ok(notebook)
+    }
+
+    /// Deep-copies a project in one transaction. In `Copy` mode,
+    /// `copy_file(dataset, new_uuid)` must duplicate the dataset's file and
+    /// return the new path; an error rolls back the whole clone.
+    pub fn clone_project(
+        &self,
+        uuid: &str,
+        new_name: &str,
+        options: &CloneOptions,
+        mut copy_file: impl FnMut(&Dataset, &str) -> Result<String>,
+    ) -> Result<ClonedProject> {
+        let source: Project = self
+            .conn
+            .query_row(
+                &format!("SELECT {} FROM projects WHERE uuid = ?1 AND is_active = 1", PROJECT_COLUMNS),
+                params![uuid],
+                project_from_row,
+            )
+            .optional()?
+            .ok_or_else(|| anyhow::anyhow!("Project {} not found", uuid))?;
+
+        let tx = self.begin()?;
+
+        let new_uuid = uuid::Uuid::new_v4().to_string();
+        self.conn.execute(
+            "INSERT INTO projects (uuid, workspace_id, name, description, owner_id)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![&new_uuid, source.workspace_id, new_name, &source.description, source.owner_id],
+        )?;
+        let project_id = self.conn.last_insert_rowid();
+
+        let workspace_uuid: Option<String> = self.conn
+            .query_row("SELECT uuid FROM workspaces WHERE id = ?1", params![source.workspace_id], |row| row.get(0))
+            .optional()?;
+
+        self.add_entity_lineage(
+            "project",
+            &new_uuid,
+            &source.uuid,
+            &serde_json::json!({
+                "include_notebooks": options.include_notebooks,
+                "datasets": options.datasets,
+            })
+            .to_string(),
+        )?;
+        self.add_to_sync_queue(
+            "project",
+            &new_uuid,
+            "create",
+            &serde_json::json!({
+                "workspace_uuid": workspace_uuid,
+                "name": new_name,
+                "description": source.description,
+                "cloned_from": source.uuid,
+            })
+            .to_string(),
+        )?;
+
+        let mut notebooks = 0;
+        if options.include_notebooks {
+            let mut stmt = self.conn.prepare(
+                "SELECT uuid FROM notebooks WHERE project_id = ?1 AND is_active = 1 ORDER BY id"
+            )?;
+            let uuids = stmt
+                .query_map(params![source.id], |row| row.get::<_, String>(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+
+            for notebook_uuid in uuids {
+                if let Some(notebook) = self.get_notebook_by_uuid(&notebook_uuid)? {
+                    self.copy_notebook(&notebook, project_id, &new_uuid, &notebook.name)?;
+                    notebooks += 1;
+                }
+            }
+        }
+
+        let mut datasets = 0;
+        if options.datasets != DatasetCloneMode::Skip {
+            for dataset in self.list_datasets(source.id)? {
+                let dataset_uuid = uuid::Uuid::new_v4().to_string();
+                let file_path = match options.datasets {
+                    DatasetCloneMode::Copy => copy_file(&dataset, &dataset_uuid)?,
+                    _ => dataset.file_path.clone(),
+                };
+
+                self.create_dataset(&NewDataset {
+                    uuid: dataset_uuid.clone(),
+                    project_id,
+                    name: dataset.name.clone(),
+                    file_path,
+                    format: dataset.format.clone(),
+                    row_count: dataset.row_count,
+                    size_bytes: dataset.size_bytes,
+                    parent_uuid: Some(dataset.uuid.clone()),
+                })?;
+
+                let params_json = serde_json::json!({ "mode": options.datasets }).to_string();
+                self.add_dataset_lineage(&dataset_uuid, &dataset.uuid, "clone", &params_json)?;
+                self.add_to_sync_queue(
+                    "dataset",
+                    &dataset_uuid,
+                    "create",
+                    &serde_json::json!({
+                        "project_uuid": new_uuid,
+                        "name": dataset.name,
+                        "format": dataset.format,
+                        "cloned_from": dataset.uuid,
+                    })
+                    .to_string(),
+                )?;
+                datasets += 1;
+            }
+        }
+
+        let project = self.conn.query_row(
+            &format!("SELECT {} FROM projects WHERE id = ?1", PROJECT_COLUMNS),
+            params![project_id],
+            project_from_row,
+        )?;
+
+        tx.commit()?;
+
+        Ok(ClonedProject { project, notebooks, datasets })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clone_project() {
+        let db_path = std::env::temp_dir().join("test_novem_clones.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        db.conn
+            .execute_batch(
+                "INSERT INTO users (id, uuid, email, username) VALUES (1, 'u1', 'a@example.com', 'ana');
+                 INSERT INTO workspaces (id, uuid, name, owner_id) VALUES (1, 'ws', 'Team', 1);
+                 INSERT INTO projects (id, uuid, workspace_id, name, owner_id) VALUES (1, 'q3', 1, 'Q3 review', 1);
+                 INSERT INTO notebooks (id, uuid, project_id, name) VALUES (1, 'nb', 1, 'Revenue');
+                 INSERT INTO notebook_cells (uuid, notebook_id, position, source) VALUES ('c1', 1, 0, 'SELECT 1');
+                 INSERT INTO datasets (uuid, project_id, name, file_path, format, size_bytes)
+                    VALUES ('ds', 1, 'orders', '/data/orders.csv', 'csv', 2048);",
+            )
+            .unwrap();
+
+        let cloned = db
+            .clone_project("q3", "Q4 review", &CloneOptions::default(), |_, _| unreachable!())
+            .unwrap();
+        assert_eq!(cloned.notebooks, 1);
+        assert_eq!(cloned.datasets, 1);
+        assert_ne!(cloned.project.uuid, "q3");
+
+        let datasets = db.list_datasets(cloned.project.id).unwrap();
+        assert_eq!(datasets[0].file_path, "/data/orders.csv");
+        assert_eq!(datasets[0].parent_uuid.as_deref(), Some("ds"));
+
+        // A failed copy leaves nothing behind
+        let options = CloneOptions { datasets: DatasetCloneMode::Copy, ..Default::default() };
+        assert!(db
+            .clone_project("q3", "Broken", &options, |_, _| Err(anyhow::anyhow!("disk full")))
+            .is_err());
+        let projects: i64 = db.conn.query_row("SELECT COUNT(*) FROM projects", [], |row| row.get(0)).unwrap();
+        assert_eq!(projects, 2);
+
+        // Project, notebook and dataset creates are queued for sync
+        assert_eq!(db.get_pending_sync_items().unwrap().len(), 3);
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
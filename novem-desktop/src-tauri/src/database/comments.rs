@@ -0,0 +1,287 @@
+use anyhow::Result;
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+use super::LocalDatabase;
+use crate::i18n::tr;
+
+/// Longest comment accepted, in characters.
+const MAX_COMMENT_LENGTH: usize = 10_000;
+
+/// What a comment is attached to, addressed by UUID.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "uuid", rename_all = "snake_case")]
+pub enum CommentTarget {
+    Project(String),
+    NotebookCell(String),
+}
+
+impl CommentTarget {
+    pub fn entity_type(&self) -> &'static str {
+        match self {
+            CommentTarget::Project(_) => "project",
+            CommentTarget::NotebookCell(_) => "notebook_cell",
+        }
+    }
+
+    pub fn uuid(&self) -> &str {
+        match self {
+            CommentTarget::Project(uuid) | CommentTarget::NotebookCell(uuid) => uuid,
+        }
+    }
+
+    fn exists_sql(&self) -> &'static str {
+        match self {
+            CommentTarget::Project(_) => "SELECT 1 FROM projects WHERE uuid = ?1 AND deleted_at IS NULL",
+            CommentTarget::NotebookCell(_) => "SELECT 1 FROM notebook_cells WHERE uuid = ?1",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Comment {
+    pub id: i64,
+    pub uuid: String,
+    pub entity_type: String,
+    pub entity_uuid: String,
+    /// Backend user ID of whoever was signed in, if anyone
+    pub author_id: Option<i64>,
+    pub body: String,
+    pub resolved: bool,
+    pub created_at: String,
+    pub updated_at: String,
+    pub sync_status: String,
+}
+
+const COMMENT_COLUMNS: &str =
+    "id, uuid, entity_type, entity_uuid, author_id, body, resolved, created_at, updated_at, sync_status";
+
+fn comment_from_row(row: &Row) -> rusqlite::Result<Comment> {
+    Ok(Comment {
+        id: row.get(0)?,
+        uuid: row.get(1)?,
+        entity_type: row.get(2)?,
+        entity_uuid: row.get(3)?,
+        author_id: row.get(4)?,
+        body: row.get(5)?,
+        resolved: row.get(6)?,
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+        sync_status: row.get(9)?,
+    })
+}
+
+fn check_body(body: &str) -> Result<()> {
+    if body.trim().is_empty() {
+        anyhow::bail!(tr!("comment-empty"));
+    }
+    if body.chars().count() > MAX_COMMENT_LENGTH {
+        anyhow::bail!(tr!("comment-too-long", max = MAX_COMMENT_LENGTH));
+    }
+    Ok(())
+}
+
+impl LocalDatabase {
+    // Comment operations
+    pub fn get_comment_by_uuid(&self, uuid: &str) -> Result<Option<Comment>> {
+        let comment = self
+            .conn
+            .query_row(
+                &format!("SELECT {} FROM comments WHERE uuid = ?1", COMMENT_COLUMNS),
+                params![uuid],
+                comment_from_row,
+            )
+            .optional()?;
+
+        Ok(comment)
+    }
+
+    /// Oldest first, so a thread reads top to bottom.
+    pub fn list_comments(&self, target: &CommentTarget, include_resolved: bool) -> Result<Vec<Comment>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM comments
+             WHERE entity_type = ?1 AND entity_uuid = ?2 AND (?3 OR resolved = 0)
+             ORDER BY created_at, id",
+            COMMENT_COLUMNS
+        ))?;
+
+        let comments = stmt
+            .query_map(params![target.entity_type(), target.uuid(), include_resolved], comment_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(comments)
+    }
+
+    /// Saves a comment locally and queues it for sync.
+    pub fn create_comment(&self, target: &CommentTarget, author_id: Option<i64>, body: &str) -> Result<Comment> {
+        check_body(body)?;
+        let exists = self
+            .conn
+            .query_row(target.exists_sql(), params![target.uuid()], |_| Ok(()))
+            .optional()?
+            .is_some();
+        if !exists {
+            anyhow::bail!(tr!("comment-target-not-found", entity_type = target.entity_type(), uuid = target.uuid()));
+        }
+
+        let uuid = uuid::Uuid::new_v4().to_string();
+        let tx = self.begin()?;
+        self.conn.execute(
+            "INSERT INTO comments (uuid, entity_type, entity_uuid, author_id, body) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![&uuid, target.entity_type(), target.uuid(), author_id, body],
+        )?;
+        let comment = self
+            .get_comment_by_uuid(&uuid)?
+            .ok_or_else(|| anyhow::anyhow!("Comment {} missing after insert", uuid))?;
+        self.queue_comment_sync(None, &comment)?;
+        tx.commit()?;
+
+        Ok(comment)
+    }
+
+    /// Edits the body and/or resolves or reopens a comment. Returns `None`
+    /// if the comment doesn't exist.
+    pub fn update_comment(&self, uuid: &str, body: Option<&str>, resolved: Option<bool>) -> Result<Option<Comment>> {
+        if let Some(body) = body {
+            check_body(body)?;
+        }
+
+        let tx = self.begin()?;
+        let Some(before) = self.get_comment_by_uuid(uuid)? else {
+            return Ok(None);
+        };
+        self.conn.execute(
+            "UPDATE comments
+             SET body = COALESCE(?1, body),
+                 resolved = COALESCE(?2, resolved),
+                 sync_status = 'pending',
+                 updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE uuid = ?3",
+            params![body, resolved, uuid],
+        )?;
+
+        let comment = self
+            .get_comment_by_uuid(uuid)?
+            .ok_or_else(|| anyhow::anyhow!("Comment {} missing after update", uuid))?;
+        self.queue_comment_sync(Some(&before), &comment)?;
+        tx.commit()?;
+
+        Ok(Some(comment))
+    }
+
+    /// Deletes a comment. One that never reached the backend just has its
+    /// queued changes dropped; otherwise it is tombstoned and the delete
+    /// queued. Returns the deleted comment, or `None` if it doesn't exist.
+    pub fn delete_comment(&self, uuid: &str) -> Result<Option<Comment>> {
+        let Some(comment) = self.get_comment_by_uuid(uuid)? else {
+            return Ok(None);
+        };
+
+        let tx = self.begin()?;
+        self.conn.execute("DELETE FROM comments WHERE id = ?1", params![comment.id])?;
+
+        let unsynced_create = self.conn.execute(
+            "DELETE FROM sync_queue
+             WHERE status = 'pending' AND action = 'create' AND entity_type = 'comment' AND entity_uuid = ?1",
+            params![uuid],
+        )?;
+        if unsynced_create > 0 {
+            self.conn.execute(
+                "DELETE FROM sync_queue WHERE status = 'pending' AND entity_type = 'comment' AND entity_uuid = ?1",
+                params![uuid],
+            )?;
+        } else {
+            self.conn.execute(
+                "INSERT OR REPLACE INTO tombstones (entity_type, entity_uuid, root_type, root_uuid, purged)
+                 VALUES ('comment', ?1, 'comment', ?1, 1)",
+                params![uuid],
+            )?;
+            self.add_to_sync_queue(
+                "comment",
+                uuid,
+                "delete",
+                &serde_json::json!({ "entity_type": comment.entity_type, "entity_uuid": comment.entity_uuid }).to_string(),
+            )?;
+        }
+        tx.commit()?;
+
+        Ok(Some(comment))
+    }
+
+    /// Queues a new comment, or the fields an edit changed since `before`.
+    fn queue_comment_sync(&self, before: Option<&Comment>, comment: &Comment) -> Result<()> {
+        let payload = |comment: &Comment| {
+            serde_json::json!({
+                "entity_type": comment.entity_type,
+                "entity_uuid": comment.entity_uuid,
+                "author_id": comment.author_id,
+                "body": comment.body,
+                "resolved": comment.resolved,
+            })
+        };
+        match before {
+            Some(before) => {
+                self.queue_update("comment", &comment.uuid, &payload(before), &payload(comment))?;
+            }
+            None => self.add_to_sync_queue("comment", &comment.uuid, "create", &payload(comment).to_string())?,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queued(db: &LocalDatabase, uuid: &str) -> Vec<String> {
+        let mut stmt = db.conn
+            .prepare("SELECT action FROM sync_queue WHERE entity_uuid = ?1 ORDER BY id")
+            .unwrap();
+        stmt.query_map(params![uuid], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_comment_lifecycle_and_sync() {
+        let db_path = std::env::temp_dir().join("test_novem_comments.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        db.conn
+            .execute_batch(
+                "INSERT INTO users (id, uuid, email, username) VALUES (1, 'u1', 'a@example.com', 'ana');
+                 INSERT INTO workspaces (id, uuid, name, owner_id) VALUES (1, 'ws1', 'Team', 1);
+                 INSERT INTO projects (id, uuid, workspace_id, name, owner_id) VALUES (1, 'p1', 1, 'Sales', 1);",
+            )
+            .unwrap();
+
+        let project = CommentTarget::Project("p1".to_string());
+        assert!(db.create_comment(&project, Some(1), "   ").is_err());
+        assert!(db.create_comment(&CommentTarget::NotebookCell("missing".to_string()), Some(1), "Hi").is_err());
+
+        let first = db.create_comment(&project, Some(1), "Check the date filter").unwrap();
+        let second = db.create_comment(&project, None, "Looks good").unwrap();
+        db.update_comment(&first.uuid, None, Some(true)).unwrap().unwrap();
+        assert_eq!(queued(&db, &first.uuid), vec!["create", "update"]);
+
+        let open = db.list_comments(&project, false).unwrap();
+        assert_eq!(open.iter().map(|c| c.uuid.as_str()).collect::<Vec<_>>(), vec![second.uuid.as_str()]);
+        assert_eq!(db.list_comments(&project, true).unwrap().len(), 2);
+
+        // Never synced: the pending changes are dropped instead
+        db.delete_comment(&first.uuid).unwrap().unwrap();
+        assert!(queued(&db, &first.uuid).is_empty());
+
+        // Already synced: the delete is queued
+        db.conn.execute("UPDATE sync_queue SET status = 'synced'", []).unwrap();
+        db.delete_comment(&second.uuid).unwrap().unwrap();
+        assert_eq!(queued(&db, &second.uuid), vec!["create", "delete"]);
+        assert_eq!(db.pending_deletes().unwrap()[0].uuid, second.uuid);
+        assert!(db.delete_comment(&second.uuid).unwrap().is_none());
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
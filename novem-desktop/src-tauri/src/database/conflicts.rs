@@ -0,0 +1,498 @@
+use anyhow::Result;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::versions::versioned_table;
+use super::{LocalDatabase, VersionCheck};
+use crate::i18n::tr;
+use crate::timestamps;
+
+/// How a conflict on an entity type is settled when it is detected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictStrategy {
+    ServerWins,
+    LocalWins,
+    /// Whichever copy has the later `updated_at`
+    NewestWins,
+    /// Kept for the user to pick with `resolve_conflict`
+    Manual,
+}
+
+/// Which copy a conflict was settled for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictChoice {
+    Local,
+    Server,
+}
+
+impl ConflictStrategy {
+    fn as_str(self) -> &'static str {
+        match self {
+            ConflictStrategy::ServerWins => "server_wins",
+            ConflictStrategy::LocalWins => "local_wins",
+            ConflictStrategy::NewestWins => "newest_wins",
+            ConflictStrategy::Manual => "manual",
+        }
+    }
+
+    fn from_name(name: &str) -> Self {
+        match name {
+            "server_wins" => ConflictStrategy::ServerWins,
+            "local_wins" => ConflictStrategy::LocalWins,
+            "newest_wins" => ConflictStrategy::NewestWins,
+            _ => ConflictStrategy::Manual,
+        }
+    }
+}
+
+impl ConflictChoice {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ConflictChoice::Local => "local",
+            ConflictChoice::Server => "server",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "local" => Some(ConflictChoice::Local),
+            "server" => Some(ConflictChoice::Server),
+            _ => None,
+        }
+    }
+}
+
+/// Both copies of a row edited here and on the backend since the last sync.
+#[derive(Debug, Clone, Serialize)]
+pub struct Conflict {
+    pub id: i64,
+    pub entity_type: String,
+    pub entity_uuid: String,
+    pub local_version: i64,
+    pub server_version: i64,
+    pub local_data: Value,
+    pub server_data: Value,
+    pub strategy: ConflictStrategy,
+    pub detected_at: String,
+    pub resolved_at: Option<String>,
+    /// `None` while waiting for the user
+    pub resolution: Option<ConflictChoice>,
+}
+
+/// What the sync should do after offering the backend's copy of a row.
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerCopyResult {
+    pub check: VersionCheck,
+    /// The backend's copy was written over the local row
+    pub applied: bool,
+    /// Recorded when both sides had changed
+    pub conflict: Option<Conflict>,
+}
+
+/// Columns both sides edit, by table; everything else is local or set by
+/// the app itself.
+fn synced_fields(table: &str) -> &'static [&'static str] {
+    match table {
+        "datasets" => &["name", "row_count", "size_bytes"],
+        _ => &["name", "description"],
+    }
+}
+
+const CONFLICT_COLUMNS: &str =
+    "id, entity_type, entity_uuid, local_version, server_version, local_data, server_data,
+     strategy, detected_at, resolved_at, resolution";
+
+fn parse_json(text: String) -> Value {
+    serde_json::from_str(&text).unwrap_or(Value::Null)
+}
+
+fn conflict_from_row(row: &Row) -> rusqlite::Result<Conflict> {
+    Ok(Conflict {
+        id: row.get(0)?,
+        entity_type: row.get(1)?,
+        entity_uuid: row.get(2)?,
+        local_version: row.get(3)?,
+        server_version: row.get(4)?,
+        local_data: parse_json(row.get(5)?),
+        server_data: parse_json(row.get(6)?),
+        strategy: ConflictStrategy::from_name(&row.get::<_, String>(7)?),
+        detected_at: row.get(8)?,
+        resolved_at: row.get(9)?,
+        resolution: row.get::<_, Option<String>>(10)?.as_deref().and_then(ConflictChoice::from_name),
+    })
+}
+
+fn sql_to_json(value: SqlValue) -> Value {
+    match value {
+        SqlValue::Integer(n) => Value::from(n),
+        SqlValue::Real(n) => Value::from(n),
+        SqlValue::Text(text) => Value::from(text),
+        SqlValue::Null | SqlValue::Blob(_) => Value::Null,
+    }
+}
+
+fn json_to_sql(value: &Value) -> SqlValue {
+    match value {
+        Value::Bool(flag) => SqlValue::Integer(i64::from(*flag)),
+        Value::Number(n) => n.as_i64().map_or_else(|| SqlValue::Real(n.as_f64().unwrap_or_default()), SqlValue::Integer),
+        Value::String(text) => SqlValue::Text(text.clone()),
+        Value::Null | Value::Array(_) | Value::Object(_) => SqlValue::Null,
+    }
+}
+
+impl LocalDatabase {
+    // Sync conflicts
+    /// The synced columns of a row with its `updated_at` and version, as
+    /// stored in a conflict.
+    fn local_copy(&self, table: &str, uuid: &str) -> Result<Value> {
+        let fields = synced_fields(table);
+        let columns = [fields, &["updated_at", "version"]].concat();
+        let values = self.conn.query_row(
+            &format!("SELECT {} FROM {} WHERE uuid = ?1", columns.join(", "), table),
+            params![uuid],
+            |row| (0..columns.len()).map(|i| row.get::<_, SqlValue>(i)).collect::<rusqlite::Result<Vec<_>>>(),
+        )?;
+        Ok(Value::Object(
+            columns.iter().map(|column| column.to_string()).zip(values.into_iter().map(sql_to_json)).collect(),
+        ))
+    }
+
+    /// Writes the backend's copy over a row; fields it leaves out keep
+    /// their local value.
+    fn apply_copy(&self, table: &str, uuid: &str, server_version: i64, data: &Value) -> Result<()> {
+        let mut values = Vec::new();
+        let mut assignments = Vec::new();
+        for field in synced_fields(table) {
+            if let Some(value) = data.get(field) {
+                values.push(json_to_sql(value));
+                assignments.push(format!("{} = ?{}", field, values.len()));
+            }
+        }
+        if let Some(updated_at) = data.get("updated_at").and_then(Value::as_str) {
+            values.push(SqlValue::Text(timestamps::normalize(updated_at)));
+            assignments.push(format!("updated_at = ?{}", values.len()));
+        }
+        values.push(SqlValue::Integer(server_version));
+        assignments.push(format!("version = ?{n}, server_version = ?{n}", n = values.len()));
+        values.push(SqlValue::Text(uuid.to_string()));
+
+        self.conn.execute(
+            &format!(
+                "UPDATE {} SET {}, sync_status = 'synced', last_synced_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+                 WHERE uuid = ?{}",
+                table,
+                assignments.join(", "),
+                values.len()
+            ),
+            params_from_iter(values),
+        )?;
+        Ok(())
+    }
+
+    /// Keeps the local copy over the backend's: it moves past the backend's
+    /// version and is queued to be pushed again.
+    fn keep_local(&self, entity_type: &str, table: &str, uuid: &str, server_version: i64) -> Result<()> {
+        self.conn.execute(
+            &format!(
+                "UPDATE {} SET version = MAX(version, ?2) + 1, server_version = ?2, sync_status = 'pending'
+                 WHERE uuid = ?1",
+                table
+            ),
+            params![uuid, server_version],
+        )?;
+        let copy = self.local_copy(table, uuid)?;
+        self.add_to_sync_queue(entity_type, uuid, "update", &copy.to_string())
+    }
+
+    /// Offers the backend's copy of a row, at `server_version`, to the
+    /// local one. An unchanged local row takes it; when both changed the
+    /// conflict is recorded and settled per `strategy`.
+    pub fn apply_server_copy(
+        &self,
+        entity_type: &str,
+        uuid: &str,
+        server_version: i64,
+        data: &Value,
+        strategy: ConflictStrategy,
+    ) -> Result<ServerCopyResult> {
+        let table = versioned_table(entity_type)?;
+        let tx = self.begin()?;
+
+        let check = self.check_server_version(entity_type, uuid, server_version)?;
+        let mut result = ServerCopyResult { check, applied: false, conflict: None };
+        match check {
+            VersionCheck::InSync | VersionCheck::LocalAhead => {}
+            VersionCheck::ServerAhead => {
+                self.apply_copy(table, uuid, server_version, data)?;
+                result.applied = true;
+            }
+            VersionCheck::Conflict => {
+                let local = self.local_copy(table, uuid)?;
+                let resolution = match strategy {
+                    ConflictStrategy::ServerWins => Some(ConflictChoice::Server),
+                    ConflictStrategy::LocalWins => Some(ConflictChoice::Local),
+                    ConflictStrategy::NewestWins => {
+                        let stamp = |copy: &Value| {
+                            copy.get("updated_at").and_then(Value::as_str).map(timestamps::normalize)
+                        };
+                        // Ties and undated copies go to the local edit
+                        if stamp(data) > stamp(&local) {
+                            Some(ConflictChoice::Server)
+                        } else {
+                            Some(ConflictChoice::Local)
+                        }
+                    }
+                    ConflictStrategy::Manual => None,
+                };
+
+                // A newer backend copy replaces one still waiting on the user
+                let id = match self.open_conflict_id(entity_type, uuid)? {
+                    Some(id) => {
+                        self.conn.execute(
+                            "UPDATE conflicts SET local_version = ?2, server_version = ?3, local_data = ?4,
+                                server_data = ?5, strategy = ?6, detected_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+                             WHERE id = ?1",
+                            params![
+                                id,
+                                local["version"].as_i64(),
+                                server_version,
+                                local.to_string(),
+                                data.to_string(),
+                                strategy.as_str(),
+                            ],
+                        )?;
+                        id
+                    }
+                    None => {
+                        self.conn.execute(
+                            "INSERT INTO conflicts (entity_type, entity_uuid, local_version, server_version,
+                                local_data, server_data, strategy)
+                             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                            params![
+                                entity_type,
+                                uuid,
+                                local["version"].as_i64(),
+                                server_version,
+                                local.to_string(),
+                                data.to_string(),
+                                strategy.as_str(),
+                            ],
+                        )?;
+                        self.conn.last_insert_rowid()
+                    }
+                };
+
+                if let Some(choice) = resolution {
+                    self.settle(id, choice)?;
+                    result.applied = choice == ConflictChoice::Server;
+                }
+                result.conflict = self.get_conflict(id)?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(result)
+    }
+
+    /// Stores the backend's copy of a row this device has never had, as
+    /// synced at `server_version`. A workspace is owned by `owner_id`; a
+    /// project joins the workspace named by its `workspace_uuid`, owned by
+    /// that workspace's owner. Returns `false` for datasets, whose file is
+    /// only on the device that added them.
+    pub fn insert_server_copy(
+        &self,
+        entity_type: &str,
+        uuid: &str,
+        server_version: i64,
+        data: &Value,
+        owner_id: Option<i64>,
+    ) -> Result<bool> {
+        let table = versioned_table(entity_type)?;
+        if table == "datasets" {
+            return Ok(false);
+        }
+        let text = |field: &str| data.get(field).and_then(Value::as_str);
+        let name = text("name")
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("The backend's copy of {} {} has no name", entity_type, uuid))?;
+        let description = text("description");
+        let updated_at = text("updated_at").map(timestamps::normalize);
+
+        let (workspace_id, owner_id) = match table {
+            "projects" => {
+                let workspace_uuid = text("workspace_uuid")
+                    .ok_or_else(|| anyhow::anyhow!("The backend's copy of project {} has no workspace", uuid))?;
+                let workspace: (i64, i64) = self
+                    .conn
+                    .query_row(
+                        "SELECT id, owner_id FROM workspaces WHERE uuid = ?1 AND deleted_at IS NULL",
+                        params![workspace_uuid],
+                        |row| Ok((row.get(0)?, row.get(1)?)),
+                    )
+                    .optional()?
+                    .ok_or_else(|| anyhow::anyhow!(tr!("workspace-not-found", uuid = workspace_uuid)))?;
+                (Some(workspace.0), workspace.1)
+            }
+            _ => (None, owner_id.ok_or_else(|| anyhow::anyhow!(tr!("workspace-not-signed-in")))?),
+        };
+
+        match workspace_id {
+            Some(workspace_id) => self.conn.execute(
+                "INSERT INTO projects (uuid, workspace_id, name, description, owner_id, updated_at, version,
+                    server_version, sync_status, last_synced_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, COALESCE(?6, strftime('%Y-%m-%dT%H:%M:%SZ', 'now')), ?7, ?7, 'synced',
+                    strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))",
+                params![uuid, workspace_id, name, description, owner_id, updated_at, server_version],
+            )?,
+            None => self.conn.execute(
+                "INSERT INTO workspaces (uuid, name, description, owner_id, updated_at, version, server_version,
+                    sync_status, last_synced_at)
+                 VALUES (?1, ?2, ?3, ?4, COALESCE(?5, strftime('%Y-%m-%dT%H:%M:%SZ', 'now')), ?6, ?6, 'synced',
+                    strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))",
+                params![uuid, name, description, owner_id, updated_at, server_version],
+            )?,
+        };
+        Ok(true)
+    }
+
+    fn open_conflict_id(&self, entity_type: &str, uuid: &str) -> Result<Option<i64>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT id FROM conflicts WHERE entity_type = ?1 AND entity_uuid = ?2 AND resolved_at IS NULL",
+                params![entity_type, uuid],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    /// Applies the chosen copy of an open conflict and closes it.
+    fn settle(&self, id: i64, choice: ConflictChoice) -> Result<Conflict> {
+        let conflict = self
+            .get_conflict(id)?
+            .ok_or_else(|| anyhow::anyhow!(tr!("conflict-not-found", id = id)))?;
+        if conflict.resolution.is_some() {
+            anyhow::bail!(tr!("conflict-already-resolved", id = id));
+        }
+
+        let table = versioned_table(&conflict.entity_type)?;
+        match choice {
+            ConflictChoice::Server => {
+                self.apply_copy(table, &conflict.entity_uuid, conflict.server_version, &conflict.server_data)?
+            }
+            ConflictChoice::Local => {
+                self.keep_local(&conflict.entity_type, table, &conflict.entity_uuid, conflict.server_version)?
+            }
+        }
+        self.conn.execute(
+            "UPDATE conflicts SET resolution = ?2, resolved_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = ?1",
+            params![id, choice.as_str()],
+        )?;
+
+        Ok(self.get_conflict(id)?.unwrap_or(conflict))
+    }
+
+    pub fn get_conflict(&self, id: i64) -> Result<Option<Conflict>> {
+        Ok(self
+            .conn
+            .query_row(
+                &format!("SELECT {} FROM conflicts WHERE id = ?1", CONFLICT_COLUMNS),
+                params![id],
+                conflict_from_row,
+            )
+            .optional()?)
+    }
+
+    /// Conflicts waiting on the user, oldest first; with `include_resolved`
+    /// also the settled ones.
+    pub fn list_conflicts(&self, include_resolved: bool) -> Result<Vec<Conflict>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM conflicts WHERE ?1 OR resolved_at IS NULL ORDER BY detected_at ASC, id ASC",
+            CONFLICT_COLUMNS
+        ))?;
+        let conflicts = stmt
+            .query_map(params![include_resolved], conflict_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(conflicts)
+    }
+
+    /// Settles a conflict for the user's choice of copy.
+    pub fn resolve_conflict(&self, id: i64, choice: ConflictChoice) -> Result<Conflict> {
+        let tx = self.begin()?;
+        let conflict = self.settle(id, choice)?;
+        tx.commit()?;
+        Ok(conflict)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_conflict_strategies() {
+        let db_path = std::env::temp_dir().join("test_novem_conflicts.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        db.conn
+            .execute_batch(
+                "INSERT INTO users (id, uuid, email, username) VALUES (1, 'u1', 'a@example.com', 'ana');
+                 INSERT INTO workspaces (id, uuid, name, owner_id, sync_status, server_version) VALUES (1, 'ws1', 'Team', 1, 'synced', 1);
+                 INSERT INTO projects (id, uuid, workspace_id, name, owner_id, updated_at, sync_status, server_version)
+                    VALUES (1, 'p1', 1, 'Sales', 1, '2025-01-01T00:00:00Z', 'synced', 1);
+                 UPDATE projects SET name = 'Sales (local)', sync_status = 'pending' WHERE id = 1;",
+            )
+            .unwrap();
+        let project = |db: &LocalDatabase| {
+            db.conn
+                .query_row("SELECT name, version, sync_status FROM projects WHERE id = 1", [], |row| {
+                    Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?, row.get::<_, String>(2)?))
+                })
+                .unwrap()
+        };
+
+        // Unchanged here: the backend's copy is simply taken
+        let result = db
+            .apply_server_copy("workspace", "ws1", 2, &json!({ "name": "Team A" }), ConflictStrategy::Manual)
+            .unwrap();
+        assert!(result.applied && result.conflict.is_none());
+        assert_eq!(db.get_workspace_by_uuid("ws1").unwrap().unwrap().name, "Team A");
+
+        // Manual: both copies wait for the user, a newer backend copy replacing the older
+        let server = json!({ "name": "Sales (server)", "updated_at": "2024-06-01T00:00:00Z" });
+        db.apply_server_copy("project", "p1", 3, &server, ConflictStrategy::Manual).unwrap();
+        let result = db.apply_server_copy("project", "p1", 4, &server, ConflictStrategy::Manual).unwrap();
+        let conflict = result.conflict.unwrap();
+        assert_eq!((conflict.local_version, conflict.server_version, conflict.resolution), (2, 4, None));
+        assert_eq!(conflict.local_data["name"], "Sales (local)");
+        assert_eq!(db.list_conflicts(false).unwrap().len(), 1);
+        assert_eq!(project(&db).2, "conflict");
+
+        let resolved = db.resolve_conflict(conflict.id, ConflictChoice::Local).unwrap();
+        assert_eq!(resolved.resolution, Some(ConflictChoice::Local));
+        assert_eq!(project(&db), ("Sales (local)".to_string(), 5, "pending".to_string()));
+        assert!(db.resolve_conflict(conflict.id, ConflictChoice::Server).is_err());
+        assert!(db.list_conflicts(false).unwrap().is_empty());
+
+        // Newest wins: the local edit is later than the backend's
+        let result = db.apply_server_copy("project", "p1", 6, &server, ConflictStrategy::NewestWins).unwrap();
+        assert!(!result.applied);
+        assert_eq!(result.conflict.unwrap().resolution, Some(ConflictChoice::Local));
+        assert_eq!(project(&db).1, 7);
+
+        let result = db.apply_server_copy("project", "p1", 8, &server, ConflictStrategy::ServerWins).unwrap();
+        assert!(result.applied);
+        assert_eq!(project(&db), ("Sales (server)".to_string(), 8, "synced".to_string()));
+        assert_eq!(db.list_conflicts(true).unwrap().len(), 3);
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
@@ -0,0 +1,212 @@
+use anyhow::Result;
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+use super::LocalDatabase;
+
+/// An external data source notebooks and queries can read from. Its
+/// password or key lives in the secrets vault under `secret_name`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataConnection {
+    pub id: i64,
+    pub uuid: String,
+    /// `None` for connections available in every workspace
+    pub workspace_id: Option<i64>,
+    pub name: String,
+    pub kind: String, // 'postgres', 'mysql', 's3', 'bigquery'
+    pub host: Option<String>,
+    pub port: Option<i64>,
+    /// Database, bucket or project, depending on the kind
+    pub database: Option<String>,
+    pub username: Option<String>,
+    pub secret_name: Option<String>,
+    pub options: String, // JSON
+    pub created_at: String,
+    pub updated_at: String,
+    pub last_tested_at: Option<String>,
+    pub last_test_ok: Option<bool>,
+    pub last_test_message: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewDataConnection {
+    #[serde(default)]
+    pub workspace_id: Option<i64>,
+    pub name: String,
+    pub kind: String,
+    #[serde(default)]
+    pub host: Option<String>,
+    #[serde(default)]
+    pub port: Option<i64>,
+    #[serde(default)]
+    pub database: Option<String>,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub secret_name: Option<String>,
+    #[serde(default)]
+    pub options: Option<serde_json::Value>,
+}
+
+/// Source types the compute engine knows how to reach.
+pub const CONNECTION_KINDS: &[&str] = &["postgres", "mysql", "s3", "bigquery"];
+
+const CONNECTION_COLUMNS: &str =
+    "id, uuid, workspace_id, name, kind, host, port, database, username, secret_name, options,
+     created_at, updated_at, last_tested_at, last_test_ok, last_test_message";
+
+fn connection_from_row(row: &Row) -> rusqlite::Result<DataConnection> {
+    Ok(DataConnection {
+        id: row.get(0)?,
+        uuid: row.get(1)?,
+        workspace_id: row.get(2)?,
+        name: row.get(3)?,
+        kind: row.get(4)?,
+        host: row.get(5)?,
+        port: row.get(6)?,
+        database: row.get(7)?,
+        username: row.get(8)?,
+        secret_name: row.get(9)?,
+        options: row.get(10)?,
+        created_at: row.get(11)?,
+        updated_at: row.get(12)?,
+        last_tested_at: row.get(13)?,
+        last_test_ok: row.get(14)?,
+        last_test_message: row.get(15)?,
+    })
+}
+
+impl LocalDatabase {
+    // Data connection operations
+    pub fn create_connection(&self, uuid: &str, connection: &NewDataConnection) -> Result<DataConnection> {
+        if !CONNECTION_KINDS.contains(&connection.kind.as_str()) {
+            return Err(anyhow::anyhow!("Unknown connection type '{}'", connection.kind));
+        }
+        let options = connection
+            .options
+            .clone()
+            .unwrap_or_else(|| serde_json::json!({}))
+            .to_string();
+
+        self.conn.execute(
+            "INSERT INTO connections
+                 (uuid, workspace_id, name, kind, host, port, database, username, secret_name, options)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                uuid,
+                connection.workspace_id,
+                &connection.name,
+                &connection.kind,
+                &connection.host,
+                connection.port,
+                &connection.database,
+                &connection.username,
+                &connection.secret_name,
+                &options,
+            ],
+        )?;
+
+        self.get_connection(uuid)?
+            .ok_or_else(|| anyhow::anyhow!("Connection {} missing after insert", uuid))
+    }
+
+    pub fn get_connection(&self, uuid: &str) -> Result<Option<DataConnection>> {
+        let connection = self
+            .conn
+            .query_row(
+                &format!("SELECT {} FROM connections WHERE uuid = ?1", CONNECTION_COLUMNS),
+                params![uuid],
+                connection_from_row,
+            )
+            .optional()?;
+
+        Ok(connection)
+    }
+
+    /// A workspace's connections plus those shared by all workspaces; every
+    /// connection when `workspace_id` is `None`.
+    pub fn list_connections(&self, workspace_id: Option<i64>) -> Result<Vec<DataConnection>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM connections
+             WHERE ?1 IS NULL OR workspace_id = ?1 OR workspace_id IS NULL
+             ORDER BY name COLLATE NOCASE ASC",
+            CONNECTION_COLUMNS
+        ))?;
+
+        let connections = stmt
+            .query_map(params![workspace_id], connection_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(connections)
+    }
+
+    pub fn record_connection_test(&self, uuid: &str, ok: bool, message: Option<&str>) -> Result<bool> {
+        let count = self.conn.execute(
+            "UPDATE connections
+             SET last_tested_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now'), last_test_ok = ?2, last_test_message = ?3
+             WHERE uuid = ?1",
+            params![uuid, ok, message],
+        )?;
+        Ok(count > 0)
+    }
+
+    /// Removes a connection. Its secret stays in the vault, as other
+    /// connections may share it.
+    pub fn delete_connection(&self, uuid: &str) -> Result<bool> {
+        let count = self.conn.execute("DELETE FROM connections WHERE uuid = ?1", params![uuid])?;
+        Ok(count > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connections_scoped_to_workspaces() {
+        let db_path = std::env::temp_dir().join("test_novem_connections.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        db.conn
+            .execute_batch(
+                "INSERT INTO users (id, uuid, email, username) VALUES (1, 'u1', 'a@example.com', 'ana');
+                 INSERT INTO workspaces (id, uuid, name, owner_id) VALUES (1, 'ws1', 'Team', 1);
+                 INSERT INTO workspaces (id, uuid, name, owner_id) VALUES (2, 'ws2', 'Other', 1);",
+            )
+            .unwrap();
+
+        let new = |workspace_id: Option<i64>, name: &str, kind: &str| NewDataConnection {
+            workspace_id,
+            name: name.to_string(),
+            kind: kind.to_string(),
+            host: Some("db.internal".to_string()),
+            port: Some(5432),
+            database: Some("sales".to_string()),
+            username: Some("ana".to_string()),
+            secret_name: Some("warehouse".to_string()),
+            options: None,
+        };
+
+        db.create_connection("c1", &new(Some(1), "Warehouse", "postgres")).unwrap();
+        db.create_connection("c2", &new(Some(2), "Billing", "mysql")).unwrap();
+        db.create_connection("c3", &new(None, "Lake", "s3")).unwrap();
+        assert!(db.create_connection("c4", &new(None, "Sheet", "excel")).is_err());
+
+        let names: Vec<_> = db.list_connections(Some(1)).unwrap().into_iter().map(|c| c.name).collect();
+        assert_eq!(names, ["Lake", "Warehouse"]);
+        assert_eq!(db.list_connections(None).unwrap().len(), 3);
+
+        assert!(db.record_connection_test("c1", false, Some("connection refused")).unwrap());
+        let tested = db.get_connection("c1").unwrap().unwrap();
+        assert_eq!(tested.last_test_ok, Some(false));
+        assert!(tested.last_tested_at.is_some());
+        assert_eq!(tested.options, "{}");
+
+        assert!(db.delete_connection("c1").unwrap());
+        assert!(db.get_connection("c1").unwrap().is_none());
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
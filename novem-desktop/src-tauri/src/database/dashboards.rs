@@ -0,0 +1,109 @@
+use anyhow::Result;
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+use super::LocalDatabase;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dashboard {
+    pub id: i64,
+    pub uuid: String,
+    pub notebook_uuid: String,
+    pub name: String,
+    pub layout_spec: String, // JSON
+    pub definition: String, // JSON
+    pub version: i64,
+    pub auto_republish: bool,
+    pub published_at: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub sync_status: String,
+}
+
+const DASHBOARD_COLUMNS: &str =
+    "id, uuid, notebook_uuid, name, layout_spec, definition, version, auto_republish,
+     published_at, created_at, updated_at, sync_status";
+
+fn dashboard_from_row(row: &Row) -> rusqlite::Result<Dashboard> {
+    Ok(Dashboard {
+        id: row.get(0)?,
+        uuid: row.get(1)?,
+        notebook_uuid: row.get(2)?,
+        name: row.get(3)?,
+        layout_spec: row.get(4)?,
+        definition: row.get(5)?,
+        version: row.get(6)?,
+        auto_republish: row.get(7)?,
+        published_at: row.get(8)?,
+        created_at: row.get(9)?,
+        updated_at: row.get(10)?,
+        sync_status: row.get(11)?,
+    })
+}
+
+impl LocalDatabase {
+    // Dashboard operations
+
+    /// Publishes a notebook's dashboard. Re-publishing keeps the dashboard's
+    /// UUID and bumps its version.
+    pub fn save_dashboard(
+        &self,
+        uuid: &str,
+        notebook_uuid: &str,
+        name: &str,
+        layout_spec: &str,
+        definition: &str,
+    ) -> Result<Dashboard> {
+        self.conn.execute(
+            "INSERT INTO dashboards (uuid, notebook_uuid, name, layout_spec, definition)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(notebook_uuid) DO UPDATE SET
+                name = excluded.name,
+                layout_spec = excluded.layout_spec,
+                definition = excluded.definition,
+                version = version + 1,
+                published_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now'),
+                updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now'),
+                sync_status = 'pending'",
+            params![uuid, notebook_uuid, name, layout_spec, definition],
+        )?;
+
+        self.get_dashboard_for_notebook(notebook_uuid)?
+            .ok_or_else(|| anyhow::anyhow!("Dashboard for notebook {} missing after save", notebook_uuid))
+    }
+
+    pub fn get_dashboard(&self, uuid: &str) -> Result<Option<Dashboard>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM dashboards WHERE uuid = ?1",
+            DASHBOARD_COLUMNS
+        ))?;
+
+        let dashboard = stmt.query_row(params![uuid], dashboard_from_row).optional()?;
+
+        Ok(dashboard)
+    }
+
+    pub fn get_dashboard_for_notebook(&self, notebook_uuid: &str) -> Result<Option<Dashboard>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM dashboards WHERE notebook_uuid = ?1",
+            DASHBOARD_COLUMNS
+        ))?;
+
+        let dashboard = stmt.query_row(params![notebook_uuid], dashboard_from_row).optional()?;
+
+        Ok(dashboard)
+    }
+
+    pub fn list_dashboards(&self) -> Result<Vec<Dashboard>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM dashboards ORDER BY published_at DESC",
+            DASHBOARD_COLUMNS
+        ))?;
+
+        let dashboards = stmt
+            .query_map([], dashboard_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(dashboards)
+    }
+}
@@ -0,0 +1,354 @@
+use anyhow::Result;
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+use super::LocalDatabase;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dataset {
+    pub id: i64,
+    pub uuid: String,
+    pub project_id: i64,
+    pub name: String,
+    pub file_path: String,
+    pub format: String, // 'csv', 'parquet'
+    pub row_count: Option<i64>,
+    pub size_bytes: i64,
+    pub parent_uuid: Option<String>,
+    pub default_sample_uuid: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub is_active: bool,
+    pub sync_status: String,
+    pub last_synced_at: Option<String>,
+    #[serde(default)]
+    pub version: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewDataset {
+    pub uuid: String,
+    pub project_id: i64,
+    pub name: String,
+    pub file_path: String,
+    pub format: String,
+    pub row_count: Option<i64>,
+    pub size_bytes: i64,
+    pub parent_uuid: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatasetLineage {
+    pub id: i64,
+    pub dataset_uuid: String,
+    pub parent_uuid: String,
+    pub operation: String, // 'sample', ...
+    pub params: String, // JSON
+    pub created_at: String,
+}
+
+/// Persisted sketch state for one column. `sketch` is JSON; `hll` holds the
+/// raw HyperLogLog registers.
+#[derive(Debug, Clone)]
+pub struct ColumnStatsRecord {
+    pub column_name: String,
+    pub position: i64,
+    pub sketch: String, // JSON
+    pub hll: Vec<u8>,
+}
+
+/// Column-level schema of a dataset, derived from its sketches whenever they
+/// are saved, so the UI can list columns without reading the file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatasetColumn {
+    pub position: i64,
+    pub name: String,
+    pub dtype: String,
+    pub null_count: i64,
+    /// Rendered as text; numbers and strings alike
+    pub min_value: Option<String>,
+    pub max_value: Option<String>,
+    /// Approximate distinct count
+    pub cardinality: i64,
+}
+
+const DATASET_COLUMNS: &str =
+    "id, uuid, project_id, name, file_path, format, row_count, size_bytes, parent_uuid,
+     default_sample_uuid, created_at, updated_at, is_active, sync_status, last_synced_at, version";
+
+fn dataset_from_row(row: &Row) -> rusqlite::Result<Dataset> {
+    Ok(Dataset {
+        id: row.get(0)?,
+        uuid: row.get(1)?,
+        project_id: row.get(2)?,
+        name: row.get(3)?,
+        file_path: row.get(4)?,
+        format: row.get(5)?,
+        row_count: row.get(6)?,
+        size_bytes: row.get(7)?,
+        parent_uuid: row.get(8)?,
+        default_sample_uuid: row.get(9)?,
+        created_at: row.get(10)?,
+        updated_at: row.get(11)?,
+        is_active: row.get(12)?,
+        sync_status: row.get(13)?,
+        last_synced_at: row.get(14)?,
+        version: row.get(15)?,
+    })
+}
+
+impl LocalDatabase {
+    // Dataset operations
+    pub fn create_dataset(&self, dataset: &NewDataset) -> Result<Dataset> {
+        self.conn.execute(
+            "INSERT INTO datasets (uuid, project_id, name, file_path, format, row_count, size_bytes, parent_uuid)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                &dataset.uuid,
+                dataset.project_id,
+                &dataset.name,
+                &dataset.file_path,
+                &dataset.format,
+                dataset.row_count,
+                dataset.size_bytes,
+                &dataset.parent_uuid,
+            ],
+        )?;
+
+        self.get_dataset(&dataset.uuid)?
+            .ok_or_else(|| anyhow::anyhow!("Dataset {} missing after insert", dataset.uuid))
+    }
+
+    pub fn get_dataset(&self, uuid: &str) -> Result<Option<Dataset>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM datasets WHERE uuid = ?1 AND is_active = 1",
+            DATASET_COLUMNS
+        ))?;
+
+        let dataset = stmt.query_row(params![uuid], dataset_from_row).optional()?;
+
+        Ok(dataset)
+    }
+
+    pub fn list_datasets(&self, project_id: i64) -> Result<Vec<Dataset>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM datasets
+             WHERE project_id = ?1 AND is_active = 1
+             ORDER BY updated_at DESC",
+            DATASET_COLUMNS
+        ))?;
+
+        let datasets = stmt
+            .query_map(params![project_id], dataset_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(datasets)
+    }
+
+    /// Points exploration of `uuid` at a smaller derived sample by default.
+    pub fn set_default_sample(&self, uuid: &str, sample_uuid: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE datasets
+             SET default_sample_uuid = ?1, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE uuid = ?2",
+            params![sample_uuid, uuid],
+        )?;
+        Ok(())
+    }
+
+    /// Records new row count and file size after the dataset file changed.
+    pub fn update_dataset_size(&self, uuid: &str, row_count: i64, size_bytes: i64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE datasets
+             SET row_count = ?1, size_bytes = ?2, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now'), sync_status = 'pending'
+             WHERE uuid = ?3",
+            params![row_count, size_bytes, uuid],
+        )?;
+        Ok(())
+    }
+
+    /// Points a dataset at a rewritten file, e.g. after a refresh. Column
+    /// sketches describe the old data and are dropped.
+    pub fn replace_dataset_file(&self, uuid: &str, file_path: &str, format: &str, row_count: i64, size_bytes: i64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE datasets
+             SET file_path = ?1, format = ?2, row_count = ?3, size_bytes = ?4,
+                 updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now'), sync_status = 'pending'
+             WHERE uuid = ?5",
+            params![file_path, format, row_count, size_bytes, uuid],
+        )?;
+        self.clear_dataset_profile(uuid)
+    }
+
+    // Lineage operations
+    pub fn add_dataset_lineage(&self, dataset_uuid: &str, parent_uuid: &str, operation: &str, params_json: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO dataset_lineage (dataset_uuid, parent_uuid, operation, params)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![dataset_uuid, parent_uuid, operation, params_json],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_dataset_lineage(&self, dataset_uuid: &str) -> Result<Vec<DatasetLineage>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, dataset_uuid, parent_uuid, operation, params, created_at
+             FROM dataset_lineage
+             WHERE dataset_uuid = ?1
+             ORDER BY created_at ASC"
+        )?;
+
+        let lineage = stmt
+            .query_map(params![dataset_uuid], |row| {
+                Ok(DatasetLineage {
+                    id: row.get(0)?,
+                    dataset_uuid: row.get(1)?,
+                    parent_uuid: row.get(2)?,
+                    operation: row.get(3)?,
+                    params: row.get(4)?,
+                    created_at: row.get(5)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(lineage)
+    }
+
+    // Column statistics operations
+    pub fn get_column_stats(&self, dataset_uuid: &str) -> Result<Vec<ColumnStatsRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT column_name, position, sketch, hll
+             FROM column_stats
+             WHERE dataset_uuid = ?1
+             ORDER BY position ASC"
+        )?;
+
+        let stats = stmt
+            .query_map(params![dataset_uuid], |row| {
+                Ok(ColumnStatsRecord {
+                    column_name: row.get(0)?,
+                    position: row.get(1)?,
+                    sketch: row.get(2)?,
+                    hll: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(stats)
+    }
+
+    /// Replaces the stored sketches of a dataset in one transaction.
+    pub fn save_column_stats(&self, dataset_uuid: &str, stats: &[ColumnStatsRecord]) -> Result<()> {
+        let tx = self.begin()?;
+
+        tx.execute("DELETE FROM column_stats WHERE dataset_uuid = ?1", params![dataset_uuid])?;
+        for record in stats {
+            tx.execute(
+                "INSERT INTO column_stats (dataset_uuid, column_name, position, sketch, hll)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![dataset_uuid, &record.column_name, record.position, &record.sketch, &record.hll],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    // Schema operations
+    pub fn get_dataset_columns(&self, dataset_uuid: &str) -> Result<Vec<DatasetColumn>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT position, name, dtype, null_count, min_value, max_value, cardinality
+             FROM dataset_columns
+             WHERE dataset_uuid = ?1
+             ORDER BY position ASC"
+        )?;
+
+        let columns = stmt
+            .query_map(params![dataset_uuid], |row| {
+                Ok(DatasetColumn {
+                    position: row.get(0)?,
+                    name: row.get(1)?,
+                    dtype: row.get(2)?,
+                    null_count: row.get(3)?,
+                    min_value: row.get(4)?,
+                    max_value: row.get(5)?,
+                    cardinality: row.get(6)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(columns)
+    }
+
+    /// Replaces the stored schema of a dataset in one transaction.
+    pub fn save_dataset_columns(&self, dataset_uuid: &str, columns: &[DatasetColumn]) -> Result<()> {
+        let tx = self.begin()?;
+
+        tx.execute("DELETE FROM dataset_columns WHERE dataset_uuid = ?1", params![dataset_uuid])?;
+        for column in columns {
+            tx.execute(
+                "INSERT INTO dataset_columns (dataset_uuid, position, name, dtype, null_count, min_value, max_value, cardinality)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    dataset_uuid,
+                    column.position,
+                    &column.name,
+                    &column.dtype,
+                    column.null_count,
+                    &column.min_value,
+                    &column.max_value,
+                    column.cardinality,
+                ],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Drops the sketches and schema of a dataset whose data changed under
+    /// them; both are rebuilt the next time the dataset is profiled.
+    pub fn clear_dataset_profile(&self, dataset_uuid: &str) -> Result<()> {
+        self.atomically(|db| {
+            db.save_column_stats(dataset_uuid, &[])?;
+            db.save_dataset_columns(dataset_uuid, &[])
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dataset_columns_replaced_and_cleared() {
+        let db_path = std::env::temp_dir().join("test_novem_dataset_columns.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        let column = |position: i64, name: &str| DatasetColumn {
+            position,
+            name: name.to_string(),
+            dtype: "Int64".to_string(),
+            null_count: 2,
+            min_value: Some("1".to_string()),
+            max_value: Some("9".to_string()),
+            cardinality: 8,
+        };
+
+        db.save_dataset_columns("d1", &[column(1, "b"), column(0, "a")]).unwrap();
+        db.save_dataset_columns("d2", &[column(0, "x")]).unwrap();
+        let names: Vec<_> = db.get_dataset_columns("d1").unwrap().into_iter().map(|c| c.name).collect();
+        assert_eq!(names, ["a", "b"]);
+
+        db.save_dataset_columns("d1", &[column(0, "c")]).unwrap();
+        assert_eq!(db.get_dataset_columns("d1").unwrap().len(), 1);
+
+        db.clear_dataset_profile("d1").unwrap();
+        assert!(db.get_dataset_columns("d1").unwrap().is_empty());
+        assert_eq!(db.get_dataset_columns("d2").unwrap().len(), 1);
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
@@ -0,0 +1,353 @@
+use anyhow::Result;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+
+use super::LocalDatabase;
+
+/// Local data hanging off a workspace's rows (or every row on the device),
+/// collected before anything is removed.
+#[derive(Debug, Clone, Default)]
+pub struct LocalFootprint {
+    pub workspace_id: Option<i64>,
+    pub project_ids: Vec<i64>,
+    pub dataset_uuids: Vec<String>,
+    pub dataset_files: Vec<String>,
+    pub dashboard_uuids: Vec<String>,
+    /// Managed copies of the projects' attachments
+    pub attachment_files: Vec<String>,
+    /// Every entity UUID in scope, for tables keyed by `entity_uuid`
+    pub entity_uuids: Vec<String>,
+}
+
+fn strings(conn: &Connection, sql: &str, args: &[i64]) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt
+        .query_map(params_from_iter(args), |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+pub(super) fn placeholders(count: usize) -> String {
+    vec!["?"; count].join(", ")
+}
+
+// Trashed rows are included throughout: deprovisioning removes what is on
+// disk, not what is visible
+pub(super) fn collect_footprint(conn: &Connection, workspace_uuid: Option<&str>) -> Result<Option<LocalFootprint>> {
+    let mut footprint = LocalFootprint::default();
+
+    let project_sql = match workspace_uuid {
+        Some(uuid) => {
+            let workspace_id: Option<i64> = conn
+                .query_row("SELECT id FROM workspaces WHERE uuid = ?1", params![uuid], |row| row.get(0))
+                .optional()?;
+            let Some(workspace_id) = workspace_id else {
+                return Ok(None);
+            };
+            footprint.workspace_id = Some(workspace_id);
+            footprint.entity_uuids.push(uuid.to_string());
+            format!("SELECT id, uuid FROM projects WHERE workspace_id = {}", workspace_id)
+        }
+        None => {
+            footprint.entity_uuids.extend(strings(conn, "SELECT uuid FROM workspaces", &[])?);
+            "SELECT id, uuid FROM projects".to_string()
+        }
+    };
+
+    let mut stmt = conn.prepare(&project_sql)?;
+    let projects = stmt
+        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    add_projects(conn, &mut footprint, projects)?;
+
+    Ok(Some(footprint))
+}
+
+/// The footprint of one project, trashed or not.
+pub(super) fn project_footprint(conn: &Connection, project_id: i64, project_uuid: &str) -> Result<LocalFootprint> {
+    let mut footprint = LocalFootprint::default();
+    add_projects(conn, &mut footprint, vec![(project_id, project_uuid.to_string())])?;
+    Ok(footprint)
+}
+
+/// Adds projects and the notebooks, datasets and dashboards under them.
+fn add_projects(conn: &Connection, footprint: &mut LocalFootprint, projects: Vec<(i64, String)>) -> Result<()> {
+    for (id, uuid) in projects {
+        footprint.project_ids.push(id);
+        footprint.entity_uuids.push(uuid);
+    }
+
+    if footprint.project_ids.is_empty() {
+        return Ok(());
+    }
+    let in_projects = placeholders(footprint.project_ids.len());
+    let ids = &footprint.project_ids;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT uuid, file_path FROM datasets WHERE project_id IN ({})",
+        in_projects
+    ))?;
+    let datasets = stmt
+        .query_map(params_from_iter(ids), |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    for (uuid, file_path) in datasets {
+        footprint.entity_uuids.push(uuid.clone());
+        footprint.dataset_uuids.push(uuid);
+        footprint.dataset_files.push(file_path);
+    }
+
+    footprint.entity_uuids.extend(strings(
+        conn,
+        &format!("SELECT uuid FROM notebooks WHERE project_id IN ({})", in_projects),
+        ids,
+    )?);
+    footprint.dashboard_uuids = strings(
+        conn,
+        &format!(
+            "SELECT d.uuid FROM dashboards d JOIN notebooks n ON n.uuid = d.notebook_uuid
+             WHERE n.project_id IN ({})",
+            in_projects
+        ),
+        ids,
+    )?;
+    footprint.entity_uuids.extend(footprint.dashboard_uuids.iter().cloned());
+    footprint.attachment_files = strings(
+        conn,
+        &format!("SELECT file_path FROM attachments WHERE project_id IN ({})", in_projects),
+        ids,
+    )?;
+
+    Ok(())
+}
+
+/// Deletes every row a footprint covers, plus the rows of `entity_tables`
+/// keyed by its entity UUIDs. Returns the number of rows removed.
+pub(super) fn remove_footprint(conn: &Connection, footprint: &LocalFootprint, entity_tables: &[&str]) -> Result<usize> {
+    let mut removed = 0;
+
+    if !footprint.dataset_uuids.is_empty() {
+        let in_datasets = placeholders(footprint.dataset_uuids.len());
+        for table in ["column_stats", "dataset_columns", "dataset_lineage", "dataset_recipes", "dataset_refresh_schedules"] {
+            removed += conn.execute(
+                &format!("DELETE FROM {} WHERE dataset_uuid IN ({})", table, in_datasets),
+                params_from_iter(&footprint.dataset_uuids),
+            )?;
+        }
+        removed += conn.execute(
+            &format!("DELETE FROM datasets WHERE uuid IN ({})", in_datasets),
+            params_from_iter(&footprint.dataset_uuids),
+        )?;
+    }
+
+    if !footprint.project_ids.is_empty() {
+        let in_projects = placeholders(footprint.project_ids.len());
+        let ids = &footprint.project_ids;
+
+        removed += conn.execute(
+            &format!(
+                "DELETE FROM dashboards WHERE notebook_uuid IN
+                    (SELECT uuid FROM notebooks WHERE project_id IN ({}))",
+                in_projects
+            ),
+            params_from_iter(ids),
+        )?;
+        removed += conn.execute(
+            &format!(
+                "DELETE FROM comments WHERE entity_type = 'notebook_cell' AND entity_uuid IN
+                    (SELECT c.uuid FROM notebook_cells c
+                     JOIN notebooks n ON n.id = c.notebook_id
+                     WHERE n.project_id IN ({}))",
+                in_projects
+            ),
+            params_from_iter(ids),
+        )?;
+        removed += conn.execute(
+            &format!(
+                "DELETE FROM executions WHERE notebook_uuid IN
+                    (SELECT uuid FROM notebooks WHERE project_id IN ({}))",
+                in_projects
+            ),
+            params_from_iter(ids),
+        )?;
+        removed += conn.execute(
+            &format!(
+                "DELETE FROM notebook_cells WHERE notebook_id IN
+                    (SELECT id FROM notebooks WHERE project_id IN ({}))",
+                in_projects
+            ),
+            params_from_iter(ids),
+        )?;
+        for table in [
+            "query_history",
+            "engine_metrics",
+            "jobs",
+            "project_tags",
+            "archived_projects",
+            "attachments",
+            "datasets",
+            "notebooks",
+        ] {
+            removed += conn.execute(
+                &format!("DELETE FROM {} WHERE project_id IN ({})", table, in_projects),
+                params_from_iter(ids),
+            )?;
+        }
+        removed += conn.execute(
+            &format!("DELETE FROM projects WHERE id IN ({})", in_projects),
+            params_from_iter(ids),
+        )?;
+    }
+
+    let in_entities = placeholders(footprint.entity_uuids.len());
+    for table in entity_tables {
+        removed += conn.execute(
+            &format!("DELETE FROM {} WHERE entity_uuid IN ({})", table, in_entities),
+            params_from_iter(&footprint.entity_uuids),
+        )?;
+    }
+
+    if let Some(workspace_id) = footprint.workspace_id {
+        removed += conn.execute("DELETE FROM engine_env WHERE workspace_id = ?1", params![workspace_id])?;
+        removed += conn.execute("DELETE FROM connections WHERE workspace_id = ?1", params![workspace_id])?;
+        removed += conn.execute("DELETE FROM workspace_members WHERE workspace_id = ?1", params![workspace_id])?;
+        removed += conn.execute("DELETE FROM archived_workspaces WHERE workspace_id = ?1", params![workspace_id])?;
+        removed += conn.execute("DELETE FROM projects WHERE workspace_id = ?1", params![workspace_id])?;
+        removed += conn.execute("DELETE FROM workspaces WHERE id = ?1", params![workspace_id])?;
+    }
+    Ok(removed)
+}
+
+impl LocalDatabase {
+    // Deprovisioning operations
+    /// `None` when the workspace isn't on this device.
+    pub fn local_footprint(&self, workspace_uuid: Option<&str>) -> Result<Option<LocalFootprint>> {
+        collect_footprint(&self.conn, workspace_uuid)
+    }
+
+    /// Removes a workspace and everything under it from this device only.
+    /// Unlike a trash delete nothing is tombstoned or queued for sync: the
+    /// backend copy is untouched. Returns the number of rows removed.
+    pub fn purge_workspace(&self, workspace_uuid: &str) -> Result<usize> {
+        let tx = self.begin()?;
+
+        let Some(footprint) = collect_footprint(&tx, Some(workspace_uuid))? else {
+            return Ok(0);
+        };
+        let removed = remove_footprint(
+            &tx,
+            &footprint,
+            &[
+                "sync_queue",
+                "transfers",
+                "tombstones",
+                "entity_lineage",
+                "activity_log",
+                "recent_items",
+                "comments",
+                "conflicts",
+                "share_links",
+            ],
+        )?;
+
+        tx.commit()?;
+        Ok(removed)
+    }
+
+    /// Deletes engine environment variables, which commonly hold API keys
+    /// and connection strings: one workspace's, or all of them.
+    pub fn clear_engine_env(&self, workspace_uuid: Option<&str>) -> Result<usize> {
+        let removed = match workspace_uuid {
+            Some(uuid) => self.conn.execute(
+                "DELETE FROM engine_env WHERE workspace_id IN (SELECT id FROM workspaces WHERE uuid = ?1)",
+                params![uuid],
+            )?,
+            None => self.conn.execute("DELETE FROM engine_env", [])?,
+        };
+        Ok(removed)
+    }
+
+    pub fn count_engine_env(&self, workspace_uuid: Option<&str>) -> Result<i64> {
+        let count = match workspace_uuid {
+            Some(uuid) => self.conn.query_row(
+                "SELECT COUNT(*) FROM engine_env WHERE workspace_id IN (SELECT id FROM workspaces WHERE uuid = ?1)",
+                params![uuid],
+                |row| row.get(0),
+            )?,
+            None => self.conn.query_row("SELECT COUNT(*) FROM engine_env", [], |row| row.get(0))?,
+        };
+        Ok(count)
+    }
+
+    pub fn clear_settings(&self) -> Result<usize> {
+        Ok(self.conn.execute("DELETE FROM settings", [])?)
+    }
+
+    pub fn count_settings(&self) -> Result<i64> {
+        Ok(self.conn.query_row("SELECT COUNT(*) FROM settings", [], |row| row.get(0))?)
+    }
+
+    /// Rows still referring to a workspace, to verify a purge.
+    pub fn count_workspace_rows(&self, workspace_uuid: &str) -> Result<i64> {
+        let count = self.conn.query_row(
+            "SELECT (SELECT COUNT(*) FROM workspaces WHERE uuid = ?1)
+                  + (SELECT COUNT(*) FROM projects WHERE workspace_id IN (SELECT id FROM workspaces WHERE uuid = ?1))",
+            params![workspace_uuid],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_purge_workspace() {
+        let db_path = std::env::temp_dir().join("test_novem_deprovision.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        db.conn
+            .execute_batch(
+                "INSERT INTO users (id, uuid, email, username) VALUES (1, 'u1', 'a@example.com', 'ana');
+                 INSERT INTO workspaces (id, uuid, name, owner_id) VALUES (1, 'ws1', 'Team', 1);
+                 INSERT INTO workspaces (id, uuid, name, owner_id) VALUES (2, 'ws2', 'Other', 1);
+                 INSERT INTO projects (id, uuid, workspace_id, name, owner_id) VALUES (1, 'p1', 1, 'Sales', 1);
+                 INSERT INTO projects (id, uuid, workspace_id, name, owner_id, is_active) VALUES (2, 'p2', 1, 'Old', 1, 0);
+                 INSERT INTO projects (id, uuid, workspace_id, name, owner_id) VALUES (3, 'p3', 2, 'Kept', 1);
+                 INSERT INTO notebooks (id, uuid, project_id, name) VALUES (1, 'nb', 1, 'Analysis');
+                 INSERT INTO notebook_cells (uuid, notebook_id, position) VALUES ('c1', 1, 0);
+                 INSERT INTO comments (uuid, entity_type, entity_uuid, body) VALUES
+                     ('m1', 'notebook_cell', 'c1', 'Why a left join?'),
+                     ('m2', 'project', 'p1', 'Ready for review'),
+                     ('m3', 'project', 'p3', 'Kept');
+                 INSERT INTO datasets (uuid, project_id, name, file_path, format)
+                    VALUES ('ds', 2, 'orders', '/tmp/orders.parquet', 'parquet');
+                 INSERT INTO datasets (uuid, project_id, name, file_path, format)
+                    VALUES ('kept', 3, 'leads', '/tmp/leads.parquet', 'parquet');
+                 INSERT INTO engine_env (workspace_id, name, value) VALUES (1, 'API_KEY', 'secret');
+                 INSERT INTO engine_env (workspace_id, name, value) VALUES (NULL, 'TZ', 'UTC');",
+            )
+            .unwrap();
+        db.add_to_sync_queue("notebook", "nb", "update", "{}").unwrap();
+
+        let footprint = db.local_footprint(Some("ws1")).unwrap().unwrap();
+        assert_eq!(footprint.project_ids, vec![1, 2]);
+        assert_eq!(footprint.dataset_files, vec!["/tmp/orders.parquet"]);
+        assert!(db.local_footprint(Some("missing")).unwrap().is_none());
+
+        assert!(db.purge_workspace("ws1").unwrap() > 0);
+        assert_eq!(db.count_workspace_rows("ws1").unwrap(), 0);
+        assert!(db.get_dataset("kept").unwrap().is_some());
+        assert_eq!(db.get_engine_env(None).unwrap().len(), 1);
+        assert!(db.get_comment_by_uuid("m1").unwrap().is_none());
+        assert!(db.get_comment_by_uuid("m2").unwrap().is_none());
+        assert!(db.get_comment_by_uuid("m3").unwrap().is_some());
+
+        let pending: i64 = db.conn.query_row("SELECT COUNT(*) FROM sync_queue", [], |row| row.get(0)).unwrap();
+        assert_eq!(pending, 0);
+
+        drop(db);
+        let _ = std::fs::remove_file(&db_path);
+    }
+}
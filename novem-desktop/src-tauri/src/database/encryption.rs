@@ -0,0 +1,153 @@
+use anyhow::{Context, Result};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use rusqlite::Connection;
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+const KEYRING_SERVICE: &str = "com.novem.app";
+const KEYRING_ACCOUNT: &str = "local-database";
+const SECRET_LENGTH: usize = 48;
+
+/// What every unencrypted SQLite file starts with.
+const PLAINTEXT_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// Key the local database is encrypted with. Derived from a random secret
+/// kept in the OS keyring (Keychain, Credential Manager, Secret Service),
+/// so a copy of `novem.db` taken off the machine can't be read.
+#[derive(Clone)]
+pub struct DatabaseKey([u8; 32]);
+
+impl DatabaseKey {
+    /// Reads the secret from the keyring, creating it on first launch.
+    pub fn load_or_create() -> Result<Self> {
+        let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_ACCOUNT).context("Failed to open OS keyring")?;
+
+        let secret = match entry.get_password() {
+            Ok(secret) => secret,
+            Err(keyring::Error::NoEntry) => {
+                let secret: String = rand::thread_rng()
+                    .sample_iter(&Alphanumeric)
+                    .take(SECRET_LENGTH)
+                    .map(char::from)
+                    .collect();
+                entry.set_password(&secret).context("Failed to store database secret in OS keyring")?;
+                println!("[NOVEM] Created local database key");
+                secret
+            }
+            Err(e) => return Err(e).context("Failed to read database secret from OS keyring"),
+        };
+
+        Ok(Self::derive(&secret))
+    }
+
+    fn derive(secret: &str) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(b"novem-local-database:");
+        hasher.update(secret.as_bytes());
+        Self(hasher.finalize().into())
+    }
+
+    /// SQLCipher's raw key literal; the secret is random already, so its
+    /// own slow passphrase derivation is skipped.
+    fn sql_literal(&self) -> String {
+        let hex: String = self.0.iter().map(|byte| format!("{:02x}", byte)).collect();
+        format!("\"x'{}'\"", hex)
+    }
+}
+
+impl fmt::Debug for DatabaseKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("DatabaseKey(..)")
+    }
+}
+
+/// Unlocks a freshly opened connection. Must run before anything else
+/// touches the database.
+pub(super) fn unlock(conn: &Connection, key: &DatabaseKey) -> rusqlite::Result<()> {
+    conn.execute_batch(&format!("PRAGMA key = {};", key.sql_literal()))
+}
+
+fn is_plaintext(db_path: &Path) -> Result<bool> {
+    let mut header = [0u8; 16];
+    let mut file = match std::fs::File::open(db_path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e).context(format!("Failed to open {:?}", db_path)),
+    };
+
+    match file.read_exact(&mut header) {
+        Ok(()) => Ok(&header == PLAINTEXT_HEADER),
+        // Empty or truncated; SQLite treats it as a new database
+        Err(_) => Ok(false),
+    }
+}
+
+/// Encrypts a database written before encryption, in place: exported into
+/// an encrypted copy that then replaces the original. Does nothing if the
+/// file is missing or already encrypted.
+pub(super) fn encrypt_plaintext(db_path: &Path, key: &DatabaseKey) -> Result<()> {
+    if !is_plaintext(db_path)? {
+        return Ok(());
+    }
+
+    let mut staging = db_path.as_os_str().to_owned();
+    staging.push(".encrypting");
+    let staging = PathBuf::from(staging);
+    let _ = std::fs::remove_file(&staging);
+
+    {
+        let conn = Connection::open(db_path).context(format!("Failed to open {:?}", db_path))?;
+        // `sqlcipher_export` copies everything but the schema version
+        let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+        conn.execute(
+            &format!("ATTACH DATABASE ?1 AS encrypted KEY {}", key.sql_literal()),
+            [staging.to_string_lossy()],
+        )?;
+        conn.query_row("SELECT sqlcipher_export('encrypted')", [], |_| Ok(()))?;
+        conn.execute_batch(&format!("PRAGMA encrypted.user_version = {}; DETACH DATABASE encrypted;", version))?;
+    }
+
+    std::fs::rename(&staging, db_path).context("Failed to replace unencrypted database")?;
+    for suffix in ["-journal", "-wal", "-shm"] {
+        let mut side_file = db_path.as_os_str().to_owned();
+        side_file.push(suffix);
+        let _ = std::fs::remove_file(PathBuf::from(side_file));
+    }
+
+    println!("[NOVEM] Encrypted existing local database");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::LocalDatabase;
+
+    #[test]
+    fn test_plaintext_database_is_encrypted_in_place() {
+        let db_path = std::env::temp_dir().join("test_novem_encryption.db");
+        let _ = std::fs::remove_file(&db_path);
+        let key = DatabaseKey::derive("secret");
+
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+        db.set_setting("locale", "\"fr-FR\"").unwrap();
+        drop(db);
+        assert!(is_plaintext(&db_path).unwrap());
+
+        let db = LocalDatabase::new(db_path.clone(), Some(&key)).unwrap();
+        assert_eq!(db.get_setting("locale").unwrap().as_deref(), Some("\"fr-FR\""));
+        drop(db);
+        assert!(!is_plaintext(&db_path).unwrap());
+
+        // Reopening needs the same key
+        assert!(LocalDatabase::new(db_path.clone(), Some(&key)).is_ok());
+        assert!(LocalDatabase::new(db_path.clone(), None).is_err());
+        assert!(LocalDatabase::new(db_path.clone(), Some(&DatabaseKey::derive("other"))).is_err());
+
+        std::fs::remove_file(db_path).ok();
+    }
+}
@@ -0,0 +1,58 @@
+use anyhow::Result;
+use rusqlite::{params, OptionalExtension};
+use std::collections::BTreeMap;
+
+use super::LocalDatabase;
+
+impl LocalDatabase {
+    // Engine environment operations
+
+    /// Variables injected into engines; `None` is the global set applied to
+    /// every engine, a workspace ID the set for that workspace's projects.
+    pub fn get_engine_env(&self, workspace_id: Option<i64>) -> Result<BTreeMap<String, String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT name, value FROM engine_env WHERE workspace_id IS ?1 ORDER BY name"
+        )?;
+
+        let vars = stmt
+            .query_map(params![workspace_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<BTreeMap<_, _>, _>>()?;
+
+        Ok(vars)
+    }
+
+    /// Replaces the whole variable set of a scope.
+    pub fn set_engine_env(&self, workspace_id: Option<i64>, vars: &BTreeMap<String, String>) -> Result<()> {
+        let tx = self.begin()?;
+
+        tx.execute("DELETE FROM engine_env WHERE workspace_id IS ?1", params![workspace_id])?;
+        for (name, value) in vars {
+            tx.execute(
+                "INSERT INTO engine_env (workspace_id, name, value) VALUES (?1, ?2, ?3)",
+                params![workspace_id, name, value],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Global variables overlaid with those of the project's workspace.
+    pub fn get_project_engine_env(&self, project_id: i64) -> Result<BTreeMap<String, String>> {
+        let workspace_id: Option<i64> = self
+            .conn
+            .query_row(
+                "SELECT workspace_id FROM projects WHERE id = ?1",
+                params![project_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        let mut vars = self.get_engine_env(None)?;
+        if let Some(workspace_id) = workspace_id {
+            vars.extend(self.get_engine_env(Some(workspace_id))?);
+        }
+
+        Ok(vars)
+    }
+}
@@ -0,0 +1,125 @@
+use anyhow::Result;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+
+use super::LocalDatabase;
+
+/// CPU and memory of one engine's process tree at one moment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineMetric {
+    pub project_id: Option<i64>, // None = shared engine
+    pub pid: u32,
+    pub sampled_at: i64, // unix seconds
+    /// Summed over the tree, so it can exceed 100 on multi-core machines
+    pub cpu_percent: f64,
+    pub memory_mb: f64,
+    pub process_count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineMetricPoint {
+    pub timestamp: i64, // unix seconds, start of the bucket
+    pub cpu_percent: f64,
+    pub cpu_peak: f64,
+    pub memory_mb: f64,
+    pub memory_peak_mb: f64,
+}
+
+impl LocalDatabase {
+    // Engine metrics operations
+    pub fn add_engine_metric(&self, metric: &EngineMetric) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO engine_metrics (project_id, pid, sampled_at, cpu_percent, memory_mb, process_count)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                metric.project_id,
+                metric.pid,
+                metric.sampled_at,
+                metric.cpu_percent,
+                metric.memory_mb,
+                metric.process_count,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Drops samples taken before `before`, returning how many went.
+    pub fn prune_engine_metrics(&self, before: i64) -> Result<usize> {
+        let deleted = self
+            .conn
+            .execute("DELETE FROM engine_metrics WHERE sampled_at < ?1", params![before])?;
+        Ok(deleted)
+    }
+
+    /// One engine's samples since `since`, averaged into buckets of
+    /// `resolution` seconds.
+    pub fn get_engine_metrics(&self, project_id: Option<i64>, since: i64, resolution: i64) -> Result<Vec<EngineMetricPoint>> {
+        let resolution = resolution.max(1);
+        let mut stmt = self.conn.prepare(
+            "SELECT (sampled_at / ?3) * ?3 AS bucket,
+                    AVG(cpu_percent), MAX(cpu_percent),
+                    AVG(memory_mb), MAX(memory_mb)
+             FROM engine_metrics
+             WHERE project_id IS ?1 AND sampled_at >= ?2
+             GROUP BY bucket
+             ORDER BY bucket ASC"
+        )?;
+
+        let points = stmt
+            .query_map(params![project_id, since, resolution], |row| {
+                Ok(EngineMetricPoint {
+                    timestamp: row.get(0)?,
+                    cpu_percent: row.get(1)?,
+                    cpu_peak: row.get(2)?,
+                    memory_mb: row.get(3)?,
+                    memory_peak_mb: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(points)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_engine_metrics_by_engine() {
+        let db_path = std::env::temp_dir().join("test_novem_engine_metrics.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        let now = 1_000_020;
+        for i in 0..12 {
+            for project_id in [None, Some(7)] {
+                db.add_engine_metric(&EngineMetric {
+                    project_id,
+                    pid: 42,
+                    sampled_at: now - 120 + i * 10,
+                    cpu_percent: if i % 2 == 0 { 50.0 } else { 150.0 },
+                    memory_mb: if project_id.is_some() { 2048.0 } else { 512.0 },
+                    process_count: 2,
+                })
+                .unwrap();
+            }
+        }
+
+        let shared = db.get_engine_metrics(None, now - 120, 60).unwrap();
+        assert_eq!(shared.len(), 2);
+        assert_eq!(shared[0].cpu_percent, 100.0);
+        assert_eq!(shared[0].cpu_peak, 150.0);
+        assert_eq!(shared[0].memory_mb, 512.0);
+
+        let project = db.get_engine_metrics(Some(7), now - 120, 60).unwrap();
+        assert_eq!(project[1].memory_peak_mb, 2048.0);
+
+        // Half of each engine's samples are more than a minute old
+        assert_eq!(db.prune_engine_metrics(now - 60).unwrap(), 12);
+        assert_eq!(db.get_engine_metrics(None, 0, 60).unwrap().len(), 1);
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
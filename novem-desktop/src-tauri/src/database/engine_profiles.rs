@@ -0,0 +1,131 @@
+use anyhow::Result;
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::LocalDatabase;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EngineProfile {
+    pub id: i64,
+    pub name: String,
+    pub python_path: Option<String>,
+    pub extra_args: Vec<String>,
+    pub env: BTreeMap<String, String>,
+    pub working_dir: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewEngineProfile {
+    pub name: String,
+    pub python_path: Option<String>,
+    #[serde(default)]
+    pub extra_args: Vec<String>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    pub working_dir: Option<String>,
+}
+
+const PROFILE_COLUMNS: &str = "id, name, python_path, extra_args, env, working_dir, created_at";
+
+fn profile_from_row(row: &Row) -> rusqlite::Result<EngineProfile> {
+    let extra_args: String = row.get(3)?;
+    let env: String = row.get(4)?;
+
+    Ok(EngineProfile {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        python_path: row.get(2)?,
+        extra_args: serde_json::from_str(&extra_args).unwrap_or_default(),
+        env: serde_json::from_str(&env).unwrap_or_default(),
+        working_dir: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
+
+impl LocalDatabase {
+    // Engine profile operations
+    pub fn list_engine_profiles(&self) -> Result<Vec<EngineProfile>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM engine_profiles ORDER BY name COLLATE NOCASE",
+            PROFILE_COLUMNS
+        ))?;
+
+        let profiles = stmt
+            .query_map([], profile_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(profiles)
+    }
+
+    pub fn get_engine_profile(&self, id: i64) -> Result<Option<EngineProfile>> {
+        let profile = self.conn
+            .query_row(
+                &format!("SELECT {} FROM engine_profiles WHERE id = ?1", PROFILE_COLUMNS),
+                params![id],
+                profile_from_row,
+            )
+            .optional()?;
+
+        Ok(profile)
+    }
+
+    pub fn create_engine_profile(&self, profile: &NewEngineProfile) -> Result<EngineProfile> {
+        self.conn.execute(
+            "INSERT INTO engine_profiles (name, python_path, extra_args, env, working_dir)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                &profile.name,
+                &profile.python_path,
+                serde_json::to_string(&profile.extra_args)?,
+                serde_json::to_string(&profile.env)?,
+                &profile.working_dir,
+            ],
+        )?;
+
+        let id = self.conn.last_insert_rowid();
+        self.get_engine_profile(id)?
+            .ok_or_else(|| anyhow::anyhow!("Engine profile {} missing after insert", id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_engine_profiles() {
+        let db_path = std::env::temp_dir().join("test_novem_engine_profiles.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        let gpu = NewEngineProfile {
+            name: "GPU env".to_string(),
+            python_path: Some("/opt/conda/envs/gpu/bin/python".to_string()),
+            extra_args: vec!["--workers".to_string(), "2".to_string()],
+            env: BTreeMap::from([("CUDA_VISIBLE_DEVICES".to_string(), "0".to_string())]),
+            working_dir: None,
+        };
+        let created = db.create_engine_profile(&gpu).unwrap();
+        assert_eq!(created.extra_args, gpu.extra_args);
+        assert_eq!(created.env["CUDA_VISIBLE_DEVICES"], "0");
+
+        // Names are unique
+        assert!(db.create_engine_profile(&gpu).is_err());
+
+        db.create_engine_profile(&NewEngineProfile {
+            name: "minimal env".to_string(),
+            python_path: None,
+            extra_args: Vec::new(),
+            env: BTreeMap::new(),
+            working_dir: None,
+        })
+        .unwrap();
+        let names: Vec<String> = db.list_engine_profiles().unwrap().into_iter().map(|p| p.name).collect();
+        assert_eq!(names, vec!["GPU env", "minimal env"]);
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
@@ -0,0 +1,187 @@
+use anyhow::Result;
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+use super::LocalDatabase;
+
+/// One run of a notebook cell, kept so results can be traced back to the
+/// code and engine that produced them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Execution {
+    pub id: i64,
+    pub uuid: String,
+    pub notebook_uuid: String,
+    pub cell_uuid: String,
+    /// The cell's source at the time it ran
+    pub source: String,
+    pub status: String, // 'ok', 'error', 'cancelled'
+    pub duration_ms: i64,
+    /// Where the captured streams were stored, e.g. an attachment UUID
+    pub stdout_ref: Option<String>,
+    pub stderr_ref: Option<String>,
+    pub engine_version: Option<String>,
+    pub started_at: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewExecution {
+    pub cell_uuid: String,
+    pub status: String,
+    pub duration_ms: i64,
+    /// When the run began; now minus the duration when omitted
+    #[serde(default)]
+    pub started_at: Option<String>,
+    #[serde(default)]
+    pub stdout_ref: Option<String>,
+    #[serde(default)]
+    pub stderr_ref: Option<String>,
+    #[serde(default)]
+    pub engine_version: Option<String>,
+}
+
+const EXECUTION_STATUSES: &[&str] = &["ok", "error", "cancelled"];
+
+const EXECUTION_COLUMNS: &str =
+    "id, uuid, notebook_uuid, cell_uuid, source, status, duration_ms, stdout_ref, stderr_ref, engine_version,
+     started_at, created_at";
+
+fn execution_from_row(row: &Row) -> rusqlite::Result<Execution> {
+    Ok(Execution {
+        id: row.get(0)?,
+        uuid: row.get(1)?,
+        notebook_uuid: row.get(2)?,
+        cell_uuid: row.get(3)?,
+        source: row.get(4)?,
+        status: row.get(5)?,
+        duration_ms: row.get(6)?,
+        stdout_ref: row.get(7)?,
+        stderr_ref: row.get(8)?,
+        engine_version: row.get(9)?,
+        started_at: row.get(10)?,
+        created_at: row.get(11)?,
+    })
+}
+
+impl LocalDatabase {
+    // Execution history operations
+    /// Records a finished cell run against the cell's notebook, snapshotting
+    /// the cell source so later edits don't rewrite history.
+    pub fn record_execution(&self, execution: &NewExecution) -> Result<Execution> {
+        if !EXECUTION_STATUSES.contains(&execution.status.as_str()) {
+            return Err(anyhow::anyhow!("Unknown execution status '{}'", execution.status));
+        }
+
+        let cell = self.conn.query_row(
+            "SELECT n.uuid, c.source FROM notebook_cells c
+             JOIN notebooks n ON n.id = c.notebook_id
+             WHERE c.uuid = ?1",
+            params![&execution.cell_uuid],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+        ).optional()?;
+        let Some((notebook_uuid, source)) = cell else {
+            return Err(anyhow::anyhow!("Cell {} not found", execution.cell_uuid));
+        };
+
+        let uuid = uuid::Uuid::new_v4().to_string();
+        self.conn.execute(
+            "INSERT INTO executions
+                 (uuid, notebook_uuid, cell_uuid, source, status, duration_ms, stdout_ref, stderr_ref, engine_version, started_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9,
+                     COALESCE(?10, strftime('%Y-%m-%dT%H:%M:%SZ', 'now', '-' || (?6 / 1000) || ' seconds')))",
+            params![
+                &uuid,
+                &notebook_uuid,
+                &execution.cell_uuid,
+                &source,
+                &execution.status,
+                execution.duration_ms.max(0),
+                &execution.stdout_ref,
+                &execution.stderr_ref,
+                &execution.engine_version,
+                &execution.started_at,
+            ],
+        )?;
+
+        let execution = self.conn.query_row(
+            &format!("SELECT {} FROM executions WHERE uuid = ?1", EXECUTION_COLUMNS),
+            params![&uuid],
+            execution_from_row,
+        )?;
+        Ok(execution)
+    }
+
+    /// Runs of a notebook's cells, newest first, optionally of one cell.
+    pub fn get_execution_history(
+        &self,
+        notebook_uuid: &str,
+        cell_uuid: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Execution>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM executions
+             WHERE notebook_uuid = ?1 AND (?2 IS NULL OR cell_uuid = ?2)
+             ORDER BY started_at DESC, id DESC
+             LIMIT ?3",
+            EXECUTION_COLUMNS
+        ))?;
+
+        let executions = stmt
+            .query_map(params![notebook_uuid, cell_uuid, limit], execution_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(executions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_execution_history_snapshots_source() {
+        let db_path = std::env::temp_dir().join("test_novem_executions.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        db.conn
+            .execute_batch(
+                "INSERT INTO users (id, uuid, email, username) VALUES (1, 'u1', 'a@example.com', 'ana');
+                 INSERT INTO workspaces (id, uuid, name, owner_id) VALUES (1, 'ws', 'Team', 1);
+                 INSERT INTO projects (id, uuid, workspace_id, name, owner_id) VALUES (1, 'p', 1, 'Churn', 1);
+                 INSERT INTO notebooks (id, uuid, project_id, name) VALUES (1, 'nb', 1, 'Analysis');
+                 INSERT INTO notebook_cells (uuid, notebook_id, position, source) VALUES ('c1', 1, 0, 'x = 1');
+                 INSERT INTO notebook_cells (uuid, notebook_id, position, source) VALUES ('c2', 1, 1, 'print(x)');",
+            )
+            .unwrap();
+
+        let run = |cell: &str, status: &str, started_at: &str| NewExecution {
+            cell_uuid: cell.to_string(),
+            status: status.to_string(),
+            duration_ms: 120,
+            started_at: Some(started_at.to_string()),
+            stdout_ref: None,
+            stderr_ref: None,
+            engine_version: Some("3.11.9".to_string()),
+        };
+
+        db.record_execution(&run("c1", "ok", "2026-01-01T10:00:00Z")).unwrap();
+        db.conn.execute("UPDATE notebook_cells SET source = 'x = 2' WHERE uuid = 'c1'", []).unwrap();
+        db.record_execution(&run("c1", "ok", "2026-01-01T10:05:00Z")).unwrap();
+        db.record_execution(&run("c2", "error", "2026-01-01T10:06:00Z")).unwrap();
+        assert!(db.record_execution(&run("c2", "exploded", "2026-01-01T10:07:00Z")).is_err());
+        assert!(db.record_execution(&run("missing", "ok", "2026-01-01T10:07:00Z")).is_err());
+
+        let history = db.get_execution_history("nb", None, 50).unwrap();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0].status, "error");
+
+        let first_cell = db.get_execution_history("nb", Some("c1"), 50).unwrap();
+        let sources: Vec<_> = first_cell.iter().map(|e| e.source.as_str()).collect();
+        assert_eq!(sources, ["x = 2", "x = 1"]);
+        assert_eq!(db.get_execution_history("nb", None, 1).unwrap().len(), 1);
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
@@ -0,0 +1,103 @@
+use anyhow::Result;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use super::LocalDatabase;
+
+/// Something that can be pinned to the top of its list, addressed by UUID.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "uuid", rename_all = "snake_case")]
+pub enum FavoriteEntity {
+    Workspace(String),
+    Project(String),
+}
+
+impl FavoriteEntity {
+    pub fn entity_type(&self) -> &'static str {
+        match self {
+            FavoriteEntity::Workspace(_) => "workspace",
+            FavoriteEntity::Project(_) => "project",
+        }
+    }
+
+    pub fn uuid(&self) -> &str {
+        match self {
+            FavoriteEntity::Workspace(uuid) | FavoriteEntity::Project(uuid) => uuid,
+        }
+    }
+
+    fn table(&self) -> &'static str {
+        match self {
+            FavoriteEntity::Workspace(_) => "workspaces",
+            FavoriteEntity::Project(_) => "projects",
+        }
+    }
+}
+
+impl LocalDatabase {
+    // Favorites
+    /// Pins or unpins `entity`. Returns whether it is pinned now, or `None`
+    /// if it doesn't exist or is in the trash. Pinning is a local preference:
+    /// it neither touches `updated_at` nor queues a sync.
+    pub fn toggle_favorite(&self, entity: &FavoriteEntity) -> Result<Option<bool>> {
+        let pinned = self
+            .conn
+            .query_row(
+                &format!(
+                    "UPDATE {} SET is_pinned = NOT is_pinned
+                     WHERE uuid = ?1 AND deleted_at IS NULL
+                     RETURNING is_pinned",
+                    entity.table()
+                ),
+                params![entity.uuid()],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(pinned)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::PageRequest;
+
+    #[test]
+    fn test_pinned_workspaces_come_first() {
+        let db_path = std::env::temp_dir().join("test_novem_favorites.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        db.conn
+            .execute_batch(
+                "INSERT INTO users (id, uuid, email, username) VALUES (1, 'u1', 'a@example.com', 'ana');
+                 INSERT INTO workspaces (id, uuid, name, owner_id, updated_at) VALUES
+                     (1, 'old', 'Old', 1, '2024-01-01T00:00:00Z'),
+                     (2, 'new', 'New', 1, '2025-01-01T00:00:00Z'),
+                     (3, 'newer', 'Newer', 1, '2025-06-01T00:00:00Z');",
+            )
+            .unwrap();
+
+        let old = FavoriteEntity::Workspace("old".to_string());
+        assert_eq!(db.toggle_favorite(&old).unwrap(), Some(true));
+        assert_eq!(db.toggle_favorite(&FavoriteEntity::Project("missing".to_string())).unwrap(), None);
+
+        // Paging carries on past the pinned rows into the rest
+        let mut page = PageRequest { cursor: None, limit: Some(2) };
+        let first = db.get_workspaces(1, false, &page).unwrap();
+        assert_eq!(first.items.iter().map(|w| w.uuid.as_str()).collect::<Vec<_>>(), vec!["old", "newer"]);
+        assert!(first.items[0].is_pinned);
+        page.cursor = first.next_cursor;
+        let second = db.get_workspaces(1, false, &page).unwrap();
+        assert_eq!(second.items.iter().map(|w| w.uuid.as_str()).collect::<Vec<_>>(), vec!["new"]);
+
+        assert_eq!(db.toggle_favorite(&old).unwrap(), Some(false));
+        let updated_at: String = db.conn
+            .query_row("SELECT updated_at FROM workspaces WHERE uuid = 'old'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(updated_at, "2024-01-01T00:00:00Z");
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
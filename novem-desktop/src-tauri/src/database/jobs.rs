@@ -0,0 +1,203 @@
+use anyhow::Result;
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+use super::LocalDatabase;
+
+/// A long-running computation submitted to an engine, kept so it stays
+/// visible in the jobs panel across restarts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Job {
+    pub id: i64,
+    pub uuid: String,
+    /// `None` for jobs on the shared engine
+    pub project_id: Option<i64>,
+    pub kind: String,
+    pub params: String, // JSON
+    pub status: String, // 'queued', 'running', 'completed', 'failed', 'cancelled', 'interrupted'
+    /// 0.0 to 1.0
+    pub progress: f64,
+    /// Where the output went, e.g. a dataset UUID or a file path
+    pub result_ref: Option<String>,
+    pub error: Option<String>,
+    pub created_at: String,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+}
+
+const JOB_COLUMNS: &str =
+    "id, uuid, project_id, kind, params, status, progress, result_ref, error, created_at, started_at, finished_at";
+
+/// Statuses a job never leaves.
+const FINISHED: &str = "('completed', 'failed', 'cancelled', 'interrupted')";
+
+fn job_from_row(row: &Row) -> rusqlite::Result<Job> {
+    Ok(Job {
+        id: row.get(0)?,
+        uuid: row.get(1)?,
+        project_id: row.get(2)?,
+        kind: row.get(3)?,
+        params: row.get(4)?,
+        status: row.get(5)?,
+        progress: row.get(6)?,
+        result_ref: row.get(7)?,
+        error: row.get(8)?,
+        created_at: row.get(9)?,
+        started_at: row.get(10)?,
+        finished_at: row.get(11)?,
+    })
+}
+
+impl LocalDatabase {
+    // Job operations
+    pub fn create_job(&self, uuid: &str, project_id: Option<i64>, kind: &str, params: &str) -> Result<Job> {
+        self.conn.execute(
+            "INSERT INTO jobs (uuid, project_id, kind, params, status) VALUES (?1, ?2, ?3, ?4, 'queued')",
+            params![uuid, project_id, kind, params],
+        )?;
+
+        self.get_job(uuid)?
+            .ok_or_else(|| anyhow::anyhow!("Job {} missing after insert", uuid))
+    }
+
+    pub fn get_job(&self, uuid: &str) -> Result<Option<Job>> {
+        let job = self
+            .conn
+            .query_row(
+                &format!("SELECT {} FROM jobs WHERE uuid = ?1", JOB_COLUMNS),
+                params![uuid],
+                job_from_row,
+            )
+            .optional()?;
+
+        Ok(job)
+    }
+
+    /// Newest first, optionally only one project's or one status.
+    pub fn list_jobs(&self, project_id: Option<i64>, status: Option<&str>) -> Result<Vec<Job>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM jobs
+             WHERE (?1 IS NULL OR project_id = ?1) AND (?2 IS NULL OR status = ?2)
+             ORDER BY created_at DESC, id DESC",
+            JOB_COLUMNS
+        ))?;
+
+        let jobs = stmt
+            .query_map(params![project_id, status], job_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(jobs)
+    }
+
+    /// Marks a queued job as picked up by its engine.
+    pub fn start_job(&self, uuid: &str) -> Result<bool> {
+        let count = self.conn.execute(
+            "UPDATE jobs SET status = 'running', started_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE uuid = ?1 AND status = 'queued'",
+            params![uuid],
+        )?;
+        Ok(count > 0)
+    }
+
+    pub fn update_job_progress(&self, uuid: &str, progress: f64) -> Result<bool> {
+        let count = self.conn.execute(
+            "UPDATE jobs SET progress = MAX(0.0, MIN(1.0, ?2)) WHERE uuid = ?1 AND status = 'running'",
+            params![uuid, progress],
+        )?;
+        Ok(count > 0)
+    }
+
+    /// Records how a job ended: completed with its result, or failed with
+    /// `error`. Returns `false` if it had already finished.
+    pub fn finish_job(&self, uuid: &str, result_ref: Option<&str>, error: Option<&str>) -> Result<bool> {
+        let count = self.conn.execute(
+            &format!(
+                "UPDATE jobs
+                 SET status = CASE WHEN ?3 IS NULL THEN 'completed' ELSE 'failed' END,
+                     progress = CASE WHEN ?3 IS NULL THEN 1.0 ELSE progress END,
+                     result_ref = ?2, error = ?3,
+                     started_at = COALESCE(started_at, strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                     finished_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+                 WHERE uuid = ?1 AND status NOT IN {}",
+                FINISHED
+            ),
+            params![uuid, result_ref, error],
+        )?;
+        Ok(count > 0)
+    }
+
+    pub fn cancel_job(&self, uuid: &str) -> Result<bool> {
+        let count = self.conn.execute(
+            &format!(
+                "UPDATE jobs SET status = 'cancelled', finished_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+                 WHERE uuid = ?1 AND status NOT IN {}",
+                FINISHED
+            ),
+            params![uuid],
+        )?;
+        Ok(count > 0)
+    }
+
+    /// Removes a finished job from the list. Jobs still queued or running
+    /// are kept; cancel them first.
+    pub fn delete_job(&self, uuid: &str) -> Result<bool> {
+        let count = self.conn.execute(
+            &format!("DELETE FROM jobs WHERE uuid = ?1 AND status IN {}", FINISHED),
+            params![uuid],
+        )?;
+        Ok(count > 0)
+    }
+
+    /// Jobs left running by a crash or quit can't be resumed: the engine
+    /// that ran them is gone. Marked on startup so they don't look stuck.
+    pub fn interrupt_running_jobs(&self) -> Result<usize> {
+        let count = self.conn.execute(
+            "UPDATE jobs SET status = 'interrupted', finished_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE status = 'running'",
+            [],
+        )?;
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_job_lifecycle() {
+        let db_path = std::env::temp_dir().join("test_novem_jobs.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        db.create_job("j1", None, "train_model", r#"{"epochs": 5}"#).unwrap();
+        db.create_job("j2", None, "profile_dataset", "{}").unwrap();
+        assert!(!db.update_job_progress("j1", 0.5).unwrap());
+
+        assert!(db.start_job("j1").unwrap());
+        assert!(!db.start_job("j1").unwrap());
+        db.update_job_progress("j1", 1.7).unwrap();
+        assert_eq!(db.get_job("j1").unwrap().unwrap().progress, 1.0);
+
+        assert!(db.finish_job("j1", None, Some("out of memory")).unwrap());
+        assert!(!db.finish_job("j1", Some("ds-1"), None).unwrap());
+        let job = db.get_job("j1").unwrap().unwrap();
+        assert_eq!((job.status.as_str(), job.error.as_deref()), ("failed", Some("out of memory")));
+        assert!(job.finished_at.is_some());
+
+        // Only jobs that were running are cut off by a restart
+        db.create_job("j3", None, "export", "{}").unwrap();
+        db.start_job("j2").unwrap();
+        assert_eq!(db.interrupt_running_jobs().unwrap(), 1);
+        assert_eq!(db.list_jobs(None, Some("interrupted")).unwrap()[0].uuid, "j2");
+        assert_eq!(db.list_jobs(None, Some("queued")).unwrap()[0].uuid, "j3");
+
+        assert!(!db.delete_job("j3").unwrap());
+        assert!(db.cancel_job("j3").unwrap());
+        assert!(db.delete_job("j3").unwrap());
+        assert_eq!(db.list_jobs(None, None).unwrap().len(), 2);
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
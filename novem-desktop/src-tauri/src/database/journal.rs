@@ -0,0 +1,84 @@
+use anyhow::Result;
+use rusqlite::params;
+
+use super::LocalDatabase;
+
+#[derive(Debug, Clone)]
+pub struct JournalEntry {
+    pub id: i64,
+    pub kind: String,
+    pub step: String,
+    pub state: String, // JSON, enough to roll back or resume
+    pub started_at: String,
+}
+
+impl LocalDatabase {
+    // Operation journal operations
+    pub fn begin_operation(&self, kind: &str, state_json: &str) -> Result<i64> {
+        self.conn.execute(
+            "INSERT INTO operation_journal (kind, step, state, status) VALUES (?1, 'started', ?2, 'running')",
+            params![kind, state_json],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Records that an operation got past a point it can be resumed from.
+    pub fn advance_operation(&self, id: i64, step: &str, state_json: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE operation_journal SET step = ?1, state = ?2, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = ?3",
+            params![step, state_json, id],
+        )?;
+        Ok(())
+    }
+
+    /// `status` is 'completed', 'rolled_back', 'resumed' or 'failed'.
+    pub fn finish_operation(&self, id: i64, status: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE operation_journal SET status = ?1, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = ?2",
+            params![status, id],
+        )?;
+        Ok(())
+    }
+
+    /// Operations still marked running, i.e. cut short by a crash.
+    pub fn get_interrupted_operations(&self) -> Result<Vec<JournalEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, kind, step, state, started_at
+             FROM operation_journal
+             WHERE status = 'running'
+             ORDER BY id ASC"
+        )?;
+
+        let entries = stmt
+            .query_map([], |row| {
+                Ok(JournalEntry {
+                    id: row.get(0)?,
+                    kind: row.get(1)?,
+                    step: row.get(2)?,
+                    state: row.get(3)?,
+                    started_at: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(entries)
+    }
+
+    /// Drops finished entries older than a week.
+    pub fn prune_operation_journal(&self) -> Result<usize> {
+        let count = self.conn.execute(
+            "DELETE FROM operation_journal WHERE status != 'running' AND updated_at < strftime('%Y-%m-%dT%H:%M:%SZ', 'now', '-7 days')",
+            [],
+        )?;
+        Ok(count)
+    }
+
+    pub fn is_dataset_file_registered(&self, file_path: &str) -> Result<bool> {
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM datasets WHERE file_path = ?1",
+            params![file_path],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+}
@@ -0,0 +1,196 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Instant;
+
+use super::LocalDatabase;
+use crate::timestamps;
+
+/// Internal setting: when maintenance last completed, as a UTC timestamp.
+pub const LAST_MAINTENANCE_SETTING: &str = "db.last_maintenance_at";
+
+/// `PRAGMA auto_vacuum` value that lets `incremental_vacuum` free pages.
+const AUTO_VACUUM_INCREMENTAL: i64 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VacuumKind {
+    /// Free pages were released without rewriting the file
+    Incremental,
+    /// The file was rewritten once to switch on incremental vacuuming
+    Full,
+    /// Skipped because the integrity check failed
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DbMaintenanceReport {
+    pub ran_at: String,
+    pub integrity_ok: bool,
+    /// What `integrity_check` reported when it wasn't "ok"
+    pub integrity_problems: Vec<String>,
+    pub vacuum: VacuumKind,
+    pub pages_freed: i64,
+    pub size_before_bytes: i64,
+    pub size_after_bytes: i64,
+    /// Completed sync queue entries older than a week that were removed
+    pub sync_items_cleared: usize,
+    pub duration_ms: i64,
+}
+
+/// Rows and disk space of one table, its indexes included.
+#[derive(Debug, Clone, Serialize)]
+pub struct TableUsage {
+    pub name: String,
+    pub rows: i64,
+    /// `None` when SQLite was built without the `dbstat` table
+    pub bytes: Option<i64>,
+}
+
+impl LocalDatabase {
+    // Maintenance operations
+    fn pragma_i64(&self, name: &str) -> Result<i64> {
+        Ok(self.conn.query_row(&format!("PRAGMA {}", name), [], |row| row.get(0))?)
+    }
+
+    fn size_bytes(&self) -> Result<i64> {
+        Ok(self.pragma_i64("page_count")? * self.pragma_i64("page_size")?)
+    }
+
+    /// Checks integrity, refreshes planner statistics, clears old sync
+    /// entries and releases free pages. A database failing the integrity
+    /// check is left as is rather than rewritten.
+    pub fn run_maintenance(&self) -> Result<DbMaintenanceReport> {
+        let started = Instant::now();
+        let size_before_bytes = self.size_bytes()?;
+
+        let mut stmt = self.conn.prepare("PRAGMA integrity_check")?;
+        let integrity_problems = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .filter(|line| line != "ok")
+            .collect::<Vec<_>>();
+        drop(stmt);
+        let integrity_ok = integrity_problems.is_empty();
+
+        self.conn.execute_batch("ANALYZE")?;
+        let sync_items_cleared = self.clear_completed_sync_items()?;
+
+        let free_before = self.pragma_i64("freelist_count")?;
+        let vacuum = if !integrity_ok {
+            VacuumKind::Skipped
+        } else if self.pragma_i64("auto_vacuum")? == AUTO_VACUUM_INCREMENTAL {
+            self.conn.execute_batch("PRAGMA incremental_vacuum")?;
+            VacuumKind::Incremental
+        } else {
+            self.conn.execute_batch("PRAGMA auto_vacuum = INCREMENTAL; VACUUM;")?;
+            VacuumKind::Full
+        };
+        let pages_freed = free_before - self.pragma_i64("freelist_count")?;
+
+        let ran_at = timestamps::now();
+        if integrity_ok {
+            self.set_setting(LAST_MAINTENANCE_SETTING, &ran_at)?;
+        }
+
+        Ok(DbMaintenanceReport {
+            ran_at,
+            integrity_ok,
+            integrity_problems,
+            vacuum,
+            pages_freed,
+            size_before_bytes,
+            size_after_bytes: self.size_bytes()?,
+            sync_items_cleared,
+            duration_ms: started.elapsed().as_millis() as i64,
+        })
+    }
+
+    /// Space inside the file that a vacuum would release.
+    pub fn free_bytes(&self) -> Result<i64> {
+        Ok(self.pragma_i64("freelist_count")? * self.pragma_i64("page_size")?)
+    }
+
+    /// Row count and size of every table, largest first. Full-text index
+    /// storage shows up under its shadow tables.
+    pub fn table_usage(&self) -> Result<Vec<TableUsage>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+        )?;
+        let names = stmt.query_map([], |row| row.get::<_, String>(0))?.collect::<Result<Vec<_>, _>>()?;
+        drop(stmt);
+
+        let sizes: Option<HashMap<String, i64>> = self
+            .conn
+            .prepare(
+                "SELECT m.tbl_name, SUM(s.pgsize) FROM dbstat s
+                 JOIN sqlite_master m ON m.name = s.name
+                 GROUP BY m.tbl_name",
+            )
+            .and_then(|mut stmt| {
+                stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .collect::<rusqlite::Result<HashMap<_, _>>>()
+            })
+            .ok();
+
+        let mut tables = Vec::with_capacity(names.len());
+        for name in names {
+            let rows = self.conn.query_row(&format!("SELECT COUNT(*) FROM \"{}\"", name), [], |row| row.get(0))?;
+            let bytes = sizes.as_ref().map(|sizes| sizes.get(&name).copied().unwrap_or(0));
+            tables.push(TableUsage { name, rows, bytes });
+        }
+        tables.sort_by(|a, b| b.bytes.cmp(&a.bytes).then(b.rows.cmp(&a.rows)));
+        Ok(tables)
+    }
+
+    /// Whether maintenance hasn't completed since `cutoff` (or ever).
+    pub fn maintenance_due(&self, cutoff: &str) -> Result<bool> {
+        Ok(self
+            .get_setting(LAST_MAINTENANCE_SETTING)?
+            .is_none_or(|last| last.as_str() < cutoff))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_maintenance() {
+        let db_path = std::env::temp_dir().join("test_novem_maintenance.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        db.conn
+            .execute(
+                "INSERT INTO sync_queue (entity_type, entity_uuid, action, payload, status, updated_at)
+                 VALUES ('project', 'p1', 'create', '{}', 'completed', '2025-01-01T00:00:00Z')",
+                [],
+            )
+            .unwrap();
+        assert!(db.maintenance_due("2025-01-01T00:00:00Z").unwrap());
+
+        let first = db.run_maintenance().unwrap();
+        assert!(first.integrity_ok);
+        assert_eq!(first.vacuum, VacuumKind::Full);
+        assert_eq!(first.sync_items_cleared, 1);
+        assert!(!db.maintenance_due("2025-01-01T00:00:00Z").unwrap());
+
+        // Once switched over, later runs only release free pages
+        let second = db.run_maintenance().unwrap();
+        assert_eq!(second.vacuum, VacuumKind::Incremental);
+        assert_eq!(second.sync_items_cleared, 0);
+
+        let tables = db.table_usage().unwrap();
+        let settings = tables.iter().find(|t| t.name == "settings").unwrap();
+        assert_eq!(settings.rows, 1);
+        assert!(settings.bytes.unwrap() > 0);
+        assert_eq!(db.free_bytes().unwrap(), 0);
+
+        drop(db);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", db_path.display(), suffix));
+        }
+    }
+}
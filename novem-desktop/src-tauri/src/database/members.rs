@@ -0,0 +1,189 @@
+use anyhow::Result;
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+use super::LocalDatabase;
+
+/// A member's role in a workspace, mirroring the backend's
+/// `WorkspaceMembership.Role`. Ordered so a higher role includes the
+/// permissions of every lower one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WorkspaceRole {
+    Guest,
+    Member,
+    Admin,
+    Owner,
+}
+
+impl WorkspaceRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WorkspaceRole::Guest => "guest",
+            WorkspaceRole::Member => "member",
+            WorkspaceRole::Admin => "admin",
+            WorkspaceRole::Owner => "owner",
+        }
+    }
+
+    fn parse(role: &str) -> Option<Self> {
+        match role {
+            "guest" => Some(WorkspaceRole::Guest),
+            "member" => Some(WorkspaceRole::Member),
+            "admin" => Some(WorkspaceRole::Admin),
+            "owner" => Some(WorkspaceRole::Owner),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceMember {
+    pub workspace_id: i64,
+    pub user_id: i64,
+    pub role: WorkspaceRole,
+    pub invited_at: String,
+    pub status: String, // 'pending', 'active'
+    /// From the local users table, when the user has been cached
+    pub username: Option<String>,
+    pub email: Option<String>,
+}
+
+/// A membership as the backend reports it.
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewWorkspaceMember {
+    pub user_id: i64,
+    pub role: WorkspaceRole,
+    pub invited_at: Option<String>,
+    #[serde(default = "default_status")]
+    pub status: String,
+}
+
+fn default_status() -> String {
+    "active".to_string()
+}
+
+fn member_from_row(row: &Row) -> rusqlite::Result<WorkspaceMember> {
+    let role: String = row.get(2)?;
+    Ok(WorkspaceMember {
+        workspace_id: row.get(0)?,
+        user_id: row.get(1)?,
+        // Roles added by a newer backend get no more than a guest's rights
+        role: WorkspaceRole::parse(&role).unwrap_or(WorkspaceRole::Guest),
+        invited_at: row.get(3)?,
+        status: row.get(4)?,
+        username: row.get(5)?,
+        email: row.get(6)?,
+    })
+}
+
+impl LocalDatabase {
+    // Workspace member operations
+    pub fn get_workspace_members(&self, workspace_id: i64) -> Result<Vec<WorkspaceMember>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT m.workspace_id, m.user_id, m.role, m.invited_at, m.status, u.username, u.email
+             FROM workspace_members m
+             LEFT JOIN users u ON u.id = m.user_id
+             WHERE m.workspace_id = ?1
+             ORDER BY m.status = 'pending', u.username COLLATE NOCASE"
+        )?;
+
+        let members = stmt
+            .query_map(params![workspace_id], member_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(members)
+    }
+
+    /// Replaces a workspace's cached members with the backend's list.
+    pub fn replace_workspace_members(&self, workspace_id: i64, members: &[NewWorkspaceMember]) -> Result<()> {
+        let tx = self.begin()?;
+
+        self.conn.execute("DELETE FROM workspace_members WHERE workspace_id = ?1", params![workspace_id])?;
+        for member in members {
+            self.conn.execute(
+                "INSERT INTO workspace_members (workspace_id, user_id, role, invited_at, status)
+                 VALUES (?1, ?2, ?3, COALESCE(?4, strftime('%Y-%m-%dT%H:%M:%SZ', 'now')), ?5)",
+                params![workspace_id, member.user_id, member.role.as_str(), &member.invited_at, &member.status],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// The user's role in the workspace, or `None` if they aren't an active
+    /// member. The workspace owner always counts as `Owner`.
+    pub fn get_workspace_role(&self, workspace_id: i64, user_id: i64) -> Result<Option<WorkspaceRole>> {
+        let owner_id: Option<i64> = self
+            .conn
+            .query_row("SELECT owner_id FROM workspaces WHERE id = ?1", params![workspace_id], |row| row.get(0))
+            .optional()?;
+        if owner_id == Some(user_id) {
+            return Ok(Some(WorkspaceRole::Owner));
+        }
+
+        let role: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT role FROM workspace_members
+                 WHERE workspace_id = ?1 AND user_id = ?2 AND status = 'active'",
+                params![workspace_id, user_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(role.map(|role| WorkspaceRole::parse(&role).unwrap_or(WorkspaceRole::Guest)))
+    }
+
+    /// Whether the user holds at least `required` in the workspace.
+    pub fn has_workspace_role(&self, workspace_id: i64, user_id: i64, required: WorkspaceRole) -> Result<bool> {
+        Ok(self.get_workspace_role(workspace_id, user_id)?.is_some_and(|role| role >= required))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workspace_roles() {
+        let db_path = std::env::temp_dir().join("test_novem_members.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        db.conn
+            .execute_batch(
+                "INSERT INTO users (id, uuid, email, username) VALUES (1, 'u1', 'a@example.com', 'ana');
+                 INSERT INTO users (id, uuid, email, username) VALUES (2, 'u2', 'b@example.com', 'ben');
+                 INSERT INTO workspaces (id, uuid, name, owner_id) VALUES (1, 'ws', 'Team', 1);",
+            )
+            .unwrap();
+
+        let member = |user_id, role, status: &str| NewWorkspaceMember {
+            user_id,
+            role,
+            invited_at: None,
+            status: status.to_string(),
+        };
+        db.replace_workspace_members(
+            1,
+            &[member(2, WorkspaceRole::Member, "active"), member(3, WorkspaceRole::Admin, "pending")],
+        )
+        .unwrap();
+
+        let members = db.get_workspace_members(1).unwrap();
+        assert_eq!(members.len(), 2);
+        assert_eq!(members[0].username.as_deref(), Some("ben"));
+        assert_eq!(members[1].username, None);
+
+        assert_eq!(db.get_workspace_role(1, 1).unwrap(), Some(WorkspaceRole::Owner));
+        assert!(db.has_workspace_role(1, 2, WorkspaceRole::Member).unwrap());
+        assert!(!db.has_workspace_role(1, 2, WorkspaceRole::Admin).unwrap());
+        // Pending invitations grant nothing yet
+        assert_eq!(db.get_workspace_role(1, 3).unwrap(), None);
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
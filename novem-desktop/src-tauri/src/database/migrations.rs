@@ -0,0 +1,437 @@
+use anyhow::{Context, Result};
+use rusqlite::Connection;
+
+use super::LocalDatabase;
+
+const LEGACY_DEFAULT: &str = "DEFAULT CURRENT_TIMESTAMP";
+const UTC_DEFAULT: &str = "DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))";
+
+/// One step of the schema's history, applied in a single transaction along
+/// with the bump of `user_version`, so a failure leaves the previous
+/// version intact.
+struct Migration {
+    version: i64,
+    description: &'static str,
+    /// Recreates tables others may reference; foreign keys are off while
+    /// it runs and checked before it commits
+    rebuilds_tables: bool,
+    apply: fn(&Connection) -> Result<()>,
+}
+
+/// Ordered by version, without gaps; the last one is the version
+/// `initialize_schema` creates, stored in `PRAGMA user_version`. Append new
+/// steps; a migration that has shipped must never change.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "timestamps as RFC3339 UTC",
+        rebuilds_tables: true,
+        apply: timestamps_to_utc,
+    },
+    Migration {
+        version: 2,
+        description: "deleted_at for trashed entities",
+        rebuilds_tables: false,
+        apply: trash_deleted_at,
+    },
+    Migration {
+        version: 3,
+        description: "is_pinned for workspaces and projects",
+        rebuilds_tables: false,
+        apply: pinned_flags,
+    },
+    Migration {
+        version: 4,
+        description: "sync state for tombstones",
+        rebuilds_tables: false,
+        apply: tombstone_sync_state,
+    },
+    Migration {
+        version: 5,
+        description: "row versions for workspaces, projects and datasets",
+        rebuilds_tables: false,
+        apply: row_versions,
+    },
+    Migration {
+        version: 6,
+        description: "deleted_at for attachments",
+        rebuilds_tables: false,
+        apply: attachments_deleted_at,
+    },
+    Migration {
+        version: 7,
+        description: "next_attempt_at for sync retries",
+        rebuilds_tables: false,
+        apply: sync_next_attempt,
+    },
+    Migration {
+        version: 8,
+        description: "changed_fields for sync deltas",
+        rebuilds_tables: false,
+        apply: sync_changed_fields,
+    },
+];
+
+impl LocalDatabase {
+    // Schema migrations
+
+    /// Brings a database written by an older version up to date. Runs before
+    /// `initialize_schema`, which then creates tables and indexes added since
+    /// or dropped here.
+    pub(super) fn migrate(&self) -> Result<()> {
+        self.run_migrations(MIGRATIONS)
+    }
+
+    fn run_migrations(&self, migrations: &[Migration]) -> Result<()> {
+        let latest = migrations.last().map_or(0, |migration| migration.version);
+        let version: i64 = self.conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+        // Opening with an older build would silently misread columns
+        if version > latest {
+            return Err(anyhow::anyhow!(
+                "Database schema version {} is newer than this version of NOVEM supports ({}); update NOVEM to open it",
+                version,
+                latest
+            ));
+        }
+
+        let tables: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+            [],
+            |row| row.get(0),
+        )?;
+        if version == 0 && tables == 0 {
+            // A new database; `initialize_schema` creates the latest schema
+            self.conn.execute_batch(&format!("PRAGMA user_version = {}", latest))?;
+            return Ok(());
+        }
+
+        for migration in migrations.iter().filter(|migration| migration.version > version) {
+            self.apply_migration(migration)
+                .with_context(|| format!("Database migration {} ({}) failed", migration.version, migration.description))?;
+            println!("[NOVEM] Applied database migration {}: {}", migration.version, migration.description);
+        }
+        Ok(())
+    }
+
+    fn apply_migration(&self, migration: &Migration) -> Result<()> {
+        // Has no effect inside a transaction, so it is switched around it
+        let foreign_keys: bool = self.conn.query_row("PRAGMA foreign_keys", [], |row| row.get(0))?;
+        if migration.rebuilds_tables {
+            self.conn.execute_batch("PRAGMA foreign_keys = OFF")?;
+        }
+
+        let result = (|| {
+            let tx = self.conn.unchecked_transaction()?;
+            (migration.apply)(&tx)?;
+
+            if migration.rebuilds_tables {
+                let violations: i64 = tx.query_row("SELECT COUNT(*) FROM pragma_foreign_key_check", [], |row| row.get(0))?;
+                if violations > 0 {
+                    return Err(anyhow::anyhow!("Migration left {} foreign key violations", violations));
+                }
+            }
+
+            tx.execute_batch(&format!("PRAGMA user_version = {}", migration.version))?;
+            tx.commit()?;
+            Ok(())
+        })();
+
+        if migration.rebuilds_tables && foreign_keys {
+            self.conn.execute_batch("PRAGMA foreign_keys = ON")?;
+        }
+        result
+    }
+}
+
+/// Version 1: CURRENT_TIMESTAMP wrote zone-less `YYYY-MM-DD HH:MM:SS`,
+/// which readers took for local time. Rewrites every `*_at` TEXT value
+/// as RFC3339 UTC and rebuilds tables so their defaults do the same.
+fn timestamps_to_utc(conn: &Connection) -> Result<()> {
+    let mut stmt = conn.prepare(
+        "SELECT name, sql FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'"
+    )?;
+    let tables = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    drop(stmt);
+
+    for (table, sql) in &tables {
+        if sql.contains(LEGACY_DEFAULT) {
+            let staging = format!("{}_migrating", table);
+            let create = sql
+                .replacen(&format!("CREATE TABLE {}", table), &format!("CREATE TABLE {}", staging), 1)
+                .replace(LEGACY_DEFAULT, UTC_DEFAULT);
+
+            conn.execute_batch(&format!(
+                "{};
+                 INSERT INTO {staging} SELECT * FROM {table};
+                 DROP TABLE {table};
+                 ALTER TABLE {staging} RENAME TO {table};",
+                create,
+                staging = staging,
+                table = table,
+            ))?;
+        }
+
+        let mut columns = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+        let timestamp_columns = columns
+            .query_map([], |row| Ok((row.get::<_, String>(1)?, row.get::<_, String>(2)?)))?
+            .filter_map(|column| column.ok())
+            .filter(|(name, kind)| name.ends_with("_at") && kind.eq_ignore_ascii_case("TEXT"))
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+        drop(columns);
+
+        for column in timestamp_columns {
+            // strftime reads the legacy form as UTC and converts offsets;
+            // values it can't parse are left alone
+            conn.execute(
+                &format!(
+                    "UPDATE {table} SET {column} = strftime('%Y-%m-%dT%H:%M:%SZ', {column})
+                     WHERE {column} IS NOT NULL AND strftime('%Y-%m-%dT%H:%M:%SZ', {column}) IS NOT NULL",
+                    table = table,
+                    column = column,
+                ),
+                [],
+            )?;
+        }
+    }
+
+    println!("[NOVEM] Migrated timestamps in {} tables to UTC", tables.len());
+    Ok(())
+}
+
+/// Version 2: trashed workspaces, projects and datasets record when they
+/// were deleted, so the trash can be listed and purged by age. Rows trashed
+/// earlier take the time from their tombstone.
+fn trash_deleted_at(conn: &Connection) -> Result<()> {
+    let has_tombstones: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'tombstones'",
+        [],
+        |row| row.get(0),
+    )?;
+
+    for table in ["workspaces", "projects", "datasets"] {
+        let columns = table_columns(conn, table)?;
+        // Tables this database never had are created with the column
+        if columns.is_empty() || columns.iter().any(|column| column == "deleted_at") {
+            continue;
+        }
+
+        conn.execute_batch(&format!("ALTER TABLE {} ADD COLUMN deleted_at TEXT", table))?;
+        if has_tombstones {
+            conn.execute(
+                &format!(
+                    "UPDATE {} SET deleted_at = (SELECT deleted_at FROM tombstones WHERE entity_uuid = {}.uuid)
+                     WHERE is_active = 0",
+                    table, table
+                ),
+                [],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Version 3: workspaces and projects can be pinned to the top of lists.
+fn pinned_flags(conn: &Connection) -> Result<()> {
+    for table in ["workspaces", "projects"] {
+        let columns = table_columns(conn, table)?;
+        if columns.is_empty() || columns.iter().any(|column| column == "is_pinned") {
+            continue;
+        }
+        conn.execute_batch(&format!(
+            "ALTER TABLE {} ADD COLUMN is_pinned BOOLEAN NOT NULL DEFAULT 0",
+            table
+        ))?;
+    }
+    Ok(())
+}
+
+/// Version 4: tombstones outlive a purge until the backend acknowledges
+/// the delete, so they record when it did and whether the rows are gone.
+fn tombstone_sync_state(conn: &Connection) -> Result<()> {
+    let columns = table_columns(conn, "tombstones")?;
+    if columns.is_empty() || columns.iter().any(|column| column == "synced_at") {
+        return Ok(());
+    }
+    conn.execute_batch(
+        "ALTER TABLE tombstones ADD COLUMN synced_at TEXT;
+         ALTER TABLE tombstones ADD COLUMN purged BOOLEAN NOT NULL DEFAULT 0;",
+    )?;
+    Ok(())
+}
+
+/// Version 5: synced rows carry a version, so concurrent edits on this
+/// device and the backend can be told apart. Existing rows start at 1; the
+/// ones already synced take that as the backend's version too.
+fn row_versions(conn: &Connection) -> Result<()> {
+    for table in ["workspaces", "projects", "datasets"] {
+        let columns = table_columns(conn, table)?;
+        if columns.is_empty() || columns.iter().any(|column| column == "version") {
+            continue;
+        }
+        conn.execute_batch(&format!(
+            "ALTER TABLE {table} ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+             ALTER TABLE {table} ADD COLUMN server_version INTEGER;
+             UPDATE {table} SET server_version = 1 WHERE sync_status = 'synced';",
+            table = table
+        ))?;
+    }
+    Ok(())
+}
+
+/// Version 6: attachments go to the trash along with their project.
+fn attachments_deleted_at(conn: &Connection) -> Result<()> {
+    let columns = table_columns(conn, "attachments")?;
+    if columns.is_empty() || columns.iter().any(|column| column == "deleted_at") {
+        return Ok(());
+    }
+    conn.execute_batch("ALTER TABLE attachments ADD COLUMN deleted_at TEXT")?;
+    Ok(())
+}
+
+/// Version 7: failed sync items wait out a backoff before the next push.
+/// Items already retrying are due right away.
+fn sync_next_attempt(conn: &Connection) -> Result<()> {
+    let columns = table_columns(conn, "sync_queue")?;
+    if columns.is_empty() || columns.iter().any(|column| column == "next_attempt_at") {
+        return Ok(());
+    }
+    conn.execute_batch("ALTER TABLE sync_queue ADD COLUMN next_attempt_at TEXT")?;
+    Ok(())
+}
+
+/// Version 8: updates are queued as deltas naming the fields they carry.
+/// Items queued earlier carry whole entities, which `NULL` stands for.
+fn sync_changed_fields(conn: &Connection) -> Result<()> {
+    let columns = table_columns(conn, "sync_queue")?;
+    if columns.is_empty() || columns.iter().any(|column| column == "changed_fields") {
+        return Ok(());
+    }
+    conn.execute_batch("ALTER TABLE sync_queue ADD COLUMN changed_fields TEXT")?;
+    Ok(())
+}
+
+/// Column names of `table`; empty if it doesn't exist.
+fn table_columns(conn: &Connection, table: &str) -> Result<Vec<String>> {
+    let columns = conn
+        .prepare(&format!("PRAGMA table_info({})", table))?
+        .query_map([], |row| row.get(1))?
+        .collect::<Result<_, _>>()?;
+    Ok(columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DbConnection;
+
+    const SCHEMA_VERSION: i64 = MIGRATIONS[MIGRATIONS.len() - 1].version;
+
+    #[test]
+    fn test_legacy_timestamps_are_migrated() {
+        let db_path = std::env::temp_dir().join("test_novem_migrations.db");
+        let _ = std::fs::remove_file(&db_path);
+
+        // A database as written before timestamps were zoned
+        {
+            let conn = rusqlite::Connection::open(&db_path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE users (
+                    id INTEGER PRIMARY KEY,
+                    uuid TEXT NOT NULL UNIQUE,
+                    email TEXT NOT NULL UNIQUE,
+                    username TEXT NOT NULL UNIQUE,
+                    first_name TEXT,
+                    last_name TEXT,
+                    is_active BOOLEAN NOT NULL DEFAULT 1,
+                    last_login TEXT,
+                    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+                 );
+                 INSERT INTO users (id, uuid, email, username, created_at)
+                    VALUES (1, 'u1', 'a@example.com', 'ana', '2025-03-14 09:26:53');",
+            )
+            .unwrap();
+        }
+
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        let created_at: String = db.conn
+            .query_row("SELECT created_at FROM users WHERE id = 1", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(created_at, "2025-03-14T09:26:53Z");
+
+        db.conn
+            .execute("INSERT INTO users (id, uuid, email, username) VALUES (2, 'u2', 'b@example.com', 'ben')", [])
+            .unwrap();
+        let created_at: String = db.conn
+            .query_row("SELECT created_at FROM users WHERE id = 2", [], |row| row.get(0))
+            .unwrap();
+        assert!(crate::timestamps::parse(&created_at).is_some());
+        assert!(created_at.ends_with('Z'));
+
+        let version: i64 = db.conn.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(version, SCHEMA_VERSION);
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+
+    #[test]
+    fn test_migrations_are_contiguous() {
+        for (index, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version, index as i64 + 1, "{}", migration.description);
+        }
+    }
+
+    #[test]
+    fn test_new_database_starts_at_latest_version() {
+        let db = LocalDatabase { conn: DbConnection::Owned(Connection::open_in_memory().unwrap()) };
+        db.migrate().unwrap();
+        db.initialize_schema().unwrap();
+
+        let version: i64 = db.conn.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(version, SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_failed_migration_rolls_back() {
+        fn create_tags(conn: &Connection) -> Result<()> {
+            conn.execute_batch("CREATE TABLE tags (name TEXT)")?;
+            Ok(())
+        }
+        fn add_color_then_fail(conn: &Connection) -> Result<()> {
+            conn.execute_batch("ALTER TABLE tags ADD COLUMN color TEXT")?;
+            Err(anyhow::anyhow!("boom"))
+        }
+        let migrations = [
+            Migration { version: 1, description: "tags", rebuilds_tables: false, apply: create_tags },
+            Migration { version: 2, description: "tag colors", rebuilds_tables: false, apply: add_color_then_fail },
+        ];
+
+        let db = LocalDatabase { conn: DbConnection::Owned(Connection::open_in_memory().unwrap()) };
+        db.conn.execute_batch("CREATE TABLE notes (body TEXT)").unwrap();
+
+        let error = db.run_migrations(&migrations).unwrap_err();
+        assert!(error.to_string().contains("migration 2"));
+
+        let version: i64 = db.conn.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(version, 1);
+        let colors: i64 = db.conn
+            .query_row("SELECT COUNT(*) FROM pragma_table_info('tags') WHERE name = 'color'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(colors, 0);
+    }
+
+    #[test]
+    fn test_newer_schema_is_refused() {
+        let db = LocalDatabase { conn: DbConnection::Owned(Connection::open_in_memory().unwrap()) };
+        db.conn
+            .execute_batch(&format!("CREATE TABLE notes (body TEXT); PRAGMA user_version = {}", SCHEMA_VERSION + 1))
+            .unwrap();
+
+        assert!(db.migrate().is_err());
+    }
+}
@@ -0,0 +1,374 @@
+use anyhow::Result;
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+use super::LocalDatabase;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notebook {
+    pub id: i64,
+    pub uuid: String,
+    pub project_id: i64,
+    pub name: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub is_active: bool,
+    pub sync_status: String,
+    pub last_synced_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotebookCell {
+    pub id: i64,
+    pub uuid: String,
+    pub notebook_id: i64,
+    pub position: i64,
+    pub cell_type: String, // 'code', 'markdown', 'sql'
+    pub source: String,
+    pub outputs: String, // JSON array of Jupyter-style outputs
+    pub tags: String, // JSON array of strings
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Cell types the notebook editor knows how to run or render.
+const CELL_TYPES: &[&str] = &["code", "markdown", "sql"];
+
+/// A cell to insert. Without a position it is appended.
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewCell {
+    pub position: Option<i64>,
+    #[serde(default = "default_cell_type")]
+    pub cell_type: String,
+    #[serde(default)]
+    pub source: String,
+}
+
+fn default_cell_type() -> String {
+    "code".to_string()
+}
+
+/// Fields of a cell to change; anything left out stays as it is.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CellUpdate {
+    pub position: Option<i64>,
+    pub cell_type: Option<String>,
+    pub source: Option<String>,
+    pub tags: Option<Vec<String>>,
+}
+
+const NOTEBOOK_COLUMNS: &str =
+    "id, uuid, project_id, name, created_at, updated_at, is_active, sync_status, last_synced_at";
+
+const CELL_COLUMNS: &str =
+    "id, uuid, notebook_id, position, cell_type, source, outputs, tags, created_at, updated_at";
+
+fn notebook_from_row(row: &Row) -> rusqlite::Result<Notebook> {
+    Ok(Notebook {
+        id: row.get(0)?,
+        uuid: row.get(1)?,
+        project_id: row.get(2)?,
+        name: row.get(3)?,
+        created_at: row.get(4)?,
+        updated_at: row.get(5)?,
+        is_active: row.get(6)?,
+        sync_status: row.get(7)?,
+        last_synced_at: row.get(8)?,
+    })
+}
+
+fn cell_from_row(row: &Row) -> rusqlite::Result<NotebookCell> {
+    Ok(NotebookCell {
+        id: row.get(0)?,
+        uuid: row.get(1)?,
+        notebook_id: row.get(2)?,
+        position: row.get(3)?,
+        cell_type: row.get(4)?,
+        source: row.get(5)?,
+        outputs: row.get(6)?,
+        tags: row.get(7)?,
+        created_at: row.get(8)?,
+        updated_at: row.get(9)?,
+    })
+}
+
+impl LocalDatabase {
+    // Notebook operations
+    pub fn get_notebook_by_uuid(&self, uuid: &str) -> Result<Option<Notebook>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM notebooks WHERE uuid = ?1 AND is_active = 1",
+            NOTEBOOK_COLUMNS
+        ))?;
+
+        let notebook = stmt.query_row(params![uuid], notebook_from_row).optional()?;
+
+        Ok(notebook)
+    }
+
+    pub fn get_notebook_cells(&self, notebook_id: i64) -> Result<Vec<NotebookCell>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM notebook_cells WHERE notebook_id = ?1 ORDER BY position ASC",
+            CELL_COLUMNS
+        ))?;
+
+        let cells = stmt
+            .query_map(params![notebook_id], cell_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(cells)
+    }
+
+    pub fn get_project_notebooks(&self, project_id: i64) -> Result<Vec<Notebook>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM notebooks WHERE project_id = ?1 AND is_active = 1 ORDER BY name COLLATE NOCASE",
+            NOTEBOOK_COLUMNS
+        ))?;
+
+        let notebooks = stmt
+            .query_map(params![project_id], notebook_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(notebooks)
+    }
+
+    pub fn create_notebook(&self, project_id: i64, name: &str) -> Result<Notebook> {
+        let project_uuid: String = self
+            .conn
+            .query_row("SELECT uuid FROM projects WHERE id = ?1", params![project_id], |row| row.get(0))
+            .optional()?
+            .ok_or_else(|| anyhow::anyhow!("Project {} not found", project_id))?;
+        let uuid = uuid::Uuid::new_v4().to_string();
+
+        let tx = self.begin()?;
+        self.conn.execute(
+            "INSERT INTO notebooks (uuid, project_id, name) VALUES (?1, ?2, ?3)",
+            params![&uuid, project_id, name],
+        )?;
+        self.add_to_sync_queue(
+            "notebook",
+            &uuid,
+            "create",
+            &serde_json::json!({ "project_uuid": project_uuid, "name": name }).to_string(),
+        )?;
+        tx.commit()?;
+
+        self.get_notebook_by_uuid(&uuid)?
+            .ok_or_else(|| anyhow::anyhow!("Notebook {} missing after insert", uuid))
+    }
+
+    pub fn get_cell_by_uuid(&self, uuid: &str) -> Result<Option<NotebookCell>> {
+        let cell = self
+            .conn
+            .query_row(
+                &format!("SELECT {} FROM notebook_cells WHERE uuid = ?1", CELL_COLUMNS),
+                params![uuid],
+                cell_from_row,
+            )
+            .optional()?;
+
+        Ok(cell)
+    }
+
+    /// Inserts a cell, shifting the cells at and after its position down.
+    pub fn create_cell(&self, notebook_uuid: &str, cell: &NewCell) -> Result<NotebookCell> {
+        let notebook = self
+            .get_notebook_by_uuid(notebook_uuid)?
+            .ok_or_else(|| anyhow::anyhow!("Notebook {} not found", notebook_uuid))?;
+        check_cell_type(&cell.cell_type)?;
+
+        let tx = self.begin()?;
+        let end = self.cell_count(notebook.id)?;
+        let position = cell.position.map_or(end, |position| position.clamp(0, end));
+        let uuid = uuid::Uuid::new_v4().to_string();
+
+        self.conn.execute(
+            "UPDATE notebook_cells SET position = position + 1 WHERE notebook_id = ?1 AND position >= ?2",
+            params![notebook.id, position],
+        )?;
+        self.conn.execute(
+            "INSERT INTO notebook_cells (uuid, notebook_id, position, cell_type, source)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![&uuid, notebook.id, position, &cell.cell_type, &cell.source],
+        )?;
+
+        let created = self
+            .get_cell_by_uuid(&uuid)?
+            .ok_or_else(|| anyhow::anyhow!("Cell {} missing after insert", uuid))?;
+        self.queue_cell_sync(&notebook, None, &created)?;
+        tx.commit()?;
+
+        Ok(created)
+    }
+
+    /// Applies an edit to a cell; a new position moves it, shifting the
+    /// cells in between. Returns `None` if the cell doesn't exist.
+    pub fn update_cell(&self, cell_uuid: &str, update: &CellUpdate) -> Result<Option<NotebookCell>> {
+        let Some(cell) = self.get_cell_by_uuid(cell_uuid)? else {
+            return Ok(None);
+        };
+        if let Some(cell_type) = &update.cell_type {
+            check_cell_type(cell_type)?;
+        }
+        let notebook: Notebook = self.conn.query_row(
+            &format!("SELECT {} FROM notebooks WHERE id = ?1", NOTEBOOK_COLUMNS),
+            params![cell.notebook_id],
+            notebook_from_row,
+        )?;
+
+        let tx = self.begin()?;
+
+        if let Some(position) = update.position {
+            let position = position.clamp(0, self.cell_count(notebook.id)? - 1);
+            if position < cell.position {
+                self.conn.execute(
+                    "UPDATE notebook_cells SET position = position + 1
+                     WHERE notebook_id = ?1 AND position >= ?2 AND position < ?3",
+                    params![notebook.id, position, cell.position],
+                )?;
+            } else if position > cell.position {
+                self.conn.execute(
+                    "UPDATE notebook_cells SET position = position - 1
+                     WHERE notebook_id = ?1 AND position > ?2 AND position <= ?3",
+                    params![notebook.id, cell.position, position],
+                )?;
+            }
+            self.conn.execute(
+                "UPDATE notebook_cells SET position = ?1 WHERE id = ?2",
+                params![position, cell.id],
+            )?;
+        }
+
+        let tags = update.tags.as_ref().map(serde_json::to_string).transpose()?;
+        self.conn.execute(
+            "UPDATE notebook_cells
+             SET cell_type = COALESCE(?1, cell_type),
+                 source = COALESCE(?2, source),
+                 tags = COALESCE(?3, tags),
+                 updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE id = ?4",
+            params![&update.cell_type, &update.source, tags, cell.id],
+        )?;
+
+        let updated = self
+            .get_cell_by_uuid(cell_uuid)?
+            .ok_or_else(|| anyhow::anyhow!("Cell {} missing after update", cell_uuid))?;
+        self.queue_cell_sync(&notebook, Some(&cell), &updated)?;
+        tx.commit()?;
+
+        Ok(Some(updated))
+    }
+
+    fn cell_count(&self, notebook_id: i64) -> Result<i64> {
+        let count = self.conn.query_row(
+            "SELECT COUNT(*) FROM notebook_cells WHERE notebook_id = ?1",
+            params![notebook_id],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+
+    /// Queues a new cell, or the fields an edit changed since `before`, for
+    /// sync and marks its notebook as changed. Outputs are a local cache
+    /// and are not synced.
+    fn queue_cell_sync(&self, notebook: &Notebook, before: Option<&NotebookCell>, cell: &NotebookCell) -> Result<()> {
+        self.conn.execute(
+            "UPDATE notebooks SET sync_status = 'pending', updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE id = ?1",
+            params![notebook.id],
+        )?;
+        let payload = |cell: &NotebookCell| -> Result<serde_json::Value> {
+            Ok(serde_json::json!({
+                "notebook_uuid": notebook.uuid,
+                "position": cell.position,
+                "cell_type": cell.cell_type,
+                "source": cell.source,
+                "tags": serde_json::from_str::<serde_json::Value>(&cell.tags)?,
+            }))
+        };
+        match before {
+            Some(before) => {
+                self.queue_update("notebook_cell", &cell.uuid, &payload(before)?, &payload(cell)?)?;
+            }
+            None => self.add_to_sync_queue("notebook_cell", &cell.uuid, "create", &payload(cell)?.to_string())?,
+        }
+        Ok(())
+    }
+
+    /// Replaces a cell's cached outputs after an execution and returns the
+    /// owning notebook's UUID, or `None` if the cell doesn't exist.
+    pub fn update_cell_outputs(&self, cell_uuid: &str, outputs: &str) -> Result<Option<String>> {
+        let count = self.conn.execute(
+            "UPDATE notebook_cells
+             SET outputs = ?1, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE uuid = ?2",
+            params![outputs, cell_uuid],
+        )?;
+
+        if count == 0 {
+            return Ok(None);
+        }
+
+        let notebook_uuid = self.conn.query_row(
+            "SELECT n.uuid FROM notebooks n
+             JOIN notebook_cells c ON c.notebook_id = n.id
+             WHERE c.uuid = ?1",
+            params![cell_uuid],
+            |row| row.get(0),
+        ).optional()?;
+
+        Ok(notebook_uuid)
+    }
+}
+
+fn check_cell_type(cell_type: &str) -> Result<()> {
+    if !CELL_TYPES.contains(&cell_type) {
+        return Err(anyhow::anyhow!("Unknown cell type '{}'", cell_type));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cells_keep_their_order() {
+        let db_path = std::env::temp_dir().join("test_novem_notebooks.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        db.conn
+            .execute_batch(
+                "INSERT INTO users (id, uuid, email, username) VALUES (1, 'u1', 'a@example.com', 'ana');
+                 INSERT INTO workspaces (id, uuid, name, owner_id) VALUES (1, 'ws', 'Team', 1);
+                 INSERT INTO projects (id, uuid, workspace_id, name, owner_id) VALUES (1, 'q3', 1, 'Q3 review', 1);",
+            )
+            .unwrap();
+
+        let notebook = db.create_notebook(1, "Revenue").unwrap();
+        let new_cell = |source: &str, position| NewCell { position, cell_type: "code".to_string(), source: source.to_string() };
+        let load = db.create_cell(&notebook.uuid, &new_cell("load()", None)).unwrap();
+        db.create_cell(&notebook.uuid, &new_cell("plot()", None)).unwrap();
+        db.create_cell(&notebook.uuid, &new_cell("import pandas", Some(0))).unwrap();
+
+        let sources = |db: &LocalDatabase| {
+            db.get_notebook_cells(notebook.id).unwrap().into_iter().map(|cell| cell.source).collect::<Vec<_>>()
+        };
+        assert_eq!(sources(&db), ["import pandas", "load()", "plot()"]);
+
+        let update = CellUpdate { position: Some(2), source: Some("load_all()".to_string()), ..Default::default() };
+        let moved = db.update_cell(&load.uuid, &update).unwrap().unwrap();
+        assert_eq!(moved.position, 2);
+        assert_eq!(sources(&db), ["import pandas", "plot()", "load_all()"]);
+
+        let update = CellUpdate { cell_type: Some("latex".to_string()), ..Default::default() };
+        assert!(db.update_cell(&load.uuid, &update).is_err());
+        assert!(db.update_cell("missing", &CellUpdate::default()).unwrap().is_none());
+
+        // The notebook, three cell creates and one edit
+        assert_eq!(db.get_pending_sync_items().unwrap().len(), 5);
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
@@ -0,0 +1,219 @@
+use anyhow::Result;
+use rusqlite::{params, params_from_iter, Row};
+use serde::{Deserialize, Serialize};
+
+use super::LocalDatabase;
+
+/// How loudly a notification is announced. High ones also raise an OS
+/// toast; the rest only land in the inbox.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationPriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+impl NotificationPriority {
+    fn as_str(self) -> &'static str {
+        match self {
+            NotificationPriority::Low => "low",
+            NotificationPriority::Normal => "normal",
+            NotificationPriority::High => "high",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "low" => NotificationPriority::Low,
+            "high" => NotificationPriority::High,
+            _ => NotificationPriority::Normal,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notification {
+    pub id: i64,
+    pub kind: String, // 'sync', 'job', 'engine', 'dataset'
+    pub priority: NotificationPriority,
+    pub title: String,
+    pub body: Option<String>,
+    /// What the notification is about, for navigating to it
+    pub entity_type: Option<String>,
+    pub entity_uuid: Option<String>,
+    pub is_read: bool,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewNotification {
+    pub kind: String,
+    pub priority: NotificationPriority,
+    pub title: String,
+    pub body: Option<String>,
+    pub entity_type: Option<String>,
+    pub entity_uuid: Option<String>,
+}
+
+impl NewNotification {
+    pub fn new(kind: &str, priority: NotificationPriority, title: impl Into<String>) -> Self {
+        Self {
+            kind: kind.to_string(),
+            priority,
+            title: title.into(),
+            body: None,
+            entity_type: None,
+            entity_uuid: None,
+        }
+    }
+
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    pub fn about(mut self, entity_type: &str, entity_uuid: &str) -> Self {
+        self.entity_type = Some(entity_type.to_string());
+        self.entity_uuid = Some(entity_uuid.to_string());
+        self
+    }
+}
+
+/// Read notifications older than this are pruned.
+const READ_RETENTION_DAYS: i64 = 30;
+
+const NOTIFICATION_COLUMNS: &str =
+    "id, kind, priority, title, body, entity_type, entity_uuid, is_read, created_at";
+
+fn notification_from_row(row: &Row) -> rusqlite::Result<Notification> {
+    Ok(Notification {
+        id: row.get(0)?,
+        kind: row.get(1)?,
+        priority: NotificationPriority::parse(&row.get::<_, String>(2)?),
+        title: row.get(3)?,
+        body: row.get(4)?,
+        entity_type: row.get(5)?,
+        entity_uuid: row.get(6)?,
+        is_read: row.get(7)?,
+        created_at: row.get(8)?,
+    })
+}
+
+impl LocalDatabase {
+    // Notification operations
+    pub fn add_notification(&self, notification: &NewNotification) -> Result<Notification> {
+        self.conn.execute(
+            "INSERT INTO notifications (kind, priority, title, body, entity_type, entity_uuid)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                &notification.kind,
+                notification.priority.as_str(),
+                &notification.title,
+                &notification.body,
+                &notification.entity_type,
+                &notification.entity_uuid,
+            ],
+        )?;
+
+        let notification = self.conn.query_row(
+            &format!("SELECT {} FROM notifications WHERE id = ?1", NOTIFICATION_COLUMNS),
+            params![self.conn.last_insert_rowid()],
+            notification_from_row,
+        )?;
+        Ok(notification)
+    }
+
+    /// Newest first.
+    pub fn get_notifications(&self, unread_only: bool, limit: i64) -> Result<Vec<Notification>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM notifications
+             WHERE ?1 = 0 OR is_read = 0
+             ORDER BY created_at DESC, id DESC
+             LIMIT ?2",
+            NOTIFICATION_COLUMNS
+        ))?;
+
+        let notifications = stmt
+            .query_map(params![unread_only, limit], notification_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(notifications)
+    }
+
+    pub fn count_unread_notifications(&self) -> Result<i64> {
+        let count = self
+            .conn
+            .query_row("SELECT COUNT(*) FROM notifications WHERE is_read = 0", [], |row| row.get(0))?;
+        Ok(count)
+    }
+
+    /// Marks the given notifications read, or all of them when `ids` is
+    /// `None`. Returns how many changed.
+    pub fn mark_notifications_read(&self, ids: Option<&[i64]>) -> Result<usize> {
+        let count = match ids {
+            Some([]) => 0,
+            Some(ids) => self.conn.execute(
+                &format!(
+                    "UPDATE notifications SET is_read = 1 WHERE is_read = 0 AND id IN ({})",
+                    vec!["?"; ids.len()].join(", ")
+                ),
+                params_from_iter(ids),
+            )?,
+            None => self.conn.execute("UPDATE notifications SET is_read = 1 WHERE is_read = 0", [])?,
+        };
+        Ok(count)
+    }
+
+    pub fn prune_read_notifications(&self) -> Result<usize> {
+        let count = self.conn.execute(
+            "DELETE FROM notifications
+             WHERE is_read = 1 AND created_at < strftime('%Y-%m-%dT%H:%M:%SZ', 'now', ?1)",
+            params![format!("-{} days", READ_RETENTION_DAYS)],
+        )?;
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notification_inbox() {
+        let db_path = std::env::temp_dir().join("test_novem_notifications.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        let crashed = db
+            .add_notification(&NewNotification::new("engine", NotificationPriority::High, "Engine crashed"))
+            .unwrap();
+        assert_eq!(crashed.priority, NotificationPriority::High);
+        let job = db
+            .add_notification(
+                &NewNotification::new("job", NotificationPriority::Normal, "Job finished")
+                    .body("train_model completed")
+                    .about("job", "j1"),
+            )
+            .unwrap();
+        db.add_notification(&NewNotification::new("sync", NotificationPriority::Low, "Synced")).unwrap();
+
+        assert_eq!(db.count_unread_notifications().unwrap(), 3);
+        assert_eq!(db.mark_notifications_read(Some(&[crashed.id, job.id])).unwrap(), 2);
+        assert_eq!(db.mark_notifications_read(Some(&[crashed.id])).unwrap(), 0);
+        assert_eq!(db.get_notifications(true, 50).unwrap().len(), 1);
+        assert_eq!(db.get_notifications(false, 50).unwrap().len(), 3);
+
+        assert_eq!(db.mark_notifications_read(None).unwrap(), 1);
+        assert_eq!(db.count_unread_notifications().unwrap(), 0);
+
+        db.conn
+            .execute("UPDATE notifications SET created_at = '2000-01-01T00:00:00Z' WHERE id = ?1", params![job.id])
+            .unwrap();
+        assert_eq!(db.prune_read_notifications().unwrap(), 1);
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
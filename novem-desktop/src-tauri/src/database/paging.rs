@@ -0,0 +1,226 @@
+use anyhow::Result;
+use rusqlite::types::Value as SqlValue;
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::tr;
+
+const DEFAULT_PAGE_SIZE: i64 = 100;
+const MAX_PAGE_SIZE: i64 = 500;
+
+/// Which page of a sorted list. Pages are keyed on the last row seen rather
+/// than an offset, so they stay cheap deep into long lists and don't shift
+/// when rows are added.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PageRequest {
+    /// The previous page's `next_cursor`; the first page when omitted
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Matching rows across every page
+    pub total: i64,
+    /// Pass back as `cursor` for the next page; `None` on the last one
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    Asc,
+    #[default]
+    Desc,
+}
+
+impl SortDirection {
+    pub(super) fn sql(self) -> &'static str {
+        match self {
+            SortDirection::Asc => "ASC",
+            SortDirection::Desc => "DESC",
+        }
+    }
+
+    /// Compares a row with the cursor: rows after it in this direction.
+    pub(super) fn after(self) -> &'static str {
+        match self {
+            SortDirection::Asc => ">",
+            SortDirection::Desc => "<",
+        }
+    }
+}
+
+/// Where the previous page ended: the sort it was taken under, and its last
+/// row's pinned flag, sort value and ID (which breaks ties). Lists using it
+/// are ordered pinned rows first.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Cursor {
+    pub sort: String,
+    pub pinned: bool,
+    pub value: String,
+    pub id: i64,
+}
+
+impl Cursor {
+    fn encode(&self) -> String {
+        format!("{}:{}:{}~{}", self.sort, self.pinned as u8, self.value, self.id)
+    }
+
+    fn decode(token: &str) -> Option<Self> {
+        let (sort, rest) = token.split_once(':')?;
+        let (pinned, rest) = rest.split_once(':')?;
+        let (value, id) = rest.rsplit_once('~')?;
+        Some(Self {
+            sort: sort.to_string(),
+            pinned: match pinned {
+                "0" => false,
+                "1" => true,
+                _ => return None,
+            },
+            value: value.to_string(),
+            id: id.parse().ok()?,
+        })
+    }
+
+    /// SQL matching the rows after this cursor, for a list ordered by
+    /// `is_pinned DESC, <expression> <direction>, id <direction>`. Takes
+    /// `values()` as parameters numbered from `first`.
+    pub(super) fn condition(&self, expression: &str, direction: SortDirection, first: usize) -> String {
+        format!(
+            "(is_pinned < ?{pinned} OR (is_pinned = ?{pinned} AND ({expression}, id) {after} (?{value}, ?{id})))",
+            pinned = first,
+            value = first + 1,
+            id = first + 2,
+            expression = expression,
+            after = direction.after(),
+        )
+    }
+
+    pub(super) fn values(self) -> [SqlValue; 3] {
+        [SqlValue::Integer(self.pinned as i64), SqlValue::Text(self.value), SqlValue::Integer(self.id)]
+    }
+}
+
+impl PageRequest {
+    pub(super) fn limit(&self) -> i64 {
+        self.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE)
+    }
+
+    /// The decoded cursor. One from a list sorted differently is refused,
+    /// since its position means nothing under `sort`.
+    pub(super) fn cursor(&self, sort: &str) -> Result<Option<Cursor>> {
+        let Some(token) = self.cursor.as_deref() else {
+            return Ok(None);
+        };
+        match Cursor::decode(token) {
+            Some(cursor) if cursor.sort == sort => Ok(Some(cursor)),
+            _ => Err(anyhow::anyhow!(tr!("page-cursor-invalid"))),
+        }
+    }
+}
+
+impl<T> Page<T> {
+    /// Builds a page from up to `limit + 1` rows; the extra one only tells
+    /// whether another page follows.
+    pub(super) fn from_rows(mut items: Vec<T>, limit: i64, total: i64, key: impl Fn(&T) -> Cursor) -> Self {
+        let next_cursor = if items.len() as i64 > limit {
+            items.truncate(limit as usize);
+            items.last().map(|item| key(item).encode())
+        } else {
+            None
+        };
+
+        Self { items, total, next_cursor }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{LocalDatabase, ProjectQuery, ProjectSort};
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let cursor = Cursor {
+            sort: "updated_at".to_string(),
+            pinned: true,
+            value: "2025-03-14T09:26:53Z".to_string(),
+            id: 42,
+        };
+        assert_eq!(Cursor::decode(&cursor.encode()).unwrap(), cursor);
+        assert!(Cursor::decode("42").is_none());
+        assert!(Cursor::decode("updated_at:0:2025-03-14T09:26:53Z~x").is_none());
+        assert!(Cursor::decode("updated_at:2025-03-14T09:26:53Z~42").is_none());
+
+        // Names may contain the separators
+        let cursor = Cursor { sort: "name".to_string(), pinned: false, value: "Q1: a~b".to_string(), id: 7 };
+        assert_eq!(Cursor::decode(&cursor.encode()).unwrap(), cursor);
+
+        let request = PageRequest { cursor: Some(cursor.encode()), limit: Some(10_000) };
+        assert!(request.cursor("name").unwrap().is_some());
+        assert!(request.cursor("updated_at").is_err());
+        assert_eq!(request.limit(), MAX_PAGE_SIZE);
+
+        let page = Page::from_rows(vec![3, 2, 1], 2, 3, |id| Cursor {
+            sort: "id".to_string(),
+            pinned: false,
+            value: String::new(),
+            id: *id,
+        });
+        assert_eq!(page.items, vec![3, 2]);
+        assert_eq!(page.next_cursor.as_deref(), Some("id:0:~2"));
+    }
+
+    #[test]
+    fn test_projects_page_through() {
+        let db_path = std::env::temp_dir().join("test_novem_paging.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        // Two projects share an update time, so the ID breaks the tie
+        db.conn
+            .execute_batch(
+                "INSERT INTO users (id, uuid, email, username) VALUES (1, 'u1', 'a@example.com', 'ana');
+                 INSERT INTO workspaces (id, uuid, name, owner_id) VALUES (1, 'ws1', 'Team', 1);
+                 INSERT INTO projects (id, uuid, workspace_id, name, owner_id, updated_at) VALUES
+                     (1, 'p1', 1, 'Sales', 1, '2025-01-01T00:00:00Z'),
+                     (2, 'p2', 1, 'Churn', 1, '2025-02-01T00:00:00Z'),
+                     (3, 'p3', 1, 'Ops', 1, '2025-02-01T00:00:00Z');",
+            )
+            .unwrap();
+
+        let mut page = PageRequest { cursor: None, limit: Some(2) };
+        let first = db.get_projects(1, 1, &ProjectQuery::default(), &page).unwrap();
+        assert_eq!(first.total, 3);
+        assert_eq!(first.items.iter().map(|p| p.id).collect::<Vec<_>>(), vec![3, 2]);
+
+        page.cursor = first.next_cursor;
+        let second = db.get_projects(1, 1, &ProjectQuery::default(), &page).unwrap();
+        assert_eq!(second.items.iter().map(|p| p.id).collect::<Vec<_>>(), vec![1]);
+        assert!(second.next_cursor.is_none());
+
+        page.cursor = Some("garbage".to_string());
+        assert!(db.get_projects(1, 1, &ProjectQuery::default(), &page).is_err());
+
+        // By name, ascending, filtered; a cursor carries over between pages
+        db.conn.execute("UPDATE projects SET sync_status = 'synced' WHERE id != 2", []).unwrap();
+        let query = ProjectQuery {
+            sort: ProjectSort::Name,
+            direction: SortDirection::Asc,
+            name: Some("S".to_string()),
+            sync_status: Some("synced".to_string()),
+            ..Default::default()
+        };
+        let mut page = PageRequest { cursor: None, limit: Some(1) };
+        let first = db.get_projects(1, 1, &query, &page).unwrap();
+        assert_eq!(first.total, 2);
+        assert_eq!(first.items[0].name, "Ops");
+        page.cursor = first.next_cursor;
+        assert_eq!(db.get_projects(1, 1, &query, &page).unwrap().items[0].name, "Sales");
+        assert!(db.get_projects(1, 1, &ProjectQuery::default(), &page).is_err());
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
@@ -0,0 +1,157 @@
+use anyhow::Result;
+use rusqlite::Connection;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use super::{open_connection, DatabaseKey, DbConnection, LocalDatabase};
+use crate::i18n::tr;
+
+/// Enough for UI reads to carry on while sync, refreshes and the recovery
+/// journal write in the background.
+const POOL_SIZE: u32 = 4;
+
+pub(super) type PooledConnection = r2d2::PooledConnection<ConnectionManager>;
+
+/// Opens pool connections the same way `LocalDatabase::new` opens its own.
+pub(super) struct ConnectionManager {
+    db_path: PathBuf,
+    key: Option<DatabaseKey>,
+}
+
+impl r2d2::ManageConnection for ConnectionManager {
+    type Connection = Connection;
+    type Error = rusqlite::Error;
+
+    fn connect(&self) -> Result<Connection, rusqlite::Error> {
+        open_connection(&self.db_path, self.key.as_ref())
+    }
+
+    fn is_valid(&self, conn: &mut Connection) -> Result<(), rusqlite::Error> {
+        conn.execute_batch("")
+    }
+
+    fn has_broken(&self, _conn: &mut Connection) -> bool {
+        false
+    }
+}
+
+/// Connections to the local database shared by all commands, so a UI read
+/// doesn't wait behind a long sync write. WAL mode lets them overlap;
+/// writers still take turns, waiting up to the busy timeout. Clones share
+/// the same connections.
+#[derive(Clone)]
+pub struct DatabasePool {
+    db_path: Arc<RwLock<PathBuf>>,
+    key: Option<DatabaseKey>,
+    pool: Arc<RwLock<Option<r2d2::Pool<ConnectionManager>>>>,
+}
+
+impl DatabasePool {
+    /// Migrates the database, then opens the pool on it.
+    pub fn open(db_path: PathBuf, key: Option<DatabaseKey>) -> Result<Self> {
+        let pool = Self::build(&db_path, key.as_ref())?;
+        Ok(Self { db_path: Arc::new(RwLock::new(db_path)), key, pool: Arc::new(RwLock::new(Some(pool))) })
+    }
+
+    fn build(db_path: &Path, key: Option<&DatabaseKey>) -> Result<r2d2::Pool<ConnectionManager>> {
+        drop(LocalDatabase::new(db_path.to_path_buf(), key)?);
+
+        let manager = ConnectionManager { db_path: db_path.to_path_buf(), key: key.cloned() };
+        Ok(r2d2::Pool::builder().max_size(POOL_SIZE).build(manager)?)
+    }
+
+    /// Borrows a connection until the returned database is dropped.
+    pub fn get(&self) -> Result<LocalDatabase> {
+        let pool = self.pool.read().map_err(|e| anyhow::anyhow!("Failed to lock database: {}", e))?;
+        let pool = pool.as_ref().ok_or_else(|| anyhow::anyhow!(tr!("database-not-initialized")))?;
+
+        Ok(LocalDatabase { conn: DbConnection::Pooled(pool.get()?) })
+    }
+
+    /// Runs `f` on a pooled connection. Calls run concurrently, except with
+    /// `reopen_after`.
+    pub fn with<T>(&self, f: impl FnOnce(&LocalDatabase) -> Result<T>) -> Result<T> {
+        let pool = self.pool.read().map_err(|e| anyhow::anyhow!("Failed to lock database: {}", e))?;
+        let pool = pool.as_ref().ok_or_else(|| anyhow::anyhow!(tr!("database-not-initialized")))?;
+
+        f(&LocalDatabase { conn: DbConnection::Pooled(pool.get()?) })
+    }
+
+    /// `with` on the blocking thread pool, for async code: SQLite calls
+    /// block, and would otherwise stall the executor running commands.
+    pub async fn run<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&LocalDatabase) -> Result<T> + Send + 'static,
+    ) -> Result<T> {
+        let pool = self.clone();
+        tauri::async_runtime::spawn_blocking(move || pool.with(f)).await?
+    }
+
+    /// Waits for running calls, closes every connection, runs `f` (which
+    /// may delete the file) and opens the database again.
+    pub fn reopen_after<T>(&self, f: impl FnOnce() -> T) -> Result<T> {
+        let mut pool = self.pool.write().map_err(|e| anyhow::anyhow!("Failed to lock database: {}", e))?;
+        drop(pool.take());
+
+        let result = f();
+        let db_path = self.db_path.read().map_err(|e| anyhow::anyhow!("Failed to lock database path: {}", e))?;
+        *pool = Some(Self::build(&db_path, self.key.as_ref())?);
+        Ok(result)
+    }
+
+    /// Waits for running calls and moves every connection over to another
+    /// database file, migrating or creating it first. If that fails the
+    /// current database stays open.
+    pub fn reopen_at(&self, db_path: PathBuf) -> Result<()> {
+        let mut pool = self.pool.write().map_err(|e| anyhow::anyhow!("Failed to lock database: {}", e))?;
+        let mut current = self.db_path.write().map_err(|e| anyhow::anyhow!("Failed to lock database path: {}", e))?;
+
+        let reopened = Self::build(&db_path, self.key.as_ref())?;
+        *pool = Some(reopened);
+        *current = db_path;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reads_do_not_wait_for_writes() {
+        let db_path = std::env::temp_dir().join("test_novem_pool.db");
+        let _ = std::fs::remove_file(&db_path);
+        let pool = DatabasePool::open(db_path.clone(), None).unwrap();
+
+        let writer = pool.get().unwrap();
+        writer.conn.execute_batch("BEGIN IMMEDIATE").unwrap();
+        writer.set_setting("locale", "\"fr-FR\"").unwrap();
+
+        // Another connection reads the last committed state meanwhile
+        assert_eq!(pool.with(|db| db.get_setting("locale")).unwrap(), None);
+
+        writer.conn.execute_batch("COMMIT").unwrap();
+        drop(writer);
+        assert!(pool.with(|db| db.get_setting("locale")).unwrap().is_some());
+
+        pool.reopen_after(|| std::fs::remove_file(&db_path)).unwrap().unwrap();
+        assert_eq!(pool.with(|db| db.get_setting("locale")).unwrap(), None);
+
+        // Another account's database is separate, and the first one is
+        // still there when switching back
+        pool.with(|db| db.set_setting("locale", "\"es-ES\"")).unwrap();
+        let other_path = std::env::temp_dir().join("test_novem_pool_other.db");
+        let _ = std::fs::remove_file(&other_path);
+        pool.reopen_at(other_path.clone()).unwrap();
+        assert_eq!(pool.with(|db| db.get_setting("locale")).unwrap(), None);
+        pool.reopen_at(db_path.clone()).unwrap();
+        assert!(pool.with(|db| db.get_setting("locale")).unwrap().is_some());
+
+        drop(pool);
+        for path in [&db_path, &other_path] {
+            for suffix in ["", "-wal", "-shm"] {
+                std::fs::remove_file(format!("{}{}", path.display(), suffix)).ok();
+            }
+        }
+    }
+}
@@ -0,0 +1,674 @@
+use anyhow::Result;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::{LocalDatabase, User};
+use crate::i18n::tr;
+use crate::timestamps;
+
+/// Written into every bundle so a stray JSON file isn't mistaken for one.
+pub const LOCAL_DATA_FORMAT: &str = "novem-local-data";
+
+/// Bumped whenever the bundle's shape changes. Bundles from older versions
+/// stay importable; newer ones are refused.
+pub const LOCAL_DATA_VERSION: u32 = 1;
+
+/// This machine's workspaces, projects and datasets in a form another
+/// machine can import. Rows refer to each other by UUID, since local IDs
+/// differ between databases. Only metadata travels: dataset files stay
+/// where they are, and trashed entities are left out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalDataBundle {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+    pub users: Vec<User>,
+    pub workspaces: Vec<PortableWorkspace>,
+    pub projects: Vec<PortableProject>,
+    pub datasets: Vec<PortableDataset>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortableWorkspace {
+    pub uuid: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub owner_uuid: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub is_active: bool,
+    pub sync_status: String,
+    pub last_synced_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortableProject {
+    pub uuid: String,
+    pub workspace_uuid: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub owner_uuid: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub is_active: bool,
+    pub sync_status: String,
+    pub last_synced_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortableDataset {
+    pub uuid: String,
+    pub project_uuid: String,
+    pub name: String,
+    pub file_path: String,
+    pub format: String,
+    pub row_count: Option<i64>,
+    pub size_bytes: i64,
+    pub parent_uuid: Option<String>,
+    pub default_sample_uuid: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub is_active: bool,
+    pub sync_status: String,
+    pub last_synced_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct LocalDataCounts {
+    pub users: usize,
+    pub workspaces: usize,
+    pub projects: usize,
+    pub datasets: usize,
+}
+
+impl LocalDataBundle {
+    pub fn counts(&self) -> LocalDataCounts {
+        LocalDataCounts {
+            users: self.users.len(),
+            workspaces: self.workspaces.len(),
+            projects: self.projects.len(),
+            datasets: self.datasets.len(),
+        }
+    }
+}
+
+/// Which copy wins when an imported entity already exists here and the two
+/// were changed separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportConflictPolicy {
+    /// The one updated most recently
+    #[default]
+    KeepNewest,
+    KeepLocal,
+    PreferImported,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictResolution {
+    KeptLocal,
+    Imported,
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportConflict {
+    pub entity_type: String,
+    pub uuid: String,
+    pub resolution: ConflictResolution,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportCounts {
+    pub inserted: usize,
+    pub updated: usize,
+    /// Already here, and the local copy was kept
+    pub kept: usize,
+    pub skipped: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportReport {
+    pub users: ImportCounts,
+    pub workspaces: ImportCounts,
+    pub projects: ImportCounts,
+    pub datasets: ImportCounts,
+    pub conflicts: Vec<ImportConflict>,
+    /// Imported datasets whose file isn't on this machine
+    pub missing_files: Vec<String>,
+}
+
+/// What happens to one incoming row.
+enum Merge {
+    Insert,
+    Update(i64),
+    Keep(i64),
+}
+
+impl ImportCounts {
+    fn record(&mut self, merge: Option<&Merge>) {
+        match merge {
+            Some(Merge::Insert) => self.inserted += 1,
+            Some(Merge::Update(_)) => self.updated += 1,
+            Some(Merge::Keep(_)) => self.kept += 1,
+            None => self.skipped += 1,
+        }
+    }
+}
+
+impl ImportReport {
+    fn conflict(&mut self, entity_type: &str, uuid: &str, resolution: ConflictResolution, reason: String) {
+        self.conflicts.push(ImportConflict {
+            entity_type: entity_type.to_string(),
+            uuid: uuid.to_string(),
+            resolution,
+            reason,
+        });
+    }
+}
+
+impl LocalDatabase {
+    // Export and import operations
+    pub fn export_local_data(&self) -> Result<LocalDataBundle> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, uuid, email, username, first_name, last_name, is_active, last_login, created_at
+             FROM users ORDER BY id"
+        )?;
+        let users = stmt
+            .query_map([], |row| {
+                Ok(User {
+                    id: row.get(0)?,
+                    uuid: row.get(1)?,
+                    email: row.get(2)?,
+                    username: row.get(3)?,
+                    first_name: row.get(4)?,
+                    last_name: row.get(5)?,
+                    is_active: row.get(6)?,
+                    last_login: row.get(7)?,
+                    created_at: row.get(8)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut stmt = self.conn.prepare(
+            "SELECT w.uuid, w.name, w.description, u.uuid, w.created_at, w.updated_at,
+                    w.is_active, w.sync_status, w.last_synced_at
+             FROM workspaces w
+             JOIN users u ON u.id = w.owner_id
+             WHERE w.deleted_at IS NULL
+             ORDER BY w.id"
+        )?;
+        let workspaces = stmt
+            .query_map([], |row| {
+                Ok(PortableWorkspace {
+                    uuid: row.get(0)?,
+                    name: row.get(1)?,
+                    description: row.get(2)?,
+                    owner_uuid: row.get(3)?,
+                    created_at: row.get(4)?,
+                    updated_at: row.get(5)?,
+                    is_active: row.get(6)?,
+                    sync_status: row.get(7)?,
+                    last_synced_at: row.get(8)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut stmt = self.conn.prepare(
+            "SELECT p.uuid, w.uuid, p.name, p.description, u.uuid, p.created_at, p.updated_at,
+                    p.is_active, p.sync_status, p.last_synced_at
+             FROM projects p
+             JOIN workspaces w ON w.id = p.workspace_id AND w.deleted_at IS NULL
+             JOIN users u ON u.id = p.owner_id
+             WHERE p.deleted_at IS NULL
+             ORDER BY p.id"
+        )?;
+        let projects = stmt
+            .query_map([], |row| {
+                Ok(PortableProject {
+                    uuid: row.get(0)?,
+                    workspace_uuid: row.get(1)?,
+                    name: row.get(2)?,
+                    description: row.get(3)?,
+                    owner_uuid: row.get(4)?,
+                    created_at: row.get(5)?,
+                    updated_at: row.get(6)?,
+                    is_active: row.get(7)?,
+                    sync_status: row.get(8)?,
+                    last_synced_at: row.get(9)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut stmt = self.conn.prepare(
+            "SELECT d.uuid, p.uuid, d.name, d.file_path, d.format, d.row_count, d.size_bytes,
+                    d.parent_uuid, d.default_sample_uuid, d.created_at, d.updated_at,
+                    d.is_active, d.sync_status, d.last_synced_at
+             FROM datasets d
+             JOIN projects p ON p.id = d.project_id AND p.deleted_at IS NULL
+             JOIN workspaces w ON w.id = p.workspace_id AND w.deleted_at IS NULL
+             WHERE d.deleted_at IS NULL
+             ORDER BY d.id"
+        )?;
+        let datasets = stmt
+            .query_map([], |row| {
+                Ok(PortableDataset {
+                    uuid: row.get(0)?,
+                    project_uuid: row.get(1)?,
+                    name: row.get(2)?,
+                    file_path: row.get(3)?,
+                    format: row.get(4)?,
+                    row_count: row.get(5)?,
+                    size_bytes: row.get(6)?,
+                    parent_uuid: row.get(7)?,
+                    default_sample_uuid: row.get(8)?,
+                    created_at: row.get(9)?,
+                    updated_at: row.get(10)?,
+                    is_active: row.get(11)?,
+                    sync_status: row.get(12)?,
+                    last_synced_at: row.get(13)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(LocalDataBundle {
+            format: LOCAL_DATA_FORMAT.to_string(),
+            version: LOCAL_DATA_VERSION,
+            exported_at: timestamps::now(),
+            users,
+            workspaces,
+            projects,
+            datasets,
+        })
+    }
+
+    /// Merges a bundle into this database in one transaction. Entities are
+    /// matched by UUID; see `ImportConflictPolicy` for which copy wins. An
+    /// entity whose parent couldn't be imported is skipped, as is one that
+    /// sits in the trash here.
+    pub fn import_local_data(&self, bundle: &LocalDataBundle, policy: ImportConflictPolicy) -> Result<ImportReport> {
+        if bundle.format != LOCAL_DATA_FORMAT {
+            anyhow::bail!(tr!("import-not-a-bundle"));
+        }
+        if bundle.version > LOCAL_DATA_VERSION {
+            anyhow::bail!(tr!("import-newer-version", version = bundle.version));
+        }
+
+        let tx = self.begin()?;
+        let mut report = ImportReport::default();
+
+        let mut user_ids = HashMap::new();
+        for user in &bundle.users {
+            if let Some(id) = self.import_user(user, &mut report)? {
+                user_ids.insert(user.uuid.clone(), id);
+            }
+        }
+
+        let mut workspace_ids = HashMap::new();
+        for workspace in &bundle.workspaces {
+            let Some(owner_id) = self.resolve_id("users", &user_ids, &workspace.owner_uuid)? else {
+                report.conflict("workspace", &workspace.uuid, ConflictResolution::Skipped, "owner not found".to_string());
+                report.workspaces.record(None);
+                continue;
+            };
+
+            let merge = self.merge_plan("workspaces", "workspace", &workspace.uuid, &workspace.updated_at, policy, &mut report)?;
+            report.workspaces.record(merge.as_ref());
+            let id = match merge {
+                Some(Merge::Insert) => {
+                    self.conn.execute(
+                        "INSERT INTO workspaces (uuid, name, description, owner_id, created_at, updated_at,
+                                                 is_active, sync_status, last_synced_at)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                        params![
+                            &workspace.uuid,
+                            &workspace.name,
+                            &workspace.description,
+                            owner_id,
+                            timestamps::normalize(&workspace.created_at),
+                            timestamps::normalize(&workspace.updated_at),
+                            workspace.is_active,
+                            &workspace.sync_status,
+                            timestamps::normalize_opt(workspace.last_synced_at.as_deref()),
+                        ],
+                    )?;
+                    self.conn.last_insert_rowid()
+                }
+                Some(Merge::Update(id)) => {
+                    self.conn.execute(
+                        "UPDATE workspaces SET name = ?1, description = ?2, updated_at = ?3, is_active = ?4,
+                                               sync_status = ?5, last_synced_at = ?6
+                         WHERE id = ?7",
+                        params![
+                            &workspace.name,
+                            &workspace.description,
+                            timestamps::normalize(&workspace.updated_at),
+                            workspace.is_active,
+                            &workspace.sync_status,
+                            timestamps::normalize_opt(workspace.last_synced_at.as_deref()),
+                            id,
+                        ],
+                    )?;
+                    id
+                }
+                Some(Merge::Keep(id)) => id,
+                None => continue,
+            };
+            workspace_ids.insert(workspace.uuid.clone(), id);
+        }
+
+        let mut project_ids = HashMap::new();
+        for project in &bundle.projects {
+            let owner_id = self.resolve_id("users", &user_ids, &project.owner_uuid)?;
+            let workspace_id = self.resolve_id("workspaces", &workspace_ids, &project.workspace_uuid)?;
+            let (Some(owner_id), Some(workspace_id)) = (owner_id, workspace_id) else {
+                let reason = if owner_id.is_none() { "owner not found" } else { "workspace not imported" };
+                report.conflict("project", &project.uuid, ConflictResolution::Skipped, reason.to_string());
+                report.projects.record(None);
+                continue;
+            };
+
+            let merge = self.merge_plan("projects", "project", &project.uuid, &project.updated_at, policy, &mut report)?;
+            report.projects.record(merge.as_ref());
+            let id = match merge {
+                Some(Merge::Insert) => {
+                    self.conn.execute(
+                        "INSERT INTO projects (uuid, workspace_id, name, description, owner_id, created_at, updated_at,
+                                               is_active, sync_status, last_synced_at)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                        params![
+                            &project.uuid,
+                            workspace_id,
+                            &project.name,
+                            &project.description,
+                            owner_id,
+                            timestamps::normalize(&project.created_at),
+                            timestamps::normalize(&project.updated_at),
+                            project.is_active,
+                            &project.sync_status,
+                            timestamps::normalize_opt(project.last_synced_at.as_deref()),
+                        ],
+                    )?;
+                    self.conn.last_insert_rowid()
+                }
+                Some(Merge::Update(id)) => {
+                    self.conn.execute(
+                        "UPDATE projects SET workspace_id = ?1, name = ?2, description = ?3, updated_at = ?4,
+                                             is_active = ?5, sync_status = ?6, last_synced_at = ?7
+                         WHERE id = ?8",
+                        params![
+                            workspace_id,
+                            &project.name,
+                            &project.description,
+                            timestamps::normalize(&project.updated_at),
+                            project.is_active,
+                            &project.sync_status,
+                            timestamps::normalize_opt(project.last_synced_at.as_deref()),
+                            id,
+                        ],
+                    )?;
+                    id
+                }
+                Some(Merge::Keep(id)) => id,
+                None => continue,
+            };
+            project_ids.insert(project.uuid.clone(), id);
+        }
+
+        for dataset in &bundle.datasets {
+            let Some(project_id) = self.resolve_id("projects", &project_ids, &dataset.project_uuid)? else {
+                report.conflict("dataset", &dataset.uuid, ConflictResolution::Skipped, "project not imported".to_string());
+                report.datasets.record(None);
+                continue;
+            };
+
+            let merge = self.merge_plan("datasets", "dataset", &dataset.uuid, &dataset.updated_at, policy, &mut report)?;
+            report.datasets.record(merge.as_ref());
+            match merge {
+                Some(Merge::Insert) => {
+                    self.conn.execute(
+                        "INSERT INTO datasets (uuid, project_id, name, file_path, format, row_count, size_bytes,
+                                               parent_uuid, default_sample_uuid, created_at, updated_at,
+                                               is_active, sync_status, last_synced_at)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+                        params![
+                            &dataset.uuid,
+                            project_id,
+                            &dataset.name,
+                            &dataset.file_path,
+                            &dataset.format,
+                            dataset.row_count,
+                            dataset.size_bytes,
+                            &dataset.parent_uuid,
+                            &dataset.default_sample_uuid,
+                            timestamps::normalize(&dataset.created_at),
+                            timestamps::normalize(&dataset.updated_at),
+                            dataset.is_active,
+                            &dataset.sync_status,
+                            timestamps::normalize_opt(dataset.last_synced_at.as_deref()),
+                        ],
+                    )?;
+                }
+                Some(Merge::Update(id)) => {
+                    self.conn.execute(
+                        "UPDATE datasets SET project_id = ?1, name = ?2, file_path = ?3, format = ?4, row_count = ?5,
+                                             size_bytes = ?6, parent_uuid = ?7, default_sample_uuid = ?8,
+                                             updated_at = ?9, is_active = ?10, sync_status = ?11, last_synced_at = ?12
+                         WHERE id = ?13",
+                        params![
+                            project_id,
+                            &dataset.name,
+                            &dataset.file_path,
+                            &dataset.format,
+                            dataset.row_count,
+                            dataset.size_bytes,
+                            &dataset.parent_uuid,
+                            &dataset.default_sample_uuid,
+                            timestamps::normalize(&dataset.updated_at),
+                            dataset.is_active,
+                            &dataset.sync_status,
+                            timestamps::normalize_opt(dataset.last_synced_at.as_deref()),
+                            id,
+                        ],
+                    )?;
+                }
+                Some(Merge::Keep(_)) | None => continue,
+            }
+
+            if !Path::new(&dataset.file_path).exists() {
+                report.missing_files.push(dataset.file_path.clone());
+            }
+        }
+
+        tx.commit()?;
+        Ok(report)
+    }
+
+    /// Users carry no update time, so one already here is always kept. A
+    /// new user whose email or username is taken is merged into the local
+    /// account holding it. Returns the local ID to use for the user.
+    fn import_user(&self, user: &User, report: &mut ImportReport) -> Result<Option<i64>> {
+        let existing: Option<i64> = self.conn
+            .query_row("SELECT id FROM users WHERE uuid = ?1", params![&user.uuid], |row| row.get(0))
+            .optional()?;
+        if let Some(id) = existing {
+            report.users.record(Some(&Merge::Keep(id)));
+            return Ok(Some(id));
+        }
+
+        let same_account: Option<i64> = self.conn
+            .query_row(
+                "SELECT id FROM users WHERE email = ?1 OR username = ?2",
+                params![&user.email, &user.username],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if let Some(id) = same_account {
+            report.conflict(
+                "user",
+                &user.uuid,
+                ConflictResolution::KeptLocal,
+                format!("email or username belongs to local user {}", id),
+            );
+            report.users.record(Some(&Merge::Keep(id)));
+            return Ok(Some(id));
+        }
+
+        // IDs come from the backend and are kept where free, since other
+        // commands look users up by them
+        let id_taken = self.conn
+            .query_row("SELECT 1 FROM users WHERE id = ?1", params![user.id], |_| Ok(()))
+            .optional()?
+            .is_some();
+        self.conn.execute(
+            "INSERT INTO users (id, uuid, email, username, first_name, last_name, is_active, last_login, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                if id_taken { None } else { Some(user.id) },
+                &user.uuid,
+                &user.email,
+                &user.username,
+                &user.first_name,
+                &user.last_name,
+                user.is_active,
+                timestamps::normalize_opt(user.last_login.as_deref()),
+                timestamps::normalize(&user.created_at),
+            ],
+        )?;
+        report.users.record(Some(&Merge::Insert));
+        Ok(Some(self.conn.last_insert_rowid()))
+    }
+
+    /// The local ID for `uuid`: imported in this run, or already here and
+    /// not trashed.
+    fn resolve_id(&self, table: &str, imported: &HashMap<String, i64>, uuid: &str) -> Result<Option<i64>> {
+        if let Some(id) = imported.get(uuid) {
+            return Ok(Some(*id));
+        }
+
+        let trash_filter = if table == "users" { "" } else { " AND deleted_at IS NULL" };
+        let id = self.conn
+            .query_row(
+                &format!("SELECT id FROM {} WHERE uuid = ?1{}", table, trash_filter),
+                params![uuid],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(id)
+    }
+
+    /// Compares an incoming row with the local one sharing its UUID and
+    /// records a conflict when both changed. `None` means skip it.
+    fn merge_plan(
+        &self,
+        table: &str,
+        entity_type: &str,
+        uuid: &str,
+        updated_at: &str,
+        policy: ImportConflictPolicy,
+        report: &mut ImportReport,
+    ) -> Result<Option<Merge>> {
+        let local: Option<(i64, String, Option<String>)> = self.conn
+            .query_row(
+                &format!("SELECT id, updated_at, deleted_at FROM {} WHERE uuid = ?1", table),
+                params![uuid],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?;
+        let Some((id, local_updated_at, deleted_at)) = local else {
+            return Ok(Some(Merge::Insert));
+        };
+
+        if deleted_at.is_some() {
+            report.conflict(entity_type, uuid, ConflictResolution::Skipped, "in the trash on this machine".to_string());
+            return Ok(None);
+        }
+
+        let incoming_updated_at = timestamps::normalize(updated_at);
+        if incoming_updated_at == local_updated_at {
+            return Ok(Some(Merge::Keep(id)));
+        }
+
+        let take_incoming = match policy {
+            ImportConflictPolicy::KeepNewest => incoming_updated_at > local_updated_at,
+            ImportConflictPolicy::KeepLocal => false,
+            ImportConflictPolicy::PreferImported => true,
+        };
+        let (resolution, merge) = if take_incoming {
+            (ConflictResolution::Imported, Merge::Update(id))
+        } else {
+            (ConflictResolution::KeptLocal, Merge::Keep(id))
+        };
+        report.conflict(
+            entity_type,
+            uuid,
+            resolution,
+            format!("updated here at {} and in the bundle at {}", local_updated_at, incoming_updated_at),
+        );
+        Ok(Some(merge))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seed(db: &LocalDatabase) {
+        db.conn
+            .execute_batch(
+                "INSERT INTO users (id, uuid, email, username) VALUES (7, 'u7', 'ada@example.com', 'ada');
+                 INSERT INTO workspaces (id, uuid, name, owner_id, updated_at) VALUES (1, 'ws', 'Research', 7, '2025-01-01T00:00:00Z');
+                 INSERT INTO projects (id, uuid, workspace_id, name, owner_id, updated_at) VALUES (1, 'pr', 1, 'Churn', 7, '2025-01-01T00:00:00Z');
+                 INSERT INTO datasets (uuid, project_id, name, file_path, format) VALUES ('ds', 1, 'events', '/nowhere/events.csv', 'csv');",
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_export_and_merge() {
+        let source_path = std::env::temp_dir().join("test_novem_export_source.db");
+        let target_path = std::env::temp_dir().join("test_novem_export_target.db");
+        let _ = std::fs::remove_file(&source_path);
+        let _ = std::fs::remove_file(&target_path);
+        let source = LocalDatabase::new(source_path.clone(), None).unwrap();
+        let target = LocalDatabase::new(target_path.clone(), None).unwrap();
+
+        seed(&source);
+        source.conn.execute("UPDATE projects SET name = 'Churn v2', updated_at = '2025-03-01T00:00:00Z'", []).unwrap();
+        let bundle: LocalDataBundle =
+            serde_json::from_str(&serde_json::to_string(&source.export_local_data().unwrap()).unwrap()).unwrap();
+        assert_eq!(bundle.counts().datasets, 1);
+
+        // Fresh machine: everything is inserted
+        let report = target.import_local_data(&bundle, ImportConflictPolicy::KeepNewest).unwrap();
+        assert_eq!(report.workspaces.inserted, 1);
+        assert_eq!(report.datasets.inserted, 1);
+        assert_eq!(report.missing_files, vec!["/nowhere/events.csv".to_string()]);
+
+        // Again: nothing changed, nothing conflicts
+        let report = target.import_local_data(&bundle, ImportConflictPolicy::KeepNewest).unwrap();
+        assert_eq!(report.projects.kept, 1);
+        assert!(report.conflicts.is_empty());
+
+        // A newer local edit survives KeepNewest but not PreferImported
+        target.conn.execute("UPDATE projects SET name = 'Local', updated_at = '2025-06-01T00:00:00Z'", []).unwrap();
+        let report = target.import_local_data(&bundle, ImportConflictPolicy::KeepNewest).unwrap();
+        assert_eq!(report.conflicts[0].resolution, ConflictResolution::KeptLocal);
+        let report = target.import_local_data(&bundle, ImportConflictPolicy::PreferImported).unwrap();
+        assert_eq!(report.projects.updated, 1);
+        let name: String = target.conn.query_row("SELECT name FROM projects WHERE uuid = 'pr'", [], |row| row.get(0)).unwrap();
+        assert_eq!(name, "Churn v2");
+
+        let future = LocalDataBundle { version: LOCAL_DATA_VERSION + 1, ..bundle };
+        assert!(target.import_local_data(&future, ImportConflictPolicy::KeepNewest).is_err());
+
+        drop(source);
+        drop(target);
+        std::fs::remove_file(source_path).ok();
+        std::fs::remove_file(target_path).ok();
+    }
+}
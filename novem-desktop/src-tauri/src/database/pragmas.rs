@@ -0,0 +1,84 @@
+use anyhow::Result;
+use rusqlite::Connection;
+use serde::Serialize;
+use std::time::Duration;
+
+use super::LocalDatabase;
+
+/// How long a write waits for another connection's lock before failing
+/// with `SQLITE_BUSY`.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Connection settings as SQLite reports them, for diagnostics.
+#[derive(Debug, Clone, Serialize)]
+pub struct DbPragmaInfo {
+    pub journal_mode: String,
+    pub synchronous: String,
+    pub busy_timeout_ms: i64,
+    pub foreign_keys: bool,
+    pub wal_autocheckpoint: i64,
+    pub page_size: i64,
+    pub page_count: i64,
+    pub freelist_count: i64,
+    pub schema_version: i64,
+}
+
+/// WAL lets readers and a writer work at once, so background sync doesn't
+/// block UI commands; with it, `synchronous = NORMAL` is still crash-safe
+/// and avoids an fsync per commit.
+pub(super) fn configure(conn: &Connection) -> rusqlite::Result<()> {
+    let journal_mode: String = conn.query_row("PRAGMA journal_mode = WAL", [], |row| row.get(0))?;
+    if !journal_mode.eq_ignore_ascii_case("wal") {
+        eprintln!("[WARNING] Database stayed in {} journal mode", journal_mode);
+    }
+    conn.execute_batch("PRAGMA synchronous = NORMAL")?;
+    conn.busy_timeout(BUSY_TIMEOUT)
+}
+
+impl LocalDatabase {
+    pub fn pragma_info(&self) -> Result<DbPragmaInfo> {
+        let pragma = |name: &str| self.conn.query_row(&format!("PRAGMA {}", name), [], |row| row.get::<_, i64>(0));
+
+        let synchronous = match pragma("synchronous")? {
+            0 => "off",
+            1 => "normal",
+            2 => "full",
+            3 => "extra",
+            _ => "unknown",
+        };
+
+        Ok(DbPragmaInfo {
+            journal_mode: self.conn.query_row("PRAGMA journal_mode", [], |row| row.get(0))?,
+            synchronous: synchronous.to_string(),
+            busy_timeout_ms: pragma("busy_timeout")?,
+            foreign_keys: pragma("foreign_keys")? != 0,
+            wal_autocheckpoint: pragma("wal_autocheckpoint")?,
+            page_size: pragma("page_size")?,
+            page_count: pragma("page_count")?,
+            freelist_count: pragma("freelist_count")?,
+            schema_version: pragma("user_version")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connection_uses_wal() {
+        let db_path = std::env::temp_dir().join("test_novem_pragmas.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        let info = db.pragma_info().unwrap();
+        assert_eq!(info.journal_mode, "wal");
+        assert_eq!(info.synchronous, "normal");
+        assert_eq!(info.busy_timeout_ms, BUSY_TIMEOUT.as_millis() as i64);
+
+        drop(db);
+        for suffix in ["", "-wal", "-shm"] {
+            std::fs::remove_file(format!("{}{}", db_path.display(), suffix)).ok();
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use anyhow::Result;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+use super::LocalDatabase;
+
+const DEFAULT_HISTORY_LIMIT: i64 = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryHistoryEntry {
+    pub id: i64,
+    pub kind: String, // 'sql', 'sample', 'recipe'
+    pub query_text: String,
+    pub target: Option<String>,
+    pub project_id: Option<i64>,
+    pub params: String, // JSON, enough to re-run the query
+    pub duration_ms: i64,
+    pub row_count: Option<i64>,
+    pub status: String, // 'success', 'error'
+    pub error_message: Option<String>,
+    pub is_favorite: bool,
+    pub executed_at: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewQueryHistory {
+    pub kind: String,
+    pub query_text: String,
+    pub target: Option<String>,
+    pub project_id: Option<i64>,
+    pub params: String,
+    pub duration_ms: i64,
+    pub row_count: Option<i64>,
+    pub error_message: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct QueryHistoryFilter {
+    pub kind: Option<String>,
+    pub project_id: Option<i64>,
+    #[serde(default)]
+    pub favorites_only: bool,
+    /// Case-insensitive substring of the query text
+    pub search: Option<String>,
+    /// Only queries executed at or after this timestamp
+    pub since: Option<String>,
+    pub limit: Option<i64>,
+}
+
+const QUERY_HISTORY_COLUMNS: &str =
+    "id, kind, query_text, target, project_id, params, duration_ms, row_count,
+     status, error_message, is_favorite, executed_at";
+
+fn query_history_from_row(row: &Row) -> rusqlite::Result<QueryHistoryEntry> {
+    Ok(QueryHistoryEntry {
+        id: row.get(0)?,
+        kind: row.get(1)?,
+        query_text: row.get(2)?,
+        target: row.get(3)?,
+        project_id: row.get(4)?,
+        params: row.get(5)?,
+        duration_ms: row.get(6)?,
+        row_count: row.get(7)?,
+        status: row.get(8)?,
+        error_message: row.get(9)?,
+        is_favorite: row.get(10)?,
+        executed_at: row.get(11)?,
+    })
+}
+
+impl LocalDatabase {
+    // Query history operations
+    pub fn add_query_history(&self, entry: &NewQueryHistory) -> Result<i64> {
+        let status = if entry.error_message.is_some() { "error" } else { "success" };
+
+        self.conn.execute(
+            "INSERT INTO query_history (kind, query_text, target, project_id, params, duration_ms, row_count, status, error_message)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                &entry.kind,
+                &entry.query_text,
+                &entry.target,
+                entry.project_id,
+                &entry.params,
+                entry.duration_ms,
+                entry.row_count,
+                status,
+                &entry.error_message,
+            ],
+        )?;
+
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    pub fn get_query_history_entry(&self, id: i64) -> Result<Option<QueryHistoryEntry>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM query_history WHERE id = ?1",
+            QUERY_HISTORY_COLUMNS
+        ))?;
+
+        let entry = stmt.query_row(params![id], query_history_from_row).optional()?;
+
+        Ok(entry)
+    }
+
+    pub fn get_query_history(&self, filter: &QueryHistoryFilter) -> Result<Vec<QueryHistoryEntry>> {
+        let mut conditions = Vec::new();
+        let mut values: Vec<SqlValue> = Vec::new();
+
+        if let Some(kind) = &filter.kind {
+            values.push(SqlValue::Text(kind.clone()));
+            conditions.push(format!("kind = ?{}", values.len()));
+        }
+        if let Some(project_id) = filter.project_id {
+            values.push(SqlValue::Integer(project_id));
+            conditions.push(format!("project_id = ?{}", values.len()));
+        }
+        if filter.favorites_only {
+            conditions.push("is_favorite = 1".to_string());
+        }
+        if let Some(search) = &filter.search {
+            values.push(SqlValue::Text(format!("%{}%", search)));
+            conditions.push(format!("query_text LIKE ?{}", values.len()));
+        }
+        if let Some(since) = &filter.since {
+            values.push(SqlValue::Text(crate::timestamps::normalize(since)));
+            conditions.push(format!("executed_at >= ?{}", values.len()));
+        }
+
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+
+        values.push(SqlValue::Integer(filter.limit.unwrap_or(DEFAULT_HISTORY_LIMIT)));
+        let sql = format!(
+            "SELECT {} FROM query_history {} ORDER BY executed_at DESC, id DESC LIMIT ?{}",
+            QUERY_HISTORY_COLUMNS,
+            where_clause,
+            values.len()
+        );
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let entries = stmt
+            .query_map(params_from_iter(values), query_history_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(entries)
+    }
+
+    /// Returns false if no entry has this ID.
+    pub fn set_query_favorite(&self, id: i64, favorite: bool) -> Result<bool> {
+        let updated = self.conn.execute(
+            "UPDATE query_history SET is_favorite = ?1 WHERE id = ?2",
+            params![favorite, id],
+        )?;
+        Ok(updated > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_history_filters() {
+        let db_path = std::env::temp_dir().join("test_novem_query_history.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        let entry = |text: &str, project_id: i64| NewQueryHistory {
+            kind: "sql".to_string(),
+            query_text: text.to_string(),
+            target: None,
+            project_id: Some(project_id),
+            params: "{}".to_string(),
+            duration_ms: 12,
+            row_count: Some(3),
+            error_message: None,
+        };
+
+        let first = db.add_query_history(&entry("SELECT * FROM sales", 1)).unwrap();
+        db.add_query_history(&entry("SELECT count(*) FROM users", 1)).unwrap();
+        db.add_query_history(&entry("SELECT * FROM sales_2023", 2)).unwrap();
+
+        assert!(db.set_query_favorite(first, true).unwrap());
+        assert!(!db.set_query_favorite(999, true).unwrap());
+
+        let sales = db
+            .get_query_history(&QueryHistoryFilter { search: Some("sales".to_string()), ..Default::default() })
+            .unwrap();
+        assert_eq!(sales.len(), 2);
+
+        let favorites = db
+            .get_query_history(&QueryHistoryFilter { project_id: Some(1), favorites_only: true, ..Default::default() })
+            .unwrap();
+        assert_eq!(favorites.len(), 1);
+        assert_eq!(favorites[0].id, first);
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
@@ -0,0 +1,167 @@
+use anyhow::Result;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use super::LocalDatabase;
+
+/// How many opened items are remembered; the oldest are dropped as new
+/// ones are opened.
+const RECENT_ITEMS_KEPT: i64 = 100;
+
+const DEFAULT_RECENT_LIMIT: i64 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecentEntity {
+    Workspace,
+    Project,
+    Notebook,
+}
+
+impl RecentEntity {
+    fn as_str(self) -> &'static str {
+        match self {
+            RecentEntity::Workspace => "workspace",
+            RecentEntity::Project => "project",
+            RecentEntity::Notebook => "notebook",
+        }
+    }
+}
+
+/// Something opened recently, with what the home screen needs to open it
+/// again.
+#[derive(Debug, Clone, Serialize)]
+pub struct RecentItem {
+    pub entity_type: String,
+    pub id: i64,
+    pub uuid: String,
+    pub name: String,
+    /// Workspace of a project or notebook
+    pub workspace_uuid: Option<String>,
+    /// Project of a notebook
+    pub project_id: Option<i64>,
+    pub opened_at: String,
+    pub open_count: i64,
+}
+
+impl LocalDatabase {
+    // Recently opened items
+    pub fn record_opened(&self, entity: RecentEntity, uuid: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO recent_items (entity_type, entity_uuid) VALUES (?1, ?2)
+             ON CONFLICT(entity_type, entity_uuid) DO UPDATE SET
+                opened_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now'),
+                open_count = open_count + 1",
+            params![entity.as_str(), uuid],
+        )?;
+
+        self.conn.execute(
+            "DELETE FROM recent_items WHERE id NOT IN
+                (SELECT id FROM recent_items ORDER BY opened_at DESC, id DESC LIMIT ?1)",
+            params![RECENT_ITEMS_KEPT],
+        )?;
+        Ok(())
+    }
+
+    pub fn record_opened_project(&self, project_id: i64) -> Result<()> {
+        let uuid: Option<String> = self
+            .conn
+            .query_row("SELECT uuid FROM projects WHERE id = ?1", params![project_id], |row| row.get(0))
+            .optional()?;
+        match uuid {
+            Some(uuid) => self.record_opened(RecentEntity::Project, &uuid),
+            None => Ok(()),
+        }
+    }
+
+    /// Most recently opened first. Items since deleted or trashed, or whose
+    /// parent was, are left out.
+    pub fn get_recent_items(&self, limit: Option<i64>) -> Result<Vec<RecentItem>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT r.entity_type, w.id, w.uuid, w.name, NULL, NULL, r.opened_at, r.open_count, r.id
+             FROM recent_items r
+             JOIN workspaces w ON w.uuid = r.entity_uuid AND w.deleted_at IS NULL
+             WHERE r.entity_type = 'workspace'
+             UNION ALL
+             SELECT r.entity_type, p.id, p.uuid, p.name, w.uuid, NULL, r.opened_at, r.open_count, r.id
+             FROM recent_items r
+             JOIN projects p ON p.uuid = r.entity_uuid AND p.deleted_at IS NULL
+             JOIN workspaces w ON w.id = p.workspace_id AND w.deleted_at IS NULL
+             WHERE r.entity_type = 'project'
+             UNION ALL
+             SELECT r.entity_type, n.id, n.uuid, n.name, w.uuid, p.id, r.opened_at, r.open_count, r.id
+             FROM recent_items r
+             JOIN notebooks n ON n.uuid = r.entity_uuid AND n.is_active = 1
+             JOIN projects p ON p.id = n.project_id AND p.deleted_at IS NULL
+             JOIN workspaces w ON w.id = p.workspace_id AND w.deleted_at IS NULL
+             WHERE r.entity_type = 'notebook'
+             ORDER BY 7 DESC, 9 DESC
+             LIMIT ?1"
+        )?;
+
+        let items = stmt
+            .query_map(params![limit.unwrap_or(DEFAULT_RECENT_LIMIT).max(1)], |row| {
+                Ok(RecentItem {
+                    entity_type: row.get(0)?,
+                    id: row.get(1)?,
+                    uuid: row.get(2)?,
+                    name: row.get(3)?,
+                    workspace_uuid: row.get(4)?,
+                    project_id: row.get(5)?,
+                    opened_at: row.get(6)?,
+                    open_count: row.get(7)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(items)
+    }
+
+    pub fn clear_recent_items(&self) -> Result<usize> {
+        Ok(self.conn.execute("DELETE FROM recent_items", [])?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_items() {
+        let db_path = std::env::temp_dir().join("test_novem_recent.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        db.conn
+            .execute_batch(
+                "INSERT INTO users (id, uuid, email, username) VALUES (1, 'u1', 'a@example.com', 'ana');
+                 INSERT INTO workspaces (id, uuid, name, owner_id) VALUES (1, 'ws1', 'Team', 1);
+                 INSERT INTO projects (id, uuid, workspace_id, name, owner_id) VALUES (1, 'p1', 1, 'Sales', 1);
+                 INSERT INTO notebooks (id, uuid, project_id, name) VALUES (1, 'nb1', 1, 'EDA');",
+            )
+            .unwrap();
+
+        db.record_opened(RecentEntity::Workspace, "ws1").unwrap();
+        db.record_opened_project(1).unwrap();
+        db.record_opened(RecentEntity::Notebook, "nb1").unwrap();
+        db.record_opened(RecentEntity::Notebook, "nb1").unwrap();
+        db.record_opened(RecentEntity::Notebook, "gone").unwrap();
+
+        let items = db.get_recent_items(None).unwrap();
+        assert_eq!(items.iter().map(|item| item.uuid.as_str()).collect::<Vec<_>>(), vec!["nb1", "p1", "ws1"]);
+        assert_eq!(items[0].open_count, 2);
+        assert_eq!(items[0].project_id, Some(1));
+        assert_eq!(items[1].workspace_uuid.as_deref(), Some("ws1"));
+        assert_eq!(db.get_recent_items(Some(1)).unwrap().len(), 1);
+
+        // A trashed project hides its notebooks too
+        db.conn.execute("UPDATE projects SET deleted_at = '2025-01-01T00:00:00Z'", []).unwrap();
+        assert_eq!(db.get_recent_items(None).unwrap().len(), 1);
+
+        assert_eq!(db.clear_recent_items().unwrap(), 4);
+        assert!(db.get_recent_items(None).unwrap().is_empty());
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
@@ -0,0 +1,118 @@
+use anyhow::Result;
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::LocalDatabase;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatasetRecipe {
+    pub id: i64,
+    pub uuid: String,
+    pub dataset_uuid: String,
+    pub name: String,
+    pub steps: Value,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+const RECIPE_COLUMNS: &str = "id, uuid, dataset_uuid, name, steps, created_at, updated_at";
+
+fn recipe_from_row(row: &Row) -> rusqlite::Result<DatasetRecipe> {
+    let steps: String = row.get(4)?;
+
+    Ok(DatasetRecipe {
+        id: row.get(0)?,
+        uuid: row.get(1)?,
+        dataset_uuid: row.get(2)?,
+        name: row.get(3)?,
+        steps: serde_json::from_str(&steps).unwrap_or(Value::Array(Vec::new())),
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+impl LocalDatabase {
+    // Recipe operations
+
+    /// Creates a recipe, or replaces the steps of the dataset's recipe with
+    /// the same name.
+    pub fn save_recipe(&self, dataset_uuid: &str, name: &str, steps_json: &str) -> Result<DatasetRecipe> {
+        self.conn.execute(
+            "INSERT INTO dataset_recipes (uuid, dataset_uuid, name, steps)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(dataset_uuid, name) DO UPDATE SET
+                steps = excluded.steps,
+                updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')",
+            params![uuid::Uuid::new_v4().to_string(), dataset_uuid, name, steps_json],
+        )?;
+
+        let recipe = self.conn.query_row(
+            &format!("SELECT {} FROM dataset_recipes WHERE dataset_uuid = ?1 AND name = ?2", RECIPE_COLUMNS),
+            params![dataset_uuid, name],
+            recipe_from_row,
+        )?;
+        Ok(recipe)
+    }
+
+    pub fn get_recipe(&self, uuid: &str) -> Result<Option<DatasetRecipe>> {
+        let recipe = self.conn
+            .query_row(
+                &format!("SELECT {} FROM dataset_recipes WHERE uuid = ?1", RECIPE_COLUMNS),
+                params![uuid],
+                recipe_from_row,
+            )
+            .optional()?;
+
+        Ok(recipe)
+    }
+
+    pub fn list_recipes(&self, dataset_uuid: &str) -> Result<Vec<DatasetRecipe>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM dataset_recipes WHERE dataset_uuid = ?1 ORDER BY name COLLATE NOCASE",
+            RECIPE_COLUMNS
+        ))?;
+
+        let recipes = stmt
+            .query_map(params![dataset_uuid], recipe_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(recipes)
+    }
+
+    /// Returns whether a recipe was deleted.
+    pub fn delete_recipe(&self, uuid: &str) -> Result<bool> {
+        let deleted = self.conn.execute("DELETE FROM dataset_recipes WHERE uuid = ?1", params![uuid])?;
+        Ok(deleted > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recipes() {
+        let db_path = std::env::temp_dir().join("test_novem_recipes.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        let first = db.save_recipe("d1", "cleanup", r#"[{"op":"select","columns":["a"]}]"#).unwrap();
+        assert_eq!(first.steps[0]["op"], "select");
+
+        // Saving under the same name updates the recipe in place
+        let updated = db.save_recipe("d1", "cleanup", "[]").unwrap();
+        assert_eq!(updated.uuid, first.uuid);
+        assert_eq!(updated.steps, serde_json::json!([]));
+
+        db.save_recipe("d2", "cleanup", "[]").unwrap();
+        assert_eq!(db.list_recipes("d1").unwrap().len(), 1);
+
+        assert!(db.delete_recipe(&first.uuid).unwrap());
+        assert!(db.get_recipe(&first.uuid).unwrap().is_none());
+        assert!(!db.delete_recipe(&first.uuid).unwrap());
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
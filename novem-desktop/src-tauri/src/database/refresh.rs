@@ -0,0 +1,182 @@
+use anyhow::Result;
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+use super::LocalDatabase;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatasetRefresh {
+    pub dataset_uuid: String,
+    pub source: serde_json::Value,
+    pub interval_secs: i64,
+    pub next_run_at: String,
+    pub last_started_at: Option<String>,
+    pub last_success_at: Option<String>,
+    pub last_error: Option<String>,
+    pub consecutive_failures: i64,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+const REFRESH_COLUMNS: &str =
+    "dataset_uuid, source, interval_secs, next_run_at, last_started_at, last_success_at,
+     last_error, consecutive_failures, created_at, updated_at";
+
+fn refresh_from_row(row: &Row) -> rusqlite::Result<DatasetRefresh> {
+    let source: String = row.get(1)?;
+    Ok(DatasetRefresh {
+        dataset_uuid: row.get(0)?,
+        source: serde_json::from_str(&source).unwrap_or(serde_json::Value::Null),
+        interval_secs: row.get(2)?,
+        next_run_at: row.get(3)?,
+        last_started_at: row.get(4)?,
+        last_success_at: row.get(5)?,
+        last_error: row.get(6)?,
+        consecutive_failures: row.get(7)?,
+        created_at: row.get(8)?,
+        updated_at: row.get(9)?,
+    })
+}
+
+impl LocalDatabase {
+    // Dataset refresh schedule operations
+
+    /// Creates or replaces a dataset's refresh schedule. The first refresh
+    /// is due immediately.
+    pub fn set_dataset_refresh(&self, dataset_uuid: &str, source_json: &str, interval_secs: i64) -> Result<DatasetRefresh> {
+        self.conn.execute(
+            "INSERT INTO dataset_refresh_schedules (dataset_uuid, source, interval_secs, next_run_at)
+             VALUES (?1, ?2, ?3, strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+             ON CONFLICT(dataset_uuid) DO UPDATE SET
+                source = excluded.source,
+                interval_secs = excluded.interval_secs,
+                next_run_at = excluded.next_run_at,
+                consecutive_failures = 0,
+                updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')",
+            params![dataset_uuid, source_json, interval_secs],
+        )?;
+
+        self.get_dataset_refresh(dataset_uuid)?
+            .ok_or_else(|| anyhow::anyhow!("Refresh schedule for {} missing after insert", dataset_uuid))
+    }
+
+    pub fn get_dataset_refresh(&self, dataset_uuid: &str) -> Result<Option<DatasetRefresh>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM dataset_refresh_schedules WHERE dataset_uuid = ?1",
+            REFRESH_COLUMNS
+        ))?;
+
+        Ok(stmt.query_row(params![dataset_uuid], refresh_from_row).optional()?)
+    }
+
+    pub fn delete_dataset_refresh(&self, dataset_uuid: &str) -> Result<bool> {
+        let deleted = self.conn.execute(
+            "DELETE FROM dataset_refresh_schedules WHERE dataset_uuid = ?1",
+            params![dataset_uuid],
+        )?;
+        Ok(deleted > 0)
+    }
+
+    /// Schedules due at or before `now` whose dataset still exists.
+    pub fn get_due_refreshes(&self, now: &str) -> Result<Vec<DatasetRefresh>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM dataset_refresh_schedules
+             WHERE next_run_at <= ?1
+               AND dataset_uuid IN (SELECT uuid FROM datasets WHERE is_active = 1)
+             ORDER BY next_run_at ASC",
+            REFRESH_COLUMNS
+        ))?;
+
+        let refreshes = stmt
+            .query_map(params![now], refresh_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(refreshes)
+    }
+
+    pub fn mark_refresh_started(&self, dataset_uuid: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE dataset_refresh_schedules
+             SET last_started_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE dataset_uuid = ?1",
+            params![dataset_uuid],
+        )?;
+        Ok(())
+    }
+
+    pub fn record_refresh_success(&self, dataset_uuid: &str, next_run_at: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE dataset_refresh_schedules
+             SET last_success_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now'), last_error = NULL,
+                 consecutive_failures = 0, next_run_at = ?1
+             WHERE dataset_uuid = ?2",
+            params![next_run_at, dataset_uuid],
+        )?;
+        Ok(())
+    }
+
+    pub fn record_refresh_failure(&self, dataset_uuid: &str, error: &str, next_run_at: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE dataset_refresh_schedules
+             SET last_error = ?1, consecutive_failures = consecutive_failures + 1, next_run_at = ?2
+             WHERE dataset_uuid = ?3",
+            params![error, next_run_at, dataset_uuid],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::NewDataset;
+
+    #[test]
+    fn test_due_refreshes() {
+        let db_path = std::env::temp_dir().join("test_novem_refresh.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        db.conn
+            .execute_batch(
+                "INSERT INTO users (id, uuid, email, username) VALUES (1, 'u1', 'a@example.com', 'ana');
+                 INSERT INTO workspaces (id, uuid, name, owner_id) VALUES (1, 'ws1', 'Team', 1);
+                 INSERT INTO projects (id, uuid, workspace_id, name, owner_id) VALUES (1, 'p1', 1, 'Sales', 1);",
+            )
+            .unwrap();
+        db.create_dataset(&NewDataset {
+            uuid: "ds".to_string(),
+            project_id: 1,
+            name: "orders".to_string(),
+            file_path: "/tmp/orders.parquet".to_string(),
+            format: "parquet".to_string(),
+            row_count: None,
+            size_bytes: 0,
+            parent_uuid: None,
+        })
+        .unwrap();
+        db.set_dataset_refresh("ds", r#"{"kind":"file","path":"/tmp/orders.csv"}"#, 3600).unwrap();
+        // A schedule whose dataset is gone is never due
+        db.set_dataset_refresh("gone", r#"{"kind":"file","path":"/tmp/gone.csv"}"#, 3600).unwrap();
+
+        let due = db.get_due_refreshes("2999-01-01T00:00:00Z").unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].source["path"], "/tmp/orders.csv");
+
+        db.record_refresh_failure("ds", "timed out", "2999-01-01T00:01:00Z").unwrap();
+        db.record_refresh_failure("ds", "timed out", "2999-01-01T00:02:00Z").unwrap();
+        assert!(db.get_due_refreshes("2999-01-01T00:00:00Z").unwrap().is_empty());
+        assert_eq!(db.get_dataset_refresh("ds").unwrap().unwrap().consecutive_failures, 2);
+
+        db.record_refresh_success("ds", "2999-01-01T01:00:00Z").unwrap();
+        let refresh = db.get_dataset_refresh("ds").unwrap().unwrap();
+        assert_eq!(refresh.consecutive_failures, 0);
+        assert!(refresh.last_error.is_none() && refresh.last_success_at.is_some());
+
+        assert!(db.delete_dataset_refresh("ds").unwrap());
+        assert!(db.get_dataset_refresh("ds").unwrap().is_none());
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
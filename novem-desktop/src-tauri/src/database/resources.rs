@@ -0,0 +1,184 @@
+use anyhow::Result;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+
+use super::LocalDatabase;
+
+/// Retention tiers as (bucket seconds, keep for seconds). Samples older than
+/// a tier's retention are averaged into the next tier's buckets; the last
+/// tier's expired rows are dropped, so the table behaves as a ring buffer.
+pub const RESOURCE_TIERS: &[(i64, i64)] = &[
+    (0, 60 * 60),                 // raw samples for an hour
+    (60, 24 * 60 * 60),           // 1-minute averages for a day
+    (15 * 60, 30 * 24 * 60 * 60), // 15-minute averages for 30 days
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceSample {
+    pub sampled_at: i64, // unix seconds
+    pub cpu_percent: f64,
+    pub memory_percent: f64,
+    pub memory_used_gb: f64,
+    pub engine_cpu_percent: Option<f64>,
+    pub engine_memory_mb: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourcePoint {
+    pub timestamp: i64, // unix seconds, start of the bucket
+    pub cpu_percent: f64,
+    pub memory_percent: f64,
+    pub memory_used_gb: f64,
+    pub engine_cpu_percent: Option<f64>,
+    pub engine_memory_mb: Option<f64>,
+    pub cpu_peak: f64,
+}
+
+impl LocalDatabase {
+    // Resource history operations
+    pub fn add_resource_sample(&self, sample: &ResourceSample) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO resource_samples
+                (resolution, sampled_at, cpu_percent, cpu_peak, memory_percent, memory_used_gb,
+                 engine_cpu_percent, engine_memory_mb, sample_count)
+             VALUES (0, ?1, ?2, ?2, ?3, ?4, ?5, ?6, 1)",
+            params![
+                sample.sampled_at,
+                sample.cpu_percent,
+                sample.memory_percent,
+                sample.memory_used_gb,
+                sample.engine_cpu_percent,
+                sample.engine_memory_mb,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Downsamples aged samples into coarser tiers and drops expired ones.
+    pub fn compact_resource_samples(&self, now: i64) -> Result<()> {
+        let tx = self.begin()?;
+
+        for window in RESOURCE_TIERS.windows(2) {
+            let (resolution, retention) = window[0];
+            let (next_resolution, _) = window[1];
+
+            // Align so a bucket is never split between tiers
+            let cutoff = (now - retention) / next_resolution * next_resolution;
+
+            tx.execute(
+                "INSERT INTO resource_samples
+                    (resolution, sampled_at, cpu_percent, cpu_peak, memory_percent, memory_used_gb,
+                     engine_cpu_percent, engine_memory_mb, sample_count)
+                 SELECT ?1, (sampled_at / ?1) * ?1,
+                        SUM(cpu_percent * sample_count) / SUM(sample_count),
+                        MAX(cpu_peak),
+                        SUM(memory_percent * sample_count) / SUM(sample_count),
+                        SUM(memory_used_gb * sample_count) / SUM(sample_count),
+                        AVG(engine_cpu_percent),
+                        AVG(engine_memory_mb),
+                        SUM(sample_count)
+                 FROM resource_samples
+                 WHERE resolution = ?2 AND sampled_at < ?3
+                 GROUP BY sampled_at / ?1",
+                params![next_resolution, resolution, cutoff],
+            )?;
+
+            tx.execute(
+                "DELETE FROM resource_samples WHERE resolution = ?1 AND sampled_at < ?2",
+                params![resolution, cutoff],
+            )?;
+        }
+
+        if let Some((resolution, retention)) = RESOURCE_TIERS.last() {
+            tx.execute(
+                "DELETE FROM resource_samples WHERE resolution = ?1 AND sampled_at < ?2",
+                params![resolution, now - retention],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Samples since `since`, averaged into buckets of `resolution` seconds.
+    pub fn get_resource_history(&self, since: i64, resolution: i64) -> Result<Vec<ResourcePoint>> {
+        let resolution = resolution.max(1);
+        let mut stmt = self.conn.prepare(
+            "SELECT (sampled_at / ?2) * ?2 AS bucket,
+                    SUM(cpu_percent * sample_count) / SUM(sample_count),
+                    SUM(memory_percent * sample_count) / SUM(sample_count),
+                    SUM(memory_used_gb * sample_count) / SUM(sample_count),
+                    AVG(engine_cpu_percent),
+                    AVG(engine_memory_mb),
+                    MAX(cpu_peak)
+             FROM resource_samples
+             WHERE sampled_at >= ?1
+             GROUP BY bucket
+             ORDER BY bucket ASC"
+        )?;
+
+        let points = stmt
+            .query_map(params![since, resolution], |row| {
+                Ok(ResourcePoint {
+                    timestamp: row.get(0)?,
+                    cpu_percent: row.get(1)?,
+                    memory_percent: row.get(2)?,
+                    memory_used_gb: row.get(3)?,
+                    engine_cpu_percent: row.get(4)?,
+                    engine_memory_mb: row.get(5)?,
+                    cpu_peak: row.get(6)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(points)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compaction_downsamples_by_age() {
+        let db_path = std::env::temp_dir().join("test_novem_resources.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        let now = 10 * 24 * 60 * 60;
+        // Two hours of 10-second samples, cpu alternating 10/30
+        for i in 0..720 {
+            db.add_resource_sample(&ResourceSample {
+                sampled_at: now - 2 * 60 * 60 + i * 10,
+                cpu_percent: if i % 2 == 0 { 10.0 } else { 30.0 },
+                memory_percent: 50.0,
+                memory_used_gb: 8.0,
+                engine_cpu_percent: None,
+                engine_memory_mb: Some(512.0),
+            })
+            .unwrap();
+        }
+
+        db.compact_resource_samples(now).unwrap();
+
+        let raw: i64 = db
+            .conn
+            .query_row("SELECT COUNT(*) FROM resource_samples WHERE resolution = 0", [], |row| row.get(0))
+            .unwrap();
+        let minutes: i64 = db
+            .conn
+            .query_row("SELECT COUNT(*) FROM resource_samples WHERE resolution = 60", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(raw, 360);
+        assert_eq!(minutes, 60);
+
+        let hourly = db.get_resource_history(now - 2 * 60 * 60, 3600).unwrap();
+        assert_eq!(hourly.len(), 2);
+        assert!((hourly[0].cpu_percent - 20.0).abs() < 1e-9);
+        assert_eq!(hourly[0].cpu_peak, 30.0);
+        assert_eq!(hourly[1].engine_memory_mb, Some(512.0));
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
@@ -0,0 +1,145 @@
+use anyhow::Result;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use super::LocalDatabase;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResultCacheStats {
+    pub entries: i64,
+    /// Compressed size of all entries
+    pub size_bytes: i64,
+    pub hits: i64,
+}
+
+impl LocalDatabase {
+    // Result cache operations
+    /// The stored (compressed) result under `key`, unless it has expired.
+    /// Counts as a use for eviction.
+    pub fn get_cached_result(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let data: Option<Vec<u8>> = self
+            .conn
+            .query_row(
+                "SELECT data FROM result_cache
+                 WHERE key = ?1 AND expires_at > strftime('%Y-%m-%dT%H:%M:%SZ', 'now')",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        if data.is_some() {
+            self.conn.execute(
+                "UPDATE result_cache
+                 SET hits = hits + 1, last_used_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+                 WHERE key = ?1",
+                params![key],
+            )?;
+        }
+        Ok(data)
+    }
+
+    pub fn put_cached_result(&self, key: &str, endpoint: &str, data: &[u8], ttl_secs: u64) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO result_cache (key, endpoint, data, size_bytes, expires_at)
+             VALUES (?1, ?2, ?3, ?4, strftime('%Y-%m-%dT%H:%M:%SZ', 'now', '+' || ?5 || ' seconds'))
+             ON CONFLICT(key) DO UPDATE SET
+                 endpoint = excluded.endpoint,
+                 data = excluded.data,
+                 size_bytes = excluded.size_bytes,
+                 expires_at = excluded.expires_at,
+                 created_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now'),
+                 last_used_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')",
+            params![key, endpoint, data, data.len() as i64, ttl_secs as i64],
+        )?;
+        Ok(())
+    }
+
+    /// Drops expired entries, then the least recently used ones until the
+    /// cache fits in `max_bytes`. Returns the number of entries removed.
+    pub fn evict_result_cache(&self, max_bytes: i64) -> Result<usize> {
+        let tx = self.begin()?;
+
+        let mut removed = tx.execute(
+            "DELETE FROM result_cache WHERE expires_at <= strftime('%Y-%m-%dT%H:%M:%SZ', 'now')",
+            [],
+        )?;
+
+        let mut total: i64 = tx.query_row("SELECT COALESCE(SUM(size_bytes), 0) FROM result_cache", [], |row| row.get(0))?;
+        if total > max_bytes {
+            let mut stmt = tx.prepare("SELECT key, size_bytes FROM result_cache ORDER BY last_used_at ASC, created_at ASC")?;
+            let entries = stmt
+                .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))?
+                .collect::<Result<Vec<_>, _>>()?;
+            drop(stmt);
+
+            for (key, size) in entries {
+                if total <= max_bytes {
+                    break;
+                }
+                removed += tx.execute("DELETE FROM result_cache WHERE key = ?1", params![key])?;
+                total -= size;
+            }
+        }
+
+        tx.commit()?;
+        Ok(removed)
+    }
+
+    pub fn clear_result_cache(&self) -> Result<usize> {
+        Ok(self.conn.execute("DELETE FROM result_cache", [])?)
+    }
+
+    pub fn result_cache_stats(&self) -> Result<ResultCacheStats> {
+        let stats = self.conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(size_bytes), 0), COALESCE(SUM(hits), 0) FROM result_cache",
+            [],
+            |row| {
+                Ok(ResultCacheStats {
+                    entries: row.get(0)?,
+                    size_bytes: row.get(1)?,
+                    hits: row.get(2)?,
+                })
+            },
+        )?;
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_result_cache_expiry_and_eviction() {
+        let db_path = std::env::temp_dir().join("test_novem_result_cache.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        db.put_cached_result("a", "/stats", &[1; 100], 600).unwrap();
+        db.put_cached_result("b", "/stats", &[2; 100], 600).unwrap();
+        db.put_cached_result("c", "/stats", &[3; 100], 600).unwrap();
+        assert_eq!(db.get_cached_result("a").unwrap(), Some(vec![1; 100]));
+        assert_eq!(db.get_cached_result("missing").unwrap(), None);
+
+        // Expired entries are never served and go first
+        db.conn
+            .execute("UPDATE result_cache SET expires_at = '2000-01-01T00:00:00Z' WHERE key = 'c'", [])
+            .unwrap();
+        assert_eq!(db.get_cached_result("c").unwrap(), None);
+
+        // 'b' was used least recently
+        db.conn
+            .execute("UPDATE result_cache SET last_used_at = '2001-01-01T00:00:00Z' WHERE key = 'b'", [])
+            .unwrap();
+        assert_eq!(db.evict_result_cache(150).unwrap(), 2);
+        assert!(db.get_cached_result("a").unwrap().is_some());
+        assert!(db.get_cached_result("b").unwrap().is_none());
+
+        let stats = db.result_cache_stats().unwrap();
+        assert_eq!((stats.entries, stats.size_bytes), (1, 100));
+        assert_eq!(db.clear_result_cache().unwrap(), 1);
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
@@ -0,0 +1,203 @@
+use anyhow::Result;
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+use super::LocalDatabase;
+
+/// A task the scheduler fires whenever its cron expression comes due.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledTask {
+    pub id: i64,
+    pub uuid: String,
+    pub name: String,
+    pub cron: String,
+    pub action: String, // 'sync', 'refresh_dataset', 'run_query'
+    pub payload: serde_json::Value,
+    pub enabled: bool,
+    pub last_run_at: Option<String>,
+    pub last_error: Option<String>,
+    /// `None` while disabled
+    pub next_run_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+const SCHEDULE_COLUMNS: &str =
+    "id, uuid, name, cron, action, payload, enabled, last_run_at, last_error, next_run_at, created_at, updated_at";
+
+fn schedule_from_row(row: &Row) -> rusqlite::Result<ScheduledTask> {
+    let payload: String = row.get(5)?;
+    Ok(ScheduledTask {
+        id: row.get(0)?,
+        uuid: row.get(1)?,
+        name: row.get(2)?,
+        cron: row.get(3)?,
+        action: row.get(4)?,
+        payload: serde_json::from_str(&payload).unwrap_or(serde_json::Value::Null),
+        enabled: row.get(6)?,
+        last_run_at: row.get(7)?,
+        last_error: row.get(8)?,
+        next_run_at: row.get(9)?,
+        created_at: row.get(10)?,
+        updated_at: row.get(11)?,
+    })
+}
+
+impl LocalDatabase {
+    // Scheduled task operations
+    pub fn create_schedule(
+        &self,
+        uuid: &str,
+        name: &str,
+        cron: &str,
+        action: &str,
+        payload_json: &str,
+        next_run_at: &str,
+    ) -> Result<ScheduledTask> {
+        self.conn.execute(
+            "INSERT INTO schedules (uuid, name, cron, action, payload, next_run_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![uuid, name, cron, action, payload_json, next_run_at],
+        )?;
+
+        self.get_schedule(uuid)?
+            .ok_or_else(|| anyhow::anyhow!("Schedule {} missing after insert", uuid))
+    }
+
+    pub fn get_schedule(&self, uuid: &str) -> Result<Option<ScheduledTask>> {
+        let schedule = self
+            .conn
+            .query_row(
+                &format!("SELECT {} FROM schedules WHERE uuid = ?1", SCHEDULE_COLUMNS),
+                params![uuid],
+                schedule_from_row,
+            )
+            .optional()?;
+
+        Ok(schedule)
+    }
+
+    pub fn list_schedules(&self) -> Result<Vec<ScheduledTask>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM schedules ORDER BY name COLLATE NOCASE ASC",
+            SCHEDULE_COLUMNS
+        ))?;
+
+        let schedules = stmt
+            .query_map([], schedule_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(schedules)
+    }
+
+    /// Replaces a schedule's definition. `next_run_at` is recomputed by the
+    /// caller and is `None` when the schedule is disabled.
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_schedule(
+        &self,
+        uuid: &str,
+        name: &str,
+        cron: &str,
+        action: &str,
+        payload_json: &str,
+        enabled: bool,
+        next_run_at: Option<&str>,
+    ) -> Result<bool> {
+        let count = self.conn.execute(
+            "UPDATE schedules
+             SET name = ?2, cron = ?3, action = ?4, payload = ?5, enabled = ?6, next_run_at = ?7,
+                 updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE uuid = ?1",
+            params![uuid, name, cron, action, payload_json, enabled, next_run_at],
+        )?;
+        Ok(count > 0)
+    }
+
+    pub fn delete_schedule(&self, uuid: &str) -> Result<bool> {
+        let count = self.conn.execute("DELETE FROM schedules WHERE uuid = ?1", params![uuid])?;
+        Ok(count > 0)
+    }
+
+    /// Enabled schedules due at or before `now`, most overdue first.
+    pub fn get_due_schedules(&self, now: &str) -> Result<Vec<ScheduledTask>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM schedules
+             WHERE enabled = 1 AND next_run_at IS NOT NULL AND next_run_at <= ?1
+             ORDER BY next_run_at ASC",
+            SCHEDULE_COLUMNS
+        ))?;
+
+        let schedules = stmt
+            .query_map(params![now], schedule_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(schedules)
+    }
+
+    /// Moves a schedule on to its next run as it fires, so a slow task is
+    /// not picked up again by the next scheduler pass.
+    pub fn mark_schedule_started(&self, uuid: &str, next_run_at: Option<&str>) -> Result<bool> {
+        let count = self.conn.execute(
+            "UPDATE schedules
+             SET last_run_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now'), next_run_at = ?2
+             WHERE uuid = ?1",
+            params![uuid, next_run_at],
+        )?;
+        Ok(count > 0)
+    }
+
+    pub fn record_schedule_outcome(&self, uuid: &str, error: Option<&str>) -> Result<bool> {
+        let count = self.conn.execute(
+            "UPDATE schedules SET last_error = ?2 WHERE uuid = ?1",
+            params![uuid, error],
+        )?;
+        Ok(count > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_due_schedules() {
+        let db_path = std::env::temp_dir().join("test_novem_schedules.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        db.create_schedule("s1", "Nightly sync", "0 2 * * *", "sync", "{}", "2024-01-01T02:00:00Z").unwrap();
+        let refresh = db
+            .create_schedule(
+                "s2",
+                "Orders",
+                "*/15 * * * *",
+                "refresh_dataset",
+                r#"{"dataset_uuid":"d1"}"#,
+                "2024-01-01T00:15:00Z",
+            )
+            .unwrap();
+        assert_eq!(refresh.payload["dataset_uuid"], "d1");
+        assert!(refresh.enabled);
+
+        let due: Vec<_> = db.get_due_schedules("2024-01-01T03:00:00Z").unwrap().into_iter().map(|s| s.uuid).collect();
+        assert_eq!(due, ["s2", "s1"]);
+
+        // Firing moves it past the pass that picked it up
+        assert!(db.mark_schedule_started("s2", Some("2024-01-01T03:15:00Z")).unwrap());
+        assert!(db.record_schedule_outcome("s2", Some("dataset not found")).unwrap());
+        let fired = db.get_schedule("s2").unwrap().unwrap();
+        assert!(fired.last_run_at.is_some());
+        assert_eq!(fired.last_error.as_deref(), Some("dataset not found"));
+        assert_eq!(db.get_due_schedules("2024-01-01T03:00:00Z").unwrap().len(), 1);
+
+        // Disabled schedules never come due
+        assert!(db.update_schedule("s1", "Nightly sync", "0 2 * * *", "sync", "{}", false, None).unwrap());
+        assert!(db.get_due_schedules("2030-01-01T00:00:00Z").unwrap().iter().all(|s| s.uuid != "s1"));
+
+        assert!(db.delete_schedule("s1").unwrap());
+        assert_eq!(db.list_schedules().unwrap().len(), 1);
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
@@ -0,0 +1,425 @@
+use anyhow::Result;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params_from_iter, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use super::LocalDatabase;
+
+const DEFAULT_SEARCH_LIMIT: i64 = 20;
+
+/// Notebooks are indexed once per cell, so a query fetches this many rows
+/// per requested result before collapsing them into one hit per entity.
+const ROWS_PER_RESULT: i64 = 4;
+
+const HIGHLIGHT_OPEN: &str = "<mark>";
+const HIGHLIGHT_CLOSE: &str = "</mark>";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchEntityType {
+    Workspace,
+    Project,
+    Notebook,
+    Dataset,
+    /// Favorited query history entries
+    Query,
+}
+
+impl SearchEntityType {
+    pub const ALL: [SearchEntityType; 5] = [
+        SearchEntityType::Workspace,
+        SearchEntityType::Project,
+        SearchEntityType::Notebook,
+        SearchEntityType::Dataset,
+        SearchEntityType::Query,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SearchEntityType::Workspace => "workspace",
+            SearchEntityType::Project => "project",
+            SearchEntityType::Notebook => "notebook",
+            SearchEntityType::Dataset => "dataset",
+            SearchEntityType::Query => "query",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SearchFilter {
+    /// Empty = every type
+    #[serde(default)]
+    pub entity_types: Vec<SearchEntityType>,
+    pub workspace_uuid: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResult {
+    pub entity_type: String,
+    pub uuid: String, // query history id for saved queries
+    pub title: String,
+    /// The title with matches wrapped in `<mark>`, when the title matched
+    pub title_highlight: Option<String>,
+    /// Matching excerpt of the description, cell source, column names or
+    /// query text
+    pub snippet: Option<String>,
+    pub project_uuid: Option<String>,
+    pub workspace_uuid: Option<String>,
+    /// Higher is better; only comparable within one search
+    pub score: f64,
+}
+
+/// Every searchable entity with what a result shows and filters on.
+/// Trashed entities, and those under a trashed parent, are inactive.
+const SEARCH_ENTITIES: &str = "
+    SELECT 'workspace' AS kind, w.uuid AS uuid, w.name AS title,
+           NULL AS project_uuid, w.uuid AS workspace_uuid, w.is_active AS is_active
+    FROM workspaces w
+    UNION ALL
+    SELECT 'project', p.uuid, p.name, p.uuid, w.uuid, p.is_active AND w.is_active
+    FROM projects p JOIN workspaces w ON w.id = p.workspace_id
+    UNION ALL
+    SELECT 'notebook', n.uuid, n.name, p.uuid, w.uuid, n.is_active AND p.is_active AND w.is_active
+    FROM notebooks n JOIN projects p ON p.id = n.project_id JOIN workspaces w ON w.id = p.workspace_id
+    UNION ALL
+    SELECT 'dataset', d.uuid, d.name, p.uuid, w.uuid, d.is_active AND p.is_active AND w.is_active
+    FROM datasets d JOIN projects p ON p.id = d.project_id JOIN workspaces w ON w.id = p.workspace_id
+    UNION ALL
+    SELECT 'query', CAST(q.id AS TEXT), COALESCE(q.target, substr(q.query_text, 1, 80)), p.uuid, w.uuid,
+           COALESCE(p.is_active AND w.is_active, 1)
+    FROM query_history q LEFT JOIN projects p ON p.id = q.project_id LEFT JOIN workspaces w ON w.id = p.workspace_id";
+
+/// Rows the index holds for each source table, used to backfill it.
+/// `key` identifies the row; `entity_uuid` the result it belongs to.
+const INDEX_SOURCES: &str = "
+    SELECT 'workspace', uuid, uuid, name, COALESCE(description, '') FROM workspaces
+    UNION ALL
+    SELECT 'project', uuid, uuid, name, COALESCE(description, '') FROM projects
+    UNION ALL
+    SELECT 'notebook', uuid, uuid, name, '' FROM notebooks
+    UNION ALL
+    SELECT 'notebook', 'cell:' || c.uuid, n.uuid, '', c.source
+    FROM notebook_cells c JOIN notebooks n ON n.id = c.notebook_id
+    UNION ALL
+    SELECT 'dataset', d.uuid, d.uuid, d.name,
+           COALESCE((SELECT group_concat(column_name, ' ') FROM column_stats WHERE dataset_uuid = d.uuid), '')
+    FROM datasets d
+    UNION ALL
+    SELECT 'query', CAST(id AS TEXT), CAST(id AS TEXT), COALESCE(target, ''), query_text
+    FROM query_history WHERE is_favorite = 1";
+
+/// Keeps `search_index` in step with its source tables.
+const INDEX_TRIGGERS: &str = "
+    CREATE TRIGGER IF NOT EXISTS search_workspaces_insert AFTER INSERT ON workspaces BEGIN
+        INSERT INTO search_index (kind, key, entity_uuid, title, body)
+        VALUES ('workspace', new.uuid, new.uuid, new.name, COALESCE(new.description, ''));
+    END;
+    CREATE TRIGGER IF NOT EXISTS search_workspaces_update AFTER UPDATE OF uuid, name, description ON workspaces BEGIN
+        DELETE FROM search_index WHERE kind = 'workspace' AND key = old.uuid;
+        INSERT INTO search_index (kind, key, entity_uuid, title, body)
+        VALUES ('workspace', new.uuid, new.uuid, new.name, COALESCE(new.description, ''));
+    END;
+    CREATE TRIGGER IF NOT EXISTS search_workspaces_delete AFTER DELETE ON workspaces BEGIN
+        DELETE FROM search_index WHERE kind = 'workspace' AND key = old.uuid;
+    END;
+
+    CREATE TRIGGER IF NOT EXISTS search_projects_insert AFTER INSERT ON projects BEGIN
+        INSERT INTO search_index (kind, key, entity_uuid, title, body)
+        VALUES ('project', new.uuid, new.uuid, new.name, COALESCE(new.description, ''));
+    END;
+    CREATE TRIGGER IF NOT EXISTS search_projects_update AFTER UPDATE OF uuid, name, description ON projects BEGIN
+        DELETE FROM search_index WHERE kind = 'project' AND key = old.uuid;
+        INSERT INTO search_index (kind, key, entity_uuid, title, body)
+        VALUES ('project', new.uuid, new.uuid, new.name, COALESCE(new.description, ''));
+    END;
+    CREATE TRIGGER IF NOT EXISTS search_projects_delete AFTER DELETE ON projects BEGIN
+        DELETE FROM search_index WHERE kind = 'project' AND key = old.uuid;
+    END;
+
+    CREATE TRIGGER IF NOT EXISTS search_notebooks_insert AFTER INSERT ON notebooks BEGIN
+        INSERT INTO search_index (kind, key, entity_uuid, title, body)
+        VALUES ('notebook', new.uuid, new.uuid, new.name, '');
+    END;
+    CREATE TRIGGER IF NOT EXISTS search_notebooks_update AFTER UPDATE OF name ON notebooks BEGIN
+        UPDATE search_index SET title = new.name WHERE kind = 'notebook' AND key = new.uuid;
+    END;
+    CREATE TRIGGER IF NOT EXISTS search_notebooks_delete AFTER DELETE ON notebooks BEGIN
+        DELETE FROM search_index WHERE kind = 'notebook' AND key = old.uuid;
+    END;
+
+    CREATE TRIGGER IF NOT EXISTS search_cells_insert AFTER INSERT ON notebook_cells BEGIN
+        INSERT INTO search_index (kind, key, entity_uuid, title, body)
+        SELECT 'notebook', 'cell:' || new.uuid, uuid, '', new.source FROM notebooks WHERE id = new.notebook_id;
+    END;
+    CREATE TRIGGER IF NOT EXISTS search_cells_update AFTER UPDATE OF source, notebook_id ON notebook_cells BEGIN
+        DELETE FROM search_index WHERE kind = 'notebook' AND key = 'cell:' || old.uuid;
+        INSERT INTO search_index (kind, key, entity_uuid, title, body)
+        SELECT 'notebook', 'cell:' || new.uuid, uuid, '', new.source FROM notebooks WHERE id = new.notebook_id;
+    END;
+    CREATE TRIGGER IF NOT EXISTS search_cells_delete AFTER DELETE ON notebook_cells BEGIN
+        DELETE FROM search_index WHERE kind = 'notebook' AND key = 'cell:' || old.uuid;
+    END;
+
+    CREATE TRIGGER IF NOT EXISTS search_datasets_insert AFTER INSERT ON datasets BEGIN
+        INSERT INTO search_index (kind, key, entity_uuid, title, body)
+        VALUES ('dataset', new.uuid, new.uuid, new.name, '');
+    END;
+    CREATE TRIGGER IF NOT EXISTS search_datasets_update AFTER UPDATE OF name ON datasets BEGIN
+        UPDATE search_index SET title = new.name WHERE kind = 'dataset' AND key = new.uuid;
+    END;
+    CREATE TRIGGER IF NOT EXISTS search_datasets_delete AFTER DELETE ON datasets BEGIN
+        DELETE FROM search_index WHERE kind = 'dataset' AND key = old.uuid;
+    END;
+
+    CREATE TRIGGER IF NOT EXISTS search_columns_insert AFTER INSERT ON column_stats BEGIN
+        UPDATE search_index
+        SET body = COALESCE((SELECT group_concat(column_name, ' ') FROM column_stats WHERE dataset_uuid = new.dataset_uuid), '')
+        WHERE kind = 'dataset' AND key = new.dataset_uuid;
+    END;
+    CREATE TRIGGER IF NOT EXISTS search_columns_delete AFTER DELETE ON column_stats BEGIN
+        UPDATE search_index
+        SET body = COALESCE((SELECT group_concat(column_name, ' ') FROM column_stats WHERE dataset_uuid = old.dataset_uuid), '')
+        WHERE kind = 'dataset' AND key = old.dataset_uuid;
+    END;
+
+    CREATE TRIGGER IF NOT EXISTS search_queries_insert AFTER INSERT ON query_history WHEN new.is_favorite BEGIN
+        INSERT INTO search_index (kind, key, entity_uuid, title, body)
+        VALUES ('query', CAST(new.id AS TEXT), CAST(new.id AS TEXT), COALESCE(new.target, ''), new.query_text);
+    END;
+    CREATE TRIGGER IF NOT EXISTS search_queries_update AFTER UPDATE OF is_favorite, target, query_text ON query_history BEGIN
+        DELETE FROM search_index WHERE kind = 'query' AND key = CAST(old.id AS TEXT);
+        INSERT INTO search_index (kind, key, entity_uuid, title, body)
+        SELECT 'query', CAST(new.id AS TEXT), CAST(new.id AS TEXT), COALESCE(new.target, ''), new.query_text
+        WHERE new.is_favorite;
+    END;
+    CREATE TRIGGER IF NOT EXISTS search_queries_delete AFTER DELETE ON query_history BEGIN
+        DELETE FROM search_index WHERE kind = 'query' AND key = CAST(old.id AS TEXT);
+    END;";
+
+/// Turns free text into an FTS5 query matching every word as a prefix, so
+/// punctuation and operators typed by the user are never parsed as syntax.
+fn match_expression(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(|term| format!("\"{}\"*", term))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+fn highlighted(text: String) -> Option<String> {
+    if text.contains(HIGHLIGHT_OPEN) {
+        Some(text)
+    } else {
+        None
+    }
+}
+
+/// Titles equal to or starting with the query rank above matches that
+/// only appear further in.
+fn title_boost(title: &str, query: &str) -> f64 {
+    let (title, query) = (title.to_lowercase(), query.trim().to_lowercase());
+    if title == query {
+        3.0
+    } else if title.starts_with(&query) {
+        1.5
+    } else {
+        1.0
+    }
+}
+
+impl LocalDatabase {
+    // Global search operations
+
+    /// Creates the full-text index and the triggers maintaining it, filling
+    /// it from existing rows the first time.
+    pub(super) fn initialize_search_index(&self) -> Result<()> {
+        let exists = self
+            .conn
+            .query_row(
+                "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'search_index'",
+                [],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+
+        if !exists {
+            let tx = self.begin()?;
+            tx.execute_batch(&format!(
+                "CREATE VIRTUAL TABLE search_index USING fts5(
+                    kind UNINDEXED,
+                    key UNINDEXED,
+                    entity_uuid UNINDEXED,
+                    title,
+                    body,
+                    tokenize = 'unicode61 remove_diacritics 2',
+                    prefix = '2 3'
+                );
+                INSERT INTO search_index (kind, key, entity_uuid, title, body) {};",
+                INDEX_SOURCES
+            ))?;
+            tx.commit()?;
+        }
+
+        self.conn.execute_batch(INDEX_TRIGGERS)?;
+        Ok(())
+    }
+
+    /// Ranked matches for `query` across workspaces, projects, notebooks
+    /// (names and cell sources), datasets (names and column names) and
+    /// saved queries. Trashed entities are left out.
+    pub fn global_search(&self, query: &str, filter: &SearchFilter) -> Result<Vec<SearchResult>> {
+        let Some(expression) = match_expression(query) else {
+            return Ok(Vec::new());
+        };
+        let limit = filter.limit.unwrap_or(DEFAULT_SEARCH_LIMIT).max(1);
+
+        let types = if filter.entity_types.is_empty() {
+            SearchEntityType::ALL.to_vec()
+        } else {
+            filter.entity_types.clone()
+        };
+
+        let mut values: Vec<SqlValue> = vec![SqlValue::Text(expression)];
+        let placeholders: Vec<String> = types
+            .iter()
+            .map(|entity_type| {
+                values.push(SqlValue::Text(entity_type.as_str().to_string()));
+                format!("?{}", values.len())
+            })
+            .collect();
+        let mut conditions = vec![
+            "search_index MATCH ?1".to_string(),
+            format!("search_index.kind IN ({})", placeholders.join(", ")),
+            "e.is_active".to_string(),
+        ];
+        if let Some(workspace_uuid) = &filter.workspace_uuid {
+            values.push(SqlValue::Text(workspace_uuid.clone()));
+            conditions.push(format!("e.workspace_uuid = ?{}", values.len()));
+        }
+        values.push(SqlValue::Integer(limit * ROWS_PER_RESULT));
+
+        // Title matches weigh ten times as much as body matches
+        let sql = format!(
+            "WITH e AS ({})
+             SELECT search_index.kind, e.uuid, e.title,
+                    highlight(search_index, 3, '{open}', '{close}'),
+                    snippet(search_index, 4, '{open}', '{close}', '…', 12),
+                    e.project_uuid, e.workspace_uuid,
+                    bm25(search_index, 0.0, 0.0, 0.0, 10.0, 1.0) AS weight
+             FROM search_index
+             JOIN e ON e.kind = search_index.kind AND e.uuid = search_index.entity_uuid
+             WHERE {}
+             ORDER BY weight
+             LIMIT ?{}",
+            SEARCH_ENTITIES,
+            conditions.join(" AND "),
+            values.len(),
+            open = HIGHLIGHT_OPEN,
+            close = HIGHLIGHT_CLOSE,
+        );
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt
+            .query_map(params_from_iter(values.iter()), |row| {
+                let title: String = row.get(2)?;
+                let weight: f64 = row.get(7)?;
+                Ok(SearchResult {
+                    entity_type: row.get(0)?,
+                    uuid: row.get(1)?,
+                    score: -weight * title_boost(&title, query),
+                    title,
+                    title_highlight: highlighted(row.get(3)?),
+                    snippet: highlighted(row.get(4)?),
+                    project_uuid: row.get(5)?,
+                    workspace_uuid: row.get(6)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Rows come best first, so the first per entity is its best match
+        let mut seen = HashSet::new();
+        let mut results: Vec<SearchResult> = rows
+            .into_iter()
+            .filter(|result| seen.insert((result.entity_type.clone(), result.uuid.clone())))
+            .collect();
+        results.sort_by(|a, b| b.score.total_cmp(&a.score));
+        results.truncate(limit as usize);
+
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_global_search() {
+        let db_path = std::env::temp_dir().join("test_novem_search.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        db.conn
+            .execute_batch(
+                "INSERT INTO users (id, uuid, email, username) VALUES (1, 'u1', 'a@example.com', 'ana');
+                 INSERT INTO workspaces (id, uuid, name, owner_id) VALUES (1, 'ws1', 'Team', 1);
+                 INSERT INTO workspaces (id, uuid, name, owner_id) VALUES (2, 'ws2', 'Archive', 1);
+                 INSERT INTO projects (id, uuid, workspace_id, name, description, owner_id)
+                     VALUES (1, 'p1', 1, 'Revenue', 'Quarterly revenue by region', 1);
+                 INSERT INTO projects (id, uuid, workspace_id, name, owner_id) VALUES (2, 'p2', 2, 'Old revenue', 1);
+                 INSERT INTO notebooks (id, uuid, project_id, name) VALUES (1, 'nb1', 1, 'Exploration');
+                 INSERT INTO notebook_cells (uuid, notebook_id, position, source)
+                     VALUES ('c1', 1, 0, 'df = load(\"orders\")'), ('c2', 1, 1, 'df.groupby(\"region\").sum()');
+                 INSERT INTO datasets (uuid, project_id, name, file_path, format) VALUES ('ds1', 1, 'orders', '/tmp/o.parquet', 'parquet');
+                 INSERT INTO column_stats (dataset_uuid, column_name, position, sketch, hll)
+                     VALUES ('ds1', 'region_code', 0, '{}', x'00');",
+            )
+            .unwrap();
+
+        let all = db.global_search("revenue", &SearchFilter::default()).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].uuid, "p1");
+        assert!(all[0].title_highlight.as_deref().unwrap().contains("<mark>Revenue</mark>"));
+
+        // Both cells match, but the notebook is one result; the dataset
+        // matches on a column name prefix
+        let region = db
+            .global_search("region", &SearchFilter { workspace_uuid: Some("ws1".to_string()), ..Default::default() })
+            .unwrap();
+        let kinds: Vec<&str> = region.iter().map(|result| result.entity_type.as_str()).collect();
+        assert_eq!(region.len(), 3);
+        assert!(kinds.contains(&"notebook") && kinds.contains(&"dataset") && kinds.contains(&"project"));
+
+        let datasets = db
+            .global_search("region", &SearchFilter { entity_types: vec![SearchEntityType::Dataset], ..Default::default() })
+            .unwrap();
+        assert_eq!(datasets.len(), 1);
+        assert!(datasets[0].snippet.as_deref().unwrap().contains("<mark>region</mark>_code"));
+
+        // Renames, trashing and favorites are picked up by the triggers
+        db.conn
+            .execute_batch(
+                "UPDATE projects SET name = 'Archived', is_active = 0 WHERE id = 2;
+                 INSERT INTO query_history (kind, query_text, duration_ms, status, is_favorite)
+                     VALUES ('sql', 'SELECT * FROM orders', 5, 'success', 0);
+                 UPDATE query_history SET is_favorite = 1;",
+            )
+            .unwrap();
+        assert!(db.global_search("old", &SearchFilter::default()).unwrap().is_empty());
+        let orders = db.global_search("orders \"", &SearchFilter::default()).unwrap();
+        assert!(orders.iter().any(|result| result.entity_type == "query"));
+        assert!(db.global_search("  *  ", &SearchFilter::default()).unwrap().is_empty());
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
@@ -0,0 +1,64 @@
+use anyhow::Result;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use super::LocalDatabase;
+
+/// A stored secret as the UI sees it: never its value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretInfo {
+    pub name: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl LocalDatabase {
+    // Secret operations; values arrive and leave sealed, see `secrets`
+    pub fn put_secret(&self, name: &str, nonce: &[u8], ciphertext: &[u8]) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO secrets (name, nonce, ciphertext) VALUES (?1, ?2, ?3)
+             ON CONFLICT(name) DO UPDATE SET
+                 nonce = excluded.nonce,
+                 ciphertext = excluded.ciphertext,
+                 updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')",
+            params![name, nonce, ciphertext],
+        )?;
+        Ok(())
+    }
+
+    /// The nonce and ciphertext stored under `name`.
+    pub fn get_sealed_secret(&self, name: &str) -> Result<Option<(Vec<u8>, Vec<u8>)>> {
+        let sealed = self
+            .conn
+            .query_row(
+                "SELECT nonce, ciphertext FROM secrets WHERE name = ?1",
+                params![name],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        Ok(sealed)
+    }
+
+    pub fn list_secrets(&self) -> Result<Vec<SecretInfo>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT name, created_at, updated_at FROM secrets ORDER BY name COLLATE NOCASE ASC"
+        )?;
+
+        let secrets = stmt
+            .query_map([], |row| {
+                Ok(SecretInfo {
+                    name: row.get(0)?,
+                    created_at: row.get(1)?,
+                    updated_at: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(secrets)
+    }
+
+    pub fn delete_secret(&self, name: &str) -> Result<bool> {
+        let count = self.conn.execute("DELETE FROM secrets WHERE name = ?1", params![name])?;
+        Ok(count > 0)
+    }
+}
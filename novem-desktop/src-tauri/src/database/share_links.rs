@@ -0,0 +1,258 @@
+use anyhow::Result;
+use rand::Rng;
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+use super::LocalDatabase;
+use crate::i18n::tr;
+
+/// Random bytes in a share token, hex encoded in the link.
+const TOKEN_BYTES: usize = 24;
+
+/// Entity types a share link can point at, with the table each lives in.
+const SHAREABLE: &[(&str, &str)] = &[
+    ("project", "projects"),
+    ("notebook", "notebooks"),
+    ("dashboard", "dashboards"),
+    ("dataset", "datasets"),
+];
+
+pub const SHARE_PERMISSIONS: &[&str] = &["view", "comment", "edit"];
+
+/// A link granting access to an entity to whoever holds its token. Links
+/// are made locally and only work once the backend has them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareLink {
+    pub id: i64,
+    pub uuid: String,
+    pub entity_type: String,
+    pub entity_uuid: String,
+    pub token: String,
+    pub permission: String, // 'view', 'comment', 'edit'
+    pub expires_at: Option<String>,
+    /// Backend user ID of whoever was signed in, if anyone
+    pub created_by: Option<i64>,
+    pub created_at: String,
+    pub revoked_at: Option<String>,
+    pub sync_status: String,
+    /// Synced, not revoked and not expired
+    pub active: bool,
+}
+
+const SHARE_LINK_COLUMNS: &str =
+    "id, uuid, entity_type, entity_uuid, token, permission, expires_at, created_by, created_at, revoked_at, sync_status,
+     (sync_status = 'synced' AND revoked_at IS NULL
+      AND (expires_at IS NULL OR expires_at > strftime('%Y-%m-%dT%H:%M:%SZ', 'now')))";
+
+fn share_link_from_row(row: &Row) -> rusqlite::Result<ShareLink> {
+    Ok(ShareLink {
+        id: row.get(0)?,
+        uuid: row.get(1)?,
+        entity_type: row.get(2)?,
+        entity_uuid: row.get(3)?,
+        token: row.get(4)?,
+        permission: row.get(5)?,
+        expires_at: row.get(6)?,
+        created_by: row.get(7)?,
+        created_at: row.get(8)?,
+        revoked_at: row.get(9)?,
+        sync_status: row.get(10)?,
+        active: row.get(11)?,
+    })
+}
+
+fn new_token() -> String {
+    let bytes: [u8; TOKEN_BYTES] = rand::thread_rng().gen();
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+impl LocalDatabase {
+    // Share link operations
+    pub fn get_share_link(&self, uuid: &str) -> Result<Option<ShareLink>> {
+        let link = self
+            .conn
+            .query_row(
+                &format!("SELECT {} FROM share_links WHERE uuid = ?1", SHARE_LINK_COLUMNS),
+                params![uuid],
+                share_link_from_row,
+            )
+            .optional()?;
+
+        Ok(link)
+    }
+
+    /// An entity's links, newest first; revoked ones only when asked.
+    pub fn list_share_links(&self, entity_type: &str, entity_uuid: &str, include_revoked: bool) -> Result<Vec<ShareLink>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM share_links
+             WHERE entity_type = ?1 AND entity_uuid = ?2 AND (?3 OR revoked_at IS NULL)
+             ORDER BY created_at DESC, id DESC",
+            SHARE_LINK_COLUMNS
+        ))?;
+
+        let links = stmt
+            .query_map(params![entity_type, entity_uuid, include_revoked], share_link_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(links)
+    }
+
+    /// Creates a link with a fresh token and queues it for the backend,
+    /// which activates it once the push is accepted.
+    pub fn create_share_link(
+        &self,
+        entity_type: &str,
+        entity_uuid: &str,
+        permission: &str,
+        expires_at: Option<&str>,
+        created_by: Option<i64>,
+    ) -> Result<ShareLink> {
+        let table = SHAREABLE
+            .iter()
+            .find(|(shareable, _)| *shareable == entity_type)
+            .map(|(_, table)| *table)
+            .ok_or_else(|| anyhow::anyhow!(tr!("share-entity-unsupported", entity_type = entity_type)))?;
+        if !SHARE_PERMISSIONS.contains(&permission) {
+            anyhow::bail!(tr!("share-permission-invalid", permission = permission));
+        }
+        let exists = self
+            .conn
+            .query_row(&format!("SELECT 1 FROM {} WHERE uuid = ?1", table), params![entity_uuid], |_| Ok(()))
+            .optional()?
+            .is_some();
+        if !exists {
+            anyhow::bail!(tr!("share-target-not-found", entity_type = entity_type, uuid = entity_uuid));
+        }
+
+        let uuid = uuid::Uuid::new_v4().to_string();
+        let token = new_token();
+        let tx = self.begin()?;
+        self.conn.execute(
+            "INSERT INTO share_links (uuid, entity_type, entity_uuid, token, permission, expires_at, created_by)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![&uuid, entity_type, entity_uuid, &token, permission, expires_at, created_by],
+        )?;
+        self.add_to_sync_queue(
+            "share_link",
+            &uuid,
+            "create",
+            &serde_json::json!({
+                "entity_type": entity_type,
+                "entity_uuid": entity_uuid,
+                "token": token,
+                "permission": permission,
+                "expires_at": expires_at,
+                "created_by": created_by,
+            })
+            .to_string(),
+        )?;
+        let link = self
+            .get_share_link(&uuid)?
+            .ok_or_else(|| anyhow::anyhow!("Share link {} missing after insert", uuid))?;
+        tx.commit()?;
+
+        Ok(link)
+    }
+
+    /// Revokes a link. One that never reached the backend just has its
+    /// queued create dropped; otherwise the revocation is queued. Returns
+    /// `None` if the link doesn't exist or was already revoked.
+    pub fn revoke_share_link(&self, uuid: &str) -> Result<Option<ShareLink>> {
+        let tx = self.begin()?;
+        let count = self.conn.execute(
+            "UPDATE share_links SET revoked_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE uuid = ?1 AND revoked_at IS NULL",
+            params![uuid],
+        )?;
+        if count == 0 {
+            return Ok(None);
+        }
+
+        let unsynced_create = self.conn.execute(
+            "DELETE FROM sync_queue
+             WHERE status = 'pending' AND action = 'create' AND entity_type = 'share_link' AND entity_uuid = ?1",
+            params![uuid],
+        )?;
+        if unsynced_create == 0 {
+            self.add_to_sync_queue("share_link", uuid, "delete", "{}")?;
+        }
+        let link = self.get_share_link(uuid)?;
+        tx.commit()?;
+
+        Ok(link)
+    }
+
+    /// Records that the backend accepted a link, which makes it usable.
+    pub fn mark_share_link_synced(&self, uuid: &str) -> Result<bool> {
+        let count = self.conn.execute(
+            "UPDATE share_links SET sync_status = 'synced' WHERE uuid = ?1",
+            params![uuid],
+        )?;
+        Ok(count > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queued(db: &LocalDatabase, uuid: &str) -> Vec<String> {
+        let mut stmt = db.conn
+            .prepare("SELECT action FROM sync_queue WHERE entity_uuid = ?1 ORDER BY id")
+            .unwrap();
+        stmt.query_map(params![uuid], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_share_links_activate_once_synced() {
+        let db_path = std::env::temp_dir().join("test_novem_share_links.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        db.conn
+            .execute_batch(
+                "INSERT INTO users (id, uuid, email, username) VALUES (1, 'u1', 'a@example.com', 'ana');
+                 INSERT INTO workspaces (id, uuid, name, owner_id) VALUES (1, 'ws1', 'Team', 1);
+                 INSERT INTO projects (id, uuid, workspace_id, name, owner_id) VALUES (1, 'p1', 1, 'Sales', 1);",
+            )
+            .unwrap();
+
+        assert!(db.create_share_link("project", "missing", "view", None, Some(1)).is_err());
+        assert!(db.create_share_link("project", "p1", "own", None, Some(1)).is_err());
+        assert!(db.create_share_link("workspace", "ws1", "view", None, Some(1)).is_err());
+
+        let link = db.create_share_link("project", "p1", "view", None, Some(1)).unwrap();
+        assert_eq!(link.token.len(), TOKEN_BYTES * 2);
+        assert!(!link.active);
+        assert_eq!(queued(&db, &link.uuid), vec!["create"]);
+
+        assert!(db.mark_share_link_synced(&link.uuid).unwrap());
+        assert!(db.get_share_link(&link.uuid).unwrap().unwrap().active);
+
+        let expired = db
+            .create_share_link("project", "p1", "edit", Some("2000-01-01T00:00:00Z"), Some(1))
+            .unwrap();
+        db.mark_share_link_synced(&expired.uuid).unwrap();
+        assert!(!db.get_share_link(&expired.uuid).unwrap().unwrap().active);
+
+        // Never synced: the queued create is dropped instead
+        let offline = db.create_share_link("project", "p1", "comment", None, None).unwrap();
+        assert!(db.revoke_share_link(&offline.uuid).unwrap().unwrap().revoked_at.is_some());
+        assert!(queued(&db, &offline.uuid).is_empty());
+
+        // Already synced: the revocation is queued
+        db.conn.execute("UPDATE sync_queue SET status = 'synced'", []).unwrap();
+        assert!(!db.revoke_share_link(&link.uuid).unwrap().unwrap().active);
+        assert_eq!(queued(&db, &link.uuid), vec!["create", "delete"]);
+        assert!(db.revoke_share_link(&link.uuid).unwrap().is_none());
+
+        assert_eq!(db.list_share_links("project", "p1", false).unwrap().len(), 1);
+        assert_eq!(db.list_share_links("project", "p1", true).unwrap().len(), 3);
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
@@ -0,0 +1,338 @@
+use anyhow::Result;
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+use super::clones::{project_from_row, PROJECT_COLUMNS};
+use super::{LocalDatabase, NewDataset, Project};
+use crate::i18n::tr;
+
+/// What a new project is seeded with. Stored as JSON, so templates survive
+/// the source project being deleted.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TemplateSpec {
+    pub description: Option<String>,
+    #[serde(default)]
+    pub notebooks: Vec<TemplateNotebook>,
+    #[serde(default)]
+    pub datasets: Vec<TemplateDataset>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateNotebook {
+    pub name: String,
+    #[serde(default)]
+    pub cells: Vec<TemplateCell>,
+}
+
+/// A starter cell; outputs are left behind.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateCell {
+    pub cell_type: String,
+    pub source: String,
+    #[serde(default = "empty_tags")]
+    pub tags: String,
+}
+
+fn empty_tags() -> String {
+    "[]".to_string()
+}
+
+/// A dataset the new project references by file, the way a clone does.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateDataset {
+    pub name: String,
+    pub file_path: String,
+    pub format: String,
+    pub row_count: Option<i64>,
+    pub size_bytes: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectTemplate {
+    pub id: i64,
+    pub uuid: String,
+    pub name: String,
+    /// Project the template was saved from, if any
+    pub source_project_uuid: Option<String>,
+    pub spec: TemplateSpec,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TemplatedProject {
+    pub project: Project,
+    pub notebooks: usize,
+    pub datasets: usize,
+}
+
+const TEMPLATE_COLUMNS: &str = "id, uuid, name, source_project_uuid, spec, created_at";
+
+fn template_from_row(row: &Row) -> rusqlite::Result<ProjectTemplate> {
+    let spec: String = row.get(4)?;
+    Ok(ProjectTemplate {
+        id: row.get(0)?,
+        uuid: row.get(1)?,
+        name: row.get(2)?,
+        source_project_uuid: row.get(3)?,
+        spec: serde_json::from_str(&spec).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(4, rusqlite::types::Type::Text, Box::new(e))
+        })?,
+        created_at: row.get(5)?,
+    })
+}
+
+impl LocalDatabase {
+    // Project template operations
+    pub fn get_project_template(&self, uuid: &str) -> Result<Option<ProjectTemplate>> {
+        let template = self
+            .conn
+            .query_row(
+                &format!("SELECT {} FROM project_templates WHERE uuid = ?1", TEMPLATE_COLUMNS),
+                params![uuid],
+                template_from_row,
+            )
+            .optional()?;
+
+        Ok(template)
+    }
+
+    pub fn list_project_templates(&self) -> Result<Vec<ProjectTemplate>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM project_templates ORDER BY name COLLATE NOCASE, id",
+            TEMPLATE_COLUMNS
+        ))?;
+
+        let templates = stmt
+            .query_map([], template_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(templates)
+    }
+
+    /// Captures a project's description, notebooks (without outputs) and
+    /// dataset references as a template.
+    pub fn save_project_template(&self, project_uuid: &str, name: &str) -> Result<ProjectTemplate> {
+        if name.trim().is_empty() {
+            anyhow::bail!(tr!("template-name-empty"));
+        }
+
+        let project: Project = self
+            .conn
+            .query_row(
+                &format!("SELECT {} FROM projects WHERE uuid = ?1 AND deleted_at IS NULL", PROJECT_COLUMNS),
+                params![project_uuid],
+                project_from_row,
+            )
+            .optional()?
+            .ok_or_else(|| anyhow::anyhow!("Project {} not found", project_uuid))?;
+
+        let mut notebooks = Vec::new();
+        for notebook in self.get_project_notebooks(project.id)? {
+            let cells = self
+                .get_notebook_cells(notebook.id)?
+                .into_iter()
+                .map(|cell| TemplateCell { cell_type: cell.cell_type, source: cell.source, tags: cell.tags })
+                .collect();
+            notebooks.push(TemplateNotebook { name: notebook.name, cells });
+        }
+
+        let datasets = self
+            .list_datasets(project.id)?
+            .into_iter()
+            .map(|dataset| TemplateDataset {
+                name: dataset.name,
+                file_path: dataset.file_path,
+                format: dataset.format,
+                row_count: dataset.row_count,
+                size_bytes: dataset.size_bytes,
+            })
+            .collect();
+
+        let spec = TemplateSpec { description: project.description, notebooks, datasets };
+        let uuid = uuid::Uuid::new_v4().to_string();
+        self.conn.execute(
+            "INSERT INTO project_templates (uuid, name, source_project_uuid, spec) VALUES (?1, ?2, ?3, ?4)",
+            params![&uuid, name.trim(), project_uuid, serde_json::to_string(&spec)?],
+        )?;
+
+        self.get_project_template(&uuid)?
+            .ok_or_else(|| anyhow::anyhow!("Template {} missing after insert", uuid))
+    }
+
+    pub fn delete_project_template(&self, uuid: &str) -> Result<bool> {
+        let count = self.conn.execute("DELETE FROM project_templates WHERE uuid = ?1", params![uuid])?;
+        Ok(count > 0)
+    }
+
+    /// Creates a project in `workspace_id` from a template, in one
+    /// transaction, and queues it and everything in it for sync. Owned by
+    /// `owner_id`, or by the workspace owner when no one is signed in.
+    pub fn create_project_from_template(
+        &self,
+        template_uuid: &str,
+        workspace_id: i64,
+        name: &str,
+        owner_id: Option<i64>,
+    ) -> Result<TemplatedProject> {
+        if name.trim().is_empty() {
+            anyhow::bail!(tr!("project-name-empty"));
+        }
+
+        let template = self
+            .get_project_template(template_uuid)?
+            .ok_or_else(|| anyhow::anyhow!(tr!("template-not-found", uuid = template_uuid)))?;
+        let (workspace_uuid, workspace_owner): (String, i64) = self
+            .conn
+            .query_row(
+                "SELECT uuid, owner_id FROM workspaces WHERE id = ?1 AND deleted_at IS NULL",
+                params![workspace_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?
+            .ok_or_else(|| anyhow::anyhow!(tr!("workspace-not-found", uuid = workspace_id.to_string())))?;
+        let spec = &template.spec;
+
+        let tx = self.begin()?;
+
+        let project_uuid = uuid::Uuid::new_v4().to_string();
+        self.conn.execute(
+            "INSERT INTO projects (uuid, workspace_id, name, description, owner_id) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![&project_uuid, workspace_id, name.trim(), &spec.description, owner_id.unwrap_or(workspace_owner)],
+        )?;
+        let project_id = self.conn.last_insert_rowid();
+        self.add_to_sync_queue(
+            "project",
+            &project_uuid,
+            "create",
+            &serde_json::json!({
+                "workspace_uuid": workspace_uuid,
+                "name": name.trim(),
+                "description": spec.description,
+                "template_uuid": template.uuid,
+            })
+            .to_string(),
+        )?;
+
+        for notebook in &spec.notebooks {
+            let notebook_uuid = uuid::Uuid::new_v4().to_string();
+            self.conn.execute(
+                "INSERT INTO notebooks (uuid, project_id, name) VALUES (?1, ?2, ?3)",
+                params![&notebook_uuid, project_id, &notebook.name],
+            )?;
+            let notebook_id = self.conn.last_insert_rowid();
+
+            for (position, cell) in notebook.cells.iter().enumerate() {
+                self.conn.execute(
+                    "INSERT INTO notebook_cells (uuid, notebook_id, position, cell_type, source, tags)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![
+                        uuid::Uuid::new_v4().to_string(),
+                        notebook_id,
+                        position as i64,
+                        &cell.cell_type,
+                        &cell.source,
+                        &cell.tags,
+                    ],
+                )?;
+            }
+
+            self.add_to_sync_queue(
+                "notebook",
+                &notebook_uuid,
+                "create",
+                &serde_json::json!({ "project_uuid": project_uuid, "name": notebook.name }).to_string(),
+            )?;
+        }
+
+        for dataset in &spec.datasets {
+            let dataset_uuid = uuid::Uuid::new_v4().to_string();
+            self.create_dataset(&NewDataset {
+                uuid: dataset_uuid.clone(),
+                project_id,
+                name: dataset.name.clone(),
+                file_path: dataset.file_path.clone(),
+                format: dataset.format.clone(),
+                row_count: dataset.row_count,
+                size_bytes: dataset.size_bytes,
+                parent_uuid: None,
+            })?;
+            self.add_to_sync_queue(
+                "dataset",
+                &dataset_uuid,
+                "create",
+                &serde_json::json!({
+                    "project_uuid": project_uuid,
+                    "name": dataset.name,
+                    "format": dataset.format,
+                })
+                .to_string(),
+            )?;
+        }
+
+        let project = self.conn.query_row(
+            &format!("SELECT {} FROM projects WHERE id = ?1", PROJECT_COLUMNS),
+            params![project_id],
+            project_from_row,
+        )?;
+
+        tx.commit()?;
+
+        Ok(TemplatedProject { project, notebooks: spec.notebooks.len(), datasets: spec.datasets.len() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_from_template() {
+        let db_path = std::env::temp_dir().join("test_novem_templates.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        db.conn
+            .execute_batch(
+                "INSERT INTO users (id, uuid, email, username) VALUES (1, 'u1', 'a@example.com', 'ana');
+                 INSERT INTO workspaces (id, uuid, name, owner_id) VALUES (1, 'ws', 'Team', 1);
+                 INSERT INTO workspaces (id, uuid, name, owner_id) VALUES (2, 'ws2', 'Other', 1);
+                 INSERT INTO projects (id, uuid, workspace_id, name, description, owner_id)
+                    VALUES (1, 'q3', 1, 'Q3 review', 'Quarterly numbers', 1);
+                 INSERT INTO notebooks (id, uuid, project_id, name) VALUES (1, 'nb', 1, 'Revenue');
+                 INSERT INTO notebook_cells (uuid, notebook_id, position, source, outputs)
+                    VALUES ('c1', 1, 0, 'SELECT 1', '[{\"text\": \"1\"}]');
+                 INSERT INTO datasets (uuid, project_id, name, file_path, format, size_bytes)
+                    VALUES ('ds', 1, 'orders', '/data/orders.csv', 'csv', 2048);",
+            )
+            .unwrap();
+
+        assert!(db.save_project_template("q3", " ").is_err());
+        let template = db.save_project_template("q3", "Quarterly review").unwrap();
+        assert_eq!(template.spec.notebooks[0].cells.len(), 1);
+        assert_eq!(db.list_project_templates().unwrap().len(), 1);
+
+        // Still usable once the source project is gone
+        db.conn.execute("UPDATE projects SET deleted_at = '2025-01-01T00:00:00Z'", []).unwrap();
+        let created = db.create_project_from_template(&template.uuid, 2, "Q4 review", None).unwrap();
+        assert_eq!((created.notebooks, created.datasets), (1, 1));
+        assert_eq!(created.project.workspace_id, 2);
+        assert_eq!(created.project.description.as_deref(), Some("Quarterly numbers"));
+
+        let notebooks = db.get_project_notebooks(created.project.id).unwrap();
+        let cells = db.get_notebook_cells(notebooks[0].id).unwrap();
+        assert_eq!(cells[0].source, "SELECT 1");
+        assert_eq!(cells[0].outputs, "[]");
+        assert_eq!(db.list_datasets(created.project.id).unwrap()[0].file_path, "/data/orders.csv");
+
+        // Project, notebook and dataset creates are queued for sync
+        assert_eq!(db.get_pending_sync_items().unwrap().len(), 3);
+
+        assert!(db.create_project_from_template("missing", 2, "Nope", None).is_err());
+        assert!(db.create_project_from_template(&template.uuid, 9, "Nope", None).is_err());
+        assert!(db.delete_project_template(&template.uuid).unwrap());
+        assert!(db.list_project_templates().unwrap().is_empty());
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
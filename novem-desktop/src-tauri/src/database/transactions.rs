@@ -0,0 +1,118 @@
+use anyhow::Result;
+use rusqlite::Connection;
+use std::ops::Deref;
+
+use super::LocalDatabase;
+
+const SAVEPOINT: &str = "novem_nested";
+
+/// A transaction that nests: begun on an idle connection it is a real
+/// transaction, begun inside one it is a savepoint. Operations that are
+/// atomic on their own can then be combined into a larger atomic one.
+/// Dropping it without `commit` rolls back its part.
+pub struct DbTransaction<'a> {
+    conn: &'a Connection,
+    nested: bool,
+    finished: bool,
+}
+
+impl<'a> DbTransaction<'a> {
+    fn begin(conn: &'a Connection) -> Result<Self> {
+        let nested = !conn.is_autocommit();
+        conn.execute_batch(&if nested { format!("SAVEPOINT {}", SAVEPOINT) } else { "BEGIN".to_string() })?;
+        Ok(Self { conn, nested, finished: false })
+    }
+
+    pub fn commit(mut self) -> Result<()> {
+        self.finished = true;
+        if self.nested {
+            self.conn.execute_batch(&format!("RELEASE {}", SAVEPOINT))?;
+        } else {
+            self.conn.execute_batch("COMMIT")?;
+        }
+        Ok(())
+    }
+}
+
+impl Deref for DbTransaction<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn
+    }
+}
+
+impl Drop for DbTransaction<'_> {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        let rollback = if self.nested {
+            format!("ROLLBACK TO {sp}; RELEASE {sp}", sp = SAVEPOINT)
+        } else {
+            "ROLLBACK".to_string()
+        };
+        if let Err(e) = self.conn.execute_batch(&rollback) {
+            eprintln!("[ERROR] Failed to roll back transaction: {}", e);
+        }
+    }
+}
+
+impl LocalDatabase {
+    // Transactions
+    /// Starts a transaction, or a savepoint if one is already open.
+    pub fn begin(&self) -> Result<DbTransaction<'_>> {
+        DbTransaction::begin(&self.conn)
+    }
+
+    /// Runs `f` atomically: everything it writes, including through other
+    /// operations that use their own transactions, is committed together
+    /// or not at all.
+    pub fn atomically<T>(&self, f: impl FnOnce(&LocalDatabase) -> Result<T>) -> Result<T> {
+        let tx = self.begin()?;
+        let value = f(self)?;
+        tx.commit()?;
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nested_transactions() {
+        let db_path = std::env::temp_dir().join("test_novem_transactions.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+        let queued = |db: &LocalDatabase| -> i64 {
+            db.conn.query_row("SELECT COUNT(*) FROM sync_queue", [], |row| row.get(0)).unwrap()
+        };
+
+        // A failure after an inner operation committed undoes it too
+        let result: Result<()> = db.atomically(|db| {
+            db.add_to_sync_queue("workspace", "ws1", "create", "{}")?;
+            db.atomically(|db| db.add_to_sync_queue("project", "p1", "create", "{}"))?;
+            anyhow::bail!("crashed halfway")
+        });
+        assert!(result.is_err());
+        assert_eq!(queued(&db), 0);
+        assert!(db.conn.is_autocommit());
+
+        // A failing inner part rolls back alone when the caller carries on
+        db.atomically(|db| {
+            db.add_to_sync_queue("workspace", "ws1", "create", "{}")?;
+            let inner: Result<()> = db.atomically(|db| {
+                db.add_to_sync_queue("project", "p1", "create", "{}")?;
+                anyhow::bail!("rejected")
+            });
+            assert!(inner.is_err());
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(queued(&db), 1);
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
@@ -0,0 +1,233 @@
+use anyhow::Result;
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+use super::LocalDatabase;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transfer {
+    pub id: i64,
+    pub uuid: String,
+    pub entity_type: String, // 'artifact', 'attachment'
+    pub entity_uuid: String,
+    pub file_path: String,
+    pub file_name: String,
+    pub total_bytes: i64,
+    pub bytes_sent: i64,
+    pub chunk_index: i64,
+    pub chunk_size: i64,
+    pub status: String, // 'pending', 'uploading', 'failed', 'completed', 'cancelled'
+    pub retry_count: i64,
+    pub error_message: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+const TRANSFER_COLUMNS: &str =
+    "id, uuid, entity_type, entity_uuid, file_path, file_name, total_bytes, bytes_sent,
+     chunk_index, chunk_size, status, retry_count, error_message, created_at, updated_at";
+
+fn transfer_from_row(row: &Row) -> rusqlite::Result<Transfer> {
+    Ok(Transfer {
+        id: row.get(0)?,
+        uuid: row.get(1)?,
+        entity_type: row.get(2)?,
+        entity_uuid: row.get(3)?,
+        file_path: row.get(4)?,
+        file_name: row.get(5)?,
+        total_bytes: row.get(6)?,
+        bytes_sent: row.get(7)?,
+        chunk_index: row.get(8)?,
+        chunk_size: row.get(9)?,
+        status: row.get(10)?,
+        retry_count: row.get(11)?,
+        error_message: row.get(12)?,
+        created_at: row.get(13)?,
+        updated_at: row.get(14)?,
+    })
+}
+
+impl LocalDatabase {
+    // Transfer operations
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_transfer(
+        &self,
+        uuid: &str,
+        entity_type: &str,
+        entity_uuid: &str,
+        file_path: &str,
+        file_name: &str,
+        total_bytes: i64,
+        chunk_size: i64,
+    ) -> Result<Transfer> {
+        self.conn.execute(
+            "INSERT INTO transfers (uuid, entity_type, entity_uuid, file_path, file_name, total_bytes, chunk_size, status)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 'pending')",
+            params![uuid, entity_type, entity_uuid, file_path, file_name, total_bytes, chunk_size],
+        )?;
+
+        self.get_transfer(uuid)?
+            .ok_or_else(|| anyhow::anyhow!("Transfer {} missing after insert", uuid))
+    }
+
+    pub fn get_transfer(&self, uuid: &str) -> Result<Option<Transfer>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM transfers WHERE uuid = ?1",
+            TRANSFER_COLUMNS
+        ))?;
+
+        let transfer = stmt.query_row(params![uuid], transfer_from_row).optional()?;
+
+        Ok(transfer)
+    }
+
+    pub fn list_transfers(&self, status: Option<&str>) -> Result<Vec<Transfer>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM transfers
+             WHERE (?1 IS NULL OR status = ?1)
+             ORDER BY created_at DESC",
+            TRANSFER_COLUMNS
+        ))?;
+
+        let transfers = stmt
+            .query_map(params![status], transfer_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(transfers)
+    }
+
+    /// Transfers waiting to be (re)started by the resume worker.
+    pub fn get_resumable_transfers(&self, max_retries: i64) -> Result<Vec<Transfer>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM transfers
+             WHERE status = 'pending' AND retry_count < ?1
+             ORDER BY created_at ASC",
+            TRANSFER_COLUMNS
+        ))?;
+
+        let transfers = stmt
+            .query_map(params![max_retries], transfer_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(transfers)
+    }
+
+    pub fn update_transfer_progress(&self, uuid: &str, bytes_sent: i64, chunk_index: i64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE transfers
+             SET bytes_sent = ?1, chunk_index = ?2, error_message = NULL, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE uuid = ?3",
+            params![bytes_sent, chunk_index, uuid],
+        )?;
+        Ok(())
+    }
+
+    pub fn update_transfer_status(&self, uuid: &str, status: &str, error: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE transfers
+             SET status = ?1, error_message = ?2, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE uuid = ?3",
+            params![status, error, uuid],
+        )?;
+        Ok(())
+    }
+
+    /// Marks an upload finished, unless it was cancelled meanwhile. Returns
+    /// whether it was.
+    pub fn complete_transfer(&self, uuid: &str) -> Result<bool> {
+        let count = self.conn.execute(
+            "UPDATE transfers
+             SET status = 'completed', error_message = NULL, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE uuid = ?1 AND status = 'uploading'",
+            params![uuid],
+        )?;
+        Ok(count > 0)
+    }
+
+    /// Records a failed attempt. The transfer goes back to 'pending' so it is
+    /// resumed later, or to 'failed' once `max_retries` is reached.
+    pub fn record_transfer_failure(&self, uuid: &str, error: &str, max_retries: i64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE transfers
+             SET retry_count = retry_count + 1,
+                 status = CASE WHEN retry_count + 1 >= ?1 THEN 'failed' ELSE 'pending' END,
+                 error_message = ?2,
+                 updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE uuid = ?3 AND status NOT IN ('completed', 'cancelled')",
+            params![max_retries, error, uuid],
+        )?;
+        Ok(())
+    }
+
+    /// Puts a failed or interrupted transfer back in the queue, keeping the
+    /// bytes already sent so the upload continues where it stopped.
+    pub fn requeue_transfer(&self, uuid: &str) -> Result<bool> {
+        let count = self.conn.execute(
+            "UPDATE transfers
+             SET status = 'pending', retry_count = 0, error_message = NULL, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE uuid = ?1 AND status IN ('pending', 'failed')",
+            params![uuid],
+        )?;
+        Ok(count > 0)
+    }
+
+    pub fn cancel_transfer(&self, uuid: &str) -> Result<bool> {
+        let count = self.conn.execute(
+            "UPDATE transfers
+             SET status = 'cancelled', updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE uuid = ?1 AND status NOT IN ('completed', 'cancelled')",
+            params![uuid],
+        )?;
+        Ok(count > 0)
+    }
+
+    /// Transfers left in 'uploading' by a crash or forced quit are returned
+    /// to the queue on startup.
+    pub fn reset_interrupted_transfers(&self) -> Result<usize> {
+        let count = self.conn.execute(
+            "UPDATE transfers
+             SET status = 'pending', updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE status = 'uploading'",
+            [],
+        )?;
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfer_failure_and_requeue() {
+        let db_path = std::env::temp_dir().join("test_novem_transfers.db");
+        std::fs::remove_file(&db_path).ok();
+
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+        db.create_transfer("t-1", "artifact", "a-1", "/tmp/a.bin", "a.bin", 10, 4)
+            .unwrap();
+        db.update_transfer_progress("t-1", 4, 1).unwrap();
+
+        db.record_transfer_failure("t-1", "connection reset", 2).unwrap();
+        assert_eq!(db.get_transfer("t-1").unwrap().unwrap().status, "pending");
+
+        db.record_transfer_failure("t-1", "connection reset", 2).unwrap();
+        let transfer = db.get_transfer("t-1").unwrap().unwrap();
+        assert_eq!(transfer.status, "failed");
+        assert!(db.get_resumable_transfers(2).unwrap().is_empty());
+
+        assert!(db.requeue_transfer("t-1").unwrap());
+        let transfer = db.get_transfer("t-1").unwrap().unwrap();
+        assert_eq!(transfer.status, "pending");
+        assert_eq!(transfer.bytes_sent, 4);
+        assert_eq!(transfer.chunk_index, 1);
+
+        // A cancel landing during the last chunk wins over completion
+        db.update_transfer_status("t-1", "uploading", None).unwrap();
+        assert!(db.cancel_transfer("t-1").unwrap());
+        assert!(!db.complete_transfer("t-1").unwrap());
+        assert_eq!(db.get_transfer("t-1").unwrap().unwrap().status, "cancelled");
+
+        std::fs::remove_file(db_path).ok();
+    }
+}
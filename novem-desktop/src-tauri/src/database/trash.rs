@@ -0,0 +1,755 @@
+use anyhow::Result;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use super::deprovision::{collect_footprint, placeholders, project_footprint, remove_footprint};
+use super::{LocalDatabase, LocalFootprint};
+
+/// Something the user can move to the trash, addressed by UUID.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "uuid", rename_all = "snake_case")]
+pub enum TrashEntity {
+    Workspace(String),
+    Project(String),
+    Dataset(String),
+}
+
+impl TrashEntity {
+    fn from_tombstone(entity_type: &str, uuid: String) -> Option<Self> {
+        match entity_type {
+            "workspace" => Some(TrashEntity::Workspace(uuid)),
+            "project" => Some(TrashEntity::Project(uuid)),
+            "dataset" => Some(TrashEntity::Dataset(uuid)),
+            _ => None,
+        }
+    }
+
+    pub fn entity_type(&self) -> &'static str {
+        match self {
+            TrashEntity::Workspace(_) => "workspace",
+            TrashEntity::Project(_) => "project",
+            TrashEntity::Dataset(_) => "dataset",
+        }
+    }
+
+    pub fn uuid(&self) -> &str {
+        match self {
+            TrashEntity::Workspace(uuid) | TrashEntity::Project(uuid) | TrashEntity::Dataset(uuid) => uuid,
+        }
+    }
+}
+
+/// Table holding each tombstoned entity type.
+fn entity_table(entity_type: &str) -> Result<&'static str> {
+    match entity_type {
+        "workspace" => Ok("workspaces"),
+        "project" => Ok("projects"),
+        "notebook" => Ok("notebooks"),
+        "dataset" => Ok("datasets"),
+        "attachment" => Ok("attachments"),
+        _ => Err(anyhow::anyhow!("Unknown trashed entity type '{}'", entity_type)),
+    }
+}
+
+/// Tables that record when a row was trashed; notebooks only go with their
+/// project.
+const DELETED_AT_TABLES: &[&str] = &["workspaces", "projects", "datasets"];
+
+/// Tables of files kept only on this device. They have no sync state of
+/// their own, so `deleted_at` alone hides their trashed rows.
+const LOCAL_ONLY_TABLES: &[&str] = &["attachments"];
+
+/// What deleting an entity takes with it. Already-trashed children are left
+/// out; they have their own tombstones.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeleteImpact {
+    pub entity_type: String,
+    pub uuid: String,
+    pub name: String,
+    pub projects: usize,
+    pub notebooks: usize,
+    pub datasets: usize,
+    pub attachments: usize,
+    /// Size of the local dataset files, freed when the trash is emptied
+    pub local_bytes: i64,
+    /// Unsynced changes to the deleted entities that will never be pushed
+    pub pending_sync_items: usize,
+    /// Local IDs of the deleted projects, e.g. to stop their engines
+    pub project_ids: Vec<i64>,
+}
+
+/// A deleted entity, with what went to the trash along with it.
+#[derive(Debug, Clone, Serialize)]
+pub struct TrashItem {
+    #[serde(flatten)]
+    pub entity: TrashEntity,
+    pub name: String,
+    pub deleted_at: String,
+    pub projects: usize,
+    pub notebooks: usize,
+    pub datasets: usize,
+    pub attachments: usize,
+    /// The delete is still queued, so the backend hasn't seen it yet
+    pub unsynced: bool,
+}
+
+/// What emptying the trash removed.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TrashPurge {
+    pub entities: usize,
+    pub rows_removed: usize,
+    /// Files of the purged datasets, for the caller to delete
+    pub dataset_files: Vec<String>,
+    /// Managed copies of the purged projects' attachments, likewise
+    pub attachment_files: Vec<String>,
+}
+
+/// A delete the backend hasn't acknowledged yet, replayed from its
+/// tombstones by the sync push.
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingDelete {
+    pub entity_type: String,
+    pub uuid: String,
+    pub deleted_at: String,
+    /// Removed from this device for good; only the tombstones are left
+    pub purged: bool,
+    /// Entities deleted along with it, as `{ "type", "uuid" }`
+    pub cascade: Vec<serde_json::Value>,
+}
+
+/// Every active row a delete reaches, as (table, entity type, id, uuid).
+struct Scope {
+    name: String,
+    rows: Vec<(&'static str, &'static str, i64, String)>,
+    local_bytes: i64,
+}
+
+impl Scope {
+    fn count(&self, table: &str) -> usize {
+        self.rows.iter().filter(|(t, ..)| *t == table).count()
+    }
+
+    fn ids(&self, table: &str) -> Vec<i64> {
+        self.rows.iter().filter(|(t, ..)| *t == table).map(|(_, _, id, _)| *id).collect()
+    }
+}
+
+fn active_rows(conn: &Connection, sql: &str, parent_id: i64) -> Result<Vec<(i64, String)>> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt
+        .query_map(params![parent_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+fn collect_scope(conn: &Connection, entity: &TrashEntity) -> Result<Scope> {
+    let (root_table, root_sql) = match entity {
+        TrashEntity::Workspace(_) => ("workspaces", "SELECT id, name FROM workspaces WHERE uuid = ?1 AND is_active = 1"),
+        TrashEntity::Project(_) => ("projects", "SELECT id, name FROM projects WHERE uuid = ?1 AND is_active = 1"),
+        TrashEntity::Dataset(_) => ("datasets", "SELECT id, name FROM datasets WHERE uuid = ?1 AND is_active = 1"),
+    };
+
+    let (root_id, name): (i64, String) = conn
+        .query_row(root_sql, params![entity.uuid()], |row| Ok((row.get(0)?, row.get(1)?)))
+        .optional()?
+        .ok_or_else(|| anyhow::anyhow!("No active {} with UUID {}", entity.entity_type(), entity.uuid()))?;
+
+    let mut rows = vec![(root_table, entity.entity_type(), root_id, entity.uuid().to_string())];
+
+    let projects = match entity {
+        TrashEntity::Workspace(_) => {
+            let projects = active_rows(
+                conn,
+                "SELECT id, uuid FROM projects WHERE workspace_id = ?1 AND is_active = 1",
+                root_id,
+            )?;
+            rows.extend(projects.iter().map(|(id, uuid)| ("projects", "project", *id, uuid.clone())));
+            projects.into_iter().map(|(id, _)| id).collect()
+        }
+        TrashEntity::Project(_) => vec![root_id],
+        TrashEntity::Dataset(_) => Vec::new(),
+    };
+
+    let mut local_bytes = match entity {
+        TrashEntity::Dataset(_) => conn.query_row(
+            "SELECT size_bytes FROM datasets WHERE id = ?1",
+            params![root_id],
+            |row| row.get::<_, i64>(0),
+        )?,
+        _ => 0,
+    };
+    for project_id in projects {
+        for (id, uuid) in active_rows(
+            conn,
+            "SELECT id, uuid FROM notebooks WHERE project_id = ?1 AND is_active = 1",
+            project_id,
+        )? {
+            rows.push(("notebooks", "notebook", id, uuid));
+        }
+
+        for (id, uuid) in active_rows(
+            conn,
+            "SELECT id, uuid FROM datasets WHERE project_id = ?1 AND is_active = 1",
+            project_id,
+        )? {
+            rows.push(("datasets", "dataset", id, uuid));
+        }
+
+        for (id, uuid) in active_rows(
+            conn,
+            "SELECT id, uuid FROM attachments WHERE project_id = ?1 AND deleted_at IS NULL",
+            project_id,
+        )? {
+            rows.push(("attachments", "attachment", id, uuid));
+        }
+
+        local_bytes += conn.query_row(
+            "SELECT COALESCE(SUM(size_bytes), 0) FROM datasets WHERE project_id = ?1 AND is_active = 1",
+            params![project_id],
+            |row| row.get::<_, i64>(0),
+        )?;
+    }
+
+    Ok(Scope { name, rows, local_bytes })
+}
+
+fn pending_sync_items(conn: &Connection, scope: &Scope) -> Result<usize> {
+    let mut stmt = conn.prepare("SELECT COUNT(*) FROM sync_queue WHERE status = 'pending' AND entity_uuid = ?1")?;
+    let mut count = 0;
+    for (_, _, _, uuid) in &scope.rows {
+        count += stmt.query_row(params![uuid], |row| row.get::<_, i64>(0))? as usize;
+    }
+    Ok(count)
+}
+
+fn trash_item(conn: &Connection, entity: TrashEntity, deleted_at: String) -> Result<TrashItem> {
+    let name: Option<String> = conn
+        .query_row(
+            &format!("SELECT name FROM {} WHERE uuid = ?1", entity_table(entity.entity_type())?),
+            params![entity.uuid()],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT entity_type, COUNT(*) FROM tombstones
+         WHERE root_type = ?1 AND root_uuid = ?2 AND entity_uuid != ?2 AND purged = 0
+         GROUP BY entity_type",
+    )?;
+    let counts = stmt
+        .query_map(params![entity.entity_type(), entity.uuid()], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    let count = |entity_type: &str| counts.iter().find(|(t, _)| t == entity_type).map_or(0, |(_, n)| *n);
+
+    let unsynced = conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM sync_queue
+                        WHERE status = 'pending' AND action = 'delete' AND entity_type = ?1 AND entity_uuid = ?2)",
+        params![entity.entity_type(), entity.uuid()],
+        |row| row.get(0),
+    )?;
+
+    Ok(TrashItem {
+        name: name.unwrap_or_default(),
+        deleted_at,
+        projects: count("project"),
+        notebooks: count("notebook"),
+        datasets: count("dataset"),
+        attachments: count("attachment"),
+        unsynced,
+        entity,
+    })
+}
+
+fn trashed_root(conn: &Connection, entity: &TrashEntity) -> Result<TrashItem> {
+    let deleted_at: String = conn
+        .query_row(
+            "SELECT deleted_at FROM tombstones
+             WHERE entity_type = ?1 AND entity_uuid = ?2 AND root_type = ?1 AND root_uuid = ?2 AND purged = 0",
+            params![entity.entity_type(), entity.uuid()],
+            |row| row.get(0),
+        )
+        .optional()?
+        .ok_or_else(|| anyhow::anyhow!("No {} with UUID {} in the trash", entity.entity_type(), entity.uuid()))?;
+    trash_item(conn, entity.clone(), deleted_at)
+}
+
+/// Removes a trashed entity and everything deleted with it for good. The
+/// queued delete is kept, and so are the tombstones until the backend has
+/// acknowledged it.
+fn purge_root(conn: &Connection, entity: &TrashEntity, purge: &mut TrashPurge) -> Result<()> {
+    let footprint = match entity {
+        TrashEntity::Workspace(uuid) => collect_footprint(conn, Some(uuid))?,
+        TrashEntity::Project(uuid) => conn
+            .query_row("SELECT id FROM projects WHERE uuid = ?1", params![uuid], |row| row.get::<_, i64>(0))
+            .optional()?
+            .map(|id| project_footprint(conn, id, uuid))
+            .transpose()?,
+        TrashEntity::Dataset(uuid) => conn
+            .query_row("SELECT file_path FROM datasets WHERE uuid = ?1", params![uuid], |row| row.get::<_, String>(0))
+            .optional()?
+            .map(|file_path| LocalFootprint {
+                dataset_uuids: vec![uuid.clone()],
+                dataset_files: vec![file_path],
+                entity_uuids: vec![uuid.clone()],
+                ..Default::default()
+            }),
+    };
+
+    let mut roots = vec![entity.uuid().to_string()];
+    // Already gone with an earlier purged parent
+    if let Some(footprint) = footprint {
+        purge.rows_removed += remove_footprint(
+            conn,
+            &footprint,
+            &["transfers", "entity_lineage", "recent_items", "comments", "conflicts", "share_links"],
+        )?;
+        roots.extend(footprint.entity_uuids);
+        purge.dataset_files.extend(footprint.dataset_files);
+        purge.attachment_files.extend(footprint.attachment_files);
+    }
+
+    // Children trashed on their own before are purged along with it
+    let in_roots = placeholders(roots.len());
+    purge.rows_removed += conn.execute(
+        &format!("DELETE FROM tombstones WHERE synced_at IS NOT NULL AND root_uuid IN ({})", in_roots),
+        params_from_iter(&roots),
+    )?;
+    conn.execute(
+        &format!("UPDATE tombstones SET purged = 1 WHERE root_uuid IN ({})", in_roots),
+        params_from_iter(&roots),
+    )?;
+    purge.entities += 1;
+    Ok(())
+}
+
+fn impact(entity: &TrashEntity, scope: &Scope, pending_sync_items: usize) -> DeleteImpact {
+    let project_ids = scope.ids("projects");
+
+    DeleteImpact {
+        entity_type: entity.entity_type().to_string(),
+        uuid: entity.uuid().to_string(),
+        name: scope.name.clone(),
+        // The root itself doesn't count as its own child
+        projects: project_ids.len() - usize::from(matches!(entity, TrashEntity::Project(_))),
+        notebooks: scope.count("notebooks"),
+        datasets: scope.count("datasets"),
+        attachments: scope.count("attachments"),
+        local_bytes: scope.local_bytes,
+        pending_sync_items,
+        project_ids,
+    }
+}
+
+impl LocalDatabase {
+    // Trash operations
+    pub fn preview_delete(&self, entity: &TrashEntity) -> Result<DeleteImpact> {
+        let scope = collect_scope(&self.conn, entity)?;
+        let pending = pending_sync_items(&self.conn, &scope)?;
+        Ok(impact(entity, &scope, pending))
+    }
+
+    /// Moves an entity and everything under it to the trash in one
+    /// transaction: rows are deactivated and tombstoned, their unsynced
+    /// changes dropped, and a single cascading delete queued for sync.
+    /// Deleting a workspace takes its projects with their notebooks,
+    /// datasets and attachments.
+    pub fn delete_entity(&self, entity: &TrashEntity) -> Result<DeleteImpact> {
+        let tx = self.begin()?;
+
+        let scope = collect_scope(&tx, entity)?;
+        let pending = pending_sync_items(&tx, &scope)?;
+        let deleted_at = crate::timestamps::now();
+
+        for (table, entity_type, id, uuid) in &scope.rows {
+            let sql = if LOCAL_ONLY_TABLES.contains(table) {
+                format!("UPDATE {} SET deleted_at = ?2 WHERE id = ?1", table)
+            } else {
+                let stamp = if DELETED_AT_TABLES.contains(table) { ", deleted_at = ?2" } else { "" };
+                format!(
+                    "UPDATE {} SET is_active = 0, sync_status = 'pending', updated_at = ?2{} WHERE id = ?1",
+                    table, stamp
+                )
+            };
+            tx.execute(&sql, params![id, deleted_at])?;
+
+            tx.execute(
+                "INSERT INTO tombstones (entity_type, entity_uuid, root_type, root_uuid, deleted_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT(entity_type, entity_uuid) DO UPDATE SET
+                    root_type = excluded.root_type,
+                    root_uuid = excluded.root_uuid,
+                    deleted_at = excluded.deleted_at,
+                    synced_at = NULL,
+                    purged = 0",
+                params![entity_type, uuid, entity.entity_type(), entity.uuid(), deleted_at],
+            )?;
+
+            tx.execute(
+                "DELETE FROM sync_queue WHERE status = 'pending' AND entity_uuid = ?1",
+                params![uuid],
+            )?;
+        }
+
+        let cascade: Vec<serde_json::Value> = scope
+            .rows
+            .iter()
+            .skip(1)
+            .map(|(_, entity_type, _, uuid)| serde_json::json!({ "type": entity_type, "uuid": uuid }))
+            .collect();
+
+        tx.execute(
+            "INSERT INTO sync_queue (entity_type, entity_uuid, action, payload, status)
+             VALUES (?1, ?2, 'delete', ?3, 'pending')",
+            params![
+                entity.entity_type(),
+                entity.uuid(),
+                serde_json::json!({ "cascade": cascade }).to_string(),
+            ],
+        )?;
+
+        tx.commit()?;
+
+        Ok(impact(entity, &scope, pending))
+    }
+
+    /// Deleted entities, newest first. Children deleted along with a
+    /// parent are counted under it rather than listed.
+    pub fn list_trash(&self) -> Result<Vec<TrashItem>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT entity_type, entity_uuid, deleted_at FROM tombstones
+             WHERE entity_type = root_type AND entity_uuid = root_uuid AND purged = 0
+             ORDER BY deleted_at DESC, id DESC",
+        )?;
+        let roots = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        roots
+            .into_iter()
+            .filter_map(|(entity_type, uuid, deleted_at)| {
+                TrashEntity::from_tombstone(&entity_type, uuid).map(|entity| (entity, deleted_at))
+            })
+            .map(|(entity, deleted_at)| trash_item(&self.conn, entity, deleted_at))
+            .collect()
+    }
+
+    /// Brings a deleted entity back with everything deleted along with it.
+    /// A delete that hasn't synced yet is simply dropped from the queue;
+    /// otherwise a restore is queued in its place.
+    pub fn restore_entity(&self, entity: &TrashEntity) -> Result<TrashItem> {
+        let tx = self.begin()?;
+        let item = trashed_root(&tx, entity)?;
+
+        let parent_sql = match entity {
+            TrashEntity::Workspace(_) => None,
+            TrashEntity::Project(_) => Some(
+                "SELECT w.is_active FROM projects p JOIN workspaces w ON w.id = p.workspace_id WHERE p.uuid = ?1",
+            ),
+            TrashEntity::Dataset(_) => Some(
+                "SELECT p.is_active FROM datasets d JOIN projects p ON p.id = d.project_id WHERE d.uuid = ?1",
+            ),
+        };
+        if let Some(sql) = parent_sql {
+            let parent_active: bool = tx.query_row(sql, params![entity.uuid()], |row| row.get(0))?;
+            if !parent_active {
+                return Err(anyhow::anyhow!(
+                    "The {} '{}' is inside a deleted parent; restore that first",
+                    entity.entity_type(),
+                    item.name
+                ));
+            }
+        }
+
+        let mut stmt = tx.prepare("SELECT entity_type, entity_uuid FROM tombstones WHERE root_type = ?1 AND root_uuid = ?2")?;
+        let rows = stmt
+            .query_map(params![entity.entity_type(), entity.uuid()], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        drop(stmt);
+
+        for (entity_type, uuid) in &rows {
+            let table = entity_table(entity_type)?;
+            let sql = if LOCAL_ONLY_TABLES.contains(&table) {
+                format!("UPDATE {} SET deleted_at = NULL WHERE uuid = ?1", table)
+            } else {
+                let stamp = if DELETED_AT_TABLES.contains(&table) { ", deleted_at = NULL" } else { "" };
+                format!(
+                    "UPDATE {} SET is_active = 1, sync_status = 'pending',
+                        updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now'){}
+                     WHERE uuid = ?1",
+                    table, stamp
+                )
+            };
+            tx.execute(&sql, params![uuid])?;
+        }
+        tx.execute(
+            "DELETE FROM tombstones WHERE root_type = ?1 AND root_uuid = ?2",
+            params![entity.entity_type(), entity.uuid()],
+        )?;
+
+        let unqueued = tx.execute(
+            "DELETE FROM sync_queue
+             WHERE status = 'pending' AND action = 'delete' AND entity_type = ?1 AND entity_uuid = ?2",
+            params![entity.entity_type(), entity.uuid()],
+        )?;
+        if unqueued == 0 {
+            let cascade: Vec<serde_json::Value> = rows
+                .iter()
+                .filter(|(_, uuid)| uuid != entity.uuid())
+                .map(|(entity_type, uuid)| serde_json::json!({ "type": entity_type, "uuid": uuid }))
+                .collect();
+            tx.execute(
+                "INSERT INTO sync_queue (entity_type, entity_uuid, action, payload, status)
+                 VALUES (?1, ?2, 'restore', ?3, 'pending')",
+                params![
+                    entity.entity_type(),
+                    entity.uuid(),
+                    serde_json::json!({ "cascade": cascade }).to_string(),
+                ],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(item)
+    }
+
+    /// Permanently removes everything deleted at or before `cutoff` (an
+    /// RFC3339 timestamp).
+    pub fn purge_trash(&self, cutoff: &str) -> Result<TrashPurge> {
+        let tx = self.begin()?;
+
+        let mut stmt = tx.prepare(
+            "SELECT entity_type, entity_uuid FROM tombstones
+             WHERE entity_type = root_type AND entity_uuid = root_uuid AND purged = 0 AND deleted_at <= ?1",
+        )?;
+        let roots = stmt
+            .query_map(params![crate::timestamps::normalize(cutoff)], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        drop(stmt);
+
+        let mut purge = TrashPurge::default();
+        for (entity_type, uuid) in roots {
+            if let Some(entity) = TrashEntity::from_tombstone(&entity_type, uuid) {
+                purge_root(&tx, &entity, &mut purge)?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(purge)
+    }
+
+    /// Permanently removes one entity from the trash.
+    pub fn purge_entity(&self, entity: &TrashEntity) -> Result<TrashPurge> {
+        let tx = self.begin()?;
+        trashed_root(&tx, entity)?;
+
+        let mut purge = TrashPurge::default();
+        purge_root(&tx, entity, &mut purge)?;
+
+        tx.commit()?;
+        Ok(purge)
+    }
+
+    /// Deletes the backend hasn't acknowledged, oldest first, including
+    /// those already purged here.
+    pub fn pending_deletes(&self) -> Result<Vec<PendingDelete>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT root_type, root_uuid, entity_type, entity_uuid, deleted_at, purged FROM tombstones
+             WHERE synced_at IS NULL
+             ORDER BY deleted_at ASC, id ASC",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                    row.get::<_, bool>(5)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut deletes: Vec<PendingDelete> = Vec::new();
+        for (root_type, root_uuid, entity_type, uuid, deleted_at, purged) in rows {
+            let index = match deletes.iter().position(|d| d.entity_type == root_type && d.uuid == root_uuid) {
+                Some(index) => index,
+                None => {
+                    deletes.push(PendingDelete {
+                        entity_type: root_type,
+                        uuid: root_uuid,
+                        deleted_at,
+                        purged,
+                        cascade: Vec::new(),
+                    });
+                    deletes.len() - 1
+                }
+            };
+            let delete = &mut deletes[index];
+            if uuid != delete.uuid {
+                delete.cascade.push(serde_json::json!({ "type": entity_type, "uuid": uuid }));
+            }
+        }
+        Ok(deletes)
+    }
+
+    /// Records that the backend has applied a delete: its tombstones are
+    /// pruned once the rows are purged, and kept for restore until then.
+    /// Returns `false` if nothing was waiting on it.
+    pub fn acknowledge_delete(&self, entity_type: &str, uuid: &str) -> Result<bool> {
+        let tx = self.begin()?;
+
+        let acknowledged = tx.execute(
+            "UPDATE tombstones SET synced_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE root_type = ?1 AND root_uuid = ?2 AND synced_at IS NULL",
+            params![entity_type, uuid],
+        )?;
+        tx.execute(
+            "DELETE FROM tombstones WHERE root_type = ?1 AND root_uuid = ?2 AND purged = 1",
+            params![entity_type, uuid],
+        )?;
+        tx.execute(
+            "UPDATE sync_queue SET status = 'completed', updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE status = 'pending' AND action = 'delete' AND entity_type = ?1 AND entity_uuid = ?2",
+            params![entity_type, uuid],
+        )?;
+
+        tx.commit()?;
+        Ok(acknowledged > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cascading_delete() {
+        let db_path = std::env::temp_dir().join("test_novem_trash.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        db.conn
+            .execute_batch(
+                "INSERT INTO users (id, uuid, email, username) VALUES (1, 'u1', 'a@example.com', 'ana');
+                 INSERT INTO workspaces (id, uuid, name, owner_id) VALUES (1, 'ws', 'Team', 1);
+                 INSERT INTO projects (id, uuid, workspace_id, name, owner_id) VALUES (1, 'p1', 1, 'Sales', 1);
+                 INSERT INTO projects (id, uuid, workspace_id, name, owner_id, is_active) VALUES (2, 'p2', 1, 'Old', 1, 0);
+                 INSERT INTO notebooks (id, uuid, project_id, name) VALUES (1, 'nb', 1, 'Analysis');
+                 INSERT INTO datasets (uuid, project_id, name, file_path, format, size_bytes)
+                    VALUES ('ds', 1, 'orders', '/tmp/orders.csv', 'csv', 2048);
+                 INSERT INTO attachments (uuid, project_id, name, file_path, checksum, mime_type, size_bytes)
+                    VALUES ('a1', 1, 'brief.pdf', '/tmp/a1.pdf', '00', 'application/pdf', 10);",
+            )
+            .unwrap();
+        db.add_to_sync_queue("notebook", "nb", "update", "{}").unwrap();
+
+        let entity = TrashEntity::Workspace("ws".to_string());
+        let preview = db.preview_delete(&entity).unwrap();
+        assert_eq!(preview.projects, 1);
+        assert_eq!(preview.notebooks, 1);
+        assert_eq!(preview.datasets, 1);
+        assert_eq!(preview.attachments, 1);
+        assert_eq!(preview.local_bytes, 2048);
+        assert_eq!(preview.pending_sync_items, 1);
+
+        db.delete_entity(&entity).unwrap();
+
+        let tombstones: i64 = db.conn.query_row("SELECT COUNT(*) FROM tombstones", [], |row| row.get(0)).unwrap();
+        assert_eq!(tombstones, 5);
+        assert!(db.list_attachments(1).unwrap().is_empty());
+        let pending = db.get_pending_sync_items().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].action, "delete");
+        let payload: serde_json::Value = serde_json::from_str(&pending[0].payload).unwrap();
+        assert_eq!(payload["cascade"].as_array().unwrap().len(), 4);
+        assert!(db.preview_delete(&entity).is_err());
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+
+    #[test]
+    fn test_restore_and_purge() {
+        let db_path = std::env::temp_dir().join("test_novem_trash_restore.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        db.conn
+            .execute_batch(
+                "INSERT INTO users (id, uuid, email, username) VALUES (1, 'u1', 'a@example.com', 'ana');
+                 INSERT INTO workspaces (id, uuid, name, owner_id) VALUES (1, 'ws', 'Team', 1);
+                 INSERT INTO projects (id, uuid, workspace_id, name, owner_id) VALUES (1, 'p1', 1, 'Sales', 1);
+                 INSERT INTO notebooks (id, uuid, project_id, name) VALUES (1, 'nb', 1, 'Analysis');
+                 INSERT INTO datasets (uuid, project_id, name, file_path, format)
+                    VALUES ('ds', 1, 'orders', '/tmp/orders.csv', 'csv');
+                 INSERT INTO datasets (uuid, project_id, name, file_path, format)
+                    VALUES ('leads', 1, 'leads', '/tmp/leads.csv', 'csv');
+                 INSERT INTO attachments (uuid, project_id, name, file_path, checksum, mime_type, size_bytes)
+                    VALUES ('a1', 1, 'brief.pdf', '/tmp/a1.pdf', '00', 'application/pdf', 10);",
+            )
+            .unwrap();
+
+        let project = TrashEntity::Project("p1".to_string());
+        db.delete_entity(&project).unwrap();
+        let deleted_at: Option<String> =
+            db.conn.query_row("SELECT deleted_at FROM projects WHERE id = 1", [], |row| row.get(0)).unwrap();
+        assert!(deleted_at.is_some());
+
+        let trash = db.list_trash().unwrap();
+        assert_eq!(trash.len(), 1);
+        assert_eq!((trash[0].name.as_str(), trash[0].notebooks, trash[0].datasets), ("Sales", 1, 2));
+        assert_eq!(trash[0].attachments, 1);
+        assert!(trash[0].unsynced);
+
+        // An unsynced delete is just taken back
+        db.restore_entity(&project).unwrap();
+        assert!(db.list_trash().unwrap().is_empty());
+        assert_eq!(db.list_datasets(1).unwrap().len(), 2);
+        assert_eq!(db.list_attachments(1).unwrap().len(), 1);
+        assert!(db.get_pending_sync_items().unwrap().is_empty());
+
+        let dataset = TrashEntity::Dataset("ds".to_string());
+        db.delete_entity(&dataset).unwrap();
+        db.conn.execute("UPDATE sync_queue SET status = 'synced'", []).unwrap();
+        db.delete_entity(&project).unwrap();
+
+        // Not while its project is still in the trash
+        assert!(db.restore_entity(&dataset).is_err());
+
+        assert_eq!(db.purge_trash("2000-01-01T00:00:00Z").unwrap().entities, 0);
+        let purge = db.purge_entity(&project).unwrap();
+        assert_eq!(purge.dataset_files.len(), 2);
+        assert_eq!(purge.attachment_files, vec!["/tmp/a1.pdf"]);
+        let datasets: i64 = db.conn.query_row("SELECT COUNT(*) FROM datasets", [], |row| row.get(0)).unwrap();
+        assert_eq!(datasets, 0);
+        assert!(db.list_trash().unwrap().is_empty());
+
+        let pending = db.get_pending_sync_items().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!((pending[0].entity_uuid.as_str(), pending[0].action.as_str()), ("p1", "delete"));
+
+        // The purged deletes wait in their tombstones until acknowledged
+        let deletes = db.pending_deletes().unwrap();
+        assert_eq!(deletes.iter().map(|d| d.uuid.as_str()).collect::<Vec<_>>(), vec!["ds", "p1"]);
+        assert!(deletes.iter().all(|d| d.purged));
+        assert_eq!(deletes[1].cascade.len(), 3);
+
+        assert!(db.acknowledge_delete("project", "p1").unwrap());
+        assert!(db.acknowledge_delete("dataset", "ds").unwrap());
+        assert!(!db.acknowledge_delete("dataset", "ds").unwrap());
+        assert!(db.pending_deletes().unwrap().is_empty());
+        assert!(db.get_pending_sync_items().unwrap().is_empty());
+        let tombstones: i64 = db.conn.query_row("SELECT COUNT(*) FROM tombstones", [], |row| row.get(0)).unwrap();
+        assert_eq!(tombstones, 0);
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
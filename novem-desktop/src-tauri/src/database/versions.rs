@@ -0,0 +1,181 @@
+use anyhow::Result;
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+
+use super::LocalDatabase;
+
+/// Bumps `version` whenever a row is marked as changed locally. Writes that
+/// set `version` themselves, like applying the backend's copy, are left
+/// alone.
+pub(super) const VERSION_TRIGGERS: &str = "
+    CREATE TRIGGER IF NOT EXISTS version_workspaces_edit AFTER UPDATE OF sync_status ON workspaces
+    WHEN new.sync_status = 'pending' AND new.version = old.version BEGIN
+        UPDATE workspaces SET version = old.version + 1 WHERE id = new.id;
+    END;
+    CREATE TRIGGER IF NOT EXISTS version_projects_edit AFTER UPDATE OF sync_status ON projects
+    WHEN new.sync_status = 'pending' AND new.version = old.version BEGIN
+        UPDATE projects SET version = old.version + 1 WHERE id = new.id;
+    END;
+    CREATE TRIGGER IF NOT EXISTS version_datasets_edit AFTER UPDATE OF sync_status ON datasets
+    WHEN new.sync_status = 'pending' AND new.version = old.version BEGIN
+        UPDATE datasets SET version = old.version + 1 WHERE id = new.id;
+    END;";
+
+/// How a row compares with the backend's copy during sync.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionCheck {
+    /// Neither side changed since the last sync
+    InSync,
+    /// Only the backend changed; its copy can be applied
+    ServerAhead,
+    /// Only this device changed; the local edit can be pushed
+    LocalAhead,
+    /// Both changed since the last sync; the row is marked as a conflict
+    /// rather than either copy overwriting the other
+    Conflict,
+}
+
+pub(super) fn versioned_table(entity_type: &str) -> Result<&'static str> {
+    match entity_type {
+        "workspace" => Ok("workspaces"),
+        "project" => Ok("projects"),
+        "dataset" => Ok("datasets"),
+        _ => Err(anyhow::anyhow!("'{}' rows are not versioned", entity_type)),
+    }
+}
+
+/// Whether rows of `entity_type` carry a version and can take the
+/// backend's copy.
+pub fn is_versioned(entity_type: &str) -> bool {
+    versioned_table(entity_type).is_ok()
+}
+
+impl LocalDatabase {
+    // Row versions
+    /// Compares a row with the version the backend reports for it. The
+    /// backend is expected to store the version pushed to it and bump it
+    /// on its own edits, so both sides moving off the last synced version
+    /// means concurrent edits.
+    pub fn check_server_version(&self, entity_type: &str, uuid: &str, server_version: i64) -> Result<VersionCheck> {
+        let table = versioned_table(entity_type)?;
+        let (version, synced_version): (i64, Option<i64>) = self
+            .conn
+            .query_row(
+                &format!("SELECT version, server_version FROM {} WHERE uuid = ?1", table),
+                params![uuid],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?
+            .ok_or_else(|| anyhow::anyhow!("No {} with UUID {}", entity_type, uuid))?;
+
+        let local_changed = synced_version != Some(version);
+        let server_changed = synced_version != Some(server_version);
+        let check = match (local_changed, server_changed) {
+            (false, false) => VersionCheck::InSync,
+            (false, true) => VersionCheck::ServerAhead,
+            (true, false) => VersionCheck::LocalAhead,
+            // A row pushed but never acknowledged is fine if the backend
+            // holds exactly what was pushed
+            (true, true) if server_version == version => VersionCheck::InSync,
+            (true, true) => VersionCheck::Conflict,
+        };
+
+        if check == VersionCheck::Conflict {
+            self.conn.execute(
+                &format!("UPDATE {} SET sync_status = 'conflict' WHERE uuid = ?1", table),
+                params![uuid],
+            )?;
+        }
+        Ok(check)
+    }
+
+    /// Whether this device has the row at all, deleted or not.
+    pub fn has_versioned_row(&self, entity_type: &str, uuid: &str) -> Result<bool> {
+        let table = versioned_table(entity_type)?;
+        Ok(self
+            .conn
+            .query_row(&format!("SELECT 1 FROM {} WHERE uuid = ?1", table), params![uuid], |_| Ok(()))
+            .optional()?
+            .is_some())
+    }
+
+    /// Records that the backend now holds `version` of a row, after a push
+    /// it accepted or after its copy was applied here. A row edited again
+    /// in the meantime stays pending. Returns `false` if it doesn't exist.
+    pub fn mark_version_synced(&self, entity_type: &str, uuid: &str, version: i64) -> Result<bool> {
+        let table = versioned_table(entity_type)?;
+        let tx = self.begin()?;
+
+        let synced = tx.execute(
+            &format!(
+                "UPDATE {} SET version = ?2, server_version = ?2, sync_status = 'synced',
+                    last_synced_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+                 WHERE uuid = ?1 AND version <= ?2",
+                table
+            ),
+            params![uuid, version],
+        )?;
+        let edited_since = tx.execute(
+            &format!(
+                "UPDATE {} SET server_version = ?2, last_synced_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+                 WHERE uuid = ?1 AND version > ?2",
+                table
+            ),
+            params![uuid, version],
+        )?;
+
+        tx.commit()?;
+        Ok(synced + edited_since > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_concurrent_edits_conflict() {
+        let db_path = std::env::temp_dir().join("test_novem_versions.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        db.conn
+            .execute_batch(
+                "INSERT INTO users (id, uuid, email, username) VALUES (1, 'u1', 'a@example.com', 'ana');
+                 INSERT INTO workspaces (id, uuid, name, owner_id) VALUES (1, 'ws1', 'Team', 1);
+                 INSERT INTO projects (id, uuid, workspace_id, name, owner_id) VALUES (1, 'p1', 1, 'Sales', 1);",
+            )
+            .unwrap();
+        let version = |db: &LocalDatabase| db.get_workspace_by_uuid("ws1").unwrap().unwrap().version;
+
+        // Created here and pushed as version 1
+        assert_eq!(db.check_server_version("workspace", "ws1", 1).unwrap(), VersionCheck::InSync);
+        assert!(db.mark_version_synced("workspace", "ws1", 1).unwrap());
+
+        db.set_workspace_archived("ws1", true).unwrap();
+        assert_eq!(version(&db), 2);
+        assert_eq!(db.check_server_version("workspace", "ws1", 1).unwrap(), VersionCheck::LocalAhead);
+        assert_eq!(db.check_server_version("workspace", "ws1", 3).unwrap(), VersionCheck::Conflict);
+        assert_eq!(db.get_workspace_by_uuid("ws1").unwrap().unwrap().sync_status, "conflict");
+
+        // Applying the backend's copy settles it
+        assert!(db.mark_version_synced("workspace", "ws1", 3).unwrap());
+        assert_eq!(version(&db), 3);
+        assert_eq!(db.check_server_version("workspace", "ws1", 4).unwrap(), VersionCheck::ServerAhead);
+
+        // An edit made while a push was in flight is kept pending
+        db.set_workspace_archived("ws1", false).unwrap();
+        db.set_workspace_archived("ws1", true).unwrap();
+        assert_eq!(version(&db), 5);
+        assert!(db.mark_version_synced("workspace", "ws1", 4).unwrap());
+        let workspace = db.get_workspace_by_uuid("ws1").unwrap().unwrap();
+        assert_eq!((workspace.version, workspace.sync_status.as_str()), (5, "pending"));
+
+        assert!(db.check_server_version("notebook", "nb", 1).is_err());
+        assert!(!db.mark_version_synced("project", "missing", 1).unwrap());
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
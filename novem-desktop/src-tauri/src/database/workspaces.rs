@@ -0,0 +1,291 @@
+use anyhow::Result;
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+
+use super::clones::{project_from_row, PROJECT_COLUMNS};
+use super::{LocalDatabase, Project, Workspace};
+use crate::i18n::tr;
+
+/// One recent change inside a workspace, for the activity feed.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceActivity {
+    pub kind: String, // 'notebook', 'dataset', 'query'
+    pub project_id: i64,
+    pub title: String,
+    pub at: String,
+}
+
+/// UUID for a row created on this device. Version 7 UUIDs start with their
+/// creation time, so rows created offline sort and index in creation order.
+fn local_uuid() -> String {
+    uuid::Uuid::now_v7().to_string()
+}
+
+impl LocalDatabase {
+    // Workspace operations
+    pub fn get_workspace_by_uuid(&self, uuid: &str) -> Result<Option<Workspace>> {
+        let workspace = self.conn
+            .query_row(
+                "SELECT id, uuid, name, description, owner_id, created_at, updated_at,
+                        is_active, sync_status, last_synced_at, is_pinned, version
+                 FROM workspaces WHERE uuid = ?1 AND is_active = 1",
+                params![uuid],
+                |row| {
+                    Ok(Workspace {
+                        id: row.get(0)?,
+                        uuid: row.get(1)?,
+                        name: row.get(2)?,
+                        description: row.get(3)?,
+                        owner_id: row.get(4)?,
+                        created_at: row.get(5)?,
+                        updated_at: row.get(6)?,
+                        is_active: row.get(7)?,
+                        sync_status: row.get(8)?,
+                        last_synced_at: row.get(9)?,
+                        is_pinned: row.get(10)?,
+                        version: row.get(11)?,
+                    })
+                },
+            )
+            .optional()?;
+
+        Ok(workspace)
+    }
+
+    /// Creates a workspace owned by `owner_id` while offline: it gets its
+    /// UUID here and stays pending until the queued create is pushed.
+    pub fn create_workspace(&self, name: &str, description: Option<&str>, owner_id: i64) -> Result<Workspace> {
+        if name.trim().is_empty() {
+            anyhow::bail!(tr!("workspace-name-empty"));
+        }
+        let uuid = local_uuid();
+
+        let tx = self.begin()?;
+        self.conn.execute(
+            "INSERT INTO workspaces (uuid, name, description, owner_id, sync_status)
+             VALUES (?1, ?2, ?3, ?4, 'pending')",
+            params![&uuid, name.trim(), description, owner_id],
+        )?;
+        self.add_to_sync_queue(
+            "workspace",
+            &uuid,
+            "create",
+            &serde_json::json!({ "name": name.trim(), "description": description }).to_string(),
+        )?;
+        tx.commit()?;
+
+        self.get_workspace_by_uuid(&uuid)?
+            .ok_or_else(|| anyhow::anyhow!("Workspace {} missing after insert", uuid))
+    }
+
+    /// Creates an empty project in the workspace while offline, queued for
+    /// sync like `create_workspace`. Owned by `owner_id`, or by the
+    /// workspace owner when no one is signed in.
+    pub fn create_project(
+        &self,
+        workspace_id: i64,
+        name: &str,
+        description: Option<&str>,
+        owner_id: Option<i64>,
+    ) -> Result<Project> {
+        if name.trim().is_empty() {
+            anyhow::bail!(tr!("project-name-empty"));
+        }
+        let (workspace_uuid, workspace_owner): (String, i64) = self
+            .conn
+            .query_row(
+                "SELECT uuid, owner_id FROM workspaces WHERE id = ?1 AND deleted_at IS NULL",
+                params![workspace_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?
+            .ok_or_else(|| anyhow::anyhow!(tr!("workspace-not-found", uuid = workspace_id.to_string())))?;
+        let uuid = local_uuid();
+
+        let tx = self.begin()?;
+        self.conn.execute(
+            "INSERT INTO projects (uuid, workspace_id, name, description, owner_id, sync_status)
+             VALUES (?1, ?2, ?3, ?4, ?5, 'pending')",
+            params![&uuid, workspace_id, name.trim(), description, owner_id.unwrap_or(workspace_owner)],
+        )?;
+        self.add_to_sync_queue(
+            "project",
+            &uuid,
+            "create",
+            &serde_json::json!({
+                "workspace_uuid": workspace_uuid,
+                "name": name.trim(),
+                "description": description,
+            })
+            .to_string(),
+        )?;
+        tx.commit()?;
+
+        Ok(self.conn.query_row(
+            &format!("SELECT {} FROM projects WHERE uuid = ?1", PROJECT_COLUMNS),
+            params![&uuid],
+            project_from_row,
+        )?)
+    }
+
+    /// Archives or unarchives a workspace and queues the change for sync.
+    /// Archived workspaces keep all their data but drop out of the default
+    /// workspace listing.
+    pub fn set_workspace_archived(&self, uuid: &str, archived: bool) -> Result<()> {
+        let workspace = self
+            .get_workspace_by_uuid(uuid)?
+            .ok_or_else(|| anyhow::anyhow!(tr!("workspace-not-found", uuid = uuid)))?;
+
+        let tx = self.begin()?;
+        let changed = if archived {
+            self.conn.execute(
+                "INSERT OR IGNORE INTO archived_workspaces (workspace_id) VALUES (?1)",
+                params![workspace.id],
+            )?
+        } else {
+            self.conn.execute("DELETE FROM archived_workspaces WHERE workspace_id = ?1", params![workspace.id])?
+        };
+        match (changed, archived) {
+            (0, true) => anyhow::bail!(tr!("workspace-already-archived", name = workspace.name.as_str())),
+            (0, false) => anyhow::bail!(tr!("workspace-not-archived", name = workspace.name.as_str())),
+            _ => {}
+        }
+
+        self.conn.execute(
+            "UPDATE workspaces SET sync_status = 'pending', updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = ?1",
+            params![workspace.id],
+        )?;
+        self.queue_delta("workspace", uuid, serde_json::json!({ "archived": archived }))?;
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Every open project in the workspace, whoever owns it.
+    pub fn get_workspace_projects(&self, workspace_id: i64) -> Result<Vec<Project>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM projects
+             WHERE workspace_id = ?1 AND is_active = 1
+               AND id NOT IN (SELECT project_id FROM archived_projects)
+             ORDER BY is_pinned DESC, updated_at DESC",
+            PROJECT_COLUMNS
+        ))?;
+
+        let projects = stmt
+            .query_map(params![workspace_id], project_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(projects)
+    }
+
+    /// Latest notebook edits, dataset changes and queries across the
+    /// workspace's projects, newest first.
+    pub fn get_workspace_activity(&self, workspace_id: i64, limit: i64) -> Result<Vec<WorkspaceActivity>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT kind, project_id, title, at FROM (
+                SELECT 'notebook' AS kind, n.project_id, n.name AS title, n.updated_at AS at
+                FROM notebooks n WHERE n.is_active = 1
+                UNION ALL
+                SELECT 'dataset', d.project_id, d.name, d.updated_at
+                FROM datasets d
+                UNION ALL
+                SELECT 'query', q.project_id, q.query_text, q.executed_at
+                FROM query_history q WHERE q.project_id IS NOT NULL
+             ) activity
+             WHERE project_id IN (SELECT id FROM projects WHERE workspace_id = ?1 AND is_active = 1)
+             ORDER BY at DESC
+             LIMIT ?2"
+        )?;
+
+        let activity = stmt
+            .query_map(params![workspace_id, limit], |row| {
+                Ok(WorkspaceActivity {
+                    kind: row.get(0)?,
+                    project_id: row.get(1)?,
+                    title: row.get(2)?,
+                    at: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(activity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::PageRequest;
+
+    #[test]
+    fn test_workspace_activity() {
+        let db_path = std::env::temp_dir().join("test_novem_workspaces.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        db.conn
+            .execute_batch(
+                "INSERT INTO users (id, uuid, email, username) VALUES (1, 'u1', 'a@example.com', 'ana');
+                 INSERT INTO users (id, uuid, email, username) VALUES (2, 'u2', 'b@example.com', 'ben');
+                 INSERT INTO workspaces (id, uuid, name, owner_id) VALUES (1, 'ws1', 'Team', 1);
+                 INSERT INTO workspaces (id, uuid, name, owner_id) VALUES (2, 'ws2', 'Other', 1);
+                 INSERT INTO projects (id, uuid, workspace_id, name, owner_id) VALUES (1, 'p1', 1, 'Sales', 1);
+                 INSERT INTO projects (id, uuid, workspace_id, name, owner_id) VALUES (2, 'p2', 1, 'Churn', 2);
+                 INSERT INTO projects (id, uuid, workspace_id, name, owner_id) VALUES (3, 'p3', 2, 'Elsewhere', 1);
+                 INSERT INTO notebooks (id, uuid, project_id, name, updated_at)
+                    VALUES (1, 'n1', 1, 'Forecast', '2025-03-14T09:00:00Z');
+                 INSERT INTO notebooks (id, uuid, project_id, name, updated_at)
+                    VALUES (2, 'n2', 3, 'Hidden', '2025-03-14T12:00:00Z');
+                 INSERT INTO query_history (kind, query_text, project_id, params, duration_ms, status, executed_at)
+                    VALUES ('sql', 'SELECT 1', 2, '{}', 3, 'success', '2025-03-14T10:00:00Z');",
+            )
+            .unwrap();
+
+        let workspace = db.get_workspace_by_uuid("ws1").unwrap().unwrap();
+        assert_eq!(workspace.name, "Team");
+        assert!(db.get_workspace_by_uuid("missing").unwrap().is_none());
+
+        // Projects of other members are included
+        let projects = db.get_workspace_projects(workspace.id).unwrap();
+        assert_eq!(projects.len(), 2);
+
+        let activity = db.get_workspace_activity(workspace.id, 10).unwrap();
+        let titles: Vec<&str> = activity.iter().map(|a| a.title.as_str()).collect();
+        assert_eq!(titles, vec!["SELECT 1", "Forecast"]);
+
+        // Archived workspaces only show up when asked for
+        let page = PageRequest::default();
+        db.set_workspace_archived("ws2", true).unwrap();
+        assert!(db.set_workspace_archived("ws2", true).is_err());
+        let open = db.get_workspaces(1, false, &page).unwrap();
+        assert_eq!(open.items.iter().map(|w| w.uuid.as_str()).collect::<Vec<_>>(), vec!["ws1"]);
+        assert_eq!(open.total, 1);
+        assert_eq!(db.get_workspaces(1, true, &page).unwrap().items[0].uuid, "ws2");
+        assert_eq!(db.get_pending_sync_items().unwrap().len(), 1);
+
+        db.set_workspace_archived("ws2", false).unwrap();
+        assert!(db.set_workspace_archived("ws2", false).is_err());
+        assert_eq!(db.get_workspaces(1, false, &page).unwrap().total, 2);
+
+        // A workspace whose first project is rejected is not created either
+        let queued = db.get_pending_sync_items().unwrap().len();
+        let result = db.atomically(|db| {
+            let workspace = db.create_workspace("Research", None, 1)?;
+            db.create_project(workspace.id, "  ", None, Some(1))
+        });
+        assert!(result.is_err());
+        assert_eq!(db.get_workspaces(1, false, &page).unwrap().total, 2);
+        assert_eq!(db.get_pending_sync_items().unwrap().len(), queued);
+
+        let workspace = db.create_workspace(" Research ", Some("Experiments"), 1).unwrap();
+        let project = db.create_project(workspace.id, "Pilot", None, None).unwrap();
+        assert_eq!((workspace.name.as_str(), project.workspace_id), ("Research", workspace.id));
+        assert_eq!((project.owner_id, project.sync_status.as_str()), (1, "pending"));
+        assert_eq!(uuid::Uuid::parse_str(&project.uuid).unwrap().get_version_num(), 7);
+        assert_eq!(db.get_pending_sync_items().unwrap().len(), queued + 2);
+        assert!(db.create_workspace("", None, 1).is_err());
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
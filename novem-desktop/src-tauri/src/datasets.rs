@@ -0,0 +1,226 @@
+use anyhow::{Context, Result};
+use arrow::compute::cast;
+use arrow::csv;
+use arrow::datatypes::SchemaRef;
+use arrow::error::ArrowError;
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::{ArrowWriter, ProjectionMask};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+pub mod expressions;
+pub mod recipes;
+pub mod sampling;
+pub mod stats;
+
+const BATCH_SIZE: usize = 8192;
+const SCHEMA_INFERENCE_ROWS: usize = 1000;
+
+pub type BatchIter = Box<dyn Iterator<Item = Result<RecordBatch, ArrowError>> + Send>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatasetFormat {
+    Csv,
+    Parquet,
+}
+
+impl DatasetFormat {
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let extension = path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        match extension.as_str() {
+            "csv" => Ok(DatasetFormat::Csv),
+            "parquet" | "pq" => Ok(DatasetFormat::Parquet),
+            other => Err(anyhow::anyhow!("Unsupported dataset format: '{}'", other)),
+        }
+    }
+
+    pub fn parse(format: &str) -> Result<Self> {
+        match format {
+            "csv" => Ok(DatasetFormat::Csv),
+            "parquet" => Ok(DatasetFormat::Parquet),
+            other => Err(anyhow::anyhow!("Unsupported dataset format: '{}'", other)),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DatasetFormat::Csv => "csv",
+            DatasetFormat::Parquet => "parquet",
+        }
+    }
+}
+
+/// Where derived datasets (samples, transforms) are written.
+pub fn managed_dataset_path(data_dir: &Path, uuid: &str) -> PathBuf {
+    data_dir.join("datasets").join(format!("{}.parquet", uuid))
+}
+
+fn csv_schema(path: &Path) -> Result<SchemaRef> {
+    let file = File::open(path).context(format!("Failed to open {:?}", path))?;
+    let (schema, _) = csv::reader::Format::default()
+        .with_header(true)
+        .infer_schema(file, Some(SCHEMA_INFERENCE_ROWS))?;
+    Ok(Arc::new(schema))
+}
+
+/// Reads the schema of a dataset file without loading any rows.
+pub fn read_schema(path: &Path, format: DatasetFormat) -> Result<SchemaRef> {
+    match format {
+        DatasetFormat::Csv => csv_schema(path),
+        DatasetFormat::Parquet => {
+            let file = File::open(path).context(format!("Failed to open {:?}", path))?;
+            Ok(ParquetRecordBatchReaderBuilder::try_new(file)?.schema().clone())
+        }
+    }
+}
+
+/// Streams a dataset file as Arrow record batches, optionally reading only
+/// the given top-level columns.
+pub fn open_batches(path: &Path, format: DatasetFormat, projection: Option<Vec<usize>>) -> Result<BatchIter> {
+    let file = File::open(path).context(format!("Failed to open {:?}", path))?;
+
+    match format {
+        DatasetFormat::Csv => {
+            let mut builder = csv::ReaderBuilder::new(csv_schema(path)?)
+                .with_header(true)
+                .with_batch_size(BATCH_SIZE);
+            if let Some(columns) = projection {
+                builder = builder.with_projection(columns);
+            }
+            Ok(Box::new(builder.build(file)?))
+        }
+        DatasetFormat::Parquet => {
+            let mut builder = ParquetRecordBatchReaderBuilder::try_new(file)?.with_batch_size(BATCH_SIZE);
+            if let Some(columns) = projection {
+                let mask = ProjectionMask::roots(builder.parquet_schema(), columns);
+                builder = builder.with_projection(mask);
+            }
+            Ok(Box::new(builder.build()?))
+        }
+    }
+}
+
+/// Counts rows, using Parquet footer metadata when available.
+pub fn count_rows(path: &Path, format: DatasetFormat) -> Result<u64> {
+    match format {
+        DatasetFormat::Parquet => {
+            let file = File::open(path).context(format!("Failed to open {:?}", path))?;
+            let builder = ParquetRecordBatchReaderBuilder::try_new(file)?;
+            Ok(builder.metadata().file_metadata().num_rows() as u64)
+        }
+        DatasetFormat::Csv => {
+            let mut rows = 0u64;
+            for batch in open_batches(path, format, Some(vec![0]))? {
+                rows += batch?.num_rows() as u64;
+            }
+            Ok(rows)
+        }
+    }
+}
+
+/// Writes batches to a new Parquet file, returning the number of rows written.
+pub fn write_parquet(path: &Path, schema: SchemaRef, batches: impl Iterator<Item = Result<RecordBatch>>) -> Result<u64> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).context(format!("Failed to create {:?}", parent))?;
+    }
+
+    let file = File::create(path).context(format!("Failed to create {:?}", path))?;
+    let mut writer = ArrowWriter::try_new(file, schema, None)?;
+    let mut rows = 0u64;
+
+    for batch in batches {
+        let batch = batch?;
+        rows += batch.num_rows() as u64;
+        writer.write(&batch)?;
+    }
+
+    writer.close()?;
+    Ok(rows)
+}
+
+/// Reorders and casts a batch to `schema`, matching columns by name.
+pub fn conform_batch(batch: &RecordBatch, schema: &SchemaRef) -> Result<RecordBatch> {
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let column = batch
+                .column_by_name(field.name())
+                .ok_or_else(|| anyhow::anyhow!("Appended data is missing column '{}'", field.name()))?;
+            cast(column, field.data_type())
+                .context(format!("Column '{}' cannot be converted to {}", field.name(), field.data_type()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(RecordBatch::try_new(schema.clone(), columns)?)
+}
+
+/// Scratch file a Parquet append is written to before replacing the original.
+pub fn append_temp_path(path: &Path) -> PathBuf {
+    path.with_extension("parquet.tmp")
+}
+
+/// Appends batches (already conformed to `schema`) to a dataset file and
+/// returns the number of rows appended. CSV is appended in place; Parquet
+/// is rewritten to a temporary file that replaces the original.
+pub fn append_batches(
+    path: &Path,
+    format: DatasetFormat,
+    schema: SchemaRef,
+    batches: impl Iterator<Item = Result<RecordBatch>>,
+) -> Result<u64> {
+    match format {
+        DatasetFormat::Csv => {
+            let mut file = OpenOptions::new()
+                .read(true)
+                .append(true)
+                .open(path)
+                .context(format!("Failed to open {:?}", path))?;
+
+            // Make sure appended rows start on a fresh line
+            if file.metadata()?.len() > 0 {
+                let mut last = [0u8; 1];
+                file.seek(SeekFrom::End(-1))?;
+                file.read_exact(&mut last)?;
+                if last[0] != b'\n' {
+                    file.write_all(b"\n")?;
+                }
+            }
+
+            let mut writer = csv::WriterBuilder::new().with_header(false).build(file);
+            let mut rows = 0u64;
+            for batch in batches {
+                let batch = batch?;
+                rows += batch.num_rows() as u64;
+                writer.write(&batch)?;
+            }
+            Ok(rows)
+        }
+        DatasetFormat::Parquet => {
+            let temp = append_temp_path(path);
+            let existing = open_batches(path, format, None)?.map(|batch| batch.map_err(Into::into));
+
+            let mut appended = 0u64;
+            let total = write_parquet(&temp, schema, existing.chain(batches.inspect(|batch| {
+                if let Ok(batch) = batch {
+                    appended += batch.num_rows() as u64;
+                }
+            })));
+
+            if let Err(e) = total {
+                let _ = std::fs::remove_file(&temp);
+                return Err(e);
+            }
+
+            std::fs::rename(&temp, path).context(format!("Failed to replace {:?}", path))?;
+            Ok(appended)
+        }
+    }
+}
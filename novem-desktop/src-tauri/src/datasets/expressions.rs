@@ -0,0 +1,509 @@
+use anyhow::Result;
+use arrow::array::{
+    new_null_array, Array, ArrayRef, AsArray, BooleanArray, Float64Array, Int64Array, StringArray,
+};
+use arrow::compute::kernels::{boolean, cmp, numeric, zip};
+use arrow::compute::{cast, is_not_null, is_null};
+use arrow::datatypes::{DataType, Float64Type, Int64Type};
+use arrow::record_batch::RecordBatch;
+use std::sync::Arc;
+
+/// A column expression used by recipe `filter` and `derive` steps:
+///
+/// ```text
+/// price * quantity
+/// upper(trim(country)) = 'US' and not (discount is null)
+/// concat(first_name, ' ', "last name")
+/// ```
+///
+/// Columns are bare identifiers, or double-quoted when they contain other
+/// characters; strings are single-quoted.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Column(String),
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Bool(bool),
+    Null,
+    Neg(Box<Expr>),
+    Not(Box<Expr>),
+    IsNull { expr: Box<Expr>, negated: bool },
+    Binary { op: BinaryOp, left: Box<Expr>, right: Box<Expr> },
+    Call { function: String, args: Vec<Expr> },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    And,
+    Or,
+}
+
+const FUNCTIONS: &[&str] = &["upper", "lower", "trim", "length", "abs", "round", "coalesce", "concat", "contains"];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Column(String),
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Symbol(&'static str),
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    const SYMBOLS: &[&str] = &["<=", ">=", "!=", "<>", "==", "+", "-", "*", "/", "%", "=", "<", ">", "(", ")", ","];
+
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '\'' || c == '"' {
+            // A doubled quote inside the literal stands for the quote itself
+            let mut text = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    Some(&q) if q == c && chars.get(i + 1) == Some(&c) => {
+                        text.push(c);
+                        i += 2;
+                    }
+                    Some(&q) if q == c => {
+                        i += 1;
+                        break;
+                    }
+                    Some(&other) => {
+                        text.push(other);
+                        i += 1;
+                    }
+                    None => return Err(anyhow::anyhow!("Unterminated quote in expression")),
+                }
+            }
+            tokens.push(if c == '\'' { Token::Str(text) } else { Token::Column(text) });
+        } else if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(|d| d.is_ascii_digit())) {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            tokens.push(match text.parse::<i64>() {
+                Ok(value) => Token::Int(value),
+                Err(_) => Token::Float(text.parse().map_err(|_| anyhow::anyhow!("Invalid number '{}'", text))?),
+            });
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else {
+            let rest: String = chars[i..].iter().take(2).collect();
+            let symbol = SYMBOLS
+                .iter()
+                .find(|symbol| rest.starts_with(**symbol))
+                .ok_or_else(|| anyhow::anyhow!("Unexpected '{}' in expression", c))?;
+            tokens.push(Token::Symbol(symbol));
+            i += symbol.len();
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Recursive descent, loosest binding first: or, and, not, comparison,
+/// `is [not] null`, additive, multiplicative, unary minus.
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn keyword(&mut self, word: &str) -> bool {
+        match self.peek() {
+            Some(Token::Ident(ident)) if ident.eq_ignore_ascii_case(word) => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn symbol(&mut self, symbols: &[&'static str]) -> Option<&'static str> {
+        match self.peek() {
+            Some(Token::Symbol(symbol)) if symbols.contains(symbol) => {
+                let symbol = *symbol;
+                self.position += 1;
+                Some(symbol)
+            }
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, symbol: &'static str) -> Result<()> {
+        self.symbol(&[symbol])
+            .map(|_| ())
+            .ok_or_else(|| anyhow::anyhow!("Expected '{}' in expression", symbol))
+    }
+
+    fn or(&mut self) -> Result<Expr> {
+        let mut expr = self.and()?;
+        while self.keyword("or") {
+            expr = binary(BinaryOp::Or, expr, self.and()?);
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        let mut expr = self.not()?;
+        while self.keyword("and") {
+            expr = binary(BinaryOp::And, expr, self.not()?);
+        }
+        Ok(expr)
+    }
+
+    fn not(&mut self) -> Result<Expr> {
+        if self.keyword("not") {
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr> {
+        let left = self.additive()?;
+
+        if self.keyword("is") {
+            let negated = self.keyword("not");
+            if !self.keyword("null") {
+                return Err(anyhow::anyhow!("Expected 'null' after 'is'"));
+            }
+            return Ok(Expr::IsNull { expr: Box::new(left), negated });
+        }
+
+        let op = match self.symbol(&["=", "==", "!=", "<>", "<", "<=", ">", ">="]) {
+            Some("=") | Some("==") => BinaryOp::Eq,
+            Some("!=") | Some("<>") => BinaryOp::NotEq,
+            Some("<") => BinaryOp::Lt,
+            Some("<=") => BinaryOp::LtEq,
+            Some(">") => BinaryOp::Gt,
+            Some(">=") => BinaryOp::GtEq,
+            _ => return Ok(left),
+        };
+        Ok(binary(op, left, self.additive()?))
+    }
+
+    fn additive(&mut self) -> Result<Expr> {
+        let mut expr = self.multiplicative()?;
+        while let Some(symbol) = self.symbol(&["+", "-"]) {
+            let op = if symbol == "+" { BinaryOp::Add } else { BinaryOp::Sub };
+            expr = binary(op, expr, self.multiplicative()?);
+        }
+        Ok(expr)
+    }
+
+    fn multiplicative(&mut self) -> Result<Expr> {
+        let mut expr = self.unary()?;
+        while let Some(symbol) = self.symbol(&["*", "/", "%"]) {
+            let op = match symbol {
+                "*" => BinaryOp::Mul,
+                "/" => BinaryOp::Div,
+                _ => BinaryOp::Rem,
+            };
+            expr = binary(op, expr, self.unary()?);
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        if self.symbol(&["-"]).is_some() {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr> {
+        match self.next() {
+            Some(Token::Int(value)) => Ok(Expr::Int(value)),
+            Some(Token::Float(value)) => Ok(Expr::Float(value)),
+            Some(Token::Str(value)) => Ok(Expr::Str(value)),
+            Some(Token::Column(name)) => Ok(Expr::Column(name)),
+            Some(Token::Symbol("(")) => {
+                let expr = self.or()?;
+                self.expect(")")?;
+                Ok(expr)
+            }
+            Some(Token::Ident(ident)) => {
+                if self.symbol(&["("]).is_some() {
+                    return self.call(ident.to_lowercase());
+                }
+                match ident.to_lowercase().as_str() {
+                    "true" => Ok(Expr::Bool(true)),
+                    "false" => Ok(Expr::Bool(false)),
+                    "null" => Ok(Expr::Null),
+                    _ => Ok(Expr::Column(ident)),
+                }
+            }
+            Some(token) => Err(anyhow::anyhow!("Unexpected {:?} in expression", token)),
+            None => Err(anyhow::anyhow!("Expression ends unexpectedly")),
+        }
+    }
+
+    fn call(&mut self, function: String) -> Result<Expr> {
+        if !FUNCTIONS.contains(&function.as_str()) {
+            return Err(anyhow::anyhow!("Unknown function '{}'", function));
+        }
+
+        let mut args = Vec::new();
+        if self.symbol(&[")"]).is_none() {
+            loop {
+                args.push(self.or()?);
+                if self.symbol(&[","]).is_none() {
+                    break;
+                }
+            }
+            self.expect(")")?;
+        }
+
+        let arity_ok = match function.as_str() {
+            "coalesce" | "concat" => !args.is_empty(),
+            "contains" => args.len() == 2,
+            _ => args.len() == 1,
+        };
+        if !arity_ok {
+            return Err(anyhow::anyhow!("Wrong number of arguments for {}()", function));
+        }
+
+        Ok(Expr::Call { function, args })
+    }
+}
+
+fn binary(op: BinaryOp, left: Expr, right: Expr) -> Expr {
+    Expr::Binary { op, left: Box::new(left), right: Box::new(right) }
+}
+
+pub fn parse(source: &str) -> Result<Expr> {
+    let mut parser = Parser { tokens: tokenize(source)?, position: 0 };
+    let expr = parser.or()?;
+    if let Some(token) = parser.peek() {
+        return Err(anyhow::anyhow!("Unexpected {:?} after the end of the expression", token));
+    }
+    Ok(expr)
+}
+
+fn is_integer(data_type: &DataType) -> bool {
+    data_type.is_integer()
+}
+
+fn is_numeric(data_type: &DataType) -> bool {
+    data_type.is_numeric() || *data_type == DataType::Null
+}
+
+/// Common type two operands are cast to before arithmetic or comparison.
+fn common_type(left: &DataType, right: &DataType) -> Result<DataType> {
+    if left == right && *left != DataType::Null {
+        return Ok(left.clone());
+    }
+    match (left, right) {
+        (l, r) if is_integer(l) && is_integer(r) => Ok(DataType::Int64),
+        (l, r) if is_numeric(l) && is_numeric(r) => Ok(DataType::Float64),
+        (DataType::Null, DataType::Null) => Ok(DataType::Null),
+        // Compare against the column's type: date >= '2024-01-01'
+        (DataType::Null, other) | (other, DataType::Null) => Ok(other.clone()),
+        (other, DataType::Utf8) | (DataType::Utf8, other) => Ok(other.clone()),
+        (l, r) => Err(anyhow::anyhow!("Cannot combine {} and {}", l, r)),
+    }
+}
+
+fn as_boolean(array: &ArrayRef) -> Result<BooleanArray> {
+    let array = cast(array, &DataType::Boolean)
+        .map_err(|_| anyhow::anyhow!("Expected a true/false value, got {}", array.data_type()))?;
+    Ok(array.as_boolean().clone())
+}
+
+fn as_strings(array: &ArrayRef) -> Result<StringArray> {
+    Ok(cast(array, &DataType::Utf8)?.as_string::<i32>().clone())
+}
+
+fn map_strings(array: &ArrayRef, f: impl Fn(&str) -> String) -> Result<ArrayRef> {
+    let strings = as_strings(array)?;
+    Ok(Arc::new(strings.iter().map(|value| value.map(&f)).collect::<StringArray>()))
+}
+
+/// Evaluates `expr` against every row of `batch`.
+pub fn evaluate(expr: &Expr, batch: &RecordBatch) -> Result<ArrayRef> {
+    let rows = batch.num_rows();
+
+    Ok(match expr {
+        Expr::Column(name) => batch
+            .column_by_name(name)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Unknown column '{}'", name))?,
+        Expr::Int(value) => Arc::new(Int64Array::from(vec![*value; rows])),
+        Expr::Float(value) => Arc::new(Float64Array::from(vec![*value; rows])),
+        Expr::Str(value) => Arc::new(StringArray::from(vec![value.as_str(); rows])),
+        Expr::Bool(value) => Arc::new(BooleanArray::from(vec![*value; rows])),
+        Expr::Null => new_null_array(&DataType::Null, rows),
+        Expr::Neg(inner) => numeric::neg(&evaluate(inner, batch)?)?,
+        Expr::Not(inner) => Arc::new(boolean::not(&as_boolean(&evaluate(inner, batch)?)?)?),
+        Expr::IsNull { expr, negated } => {
+            let value = evaluate(expr, batch)?;
+            Arc::new(if *negated { is_not_null(&value)? } else { is_null(&value)? })
+        }
+        Expr::Binary { op, left, right } => binary_op(*op, evaluate(left, batch)?, evaluate(right, batch)?)?,
+        Expr::Call { function, args } => {
+            let args = args.iter().map(|arg| evaluate(arg, batch)).collect::<Result<Vec<_>>>()?;
+            call(function, args, rows)?
+        }
+    })
+}
+
+fn binary_op(op: BinaryOp, left: ArrayRef, right: ArrayRef) -> Result<ArrayRef> {
+    if let BinaryOp::And | BinaryOp::Or = op {
+        let (left, right) = (as_boolean(&left)?, as_boolean(&right)?);
+        let result = match op {
+            BinaryOp::And => boolean::and_kleene(&left, &right)?,
+            _ => boolean::or_kleene(&left, &right)?,
+        };
+        return Ok(Arc::new(result));
+    }
+
+    let mut target = common_type(left.data_type(), right.data_type())?;
+    let arithmetic = matches!(op, BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem);
+    if arithmetic {
+        if !is_numeric(&target) {
+            return Err(anyhow::anyhow!("Arithmetic needs numbers, got {} and {}", left.data_type(), right.data_type()));
+        }
+        // Integer division would silently truncate
+        if op == BinaryOp::Div || target == DataType::Null {
+            target = DataType::Float64;
+        }
+    }
+    let (left, right) = (cast(&left, &target)?, cast(&right, &target)?);
+
+    Ok(match op {
+        BinaryOp::Add => numeric::add(&left, &right)?,
+        BinaryOp::Sub => numeric::sub(&left, &right)?,
+        BinaryOp::Mul => numeric::mul(&left, &right)?,
+        BinaryOp::Div => numeric::div(&left, &right)?,
+        BinaryOp::Rem => numeric::rem(&left, &right)?,
+        BinaryOp::Eq => Arc::new(cmp::eq(&left, &right)?),
+        BinaryOp::NotEq => Arc::new(cmp::neq(&left, &right)?),
+        BinaryOp::Lt => Arc::new(cmp::lt(&left, &right)?),
+        BinaryOp::LtEq => Arc::new(cmp::lt_eq(&left, &right)?),
+        BinaryOp::Gt => Arc::new(cmp::gt(&left, &right)?),
+        BinaryOp::GtEq => Arc::new(cmp::gt_eq(&left, &right)?),
+        BinaryOp::And | BinaryOp::Or => unreachable!("handled above"),
+    })
+}
+
+fn call(function: &str, args: Vec<ArrayRef>, rows: usize) -> Result<ArrayRef> {
+    let first = &args[0];
+
+    Ok(match function {
+        "upper" => map_strings(first, str::to_uppercase)?,
+        "lower" => map_strings(first, str::to_lowercase)?,
+        "trim" => map_strings(first, |value| value.trim().to_string())?,
+        "length" => {
+            let strings = as_strings(first)?;
+            Arc::new(strings.iter().map(|value| value.map(|v| v.chars().count() as i64)).collect::<Int64Array>())
+        }
+        "abs" if is_integer(first.data_type()) => {
+            let values = cast(first, &DataType::Int64)?;
+            Arc::new(values.as_primitive::<Int64Type>().unary::<_, Int64Type>(i64::wrapping_abs))
+        }
+        "abs" | "round" => {
+            let values = cast(first, &DataType::Float64)?;
+            let f = if function == "abs" { f64::abs } else { f64::round };
+            Arc::new(values.as_primitive::<Float64Type>().unary::<_, Float64Type>(f))
+        }
+        "coalesce" => {
+            let target = args
+                .iter()
+                .try_fold(DataType::Null, |target, arg| common_type(&target, arg.data_type()))?;
+            let mut result = cast(first, &target)?;
+            for arg in &args[1..] {
+                let fallback = cast(arg, &target)?;
+                result = zip::zip(&is_not_null(&result)?, &result, &fallback)?;
+            }
+            result
+        }
+        "concat" => {
+            // Like most SQL dialects' concat(), nulls count as empty strings
+            let strings = args.iter().map(as_strings).collect::<Result<Vec<_>>>()?;
+            let joined: StringArray = (0..rows)
+                .map(|row| {
+                    Some(strings.iter().filter(|s| !s.is_null(row)).map(|s| s.value(row)).collect::<String>())
+                })
+                .collect();
+            Arc::new(joined)
+        }
+        "contains" => {
+            let (haystack, needle) = (as_strings(first)?, as_strings(&args[1])?);
+            Arc::new(arrow::compute::kernels::comparison::contains(&haystack, &needle)?)
+        }
+        other => return Err(anyhow::anyhow!("Unknown function '{}'", other)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::{Field, Schema};
+
+    #[test]
+    fn test_parse_and_evaluate() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("price", DataType::Float64, true),
+            Field::new("qty", DataType::Int64, true),
+            Field::new("country name", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Float64Array::from(vec![Some(2.5), Some(10.0), None])),
+                Arc::new(Int64Array::from(vec![4, 1, 3])),
+                Arc::new(StringArray::from(vec![Some(" us "), Some("fr"), None])),
+            ],
+        )
+        .unwrap();
+
+        let total = evaluate(&parse("price * qty + 1").unwrap(), &batch).unwrap();
+        assert_eq!(total.as_primitive::<Float64Type>().value(0), 11.0);
+        assert!(total.is_null(2));
+
+        let filter = parse("upper(trim(\"country name\")) = 'US' or price is null").unwrap();
+        let mask = evaluate(&filter, &batch).unwrap();
+        assert_eq!(mask.as_boolean().iter().collect::<Vec<_>>(), vec![Some(true), Some(false), Some(true)]);
+
+        let label = evaluate(&parse("concat(qty, '-', coalesce(\"country name\", 'n/a'))").unwrap(), &batch).unwrap();
+        assert_eq!(label.as_string::<i32>().value(2), "3-n/a");
+
+        assert!(parse("price +").is_err());
+        assert!(parse("explode(price)").is_err());
+        assert!(evaluate(&parse("missing > 1").unwrap(), &batch).is_err());
+    }
+}
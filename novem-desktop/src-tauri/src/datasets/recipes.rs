@@ -0,0 +1,224 @@
+use anyhow::{Context, Result};
+use arrow::array::{ArrayRef, AsArray};
+use arrow::compute::kernels::cast::{cast_with_options, CastOptions};
+use arrow::compute::filter_record_batch;
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use arrow::record_batch::RecordBatch;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+
+use super::expressions::{self, Expr};
+use super::{open_batches, read_schema, write_parquet, DatasetFormat};
+
+/// One cleanup step of a recipe, applied to every row batch in order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum RecipeStep {
+    /// Keeps rows where the expression is true
+    Filter { expression: String },
+    /// Keeps these columns, in this order
+    Select { columns: Vec<String> },
+    Rename { from: String, to: String },
+    /// Values that can't be converted become null
+    Cast { column: String, to: CastType },
+    /// Adds a column, or replaces one with the same name
+    Derive { name: String, expression: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CastType {
+    Integer,
+    Float,
+    String,
+    Boolean,
+    Date,
+    Timestamp,
+}
+
+impl CastType {
+    fn data_type(self) -> DataType {
+        match self {
+            CastType::Integer => DataType::Int64,
+            CastType::Float => DataType::Float64,
+            CastType::String => DataType::Utf8,
+            CastType::Boolean => DataType::Boolean,
+            CastType::Date => DataType::Date32,
+            CastType::Timestamp => DataType::Timestamp(TimeUnit::Microsecond, None),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecipeOutcome {
+    pub source_rows: u64,
+    pub output_rows: u64,
+    pub columns: Vec<String>,
+}
+
+/// A recipe with its expressions parsed once up front.
+enum CompiledStep {
+    Filter(Expr),
+    Select(Vec<String>),
+    Rename { from: String, to: String },
+    Cast { column: String, to: DataType },
+    Derive { name: String, expr: Expr },
+}
+
+fn compile(steps: &[RecipeStep]) -> Result<Vec<CompiledStep>> {
+    steps
+        .iter()
+        .map(|step| {
+            let compiled = match step {
+                RecipeStep::Filter { expression } => CompiledStep::Filter(expressions::parse(expression)?),
+                RecipeStep::Select { columns } => CompiledStep::Select(columns.clone()),
+                RecipeStep::Rename { from, to } => CompiledStep::Rename { from: from.clone(), to: to.clone() },
+                RecipeStep::Cast { column, to } => CompiledStep::Cast { column: column.clone(), to: to.data_type() },
+                RecipeStep::Derive { name, expression } => CompiledStep::Derive {
+                    name: name.clone(),
+                    expr: expressions::parse(expression)?,
+                },
+            };
+            Ok(compiled)
+        })
+        .collect::<Result<Vec<_>>>()
+        .context("Invalid recipe")
+}
+
+fn column_index(batch: &RecordBatch, name: &str) -> Result<usize> {
+    batch
+        .schema()
+        .index_of(name)
+        .map_err(|_| anyhow::anyhow!("Unknown column '{}'", name))
+}
+
+/// Replaces the column at `index`, or appends one when `index` is `None`.
+fn with_column(batch: &RecordBatch, index: Option<usize>, field: Field, column: ArrayRef) -> Result<RecordBatch> {
+    let mut fields: Vec<Field> = batch.schema().fields().iter().map(|f| f.as_ref().clone()).collect();
+    let mut columns = batch.columns().to_vec();
+    match index {
+        Some(index) => {
+            fields[index] = field;
+            columns[index] = column;
+        }
+        None => {
+            fields.push(field);
+            columns.push(column);
+        }
+    }
+    Ok(RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)?)
+}
+
+fn apply_step(step: &CompiledStep, batch: RecordBatch) -> Result<RecordBatch> {
+    match step {
+        CompiledStep::Filter(expr) => {
+            let mask = expressions::evaluate(expr, &batch)?;
+            let mask = arrow::compute::cast(&mask, &DataType::Boolean)
+                .map_err(|_| anyhow::anyhow!("Filter expression must be true or false, got {}", mask.data_type()))?;
+            // Rows where the condition is null are dropped, as in SQL
+            Ok(filter_record_batch(&batch, mask.as_boolean())?)
+        }
+        CompiledStep::Select(columns) => {
+            let indices = columns
+                .iter()
+                .map(|name| column_index(&batch, name))
+                .collect::<Result<Vec<_>>>()?;
+            Ok(batch.project(&indices)?)
+        }
+        CompiledStep::Rename { from, to } => {
+            let index = column_index(&batch, from)?;
+            if from != to && batch.schema().index_of(to).is_ok() {
+                return Err(anyhow::anyhow!("Column '{}' already exists", to));
+            }
+            let field = batch.schema().field(index).clone().with_name(to);
+            let column = batch.column(index).clone();
+            with_column(&batch, Some(index), field, column)
+        }
+        CompiledStep::Cast { column, to } => {
+            let index = column_index(&batch, column)?;
+            let options = CastOptions { safe: true, ..Default::default() };
+            let converted = cast_with_options(batch.column(index), to, &options)
+                .context(format!("Column '{}' cannot be converted to {}", column, to))?;
+            with_column(&batch, Some(index), Field::new(column, to.clone(), true), converted)
+        }
+        CompiledStep::Derive { name, expr } => {
+            let column = expressions::evaluate(expr, &batch)?;
+            let field = Field::new(name, column.data_type().clone(), true);
+            with_column(&batch, batch.schema().index_of(name).ok(), field, column)
+        }
+    }
+}
+
+fn apply_steps(steps: &[CompiledStep], batch: RecordBatch) -> Result<RecordBatch> {
+    steps.iter().enumerate().try_fold(batch, |batch, (index, step)| {
+        apply_step(step, batch).context(format!("Recipe step {} failed", index + 1))
+    })
+}
+
+/// Schema a recipe produces from `schema`, found by running it on no rows.
+/// Fails the same way the real run would for unknown columns or bad types.
+pub fn output_schema(schema: &SchemaRef, steps: &[RecipeStep]) -> Result<SchemaRef> {
+    let compiled = compile(steps)?;
+    Ok(apply_steps(&compiled, RecordBatch::new_empty(schema.clone()))?.schema())
+}
+
+/// Streams `source` through the recipe into a new Parquet file at `dest`.
+pub fn apply_file(source: &Path, format: DatasetFormat, dest: &Path, steps: &[RecipeStep]) -> Result<RecipeOutcome> {
+    let compiled = compile(steps)?;
+    let schema = output_schema(&read_schema(source, format)?, steps)?;
+
+    let mut source_rows = 0u64;
+    let batches = open_batches(source, format, None)?.map(|batch| {
+        let batch = batch?;
+        source_rows += batch.num_rows() as u64;
+        let batch = apply_steps(&compiled, batch)?;
+        // Per-batch schemas only differ in metadata the writer doesn't need
+        Ok(RecordBatch::try_new(schema.clone(), batch.columns().to_vec())?)
+    });
+    let output_rows = write_parquet(dest, schema.clone(), batches)?;
+
+    Ok(RecipeOutcome {
+        source_rows,
+        output_rows,
+        columns: schema.fields().iter().map(|field| field.name().clone()).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_recipe() {
+        let source = std::env::temp_dir().join("test_novem_recipe_source.csv");
+        let dest = std::env::temp_dir().join("test_novem_recipe.parquet");
+        std::fs::write(&source, "id,price,qty,country\n1,2.5,4, us\n2,10,1,fr\n3,,3,us\n4,1.5,x,us\n").unwrap();
+
+        let steps: Vec<RecipeStep> = serde_json::from_value(serde_json::json!([
+            { "op": "cast", "column": "qty", "to": "integer" },
+            { "op": "derive", "name": "country", "expression": "upper(trim(country))" },
+            { "op": "filter", "expression": "country = 'US' and qty is not null" },
+            { "op": "derive", "name": "total", "expression": "coalesce(price, 0) * qty" },
+            { "op": "rename", "from": "id", "to": "order_id" },
+            { "op": "select", "columns": ["order_id", "total"] }
+        ]))
+        .unwrap();
+
+        let outcome = apply_file(&source, DatasetFormat::Csv, &dest, &steps).unwrap();
+        assert_eq!(outcome.source_rows, 4);
+        assert_eq!(outcome.output_rows, 2);
+        assert_eq!(outcome.columns, vec!["order_id", "total"]);
+
+        let batch = open_batches(&dest, DatasetFormat::Parquet, None).unwrap().next().unwrap().unwrap();
+        let totals = batch.column(1).as_primitive::<arrow::datatypes::Float64Type>();
+        assert_eq!(totals.values().to_vec(), vec![10.0, 0.0]);
+
+        let schema = read_schema(&source, DatasetFormat::Csv).unwrap();
+        let bad = vec![RecipeStep::Select { columns: vec!["missing".to_string()] }];
+        assert!(output_schema(&schema, &bad).is_err());
+
+        std::fs::remove_file(source).ok();
+        std::fs::remove_file(dest).ok();
+    }
+}
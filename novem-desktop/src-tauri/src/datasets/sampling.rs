@@ -0,0 +1,270 @@
+use anyhow::Result;
+use arrow::array::{Array, StringArray, UInt32Array};
+use arrow::compute::{cast, take_record_batch};
+use arrow::datatypes::DataType;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::{open_batches, count_rows, read_schema, write_parquet, DatasetFormat};
+
+const NULL_STRATUM: &str = "<null>";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SampleMethod {
+    /// Uniform random rows (reservoir sampling)
+    Reservoir,
+    /// Proportional allocation across the values of one column
+    Stratified,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SampleSpec {
+    pub method: SampleMethod,
+    pub size: Option<u64>,
+    pub fraction: Option<f64>,
+    pub seed: Option<u64>,
+    pub stratify_by: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SampleOutcome {
+    pub source_rows: u64,
+    pub sample_rows: u64,
+    pub seed: u64,
+}
+
+/// Algorithm R over row indices: keeps a uniform sample of `capacity`
+/// indices from a stream of unknown length.
+struct Reservoir {
+    capacity: usize,
+    seen: u64,
+    indices: Vec<u64>,
+}
+
+impl Reservoir {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            seen: 0,
+            indices: Vec::with_capacity(capacity),
+        }
+    }
+
+    fn offer(&mut self, index: u64, rng: &mut StdRng) {
+        if self.indices.len() < self.capacity {
+            self.indices.push(index);
+        } else if self.capacity > 0 {
+            let slot = rng.gen_range(0..=self.seen);
+            if (slot as usize) < self.capacity {
+                self.indices[slot as usize] = index;
+            }
+        }
+        self.seen += 1;
+    }
+}
+
+fn target_size(spec: &SampleSpec, total_rows: u64) -> Result<u64> {
+    match (spec.size, spec.fraction) {
+        (Some(size), None) => Ok(size.min(total_rows)),
+        (None, Some(fraction)) if (0.0..=1.0).contains(&fraction) => {
+            Ok(((total_rows as f64) * fraction).round() as u64)
+        }
+        (None, Some(fraction)) => Err(anyhow::anyhow!("Sample fraction must be between 0 and 1, got {}", fraction)),
+        _ => Err(anyhow::anyhow!("Specify exactly one of sample size or fraction")),
+    }
+}
+
+/// Splits `target` rows across strata proportionally to their size, handing
+/// leftover rows to the strata with the largest remainders.
+fn allocate(counts: &HashMap<String, u64>, total_rows: u64, target: u64) -> HashMap<String, usize> {
+    if total_rows == 0 {
+        return HashMap::new();
+    }
+
+    let mut shares: Vec<(&String, u64, f64)> = counts
+        .iter()
+        .map(|(key, count)| {
+            let exact = target as f64 * (*count as f64) / total_rows as f64;
+            (key, exact.floor() as u64, exact - exact.floor())
+        })
+        .collect();
+
+    let mut remaining = target.saturating_sub(shares.iter().map(|(_, base, _)| base).sum());
+    shares.sort_by(|a, b| b.2.total_cmp(&a.2).then_with(|| a.0.cmp(b.0)));
+
+    let mut allocation = HashMap::new();
+    for (key, base, _) in shares {
+        let extra = if remaining > 0 && base < counts[key] { remaining -= 1; 1 } else { 0 };
+        allocation.insert(key.clone(), (base + extra) as usize);
+    }
+    allocation
+}
+
+fn stratum_keys(column: &dyn Array) -> Result<Vec<String>> {
+    let strings = cast(column, &DataType::Utf8)?;
+    let strings = strings
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .ok_or_else(|| anyhow::anyhow!("Failed to read stratification column"))?;
+
+    Ok((0..strings.len())
+        .map(|i| if strings.is_null(i) { NULL_STRATUM.to_string() } else { strings.value(i).to_string() })
+        .collect())
+}
+
+fn select_uniform(source: &Path, format: DatasetFormat, target: u64, rng: &mut StdRng) -> Result<Vec<u64>> {
+    let mut reservoir = Reservoir::new(target as usize);
+    let mut index = 0u64;
+
+    // Only row positions matter here, so read the narrowest projection
+    for batch in open_batches(source, format, Some(vec![0]))? {
+        for _ in 0..batch?.num_rows() {
+            reservoir.offer(index, rng);
+            index += 1;
+        }
+    }
+
+    Ok(reservoir.indices)
+}
+
+fn select_stratified(
+    source: &Path,
+    format: DatasetFormat,
+    column: usize,
+    total_rows: u64,
+    target: u64,
+    rng: &mut StdRng,
+) -> Result<Vec<u64>> {
+    let mut counts: HashMap<String, u64> = HashMap::new();
+    for batch in open_batches(source, format, Some(vec![column]))? {
+        for key in stratum_keys(batch?.column(0))? {
+            *counts.entry(key).or_insert(0) += 1;
+        }
+    }
+
+    let mut reservoirs: HashMap<String, Reservoir> = allocate(&counts, total_rows, target)
+        .into_iter()
+        .map(|(key, capacity)| (key, Reservoir::new(capacity)))
+        .collect();
+
+    let mut index = 0u64;
+    for batch in open_batches(source, format, Some(vec![column]))? {
+        for key in stratum_keys(batch?.column(0))? {
+            if let Some(reservoir) = reservoirs.get_mut(&key) {
+                reservoir.offer(index, rng);
+            }
+            index += 1;
+        }
+    }
+
+    Ok(reservoirs.into_values().flat_map(|reservoir| reservoir.indices).collect())
+}
+
+/// Samples `source` into a new Parquet file at `dest`. Rows keep their
+/// original order; the seed is returned so the sample can be reproduced.
+pub fn sample_file(source: &Path, format: DatasetFormat, dest: &Path, spec: &SampleSpec) -> Result<SampleOutcome> {
+    let schema = read_schema(source, format)?;
+    let total_rows = count_rows(source, format)?;
+    let target = target_size(spec, total_rows)?;
+
+    let seed = spec.seed.unwrap_or_else(rand::random);
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut selected = match spec.method {
+        SampleMethod::Reservoir => select_uniform(source, format, target, &mut rng)?,
+        SampleMethod::Stratified => {
+            let column_name = spec
+                .stratify_by
+                .as_deref()
+                .ok_or_else(|| anyhow::anyhow!("Stratified sampling requires a stratify_by column"))?;
+            let column = schema.index_of(column_name)?;
+            select_stratified(source, format, column, total_rows, target, &mut rng)?
+        }
+    };
+    selected.sort_unstable();
+
+    let mut offset = 0u64;
+    let mut cursor = 0usize;
+    let batches = open_batches(source, format, None)?.filter_map(|batch| {
+        let batch = match batch {
+            Ok(batch) => batch,
+            Err(e) => return Some(Err(e.into())),
+        };
+
+        let end = offset + batch.num_rows() as u64;
+        let mut local = Vec::new();
+        while cursor < selected.len() && selected[cursor] < end {
+            local.push((selected[cursor] - offset) as u32);
+            cursor += 1;
+        }
+        offset = end;
+
+        if local.is_empty() {
+            return None;
+        }
+        Some(take_record_batch(&batch, &UInt32Array::from(local)).map_err(Into::into))
+    });
+
+    let sample_rows = write_parquet(dest, schema.clone(), batches)?;
+
+    Ok(SampleOutcome {
+        source_rows: total_rows,
+        sample_rows,
+        seed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_csv(name: &str, rows: usize) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(name);
+        let mut content = String::from("id,group\n");
+        for i in 0..rows {
+            content.push_str(&format!("{},{}\n", i, if i % 4 == 0 { "a" } else { "b" }));
+        }
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_samples_are_reproducible_and_stratified() {
+        let source = write_csv("test_novem_sample_source.csv", 1000);
+        let dest = std::env::temp_dir().join("test_novem_sample.parquet");
+
+        let spec = SampleSpec {
+            method: SampleMethod::Stratified,
+            size: Some(100),
+            fraction: None,
+            seed: Some(42),
+            stratify_by: Some("group".to_string()),
+        };
+
+        let first = sample_file(&source, DatasetFormat::Csv, &dest, &spec).unwrap();
+        assert_eq!(first.source_rows, 1000);
+        assert_eq!(first.sample_rows, 100);
+
+        let mut groups = HashMap::new();
+        for batch in open_batches(&dest, DatasetFormat::Parquet, Some(vec![1])).unwrap() {
+            for key in stratum_keys(batch.unwrap().column(0)).unwrap() {
+                *groups.entry(key).or_insert(0) += 1;
+            }
+        }
+        assert_eq!(groups["a"], 25);
+        assert_eq!(groups["b"], 75);
+
+        let uniform = SampleSpec { method: SampleMethod::Reservoir, stratify_by: None, ..spec };
+        let a = sample_file(&source, DatasetFormat::Csv, &dest, &uniform).unwrap();
+        let b = sample_file(&source, DatasetFormat::Csv, &dest, &uniform).unwrap();
+        assert_eq!(a.sample_rows, 100);
+        assert_eq!(a.seed, b.seed);
+
+        std::fs::remove_file(source).ok();
+        std::fs::remove_file(dest).ok();
+    }
+}
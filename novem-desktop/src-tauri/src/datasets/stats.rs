@@ -0,0 +1,436 @@
+use anyhow::Result;
+use arrow::array::{Array, Float64Array, StringArray};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Schema};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::{open_batches, DatasetFormat};
+use crate::database::{ColumnStatsRecord, DatasetColumn};
+
+/// HyperLogLog precision: 2^12 registers, ~1.6% standard error.
+const HLL_PRECISION: u32 = 12;
+const HLL_REGISTERS: usize = 1 << HLL_PRECISION;
+
+/// t-digest compression; higher keeps more centroids and tighter quantiles.
+const TDIGEST_COMPRESSION: f64 = 100.0;
+
+const REPORTED_QUANTILES: &[(&str, f64)] = &[
+    ("p1", 0.01),
+    ("p5", 0.05),
+    ("p25", 0.25),
+    ("p50", 0.5),
+    ("p75", 0.75),
+    ("p95", 0.95),
+    ("p99", 0.99),
+];
+
+/// 64-bit FNV-1a followed by a SplitMix64 finalizer. Stable across runs and
+/// Rust versions, which matters because registers are persisted.
+fn hash_bytes(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+
+    hash ^= hash >> 30;
+    hash = hash.wrapping_mul(0xbf58476d1ce4e5b9);
+    hash ^= hash >> 27;
+    hash = hash.wrapping_mul(0x94d049bb133111eb);
+    hash ^ (hash >> 31)
+}
+
+#[derive(Debug, Clone)]
+pub struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self {
+            registers: vec![0; HLL_REGISTERS],
+        }
+    }
+}
+
+impl HyperLogLog {
+    fn from_registers(registers: Vec<u8>) -> Self {
+        if registers.len() == HLL_REGISTERS {
+            Self { registers }
+        } else {
+            Self::default()
+        }
+    }
+
+    fn insert(&mut self, hash: u64) {
+        let index = (hash >> (64 - HLL_PRECISION)) as usize;
+        let rank = ((hash << HLL_PRECISION) | (1 << (HLL_PRECISION - 1))).leading_zeros() as u8 + 1;
+        if rank > self.registers[index] {
+            self.registers[index] = rank;
+        }
+    }
+
+    fn estimate(&self) -> u64 {
+        let m = HLL_REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self.registers.iter().map(|r| 2f64.powi(-(*r as i32))).sum();
+        let raw = alpha * m * m / sum;
+
+        let zeros = self.registers.iter().filter(|r| **r == 0).count();
+        if raw <= 2.5 * m && zeros > 0 {
+            // Linear counting is more accurate for small cardinalities
+            (m * (m / zeros as f64).ln()).round() as u64
+        } else {
+            raw.round() as u64
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct Centroid {
+    mean: f64,
+    weight: f64,
+}
+
+/// Merging t-digest (Dunning) for streaming quantile estimates.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TDigest {
+    centroids: Vec<Centroid>,
+    total_weight: f64,
+    min: f64,
+    max: f64,
+    #[serde(skip)]
+    buffer: Vec<f64>,
+}
+
+impl TDigest {
+    fn k(q: f64) -> f64 {
+        TDIGEST_COMPRESSION / (2.0 * std::f64::consts::PI) * (2.0 * q - 1.0).asin()
+    }
+
+    fn k_inverse(k: f64) -> f64 {
+        ((k * 2.0 * std::f64::consts::PI / TDIGEST_COMPRESSION).sin() + 1.0) / 2.0
+    }
+
+    fn add(&mut self, value: f64) {
+        self.buffer.push(value);
+    }
+
+    /// Folds buffered values into the centroid list.
+    fn compress(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+
+        let mut points: Vec<Centroid> = self.centroids.drain(..).collect();
+        for value in self.buffer.drain(..) {
+            if points.is_empty() && self.total_weight == 0.0 {
+                self.min = value;
+                self.max = value;
+            }
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+            self.total_weight += 1.0;
+            points.push(Centroid { mean: value, weight: 1.0 });
+        }
+        points.sort_by(|a, b| a.mean.total_cmp(&b.mean));
+
+        let total = self.total_weight;
+        let mut merged: Vec<Centroid> = Vec::new();
+        let mut weight_so_far = 0.0;
+        let mut limit = total * Self::k_inverse(Self::k(0.0) + 1.0);
+
+        let mut points = points.into_iter();
+        let mut current = match points.next() {
+            Some(point) => point,
+            None => return,
+        };
+
+        for point in points {
+            if weight_so_far + current.weight + point.weight <= limit {
+                let weight = current.weight + point.weight;
+                current.mean += (point.mean - current.mean) * point.weight / weight;
+                current.weight = weight;
+            } else {
+                weight_so_far += current.weight;
+                limit = total * Self::k_inverse(Self::k((weight_so_far / total).min(1.0)) + 1.0);
+                merged.push(current);
+                current = point;
+            }
+        }
+        merged.push(current);
+
+        self.centroids = merged;
+    }
+
+    fn quantile(&self, q: f64) -> Option<f64> {
+        let first = self.centroids.first()?;
+        let last = self.centroids.last()?;
+        if self.centroids.len() == 1 {
+            return Some(first.mean);
+        }
+
+        let target = q.clamp(0.0, 1.0) * self.total_weight;
+
+        // Tails interpolate towards the exact min/max
+        let first_center = first.weight / 2.0;
+        if target <= first_center {
+            return Some(self.min + (first.mean - self.min) * (target / first_center));
+        }
+        let last_center = self.total_weight - last.weight / 2.0;
+        if target >= last_center {
+            let span = self.total_weight - last_center;
+            return Some(last.mean + (self.max - last.mean) * ((target - last_center) / span));
+        }
+
+        let mut center = first_center;
+        for pair in self.centroids.windows(2) {
+            let next_center = center + (pair[0].weight + pair[1].weight) / 2.0;
+            if target <= next_center {
+                let fraction = (target - center) / (next_center - center);
+                return Some(pair[0].mean + (pair[1].mean - pair[0].mean) * fraction);
+            }
+            center = next_center;
+        }
+
+        Some(last.mean)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum StatValue {
+    Number(f64),
+    Text(String),
+}
+
+/// Mergeable summary of one column, updated batch by batch as rows are
+/// appended so profiles never need a full rescan.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnSketch {
+    pub name: String,
+    pub data_type: String,
+    pub numeric: bool,
+    pub count: u64,
+    pub null_count: u64,
+    pub sum: f64,
+    pub min: Option<StatValue>,
+    pub max: Option<StatValue>,
+    digest: TDigest,
+    #[serde(skip)]
+    hll: HyperLogLog,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnStats {
+    pub column_name: String,
+    pub data_type: String,
+    pub count: u64,
+    pub null_count: u64,
+    pub sum: Option<f64>,
+    pub mean: Option<f64>,
+    pub min: Option<StatValue>,
+    pub max: Option<StatValue>,
+    pub distinct_estimate: u64,
+    pub quantiles: Option<BTreeMap<String, f64>>,
+}
+
+impl ColumnSketch {
+    pub fn new(name: &str, data_type: &DataType) -> Self {
+        Self {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            numeric: data_type.is_numeric(),
+            count: 0,
+            null_count: 0,
+            sum: 0.0,
+            min: None,
+            max: None,
+            digest: TDigest::default(),
+            hll: HyperLogLog::default(),
+        }
+    }
+
+    pub fn update(&mut self, column: &dyn Array) -> Result<()> {
+        if self.numeric {
+            let values = cast(column, &DataType::Float64)?;
+            let values = values
+                .as_any()
+                .downcast_ref::<Float64Array>()
+                .ok_or_else(|| anyhow::anyhow!("Failed to read numeric column '{}'", self.name))?;
+
+            for value in values.iter() {
+                let value = match value {
+                    Some(value) if !value.is_nan() => value,
+                    _ => {
+                        self.null_count += 1;
+                        continue;
+                    }
+                };
+
+                self.count += 1;
+                self.sum += value;
+                if !matches!(self.min, Some(StatValue::Number(min)) if min <= value) {
+                    self.min = Some(StatValue::Number(value));
+                }
+                if !matches!(self.max, Some(StatValue::Number(max)) if max >= value) {
+                    self.max = Some(StatValue::Number(value));
+                }
+                // Normalise -0.0 so it counts as the same distinct value as 0.0
+                self.hll.insert(hash_bytes(&(value + 0.0).to_bits().to_le_bytes()));
+                self.digest.add(value);
+            }
+            self.digest.compress();
+        } else {
+            let values = cast(column, &DataType::Utf8)?;
+            let values = values
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .ok_or_else(|| anyhow::anyhow!("Failed to read column '{}'", self.name))?;
+
+            for value in values.iter() {
+                let value = match value {
+                    Some(value) => value,
+                    None => {
+                        self.null_count += 1;
+                        continue;
+                    }
+                };
+
+                self.count += 1;
+                if !matches!(&self.min, Some(StatValue::Text(min)) if min.as_str() <= value) {
+                    self.min = Some(StatValue::Text(value.to_string()));
+                }
+                if !matches!(&self.max, Some(StatValue::Text(max)) if max.as_str() >= value) {
+                    self.max = Some(StatValue::Text(value.to_string()));
+                }
+                self.hll.insert(hash_bytes(value.as_bytes()));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn stats(&self) -> ColumnStats {
+        let has_values = self.numeric && self.count > 0;
+
+        let quantiles = has_values.then(|| {
+            REPORTED_QUANTILES
+                .iter()
+                .filter_map(|(label, q)| self.digest.quantile(*q).map(|value| (label.to_string(), value)))
+                .collect()
+        });
+
+        ColumnStats {
+            column_name: self.name.clone(),
+            data_type: self.data_type.clone(),
+            count: self.count,
+            null_count: self.null_count,
+            sum: has_values.then_some(self.sum),
+            mean: has_values.then(|| self.sum / self.count as f64),
+            min: self.min.clone(),
+            max: self.max.clone(),
+            distinct_estimate: self.hll.estimate().min(self.count),
+            quantiles,
+        }
+    }
+
+    pub fn to_record(&self, position: usize) -> Result<ColumnStatsRecord> {
+        Ok(ColumnStatsRecord {
+            column_name: self.name.clone(),
+            position: position as i64,
+            sketch: serde_json::to_string(self)?,
+            hll: self.hll.registers.clone(),
+        })
+    }
+
+    /// The schema summary stored alongside the sketch.
+    pub fn to_column(&self, position: usize) -> DatasetColumn {
+        let render = |value: &StatValue| match value {
+            StatValue::Number(number) => number.to_string(),
+            StatValue::Text(text) => text.clone(),
+        };
+
+        DatasetColumn {
+            position: position as i64,
+            name: self.name.clone(),
+            dtype: self.data_type.clone(),
+            null_count: self.null_count as i64,
+            min_value: self.min.as_ref().map(render),
+            max_value: self.max.as_ref().map(render),
+            cardinality: self.hll.estimate().min(self.count) as i64,
+        }
+    }
+
+    pub fn from_record(record: &ColumnStatsRecord) -> Result<Self> {
+        let mut sketch: ColumnSketch = serde_json::from_str(&record.sketch)?;
+        sketch.hll = HyperLogLog::from_registers(record.hll.clone());
+        Ok(sketch)
+    }
+}
+
+pub fn new_sketches(schema: &Schema) -> Vec<ColumnSketch> {
+    schema
+        .fields()
+        .iter()
+        .map(|field| ColumnSketch::new(field.name(), field.data_type()))
+        .collect()
+}
+
+/// Full pass over a dataset file; only needed the first time a dataset is
+/// profiled; afterwards appends update the sketches in place.
+pub fn profile_file(path: &Path, format: DatasetFormat, schema: &Schema) -> Result<(Vec<ColumnSketch>, u64)> {
+    let mut sketches = new_sketches(schema);
+    let mut rows = 0u64;
+
+    for batch in open_batches(path, format, None)? {
+        let batch = batch?;
+        rows += batch.num_rows() as u64;
+        for (sketch, column) in sketches.iter_mut().zip(batch.columns()) {
+            sketch.update(column.as_ref())?;
+        }
+    }
+
+    Ok((sketches, rows))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int64Array, StringArray};
+
+    #[test]
+    fn test_incremental_sketches_match_full_pass() {
+        let mut numbers = ColumnSketch::new("n", &DataType::Int64);
+        let mut labels = ColumnSketch::new("label", &DataType::Utf8);
+
+        // Two appends of 5,000 rows each
+        for chunk in 0..2i64 {
+            let values: Vec<Option<i64>> = (0..5000).map(|i| Some(chunk * 5000 + i)).collect();
+            numbers.update(&Int64Array::from(values)).unwrap();
+
+            let text: Vec<Option<String>> = (0..5000).map(|i| if i % 10 == 0 { None } else { Some(format!("l{}", i % 300)) }).collect();
+            labels.update(&StringArray::from(text)).unwrap();
+        }
+
+        // Round-trip through the stored record between appends
+        let numbers = ColumnSketch::from_record(&numbers.to_record(0).unwrap()).unwrap();
+        let stats = numbers.stats();
+
+        assert_eq!(stats.count, 10_000);
+        assert_eq!(stats.min, Some(StatValue::Number(0.0)));
+        assert_eq!(stats.max, Some(StatValue::Number(9999.0)));
+        assert_eq!(stats.mean, Some(4999.5));
+        assert!((stats.distinct_estimate as f64 - 10_000.0).abs() < 500.0);
+
+        let median = stats.quantiles.unwrap()["p50"];
+        assert!((median - 5000.0).abs() < 100.0, "median {}", median);
+
+        let labels = labels.stats();
+        assert_eq!(labels.null_count, 1000);
+        assert!(labels.quantiles.is_none());
+        assert!((labels.distinct_estimate as i64 - 270).abs() < 15, "distinct {}", labels.distinct_estimate);
+    }
+}
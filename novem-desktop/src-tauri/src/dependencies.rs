@@ -0,0 +1,447 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+use crate::engine::Supervisor;
+
+/// Setting key recording which tool last installed the engine's packages.
+pub const DEPENDENCY_TOOL_SETTING: &str = "engine.dependency_tool";
+
+/// What installs the engine's packages. uv and Poetry resolve from the
+/// lock file into the engine's `.venv` and are much faster than pip on a
+/// first run, so they win whenever the project is set up for them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallTool {
+    Uv,
+    Poetry,
+    Pip,
+}
+
+impl InstallTool {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InstallTool::Uv => "uv",
+            InstallTool::Poetry => "poetry",
+            InstallTool::Pip => "pip",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyIssue {
+    pub name: String,
+    /// Requirement line as written in requirements.txt
+    pub required: String,
+    pub installed: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyReport {
+    pub python: String,
+    pub requirements_path: String,
+    pub checked: usize,
+    pub satisfied: usize,
+    pub missing: Vec<DependencyIssue>,
+    pub mismatched: Vec<DependencyIssue>,
+    /// Requirement specs to hand to `install_engine_dependencies` to fix everything
+    pub install_specs: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Requirement {
+    name: String,
+    specifiers: Vec<(String, String)>,
+    raw: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct InstalledPackage {
+    name: String,
+    version: String,
+}
+
+/// requirements.txt is sometimes saved as UTF-16 (e.g. `pip freeze >` in
+/// PowerShell), so honour byte-order marks.
+fn decode_text(bytes: &[u8]) -> String {
+    let utf16 = |le: bool| {
+        let units: Vec<u16> = bytes[2..]
+            .chunks_exact(2)
+            .map(|pair| if le { u16::from_le_bytes([pair[0], pair[1]]) } else { u16::from_be_bytes([pair[0], pair[1]]) })
+            .collect();
+        String::from_utf16_lossy(&units)
+    };
+
+    match bytes {
+        [0xFF, 0xFE, ..] => utf16(true),
+        [0xFE, 0xFF, ..] => utf16(false),
+        [0xEF, 0xBB, 0xBF, rest @ ..] => String::from_utf8_lossy(rest).to_string(),
+        _ => String::from_utf8_lossy(bytes).to_string(),
+    }
+}
+
+/// PEP 503 name normalisation.
+fn normalize_name(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    let mut last_separator = false;
+
+    for c in name.trim().chars() {
+        if matches!(c, '-' | '_' | '.') {
+            if !last_separator {
+                normalized.push('-');
+            }
+            last_separator = true;
+        } else {
+            normalized.push(c.to_ascii_lowercase());
+            last_separator = false;
+        }
+    }
+
+    normalized
+}
+
+fn parse_requirements(text: &str) -> Vec<Requirement> {
+    text.lines()
+        .filter_map(|line| {
+            // Drop comments and environment markers
+            let line = line.split('#').next()?.split(';').next()?.trim();
+            if line.is_empty() || line.starts_with('-') || line.contains("://") {
+                return None;
+            }
+
+            let name_end = line.find(['=', '<', '>', '!', '~', '[', ' ']).unwrap_or(line.len());
+            let name = normalize_name(&line[..name_end]);
+
+            let rest = &line[name_end..];
+            let rest = match rest.find(']') {
+                Some(end) if rest.starts_with('[') => &rest[end + 1..],
+                _ => rest,
+            };
+
+            let specifiers = rest
+                .split(',')
+                .filter_map(|spec| {
+                    let spec = spec.trim();
+                    let op_len = spec.find(|c: char| !matches!(c, '=' | '<' | '>' | '!' | '~'))?;
+                    let (op, version) = spec.split_at(op_len);
+                    (!op.is_empty()).then(|| (op.to_string(), version.trim().to_string()))
+                })
+                .collect();
+
+            Some(Requirement {
+                name,
+                specifiers,
+                raw: line.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Splits "2.9.0.post0" into [(2, ""), (9, ""), (0, ""), (0, "post")].
+fn version_segments(version: &str) -> Vec<(u64, String)> {
+    version
+        .trim()
+        .trim_start_matches('v')
+        .split(['.', '-', '+'])
+        .map(|segment| {
+            let digits: String = segment.chars().take_while(|c| c.is_ascii_digit()).collect();
+            let suffix: String = segment[digits.len()..].to_lowercase();
+            match (digits.parse(), suffix.is_empty()) {
+                (Ok(number), _) => (number, suffix),
+                (Err(_), false) => {
+                    let number = suffix.trim_start_matches(|c: char| c.is_alphabetic()).parse().unwrap_or(0);
+                    let label: String = suffix.chars().take_while(|c| c.is_alphabetic()).collect();
+                    (number, label)
+                }
+                (Err(_), true) => (0, String::new()),
+            }
+        })
+        .collect()
+}
+
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a, b) = (version_segments(a), version_segments(b));
+    let rank = |label: &str| match label {
+        "" => 2,
+        "post" => 3,
+        _ => 1, // dev, a, b, rc: pre-releases sort before the release
+    };
+
+    for i in 0..a.len().max(b.len()) {
+        let left = a.get(i).cloned().unwrap_or((0, String::new()));
+        let right = b.get(i).cloned().unwrap_or((0, String::new()));
+
+        let ordering = left.0
+            .cmp(&right.0)
+            .then_with(|| rank(&left.1).cmp(&rank(&right.1)))
+            .then_with(|| left.1.cmp(&right.1));
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    Ordering::Equal
+}
+
+fn satisfies(installed: &str, op: &str, required: &str) -> bool {
+    if let Some(prefix) = required.strip_suffix(".*") {
+        let matches = installed == prefix || installed.starts_with(&format!("{}.", prefix));
+        return if op == "!=" { !matches } else { matches };
+    }
+
+    let ordering = compare_versions(installed, required);
+    match op {
+        "==" | "===" => ordering == Ordering::Equal,
+        "!=" => ordering != Ordering::Equal,
+        ">=" => ordering != Ordering::Less,
+        "<=" => ordering != Ordering::Greater,
+        ">" => ordering == Ordering::Greater,
+        "<" => ordering == Ordering::Less,
+        "~=" => {
+            // ~=1.4.2 means >=1.4.2 and ==1.4.*
+            let mut parts: Vec<&str> = required.split('.').collect();
+            parts.pop();
+            let prefix = parts.join(".");
+            ordering != Ordering::Less && (installed == prefix || installed.starts_with(&format!("{}.", prefix)))
+        }
+        _ => true,
+    }
+}
+
+fn diff(requirements: &[Requirement], installed: &HashMap<String, String>) -> (Vec<DependencyIssue>, Vec<DependencyIssue>) {
+    let mut missing = Vec::new();
+    let mut mismatched = Vec::new();
+
+    for requirement in requirements {
+        match installed.get(&requirement.name) {
+            None => missing.push(DependencyIssue {
+                name: requirement.name.clone(),
+                required: requirement.raw.clone(),
+                installed: None,
+            }),
+            Some(version) => {
+                let ok = requirement
+                    .specifiers
+                    .iter()
+                    .all(|(op, required)| satisfies(version, op, required));
+                if !ok {
+                    mismatched.push(DependencyIssue {
+                        name: requirement.name.clone(),
+                        required: requirement.raw.clone(),
+                        installed: Some(version.clone()),
+                    });
+                }
+            }
+        }
+    }
+
+    (missing, mismatched)
+}
+
+fn tool_available(program: &str) -> bool {
+    Command::new(program)
+        .arg("--version")
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+fn uses_poetry(pyproject: &str) -> bool {
+    pyproject.lines().any(|line| line.trim().starts_with("[tool.poetry"))
+}
+
+/// Picks the installer from the files in the compute_engine directory,
+/// falling back to pip when the matching tool isn't on PATH.
+pub fn detect_tool(compute_engine_dir: &Path) -> InstallTool {
+    let pyproject = std::fs::read_to_string(compute_engine_dir.join("pyproject.toml")).ok();
+    let poetry = compute_engine_dir.join("poetry.lock").is_file() || pyproject.as_deref().is_some_and(uses_poetry);
+
+    if compute_engine_dir.join("uv.lock").is_file() && tool_available("uv") {
+        return InstallTool::Uv;
+    }
+    if poetry && tool_available("poetry") {
+        return InstallTool::Poetry;
+    }
+    if pyproject.is_some() && !poetry && tool_available("uv") {
+        return InstallTool::Uv;
+    }
+    InstallTool::Pip
+}
+
+fn installed_packages(python: &Path, compute_engine_dir: &Path, tool: InstallTool) -> Result<HashMap<String, String>> {
+    // Environments uv creates have no pip of their own
+    let mut command = match tool {
+        InstallTool::Uv => {
+            let mut command = Command::new("uv");
+            command.args(["pip", "list", "--format=json", "--python"]).arg(python);
+            command
+        }
+        InstallTool::Poetry | InstallTool::Pip => {
+            let mut command = Command::new(python);
+            command.args(["-m", "pip", "list", "--format=json", "--disable-pip-version-check"]);
+            command
+        }
+    };
+    let output = command
+        .current_dir(compute_engine_dir)
+        .output()
+        .context(format!("Failed to list packages using {:?}", python))?;
+
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "{} list failed: {}",
+            tool.as_str(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let packages: Vec<InstalledPackage> = serde_json::from_slice(&output.stdout)
+        .context("Failed to parse pip list output")?;
+
+    Ok(packages
+        .into_iter()
+        .map(|package| (normalize_name(&package.name), package.version))
+        .collect())
+}
+
+/// Compares the packages installed in the engine's environment against its
+/// requirements.txt.
+pub fn verify(compute_engine_dir: &Path) -> Result<DependencyReport> {
+    let requirements_path = compute_engine_dir.join("requirements.txt");
+    let bytes = std::fs::read(&requirements_path)
+        .context(format!("Failed to read {:?}", requirements_path))?;
+    let requirements = parse_requirements(&decode_text(&bytes));
+
+    let python = Supervisor::find_python_executable(compute_engine_dir)?;
+    let installed = installed_packages(&python, compute_engine_dir, detect_tool(compute_engine_dir))?;
+    let (missing, mismatched) = diff(&requirements, &installed);
+
+    let install_specs = missing
+        .iter()
+        .chain(mismatched.iter())
+        .map(|issue| issue.required.clone())
+        .collect();
+
+    Ok(DependencyReport {
+        python: python.to_string_lossy().to_string(),
+        requirements_path: requirements_path.to_string_lossy().to_string(),
+        checked: requirements.len(),
+        satisfied: requirements.len() - missing.len() - mismatched.len(),
+        missing,
+        mismatched,
+        install_specs,
+    })
+}
+
+fn pip_install(python: &Path, specs: &[String]) -> Command {
+    let mut command = Command::new(python);
+    command.args(["-m", "pip", "install", "--disable-pip-version-check"]);
+    if specs.is_empty() {
+        command.args(["-r", "requirements.txt"]);
+    } else {
+        command.args(specs);
+    }
+    command
+}
+
+/// Installs the given requirement specs (or the whole environment) into the
+/// engine's environment, returning the tool used and its output. A full
+/// install with uv or Poetry syncs from the lock file and creates `.venv`
+/// if needed, so it works before any interpreter is set up.
+pub fn install(compute_engine_dir: &Path, specs: &[String]) -> Result<(InstallTool, String)> {
+    let tool = detect_tool(compute_engine_dir);
+
+    let mut command = match (tool, specs.is_empty()) {
+        (InstallTool::Uv, true) => {
+            let mut command = Command::new("uv");
+            command.arg("sync");
+            command
+        }
+        (InstallTool::Uv, false) => {
+            let python = Supervisor::find_python_executable(compute_engine_dir)?;
+            let mut command = Command::new("uv");
+            command.args(["pip", "install", "--python"]).arg(python).args(specs);
+            command
+        }
+        (InstallTool::Poetry, true) => {
+            let mut command = Command::new("poetry");
+            command
+                .args(["install", "--no-root", "--no-interaction"])
+                .env("POETRY_VIRTUALENVS_IN_PROJECT", "true");
+            command
+        }
+        // `poetry add` would rewrite pyproject.toml; individual fixes go
+        // straight into the environment instead
+        (InstallTool::Poetry, false) | (InstallTool::Pip, _) => {
+            pip_install(&Supervisor::find_python_executable(compute_engine_dir)?, specs)
+        }
+    };
+    command.current_dir(compute_engine_dir);
+
+    println!("[NOVEM] Installing engine dependencies with {}: {:?}", tool.as_str(), command);
+    let output = command
+        .output()
+        .context(format!("Failed to run {}", tool.as_str()))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "{} install failed: {}",
+            tool.as_str(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok((tool, stdout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requirements_diff() {
+        let mut bytes = vec![0xFF, 0xFE];
+        let text = "fastapi==0.128.7\nPyYAML==6.0.3\nuvicorn[standard]>=0.30,<1.0\npython-dateutil==2.9.0.post0\n# comment\nduckdb~=1.4.0\n";
+        for unit in text.encode_utf16() {
+            bytes.extend_from_slice(&unit.to_le_bytes());
+        }
+
+        let requirements = parse_requirements(&decode_text(&bytes));
+        assert_eq!(requirements.len(), 5);
+        assert_eq!(requirements[1].name, "pyyaml");
+        assert_eq!(requirements[2].specifiers.len(), 2);
+
+        let installed: HashMap<String, String> = [
+            ("fastapi", "0.128.7"),
+            ("uvicorn", "0.40.0"),
+            ("python-dateutil", "2.9.0.post0"),
+            ("duckdb", "1.5.0"),
+        ]
+        .into_iter()
+        .map(|(name, version)| (name.to_string(), version.to_string()))
+        .collect();
+
+        let (missing, mismatched) = diff(&requirements, &installed);
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].name, "pyyaml");
+        assert_eq!(mismatched.len(), 1);
+        assert_eq!(mismatched[0].name, "duckdb");
+    }
+
+    #[test]
+    fn test_detects_poetry_projects() {
+        assert!(uses_poetry("[tool.poetry]\nname = \"engine\"\n"));
+        assert!(uses_poetry("[project]\n[tool.poetry.dependencies]\n"));
+        assert!(!uses_poetry("[project]\nname = \"engine\"\n[tool.uv]\n"));
+
+        let dir = std::env::temp_dir().join("test_novem_install_tool");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("requirements.txt"), "fastapi\n").unwrap();
+        assert_eq!(detect_tool(&dir), InstallTool::Pip);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
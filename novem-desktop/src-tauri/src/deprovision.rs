@@ -0,0 +1,353 @@
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::backend::BackendSession;
+use crate::dashboards;
+use crate::database::LocalFootprint;
+use crate::i18n::tr;
+use crate::workspaces::ACTIVE_WORKSPACE_SETTING;
+use crate::AppState;
+
+pub const DATABASE_FILE: &str = "novem.db";
+
+/// Removed alongside the database file if SQLite left them behind.
+const DATABASE_SIDE_FILES: &[&str] = &["novem.db-journal", "novem.db-wal", "novem.db-shm"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WipeTarget {
+    /// Imported dataset files, project attachments and project engines'
+    /// DuckDB data
+    Datasets,
+    /// Published dashboards and other rendered outputs
+    Artifacts,
+    /// Engine environment variables and the backend access token
+    Credentials,
+    /// App preferences
+    Config,
+    /// Local metadata: workspaces, projects, notebooks, history
+    Database,
+}
+
+impl WipeTarget {
+    /// In the order they are wiped; the database goes last since the other
+    /// targets are found through it.
+    pub const ALL: [WipeTarget; 5] = [
+        WipeTarget::Datasets,
+        WipeTarget::Artifacts,
+        WipeTarget::Credentials,
+        WipeTarget::Config,
+        WipeTarget::Database,
+    ];
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeprovisionScope {
+    /// One workspace, or everything on the device when omitted
+    pub workspace_uuid: Option<String>,
+    /// Every target when empty
+    #[serde(default)]
+    pub targets: Vec<WipeTarget>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TargetReport {
+    pub target: WipeTarget,
+    pub files_removed: usize,
+    pub bytes_freed: u64,
+    pub rows_removed: usize,
+    /// Files or rows still present when checked afterwards
+    pub leftovers: Vec<String>,
+    pub error: Option<String>,
+}
+
+impl TargetReport {
+    fn new(target: WipeTarget) -> Self {
+        Self {
+            target,
+            files_removed: 0,
+            bytes_freed: 0,
+            rows_removed: 0,
+            leftovers: Vec::new(),
+            error: None,
+        }
+    }
+
+    fn is_clean(&self) -> bool {
+        self.error.is_none() && self.leftovers.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeprovisionReport {
+    pub workspace_uuid: Option<String>,
+    pub targets: Vec<TargetReport>,
+    /// Every target was wiped and confirmed gone
+    pub verified: bool,
+    pub backend_notified: bool,
+    pub backend_error: Option<String>,
+}
+
+/// Deletes a file or directory tree, counting what it frees. Missing paths
+/// are not an error.
+fn remove_path(path: &Path, report: &mut TargetReport) -> Result<()> {
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e).context(format!("Failed to inspect {:?}", path)),
+    };
+
+    if metadata.is_dir() {
+        for entry in std::fs::read_dir(path)? {
+            remove_path(&entry?.path(), report)?;
+        }
+        std::fs::remove_dir(path).context(format!("Failed to remove {:?}", path))?;
+    } else {
+        std::fs::remove_file(path).context(format!("Failed to remove {:?}", path))?;
+        report.files_removed += 1;
+        report.bytes_freed += metadata.len();
+    }
+    Ok(())
+}
+
+fn remove_paths(paths: &[PathBuf], report: &mut TargetReport) -> Result<()> {
+    for path in paths {
+        remove_path(path, report)?;
+    }
+    report.leftovers = paths
+        .iter()
+        .filter(|path| path.exists())
+        .map(|path| path.display().to_string())
+        .collect();
+    Ok(())
+}
+
+fn dataset_paths(state: &AppState, footprint: &LocalFootprint, whole_device: bool) -> Vec<PathBuf> {
+    let data_dir = state.data_dir();
+    if whole_device {
+        return ["datasets", "attachments", "staging", "spill", "engines"]
+            .iter()
+            .map(|dir| data_dir.join(dir))
+            .collect();
+    }
+
+    // Registered files outside managed storage belong to the user, not the app
+    footprint
+        .dataset_files
+        .iter()
+        .map(PathBuf::from)
+        .filter(|path| path.starts_with(&data_dir))
+        .chain(footprint.attachment_files.iter().map(PathBuf::from))
+        .chain(footprint.project_ids.iter().map(|id| state.engines.work_dir(*id)))
+        .collect()
+}
+
+fn artifact_paths(state: &AppState, footprint: &LocalFootprint, whole_device: bool) -> Vec<PathBuf> {
+    let artifacts_dir = state.artifacts_dir();
+    if whole_device {
+        return vec![artifacts_dir];
+    }
+
+    footprint
+        .dashboard_uuids
+        .iter()
+        .map(|uuid| dashboards::render_path(&artifacts_dir, uuid))
+        .collect()
+}
+
+fn wipe_credentials(state: &AppState, workspace: Option<&str>, report: &mut TargetReport) -> Result<()> {
+    report.rows_removed = state.with_db(|db| db.clear_engine_env(workspace))?;
+
+    if workspace.is_none() {
+        let mut session = state.backend.lock().map_err(|e| anyhow::anyhow!("Failed to lock backend session: {}", e))?;
+        session.access_token = None;
+    }
+
+    let remaining = state.with_db(|db| db.count_engine_env(workspace))?;
+    if remaining > 0 {
+        report.leftovers.push(format!("{} engine environment variables", remaining));
+    }
+    Ok(())
+}
+
+fn wipe_config(state: &AppState, workspace: Option<&str>, report: &mut TargetReport) -> Result<()> {
+    match workspace {
+        Some(uuid) => state.with_db(|db| {
+            if db.get_setting(ACTIVE_WORKSPACE_SETTING)?.as_deref() == Some(uuid) {
+                db.delete_setting(ACTIVE_WORKSPACE_SETTING)?;
+                report.rows_removed = 1;
+            }
+            if db.get_setting(ACTIVE_WORKSPACE_SETTING)?.as_deref() == Some(uuid) {
+                report.leftovers.push(ACTIVE_WORKSPACE_SETTING.to_string());
+            }
+            Ok(())
+        }),
+        None => state.with_db(|db| {
+            report.rows_removed = db.clear_settings()?;
+            let remaining = db.count_settings()?;
+            if remaining > 0 {
+                report.leftovers.push(format!("{} settings", remaining));
+            }
+            Ok(())
+        }),
+    }
+}
+
+/// Deletes the database file outright and starts over with an empty one, so
+/// nothing survives in free pages.
+fn recreate_database(state: &AppState, report: &mut TargetReport) -> Result<()> {
+    let data_dir = state.data_dir();
+    let paths: Vec<PathBuf> = std::iter::once(DATABASE_FILE)
+        .chain(DATABASE_SIDE_FILES.iter().copied())
+        .map(|file| data_dir.join(file))
+        .collect();
+
+    // Every connection is closed first, and the database reopened either
+    // way so the app keeps working
+    state.db.reopen_after(|| remove_paths(&paths, report))??;
+
+    let remaining = state.with_db(|db| db.local_footprint(None))?;
+    if let Some(footprint) = remaining {
+        if !footprint.entity_uuids.is_empty() {
+            report.leftovers.push(format!("{} entities", footprint.entity_uuids.len()));
+        }
+    }
+    Ok(())
+}
+
+fn wipe_database(state: &AppState, workspace: Option<&str>, report: &mut TargetReport) -> Result<()> {
+    match workspace {
+        Some(uuid) => state.with_db(|db| {
+            report.rows_removed = db.purge_workspace(uuid)?;
+            let remaining = db.count_workspace_rows(uuid)?;
+            if remaining > 0 {
+                report.leftovers.push(format!("{} workspace rows", remaining));
+            }
+            Ok(())
+        }),
+        None => recreate_database(state, report),
+    }
+}
+
+/// Wipes the requested targets and checks each is gone. A failing target
+/// is recorded in its report and doesn't stop the others.
+pub fn wipe(app: &AppHandle, scope: &DeprovisionScope) -> Result<Vec<TargetReport>> {
+    let state = app.state::<AppState>();
+    let workspace = scope.workspace_uuid.as_deref();
+    let whole_device = workspace.is_none();
+
+    let footprint = state
+        .with_db(|db| db.local_footprint(workspace))?
+        .ok_or_else(|| anyhow::anyhow!(tr!("workspace-not-found", uuid = workspace.unwrap_or_default())))?;
+
+    let targets: Vec<WipeTarget> = WipeTarget::ALL
+        .into_iter()
+        .filter(|target| scope.targets.is_empty() || scope.targets.contains(target))
+        .collect();
+
+    // Engines hold their DuckDB files open
+    if targets.iter().any(|target| matches!(target, WipeTarget::Datasets | WipeTarget::Database)) {
+        for project_id in &footprint.project_ids {
+            state.engines.tunnels().close(Some(*project_id));
+            if let Err(e) = state.engines.stop_project(*project_id) {
+                eprintln!("[ERROR] Failed to stop engine for project {}: {}", project_id, e);
+            }
+        }
+    }
+
+    let mut reports = Vec::new();
+    for target in targets {
+        let mut report = TargetReport::new(target);
+        let result = match target {
+            WipeTarget::Datasets => remove_paths(&dataset_paths(&state, &footprint, whole_device), &mut report),
+            WipeTarget::Artifacts => remove_paths(&artifact_paths(&state, &footprint, whole_device), &mut report),
+            WipeTarget::Credentials => wipe_credentials(&state, workspace, &mut report),
+            WipeTarget::Config => wipe_config(&state, workspace, &mut report),
+            WipeTarget::Database => wipe_database(&state, workspace, &mut report),
+        };
+
+        if let Err(e) = result {
+            eprintln!("[ERROR] Failed to wipe {:?}: {:#}", target, e);
+            report.error = Some(format!("{:#}", e));
+        }
+        println!(
+            "[NOVEM] Wiped {:?}: {} files ({} bytes), {} rows",
+            target, report.files_removed, report.bytes_freed, report.rows_removed
+        );
+        reports.push(report);
+    }
+
+    if reports.iter().any(|report| report.target == WipeTarget::Database && report.is_clean()) {
+        state.workspace.forget(workspace);
+    }
+
+    Ok(reports)
+}
+
+/// Tells the backend this device no longer holds the scope's data, using
+/// the session captured before credentials were wiped.
+pub async fn notify_backend(client: &Client, session: &BackendSession, scope: &DeprovisionScope, reports: &[TargetReport]) -> Result<()> {
+    if session.access_token.is_none() {
+        anyhow::bail!(tr!("deprovision-not-signed-in"));
+    }
+
+    let body = serde_json::json!({
+        "device_name": sysinfo::System::host_name(),
+        "workspace_uuid": scope.workspace_uuid,
+        "targets": reports.iter().map(|report| report.target).collect::<Vec<_>>(),
+        "verified": reports.iter().all(TargetReport::is_clean),
+    });
+
+    let response = session
+        .authorize(client.post(session.url("/api/auth/account/device/deprovision/")))
+        .timeout(Duration::from_secs(10))
+        .json(&body)
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        anyhow::bail!("Backend responded with {}", response.status());
+    }
+    Ok(())
+}
+
+impl DeprovisionReport {
+    pub fn new(scope: DeprovisionScope, targets: Vec<TargetReport>, notified: Result<()>) -> Self {
+        let verified = targets.iter().all(TargetReport::is_clean);
+        let backend_error = notified.err().map(|e| format!("{:#}", e));
+
+        Self {
+            workspace_uuid: scope.workspace_uuid,
+            targets,
+            verified,
+            backend_notified: backend_error.is_none(),
+            backend_error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remove_path_counts_files() {
+        let dir = std::env::temp_dir().join("test_novem_deprovision_files");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        std::fs::write(dir.join("a.parquet"), b"12345").unwrap();
+        std::fs::write(dir.join("nested").join("b.duckdb"), b"123").unwrap();
+
+        let mut report = TargetReport::new(WipeTarget::Datasets);
+        remove_paths(&[dir.clone(), dir.join("missing")], &mut report).unwrap();
+
+        assert_eq!(report.files_removed, 2);
+        assert_eq!(report.bytes_freed, 8);
+        assert!(report.is_clean());
+        assert!(!dir.exists());
+    }
+}
@@ -0,0 +1,6 @@
+mod supervisor;
+
+pub use supervisor::{
+    EngineKillSwitch, EngineLogLevel, EngineReadings, EngineShutdown, EngineSource, EngineStatus, EngineStatusCell,
+    EngineStatusChanged, LaunchProfile, ReadinessProbe, ShutdownPolicy, Supervisor, DEFAULT_ENGINE_PORT,
+};
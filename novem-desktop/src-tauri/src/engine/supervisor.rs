@@ -0,0 +1,1119 @@
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Write};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::process::{ChildStderr, Command, Stdio};
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::database::EngineMetric;
+use crate::engine_auth;
+use crate::engine_metrics::{ProcessSampler, ProcessUsage};
+use crate::events::{self, AppEvent};
+use crate::heartbeat::{HeartbeatConfig, HeartbeatState};
+use crate::http;
+use crate::interpreters;
+use crate::latency::{LatencyStats, LatencyWindow};
+use crate::ports;
+use crate::process_tree::ProcessTree;
+use crate::startup_diagnosis::{self, StartupDiagnosis, StartupFailure, StderrTail};
+
+/// Port of the shared engine that serves requests not tied to a project.
+pub const DEFAULT_ENGINE_PORT: u16 = 8765;
+
+/// How many more ports a start tries when uvicorn finds its port taken.
+const MAX_PORT_RETRIES: u32 = 3;
+
+/// How often the health monitor runs the full `/health` check on a running
+/// engine; heartbeats go out far more often.
+const SUPERVISOR_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The health monitor samples CPU and memory every this many checks (10s).
+const METRICS_EVERY: u32 = 2;
+
+/// Health checks and shutdown requests to a local engine.
+const ENGINE_REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// The frozen engine registered under `bundle.externalBin`. Tauri installs
+/// it beside the app executable with the target triple stripped.
+const SIDECAR_NAME: &str = "novem-engine";
+
+/// How startup waits for a freshly spawned engine to come up.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReadinessProbe {
+    /// Give up and mark the engine degraded after this long
+    pub timeout_secs: u64,
+    pub poll_interval_ms: u64,
+    /// Path that must answer 2xx for the engine to count as ready
+    pub endpoint: String,
+    /// POSTed once the endpoint passes, to preload libraries before the
+    /// engine is marked ready
+    pub warmup_endpoint: Option<String>,
+    pub warmup_timeout_secs: u64,
+}
+
+impl Default for ReadinessProbe {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 30,
+            poll_interval_ms: 1000,
+            endpoint: "/health".to_string(),
+            warmup_endpoint: None,
+            warmup_timeout_secs: 120,
+        }
+    }
+}
+
+/// How stopping an engine escalates: POST `/shutdown`, then SIGTERM (a
+/// CTRL_BREAK on Windows), then kill. Each wait ends as soon as it exits.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShutdownPolicy {
+    /// How long the engine gets to exit on its own after `/shutdown`
+    pub shutdown_timeout_secs: u64,
+    /// How long after SIGTERM before the kill, for DuckDB and HDF5 writes
+    /// in flight to reach disk
+    pub grace_period_secs: u64,
+}
+
+impl Default for ShutdownPolicy {
+    fn default() -> Self {
+        Self {
+            shutdown_timeout_secs: 10,
+            grace_period_secs: 3,
+        }
+    }
+}
+
+/// How far a stop had to escalate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShutdownOutcome {
+    /// The process was gone before the stop began
+    AlreadyExited,
+    /// Exited after `/shutdown`
+    Graceful,
+    /// Exited within the grace period after SIGTERM/CTRL_BREAK
+    Terminated,
+    Killed,
+}
+
+/// Published when an engine process has been stopped; the frontend gets it
+/// as `engine-stopped`.
+#[derive(Debug, Clone, Serialize)]
+pub struct EngineShutdown {
+    pub project_id: Option<i64>,
+    pub outcome: ShutdownOutcome,
+    /// False when the engine had to be killed and may have lost writes
+    pub clean: bool,
+    pub duration_ms: u64,
+}
+
+/// Verbosity of the engine's Python logging and uvicorn's access log.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EngineLogLevel {
+    Critical,
+    Error,
+    Warning,
+    #[default]
+    Info,
+    Debug,
+    /// uvicorn's level below debug, logging ASGI messages
+    Trace,
+}
+
+impl EngineLogLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EngineLogLevel::Critical => "critical",
+            EngineLogLevel::Error => "error",
+            EngineLogLevel::Warning => "warning",
+            EngineLogLevel::Info => "info",
+            EngineLogLevel::Debug => "debug",
+            EngineLogLevel::Trace => "trace",
+        }
+    }
+}
+
+/// How the engine process is launched, taken from the active engine profile.
+#[derive(Debug, Clone, Default)]
+pub struct LaunchProfile {
+    /// Interpreter to run instead of the detected virtualenv/system Python
+    pub python_path: Option<PathBuf>,
+    /// Appended to the uvicorn command line
+    pub extra_args: Vec<String>,
+    pub env: BTreeMap<String, String>,
+    /// Process working directory; defaults to the compute_engine directory
+    pub working_dir: Option<PathBuf>,
+}
+
+/// What an engine runs: the self-contained sidecar binary release builds
+/// ship, or the `main.py` sources under a Python interpreter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EngineSource {
+    Sidecar(PathBuf),
+    Script(PathBuf), // the compute_engine directory
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EngineStatus {
+    NotFound,
+    Starting,
+    Ready,
+    Degraded,
+    Crashed,
+    Stopped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EngineStatusChanged {
+    pub project_id: Option<i64>,
+    pub status: EngineStatus,
+    pub previous: EngineStatus,
+}
+
+/// Emitted as `engine-startup-progress` after every readiness poll, so slow
+/// starts (cold conda environments) show how long they've been waiting.
+#[derive(Debug, Clone, Serialize)]
+pub struct EngineStartupProgress {
+    pub project_id: Option<i64>,
+    pub attempt: u32,
+    pub elapsed_ms: u64,
+    pub timeout_ms: u64,
+}
+
+/// Current lifecycle state of one engine instance, shared between the
+/// `EngineManager`, the engine and its health monitor. Every transition is
+/// published on the event bus (and reaches the frontend as
+/// `engine-status-changed`), tagged with the owning project.
+#[derive(Clone)]
+pub struct EngineStatusCell {
+    status: Arc<Mutex<EngineStatus>>,
+    project_id: Option<i64>,
+    app: AppHandle,
+}
+
+impl EngineStatusCell {
+    pub fn new(app: AppHandle, project_id: Option<i64>) -> Self {
+        Self {
+            status: Arc::new(Mutex::new(EngineStatus::Stopped)),
+            project_id,
+            app,
+        }
+    }
+
+    pub fn get(&self) -> EngineStatus {
+        *self.status.lock().unwrap()
+    }
+
+    pub fn project_id(&self) -> Option<i64> {
+        self.project_id
+    }
+
+    pub fn set(&self, status: EngineStatus) {
+        let previous = {
+            let mut current = self.status.lock().unwrap();
+            std::mem::replace(&mut *current, status)
+        };
+
+        if previous != status {
+            match self.project_id {
+                Some(project_id) => println!("[NOVEM] Engine status (project {}): {:?} -> {:?}", project_id, previous, status),
+                None => println!("[NOVEM] Engine status: {:?} -> {:?}", previous, status),
+            }
+            events::publish(
+                &self.app,
+                AppEvent::EngineStatusChanged(EngineStatusChanged { project_id: self.project_id, status, previous }),
+            );
+        }
+    }
+
+    fn shutdown(&self, outcome: ShutdownOutcome, duration: Duration) {
+        events::publish(
+            &self.app,
+            AppEvent::EngineStopped(EngineShutdown {
+                project_id: self.project_id,
+                outcome,
+                clean: outcome != ShutdownOutcome::Killed,
+                duration_ms: duration.as_millis() as u64,
+            }),
+        );
+    }
+
+    fn heartbeat(&self, state: HeartbeatState) {
+        events::publish(&self.app, AppEvent::EngineHeartbeat(state));
+    }
+
+    /// Publishes a resource sample of this engine for the metrics recorder.
+    fn publish_metrics(&self, pid: u32, usage: ProcessUsage) {
+        events::publish(
+            &self.app,
+            AppEvent::EngineMetrics(EngineMetric {
+                project_id: self.project_id,
+                pid,
+                sampled_at: chrono::Utc::now().timestamp(),
+                cpu_percent: usage.cpu_percent,
+                memory_mb: usage.memory_mb,
+                process_count: usage.process_count,
+            }),
+        );
+    }
+
+    fn startup_progress(&self, attempt: u32, elapsed: Duration, timeout: Duration) {
+        let _ = self.app.emit(
+            "engine-startup-progress",
+            EngineStartupProgress {
+                project_id: self.project_id,
+                attempt,
+                elapsed_ms: elapsed.as_millis() as u64,
+                timeout_ms: timeout.as_millis() as u64,
+            },
+        );
+    }
+}
+
+/// One compute engine process, from finding the interpreter or sidecar to
+/// spawning it, forwarding its logs and tearing its process tree down.
+/// Every engine the app runs goes through one of these.
+pub struct Supervisor {
+    process: Arc<Mutex<Option<ProcessTree>>>,
+    port: EnginePort,
+    port_range: Option<RangeInclusive<u16>>,
+    source: Option<EngineSource>,
+    work_dir: Option<PathBuf>,
+    env: BTreeMap<String, String>,
+    launch: LaunchProfile,
+    readiness: ReadinessProbe,
+    reload: bool,
+    log_level: EngineLogLevel,
+    shutdown: ShutdownPolicy,
+    status: EngineStatusCell,
+    heartbeat_config: Arc<Mutex<HeartbeatConfig>>,
+    readings: EngineReadings,
+    stderr_tail: StderrTail,
+    monitor_started: bool,
+}
+
+impl Supervisor {
+    /// Creates an engine listening on `port`. With a `work_dir`, the engine
+    /// keeps its DuckDB files, temp data and logs there instead of `~/.novem`.
+    pub fn new(status: EngineStatusCell, port: u16, work_dir: Option<PathBuf>) -> Self {
+        let readings = EngineReadings::new(status.project_id());
+        Self {
+            process: Arc::new(Mutex::new(None)),
+            port: EnginePort::new(port),
+            port_range: None,
+            source: None,
+            work_dir,
+            env: BTreeMap::new(),
+            launch: LaunchProfile::default(),
+            readiness: ReadinessProbe::default(),
+            reload: false,
+            log_level: EngineLogLevel::default(),
+            shutdown: ShutdownPolicy::default(),
+            status,
+            heartbeat_config: Arc::new(Mutex::new(HeartbeatConfig::default())),
+            readings,
+            stderr_tail: StderrTail::default(),
+            monitor_started: false,
+        }
+    }
+
+    /// User-defined variables for the engine process; applied on next start.
+    pub fn set_env(&mut self, env: BTreeMap<String, String>) {
+        self.env = env;
+    }
+
+    /// Interpreter, arguments and environment from an engine profile; applied
+    /// on next start.
+    pub fn set_launch_profile(&mut self, launch: LaunchProfile) {
+        self.launch = launch;
+    }
+
+    /// Ports the engine may move to when its own is taken by another process.
+    /// Without a range, a taken port fails the start.
+    pub fn set_port_range(&mut self, range: RangeInclusive<u16>) {
+        self.port_range = Some(range);
+    }
+
+    /// Heartbeat settings shared with the `EngineManager`; the health monitor
+    /// reads them on every beat, so changes apply to a running engine.
+    pub fn share_heartbeat_config(&mut self, config: Arc<Mutex<HeartbeatConfig>>) {
+        self.heartbeat_config = config;
+    }
+
+    /// Takes effect on the next stop, including for a running engine.
+    pub fn set_shutdown_policy(&mut self, shutdown: ShutdownPolicy) {
+        self.shutdown = shutdown;
+    }
+
+    /// How the next start waits for the engine to become ready.
+    pub fn set_readiness_probe(&mut self, readiness: ReadinessProbe) {
+        self.readiness = readiness;
+    }
+
+    /// Runs uvicorn with `--reload`, restarting the engine whenever its Python
+    /// sources change; applied on next start.
+    pub fn set_reload(&mut self, reload: bool) {
+        self.reload = reload;
+    }
+
+    /// Level the next start launches with. A running engine is switched
+    /// through its `/control/log-level` endpoint instead.
+    pub fn set_log_level(&mut self, log_level: EngineLogLevel) {
+        self.log_level = log_level;
+    }
+
+    /// Replaces what the engine runs; applied on next (re)start.
+    pub fn set_source(&mut self, source: EngineSource) {
+        self.source = Some(source);
+    }
+
+    /// The bundled engine binary, present only in packaged release builds.
+    pub fn find_sidecar() -> Option<PathBuf> {
+        let exe_path = std::env::current_exe().ok()?;
+        let sidecar = exe_path
+            .parent()?
+            .join(SIDECAR_NAME)
+            .with_extension(std::env::consts::EXE_EXTENSION);
+
+        if sidecar.is_file() {
+            println!("[NOVEM] Found compute engine sidecar: {:?}", sidecar);
+            Some(sidecar)
+        } else {
+            None
+        }
+    }
+
+    /// Locates the engine sources: next to the repo in development, else
+    /// bundled beside the executable or under its `resources` directory.
+    pub fn find_compute_engine_dir() -> Option<PathBuf> {
+        let current_dir = std::env::current_dir().ok()?;
+
+        let dev_path = current_dir.parent()?.parent()?.join("compute_engine");
+        if dev_path.exists() && dev_path.join("main.py").exists() {
+            println!("[NOVEM] Found compute_engine (dev mode): {:?}", dev_path);
+            return Some(dev_path);
+        }
+
+        let exe_path = std::env::current_exe().ok()?;
+        let exe_dir = exe_path.parent()?;
+
+        let prod_path = exe_dir.join("compute_engine");
+        if prod_path.exists() && prod_path.join("main.py").exists() {
+            println!("[NOVEM] Found compute_engine (prod mode): {:?}", prod_path);
+            return Some(prod_path);
+        }
+
+        let resources_path = exe_dir.join("resources").join("compute_engine");
+        if resources_path.exists() && resources_path.join("main.py").exists() {
+            println!("[NOVEM] Found compute_engine (resources): {:?}", resources_path);
+            return Some(resources_path);
+        }
+
+        None
+    }
+
+    /// Python of the engine's virtual environment, falling back to the first
+    /// interpreter on the system new enough for the engine's requirements.
+    pub fn find_python_executable(compute_engine_dir: &Path) -> Result<PathBuf> {
+        let python = interpreters::find(compute_engine_dir)?;
+        println!("[NOVEM] Using Python: {:?}", python);
+        Ok(python)
+    }
+
+    pub fn start_fastapi_server(&mut self, source: EngineSource) -> Result<()> {
+        println!("[NOVEM] Starting embedded FastAPI server...");
+        
+        self.source = Some(source.clone());
+        self.status.set(EngineStatus::Starting);
+        self.readings.latency.lock().unwrap().clear();
+        *self.readings.warmup_ms.lock().unwrap() = None;
+        *self.readings.startup_failure.lock().unwrap() = None;
+        
+        let entry_point = match &source {
+            EngineSource::Sidecar(sidecar) => sidecar.clone(),
+            EngineSource::Script(compute_engine_dir) => compute_engine_dir.join("main.py"),
+        };
+        if !entry_point.is_file() {
+            self.status.set(EngineStatus::NotFound);
+            return Err(anyhow::anyhow!(
+                "Compute engine not found at {:?}",
+                entry_point
+            ));
+        }
+
+        self.claim_port()?;
+
+        // The sidecar bundles its own interpreter; scripts need one found
+        let (program, default_dir) = match &source {
+            EngineSource::Sidecar(sidecar) => (sidecar.clone(), sidecar.parent().map(Path::to_path_buf)),
+            EngineSource::Script(compute_engine_dir) => {
+                let python_exe = match &self.launch.python_path {
+                    Some(python) => python.clone(),
+                    None => Self::find_python_executable(compute_engine_dir)?,
+                };
+                (python_exe, Some(compute_engine_dir.clone()))
+            }
+        };
+        let working_dir = self
+            .launch
+            .working_dir
+            .clone()
+            .or(default_dir)
+            .ok_or_else(|| anyhow::anyhow!("No working directory for {:?}", program))?;
+
+        println!("[NOVEM] Working directory: {:?}", working_dir);
+
+        let mut port_retries = 0;
+        loop {
+            let stderr = self.spawn_server(&program, &working_dir, &source)?;
+            if self.wait_until_ready(stderr)? {
+                return Ok(());
+            }
+
+            // Another process bound the port between our check and uvicorn's
+            if port_retries == MAX_PORT_RETRIES || self.port_range.is_none() {
+                self.status.set(EngineStatus::Crashed);
+                let owner = ports::owner(self.port.get())
+                    .map(|owner| owner.to_string())
+                    .unwrap_or_else(|| "an unknown process".to_string());
+                return Err(self.fail_startup(StartupFailure::PortInUse { port: self.port.get(), owner }));
+            }
+            port_retries += 1;
+            eprintln!("[WARNING] Engine could not bind port {} (attempt {})", self.port.get(), port_retries);
+            self.move_off_port()?;
+        }
+    }
+
+    /// Spawns uvicorn on the current port. The returned reader forwards the
+    /// engine's stderr and finishes with whether it reported the port taken.
+    fn spawn_server(
+        &mut self,
+        program: &Path,
+        working_dir: &Path,
+        source: &EngineSource,
+    ) -> Result<Option<JoinHandle<bool>>> {
+        let mut command = Command::new(program);
+
+        match source {
+            EngineSource::Sidecar(_) => {
+                println!("[NOVEM] Command: {:?} --host 127.0.0.1 --port {}", program, self.port.get());
+            }
+            EngineSource::Script(_) => {
+                println!("[NOVEM] Python executable: {:?}", program);
+                println!("[NOVEM] Command: {:?} -m uvicorn main:app --host 127.0.0.1 --port {}", 
+                         program, self.port.get());
+                command.arg("-m").arg("uvicorn").arg("main:app");
+            }
+        }
+
+        command
+            .arg("--host")
+            .arg("127.0.0.1")
+            .arg("--port")
+            .arg(self.port.get().to_string())
+            .arg("--log-level")
+            .arg(self.log_level.as_str())
+            .current_dir(working_dir)
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::piped())
+            // Workspace variables are more specific than the profile's
+            .envs(&self.launch.env)
+            .envs(&self.env);
+
+        match source {
+            EngineSource::Script(compute_engine_dir) => {
+                if working_dir != compute_engine_dir {
+                    command.arg("--app-dir").arg(compute_engine_dir);
+                }
+
+                if self.reload {
+                    println!("[NOVEM] Dev mode: reloading on changes in {:?}", compute_engine_dir);
+                    command
+                        .arg("--reload")
+                        .arg("--reload-dir")
+                        .arg(compute_engine_dir);
+                }
+            }
+            EngineSource::Sidecar(_) if self.reload => {
+                println!("[WARNING] Dev mode reload needs the engine sources; the sidecar runs without it");
+            }
+            EngineSource::Sidecar(_) => {}
+        }
+
+        if !self.launch.extra_args.is_empty() {
+            println!("[NOVEM] Extra uvicorn arguments: {:?}", self.launch.extra_args);
+            command.args(&self.launch.extra_args);
+        }
+
+        if !self.env.is_empty() || !self.launch.env.is_empty() {
+            let names: Vec<&String> = self.launch.env.keys().chain(self.env.keys()).collect();
+            println!("[NOVEM] Engine environment: {:?}", names);
+        }
+
+        // Set after user variables so isolation settings can't be overridden
+        command
+            .env(engine_auth::TOKEN_ENV, engine_auth::token())
+            .env("COMPUTE_ENGINE_LOG_LEVEL", self.log_level.as_str());
+        if let Some(work_dir) = &self.work_dir {
+            std::fs::create_dir_all(work_dir)
+                .context(format!("Failed to create engine working directory {:?}", work_dir))?;
+            println!("[NOVEM] Engine data directory: {:?}", work_dir);
+
+            // tempfile falls back to the system temp dir unless this exists
+            // before the interpreter starts
+            let temp_dir = work_dir.join("temp");
+            std::fs::create_dir_all(&temp_dir)
+                .context(format!("Failed to create engine temp directory {:?}", temp_dir))?;
+
+            command
+                .env("COMPUTE_ENGINE_PORT", self.port.get().to_string())
+                .env("COMPUTE_ENGINE_APP_DATA_DIR", work_dir)
+                .env("COMPUTE_ENGINE_DATA_DIR", work_dir.join("data"))
+                .env("COMPUTE_ENGINE_TEMP_DIR", &temp_dir)
+                .env("COMPUTE_ENGINE_LOGS_DIR", work_dir.join("logs"))
+                .env("TMPDIR", &temp_dir)
+                .env("TEMP", &temp_dir)
+                .env("TMP", &temp_dir)
+                .env("XDG_CACHE_HOME", work_dir.join("cache"));
+        }
+
+        let mut process = ProcessTree::spawn(&mut command)
+            .inspect_err(|_| self.status.set(EngineStatus::Crashed))
+            .context(format!("Failed to spawn FastAPI process using {:?}", program))?;
+
+        println!("[NOVEM] FastAPI process spawned (PID: {:?})", process.id());
+        self.stderr_tail.clear();
+        let stderr = process
+            .take_stderr()
+            .map(|stderr| forward_stderr(stderr, self.stderr_tail.clone()));
+
+        let mut process_lock = self.process.lock().unwrap();
+        *process_lock = Some(process);
+        drop(process_lock);
+
+        Ok(stderr)
+    }
+
+    /// Polls the readiness endpoint. `Ok(false)` means uvicorn exited because
+    /// its port was taken, so the start can be retried on another one.
+    fn wait_until_ready(&mut self, stderr: Option<JoinHandle<bool>>) -> Result<bool> {
+        let start_time = std::time::Instant::now();
+        let timeout = Duration::from_secs(self.readiness.timeout_secs);
+        let poll_interval = Duration::from_millis(self.readiness.poll_interval_ms.max(50));
+        let endpoint = format!("/{}", self.readiness.endpoint.trim_start_matches('/'));
+
+        println!("[NOVEM] Waiting for FastAPI to be ready at http://127.0.0.1:{}{}", self.port.get(), endpoint);
+
+        let mut retry_count = 0;
+        loop {
+            if self.process_exited() {
+                // Reap what's left so the stderr pipe closes and the reader finishes
+                if let Some(mut process) = self.process.lock().unwrap().take() {
+                    let _ = process.kill();
+                }
+                let port_in_use = stderr.and_then(|reader| reader.join().ok()).unwrap_or(false);
+                if port_in_use {
+                    return Ok(false);
+                }
+
+                self.status.set(EngineStatus::Crashed);
+                let failure = startup_diagnosis::classify_exit(&self.stderr_tail.lines());
+                return Err(self.fail_startup(failure));
+            }
+
+            if start_time.elapsed() > timeout {
+                self.status.set(EngineStatus::Degraded);
+                return Err(self.fail_startup(StartupFailure::Timeout { seconds: self.readiness.timeout_secs }));
+            }
+
+            let probe = self.check_endpoint(&endpoint);
+            self.status.startup_progress(retry_count + 1, start_time.elapsed(), timeout);
+
+            match probe {
+                Ok(true) => {
+                    self.warm_up();
+                    self.status.set(EngineStatus::Ready);
+                    println!("[NOVEM] FastAPI server is ready!");
+                    println!("[NOVEM] Health check passed after {} attempts", retry_count + 1);
+                    return Ok(true);
+                }
+                Ok(false) => {
+                    retry_count += 1;
+                    if retry_count % 10 == 0 {
+                        println!("[NOVEM] Still waiting... (attempt {})", retry_count);
+                    }
+                }
+                Err(e) => {
+                    retry_count += 1;
+                    if retry_count == 1 {
+                        println!("[NOVEM] Waiting for server to start... ({})", e);
+                    }
+                }
+            }
+            
+            std::thread::sleep(poll_interval);
+        }
+    }
+
+    /// Records why the start failed, with the stderr seen so far, and
+    /// returns it as an error.
+    fn fail_startup(&mut self, failure: StartupFailure) -> anyhow::Error {
+        let diagnosis = StartupDiagnosis::new(self.status.project_id(), failure, self.stderr_tail.lines());
+        eprintln!("[ERROR] Engine failed to start: {}", diagnosis.message);
+        let error = anyhow::anyhow!(diagnosis.message.clone());
+        *self.readings.startup_failure.lock().unwrap() = Some(diagnosis);
+        error
+    }
+
+    /// Makes sure the engine's port is free, moving to another port from its
+    /// range if some other process holds it.
+    fn claim_port(&mut self) -> Result<()> {
+        if ports::is_free(self.port.get()) {
+            return Ok(());
+        }
+        self.move_off_port()
+    }
+
+    /// Moves to the next free port in the range, reporting who holds the
+    /// current one.
+    fn move_off_port(&mut self) -> Result<()> {
+        let taken = self.port.get();
+        let owner = ports::owner(taken)
+            .map(|owner| owner.to_string())
+            .unwrap_or_else(|| "an unknown process".to_string());
+
+        match self.port.move_within(self.port_range.clone()) {
+            Some(port) => {
+                eprintln!("[WARNING] Port {} is in use by {}; moving the engine to port {}", taken, owner, port);
+                Ok(())
+            }
+            None => {
+                self.status.set(EngineStatus::Crashed);
+                Err(self.fail_startup(StartupFailure::PortInUse { port: self.port.get(), owner }))
+            }
+        }
+    }
+
+    /// Calls the warm-up endpoint, if one is configured. A failed warm-up is
+    /// only logged: the engine works, its first request is just slower.
+    fn warm_up(&mut self) {
+        let Some(endpoint) = self.readiness.warmup_endpoint.as_deref().filter(|e| !e.trim().is_empty()) else {
+            return;
+        };
+        let url = format!("http://127.0.0.1:{}/{}", self.port.get(), endpoint.trim_start_matches('/'));
+        println!("[NOVEM] Warming up engine at {}", url);
+
+        let started = Instant::now();
+        let result = http::blocking_engine()
+            .post(&url)
+            .timeout(Duration::from_secs(self.readiness.warmup_timeout_secs))
+            .send()
+            .and_then(|response| response.error_for_status());
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+
+        match result {
+            Ok(_) => {
+                println!("[NOVEM] Engine warm-up took {} ms", elapsed_ms);
+                *self.readings.warmup_ms.lock().unwrap() = Some(elapsed_ms);
+            }
+            Err(e) => eprintln!("[WARNING] Engine warm-up failed after {} ms: {}", elapsed_ms, e),
+        }
+    }
+
+    pub fn check_health(&self) -> Result<bool> {
+        self.check_endpoint("/health")
+    }
+
+    fn check_endpoint(&self, endpoint: &str) -> Result<bool> {
+        let url = format!("http://127.0.0.1:{}{}", self.port.get(), endpoint);
+        
+        match http::blocking_engine().get(&url).timeout(ENGINE_REQUEST_TIMEOUT).send() {
+            Ok(response) => {
+                Ok(response.status().is_success())
+            }
+            Err(_) => Ok(false),
+        }
+    }
+
+    pub fn get_port(&self) -> u16 {
+        self.port.get()
+    }
+
+    pub fn status(&self) -> &EngineStatusCell {
+        &self.status
+    }
+
+    pub fn readings(&self) -> EngineReadings {
+        self.readings.clone()
+    }
+
+    pub fn work_dir(&self) -> Option<&PathBuf> {
+        self.work_dir.as_ref()
+    }
+
+    fn process_exited(&self) -> bool {
+        let mut process_lock = self.process.lock().unwrap();
+        match process_lock.as_mut() {
+            Some(process) => matches!(process.try_wait(), Ok(Some(_))),
+            None => true,
+        }
+    }
+
+    pub fn kill_switch(&self) -> EngineKillSwitch {
+        EngineKillSwitch {
+            process: Arc::downgrade(&self.process),
+            status: self.status.clone(),
+        }
+    }
+
+    /// Starts the background health monitor that keeps `EngineStatus` current:
+    /// a dead process becomes `Crashed`; too many missed heartbeats in a row,
+    /// or a failing health check, `Degraded`. Each health ping's round trip
+    /// goes into the latency window, and every `METRICS_EVERY` health checks
+    /// the process tree's CPU and memory are published.
+    pub fn start_health_monitor(&mut self) {
+        if self.monitor_started {
+            return;
+        }
+        self.monitor_started = true;
+
+        let process = Arc::clone(&self.process);
+        let status = self.status.clone();
+        let latency = Arc::clone(&self.readings.latency);
+        let heartbeat_config = Arc::clone(&self.heartbeat_config);
+        let heartbeat = Arc::clone(&self.readings.heartbeat);
+        // Read on every beat: a restart may move the engine to another port
+        let port = self.port.clone();
+
+        std::thread::spawn(move || {
+            let client = http::blocking_engine();
+            let mut sampler = ProcessSampler::default();
+            let mut checks = 0u32;
+            let mut last_check = Instant::now();
+            let mut healthy = true;
+
+            loop {
+                let config = heartbeat_config.lock().unwrap().clone();
+                std::thread::sleep(config.interval());
+
+                // The engine itself was dropped (e.g. its project closed)
+                if Arc::strong_count(&process) == 1 {
+                    break;
+                }
+
+                // Only a running engine is supervised; startup and shutdown
+                // manage their own transitions
+                if !matches!(status.get(), EngineStatus::Ready | EngineStatus::Degraded) {
+                    heartbeat.lock().unwrap().reset();
+                    healthy = true;
+                    last_check = Instant::now();
+                    continue;
+                }
+
+                let pid = match process.lock().unwrap().as_mut() {
+                    Some(process) => match process.try_wait() {
+                        Ok(Some(_)) => None,
+                        _ => Some(process.id()),
+                    },
+                    None => None,
+                };
+
+                let Some(pid) = pid else {
+                    eprintln!("[ERROR] Compute engine process exited unexpectedly");
+                    status.set(EngineStatus::Crashed);
+                    continue;
+                };
+
+                // Any HTTP answer proves the event loop is responsive, so an
+                // engine predating `/heartbeat` still passes with a 404
+                let started = Instant::now();
+                let answered = client.get(port.url("/heartbeat")).timeout(config.timeout()).send().is_ok();
+                let alive = {
+                    let mut state = heartbeat.lock().unwrap();
+                    if state.record(answered.then(|| started.elapsed()), &config) {
+                        if state.consecutive_misses == config.max_misses {
+                            eprintln!(
+                                "[WARNING] Compute engine missed {} heartbeats in a row",
+                                state.consecutive_misses
+                            );
+                        }
+                        status.heartbeat(state.clone());
+                    }
+                    state.alive
+                };
+
+                if last_check.elapsed() >= SUPERVISOR_INTERVAL {
+                    last_check = Instant::now();
+
+                    checks += 1;
+                    if checks == METRICS_EVERY {
+                        checks = 0;
+                        if let Some(usage) = sampler.sample(pid) {
+                            status.publish_metrics(pid, usage);
+                        }
+                    }
+
+                    let started = Instant::now();
+                    healthy = matches!(client.get(port.url("/health")).timeout(ENGINE_REQUEST_TIMEOUT).send(), Ok(response) if response.status().is_success());
+                    latency.lock().unwrap().record(healthy.then(|| started.elapsed()));
+                }
+
+                status.set(if healthy && alive { EngineStatus::Ready } else { EngineStatus::Degraded });
+            }
+        });
+    }
+
+    pub fn restart(&mut self) -> Result<()> {
+        println!("[NOVEM] Restarting FastAPI server...");
+        
+        self.stop()?;
+        std::thread::sleep(Duration::from_secs(2));
+        
+        if let Some(source) = self.source.clone() {
+            self.start_fastapi_server(source)?;
+        } else {
+            return Err(anyhow::anyhow!("Cannot restart: compute engine path not set"));
+        }
+        
+        Ok(())
+    }
+
+    /// Stops the engine, giving it a chance to close DuckDB cleanly first as
+    /// laid out by its `ShutdownPolicy`. The whole process tree goes down,
+    /// including reload and worker subprocesses.
+    pub fn stop(&mut self) -> Result<()> {
+        println!("[NOVEM] Stopping FastAPI server...");
+        self.status.set(EngineStatus::Stopped);
+        
+        let mut process_lock = self.process.lock().unwrap();
+        
+        if let Some(mut process) = process_lock.take() {
+            let started = Instant::now();
+            let outcome = self.stop_gracefully(&mut process)?;
+
+            // Kills the tree if the engine hung, or whatever outlived it if not
+            process.kill().context("Failed to kill FastAPI process tree")?;
+            if outcome == ShutdownOutcome::Killed {
+                println!("[NOVEM] FastAPI server killed");
+            }
+            self.status.shutdown(outcome, started.elapsed());
+        }
+        
+        Ok(())
+    }
+
+    fn stop_gracefully(&self, process: &mut ProcessTree) -> Result<ShutdownOutcome> {
+        if process.try_wait()?.is_some() {
+            println!("[NOVEM] FastAPI server already exited");
+            return Ok(ShutdownOutcome::AlreadyExited);
+        }
+
+        let shutdown_timeout = Duration::from_secs(self.shutdown.shutdown_timeout_secs);
+        if self.request_shutdown() {
+            if Self::wait_for_exit(process, shutdown_timeout)? {
+                println!("[NOVEM] FastAPI server stopped gracefully");
+                return Ok(ShutdownOutcome::Graceful);
+            }
+            println!(
+                "[WARNING] FastAPI server did not exit within {:?}, escalating",
+                shutdown_timeout
+            );
+        }
+
+        let grace_period = Duration::from_secs(self.shutdown.grace_period_secs);
+        process.terminate();
+        if Self::wait_for_exit(process, grace_period)? {
+            println!("[NOVEM] FastAPI server stopped after SIGTERM");
+            return Ok(ShutdownOutcome::Terminated);
+        }
+        println!("[WARNING] FastAPI server still running {:?} after SIGTERM", grace_period);
+
+        Ok(ShutdownOutcome::Killed)
+    }
+
+    fn request_shutdown(&self) -> bool {
+        let url = format!("http://127.0.0.1:{}/shutdown", self.port.get());
+
+        match http::blocking_engine().post(&url).timeout(ENGINE_REQUEST_TIMEOUT).send() {
+            Ok(response) => response.status().is_success(),
+            Err(e) => {
+                println!("[NOVEM] Graceful shutdown request failed: {}", e);
+                false
+            }
+        }
+    }
+
+    fn wait_for_exit(process: &mut ProcessTree, timeout: Duration) -> Result<bool> {
+        let start = Instant::now();
+
+        while start.elapsed() < timeout {
+            if process.try_wait()?.is_some() {
+                return Ok(true);
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+
+        Ok(process.try_wait()?.is_some())
+    }
+}
+
+/// Copies the engine's stderr to ours line by line, keeping the last lines
+/// in `tail` for diagnosing a failed start and remembering whether
+/// uvicorn failed to bind its port (EADDRINUSE, or WSAEADDRINUSE on Windows).
+fn forward_stderr(stderr: ChildStderr, tail: StderrTail) -> JoinHandle<bool> {
+    std::thread::spawn(move || {
+        let mut port_in_use = false;
+        for line in BufReader::new(stderr).lines().map_while(|line| line.ok()) {
+            let lower = line.to_lowercase();
+            if lower.contains("address already in use") || lower.contains("errno 10048") {
+                port_in_use = true;
+            }
+            tail.push(&line);
+            let _ = writeln!(std::io::stderr(), "{}", line);
+        }
+        port_in_use
+    })
+}
+
+/// Kills an engine's process tree without taking the engine's lock, which a
+/// hung startup holds for its whole readiness timeout. Holds only a weak
+/// reference, so it doesn't keep a dropped engine's health monitor alive.
+#[derive(Clone)]
+pub struct EngineKillSwitch {
+    process: Weak<Mutex<Option<ProcessTree>>>,
+    status: EngineStatusCell,
+}
+
+impl EngineKillSwitch {
+    /// PID of the running process. `None` while a stop or kill holds the
+    /// process, rather than waiting for it to finish.
+    pub fn pid(&self) -> Option<u32> {
+        let process = self.process.upgrade()?;
+        let process = process.try_lock().ok()?;
+        process.as_ref().map(|process| process.id())
+    }
+
+    /// Returns whether there was a process to kill.
+    pub fn kill(&self) -> Result<bool> {
+        let Some(process) = self.process.upgrade() else {
+            return Ok(false);
+        };
+        let Some(mut process) = process.lock().unwrap().take() else {
+            return Ok(false);
+        };
+
+        process.kill().context("Failed to kill FastAPI process tree")?;
+        println!("[NOVEM] FastAPI process tree force-killed (PID: {})", process.id());
+        self.status.set(EngineStatus::Stopped);
+        Ok(true)
+    }
+}
+
+/// What an engine and its health monitor last measured. Shared with the
+/// engine, so it can be read while a start holds the engine's lock.
+#[derive(Clone)]
+pub struct EngineReadings {
+    project_id: Option<i64>,
+    latency: Arc<Mutex<LatencyWindow>>,
+    heartbeat: Arc<Mutex<HeartbeatState>>,
+    warmup_ms: Arc<Mutex<Option<u64>>>,
+    startup_failure: Arc<Mutex<Option<StartupDiagnosis>>>,
+}
+
+impl EngineReadings {
+    fn new(project_id: Option<i64>) -> Self {
+        Self {
+            project_id,
+            latency: Arc::new(Mutex::new(LatencyWindow::default())),
+            heartbeat: Arc::new(Mutex::new(HeartbeatState::new(project_id))),
+            warmup_ms: Arc::new(Mutex::new(None)),
+            startup_failure: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Round trips of the health monitor's recent health pings.
+    pub fn latency_stats(&self) -> LatencyStats {
+        self.latency.lock().unwrap().stats(self.project_id)
+    }
+
+    pub fn heartbeat(&self) -> HeartbeatState {
+        self.heartbeat.lock().unwrap().clone()
+    }
+
+    /// How long the last start's warm-up took, if it ran and succeeded.
+    pub fn warmup_ms(&self) -> Option<u64> {
+        *self.warmup_ms.lock().unwrap()
+    }
+
+    /// Why the last start failed; cleared when the next one begins.
+    pub fn startup_failure(&self) -> Option<StartupDiagnosis> {
+        self.startup_failure.lock().unwrap().clone()
+    }
+}
+
+/// The port an engine listens on, shared with its health monitor so the
+/// monitor follows the engine when a restart moves it.
+#[derive(Clone)]
+struct EnginePort(Arc<AtomicU16>);
+
+impl EnginePort {
+    fn new(port: u16) -> Self {
+        Self(Arc::new(AtomicU16::new(port)))
+    }
+
+    fn get(&self) -> u16 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Moves to the first free port in `range` other than the current one.
+    fn move_within(&self, range: Option<RangeInclusive<u16>>) -> Option<u16> {
+        let port = ports::free_ports(range?, &[self.get()], 1).pop()?;
+        self.0.store(port, Ordering::Relaxed);
+        Some(port)
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("http://127.0.0.1:{}{}", self.get(), path)
+    }
+}
+
+impl Drop for Supervisor {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_monitor_follows_port_move() {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let taken = listener.local_addr().unwrap().port();
+        let port = EnginePort::new(taken);
+        let monitor = port.clone();
+        assert_eq!(monitor.url("/health"), format!("http://127.0.0.1:{}/health", taken));
+
+        // A restart finds its port taken and moves within its range
+        let moved = port.move_within(Some(taken.saturating_sub(50)..=taken.saturating_add(50))).unwrap();
+        assert_ne!(moved, taken);
+        assert_eq!(monitor.get(), moved);
+        assert_eq!(monitor.url("/health"), format!("http://127.0.0.1:{}/health", moved));
+
+        // Without a range there is nowhere to go
+        assert_eq!(port.move_within(None), None);
+        assert_eq!(monitor.get(), moved);
+    }
+}
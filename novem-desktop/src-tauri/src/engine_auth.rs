@@ -0,0 +1,46 @@
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use reqwest::header::{HeaderMap, HeaderValue};
+use std::sync::OnceLock;
+
+/// Header every request to a local engine must carry.
+pub const TOKEN_HEADER: &str = "X-Novem-Engine-Token";
+
+/// Environment variable the engine reads its expected token from.
+pub const TOKEN_ENV: &str = "COMPUTE_ENGINE_AUTH_TOKEN";
+
+const TOKEN_LENGTH: usize = 48;
+
+static TOKEN: OnceLock<String> = OnceLock::new();
+
+/// Secret shared with every engine this app spawns, generated once per
+/// launch. The engines listen on loopback, which any local process can
+/// reach; only this app knows the token.
+pub fn token() -> &'static str {
+    TOKEN.get_or_init(|| {
+        rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(TOKEN_LENGTH)
+            .map(char::from)
+            .collect()
+    })
+}
+
+/// Default headers for HTTP clients talking to a local engine.
+pub fn headers() -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(TOKEN_HEADER, HeaderValue::from_static(token()));
+    headers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_is_stable_per_launch() {
+        assert_eq!(token().len(), TOKEN_LENGTH);
+        assert_eq!(token(), token());
+        assert_eq!(headers().get(TOKEN_HEADER).unwrap(), token());
+    }
+}
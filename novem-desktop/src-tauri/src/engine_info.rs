@@ -0,0 +1,142 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::dependencies::{InstallTool, DEPENDENCY_TOOL_SETTING};
+use crate::engine::EngineStatus;
+use crate::AppState;
+
+/// How long to wait for the default engine before giving up on the
+/// startup collection; `get_engine_info` collects on demand after that.
+const STARTUP_WAIT: Duration = Duration::from_secs(120);
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineInfo {
+    pub python_version: String,
+    pub python_implementation: String,
+    pub python_executable: String,
+    pub platform: String,
+    /// fastapi, uvicorn, pydantic, starlette
+    pub server_packages: BTreeMap<String, String>,
+    /// Installed scientific libraries only; missing ones are omitted
+    pub scientific_packages: BTreeMap<String, String>,
+    pub git_revision: Option<String>,
+    /// How long the running engine's warm-up call took
+    #[serde(default)]
+    pub warmup_ms: Option<u64>,
+    /// What installed the environment's packages, if the app did
+    #[serde(default)]
+    pub dependency_tool: Option<InstallTool>,
+    pub collected_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct EngineInfoResponse {
+    python_version: String,
+    python_implementation: String,
+    python_executable: String,
+    platform: String,
+    server_packages: BTreeMap<String, String>,
+    scientific_packages: BTreeMap<String, String>,
+}
+
+/// Revision of the compute_engine sources: from git in a checkout, or a
+/// REVISION file written at bundle time.
+fn git_revision(compute_engine_dir: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .current_dir(compute_engine_dir)
+        .output();
+
+    if let Ok(output) = output {
+        if output.status.success() {
+            let revision = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if !revision.is_empty() {
+                return Some(revision);
+            }
+        }
+    }
+
+    std::fs::read_to_string(compute_engine_dir.join("REVISION"))
+        .ok()
+        .map(|revision| revision.trim().to_string())
+        .filter(|revision| !revision.is_empty())
+}
+
+/// Asks the default engine for its interpreter and package versions.
+pub async fn collect(state: &AppState) -> Result<EngineInfo> {
+    let base_url = state.engines.base_url(None)?;
+    let response: EngineInfoResponse = state
+        .http
+        .engine()
+        .get(format!("{}/health/info", base_url))
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    let git_revision = match state.engines.compute_engine_dir() {
+        Some(dir) => {
+            let dir = dir.to_path_buf();
+            tauri::async_runtime::spawn_blocking(move || git_revision(&dir)).await?
+        }
+        None => None,
+    };
+
+    let dependency_tool = state
+        .with_db(|db| db.get_setting(DEPENDENCY_TOOL_SETTING))
+        .ok()
+        .flatten()
+        .and_then(|json| serde_json::from_str(&json).ok());
+
+    Ok(EngineInfo {
+        python_version: response.python_version,
+        python_implementation: response.python_implementation,
+        python_executable: response.python_executable,
+        platform: response.platform,
+        server_packages: response.server_packages,
+        scientific_packages: response.scientific_packages,
+        git_revision,
+        warmup_ms: state.engines.warmup_ms(None),
+        dependency_tool,
+        collected_at: crate::timestamps::now(),
+    })
+}
+
+/// Collects engine info once the default engine is ready and caches it in
+/// `AppState`, so the diagnostics screen opens without waiting on Python.
+pub fn start_collector(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let started = std::time::Instant::now();
+
+        while state.engines.status(None) != EngineStatus::Ready {
+            if started.elapsed() > STARTUP_WAIT {
+                println!("[NOVEM] Engine not ready, skipping engine info collection");
+                return;
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+
+        match collect(&state).await {
+            Ok(info) => {
+                println!(
+                    "[NOVEM] Engine info: Python {}, revision {}",
+                    info.python_version,
+                    info.git_revision.as_deref().unwrap_or("unknown")
+                );
+                if let Ok(mut cached) = state.engine_info.lock() {
+                    *cached = Some(info);
+                }
+            }
+            Err(e) => eprintln!("[ERROR] Failed to collect engine info: {}", e),
+        }
+    });
+}
@@ -0,0 +1,618 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU16, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use tauri::AppHandle;
+
+use crate::admission::{AdmissionConfig, AdmissionController, QueueStatus};
+use crate::database::EngineProfile;
+use crate::heartbeat::{HeartbeatConfig, HeartbeatState};
+use crate::latency::LatencyStats;
+use crate::ports;
+use crate::engine::{EngineKillSwitch, EngineLogLevel, EngineReadings, EngineSource, EngineStatus, EngineStatusCell, LaunchProfile, ReadinessProbe, ShutdownPolicy, Supervisor, DEFAULT_ENGINE_PORT};
+use crate::startup_diagnosis::StartupDiagnosis;
+use crate::tunnels::TunnelManager;
+
+/// Project engines take ports above the shared engine's.
+const PROJECT_PORT_RANGE: std::ops::RangeInclusive<u16> = (DEFAULT_ENGINE_PORT + 1)..=(DEFAULT_ENGINE_PORT + 100);
+
+/// Where the shared engine goes when another program holds its port.
+const DEFAULT_FALLBACK_PORTS: std::ops::RangeInclusive<u16> = (DEFAULT_ENGINE_PORT + 101)..=(DEFAULT_ENGINE_PORT + 200);
+
+/// Setting key persisting whether engines run with uvicorn `--reload`.
+pub const DEV_MODE_SETTING: &str = "engine.dev_mode";
+
+/// Setting key persisting whether the shared engine waits for the first
+/// compute request instead of starting with the app.
+pub const LAZY_START_SETTING: &str = "engine.lazy_start";
+
+/// Setting key holding the ID of the engine profile engines launch with.
+pub const ACTIVE_PROFILE_SETTING: &str = "engine.active_profile";
+
+/// Setting key holding the startup readiness probe as JSON.
+pub const READINESS_SETTING: &str = "engine.readiness_probe";
+
+/// Setting key holding the shutdown policy as JSON.
+pub const SHUTDOWN_SETTING: &str = "engine.shutdown_policy";
+
+/// Setting key holding the engine log level as JSON.
+pub const LOG_LEVEL_SETTING: &str = "engine.log_level";
+
+/// Upper bound on concurrently running project engines.
+const MAX_PROJECT_ENGINES: usize = 8;
+
+/// An engine plus what is read about it often. A start or restart holds
+/// the engine's lock until the engine answers, so status, port, PID and
+/// readings are read from here instead and never wait on one.
+struct ManagedEngine {
+    engine: Mutex<Supervisor>,
+    status: EngineStatusCell,
+    /// The engine's port as of its last start; it may move off a taken one
+    port: AtomicU16,
+    work_dir: Option<PathBuf>,
+    kill_switch: EngineKillSwitch,
+    readings: EngineReadings,
+}
+
+impl ManagedEngine {
+    fn new(engine: Supervisor) -> Self {
+        Self {
+            status: engine.status().clone(),
+            port: AtomicU16::new(engine.get_port()),
+            work_dir: engine.work_dir().cloned(),
+            kill_switch: engine.kill_switch(),
+            readings: engine.readings(),
+            engine: Mutex::new(engine),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Supervisor> {
+        self.engine.lock().unwrap()
+    }
+
+    fn port(&self) -> u16 {
+        self.port.load(Ordering::Relaxed)
+    }
+
+    /// Records where the engine ended up after a start or restart.
+    fn sync_port(&self, engine: &Supervisor) {
+        self.port.store(engine.get_port(), Ordering::Relaxed);
+    }
+}
+
+type SharedEngine = Arc<ManagedEngine>;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EngineInstance {
+    pub project_id: Option<i64>,
+    pub port: u16,
+    pub pid: Option<u32>,
+    pub status: EngineStatus,
+    pub work_dir: Option<String>,
+}
+
+/// Owns every compute engine process: the shared engine started with the app,
+/// plus one isolated engine per open project, each with its own port and
+/// working directory. Requests are routed by project ID; `None` means the
+/// shared engine.
+pub struct EngineManager {
+    app: AppHandle,
+    compute_engine_dir: Option<PathBuf>,
+    sidecar: Option<PathBuf>,
+    engines_dir: Mutex<PathBuf>,
+    default: SharedEngine,
+    projects: Mutex<HashMap<i64, SharedEngine>>,
+    kill_switches: Mutex<HashMap<Option<i64>, EngineKillSwitch>>,
+    admission_config: Mutex<AdmissionConfig>,
+    admission: Mutex<HashMap<Option<i64>, Arc<AdmissionController>>>,
+    dev_mode: AtomicBool,
+    lazy_start: AtomicBool,
+    /// Set while the shared engine's start waits for its first request
+    deferred: Mutex<bool>,
+    active_profile: Mutex<Option<EngineProfile>>,
+    readiness: Mutex<ReadinessProbe>,
+    shutdown: Mutex<ShutdownPolicy>,
+    log_level: Mutex<EngineLogLevel>,
+    heartbeat: Arc<Mutex<HeartbeatConfig>>,
+    tunnels: TunnelManager,
+}
+
+impl EngineManager {
+    pub fn new(app: AppHandle, compute_engine_dir: Option<PathBuf>, sidecar: Option<PathBuf>, data_dir: &Path) -> Self {
+        let status = EngineStatusCell::new(app.clone(), None);
+        let mut default = Supervisor::new(status, DEFAULT_ENGINE_PORT, None);
+        default.set_port_range(DEFAULT_FALLBACK_PORTS);
+        let heartbeat = Arc::new(Mutex::new(HeartbeatConfig::default()));
+        default.share_heartbeat_config(Arc::clone(&heartbeat));
+        let kill_switches = HashMap::from([(None, default.kill_switch())]);
+        let tunnels = TunnelManager::new(app.clone());
+
+        Self {
+            app,
+            compute_engine_dir,
+            sidecar,
+            engines_dir: Mutex::new(data_dir.join("engines")),
+            default: Arc::new(ManagedEngine::new(default)),
+            projects: Mutex::new(HashMap::new()),
+            kill_switches: Mutex::new(kill_switches),
+            admission_config: Mutex::new(AdmissionConfig::default()),
+            admission: Mutex::new(HashMap::new()),
+            dev_mode: AtomicBool::new(false),
+            lazy_start: AtomicBool::new(false),
+            deferred: Mutex::new(false),
+            active_profile: Mutex::new(None),
+            readiness: Mutex::new(ReadinessProbe::default()),
+            shutdown: Mutex::new(ShutdownPolicy::default()),
+            log_level: Mutex::new(EngineLogLevel::default()),
+            heartbeat,
+            tunnels,
+        }
+    }
+
+    /// Starts the shared engine. Failures are reported through its status.
+    pub fn start_default(&self, env: BTreeMap<String, String>) {
+        let mut engine = self.default.lock();
+        engine.set_env(env);
+        engine.set_reload(self.dev_mode());
+        engine.set_log_level(self.log_level());
+        engine.set_launch_profile(self.launch_profile());
+        engine.set_readiness_probe(self.readiness_probe());
+
+        let source = match self.engine_source() {
+            Some(source) => source,
+            None => {
+                engine.status().set(EngineStatus::NotFound);
+                eprintln!("[ERROR] Could not find compute_engine directory");
+                eprintln!("[WARNING] Application will run with limited functionality");
+                return;
+            }
+        };
+
+        println!("[NOVEM] Starting embedded compute engine...");
+
+        let started = engine.start_fastapi_server(source);
+        self.default.sync_port(&engine);
+        match started {
+            Ok(_) => {
+                println!("[NOVEM] Embedded compute engine started successfully");
+                println!("[NOVEM] FastAPI available at: http://127.0.0.1:{}", engine.get_port());
+            }
+            Err(e) => {
+                eprintln!("[ERROR] Failed to start compute engine: {}", e);
+                eprintln!("[WARNING] Application will run with limited functionality");
+            }
+        }
+        engine.start_health_monitor();
+    }
+
+    /// Leaves the shared engine stopped at launch so the window shows
+    /// right away; the first compute request starts it.
+    pub fn defer_default(&self) {
+        *self.deferred.lock().unwrap() = true;
+        println!("[NOVEM] Compute engine will start on first request");
+    }
+
+    /// Whether the shared engine is still waiting for its first request.
+    pub fn is_deferred(&self) -> bool {
+        *self.deferred.lock().unwrap()
+    }
+
+    /// Starts the shared engine if its start was deferred and nothing has
+    /// started it since. Only the first caller starts it; the flag is
+    /// released before the start, so others watch its status instead of
+    /// waiting here. Returns whether this call started it.
+    pub fn start_deferred(&self, env: impl FnOnce() -> BTreeMap<String, String>) -> bool {
+        let deferred = std::mem::take(&mut *self.deferred.lock().unwrap());
+        if !deferred || self.status(None) != EngineStatus::Stopped {
+            return false;
+        }
+
+        println!("[NOVEM] First compute request, starting deferred engine");
+        self.start_default(env());
+        true
+    }
+
+    /// Stops the shared engine without forgetting it; `start_default`
+    /// brings it back.
+    pub fn stop_default(&self) -> Result<()> {
+        self.stop_engine(&self.default)
+    }
+
+    pub fn dev_mode(&self) -> bool {
+        self.dev_mode.load(Ordering::Relaxed)
+    }
+
+    /// Whether engines watch their sources and reload on change. Takes
+    /// effect when an engine is next started or restarted.
+    pub fn set_dev_mode(&self, enabled: bool) {
+        self.dev_mode.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn lazy_start(&self) -> bool {
+        self.lazy_start.load(Ordering::Relaxed)
+    }
+
+    /// Whether the shared engine is started on first use rather than with
+    /// the app. Takes effect on the next launch.
+    pub fn set_lazy_start(&self, enabled: bool) {
+        self.lazy_start.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn readiness_probe(&self) -> ReadinessProbe {
+        self.readiness.lock().unwrap().clone()
+    }
+
+    /// Applies to engines started from now on.
+    pub fn set_readiness_probe(&self, readiness: ReadinessProbe) {
+        *self.readiness.lock().unwrap() = readiness;
+    }
+
+    pub fn shutdown_policy(&self) -> ShutdownPolicy {
+        self.shutdown.lock().unwrap().clone()
+    }
+
+    /// Applies to every stop from now on, running engines included.
+    pub fn set_shutdown_policy(&self, shutdown: ShutdownPolicy) {
+        *self.shutdown.lock().unwrap() = shutdown;
+    }
+
+    pub fn log_level(&self) -> EngineLogLevel {
+        *self.log_level.lock().unwrap()
+    }
+
+    /// Level engines are started or restarted with from now on; running
+    /// ones are switched separately, over HTTP.
+    pub fn set_log_level(&self, log_level: EngineLogLevel) {
+        *self.log_level.lock().unwrap() = log_level;
+    }
+
+    pub fn heartbeat_config(&self) -> HeartbeatConfig {
+        self.heartbeat.lock().unwrap().clone()
+    }
+
+    /// Picked up by every engine's health monitor on its next beat.
+    pub fn set_heartbeat_config(&self, config: HeartbeatConfig) {
+        *self.heartbeat.lock().unwrap() = config;
+    }
+
+    /// Stops an engine under the current shutdown policy.
+    fn stop_engine(&self, engine: &SharedEngine) -> Result<()> {
+        let mut engine = engine.lock();
+        engine.set_shutdown_policy(self.shutdown_policy());
+        engine.stop()
+    }
+
+    pub fn active_profile(&self) -> Option<EngineProfile> {
+        self.active_profile.lock().unwrap().clone()
+    }
+
+    /// Profile engines launch with, `None` for the defaults. Takes effect
+    /// when an engine is next started or restarted.
+    pub fn set_active_profile(&self, profile: Option<EngineProfile>) {
+        *self.active_profile.lock().unwrap() = profile;
+    }
+
+    fn launch_profile(&self) -> LaunchProfile {
+        match &*self.active_profile.lock().unwrap() {
+            Some(profile) => LaunchProfile {
+                python_path: profile.python_path.as_ref().map(PathBuf::from),
+                extra_args: profile.extra_args.clone(),
+                env: profile.env.clone(),
+                working_dir: profile.working_dir.as_ref().map(PathBuf::from),
+            },
+            None => LaunchProfile::default(),
+        }
+    }
+
+    fn engine(&self, project_id: Option<i64>) -> Result<SharedEngine> {
+        match project_id {
+            None => Ok(Arc::clone(&self.default)),
+            Some(project_id) => self
+                .projects
+                .lock()
+                .unwrap()
+                .get(&project_id)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("No compute engine running for project {}", project_id)),
+        }
+    }
+
+    pub fn status(&self, project_id: Option<i64>) -> EngineStatus {
+        match self.engine(project_id) {
+            Ok(engine) => engine.status.get(),
+            Err(_) => EngineStatus::Stopped,
+        }
+    }
+
+    pub fn warmup_ms(&self, project_id: Option<i64>) -> Option<u64> {
+        self.engine(project_id).ok()?.readings.warmup_ms()
+    }
+
+    /// Port serving `project_id`: an open tunnel's local end takes
+    /// precedence over the local engine.
+    pub fn port(&self, project_id: Option<i64>) -> Result<u16> {
+        if let Some(port) = self.tunnels.local_port(project_id) {
+            return Ok(port);
+        }
+        Ok(self.engine(project_id)?.port())
+    }
+
+    pub fn latency_stats(&self, project_id: Option<i64>) -> Result<LatencyStats> {
+        Ok(self.engine(project_id)?.readings.latency_stats())
+    }
+
+    pub fn heartbeat(&self, project_id: Option<i64>) -> Result<HeartbeatState> {
+        Ok(self.engine(project_id)?.readings.heartbeat())
+    }
+
+    pub fn startup_failure(&self, project_id: Option<i64>) -> Option<StartupDiagnosis> {
+        self.engine(project_id).ok()?.readings.startup_failure()
+    }
+
+    pub fn tunnels(&self) -> &TunnelManager {
+        &self.tunnels
+    }
+
+    /// Where a project engine keeps its DuckDB files and scratch data.
+    pub fn work_dir(&self, project_id: i64) -> PathBuf {
+        self.engines_dir.lock().unwrap().join(project_id.to_string())
+    }
+
+    /// Points project engines at another account's data directory. Engines
+    /// already running keep their working directory until restarted.
+    pub fn set_data_dir(&self, data_dir: &Path) {
+        *self.engines_dir.lock().unwrap() = data_dir.join("engines");
+    }
+
+    /// Deletes a project engine's scratch space: temp files, caches and its
+    /// DuckDB database. The engine must already be stopped.
+    pub fn remove_work_dir(&self, project_id: i64) -> Result<()> {
+        let work_dir = self.work_dir(project_id);
+        if work_dir.exists() {
+            std::fs::remove_dir_all(&work_dir)
+                .context(format!("Failed to remove engine working directory {:?}", work_dir))?;
+            println!("[NOVEM] Removed engine working directory {:?}", work_dir);
+        }
+        Ok(())
+    }
+
+    pub fn compute_engine_dir(&self) -> Option<&Path> {
+        self.compute_engine_dir.as_deref()
+    }
+
+    /// The bundled sidecar when there is one, so end users need no Python.
+    /// Dev mode and profiles with their own interpreter run the sources
+    /// instead, as long as they're available.
+    fn engine_source(&self) -> Option<EngineSource> {
+        let needs_sources = self.dev_mode() || self.launch_profile().python_path.is_some();
+
+        match (&self.sidecar, &self.compute_engine_dir) {
+            (_, Some(dir)) if needs_sources => Some(EngineSource::Script(dir.clone())),
+            (Some(sidecar), _) => Some(EngineSource::Sidecar(sidecar.clone())),
+            (None, dir) => dir.clone().map(EngineSource::Script),
+        }
+    }
+
+    /// Admission queue guarding requests to the engine serving `project_id`.
+    pub fn admission(&self, project_id: Option<i64>) -> Arc<AdmissionController> {
+        let config = *self.admission_config.lock().unwrap();
+        let mut admission = self.admission.lock().unwrap();
+
+        Arc::clone(admission.entry(project_id).or_insert_with(|| {
+            Arc::new(AdmissionController::new(self.app.clone(), project_id, config))
+        }))
+    }
+
+    /// Request IDs running on any engine, with the engine's project.
+    pub fn running_requests(&self) -> Vec<(Option<i64>, String)> {
+        self.admission
+            .lock()
+            .unwrap()
+            .iter()
+            .flat_map(|(project_id, controller)| {
+                controller
+                    .running_requests()
+                    .into_iter()
+                    .map(move |request_id| (*project_id, request_id))
+            })
+            .collect()
+    }
+
+    /// Queue of the engine serving `project_id`; empty if nothing has been
+    /// sent to it yet.
+    pub fn queue_status(&self, project_id: Option<i64>) -> QueueStatus {
+        if let Some(controller) = self.admission.lock().unwrap().get(&project_id) {
+            return controller.status();
+        }
+
+        let config = self.admission_config();
+        QueueStatus {
+            project_id,
+            running: 0,
+            queued: 0,
+            max_concurrent: config.max_concurrent,
+            max_queued: config.max_queued,
+            running_requests: Vec::new(),
+        }
+    }
+
+    pub fn admission_config(&self) -> AdmissionConfig {
+        *self.admission_config.lock().unwrap()
+    }
+
+    /// Applies new limits to every engine, including queues already in use.
+    pub fn set_admission_config(&self, config: AdmissionConfig) {
+        *self.admission_config.lock().unwrap() = config;
+
+        for controller in self.admission.lock().unwrap().values() {
+            controller.set_config(config);
+        }
+    }
+
+    /// Base URL of the engine serving `project_id`.
+    pub fn base_url(&self, project_id: Option<i64>) -> Result<String> {
+        Ok(format!("http://127.0.0.1:{}", self.port(project_id)?))
+    }
+
+    /// Starts an isolated engine for a project, or returns the port of the
+    /// one already running.
+    pub fn start_project(&self, project_id: i64, env: BTreeMap<String, String>) -> Result<u16> {
+        let source = self
+            .engine_source()
+            .ok_or_else(|| anyhow::anyhow!("Could not find compute_engine directory"))?;
+
+        let engine = {
+            let mut projects = self.projects.lock().unwrap();
+
+            if let Some(engine) = projects.get(&project_id) {
+                return Ok(engine.port());
+            }
+
+            if projects.len() >= MAX_PROJECT_ENGINES {
+                return Err(anyhow::anyhow!(
+                    "Too many project engines running (limit {}); close a project first",
+                    MAX_PROJECT_ENGINES
+                ));
+            }
+
+            let used: Vec<u16> = projects.values().map(|engine| engine.port()).collect();
+            let port = PROJECT_PORT_RANGE
+                .clone()
+                .find(|port| !used.contains(port) && ports::is_free(*port))
+                .ok_or_else(|| anyhow::anyhow!("No free port available for a project engine"))?;
+
+            let status = EngineStatusCell::new(self.app.clone(), Some(project_id));
+            let mut engine = Supervisor::new(status, port, Some(self.work_dir(project_id)));
+            engine.set_port_range(PROJECT_PORT_RANGE);
+            engine.set_env(env);
+            engine.set_reload(self.dev_mode());
+            engine.set_log_level(self.log_level());
+            engine.set_launch_profile(self.launch_profile());
+            engine.set_readiness_probe(self.readiness_probe());
+            engine.share_heartbeat_config(Arc::clone(&self.heartbeat));
+            self.kill_switches.lock().unwrap().insert(Some(project_id), engine.kill_switch());
+            let engine = Arc::new(ManagedEngine::new(engine));
+
+            // Registered before starting so concurrent calls reuse this instance
+            projects.insert(project_id, Arc::clone(&engine));
+            engine
+        };
+
+        println!("[NOVEM] Starting compute engine for project {}...", project_id);
+
+        let mut guard = engine.lock();
+        let started = guard.start_fastapi_server(source);
+        engine.sync_port(&guard);
+        if let Err(e) = started {
+            drop(guard);
+            self.projects.lock().unwrap().remove(&project_id);
+            self.kill_switches.lock().unwrap().remove(&Some(project_id));
+            return Err(e);
+        }
+        guard.start_health_monitor();
+
+        Ok(guard.get_port())
+    }
+
+    /// Stops and forgets a project's engine. Returns false if none was running.
+    pub fn stop_project(&self, project_id: i64) -> Result<bool> {
+        let engine = self.projects.lock().unwrap().remove(&project_id);
+        self.admission.lock().unwrap().remove(&Some(project_id));
+        self.kill_switches.lock().unwrap().remove(&Some(project_id));
+
+        match engine {
+            Some(engine) => {
+                println!("[NOVEM] Stopping compute engine for project {}...", project_id);
+                self.stop_engine(&engine)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Kills an engine's whole process tree immediately, skipping graceful
+    /// shutdown, for engines stuck starting or stopping. A project engine is
+    /// forgotten afterwards, as with `stop_project`.
+    pub fn force_kill(&self, project_id: Option<i64>) -> Result<bool> {
+        let kill_switch = self.kill_switches.lock().unwrap().get(&project_id).cloned();
+        let killed = match kill_switch {
+            Some(kill_switch) => kill_switch.kill()?,
+            None => false,
+        };
+
+        if let Some(project_id) = project_id {
+            self.projects.lock().unwrap().remove(&project_id);
+            self.admission.lock().unwrap().remove(&Some(project_id));
+            self.kill_switches.lock().unwrap().remove(&Some(project_id));
+        }
+
+        Ok(killed)
+    }
+
+    /// Restarts an engine with a freshly loaded environment.
+    pub fn restart(&self, project_id: Option<i64>, env: BTreeMap<String, String>) -> Result<()> {
+        let managed = self.engine(project_id)?;
+        let mut engine = managed.lock();
+        engine.set_env(env);
+        engine.set_reload(self.dev_mode());
+        engine.set_log_level(self.log_level());
+        engine.set_launch_profile(self.launch_profile());
+        engine.set_readiness_probe(self.readiness_probe());
+        engine.set_shutdown_policy(self.shutdown_policy());
+        if let Some(source) = self.engine_source() {
+            engine.set_source(source);
+        }
+        let restarted = engine.restart();
+        managed.sync_port(&engine);
+        restarted
+    }
+
+    pub fn list(&self) -> Vec<EngineInstance> {
+        let mut engines = vec![Arc::clone(&self.default)];
+        engines.extend(self.projects.lock().unwrap().values().cloned());
+
+        let mut instances: Vec<EngineInstance> = engines
+            .iter()
+            .map(|engine| EngineInstance {
+                project_id: engine.status.project_id(),
+                port: engine.port(),
+                pid: engine.kill_switch.pid(),
+                status: engine.status.get(),
+                work_dir: engine.work_dir.as_ref().map(|dir| dir.to_string_lossy().to_string()),
+            })
+            .collect();
+
+        instances.sort_by_key(|instance| instance.project_id);
+        instances
+    }
+
+    /// Stops every project engine, leaving the shared one running; used when
+    /// switching to another account, whose project IDs mean other projects.
+    pub fn stop_projects(&self) {
+        let project_ids: Vec<i64> = self.projects.lock().unwrap().keys().copied().collect();
+        for project_id in project_ids {
+            if let Err(e) = self.stop_project(project_id) {
+                eprintln!("[ERROR] Failed to stop engine for project {}: {}", project_id, e);
+            }
+        }
+    }
+
+    /// Stops every engine; used when the app closes.
+    pub fn stop_all(&self) {
+        self.tunnels.close_all();
+
+        let projects: Vec<(i64, SharedEngine)> = self.projects.lock().unwrap().drain().collect();
+
+        for (project_id, engine) in projects {
+            if let Err(e) = self.stop_engine(&engine) {
+                eprintln!("[ERROR] Failed to stop engine for project {}: {}", project_id, e);
+            }
+        }
+
+        if let Err(e) = self.stop_engine(&self.default) {
+            eprintln!("[ERROR] Failed to stop compute engine: {}", e);
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use std::collections::HashSet;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::events::{AppEvent, EventBus};
+use crate::AppState;
+
+/// Engine metrics older than this (7 days) are pruned.
+const RETENTION_SECS: i64 = 7 * 24 * 60 * 60;
+
+/// Pruning runs every this many recorded samples (about an hour with one
+/// engine running).
+const PRUNE_EVERY: u32 = 360;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ProcessUsage {
+    pub cpu_percent: f64,
+    pub memory_mb: f64,
+    pub process_count: u32,
+}
+
+/// Reads CPU and memory of a process and all its descendants (uvicorn's
+/// reloader and workers). CPU is measured between refreshes, so the first
+/// reading of a new process is 0.
+#[derive(Default)]
+pub struct ProcessSampler {
+    system: System,
+}
+
+impl ProcessSampler {
+    pub fn sample(&mut self, pid: u32) -> Option<ProcessUsage> {
+        self.system.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing().with_cpu().with_memory(),
+        );
+
+        let root = Pid::from_u32(pid);
+        self.system.process(root)?;
+
+        // Parents can be listed after their children, so repeat until no
+        // new descendants turn up
+        let mut tree = HashSet::from([root]);
+        loop {
+            let before = tree.len();
+            for (pid, process) in self.system.processes() {
+                if process.parent().is_some_and(|parent| tree.contains(&parent)) {
+                    tree.insert(*pid);
+                }
+            }
+            if tree.len() == before {
+                break;
+            }
+        }
+
+        let mut usage = ProcessUsage {
+            cpu_percent: 0.0,
+            memory_mb: 0.0,
+            process_count: 0,
+        };
+        // Linux lists threads as processes too; they share their owner's memory
+        for process in tree.iter().filter_map(|pid| self.system.process(*pid)) {
+            if process.thread_kind().is_some() {
+                continue;
+            }
+            usage.cpu_percent += process.cpu_usage() as f64;
+            usage.memory_mb += process.memory() as f64 / (1024.0 * 1024.0);
+            usage.process_count += 1;
+        }
+        Some(usage)
+    }
+}
+
+/// Stores the metrics engine supervisors publish on the event bus and
+/// prunes old ones. Subscribes before returning so no sample is missed.
+pub fn start_recorder(app: AppHandle) {
+    let mut events = app.state::<EventBus>().subscribe();
+
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let mut recorded = 0u32;
+
+        loop {
+            let metric = match events.recv().await {
+                Ok(record) => match record.event {
+                    AppEvent::EngineMetrics(metric) => metric,
+                    _ => continue,
+                },
+                Err(RecvError::Lagged(skipped)) => {
+                    eprintln!("[WARNING] Engine metrics recorder fell behind; dropped {} events", skipped);
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+
+            if let Err(e) = state.with_db(|db| db.add_engine_metric(&metric)) {
+                eprintln!("[ERROR] Failed to record engine metrics: {}", e);
+            }
+
+            recorded += 1;
+            if recorded == PRUNE_EVERY {
+                recorded = 0;
+                let before = metric.sampled_at - RETENTION_SECS;
+                if let Err(e) = state.with_db(|db| db.prune_engine_metrics(before)) {
+                    eprintln!("[ERROR] Failed to prune engine metrics: {}", e);
+                }
+            }
+        }
+    });
+}
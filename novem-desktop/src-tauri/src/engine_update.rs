@@ -0,0 +1,268 @@
+use anyhow::{Context, Result};
+use minisign_verify::{PublicKey, Signature};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::i18n::tr;
+use crate::recovery::{self, Operation};
+use crate::AppState;
+
+/// Setting key holding where engine bundles come from, as JSON.
+pub const BUNDLE_UPDATE_SETTING: &str = "engine.bundle_update";
+
+/// Bundles ship sources only; these environments are carried over from the
+/// current engine directory.
+const ENV_DIRS: &[&str] = &[".venv", "venv"];
+
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BundleUpdateConfig {
+    /// The bundle zip. Its SHA-256 is read from `<url>.sha256` and its
+    /// signature from `<url>.minisig`
+    pub url: Option<String>,
+    /// Minisign public key (base64). Bundles must be signed with it, so
+    /// updates are refused until one is set
+    pub public_key: Option<String>,
+}
+
+impl BundleUpdateConfig {
+    pub fn validate(&self) -> Result<()> {
+        if let Some(url) = &self.url {
+            let parsed = reqwest::Url::parse(url).map_err(|_| anyhow::anyhow!(tr!("engine-update-url-invalid", url = url.as_str())))?;
+            if !matches!(parsed.scheme(), "http" | "https") {
+                return Err(anyhow::anyhow!(tr!("engine-update-url-invalid", url = url.as_str())));
+            }
+        }
+        if let Some(key) = &self.public_key {
+            PublicKey::from_base64(key.trim()).map_err(|_| anyhow::anyhow!(tr!("engine-update-key-invalid")))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BundleUpdate {
+    pub url: String,
+    pub size_bytes: u64,
+    pub sha256: String,
+    pub engine_dir: String,
+}
+
+/// A downloaded bundle whose checksum and signature matched.
+pub struct VerifiedBundle {
+    pub bytes: Vec<u8>,
+    pub sha256: String,
+}
+
+async fn fetch(client: &Client, url: &str) -> Result<reqwest::Response> {
+    client
+        .get(url)
+        .timeout(DOWNLOAD_TIMEOUT)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .context(format!("Failed to download {}", url))
+}
+
+fn public_key(config: &BundleUpdateConfig) -> Result<PublicKey> {
+    let key = config.public_key.as_deref().ok_or_else(|| anyhow::anyhow!(tr!("engine-update-key-missing")))?;
+    PublicKey::from_base64(key.trim()).map_err(|_| anyhow::anyhow!(tr!("engine-update-key-invalid")))
+}
+
+/// Downloads the bundle at `url` with its checksum and signature files.
+/// Without a configured public key nothing is downloaded.
+pub async fn download(client: &Client, config: &BundleUpdateConfig, url: &str) -> Result<VerifiedBundle> {
+    public_key(config)?;
+    let bytes = fetch(client, url).await?.bytes().await?.to_vec();
+    let checksum = fetch(client, &format!("{}.sha256", url)).await?.text().await?;
+    let signature = fetch(client, &format!("{}.minisig", url)).await?.text().await?;
+    verify(config, bytes, &checksum, &signature)
+}
+
+/// Checks a bundle against its checksum file and minisign signature.
+fn verify(config: &BundleUpdateConfig, bytes: Vec<u8>, checksum: &str, signature: &str) -> Result<VerifiedBundle> {
+    // sha256sum format: the digest, optionally followed by the file name
+    let expected = checksum.split_whitespace().next().unwrap_or_default().to_lowercase();
+    let actual: String = Sha256::digest(&bytes).iter().map(|byte| format!("{:02x}", byte)).collect();
+    if expected != actual {
+        return Err(anyhow::anyhow!(tr!(
+            "engine-update-checksum-mismatch",
+            expected = expected.as_str(),
+            actual = actual.as_str()
+        )));
+    }
+
+    let key = public_key(config)?;
+    Signature::decode(signature)
+        .and_then(|signature| key.verify(&bytes, &signature, false))
+        .map_err(|_| anyhow::anyhow!(tr!("engine-update-signature-invalid")))?;
+
+    Ok(VerifiedBundle { bytes, sha256: actual })
+}
+
+fn sibling(engine_dir: &Path, suffix: &str) -> Result<PathBuf> {
+    let name = engine_dir
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("Invalid engine directory {:?}", engine_dir))?;
+    Ok(engine_dir.with_file_name(format!("{}.{}", name.to_string_lossy(), suffix)))
+}
+
+/// Refuses to overwrite engine sources that live in a development checkout.
+pub fn check_updatable(engine_dir: &Path) -> Result<()> {
+    if engine_dir.parent().is_some_and(|parent| parent.join(".git").exists()) {
+        return Err(anyhow::anyhow!(tr!("engine-update-dev-checkout")));
+    }
+    Ok(())
+}
+
+/// Extracts a bundle next to the engine directory (so the swap is a rename
+/// on one filesystem) and returns where. A single top-level folder in the
+/// zip is unwrapped.
+pub fn stage(bytes: &[u8], engine_dir: &Path) -> Result<PathBuf> {
+    let staging_dir = sibling(engine_dir, &format!("update-{}", uuid::Uuid::new_v4()))?;
+
+    let extracted = zip::ZipArchive::new(Cursor::new(bytes))
+        .and_then(|mut archive| archive.extract_unwrapped_root_dir(&staging_dir, zip::read::root_dir_common_filter))
+        .map_err(|e| anyhow::anyhow!(tr!("engine-update-invalid-bundle", error = e.to_string())))
+        .and_then(|_| {
+            if staging_dir.join("main.py").is_file() {
+                Ok(())
+            } else {
+                Err(anyhow::anyhow!(tr!("engine-update-invalid-bundle", error = "main.py is missing")))
+            }
+        });
+
+    if let Err(e) = extracted {
+        let _ = std::fs::remove_dir_all(&staging_dir);
+        return Err(e);
+    }
+    Ok(staging_dir)
+}
+
+/// Replaces the engine directory with a staged bundle under a journal
+/// entry. Engines must be stopped, since Windows can't move a directory a
+/// running process works in.
+pub fn swap(state: &AppState, engine_dir: &Path, staging_dir: &Path) -> Result<()> {
+    let backup_dir = sibling(engine_dir, "previous")?;
+    // Left behind when a previous update couldn't delete it
+    if backup_dir.exists() {
+        std::fs::remove_dir_all(&backup_dir).context(format!("Failed to remove {:?}", backup_dir))?;
+    }
+
+    let operation = Operation::UpdateEngine {
+        engine_dir: engine_dir.to_string_lossy().to_string(),
+        staging_dir: staging_dir.to_string_lossy().to_string(),
+        backup_dir: backup_dir.to_string_lossy().to_string(),
+    };
+    let journal = recovery::begin(state, &operation);
+
+    let result = std::fs::rename(engine_dir, &backup_dir)
+        .context(format!("Failed to move {:?} aside", engine_dir))
+        .and_then(|_| {
+            recovery::advance(state, journal, "moved_aside", &operation);
+            carry_over_envs(&backup_dir, staging_dir)?;
+            std::fs::rename(staging_dir, engine_dir).context(format!("Failed to move {:?} into place", staging_dir))
+        });
+
+    if let Err(e) = result {
+        if let Err(e) = restore(engine_dir, staging_dir, &backup_dir) {
+            eprintln!("[ERROR] Failed to restore the previous compute engine: {}", e);
+        }
+        recovery::finish(state, journal, "rolled_back");
+        return Err(e);
+    }
+
+    recovery::finish(state, journal, "completed");
+    if let Err(e) = std::fs::remove_dir_all(&backup_dir) {
+        eprintln!("[WARNING] Failed to remove previous compute engine {:?}: {}", backup_dir, e);
+    }
+    Ok(())
+}
+
+fn carry_over_envs(from: &Path, to: &Path) -> Result<()> {
+    for name in ENV_DIRS {
+        let (source, dest) = (from.join(name), to.join(name));
+        if source.is_dir() && !dest.exists() {
+            std::fs::rename(&source, &dest).context(format!("Failed to move {:?}", source))?;
+        }
+    }
+    Ok(())
+}
+
+/// Puts the previous engine directory back if it was moved aside, with any
+/// environment already carried into the staged bundle, and deletes the
+/// staged bundle.
+pub fn restore(engine_dir: &Path, staging_dir: &Path, backup_dir: &Path) -> Result<()> {
+    if !engine_dir.exists() && backup_dir.exists() {
+        if staging_dir.exists() {
+            carry_over_envs(staging_dir, backup_dir)?;
+        }
+        std::fs::rename(backup_dir, engine_dir).context(format!("Failed to restore {:?}", backup_dir))?;
+    }
+    if staging_dir.exists() {
+        std::fs::remove_dir_all(staging_dir).context(format!("Failed to remove {:?}", staging_dir))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_stage_and_restore() {
+        let dir = std::env::temp_dir().join("test_novem_engine_update");
+        let _ = std::fs::remove_dir_all(&dir);
+        let engine_dir = dir.join("compute_engine");
+        std::fs::create_dir_all(engine_dir.join(".venv")).unwrap();
+        std::fs::write(engine_dir.join("main.py"), "old").unwrap();
+
+        let mut bundle = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        bundle.start_file("compute_engine/main.py", zip::write::SimpleFileOptions::default()).unwrap();
+        bundle.write_all(b"new").unwrap();
+        let bytes = bundle.finish().unwrap().into_inner();
+
+        let staging_dir = stage(&bytes, &engine_dir).unwrap();
+        assert_eq!(std::fs::read_to_string(staging_dir.join("main.py")).unwrap(), "new");
+
+        // Interrupted after the environment was carried over
+        let backup_dir = sibling(&engine_dir, "previous").unwrap();
+        std::fs::rename(&engine_dir, &backup_dir).unwrap();
+        carry_over_envs(&backup_dir, &staging_dir).unwrap();
+        restore(&engine_dir, &staging_dir, &backup_dir).unwrap();
+
+        assert_eq!(std::fs::read_to_string(engine_dir.join("main.py")).unwrap(), "old");
+        assert!(engine_dir.join(".venv").is_dir());
+        assert!(!staging_dir.exists() && !backup_dir.exists());
+
+        let mut empty = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        empty.start_file("README.md", zip::write::SimpleFileOptions::default()).unwrap();
+        assert!(stage(&empty.finish().unwrap().into_inner(), &engine_dir).is_err());
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_unsigned_bundle_rejected() {
+        let bytes = b"bundle".to_vec();
+        let checksum: String = Sha256::digest(&bytes).iter().map(|byte| format!("{:02x}", byte)).collect();
+
+        // No key configured: nothing is trusted
+        assert!(verify(&BundleUpdateConfig::default(), bytes.clone(), &checksum, "").is_err());
+
+        let config = BundleUpdateConfig {
+            url: None,
+            public_key: Some("RWQAAQIDBAUGBwABAgMEBQYHCAkKCwwNDg8QERITFBUWFxgZGhscHR4f".to_string()),
+        };
+        config.validate().unwrap();
+        assert!(verify(&config, bytes.clone(), &checksum, "").is_err());
+        assert!(verify(&config, bytes, "0000", "").is_err());
+    }
+}
@@ -0,0 +1,187 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::connectivity::ConnectivityStatus;
+use crate::database::{EngineMetric, Notification};
+use crate::guard::AppModeInfo;
+use crate::heartbeat::HeartbeatState;
+use crate::engine::{EngineShutdown, EngineStatusChanged};
+use crate::tunnels::TunnelInfo;
+use crate::watchdog::ResourceAlert;
+use crate::workspaces::WorkspaceSnapshot;
+
+/// Slow subscribers lag (and skip ahead) once this many events are unread.
+const CHANNEL_CAPACITY: usize = 256;
+
+/// Events kept for `get_recent_events`.
+const RECENT_CAPACITY: usize = 200;
+
+/// Something one subsystem did that others may want to react to.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AppEvent {
+    EngineStatusChanged(EngineStatusChanged),
+    EngineStopped(EngineShutdown),
+    EngineHeartbeat(HeartbeatState),
+    EngineMetrics(EngineMetric),
+    ResourceAlert(ResourceAlert),
+    TunnelStatus(TunnelInfo),
+    TransferUpdated { uuid: String, status: String },
+    DatasetRefreshed { uuid: String, success: bool },
+    WorkspaceActivated(WorkspaceSnapshot),
+    ModeChanged(AppModeInfo),
+    NotificationAdded(Notification),
+    /// A sync with the backend should run now
+    SyncRequested,
+    ConnectivityChanged(ConnectivityStatus),
+}
+
+impl AppEvent {
+    /// Forwards the event to the frontend under the name it has always
+    /// listened on. Events without a frontend name stay internal.
+    fn emit_to(&self, app: &AppHandle) {
+        let _ = match self {
+            AppEvent::EngineStatusChanged(change) => app.emit("engine-status-changed", change),
+            AppEvent::EngineStopped(shutdown) => app.emit("engine-stopped", shutdown),
+            AppEvent::EngineHeartbeat(state) => app.emit("engine-heartbeat", state),
+            AppEvent::EngineMetrics(metric) => app.emit("engine-metrics", metric),
+            AppEvent::ResourceAlert(alert) => app.emit("resource-alert", alert),
+            AppEvent::TunnelStatus(info) => app.emit("engine-tunnel-status", info),
+            AppEvent::WorkspaceActivated(snapshot) => app.emit("workspace-activated", snapshot),
+            AppEvent::ModeChanged(mode) => app.emit("app-mode-changed", mode),
+            AppEvent::NotificationAdded(notification) => app.emit("notification-added", notification),
+            AppEvent::SyncRequested => app.emit("sync-requested", ()),
+            AppEvent::ConnectivityChanged(status) => app.emit("connectivity-changed", status),
+            AppEvent::DatasetRefreshed { uuid, success } => {
+                app.emit("dataset-refreshed", serde_json::json!({ "uuid": uuid, "success": success }))
+            }
+            AppEvent::TransferUpdated { .. } => return,
+        };
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EventRecord {
+    /// Increases by one per event, so gaps show what a lagged reader missed
+    pub id: u64,
+    pub timestamp: String,
+    pub event: AppEvent,
+}
+
+struct Recent {
+    next_id: u64,
+    records: VecDeque<EventRecord>,
+}
+
+/// In-process publish/subscribe between subsystems. Managed as its own Tauri
+/// state (ahead of `AppState`) so engines started during setup can publish.
+pub struct EventBus {
+    sender: broadcast::Sender<EventRecord>,
+    recent: Mutex<Recent>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self {
+            sender,
+            recent: Mutex::new(Recent {
+                next_id: 1,
+                records: VecDeque::with_capacity(RECENT_CAPACITY),
+            }),
+        }
+    }
+
+    pub fn publish(&self, event: AppEvent) {
+        // Sending under the lock keeps delivery order identical to ID order
+        let mut recent = self.recent.lock().unwrap();
+        let record = EventRecord {
+            id: recent.next_id,
+            timestamp: crate::timestamps::now(),
+            event,
+        };
+        recent.next_id += 1;
+        if recent.records.len() == RECENT_CAPACITY {
+            recent.records.pop_front();
+        }
+        recent.records.push_back(record.clone());
+
+        // No subscribers is not an error
+        let _ = self.sender.send(record);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<EventRecord> {
+        self.sender.subscribe()
+    }
+
+    /// Most recent events, oldest first.
+    pub fn recent(&self, limit: usize) -> Vec<EventRecord> {
+        let recent = self.recent.lock().unwrap();
+        let skip = recent.records.len().saturating_sub(limit);
+        recent.records.iter().skip(skip).cloned().collect()
+    }
+}
+
+/// Publishes through the app's bus; a no-op before the bus is managed.
+pub fn publish(app: &AppHandle, event: AppEvent) {
+    if let Some(bus) = app.try_state::<EventBus>() {
+        bus.publish(event);
+    }
+}
+
+/// Relays bus events to the frontend. Subscribes before returning so nothing
+/// published after setup is missed.
+pub fn start_bridge(app: AppHandle) {
+    let mut events = app.state::<EventBus>().subscribe();
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(record) => record.event.emit_to(&app),
+                Err(RecvError::Lagged(skipped)) => {
+                    eprintln!("[WARNING] Frontend event bridge fell behind; dropped {} events", skipped);
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transfer(uuid: &str) -> AppEvent {
+        AppEvent::TransferUpdated { uuid: uuid.to_string(), status: "completed".to_string() }
+    }
+
+    #[test]
+    fn test_publish_and_recent() {
+        let bus = EventBus::new();
+        let mut events = bus.subscribe();
+
+        for i in 0..RECENT_CAPACITY + 5 {
+            bus.publish(transfer(&i.to_string()));
+        }
+
+        let first = events.try_recv().unwrap();
+        assert_eq!(first.id, 1);
+        assert!(matches!(first.event, AppEvent::TransferUpdated { ref uuid, .. } if uuid == "0"));
+
+        // The ring buffer keeps only the newest events
+        let recent = bus.recent(usize::MAX);
+        assert_eq!(recent.len(), RECENT_CAPACITY);
+        assert_eq!(recent.first().unwrap().id, 6);
+        assert_eq!(recent.last().unwrap().id, (RECENT_CAPACITY + 5) as u64);
+
+        let latest = bus.recent(2);
+        assert_eq!(latest.iter().map(|r| r.id).collect::<Vec<_>>(), vec![204, 205]);
+
+        let json = serde_json::to_value(&latest[0]).unwrap();
+        assert_eq!(json["event"]["kind"], "transfer_updated");
+    }
+}
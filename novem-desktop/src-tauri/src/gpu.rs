@@ -0,0 +1,179 @@
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GpuDevice {
+    pub name: String,
+    pub vram_total_mb: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GpuInfo {
+    pub cuda_available: bool,
+    pub metal_available: bool,
+    pub driver_version: Option<String>,
+    pub cuda_version: Option<String>,
+    /// VRAM of the largest device; `None` for unified memory (Apple Silicon)
+    pub vram_total_mb: Option<u64>,
+    pub devices: Vec<GpuDevice>,
+    /// Python libraries in the compute engine that can use the GPU
+    pub engine_frameworks: Vec<String>,
+    pub detected_at: String,
+}
+
+/// Response of the compute engine's `/health/gpu` endpoint.
+#[derive(Debug, Deserialize)]
+pub struct EngineGpuStatus {
+    #[serde(default)]
+    pub cuda_available: bool,
+    #[serde(default)]
+    pub mps_available: bool,
+    pub cuda_version: Option<String>,
+    #[serde(default)]
+    pub devices: Vec<GpuDevice>,
+    #[serde(default)]
+    pub frameworks: Vec<String>,
+}
+
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Parses `nvidia-smi --query-gpu=name,memory.total,driver_version
+/// --format=csv,noheader,nounits` output.
+fn parse_nvidia_smi(output: &str) -> (Vec<GpuDevice>, Option<String>) {
+    let mut devices = Vec::new();
+    let mut driver_version = None;
+
+    for line in output.lines().filter(|line| !line.trim().is_empty()) {
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        if fields.len() < 3 {
+            continue;
+        }
+
+        devices.push(GpuDevice {
+            name: fields[0].to_string(),
+            vram_total_mb: fields[1].parse().ok(),
+        });
+        driver_version.get_or_insert_with(|| fields[2].to_string());
+    }
+
+    (devices, driver_version)
+}
+
+fn detect_nvidia(info: &mut GpuInfo) {
+    let output = match run(
+        "nvidia-smi",
+        &["--query-gpu=name,memory.total,driver_version", "--format=csv,noheader,nounits"],
+    ) {
+        Some(output) => output,
+        None => return,
+    };
+
+    let (devices, driver_version) = parse_nvidia_smi(&output);
+    if !devices.is_empty() {
+        info.cuda_available = true;
+        info.driver_version = driver_version;
+        info.devices.extend(devices);
+    }
+}
+
+/// Parses "8 GB" / "1536 MB" as reported by `system_profiler`.
+fn parse_vram(text: &str) -> Option<u64> {
+    let mut parts = text.split_whitespace();
+    let amount: u64 = parts.next()?.parse().ok()?;
+    match parts.next()? {
+        "GB" => Some(amount * 1024),
+        "MB" => Some(amount),
+        _ => None,
+    }
+}
+
+fn detect_metal(info: &mut GpuInfo) {
+    let output = match run("system_profiler", &["SPDisplaysDataType", "-json"]) {
+        Some(output) => output,
+        None => return,
+    };
+
+    let json: serde_json::Value = match serde_json::from_str(&output) {
+        Ok(json) => json,
+        Err(_) => return,
+    };
+
+    let displays = json
+        .get("SPDisplaysDataType")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    for display in displays {
+        let metal = display
+            .as_object()
+            .map(|fields| fields.keys().any(|key| key.starts_with("spdisplays_mtlgpufamilysupport") || key == "spdisplays_metal"))
+            .unwrap_or(false);
+        info.metal_available |= metal;
+
+        let name = display
+            .get("sppci_model")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Unknown GPU")
+            .to_string();
+        let vram_total_mb = display
+            .get("spdisplays_vram")
+            .or_else(|| display.get("spdisplays_vram_shared"))
+            .and_then(|v| v.as_str())
+            .and_then(parse_vram);
+
+        info.devices.push(GpuDevice { name, vram_total_mb });
+    }
+}
+
+/// Detects GPUs natively, without relying on the compute engine.
+pub fn detect() -> GpuInfo {
+    let mut info = GpuInfo::default();
+
+    if cfg!(target_os = "macos") {
+        detect_metal(&mut info);
+    } else {
+        detect_nvidia(&mut info);
+    }
+
+    info.vram_total_mb = info.devices.iter().filter_map(|d| d.vram_total_mb).max();
+    info.detected_at = crate::timestamps::now();
+    info
+}
+
+impl GpuInfo {
+    /// Folds in what the engine's Python libraries can actually use.
+    pub fn merge_engine(&mut self, engine: EngineGpuStatus) {
+        self.cuda_available |= engine.cuda_available;
+        self.metal_available |= engine.mps_available;
+        self.cuda_version = engine.cuda_version.or(self.cuda_version.take());
+        self.engine_frameworks = engine.frameworks;
+
+        if self.devices.is_empty() {
+            self.devices = engine.devices;
+            self.vram_total_mb = self.devices.iter().filter_map(|d| d.vram_total_mb).max();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nvidia_smi() {
+        let output = "NVIDIA GeForce RTX 3080, 10240, 535.54.03\nNVIDIA A100-SXM4-40GB, 40960, 535.54.03\n";
+        let (devices, driver) = parse_nvidia_smi(output);
+
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[1].vram_total_mb, Some(40960));
+        assert_eq!(driver.as_deref(), Some("535.54.03"));
+        assert_eq!(parse_vram("8 GB"), Some(8192));
+    }
+}
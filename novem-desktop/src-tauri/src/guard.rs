@@ -0,0 +1,189 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::ipc::Invoke;
+use tauri::{Manager, Runtime};
+
+use crate::i18n::tr;
+use crate::AppState;
+
+/// Setting key persisting whether the app starts in guest mode.
+pub const GUEST_MODE_SETTING: &str = "app.guest_mode";
+
+/// Command-line flag forcing guest mode for the whole session.
+pub const GUEST_FLAG: &str = "--guest";
+
+/// Commands a guest may run. Anything not listed is refused, so a newly added
+/// command stays blocked until someone decides it is safe to expose.
+const GUEST_COMMANDS: &[&str] = &[
+    "get_engine_status",
+    "get_engine_port",
+    "check_backend_health",
+    "get_connectivity_status",
+    "check_compute_engine_health",
+    "diagnose_ports",
+    "get_system_resources",
+    "get_resource_history",
+    "get_engine_metrics",
+    "get_watchdog_config",
+    "get_gpu_info",
+    "get_workspaces",
+    "get_projects",
+    "get_db_pragma_info",
+    "get_storage_report",
+    "list_local_accounts",
+    "list_jobs",
+    "get_job",
+    "get_secret_names",
+    "list_connections",
+    "get_notifications",
+    "list_schedules",
+    "list_share_links",
+    "get_sync_status",
+    "get_active_workspace",
+    "list_workspace_members",
+    "check_workspace_role",
+    "global_search",
+    "health_check",
+    "get_recovery_report",
+    "get_locale",
+    "get_recent_events",
+    "get_app_mode",
+    "set_guest_mode",
+    "list_engine_tunnels",
+    "list_engines",
+    "get_engine_latency_stats",
+    "get_engine_admission",
+    "get_engine_queue_status",
+    "verify_engine_dependencies",
+    "discover_python_interpreters",
+    "get_engine_info",
+    "get_engine_dev_mode",
+    "get_engine_lazy_start",
+    "list_engine_profiles",
+    "get_engine_readiness_probe",
+    "get_engine_shutdown_policy",
+    "get_engine_log_level",
+    "get_engine_heartbeat",
+    "get_engine_heartbeat_config",
+    "read_spilled_response",
+    "get_result_cache_stats",
+    "get_proxy_limits",
+    "get_session_pool_stats",
+    "get_session_pool_config",
+    "get_engine_update_config",
+    "list_transfers",
+    "get_dashboard",
+    "list_dashboards",
+    "list_notebooks",
+    "list_cells",
+    "get_execution_history",
+    "list_datasets",
+    "get_dataset_lineage",
+    "get_column_stats",
+    "get_dataset_schema",
+    "list_recipes",
+    "get_dataset_freshness",
+    "get_query_history",
+    "preview_delete",
+    "list_trash",
+    "get_activity_log",
+    "get_activity_retention",
+    "get_setting",
+    "get_all_settings",
+    "get_recent_items",
+    "list_comments",
+    "list_attachments",
+    "list_project_templates",
+    "list_conflicts",
+];
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct AppModeInfo {
+    pub guest: bool,
+    /// Guest mode came from the command line and can't be left from the UI
+    pub locked: bool,
+}
+
+/// Whether the app runs read-only. Guests can browse everything but every
+/// command that writes data, runs code or uploads is refused.
+pub struct AppMode {
+    guest: AtomicBool,
+    locked: bool,
+}
+
+impl AppMode {
+    pub fn new(guest: bool, locked: bool) -> Self {
+        Self {
+            guest: AtomicBool::new(guest || locked),
+            locked,
+        }
+    }
+
+    pub fn is_guest(&self) -> bool {
+        self.guest.load(Ordering::Relaxed)
+    }
+
+    pub fn info(&self) -> AppModeInfo {
+        AppModeInfo {
+            guest: self.is_guest(),
+            locked: self.locked,
+        }
+    }
+
+    /// Fails when leaving a guest session that was started with `--guest`.
+    pub fn set_guest(&self, guest: bool) -> Result<(), String> {
+        if self.locked && !guest {
+            return Err(tr!("guest-mode-locked"));
+        }
+        self.guest.store(guest, Ordering::Relaxed);
+        Ok(())
+    }
+
+    pub fn allows(&self, command: &str) -> bool {
+        !self.is_guest() || GUEST_COMMANDS.contains(&command)
+    }
+}
+
+/// Wraps the generated command handler, rejecting commands the current mode
+/// doesn't allow before they run.
+pub fn guarded<R: Runtime>(
+    handler: impl Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        let allowed = match invoke.message.webview_ref().try_state::<AppState>() {
+            Some(state) => state.mode.allows(invoke.message.command()),
+            None => true,
+        };
+
+        if !allowed {
+            let message = tr!("guest-mode-blocked", command = invoke.message.command());
+            invoke.resolver.reject(message);
+            return true;
+        }
+        handler(invoke)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guest_mode() {
+        let mode = AppMode::new(false, false);
+        assert!(mode.allows("delete_entity"));
+
+        mode.set_guest(true).unwrap();
+        assert!(!mode.allows("delete_entity"));
+        assert!(!mode.allows("run_query"));
+        assert!(mode.allows("list_datasets"));
+        // Engine variables hold API keys
+        assert!(!mode.allows("get_engine_env"));
+
+        // A --guest session can't be unlocked from the UI
+        let locked = AppMode::new(false, true);
+        assert!(locked.is_guest());
+        assert!(locked.set_guest(false).is_err());
+        assert!(locked.info().locked);
+    }
+}
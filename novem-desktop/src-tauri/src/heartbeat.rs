@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Setting key holding the heartbeat configuration as JSON.
+pub const HEARTBEAT_SETTING: &str = "engine.heartbeat";
+
+/// The health monitor's keepalive: a cheap `/heartbeat` ping, much more frequent
+/// than the full `/health` check, that the engine must answer in time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HeartbeatConfig {
+    pub interval_ms: u64,
+    /// A reply slower than this counts as a miss
+    pub timeout_ms: u64,
+    /// Consecutive misses before the engine is marked `Degraded`
+    pub max_misses: u32,
+}
+
+impl Default for HeartbeatConfig {
+    fn default() -> Self {
+        Self {
+            interval_ms: 1000,
+            timeout_ms: 500,
+            max_misses: 3,
+        }
+    }
+}
+
+impl HeartbeatConfig {
+    pub fn is_valid(&self) -> bool {
+        self.interval_ms >= 100 && self.timeout_ms > 0 && self.timeout_ms <= self.interval_ms && self.max_misses > 0
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms)
+    }
+
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms)
+    }
+}
+
+/// Latest heartbeat outcome of one engine. Published as `engine-heartbeat`
+/// whenever the miss streak starts, grows or ends.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HeartbeatState {
+    pub project_id: Option<i64>,
+    pub last_rtt_ms: Option<u64>,
+    pub last_seen_at: Option<String>,
+    pub consecutive_misses: u32,
+    /// Fewer misses in a row than the configured limit
+    pub alive: bool,
+}
+
+impl HeartbeatState {
+    pub fn new(project_id: Option<i64>) -> Self {
+        Self {
+            project_id,
+            alive: true,
+            ..Default::default()
+        }
+    }
+
+    /// Records one ping; `None` if it went unanswered in time. Returns
+    /// whether the miss streak changed.
+    pub fn record(&mut self, rtt: Option<Duration>, config: &HeartbeatConfig) -> bool {
+        let previous = self.consecutive_misses;
+
+        match rtt {
+            Some(rtt) => {
+                self.last_rtt_ms = Some(rtt.as_millis() as u64);
+                self.last_seen_at = Some(crate::timestamps::now());
+                self.consecutive_misses = 0;
+            }
+            None => self.consecutive_misses = self.consecutive_misses.saturating_add(1),
+        }
+        self.alive = self.consecutive_misses < config.max_misses;
+
+        self.consecutive_misses != previous
+    }
+
+    /// Starts over for a new engine process.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.project_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_misses_degrade_after_limit() {
+        let config = HeartbeatConfig { max_misses: 2, ..Default::default() };
+        let mut state = HeartbeatState::new(None);
+
+        assert!(!state.record(Some(Duration::from_millis(4)), &config));
+        assert_eq!(state.last_rtt_ms, Some(4));
+
+        assert!(state.record(None, &config));
+        assert!(state.alive);
+        assert!(state.record(None, &config));
+        assert!(!state.alive);
+
+        assert!(state.record(Some(Duration::from_millis(7)), &config));
+        assert!(state.alive);
+        assert_eq!(state.consecutive_misses, 0);
+
+        assert!(!HeartbeatConfig { timeout_ms: 2000, ..Default::default() }.is_valid());
+    }
+}
@@ -0,0 +1,54 @@
+use reqwest::{blocking, Client, ClientBuilder};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::engine_auth;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// HTTP clients shared by all commands, so connections and TLS sessions
+/// are pooled instead of set up again on every call. Each is built on first
+/// use; timeouts are set per request with `RequestBuilder::timeout`.
+#[derive(Default)]
+pub struct HttpClients {
+    engine: OnceLock<Client>,
+    backend: OnceLock<Client>,
+}
+
+impl HttpClients {
+    /// For local engines. Sends the engine token, so it must never be
+    /// pointed anywhere else.
+    pub fn engine(&self) -> &Client {
+        self.engine
+            .get_or_init(|| build(Client::builder().default_headers(engine_auth::headers())))
+    }
+
+    /// For the backend and other remote services.
+    pub fn backend(&self) -> &Client {
+        self.backend.get_or_init(|| build(Client::builder()))
+    }
+}
+
+/// Blocking counterpart of `HttpClients::engine`, for starting, supervising
+/// and stopping engines on their own threads. Engines don't see `AppState`,
+/// so this one is process-wide.
+pub fn blocking_engine() -> &'static blocking::Client {
+    static CLIENT: OnceLock<blocking::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        blocking::Client::builder()
+            .default_headers(engine_auth::headers())
+            .connect_timeout(CONNECT_TIMEOUT)
+            .build()
+            .unwrap_or_else(|e| {
+                eprintln!("[ERROR] Failed to create blocking HTTP client, using defaults: {}", e);
+                blocking::Client::new()
+            })
+    })
+}
+
+fn build(builder: ClientBuilder) -> Client {
+    builder.connect_timeout(CONNECT_TIMEOUT).build().unwrap_or_else(|e| {
+        eprintln!("[ERROR] Failed to create HTTP client, using defaults: {}", e);
+        Client::new()
+    })
+}
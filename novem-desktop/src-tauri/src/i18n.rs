@@ -0,0 +1,204 @@
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use serde::Serialize;
+use std::sync::{OnceLock, RwLock};
+use unic_langid::LanguageIdentifier;
+
+/// Setting holding the user's locale override; unset means follow the OS.
+pub const LOCALE_SETTING: &str = "app.locale";
+
+const FALLBACK_LOCALE: &str = "en-US";
+
+/// Shipped translations, embedded so they can't go missing at runtime.
+const LOCALES: &[(&str, &str)] = &[
+    ("en-US", include_str!("../locales/en-US/main.ftl")),
+    ("es-ES", include_str!("../locales/es-ES/main.ftl")),
+    ("fr-FR", include_str!("../locales/fr-FR/main.ftl")),
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LocaleInfo {
+    /// Locale messages are currently rendered in
+    pub locale: String,
+    /// Locale reported by the OS, if any
+    pub system_locale: Option<String>,
+    /// Saved override, if the user picked a locale
+    pub override_locale: Option<String>,
+    pub available: Vec<String>,
+}
+
+struct Localizer {
+    locale: String,
+    /// The selected locale first, then the fallback for missing messages
+    bundles: Vec<FluentBundle<FluentResource>>,
+}
+
+fn bundle(locale: &str, source: &str) -> FluentBundle<FluentResource> {
+    let langid: LanguageIdentifier = locale.parse().expect("shipped locale IDs are valid");
+    let resource = FluentResource::try_new(source.to_string())
+        .unwrap_or_else(|(resource, errors)| {
+            eprintln!("[ERROR] Failed to parse {} messages: {:?}", locale, errors);
+            resource
+        });
+
+    let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+    // Messages end up in plain-text errors, where bidi isolation marks show as garbage
+    bundle.set_use_isolating(false);
+    if let Err(errors) = bundle.add_resource(resource) {
+        eprintln!("[ERROR] Duplicate {} messages: {:?}", locale, errors);
+    }
+    bundle
+}
+
+impl Localizer {
+    fn new(requested: &str) -> Self {
+        let locale = negotiate(requested);
+        let mut bundles = Vec::new();
+
+        for (id, source) in LOCALES {
+            if *id == locale {
+                bundles.insert(0, bundle(id, source));
+            } else if *id == FALLBACK_LOCALE {
+                bundles.push(bundle(id, source));
+            }
+        }
+
+        Self { locale: locale.to_string(), bundles }
+    }
+
+    fn format(&self, key: &str, args: Option<&FluentArgs>) -> String {
+        for bundle in &self.bundles {
+            let Some(pattern) = bundle.get_message(key).and_then(|message| message.value()) else {
+                continue;
+            };
+
+            let mut errors = Vec::new();
+            let text = bundle.format_pattern(pattern, args, &mut errors);
+            if !errors.is_empty() {
+                eprintln!("[WARNING] Errors formatting message '{}': {:?}", key, errors);
+            }
+            return text.into_owned();
+        }
+
+        eprintln!("[WARNING] Missing message '{}'", key);
+        key.to_string()
+    }
+}
+
+/// Picks the shipped locale closest to `requested`: an exact match, then
+/// one with the same language, then the fallback.
+fn negotiate(requested: &str) -> &'static str {
+    let Ok(requested) = requested.replace('_', "-").parse::<LanguageIdentifier>() else {
+        return FALLBACK_LOCALE;
+    };
+
+    let shipped = || {
+        LOCALES.iter().filter_map(|(id, _)| Some((*id, id.parse::<LanguageIdentifier>().ok()?)))
+    };
+
+    shipped()
+        .find(|(_, langid)| *langid == requested)
+        .or_else(|| shipped().find(|(_, langid)| langid.language == requested.language))
+        .map(|(id, _)| id)
+        .unwrap_or(FALLBACK_LOCALE)
+}
+
+pub fn system_locale() -> Option<String> {
+    sys_locale::get_locale()
+}
+
+static LOCALIZER: OnceLock<RwLock<Localizer>> = OnceLock::new();
+
+fn localizer() -> &'static RwLock<Localizer> {
+    LOCALIZER.get_or_init(|| {
+        RwLock::new(Localizer::new(&system_locale().unwrap_or_else(|| FALLBACK_LOCALE.to_string())))
+    })
+}
+
+/// Switches message rendering to `override_locale`, or back to the OS
+/// locale when `None`. Returns the locale actually in use.
+pub fn set_locale(override_locale: Option<&str>) -> String {
+    let requested = override_locale
+        .map(str::to_string)
+        .or_else(system_locale)
+        .unwrap_or_else(|| FALLBACK_LOCALE.to_string());
+
+    let selected = Localizer::new(&requested);
+    let locale = selected.locale.clone();
+    *localizer().write().unwrap_or_else(|e| e.into_inner()) = selected;
+
+    println!("[NOVEM] Locale: {} (requested {})", locale, requested);
+    locale
+}
+
+pub fn current_locale() -> String {
+    localizer().read().unwrap_or_else(|e| e.into_inner()).locale.clone()
+}
+
+pub fn is_available(locale: &str) -> bool {
+    LOCALES.iter().any(|(id, _)| *id == locale)
+}
+
+pub fn available_locales() -> Vec<String> {
+    LOCALES.iter().map(|(id, _)| id.to_string()).collect()
+}
+
+/// Renders message `key` in the current locale. Prefer the `tr!` macro.
+pub fn translate(key: &str, args: &[(&str, FluentValue)]) -> String {
+    let localizer = localizer().read().unwrap_or_else(|e| e.into_inner());
+
+    if args.is_empty() {
+        return localizer.format(key, None);
+    }
+
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, value.clone());
+    }
+    localizer.format(key, Some(&fluent_args))
+}
+
+/// `tr!("dataset-not-found", uuid = uuid)` renders a message from
+/// `locales/<locale>/main.ftl` with the given arguments.
+macro_rules! tr {
+    ($key:expr $(, $name:ident = $value:expr)* $(,)?) => {
+        $crate::i18n::translate(
+            $key,
+            &[$((stringify!($name), fluent_bundle::FluentValue::from($value))),*],
+        )
+    };
+}
+
+pub(crate) use tr;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiation_and_fallback() {
+        assert_eq!(negotiate("es_MX"), "es-ES");
+        assert_eq!(negotiate("fr-FR"), "fr-FR");
+        assert_eq!(negotiate("ja-JP"), "en-US");
+
+        let localizer = Localizer::new("es-ES");
+        let mut args = FluentArgs::new();
+        args.set("uuid", "ds1");
+        assert_eq!(localizer.format("dataset-not-found", Some(&args)), "No se encontró el conjunto de datos ds1");
+        assert_eq!(localizer.format("no-such-message", None), "no-such-message");
+
+        // Every shipped locale covers every message of the fallback
+        let fallback = LOCALES[0].1;
+        let keys: Vec<&str> = fallback
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.split_once(" = ").map(|(key, _)| key))
+            .collect();
+        for (id, _) in LOCALES {
+            let localizer = Localizer::new(id);
+            for key in &keys {
+                assert!(localizer.bundles[0].has_message(key), "{} is missing '{}'", id, key);
+            }
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::i18n::tr;
+
+/// Oldest Python the engine's pinned requirements (pandas 3, numpy 2.4)
+/// install on.
+pub const MIN_PYTHON_VERSION: (u32, u32) = (3, 11);
+
+/// Versions asked of the Windows `py` launcher, newest first.
+const LAUNCHER_VERSIONS: &[&str] = &["3.13", "3.12", "3.11"];
+
+const HOMEBREW_BIN_DIRS: &[&str] = &["/opt/homebrew/bin", "/usr/local/bin"];
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CandidateSource {
+    Venv,
+    PyLauncher,
+    Pyenv,
+    Homebrew,
+    Path,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum Rejection {
+    NotRunnable { error: String },
+    UnknownVersion { output: String },
+    TooOld,
+    /// Resolves to an interpreter already listed, e.g. a pyenv shim
+    Duplicate { of: String },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PythonCandidate {
+    /// How it was invoked, e.g. `py -3.11` or a path
+    pub command: String,
+    pub source: CandidateSource,
+    /// What the engine would run, resolved through shims and launchers
+    pub executable: Option<String>,
+    pub version: Option<String>,
+    pub rejection: Option<Rejection>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InterpreterReport {
+    pub minimum_version: String,
+    pub selected: Option<PythonCandidate>,
+    /// Every candidate probed, in order of preference
+    pub candidates: Vec<PythonCandidate>,
+}
+
+struct Candidate {
+    program: PathBuf,
+    args: Vec<String>,
+    source: CandidateSource,
+}
+
+impl Candidate {
+    fn new(program: impl Into<PathBuf>, source: CandidateSource) -> Self {
+        Self {
+            program: program.into(),
+            args: Vec::new(),
+            source,
+        }
+    }
+
+    fn command(&self) -> Command {
+        let mut command = Command::new(&self.program);
+        command.args(&self.args);
+        command
+    }
+
+    fn display(&self) -> String {
+        std::iter::once(self.program.to_string_lossy().to_string())
+            .chain(self.args.iter().cloned())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+}
+
+/// Where to look, best first: the engine's own virtual environment, then
+/// the interpreters users typically install Python with.
+fn candidates(compute_engine_dir: Option<&Path>) -> Vec<Candidate> {
+    let mut candidates = Vec::new();
+
+    if let Some(dir) = compute_engine_dir {
+        for venv in [".venv", "venv"] {
+            for python in [dir.join(venv).join("Scripts").join("python.exe"), dir.join(venv).join("bin").join("python")] {
+                if python.is_file() {
+                    candidates.push(Candidate::new(python, CandidateSource::Venv));
+                }
+            }
+        }
+    }
+
+    if cfg!(windows) {
+        for version in LAUNCHER_VERSIONS {
+            let mut launcher = Candidate::new("py", CandidateSource::PyLauncher);
+            launcher.args.push(format!("-{}", version));
+            candidates.push(launcher);
+        }
+    }
+
+    let pyenv_root = std::env::var_os("PYENV_ROOT")
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|home| home.join(".pyenv")));
+    if let Some(root) = pyenv_root {
+        for shim in [root.join("shims").join("python3"), root.join("pyenv-win").join("shims").join("python.bat")] {
+            if shim.is_file() {
+                candidates.push(Candidate::new(shim, CandidateSource::Pyenv));
+            }
+        }
+    }
+
+    if cfg!(target_os = "macos") {
+        for dir in HOMEBREW_BIN_DIRS {
+            let python = Path::new(dir).join("python3");
+            if python.is_file() {
+                candidates.push(Candidate::new(python, CandidateSource::Homebrew));
+            }
+        }
+    }
+
+    let path_names: &[&str] = if cfg!(windows) { &["python"] } else { &["python3", "python"] };
+    for name in path_names {
+        candidates.push(Candidate::new(name, CandidateSource::Path));
+    }
+
+    candidates
+}
+
+/// Parses `Python 3.11.4` (or `Python 3.13.0rc1`) as printed by `--version`.
+fn parse_version(output: &str) -> Option<(u32, u32, u32)> {
+    let version = output.trim().strip_prefix("Python ")?;
+    let mut parts = version.split('.').map(|part| {
+        let digits: String = part.chars().take_while(|c| c.is_ascii_digit()).collect();
+        digits.parse::<u32>().ok()
+    });
+    Some((parts.next()??, parts.next()??, parts.next().flatten().unwrap_or(0)))
+}
+
+fn probe(candidate: &Candidate) -> PythonCandidate {
+    let mut result = PythonCandidate {
+        command: candidate.display(),
+        source: candidate.source,
+        executable: None,
+        version: None,
+        rejection: None,
+    };
+
+    let output = match candidate.command().arg("--version").output() {
+        Ok(output) => output,
+        Err(e) => {
+            result.rejection = Some(Rejection::NotRunnable { error: e.to_string() });
+            return result;
+        }
+    };
+
+    // Python 2 printed its version to stderr
+    let text = format!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr));
+    let version = match parse_version(&text) {
+        Some(version) if output.status.success() => version,
+        _ => {
+            result.rejection = Some(Rejection::UnknownVersion { output: text.trim().to_string() });
+            return result;
+        }
+    };
+    result.version = Some(format!("{}.{}.{}", version.0, version.1, version.2));
+
+    if (version.0, version.1) < MIN_PYTHON_VERSION {
+        result.rejection = Some(Rejection::TooOld);
+        return result;
+    }
+
+    // Launchers and shims can't be spawned with `-m uvicorn` appended the
+    // way the engine is, so ask for the real interpreter
+    let resolved = candidate
+        .command()
+        .args(["-c", "import sys; print(sys.executable)"])
+        .output();
+    match resolved {
+        Ok(output) if output.status.success() => {
+            let executable = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if executable.is_empty() {
+                result.rejection = Some(Rejection::UnknownVersion { output: text.trim().to_string() });
+            } else {
+                result.executable = Some(executable);
+            }
+        }
+        Ok(output) => {
+            let error = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            result.rejection = Some(Rejection::NotRunnable { error });
+        }
+        Err(e) => result.rejection = Some(Rejection::NotRunnable { error: e.to_string() }),
+    }
+    result
+}
+
+fn same_interpreter(a: &str, b: &str) -> bool {
+    match (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+fn probe_candidates(compute_engine_dir: Option<&Path>, stop_at_first: bool) -> Vec<PythonCandidate> {
+    let mut probed: Vec<PythonCandidate> = Vec::new();
+
+    for candidate in candidates(compute_engine_dir) {
+        let mut result = probe(&candidate);
+
+        if let Some(executable) = &result.executable {
+            let original = probed.iter().find(|earlier| {
+                earlier.rejection.is_none()
+                    && earlier.executable.as_deref().is_some_and(|earlier| same_interpreter(earlier, executable))
+            });
+            if let Some(original) = original {
+                result.rejection = Some(Rejection::Duplicate { of: original.command.clone() });
+            }
+        }
+
+        let accepted = result.rejection.is_none();
+        probed.push(result);
+        if accepted && stop_at_first {
+            break;
+        }
+    }
+    probed
+}
+
+/// Probes every candidate for the diagnostics screen.
+pub fn discover(compute_engine_dir: Option<&Path>) -> InterpreterReport {
+    let candidates = probe_candidates(compute_engine_dir, false);
+    let selected = candidates.iter().find(|candidate| candidate.rejection.is_none()).cloned();
+
+    InterpreterReport {
+        minimum_version: format!("{}.{}", MIN_PYTHON_VERSION.0, MIN_PYTHON_VERSION.1),
+        selected,
+        candidates,
+    }
+}
+
+/// The preferred interpreter meeting the minimum version, probing only as
+/// far as needed.
+pub fn find(compute_engine_dir: &Path) -> Result<PathBuf> {
+    let probed = probe_candidates(Some(compute_engine_dir), true);
+
+    for candidate in &probed {
+        if let Some(rejection) = &candidate.rejection {
+            println!("[NOVEM] Skipping Python {}: {:?}", candidate.command, rejection);
+        }
+    }
+
+    probed
+        .into_iter()
+        .find(|candidate| candidate.rejection.is_none())
+        .and_then(|candidate| candidate.executable.map(PathBuf::from))
+        .ok_or_else(|| {
+            let minimum = format!("{}.{}", MIN_PYTHON_VERSION.0, MIN_PYTHON_VERSION.1);
+            anyhow::anyhow!(tr!("python-not-found", minimum = minimum))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("Python 3.11.4\n"), Some((3, 11, 4)));
+        assert_eq!(parse_version("Python 3.13.0rc1"), Some((3, 13, 0)));
+        assert_eq!(parse_version("Python 3.12"), Some((3, 12, 0)));
+        assert_eq!(parse_version("Python 2.7.18"), Some((2, 7, 18)));
+        assert_eq!(parse_version("No installed Python found!"), None);
+        assert!((3, 10) < MIN_PYTHON_VERSION && (3, 12) >= MIN_PYTHON_VERSION);
+    }
+}
@@ -0,0 +1,105 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Health pings kept per engine; ten minutes at the supervisor's pace.
+const WINDOW_SIZE: usize = 120;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencyStats {
+    pub project_id: Option<i64>,
+    /// Answered pings in the window
+    pub samples: usize,
+    /// Pings in the window that failed or timed out
+    pub failures: usize,
+    pub p50_ms: Option<f64>,
+    pub p95_ms: Option<f64>,
+    pub max_ms: Option<f64>,
+    /// Latest answered ping
+    pub last_ms: Option<f64>,
+}
+
+/// Rolling window of `/health` round trips recorded by the engine supervisor.
+/// `None` marks a ping that got no healthy answer.
+#[derive(Debug, Default)]
+pub struct LatencyWindow {
+    pings: VecDeque<Option<Duration>>,
+}
+
+impl LatencyWindow {
+    pub fn record(&mut self, latency: Option<Duration>) {
+        if self.pings.len() == WINDOW_SIZE {
+            self.pings.pop_front();
+        }
+        self.pings.push_back(latency);
+    }
+
+    /// Forgets samples from a previous engine process.
+    pub fn clear(&mut self) {
+        self.pings.clear();
+    }
+
+    pub fn stats(&self, project_id: Option<i64>) -> LatencyStats {
+        let mut millis: Vec<f64> = self
+            .pings
+            .iter()
+            .flatten()
+            .map(|latency| latency.as_secs_f64() * 1000.0)
+            .collect();
+        let last_ms = millis.last().copied();
+        millis.sort_by(f64::total_cmp);
+
+        LatencyStats {
+            project_id,
+            samples: millis.len(),
+            failures: self.pings.len() - millis.len(),
+            p50_ms: percentile(&millis, 50.0),
+            p95_ms: percentile(&millis, 95.0),
+            max_ms: millis.last().copied(),
+            last_ms,
+        }
+    }
+}
+
+/// Nearest-rank percentile of already sorted values.
+fn percentile(sorted: &[f64], p: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_stats() {
+        let mut window = LatencyWindow::default();
+        assert_eq!(window.stats(None).p50_ms, None);
+
+        // 1..=100 ms, newest last, with two failed pings mixed in
+        for ms in (1..=100).rev() {
+            window.record(Some(Duration::from_millis(ms)));
+        }
+        window.record(None);
+        window.record(None);
+
+        let stats = window.stats(Some(7));
+        assert_eq!(stats.samples, 100);
+        assert_eq!(stats.failures, 2);
+        assert_eq!(stats.p50_ms, Some(50.0));
+        assert_eq!(stats.p95_ms, Some(95.0));
+        assert_eq!(stats.max_ms, Some(100.0));
+        assert_eq!(stats.last_ms, Some(1.0));
+
+        // Old pings fall out of the window
+        for _ in 0..WINDOW_SIZE {
+            window.record(Some(Duration::from_millis(3)));
+        }
+        let stats = window.stats(None);
+        assert_eq!((stats.samples, stats.failures), (WINDOW_SIZE, 0));
+        assert_eq!(stats.max_ms, Some(3.0));
+    }
+}
@@ -1,45 +1,135 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-mod python_engine;
+mod accounts;
+mod admission;
+mod attachments;
+mod ports;
+mod process_tree;
+mod proxy;
+mod engine;
 mod database;
 mod commands;
+mod connectivity;
+mod backend;
+mod dashboards;
+mod datasets;
+mod dependencies;
+mod deprovision;
+mod engine_auth;
+mod engine_info;
+mod engine_metrics;
+mod engine_update;
+mod engine_manager;
+mod events;
+mod gpu;
+mod guard;
+mod heartbeat;
+mod http;
+mod i18n;
+mod interpreters;
+mod latency;
+mod maintenance;
+mod notifications;
+mod queries;
+mod recovery;
+mod refresh;
+mod resources;
+mod result_cache;
+mod schedules;
+mod secrets;
+mod sessions;
+mod settings;
+mod startup_diagnosis;
+mod storage;
+mod sync;
+mod timestamps;
+mod transfers;
+mod tunnels;
+mod watchdog;
+mod workspaces;
 
-use std::sync::Mutex;
+use std::sync::{Mutex, RwLock};
 use std::path::PathBuf;
-use tauri::Manager;
-use python_engine::EmbeddedPythonEngine;
-use database::LocalDatabase;
+use tauri::{Emitter, Manager};
+use engine_info::EngineInfo;
+use engine_manager::EngineManager;
+use events::EventBus;
+use gpu::GpuInfo;
+use guard::AppMode;
+use http::HttpClients;
+use proxy::ProxyLimits;
+use engine::Supervisor;
+use database::{DatabaseKey, DatabasePool, LocalDatabase, NewActivity};
+use backend::BackendSession;
+use connectivity::Connectivity;
+use recovery::RecoveryReport;
+use refresh::RefreshQueue;
+use sessions::{SessionPool, SessionPoolConfig};
+use sync::SyncWorker;
+use transfers::TransferQueue;
+use watchdog::WatchdogConfig;
+use workspaces::ActiveWorkspace;
 
 struct AppState {
-    python_engine: Mutex<EmbeddedPythonEngine>,
-    db: Mutex<Option<LocalDatabase>>,
+    engines: EngineManager,
+    mode: AppMode,
+    db: DatabasePool,
+    backend: Mutex<BackendSession>,
+    connectivity: Connectivity,
+    transfers: TransferQueue,
+    refreshes: RefreshQueue,
+    sessions: SessionPool,
+    sync: SyncWorker,
+    gpu_info: Mutex<Option<GpuInfo>>,
+    engine_info: Mutex<Option<EngineInfo>>,
+    watchdog: Mutex<WatchdogConfig>,
+    proxy_limits: Mutex<ProxyLimits>,
+    http: HttpClients,
+    workspace: ActiveWorkspace,
+    recovery_report: RecoveryReport,
+    /// Root of app data, holding the account registry
+    app_dir: PathBuf,
+    /// The open account's database and files; see `accounts::data_dir`
+    data_dir: RwLock<PathBuf>,
 }
 
-fn find_compute_engine_dir() -> Option<PathBuf> {
-    let current_dir = std::env::current_dir().ok()?;
-    
-    let dev_path = current_dir.parent()?.parent()?.join("compute_engine");
-    if dev_path.exists() && dev_path.join("main.py").exists() {
-        println!("[NOVEM] Found compute_engine (dev mode): {:?}", dev_path);
-        return Some(dev_path);
+impl AppState {
+    fn data_dir(&self) -> PathBuf {
+        self.data_dir.read().unwrap().clone()
     }
 
-    let exe_path = std::env::current_exe().ok()?;
-    let exe_dir = exe_path.parent()?;
-    
-    let prod_path = exe_dir.join("compute_engine");
-    if prod_path.exists() && prod_path.join("main.py").exists() {
-        println!("[NOVEM] Found compute_engine (prod mode): {:?}", prod_path);
-        return Some(prod_path);
+    /// Managed storage for published artifacts (dashboards, exports, ...).
+    fn artifacts_dir(&self) -> PathBuf {
+        self.data_dir().join("artifacts")
     }
 
-    let resources_path = exe_dir.join("resources").join("compute_engine");
-    if resources_path.exists() && resources_path.join("main.py").exists() {
-        println!("[NOVEM] Found compute_engine (resources): {:?}", resources_path);
-        return Some(resources_path);
+    /// Engine responses too large to hold in memory; cleared on startup.
+    fn spill_dir(&self) -> PathBuf {
+        self.data_dir().join("spill")
     }
 
-    None
+    /// For background threads and other synchronous code; async code uses
+    /// `with_db_async`.
+    fn with_db<T>(&self, f: impl FnOnce(&LocalDatabase) -> anyhow::Result<T>) -> anyhow::Result<T> {
+        self.db.with(f)
+    }
+
+    async fn with_db_async<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&LocalDatabase) -> anyhow::Result<T> + Send + 'static,
+    ) -> anyhow::Result<T> {
+        self.db.run(f).await
+    }
+
+    /// Records a mutating action, credited to the signed-in user. Failing
+    /// to record it doesn't undo or fail the action.
+    async fn log_activity(&self, activity: NewActivity) {
+        let actor_id = self.backend.lock().ok().and_then(|session| session.user_id);
+        let action = format!("{} {}", activity.action, activity.entity_type);
+        if let Err(e) = self.with_db_async(move |db| db.log_activity(actor_id, &activity)).await {
+            eprintln!("[WARNING] Failed to record activity '{}': {}", action, e);
+        }
+    }
 }
 
 fn main() {
@@ -47,6 +137,9 @@ fn main() {
         .setup(|app| {
             println!("Initializing NOVEM Desktop...");
 
+            app.manage(EventBus::new());
+            events::start_bridge(app.handle().clone());
+
             let app_dir = app.path()
                 .app_data_dir()
                 .expect("Failed to get app data directory");
@@ -56,37 +149,229 @@ fn main() {
             
             println!("App data directory: {:?}", app_dir);
 
-            let db_path = app_dir.join("novem.db");
-            let db = LocalDatabase::new(db_path)
+            // Without a keyring (e.g. no Secret Service on Linux) the
+            // database is left unencrypted rather than unusable
+            let db_key = DatabaseKey::load_or_create()
+                .inspect_err(|e| eprintln!("[WARNING] OS keyring unavailable, local database is not encrypted: {:#}", e))
+                .ok();
+
+            let account = accounts::active(&app_dir).unwrap_or_else(|e| {
+                eprintln!("[ERROR] Failed to read local accounts, opening shared data: {:#}", e);
+                None
+            });
+            let data_dir = accounts::data_dir(&app_dir, account.as_deref());
+            std::fs::create_dir_all(&data_dir)
+                .expect("Failed to create account data directory");
+            if let Some(uuid) = &account {
+                println!("[NOVEM] Opening data of account {}", uuid);
+            }
+
+            let db_path = data_dir.join(deprovision::DATABASE_FILE);
+            let db_pool = DatabasePool::open(db_path, db_key)
+                .expect("Failed to initialize database");
+            let db = db_pool.get()
                 .expect("Failed to initialize database");
             
             println!("Database initialized");
 
-            let mut python_engine = EmbeddedPythonEngine::new();
-            
-            if let Some(compute_engine_dir) = find_compute_engine_dir() {
-                println!("[NOVEM] Starting embedded compute engine...");
-                
-                match python_engine.start_fastapi_server(compute_engine_dir) {
-                    Ok(_) => {
-                        println!("[NOVEM] Embedded compute engine started successfully");
-                        println!("[NOVEM] FastAPI available at: http://127.0.0.1:{}", python_engine.get_port());
-                    }
-                    Err(e) => {
-                        eprintln!("[ERROR] Failed to start compute engine: {}", e);
-                        eprintln!("[WARNING] Application will run with limited functionality");
-                    }
+            match db.get_setting(i18n::LOCALE_SETTING) {
+                Ok(locale) => {
+                    i18n::set_locale(locale.as_deref());
+                }
+                Err(e) => eprintln!("[ERROR] Failed to load locale setting: {}", e),
+            }
+
+            let guest_flag = std::env::args().any(|arg| arg == guard::GUEST_FLAG);
+            let guest_setting = match db.get_setting(guard::GUEST_MODE_SETTING) {
+                Ok(value) => value.as_deref() == Some("true"),
+                Err(e) => {
+                    eprintln!("[ERROR] Failed to load guest mode setting: {}", e);
+                    false
+                }
+            };
+            let mode = AppMode::new(guest_setting, guest_flag);
+            if mode.is_guest() {
+                println!("[NOVEM] Starting in read-only guest mode");
+            }
+
+            let recovery_report = recovery::recover(&db).unwrap_or_else(|e| {
+                eprintln!("[ERROR] Startup recovery failed: {}", e);
+                RecoveryReport::default()
+            });
+
+            match db.interrupt_running_jobs() {
+                Ok(0) => {}
+                Ok(count) => println!("[NOVEM] Marked {} jobs cut off by the last shutdown as interrupted", count),
+                Err(e) => eprintln!("[ERROR] Failed to mark interrupted jobs: {}", e),
+            }
+
+            match db.activity_retention_days().and_then(|days| db.prune_activity_log(days)) {
+                Ok(0) => {}
+                Ok(pruned) => println!("[NOVEM] Pruned {} expired activity log entries", pruned),
+                Err(e) => eprintln!("[ERROR] Failed to prune activity log: {}", e),
+            }
+
+            match settings::result_cache_max_bytes(&db).and_then(|max_bytes| db.evict_result_cache(max_bytes)) {
+                Ok(0) => {}
+                Ok(evicted) => println!("[NOVEM] Evicted {} cached engine results", evicted),
+                Err(e) => eprintln!("[ERROR] Failed to trim result cache: {}", e),
+            }
+
+            let engines = EngineManager::new(
+                app.handle().clone(),
+                Supervisor::find_compute_engine_dir(),
+                Supervisor::find_sidecar(),
+                &data_dir,
+            );
+            match db.get_setting(engine_manager::DEV_MODE_SETTING) {
+                Ok(value) => engines.set_dev_mode(value.as_deref() == Some("true")),
+                Err(e) => eprintln!("[ERROR] Failed to load engine dev mode: {}", e),
+            }
+            match db.get_setting(engine_manager::LAZY_START_SETTING) {
+                Ok(value) => engines.set_lazy_start(value.as_deref() == Some("true")),
+                Err(e) => eprintln!("[ERROR] Failed to load engine lazy start: {}", e),
+            }
+            match db.get_setting(engine_manager::READINESS_SETTING) {
+                Ok(Some(json)) => match serde_json::from_str(&json) {
+                    Ok(readiness) => engines.set_readiness_probe(readiness),
+                    Err(e) => eprintln!("[WARNING] Ignoring invalid readiness probe setting: {}", e),
+                },
+                Ok(None) => {}
+                Err(e) => eprintln!("[ERROR] Failed to load readiness probe: {}", e),
+            }
+            match db.get_setting(engine_manager::SHUTDOWN_SETTING) {
+                Ok(Some(json)) => match serde_json::from_str(&json) {
+                    Ok(policy) => engines.set_shutdown_policy(policy),
+                    Err(e) => eprintln!("[WARNING] Ignoring invalid shutdown policy setting: {}", e),
+                },
+                Ok(None) => {}
+                Err(e) => eprintln!("[ERROR] Failed to load shutdown policy: {}", e),
+            }
+            match db.get_setting(admission::ADMISSION_SETTING) {
+                Ok(Some(json)) => match serde_json::from_str(&json) {
+                    Ok(config) => engines.set_admission_config(config),
+                    Err(e) => eprintln!("[WARNING] Ignoring invalid admission setting: {}", e),
+                },
+                Ok(None) => {}
+                Err(e) => eprintln!("[ERROR] Failed to load admission limits: {}", e),
+            }
+            match db.get_setting(engine_manager::LOG_LEVEL_SETTING) {
+                Ok(Some(json)) => match serde_json::from_str(&json) {
+                    Ok(level) => engines.set_log_level(level),
+                    Err(e) => eprintln!("[WARNING] Ignoring invalid engine log level setting: {}", e),
+                },
+                Ok(None) => {}
+                Err(e) => eprintln!("[ERROR] Failed to load engine log level: {}", e),
+            }
+            match db.get_setting(heartbeat::HEARTBEAT_SETTING) {
+                Ok(Some(json)) => match serde_json::from_str(&json) {
+                    Ok(config) => engines.set_heartbeat_config(config),
+                    Err(e) => eprintln!("[WARNING] Ignoring invalid heartbeat setting: {}", e),
+                },
+                Ok(None) => {}
+                Err(e) => eprintln!("[ERROR] Failed to load heartbeat settings: {}", e),
+            }
+            let proxy_limits = match db.get_setting(proxy::PROXY_LIMITS_SETTING) {
+                Ok(Some(json)) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                    eprintln!("[WARNING] Ignoring invalid proxy limits setting: {}", e);
+                    ProxyLimits::default()
+                }),
+                Ok(None) => ProxyLimits::default(),
+                Err(e) => {
+                    eprintln!("[ERROR] Failed to load proxy limits: {}", e);
+                    ProxyLimits::default()
                 }
+            };
+            let session_pool = match db.get_setting(sessions::SESSION_POOL_SETTING) {
+                Ok(Some(json)) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                    eprintln!("[WARNING] Ignoring invalid session pool setting: {}", e);
+                    SessionPoolConfig::default()
+                }),
+                Ok(None) => SessionPoolConfig::default(),
+                Err(e) => {
+                    eprintln!("[ERROR] Failed to load session pool settings: {}", e);
+                    SessionPoolConfig::default()
+                }
+            };
+            match db.get_setting(engine_manager::ACTIVE_PROFILE_SETTING) {
+                Ok(Some(id)) => match id.parse().map(|id| db.get_engine_profile(id)) {
+                    Ok(Ok(profile)) => engines.set_active_profile(profile),
+                    Ok(Err(e)) => eprintln!("[ERROR] Failed to load engine profile {}: {}", id, e),
+                    Err(_) => eprintln!("[WARNING] Ignoring invalid engine profile ID {:?}", id),
+                },
+                Ok(None) => {}
+                Err(e) => eprintln!("[ERROR] Failed to load active engine profile: {}", e),
+            }
+            if engines.lazy_start() {
+                engines.defer_default();
             } else {
-                eprintln!("[ERROR] Could not find compute_engine directory");
-                eprintln!("[WARNING] Application will run with limited functionality");
+                let engine_env = db.get_engine_env(None).unwrap_or_else(|e| {
+                    eprintln!("[ERROR] Failed to load engine environment: {}", e);
+                    Default::default()
+                });
+                engines.start_default(engine_env);
+            }
+
+            let active_workspace = db.get_setting(workspaces::ACTIVE_WORKSPACE_SETTING).unwrap_or_else(|e| {
+                eprintln!("[ERROR] Failed to load active workspace: {}", e);
+                None
+            });
+
+            let mut backend = BackendSession::new();
+            match settings::backend_url(&db) {
+                Ok(url) => backend.base_url = url,
+                Err(e) => eprintln!("[ERROR] Failed to load backend URL: {}", e),
             }
 
+            proxy::clear(&data_dir.join("spill"));
+
             let state = AppState {
-                python_engine: Mutex::new(python_engine),
-                db: Mutex::new(Some(db)),
+                engines,
+                mode,
+                db: db_pool,
+                backend: Mutex::new(backend),
+                connectivity: Connectivity::new(),
+                transfers: TransferQueue::new(),
+                refreshes: RefreshQueue::new(),
+                sessions: SessionPool::new(session_pool),
+                sync: SyncWorker::new(),
+                gpu_info: Mutex::new(None),
+                engine_info: Mutex::new(None),
+                watchdog: Mutex::new(WatchdogConfig::default()),
+                proxy_limits: Mutex::new(proxy_limits),
+                http: HttpClients::default(),
+                workspace: ActiveWorkspace::default(),
+                recovery_report: recovery_report.clone(),
+                app_dir,
+                data_dir: RwLock::new(data_dir),
             };
+            let mode = state.mode.info();
             app.manage(state);
+            if mode.guest {
+                events::publish(app.handle(), events::AppEvent::ModeChanged(mode));
+            }
+
+            if let Some(uuid) = active_workspace {
+                if let Err(e) = workspaces::activate(app.handle(), &uuid) {
+                    eprintln!("[WARNING] Could not reopen workspace {}: {}", uuid, e);
+                }
+            }
+
+            if !recovery_report.operations.is_empty() {
+                let _ = app.emit("recovery-report", recovery_report);
+            }
+
+            connectivity::start_monitor(app.handle().clone());
+            transfers::start_resume_worker(app.handle().clone());
+            refresh::start_scheduler(app.handle().clone());
+            maintenance::start_scheduler(app.handle().clone());
+            resources::start_sampler(app.handle().clone());
+            engine_metrics::start_recorder(app.handle().clone());
+            engine_info::start_collector(app.handle().clone());
+            sessions::start_keeper(app.handle().clone());
+            notifications::start_listener(app.handle().clone());
+            schedules::start_scheduler(app.handle().clone());
+            sync::start_worker(app.handle().clone());
 
             println!("[NOVEM] Desktop initialized");
             Ok(())
@@ -96,23 +381,206 @@ fn main() {
                 println!("[NOVEM] Application closing...");
                 
                 if let Some(state) = window.app_handle().try_state::<AppState>() {
-                    let mut engine = state.python_engine.lock().unwrap();
-                    let _ = engine.stop();
+                    state.engines.stop_all();
                 }
             }
         })
         .plugin(tauri_plugin_opener::init())
-        .invoke_handler(tauri::generate_handler![
+        .plugin(tauri_plugin_notification::init())
+        .invoke_handler(guard::guarded(tauri::generate_handler![
             commands::get_engine_status,
             commands::get_engine_port,
             commands::restart_engine,
             commands::check_backend_health,
+            commands::get_connectivity_status,
             commands::check_compute_engine_health,
+            commands::diagnose_ports,
             commands::get_system_resources,
+            commands::get_resource_history,
+            commands::get_engine_metrics,
+            commands::get_watchdog_config,
+            commands::set_watchdog_config,
+            commands::get_gpu_info,
             commands::get_workspaces,
+            commands::create_workspace_local,
+            commands::create_project_local,
+            commands::archive_workspace,
+            commands::run_db_maintenance,
+            commands::get_failed_sync_items,
+            commands::requeue_sync_item,
+            commands::unarchive_workspace,
             commands::get_projects,
+            commands::get_db_pragma_info,
+            commands::get_storage_report,
+            commands::set_active_workspace,
+            commands::get_active_workspace,
+            commands::list_workspace_members,
+            commands::cache_workspace_members,
+            commands::check_workspace_role,
+            commands::global_search,
             commands::health_check,
-        ])
+            commands::get_recovery_report,
+            commands::get_locale,
+            commands::set_locale,
+            commands::get_recent_events,
+            commands::get_app_mode,
+            commands::set_guest_mode,
+            commands::engines::start_project_engine,
+            commands::engines::stop_project_engine,
+            commands::engines::force_kill_engine,
+            commands::engines::open_engine_tunnel,
+            commands::engines::close_engine_tunnel,
+            commands::engines::list_engine_tunnels,
+            commands::engines::list_engines,
+            commands::engines::get_engine_latency_stats,
+            commands::engines::call_compute_engine,
+            commands::engines::clear_result_cache,
+            commands::engines::get_result_cache_stats,
+            commands::engines::get_engine_env,
+            commands::engines::set_engine_env,
+            commands::engines::get_engine_admission,
+            commands::engines::set_engine_admission,
+            commands::engines::get_engine_queue_status,
+            commands::engines::verify_engine_dependencies,
+            commands::engines::discover_python_interpreters,
+            commands::engines::install_engine_dependencies,
+            commands::engines::get_engine_info,
+            commands::engines::get_engine_dev_mode,
+            commands::engines::set_engine_dev_mode,
+            commands::engines::get_engine_lazy_start,
+            commands::engines::set_engine_lazy_start,
+            commands::engines::list_engine_profiles,
+            commands::engines::create_engine_profile,
+            commands::engines::activate_engine_profile,
+            commands::engines::get_engine_readiness_probe,
+            commands::engines::set_engine_readiness_probe,
+            commands::engines::get_engine_log_level,
+            commands::engines::set_engine_log_level,
+            commands::engines::get_engine_shutdown_policy,
+            commands::engines::set_engine_shutdown_policy,
+            commands::engines::get_engine_heartbeat,
+            commands::engines::get_engine_heartbeat_config,
+            commands::engines::set_engine_heartbeat_config,
+            commands::engines::read_spilled_response,
+            commands::engines::release_spilled_response,
+            commands::engines::lease_engine_session,
+            commands::engines::release_engine_session,
+            commands::engines::get_session_pool_stats,
+            commands::engines::get_session_pool_config,
+            commands::engines::set_session_pool_config,
+            commands::engines::get_proxy_limits,
+            commands::engines::set_proxy_limits,
+            commands::engines::get_engine_update_config,
+            commands::engines::set_engine_update_config,
+            commands::engines::update_compute_engine,
+            commands::set_backend_session,
+            commands::list_local_accounts,
+            commands::switch_account,
+            commands::deprovision_device,
+            commands::transfers::queue_artifact_upload,
+            commands::transfers::list_transfers,
+            commands::transfers::retry_transfer,
+            commands::transfers::cancel_transfer,
+            commands::secrets::store_secret,
+            commands::secrets::get_secret_names,
+            commands::secrets::delete_secret,
+            commands::connections::create_connection,
+            commands::connections::list_connections,
+            commands::connections::test_connection,
+            commands::connections::delete_connection,
+            commands::jobs::create_job,
+            commands::jobs::list_jobs,
+            commands::jobs::get_job,
+            commands::jobs::start_job,
+            commands::jobs::update_job_progress,
+            commands::jobs::finish_job,
+            commands::jobs::cancel_job,
+            commands::jobs::delete_job,
+            commands::notifications::get_notifications,
+            commands::notifications::mark_read,
+            commands::schedules::create_schedule,
+            commands::schedules::list_schedules,
+            commands::schedules::update_schedule,
+            commands::schedules::delete_schedule,
+            commands::share_links::list_share_links,
+            commands::share_links::create_share_link,
+            commands::share_links::revoke_share_link,
+            commands::sync::get_sync_status,
+            commands::sync::start_sync,
+            commands::sync::stop_sync,
+            commands::sync::set_sync_interval,
+            commands::sync::trigger_sync,
+            commands::dashboards::publish_dashboard,
+            commands::dashboards::get_dashboard,
+            commands::dashboards::list_dashboards,
+            commands::notebooks::list_notebooks,
+            commands::notebooks::create_notebook,
+            commands::notebooks::list_cells,
+            commands::notebooks::create_cell,
+            commands::notebooks::update_cell,
+            commands::notebooks::save_cell_outputs,
+            commands::notebooks::record_execution,
+            commands::notebooks::get_execution_history,
+            commands::notebooks::clone_notebook,
+            commands::projects::clone_project,
+            commands::projects::bulk_move_projects,
+            commands::projects::bulk_tag,
+            commands::projects::bulk_archive,
+            commands::datasets::register_dataset,
+            commands::datasets::list_datasets,
+            commands::datasets::get_dataset_lineage,
+            commands::datasets::create_sample,
+            commands::datasets::get_column_stats,
+            commands::datasets::get_dataset_schema,
+            commands::datasets::append_to_dataset,
+            commands::datasets::save_recipe,
+            commands::datasets::list_recipes,
+            commands::datasets::delete_recipe,
+            commands::datasets::apply_recipe,
+            commands::datasets::set_dataset_refresh,
+            commands::datasets::remove_dataset_refresh,
+            commands::datasets::refresh_dataset,
+            commands::datasets::get_dataset_freshness,
+            commands::queries::run_query,
+            commands::queries::get_query_history,
+            commands::queries::favorite_query,
+            commands::queries::rerun_query,
+            commands::trash::preview_delete,
+            commands::trash::delete_entity,
+            commands::trash::delete_workspace,
+            commands::trash::list_trash,
+            commands::trash::restore_entity,
+            commands::trash::purge_trash,
+            commands::trash::get_pending_deletes,
+            commands::trash::acknowledge_delete,
+            commands::versions::check_server_version,
+            commands::versions::mark_version_synced,
+            commands::versions::apply_server_copy,
+            commands::versions::list_conflicts,
+            commands::versions::resolve_conflict,
+            commands::activity::get_activity_log,
+            commands::activity::get_activity_retention,
+            commands::activity::set_activity_retention,
+            commands::settings::get_setting,
+            commands::settings::get_all_settings,
+            commands::settings::set_setting,
+            commands::portable::export_local_data,
+            commands::portable::import_local_data,
+            commands::recent::get_recent_items,
+            commands::recent::clear_recent_items,
+            commands::favorites::toggle_favorite,
+            commands::comments::list_comments,
+            commands::comments::create_comment,
+            commands::comments::update_comment,
+            commands::comments::delete_comment,
+            commands::attachments::add_attachment,
+            commands::attachments::list_attachments,
+            commands::attachments::remove_attachment,
+            commands::templates::list_project_templates,
+            commands::templates::save_project_template,
+            commands::templates::delete_project_template,
+            commands::templates::create_project_from_template,
+        ]))
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
\ No newline at end of file
@@ -0,0 +1,53 @@
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::database::NewActivity;
+use crate::{settings, timestamps, AppState};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const MAINTENANCE_PERIOD_DAYS: i64 = 7;
+
+/// Background loop running database maintenance once a week while the
+/// `db.weekly_maintenance` setting is on. Checked hourly, so a week missed
+/// while the app was closed is made up soon after the next launch.
+pub fn start_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+
+            // Vacuuming writes; guests wait for a normal session
+            if state.mode.is_guest() {
+                continue;
+            }
+
+            let cutoff = timestamps::format(chrono::Utc::now() - chrono::Duration::days(MAINTENANCE_PERIOD_DAYS));
+            let due = state
+                .with_db_async(move |db| Ok(settings::weekly_maintenance(db)? && db.maintenance_due(&cutoff)?))
+                .await;
+            match due {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(e) => {
+                    eprintln!("[ERROR] Failed to check for due database maintenance: {}", e);
+                    continue;
+                }
+            }
+
+            match state.with_db_async(|db| db.run_maintenance()).await {
+                Ok(report) => {
+                    println!(
+                        "[NOVEM] Weekly database maintenance: integrity {}, {} pages freed",
+                        if report.integrity_ok { "ok" } else { "FAILED" },
+                        report.pages_freed
+                    );
+                    state
+                        .log_activity(NewActivity::new("maintenance", "database", None).after(&report))
+                        .await;
+                }
+                Err(e) => eprintln!("[ERROR] Weekly database maintenance failed: {}", e),
+            }
+        }
+    });
+}
@@ -0,0 +1,85 @@
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::database::{NewNotification, NotificationPriority};
+use crate::events::{self, AppEvent, EventBus};
+use crate::i18n::tr;
+use crate::engine::EngineStatus;
+use crate::AppState;
+
+/// Adds a notification to the local inbox and tells the frontend. High
+/// priority ones also raise an OS toast, for when the window is hidden.
+pub async fn notify(app: &AppHandle, notification: NewNotification) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+
+    let stored = match state.with_db_async(move |db| db.add_notification(&notification)).await {
+        Ok(stored) => stored,
+        Err(e) => {
+            eprintln!("[ERROR] Failed to store notification: {}", e);
+            return;
+        }
+    };
+
+    if stored.priority == NotificationPriority::High {
+        let mut toast = app.notification().builder().title(&stored.title);
+        if let Some(body) = &stored.body {
+            toast = toast.body(body);
+        }
+        if let Err(e) = toast.show() {
+            eprintln!("[WARNING] Failed to show notification '{}': {}", stored.title, e);
+        }
+    }
+
+    events::publish(app, AppEvent::NotificationAdded(stored));
+}
+
+/// Turns engine crashes and failed dataset refreshes published on the bus
+/// into notifications. Subscribes before returning so none are missed.
+pub fn start_listener(app: AppHandle) {
+    let mut events = app.state::<EventBus>().subscribe();
+
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = app.state::<AppState>().with_db_async(|db| db.prune_read_notifications()).await {
+            eprintln!("[WARNING] Failed to prune read notifications: {}", e);
+        }
+
+        loop {
+            let event = match events.recv().await {
+                Ok(record) => record.event,
+                Err(RecvError::Lagged(skipped)) => {
+                    eprintln!("[WARNING] Notification listener fell behind; dropped {} events", skipped);
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+
+            let notification = match event {
+                AppEvent::EngineStatusChanged(change) if change.status != change.previous => {
+                    let notification = match change.status {
+                        EngineStatus::Crashed => {
+                            NewNotification::new("engine", NotificationPriority::High, tr!("notify-engine-crashed"))
+                        }
+                        EngineStatus::Degraded => {
+                            NewNotification::new("engine", NotificationPriority::Normal, tr!("notify-engine-degraded"))
+                        }
+                        _ => continue,
+                    };
+                    match change.project_id {
+                        Some(id) => notification.body(tr!("notify-engine-project", id = id)),
+                        None => notification,
+                    }
+                }
+                AppEvent::DatasetRefreshed { uuid, success: false } => {
+                    NewNotification::new("dataset", NotificationPriority::Normal, tr!("notify-refresh-failed"))
+                        .about("dataset", &uuid)
+                }
+                _ => continue,
+            };
+
+            notify(&app, notification).await;
+        }
+    });
+}
@@ -0,0 +1,247 @@
+use serde::Serialize;
+use std::net::TcpListener;
+use std::ops::RangeInclusive;
+
+use crate::engine_manager::EngineInstance;
+use crate::engine::EngineStatus;
+
+/// How many free ports a conflicting service is offered.
+const SUGGESTIONS: usize = 3;
+
+/// A process listening on a port.
+#[derive(Debug, Clone, Serialize)]
+pub struct PortOwner {
+    pub pid: u32,
+    pub name: Option<String>,
+}
+
+impl std::fmt::Display for PortOwner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "{} (PID {})", name, self.pid),
+            None => write!(f, "PID {}", self.pid),
+        }
+    }
+}
+
+/// State of one service's port, as reported by `diagnose_ports`.
+#[derive(Debug, Clone, Serialize)]
+pub struct PortDiagnosis {
+    pub service: String, // 'backend', 'compute_engine'
+    pub project_id: Option<i64>,
+    pub port: u16,
+    /// Started by the app, so it moves to a free port by itself on conflict
+    pub managed: bool,
+    pub owner: Option<PortOwner>,
+    /// The expected service answers on the port
+    pub responding: bool,
+    /// Something other than the expected service holds the port
+    pub conflict: bool,
+    /// Free ports the service could use instead
+    pub alternatives: Vec<u16>,
+}
+
+pub fn is_free(port: u16) -> bool {
+    TcpListener::bind(("127.0.0.1", port)).is_ok()
+}
+
+/// Up to `count` free ports from `range`, skipping `exclude`.
+pub fn free_ports(range: RangeInclusive<u16>, exclude: &[u16], count: usize) -> Vec<u16> {
+    range
+        .filter(|port| !exclude.contains(port) && is_free(*port))
+        .take(count)
+        .collect()
+}
+
+/// The process listening on `port` on this machine, if it can be found.
+pub fn owner(port: u16) -> Option<PortOwner> {
+    platform::owner(port)
+}
+
+/// Checks the backend (when it runs on this machine) and every engine.
+pub fn diagnose(backend_url: &str, backend_responding: bool, engines: &[EngineInstance]) -> Vec<PortDiagnosis> {
+    let mut report = Vec::new();
+
+    if let Some(port) = local_port(backend_url) {
+        let owner = owner(port);
+        let conflict = owner.is_some() && !backend_responding;
+        report.push(PortDiagnosis {
+            service: "backend".to_string(),
+            project_id: None,
+            port,
+            managed: false,
+            alternatives: if conflict { nearby_free_ports(port, &[]) } else { Vec::new() },
+            owner,
+            responding: backend_responding,
+            conflict,
+        });
+    }
+
+    let engine_ports: Vec<u16> = engines.iter().map(|engine| engine.port).collect();
+    for engine in engines {
+        let owner = owner(engine.port);
+        let responding = engine.status == EngineStatus::Ready;
+        let conflict = matches!(&owner, Some(owner) if Some(owner.pid) != engine.pid && !responding);
+        report.push(PortDiagnosis {
+            service: "compute_engine".to_string(),
+            project_id: engine.project_id,
+            port: engine.port,
+            managed: true,
+            alternatives: if conflict { nearby_free_ports(engine.port, &engine_ports) } else { Vec::new() },
+            owner,
+            responding,
+            conflict,
+        });
+    }
+
+    report
+}
+
+fn nearby_free_ports(port: u16, exclude: &[u16]) -> Vec<u16> {
+    free_ports(port.saturating_add(1)..=port.saturating_add(100), exclude, SUGGESTIONS)
+}
+
+/// Port of a URL that points at this machine.
+fn local_port(url: &str) -> Option<u16> {
+    let url = reqwest::Url::parse(url).ok()?;
+    match url.host_str()? {
+        "localhost" | "127.0.0.1" | "[::1]" => url.port_or_known_default(),
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::PortOwner;
+
+    /// Matches the port's listening socket inode against every process's
+    /// open file descriptors. Processes of other users can't be inspected.
+    pub fn owner(port: u16) -> Option<PortOwner> {
+        let inodes = listening_inodes(port);
+        if inodes.is_empty() {
+            return None;
+        }
+
+        for entry in std::fs::read_dir("/proc").ok()?.flatten() {
+            let Some(pid) = entry.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+                continue;
+            };
+            let Ok(fds) = std::fs::read_dir(entry.path().join("fd")) else {
+                continue;
+            };
+
+            let owns = fds.flatten().any(|fd| {
+                std::fs::read_link(fd.path())
+                    .map(|target| inodes.iter().any(|inode| target.to_string_lossy() == format!("socket:[{}]", inode)))
+                    .unwrap_or(false)
+            });
+            if owns {
+                let name = std::fs::read_to_string(entry.path().join("comm"))
+                    .ok()
+                    .map(|name| name.trim().to_string());
+                return Some(PortOwner { pid, name });
+            }
+        }
+
+        None
+    }
+
+    fn listening_inodes(port: u16) -> Vec<String> {
+        const LISTEN: &str = "0A";
+
+        ["/proc/net/tcp", "/proc/net/tcp6"]
+            .iter()
+            .filter_map(|path| std::fs::read_to_string(path).ok())
+            .flat_map(|table| {
+                table
+                    .lines()
+                    .skip(1)
+                    .filter_map(|line| {
+                        let fields: Vec<&str> = line.split_whitespace().collect();
+                        let local_port = fields.get(1)?.rsplit(':').next()?;
+                        let listening = *fields.get(3)? == LISTEN;
+                        (listening && u16::from_str_radix(local_port, 16).ok()? == port)
+                            .then(|| fields.get(9).map(|inode| inode.to_string()))
+                            .flatten()
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+mod platform {
+    use super::PortOwner;
+    use std::process::Command;
+
+    pub fn owner(port: u16) -> Option<PortOwner> {
+        let output = Command::new("lsof")
+            .args(["-nP", &format!("-iTCP:{}", port), "-sTCP:LISTEN", "-Fpc"])
+            .output()
+            .ok()?;
+
+        // One field per line: p<pid>, then c<command>
+        let text = String::from_utf8_lossy(&output.stdout);
+        let pid = text.lines().find_map(|line| line.strip_prefix('p')?.parse().ok())?;
+        let name = text.lines().find_map(|line| line.strip_prefix('c')).map(str::to_string);
+        Some(PortOwner { pid, name })
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::PortOwner;
+    use std::process::Command;
+
+    pub fn owner(port: u16) -> Option<PortOwner> {
+        let output = Command::new("netstat").args(["-ano", "-p", "TCP"]).output().ok()?;
+        let text = String::from_utf8_lossy(&output.stdout);
+
+        // "  TCP    127.0.0.1:8765    0.0.0.0:0    LISTENING    1234"
+        let pid: u32 = text.lines().find_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let local_port = fields.get(1)?.rsplit(':').next()?.parse::<u16>().ok()?;
+            (local_port == port && *fields.get(3)? == "LISTENING")
+                .then(|| fields.get(4)?.parse().ok())
+                .flatten()
+        })?;
+
+        let name = Command::new("tasklist")
+            .args(["/FI", &format!("PID eq {}", pid), "/FO", "CSV", "/NH"])
+            .output()
+            .ok()
+            .and_then(|output| {
+                let text = String::from_utf8_lossy(&output.stdout).to_string();
+                text.split(',').next().map(|name| name.trim_matches('"').to_string())
+            })
+            .filter(|name| !name.is_empty() && !name.starts_with("INFO:"));
+
+        Some(PortOwner { pid, name })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_port_owner() {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        assert!(!is_free(port));
+        assert!(!free_ports(port..=port, &[], 1).contains(&port));
+
+        #[cfg(target_os = "linux")]
+        {
+            let owner = owner(port).unwrap();
+            assert_eq!(owner.pid, std::process::id());
+        }
+
+        drop(listener);
+        assert!(is_free(port));
+
+        assert_eq!(local_port("http://localhost:8000"), Some(8000));
+        assert_eq!(local_port("https://api.novem.io"), None);
+    }
+}
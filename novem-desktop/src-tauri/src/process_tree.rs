@@ -0,0 +1,215 @@
+use std::io;
+use std::process::{Child, ChildStderr, Command, ExitStatus};
+
+/// A spawned process together with everything it starts in turn (uvicorn's
+/// reloader and workers). On Unix the tree gets its own process group; on
+/// Windows it is tracked by a Job Object, so it can be killed as one unit.
+pub struct ProcessTree {
+    child: Child,
+    #[cfg(windows)]
+    job: Option<job::Job>,
+}
+
+impl ProcessTree {
+    pub fn spawn(command: &mut Command) -> io::Result<Self> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            command.process_group(0);
+        }
+
+        // Its own console process group, so CTRL_BREAK reaches only the engine
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            use windows_sys::Win32::System::Threading::CREATE_NEW_PROCESS_GROUP;
+            command.creation_flags(CREATE_NEW_PROCESS_GROUP);
+        }
+
+        let child = command.spawn()?;
+
+        // Assigned right after spawning, long before uvicorn starts workers
+        #[cfg(windows)]
+        let job = match job::Job::assign(&child) {
+            Ok(job) => Some(job),
+            Err(e) => {
+                eprintln!("[WARNING] Could not create a job object; subprocesses may outlive the engine: {}", e);
+                None
+            }
+        };
+
+        Ok(Self {
+            child,
+            #[cfg(windows)]
+            job,
+        })
+    }
+
+    pub fn id(&self) -> u32 {
+        self.child.id()
+    }
+
+    /// The main process's stderr, when it was piped. Can be taken once.
+    pub fn take_stderr(&mut self) -> Option<ChildStderr> {
+        self.child.stderr.take()
+    }
+
+    /// Whether the main process has exited. Its children may still be running.
+    pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
+        self.child.try_wait()
+    }
+
+    /// Sends SIGTERM to every process in the group.
+    #[cfg(unix)]
+    pub fn terminate(&self) {
+        // SAFETY: plain signal delivery to the process group we created
+        unsafe {
+            libc::kill(-(self.child.id() as libc::pid_t), libc::SIGTERM);
+        }
+    }
+
+    /// Sends CTRL_BREAK to the engine's process group, which Python raises
+    /// as SIGBREAK. Only delivered when the app shares a console with the
+    /// engine; otherwise the kill that follows is what stops it.
+    #[cfg(windows)]
+    pub fn terminate(&self) {
+        use windows_sys::Win32::System::Console::{GenerateConsoleCtrlEvent, CTRL_BREAK_EVENT};
+
+        // SAFETY: takes plain values; the group ID is the child's PID since
+        // it was created with CREATE_NEW_PROCESS_GROUP
+        unsafe {
+            GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, self.child.id());
+        }
+    }
+
+    /// Kills every process in the tree and reaps the main one. Safe to call
+    /// after the main process has exited, to clean up what it left behind.
+    pub fn kill(&mut self) -> io::Result<()> {
+        #[cfg(unix)]
+        {
+            // SAFETY: as in `terminate`
+            unsafe {
+                libc::kill(-(self.child.id() as libc::pid_t), libc::SIGKILL);
+            }
+        }
+
+        #[cfg(windows)]
+        {
+            if let Some(job) = &self.job {
+                job.terminate();
+            }
+        }
+
+        // The group/job kill usually got it already
+        let _ = self.child.kill();
+        self.child.wait().map(|_| ())
+    }
+}
+
+#[cfg(windows)]
+mod job {
+    use std::io;
+    use std::os::windows::io::AsRawHandle;
+    use std::process::Child;
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation, SetInformationJobObject,
+        TerminateJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+    };
+
+    /// Closing the handle kills whatever is still in the job, so a crashed
+    /// or dropped engine can't leave workers behind either.
+    pub struct Job(HANDLE);
+
+    // SAFETY: job handles may be used and closed from any thread
+    unsafe impl Send for Job {}
+
+    impl Job {
+        pub fn assign(child: &Child) -> io::Result<Self> {
+            // SAFETY: FFI calls with a handle we own and a zeroed plain-data struct
+            unsafe {
+                let handle = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+                if handle.is_null() {
+                    return Err(io::Error::last_os_error());
+                }
+                let job = Job(handle);
+
+                let mut limits: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+                limits.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+                let set = SetInformationJobObject(
+                    job.0,
+                    JobObjectExtendedLimitInformation,
+                    &limits as *const _ as *const std::ffi::c_void,
+                    std::mem::size_of_val(&limits) as u32,
+                );
+                if set == 0 {
+                    return Err(io::Error::last_os_error());
+                }
+
+                if AssignProcessToJobObject(job.0, child.as_raw_handle() as HANDLE) == 0 {
+                    return Err(io::Error::last_os_error());
+                }
+
+                Ok(job)
+            }
+        }
+
+        pub fn terminate(&self) {
+            // SAFETY: the handle stays valid until drop
+            unsafe {
+                TerminateJobObject(self.0, 1);
+            }
+        }
+    }
+
+    impl Drop for Job {
+        fn drop(&mut self) {
+            // SAFETY: closed exactly once
+            unsafe {
+                CloseHandle(self.0);
+            }
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+    use std::time::{Duration, Instant};
+
+    /// Running, as opposed to gone or a zombie waiting to be reaped.
+    fn alive(pid: u32) -> bool {
+        match std::fs::read_to_string(format!("/proc/{}/stat", pid)) {
+            Ok(stat) => !stat.rsplit(')').next().unwrap_or("").trim_start().starts_with('Z'),
+            Err(_) => false,
+        }
+    }
+
+    #[test]
+    fn test_kill_takes_down_subprocesses() {
+        if !std::path::Path::new("/proc/self/stat").exists() {
+            return;
+        }
+
+        let mut command = Command::new("sh");
+        command
+            .args(["-c", "sleep 30 & echo $!; wait"])
+            .stdout(Stdio::piped());
+        let mut tree = ProcessTree::spawn(&mut command).unwrap();
+
+        let mut line = String::new();
+        BufReader::new(tree.child.stdout.take().unwrap()).read_line(&mut line).unwrap();
+        let grandchild: u32 = line.trim().parse().unwrap();
+        assert!(alive(grandchild));
+
+        tree.kill().unwrap();
+
+        let start = Instant::now();
+        while alive(grandchild) && start.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert!(!alive(grandchild));
+    }
+}
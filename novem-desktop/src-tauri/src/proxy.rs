@@ -0,0 +1,302 @@
+use anyhow::{Context, Result};
+use serde::de::{DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+
+use crate::i18n::tr;
+
+/// Setting key persisting the proxy's response size limits.
+pub const PROXY_LIMITS_SETTING: &str = "engine.proxy_limits";
+
+/// Error bodies are only shown to the user, so anything past this is cut.
+const ERROR_BODY_LIMIT: usize = 64 * 1024;
+
+/// Elements returned per page when the caller doesn't say.
+pub const DEFAULT_PAGE_SIZE: usize = 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProxyLimits {
+    /// Bodies larger than this are written to disk instead of returned
+    pub spill_threshold_bytes: u64,
+    /// Bodies larger than this are refused outright
+    pub max_body_bytes: u64,
+}
+
+impl Default for ProxyLimits {
+    fn default() -> Self {
+        Self {
+            spill_threshold_bytes: 32 * 1024 * 1024,
+            max_body_bytes: 2 * 1024 * 1024 * 1024,
+        }
+    }
+}
+
+/// Stands in for an engine response that was too large to return. The
+/// body is read back a page at a time with `read_spilled_response`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SpilledResponse {
+    /// Always true, so the frontend can tell a handle from engine JSON
+    pub spilled: bool,
+    pub handle: String,
+    pub size_bytes: u64,
+    pub content_type: Option<String>,
+}
+
+pub enum ProxyBody {
+    Inline(Vec<u8>),
+    Spilled(SpilledResponse),
+}
+
+/// Reads a response chunk by chunk, keeping it in memory up to the spill
+/// threshold and streaming the rest to a file in `spill_dir`.
+pub async fn read_body(mut response: reqwest::Response, limits: &ProxyLimits, spill_dir: &Path) -> Result<ProxyBody> {
+    if let Some(length) = response.content_length() {
+        if length > limits.max_body_bytes {
+            return Err(anyhow::anyhow!(tr!("proxy-body-too-large", limit = limits.max_body_bytes)));
+        }
+    }
+
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    let mut buffer = Vec::new();
+    let mut spill: Option<(String, PathBuf, tokio::fs::File)> = None;
+    let mut size = 0u64;
+
+    let outcome: Result<()> = async {
+        while let Some(chunk) = response.chunk().await? {
+            size += chunk.len() as u64;
+            if size > limits.max_body_bytes {
+                return Err(anyhow::anyhow!(tr!("proxy-body-too-large", limit = limits.max_body_bytes)));
+            }
+
+            if spill.is_none() && size > limits.spill_threshold_bytes {
+                tokio::fs::create_dir_all(spill_dir).await?;
+                let handle = uuid::Uuid::new_v4().to_string();
+                let path = spill_path(spill_dir, &handle)?;
+                let mut file = tokio::fs::File::create(&path)
+                    .await
+                    .context(format!("Failed to create spill file {:?}", path))?;
+                file.write_all(&buffer).await?;
+                buffer = Vec::new();
+                spill = Some((handle, path, file));
+            }
+
+            match &mut spill {
+                Some((_, _, file)) => file.write_all(&chunk).await?,
+                None => buffer.extend_from_slice(&chunk),
+            }
+        }
+        Ok(())
+    }
+    .await;
+
+    let Some((handle, path, mut file)) = spill else {
+        return outcome.map(|_| ProxyBody::Inline(buffer));
+    };
+
+    let outcome = match outcome {
+        Ok(()) => file.flush().await.map_err(Into::into),
+        Err(e) => Err(e),
+    };
+    drop(file);
+    if let Err(e) = outcome {
+        let _ = tokio::fs::remove_file(&path).await;
+        return Err(e);
+    }
+
+    println!("[NOVEM] Engine response of {} bytes spilled to {:?}", size, path);
+    Ok(ProxyBody::Spilled(SpilledResponse {
+        spilled: true,
+        handle,
+        size_bytes: size,
+        content_type,
+    }))
+}
+
+/// Reads at most `ERROR_BODY_LIMIT` bytes of an error response.
+pub async fn read_error_body(mut response: reqwest::Response) -> String {
+    let mut body = Vec::new();
+    while let Ok(Some(chunk)) = response.chunk().await {
+        body.extend_from_slice(&chunk);
+        if body.len() >= ERROR_BODY_LIMIT {
+            body.truncate(ERROR_BODY_LIMIT);
+            break;
+        }
+    }
+    String::from_utf8_lossy(&body).into_owned()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ArrayPage {
+    pub items: Vec<Value>,
+    pub offset: usize,
+    /// Length of the whole array
+    pub total: usize,
+}
+
+/// Parses one page of a spilled JSON array without loading the rest. The
+/// array is either the whole body or, with `field`, one field of a
+/// top-level object.
+pub fn read_page(spill_dir: &Path, handle: &str, field: Option<&str>, offset: usize, limit: usize) -> Result<ArrayPage> {
+    let path = spill_path(spill_dir, handle)?;
+    let file = std::fs::File::open(&path).map_err(|_| anyhow::anyhow!(tr!("proxy-spill-not-found", handle = handle)))?;
+
+    let mut page = ArrayPage {
+        items: Vec::new(),
+        offset,
+        total: 0,
+    };
+    let mut deserializer = serde_json::Deserializer::from_reader(BufReader::new(file));
+    let array = ArraySeed {
+        page: &mut page,
+        limit,
+    };
+    match field {
+        Some(field) => FieldSeed { field, array }.deserialize(&mut deserializer)?,
+        None => array.deserialize(&mut deserializer)?,
+    }
+    deserializer.end()?;
+
+    Ok(page)
+}
+
+pub fn release(spill_dir: &Path, handle: &str) -> Result<()> {
+    let path = spill_path(spill_dir, handle)?;
+    std::fs::remove_file(&path).map_err(|_| anyhow::anyhow!(tr!("proxy-spill-not-found", handle = handle)))
+}
+
+/// Spilled bodies only live for one session.
+pub fn clear(spill_dir: &Path) {
+    if spill_dir.exists() {
+        if let Err(e) = std::fs::remove_dir_all(spill_dir) {
+            eprintln!("[WARNING] Failed to clear spilled responses in {:?}: {}", spill_dir, e);
+        }
+    }
+}
+
+/// Handles are UUIDs, which also keeps them from naming files elsewhere.
+fn spill_path(spill_dir: &Path, handle: &str) -> Result<PathBuf> {
+    let uuid = uuid::Uuid::parse_str(handle).map_err(|_| anyhow::anyhow!(tr!("proxy-spill-not-found", handle = handle)))?;
+    Ok(spill_dir.join(format!("{}.json", uuid)))
+}
+
+/// Collects `limit` elements from `page.offset` on and skips the others,
+/// counting them all.
+struct ArraySeed<'a> {
+    page: &'a mut ArrayPage,
+    limit: usize,
+}
+
+impl<'de> DeserializeSeed<'de> for ArraySeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ArraySeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a JSON array")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let page = self.page;
+        let end = page.offset.saturating_add(self.limit);
+        loop {
+            let wanted = (page.offset..end).contains(&page.total);
+            let more = if wanted {
+                match seq.next_element::<Value>()? {
+                    Some(item) => {
+                        page.items.push(item);
+                        true
+                    }
+                    None => false,
+                }
+            } else {
+                seq.next_element::<IgnoredAny>()?.is_some()
+            };
+            if !more {
+                return Ok(());
+            }
+            page.total += 1;
+        }
+    }
+}
+
+struct FieldSeed<'a> {
+    field: &'a str,
+    array: ArraySeed<'a>,
+}
+
+impl<'de> DeserializeSeed<'de> for FieldSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for FieldSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a JSON object with an array field '{}'", self.field)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        let mut array = Some(self.array);
+        while let Some(key) = map.next_key::<String>()? {
+            match array.take() {
+                Some(seed) if key == self.field => map.next_value_seed(seed)?,
+                seed => {
+                    array = seed;
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        match array {
+            Some(_) => Err(serde::de::Error::custom(format!("field '{}' not found", self.field))),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_page() {
+        let spill_dir = std::env::temp_dir().join("test_novem_proxy_spill");
+        std::fs::create_dir_all(&spill_dir).unwrap();
+
+        let handle = uuid::Uuid::new_v4().to_string();
+        let rows: Vec<Value> = (0..10).map(|i| serde_json::json!({ "id": i })).collect();
+        let body = serde_json::json!({ "columns": ["id"], "rows": rows, "row_count": 10 });
+        std::fs::write(spill_path(&spill_dir, &handle).unwrap(), body.to_string()).unwrap();
+
+        let page = read_page(&spill_dir, &handle, Some("rows"), 8, 5).unwrap();
+        assert_eq!(page.total, 10);
+        assert_eq!(page.items, vec![serde_json::json!({ "id": 8 }), serde_json::json!({ "id": 9 })]);
+
+        assert!(read_page(&spill_dir, &handle, None, 0, 5).is_err());
+        assert!(read_page(&spill_dir, &handle, Some("missing"), 0, 5).is_err());
+        assert!(read_page(&spill_dir, "../../etc/passwd", None, 0, 5).is_err());
+
+        release(&spill_dir, &handle).unwrap();
+        assert!(read_page(&spill_dir, &handle, Some("rows"), 0, 5).is_err());
+    }
+}
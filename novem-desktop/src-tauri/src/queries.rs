@@ -0,0 +1,111 @@
+use anyhow::Result;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::{Duration, Instant};
+
+use crate::database::NewQueryHistory;
+use crate::settings;
+use crate::AppState;
+
+/// Rows returned to the UI per query; the engine reports whether more exist.
+const RESULT_ROW_LIMIT: usize = 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryResult {
+    pub history_id: Option<i64>,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+    pub row_count: u64,
+    pub truncated: bool,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct EngineQueryResponse {
+    columns: Vec<String>,
+    rows: Vec<Vec<Value>>,
+    row_count: u64,
+    truncated: bool,
+}
+
+async fn execute_on_engine(client: &Client, base_url: &str, sql: &str, timeout: Duration) -> Result<EngineQueryResponse> {
+    let response = client
+        .post(format!("{}/query/execute", base_url))
+        .timeout(timeout)
+        .json(&serde_json::json!({ "sql": sql, "limit": RESULT_ROW_LIMIT }))
+        .send()
+        .await?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let detail = response
+            .json::<Value>()
+            .await
+            .ok()
+            .and_then(|body| body.get("detail").and_then(|d| d.as_str()).map(str::to_string))
+            .unwrap_or_else(|| status.to_string());
+        return Err(anyhow::anyhow!("Query failed: {}", detail));
+    }
+
+    Ok(response.json().await?)
+}
+
+/// Stores a history entry; failures to record never fail the query itself.
+pub fn record(state: &AppState, entry: NewQueryHistory) -> Option<i64> {
+    match state.with_db(|db| db.add_query_history(&entry)) {
+        Ok(id) => Some(id),
+        Err(e) => {
+            eprintln!("[ERROR] Failed to record query history: {}", e);
+            None
+        }
+    }
+}
+
+/// Runs SQL on the engine serving `project_id` and records it in the query
+/// history, whether it succeeds or not.
+pub async fn run_sql(
+    state: &AppState,
+    sql: &str,
+    project_id: Option<i64>,
+    request_id: &str,
+) -> Result<QueryResult> {
+    let base_url = state.engines.base_url(project_id)?;
+    let timeout = state.with_db_async(settings::query_timeout).await?;
+    let _permit = state.engines.admission(project_id).acquire(request_id).await?;
+
+    let started = Instant::now();
+    let outcome = execute_on_engine(state.http.engine(), &base_url, sql, timeout).await;
+    let duration_ms = started.elapsed().as_millis() as u64;
+
+    let target = match project_id {
+        Some(project_id) => format!("engine:project:{}", project_id),
+        None => "engine:default".to_string(),
+    };
+
+    let history_id = record(
+        state,
+        NewQueryHistory {
+            kind: "sql".to_string(),
+            query_text: sql.to_string(),
+            target: Some(target),
+            project_id,
+            params: "{}".to_string(),
+            duration_ms: duration_ms as i64,
+            row_count: outcome.as_ref().ok().map(|r| r.row_count as i64),
+            error_message: outcome.as_ref().err().map(|e| e.to_string()),
+        },
+    );
+
+    let response = outcome?;
+    println!("[NOVEM] Query returned {} rows in {}ms", response.row_count, duration_ms);
+
+    Ok(QueryResult {
+        history_id,
+        columns: response.columns,
+        rows: response.rows,
+        row_count: response.row_count,
+        truncated: response.truncated,
+        duration_ms,
+    })
+}
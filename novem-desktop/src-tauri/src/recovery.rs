@@ -0,0 +1,308 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+
+use crate::database::{JournalEntry, LocalDatabase};
+use crate::datasets::{self, DatasetFormat};
+use crate::engine_update;
+use crate::i18n::tr;
+use crate::AppState;
+
+/// A multi-step operation recorded in the journal, with what recovery needs
+/// to undo or finish it after a crash.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Operation {
+    /// Sample file written, then registered as a dataset
+    Sample { dataset_uuid: String, file_path: String },
+    /// Recipe output written, then registered as a dataset
+    Transform { dataset_uuid: String, file_path: String },
+    /// Rows appended to a dataset file, then its size and sketches updated
+    Append { dataset_uuid: String, file_path: String, format: String, original_len: u64 },
+    /// Dataset copies staged, the clone committed, then copies moved into place
+    CloneProject { staging_dir: String, datasets_dir: String },
+    /// Engine directory moved aside, then the staged bundle moved into place
+    UpdateEngine { engine_dir: String, staging_dir: String, backup_dir: String },
+    /// File copied into managed storage, then registered as an attachment
+    AddAttachment { attachment_uuid: String, file_path: String },
+}
+
+impl Operation {
+    fn kind(&self) -> &'static str {
+        match self {
+            Operation::Sample { .. } => "sample",
+            Operation::Transform { .. } => "transform",
+            Operation::Append { .. } => "append",
+            Operation::CloneProject { .. } => "clone_project",
+            Operation::UpdateEngine { .. } => "update_engine",
+            Operation::AddAttachment { .. } => "add_attachment",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryOutcome {
+    RolledBack,
+    Resumed,
+    Failed,
+}
+
+impl RecoveryOutcome {
+    fn as_status(self) -> &'static str {
+        match self {
+            RecoveryOutcome::RolledBack => "rolled_back",
+            RecoveryOutcome::Resumed => "resumed",
+            RecoveryOutcome::Failed => "failed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecoveredOperation {
+    pub id: i64,
+    pub kind: String,
+    pub started_at: String,
+    pub outcome: RecoveryOutcome,
+    pub detail: String,
+}
+
+/// Emitted as `recovery-report` after startup when anything was recovered.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RecoveryReport {
+    pub operations: Vec<RecoveredOperation>,
+}
+
+// Journal bookkeeping never fails the operation itself; without an entry
+// the operation simply isn't recoverable.
+
+pub fn begin(state: &AppState, operation: &Operation) -> Option<i64> {
+    let result = serde_json::to_string(operation)
+        .map_err(anyhow::Error::from)
+        .and_then(|json| state.with_db(|db| db.begin_operation(operation.kind(), &json)));
+
+    match result {
+        Ok(id) => Some(id),
+        Err(e) => {
+            eprintln!("[ERROR] Failed to journal {} operation: {}", operation.kind(), e);
+            None
+        }
+    }
+}
+
+pub fn advance(state: &AppState, id: Option<i64>, step: &str, operation: &Operation) {
+    let Some(id) = id else { return };
+    let result = serde_json::to_string(operation)
+        .map_err(anyhow::Error::from)
+        .and_then(|json| state.with_db(|db| db.advance_operation(id, step, &json)));
+
+    if let Err(e) = result {
+        eprintln!("[ERROR] Failed to update journal entry {}: {}", id, e);
+    }
+}
+
+pub fn finish(state: &AppState, id: Option<i64>, status: &str) {
+    let Some(id) = id else { return };
+    if let Err(e) = state.with_db(|db| db.finish_operation(id, status)) {
+        eprintln!("[ERROR] Failed to close journal entry {}: {}", id, e);
+    }
+}
+
+/// Undoes an operation that hasn't reached a resumable step.
+pub fn roll_back(db: &LocalDatabase, operation: &Operation) -> Result<String> {
+    match operation {
+        Operation::Sample { dataset_uuid, file_path } => {
+            remove_if_exists(Path::new(file_path))?;
+            Ok(tr!("recovery-sample-removed", uuid = dataset_uuid.as_str()))
+        }
+        Operation::Transform { dataset_uuid, file_path } => {
+            remove_if_exists(Path::new(file_path))?;
+            Ok(tr!("recovery-transform-removed", uuid = dataset_uuid.as_str()))
+        }
+        Operation::Append { dataset_uuid, file_path, format, original_len } => {
+            let path = Path::new(file_path);
+            match DatasetFormat::parse(format)? {
+                DatasetFormat::Csv => {
+                    OpenOptions::new()
+                        .write(true)
+                        .open(path)
+                        .and_then(|file| file.set_len(*original_len))
+                        .context(format!("Failed to truncate {:?}", path))?;
+                }
+                DatasetFormat::Parquet => remove_if_exists(&datasets::append_temp_path(path))?,
+            }
+            Ok(tr!("recovery-append-discarded", uuid = dataset_uuid.as_str()))
+        }
+        Operation::CloneProject { .. } => {
+            let moved = settle_clone(db, operation)?;
+            Ok(tr!("recovery-clone-cleaned", moved = moved))
+        }
+        Operation::UpdateEngine { engine_dir, staging_dir, backup_dir } => {
+            engine_update::restore(Path::new(engine_dir), Path::new(staging_dir), Path::new(backup_dir))?;
+            Ok(tr!("recovery-engine-update-restored"))
+        }
+        Operation::AddAttachment { attachment_uuid, file_path } => {
+            remove_if_exists(Path::new(file_path))?;
+            Ok(tr!("recovery-attachment-removed", uuid = attachment_uuid.as_str()))
+        }
+    }
+}
+
+/// Moves staged dataset copies whose clone committed into place and deletes
+/// the rest. Returns how many were moved.
+pub fn settle_clone(db: &LocalDatabase, operation: &Operation) -> Result<usize> {
+    let Operation::CloneProject { staging_dir, datasets_dir } = operation else {
+        return Ok(0);
+    };
+    let staging_dir = Path::new(staging_dir);
+    if !staging_dir.exists() {
+        return Ok(0);
+    }
+
+    let mut moved = 0;
+    for entry in std::fs::read_dir(staging_dir)? {
+        let staged = entry?.path();
+        let dest: PathBuf = Path::new(datasets_dir).join(staged.file_name().unwrap_or_default());
+
+        if db.is_dataset_file_registered(&dest.to_string_lossy())? {
+            std::fs::rename(&staged, &dest).context(format!("Failed to move {:?} into place", staged))?;
+            moved += 1;
+        } else {
+            std::fs::remove_file(&staged)?;
+        }
+    }
+
+    std::fs::remove_dir(staging_dir)?;
+    Ok(moved)
+}
+
+fn remove_if_exists(path: &Path) -> Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(anyhow::Error::from(e).context(format!("Failed to remove {:?}", path)))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn recover_entry(db: &LocalDatabase, entry: &JournalEntry) -> Result<(RecoveryOutcome, String)> {
+    let operation: Operation = serde_json::from_str(&entry.state)?;
+
+    match (&operation, entry.step.as_str()) {
+        (Operation::Sample { dataset_uuid, .. }, _) => {
+            if db.get_dataset(dataset_uuid)?.is_some() {
+                Ok((RecoveryOutcome::Resumed, tr!("recovery-sample-kept", uuid = dataset_uuid.as_str())))
+            } else {
+                Ok((RecoveryOutcome::RolledBack, roll_back(db, &operation)?))
+            }
+        }
+        (Operation::Transform { dataset_uuid, .. }, _) => {
+            if db.get_dataset(dataset_uuid)?.is_some() {
+                Ok((RecoveryOutcome::Resumed, tr!("recovery-transform-kept", uuid = dataset_uuid.as_str())))
+            } else {
+                Ok((RecoveryOutcome::RolledBack, roll_back(db, &operation)?))
+            }
+        }
+        (Operation::AddAttachment { attachment_uuid, .. }, _) => {
+            if db.get_attachment(attachment_uuid)?.is_some() {
+                Ok((RecoveryOutcome::Resumed, tr!("recovery-attachment-kept", uuid = attachment_uuid.as_str())))
+            } else {
+                Ok((RecoveryOutcome::RolledBack, roll_back(db, &operation)?))
+            }
+        }
+        (Operation::Append { dataset_uuid, file_path, format, .. }, "written") => {
+            // The file is complete; redo the bookkeeping from the file itself
+            let path = Path::new(file_path);
+            let rows = datasets::count_rows(path, DatasetFormat::parse(format)?)?;
+            let size = std::fs::metadata(path)?.len();
+            db.update_dataset_size(dataset_uuid, rows as i64, size as i64)?;
+            // Sketches may predate the append; they are rebuilt on next use
+            db.clear_dataset_profile(dataset_uuid)?;
+            Ok((
+                RecoveryOutcome::Resumed,
+                tr!("recovery-append-finished", uuid = dataset_uuid.as_str(), rows = rows),
+            ))
+        }
+        (Operation::CloneProject { .. }, _) => {
+            let moved = settle_clone(db, &operation)?;
+            if moved > 0 {
+                Ok((RecoveryOutcome::Resumed, tr!("recovery-clone-moved", moved = moved)))
+            } else {
+                Ok((RecoveryOutcome::RolledBack, tr!("recovery-clone-removed")))
+            }
+        }
+        (Operation::UpdateEngine { engine_dir, staging_dir, backup_dir }, _)
+            if Path::new(engine_dir).exists() && !Path::new(staging_dir).exists() =>
+        {
+            // The new bundle made it into place; only the old copy is left
+            if Path::new(backup_dir).exists() {
+                std::fs::remove_dir_all(backup_dir)?;
+            }
+            Ok((RecoveryOutcome::Resumed, tr!("recovery-engine-update-kept")))
+        }
+        _ => Ok((RecoveryOutcome::RolledBack, roll_back(db, &operation)?)),
+    }
+}
+
+/// Rolls back or resumes every operation a crash interrupted. Runs at
+/// startup before any command can touch the affected data.
+pub fn recover(db: &LocalDatabase) -> Result<RecoveryReport> {
+    let mut report = RecoveryReport::default();
+
+    for entry in db.get_interrupted_operations()? {
+        let (outcome, detail) = match recover_entry(db, &entry) {
+            Ok(result) => result,
+            Err(e) => (RecoveryOutcome::Failed, e.to_string()),
+        };
+
+        println!("[NOVEM] Recovered {} operation {}: {:?} - {}", entry.kind, entry.id, outcome, detail);
+        db.finish_operation(entry.id, outcome.as_status())?;
+
+        report.operations.push(RecoveredOperation {
+            id: entry.id,
+            kind: entry.kind,
+            started_at: entry.started_at,
+            outcome,
+            detail,
+        });
+    }
+
+    db.prune_operation_journal()?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interrupted_csv_append_is_rolled_back() {
+        let dir = std::env::temp_dir().join("test_novem_recovery");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let db = LocalDatabase::new(dir.join("novem.db"), None).unwrap();
+
+        let csv = dir.join("orders.csv");
+        std::fs::write(&csv, "id,amount\n1,10\n").unwrap();
+        let operation = Operation::Append {
+            dataset_uuid: "ds".to_string(),
+            file_path: csv.to_string_lossy().to_string(),
+            format: "csv".to_string(),
+            original_len: 15,
+        };
+        db.begin_operation(operation.kind(), &serde_json::to_string(&operation).unwrap()).unwrap();
+
+        // Crash midway through writing a row
+        std::fs::write(&csv, "id,amount\n1,10\n2,2").unwrap();
+
+        let report = recover(&db).unwrap();
+        assert_eq!(report.operations.len(), 1);
+        assert_eq!(report.operations[0].outcome, RecoveryOutcome::RolledBack);
+        assert_eq!(std::fs::read_to_string(&csv).unwrap(), "id,amount\n1,10\n");
+        assert!(db.get_interrupted_operations().unwrap().is_empty());
+
+        drop(db);
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
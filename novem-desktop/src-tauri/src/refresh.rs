@@ -0,0 +1,319 @@
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::io::AsyncWriteExt;
+
+use crate::database::{Dataset, DatasetRefresh};
+use crate::datasets::{self, DatasetFormat};
+use crate::events::{self, AppEvent};
+use crate::i18n::tr;
+use crate::{timestamps, AppState};
+
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Wait before retrying a failed refresh; doubles with each consecutive
+/// failure, but never exceeds the schedule's own interval.
+const RETRY_BASE_SECS: i64 = 60;
+
+/// Shortest refresh interval a schedule may declare.
+pub const MIN_INTERVAL_SECS: i64 = 60;
+
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Where a refreshed dataset's data comes from. The format is taken from
+/// the URL or path extension unless given.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RefreshSource {
+    /// A REST endpoint or cloud storage object (e.g. a presigned URL)
+    /// returning CSV or Parquet
+    Http {
+        url: String,
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+        format: Option<String>,
+    },
+    /// A file on disk, such as one in a folder synced from cloud storage
+    File { path: String, format: Option<String> },
+}
+
+impl RefreshSource {
+    pub fn format(&self) -> Result<DatasetFormat> {
+        match self {
+            RefreshSource::Http { format: Some(format), .. } | RefreshSource::File { format: Some(format), .. } => {
+                DatasetFormat::parse(format)
+            }
+            RefreshSource::Http { url, format: None, .. } => {
+                let url = reqwest::Url::parse(url)?;
+                DatasetFormat::from_path(Path::new(url.path()))
+            }
+            RefreshSource::File { path, format: None } => DatasetFormat::from_path(Path::new(path)),
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if let RefreshSource::Http { url, .. } = self {
+            let parsed = reqwest::Url::parse(url).map_err(|_| anyhow::anyhow!(tr!("refresh-source-invalid", source = url.as_str())))?;
+            if !matches!(parsed.scheme(), "http" | "https") {
+                return Err(anyhow::anyhow!(tr!("refresh-source-invalid", source = url.as_str())));
+            }
+        }
+        self.format().map(|_| ())
+    }
+}
+
+/// Datasets being refreshed right now, so the scheduler and manual
+/// refreshes never import the same dataset twice at once.
+pub struct RefreshQueue {
+    active: Mutex<HashSet<String>>,
+}
+
+impl RefreshQueue {
+    pub fn new() -> Self {
+        Self {
+            active: Mutex::new(HashSet::new()),
+        }
+    }
+
+    fn try_claim(&self, uuid: &str) -> bool {
+        self.active.lock().unwrap().insert(uuid.to_string())
+    }
+
+    fn release(&self, uuid: &str) {
+        self.active.lock().unwrap().remove(uuid);
+    }
+
+    pub fn is_active(&self, uuid: &str) -> bool {
+        self.active.lock().unwrap().contains(uuid)
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.active.lock().unwrap().is_empty()
+    }
+}
+
+fn retry_delay(interval_secs: i64, failures: i64) -> i64 {
+    let doublings = (failures - 1).clamp(0, 20) as u32;
+    RETRY_BASE_SECS.saturating_mul(1 << doublings).min(interval_secs)
+}
+
+fn after(secs: i64) -> String {
+    timestamps::format(chrono::Utc::now() + chrono::Duration::seconds(secs))
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DatasetFreshness {
+    pub dataset_uuid: String,
+    /// When the dataset file was last written, by a refresh or otherwise
+    pub modified_at: Option<String>,
+    pub schedule: Option<DatasetRefresh>,
+    pub refreshing: bool,
+    /// Scheduled, but not refreshed successfully within its interval
+    pub stale: bool,
+}
+
+pub fn freshness(state: &AppState, dataset: &Dataset) -> Result<DatasetFreshness> {
+    let schedule = state.with_db(|db| db.get_dataset_refresh(&dataset.uuid))?;
+    let modified_at = std::fs::metadata(&dataset.file_path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .map(|modified| timestamps::format(modified.into()));
+
+    let stale = schedule.as_ref().is_some_and(|schedule| {
+        let deadline = schedule
+            .last_success_at
+            .as_deref()
+            .and_then(timestamps::parse)
+            .map(|at| at + chrono::Duration::seconds(schedule.interval_secs + SCHEDULER_INTERVAL.as_secs() as i64));
+        deadline.is_none_or(|deadline| deadline < chrono::Utc::now())
+    });
+
+    Ok(DatasetFreshness {
+        dataset_uuid: dataset.uuid.clone(),
+        modified_at,
+        schedule,
+        refreshing: state.refreshes.is_active(&dataset.uuid),
+        stale,
+    })
+}
+
+/// Re-imports a dataset from its scheduled source and records the outcome,
+/// pushing the next run back after failures.
+pub async fn run_refresh(app: &AppHandle, dataset_uuid: &str) -> Result<Dataset> {
+    let state = app.state::<AppState>();
+    if !state.refreshes.try_claim(dataset_uuid) {
+        return Err(anyhow::anyhow!(tr!("refresh-already-running", uuid = dataset_uuid)));
+    }
+
+    let result = refresh(&state, dataset_uuid).await;
+    state.refreshes.release(dataset_uuid);
+
+    events::publish(
+        app,
+        AppEvent::DatasetRefreshed { uuid: dataset_uuid.to_string(), success: result.is_ok() },
+    );
+    result
+}
+
+async fn refresh(state: &AppState, dataset_uuid: &str) -> Result<Dataset> {
+    let schedule = state
+        .with_db(|db| db.get_dataset_refresh(dataset_uuid))?
+        .ok_or_else(|| anyhow::anyhow!(tr!("refresh-not-scheduled", uuid = dataset_uuid)))?;
+    let dataset = state
+        .with_db(|db| db.get_dataset(dataset_uuid))?
+        .ok_or_else(|| anyhow::anyhow!(tr!("dataset-not-found", uuid = dataset_uuid)))?;
+
+    state.with_db(|db| db.mark_refresh_started(dataset_uuid))?;
+    println!("[NOVEM] Refreshing dataset {} ({})", dataset.name, dataset_uuid);
+
+    match import(state, &schedule, &dataset).await {
+        Ok(dataset) => {
+            let next = after(schedule.interval_secs);
+            state.with_db(|db| db.record_refresh_success(dataset_uuid, &next))?;
+            Ok(dataset)
+        }
+        Err(e) => {
+            let delay = retry_delay(schedule.interval_secs, schedule.consecutive_failures + 1);
+            eprintln!("[ERROR] Refresh of dataset {} failed, retrying in {}s: {:#}", dataset_uuid, delay, e);
+            let message = format!("{:#}", e);
+            state.with_db(|db| db.record_refresh_failure(dataset_uuid, &message, &after(delay)))?;
+            Err(e)
+        }
+    }
+}
+
+/// Fetches the source and rewrites it as the dataset's managed Parquet
+/// file. The old file stays in place until the new one is complete.
+async fn import(state: &AppState, schedule: &DatasetRefresh, dataset: &Dataset) -> Result<Dataset> {
+    let source: RefreshSource = serde_json::from_value(schedule.source.clone()).context("Invalid refresh source")?;
+    let format = source.format()?;
+    let dest = datasets::managed_dataset_path(&state.data_dir(), &dataset.uuid);
+
+    let (input, downloaded) = match &source {
+        RefreshSource::Http { url, headers, .. } => {
+            let download = dest.with_extension(format!("download.{}", format.as_str()));
+            if let Err(e) = download_to(state.http.backend(), url, headers, &download).await {
+                let _ = tokio::fs::remove_file(&download).await;
+                return Err(e);
+            }
+            (download, true)
+        }
+        RefreshSource::File { path, .. } => (PathBuf::from(path), false),
+    };
+
+    let converted = {
+        let (input, dest) = (input.clone(), dest.clone());
+        tauri::async_runtime::spawn_blocking(move || rewrite_as_parquet(&input, format, &dest)).await?
+    };
+    if downloaded {
+        let _ = std::fs::remove_file(&input);
+    }
+    let rows = converted?;
+
+    let size_bytes = std::fs::metadata(&dest).map(|m| m.len() as i64).unwrap_or(0);
+    let file_path = dest.to_string_lossy().to_string();
+    state.with_db(|db| {
+        db.replace_dataset_file(&dataset.uuid, &file_path, DatasetFormat::Parquet.as_str(), rows as i64, size_bytes)?;
+        db.get_dataset(&dataset.uuid)?
+            .ok_or_else(|| anyhow::anyhow!(tr!("dataset-not-found", uuid = dataset.uuid.as_str())))
+    })
+}
+
+async fn download_to(client: &Client, url: &str, headers: &BTreeMap<String, String>, dest: &Path) -> Result<()> {
+    let mut request = client.get(url).timeout(DOWNLOAD_TIMEOUT);
+    for (name, value) in headers {
+        request = request.header(name, value);
+    }
+
+    let mut response = request
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .context(format!("Failed to download {}", url))?;
+
+    if let Some(parent) = dest.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let mut file = tokio::fs::File::create(dest)
+        .await
+        .context(format!("Failed to create {:?}", dest))?;
+    while let Some(chunk) = response.chunk().await? {
+        file.write_all(&chunk).await?;
+    }
+    file.flush().await?;
+    Ok(())
+}
+
+fn rewrite_as_parquet(input: &Path, format: DatasetFormat, dest: &Path) -> Result<u64> {
+    let temp = dest.with_extension("refresh.tmp");
+    let schema = datasets::read_schema(input, format)?;
+    let batches = datasets::open_batches(input, format, None)?.map(|batch| batch.map_err(Into::into));
+
+    let rows = datasets::write_parquet(&temp, schema, batches)
+        .and_then(|rows| std::fs::rename(&temp, dest).context(format!("Failed to replace {:?}", dest)).map(|_| rows));
+    if rows.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    rows
+}
+
+/// Background loop starting refreshes as they come due. Each runs in its
+/// own task, so a slow source doesn't hold up the others.
+pub fn start_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+
+        loop {
+            tokio::time::sleep(SCHEDULER_INTERVAL).await;
+
+            // Guests never write datasets; schedules wait for a normal session
+            if state.mode.is_guest() {
+                continue;
+            }
+
+            let due = state
+                .with_db(|db| db.get_due_refreshes(&timestamps::now()))
+                .unwrap_or_else(|e| {
+                    eprintln!("[ERROR] Failed to load due dataset refreshes: {}", e);
+                    Vec::new()
+                });
+
+            for schedule in due {
+                if state.refreshes.is_active(&schedule.dataset_uuid) {
+                    continue;
+                }
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    // Failures are recorded on the schedule and retried
+                    let _ = run_refresh(&app, &schedule.dataset_uuid).await;
+                });
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_and_source_format() {
+        assert_eq!(retry_delay(3600, 1), 60);
+        assert_eq!(retry_delay(3600, 3), 240);
+        assert_eq!(retry_delay(3600, 10), 3600);
+        assert_eq!(retry_delay(3600, 1000), 3600);
+
+        let source: RefreshSource = serde_json::from_value(serde_json::json!({
+            "kind": "http",
+            "url": "https://example.com/exports/orders.parquet?token=abc"
+        }))
+        .unwrap();
+        assert_eq!(source.format().unwrap(), DatasetFormat::Parquet);
+        assert!(RefreshSource::File { path: "orders.xlsx".to_string(), format: None }.validate().is_err());
+    }
+}
@@ -0,0 +1,170 @@
+use anyhow::Result;
+use reqwest::Client;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::commands::{DetailedStatus, SystemResources};
+use crate::database::ResourceSample;
+use crate::events::{self, AppEvent};
+use crate::watchdog::{AlertState, Metric, ResourceAlert, RunningJob, Watchdog};
+use crate::AppState;
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(10);
+const SAMPLE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Compaction runs every this many samples (5 minutes).
+const COMPACT_EVERY: u32 = 30;
+
+/// Parses "90s", "15m", "1h", "7d" into seconds.
+pub fn parse_duration(text: &str) -> Result<i64> {
+    let text = text.trim();
+    let split = text.len().saturating_sub(1);
+    let (amount, unit) = text.split_at(split);
+    let amount: i64 = amount
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid duration '{}', expected e.g. 15m, 1h, 7d", text))?;
+
+    let seconds = match unit {
+        "s" => amount,
+        "m" => amount * 60,
+        "h" => amount * 60 * 60,
+        "d" => amount * 24 * 60 * 60,
+        _ => return Err(anyhow::anyhow!("Invalid duration '{}', expected e.g. 15m, 1h, 7d", text)),
+    };
+
+    Ok(seconds)
+}
+
+async fn fetch_resources(state: &AppState, client: &Client) -> Result<SystemResources> {
+    let port = state.engines.port(None)?;
+    let status: DetailedStatus = client
+        .get(format!("http://127.0.0.1:{}/health/status", port))
+        .timeout(SAMPLE_TIMEOUT)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    status
+        .resources
+        .ok_or_else(|| anyhow::anyhow!("No resources in response"))
+}
+
+fn to_sample(resources: &SystemResources, sampled_at: i64) -> ResourceSample {
+    ResourceSample {
+        sampled_at,
+        cpu_percent: resources.cpu_percent,
+        memory_percent: resources.memory_percent,
+        memory_used_gb: resources.memory_total_gb - resources.memory_available_gb,
+        engine_cpu_percent: resources.engine_cpu_percent,
+        engine_memory_mb: resources.engine_memory_mb,
+    }
+}
+
+/// Turns the engine's cumulative write counter into MB/s since the last
+/// reading. A counter going backwards means the engine restarted.
+struct DiskWriteRate {
+    last: Option<(i64, u64)>,
+}
+
+impl DiskWriteRate {
+    fn update(&mut self, now: i64, bytes: Option<u64>) -> Option<f64> {
+        let previous = std::mem::replace(&mut self.last, bytes.map(|bytes| (now, bytes)));
+        let (then, before) = previous?;
+        let bytes = bytes?;
+        if bytes < before || now <= then {
+            return None;
+        }
+        Some((bytes - before) as f64 / (1024.0 * 1024.0) / (now - then) as f64)
+    }
+}
+
+/// Raises or clears watchdog alerts for this round of readings.
+fn check_watchdog(
+    app: &AppHandle,
+    state: &AppState,
+    watchdog: &mut Watchdog,
+    resources: Option<&SystemResources>,
+    disk_write_rate: Option<f64>,
+    now: i64,
+) {
+    let config = match state.watchdog.lock() {
+        Ok(config) => *config,
+        Err(_) => return,
+    };
+
+    let readings = [
+        (Metric::EngineCpu, resources.and_then(|r| r.engine_cpu_percent)),
+        (Metric::EngineMemory, resources.and_then(|r| r.engine_memory_mb)),
+        (Metric::EngineDiskWrite, disk_write_rate),
+        (Metric::AppCpu, resources.and_then(|r| r.app_cpu_percent)),
+        (Metric::AppMemory, resources.and_then(|r| r.app_memory_mb)),
+    ];
+
+    for mut alert in watchdog.observe(&config, &readings, now) {
+        if alert.state == AlertState::Raised {
+            alert.jobs = running_jobs(state, &alert);
+            eprintln!(
+                "[WARNING] {:?} at {:.1} over threshold {:.1} for {}s (jobs: {:?})",
+                alert.metric,
+                alert.value,
+                alert.threshold,
+                now - alert.since,
+                alert.jobs.iter().map(|job| job.request_id.as_str()).collect::<Vec<_>>()
+            );
+        } else {
+            println!("[NOVEM] {:?} back under threshold", alert.metric);
+        }
+        events::publish(app, AppEvent::ResourceAlert(alert));
+    }
+}
+
+/// The sampler reads the default engine, so engine alerts point at its
+/// requests; app-wide alerts may come from any engine.
+fn running_jobs(state: &AppState, alert: &ResourceAlert) -> Vec<RunningJob> {
+    state
+        .engines
+        .running_requests()
+        .into_iter()
+        .filter(|(project_id, _)| !alert.metric.is_engine() || project_id.is_none())
+        .map(|(project_id, request_id)| RunningJob { project_id, request_id })
+        .collect()
+}
+
+/// Background loop recording system and engine metrics for the monitoring
+/// charts and watching for runaway usage. Samples are skipped (leaving
+/// gaps) while the engine is down.
+pub fn start_sampler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let client = state.http.engine();
+
+        let mut watchdog = Watchdog::default();
+        let mut disk_writes = DiskWriteRate { last: None };
+        let mut ticks = 0u32;
+        loop {
+            tokio::time::sleep(SAMPLE_INTERVAL).await;
+
+            let now = chrono::Utc::now().timestamp();
+            let resources = fetch_resources(&state, client).await.ok();
+
+            if let Some(resources) = &resources {
+                if let Err(e) = state.with_db(|db| db.add_resource_sample(&to_sample(resources, now))) {
+                    eprintln!("[ERROR] Failed to record resource sample: {}", e);
+                }
+            }
+
+            let disk_write_rate = disk_writes.update(now, resources.as_ref().and_then(|r| r.engine_disk_write_bytes));
+            check_watchdog(&app, &state, &mut watchdog, resources.as_ref(), disk_write_rate, now);
+
+            ticks += 1;
+            if ticks == COMPACT_EVERY {
+                ticks = 0;
+                if let Err(e) = state.with_db(|db| db.compact_resource_samples(now)) {
+                    eprintln!("[ERROR] Failed to compact resource history: {}", e);
+                }
+            }
+        }
+    });
+}
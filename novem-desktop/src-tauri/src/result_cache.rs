@@ -0,0 +1,112 @@
+use anyhow::Result;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::Deserialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::io::{Read, Write};
+
+use crate::database::LocalDatabase;
+use crate::settings;
+
+/// How long a cached result is served when the caller doesn't say.
+const DEFAULT_TTL_SECS: u64 = 300;
+
+/// Opts an engine call into the local result cache. Only for requests
+/// whose result depends on nothing but the request and the dataset.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CacheOptions {
+    pub ttl_secs: Option<u64>,
+    /// Dataset the result was computed from; editing it invalidates the entry
+    pub dataset_uuid: Option<String>,
+}
+
+impl CacheOptions {
+    pub fn ttl_secs(&self) -> u64 {
+        self.ttl_secs.filter(|secs| *secs > 0).unwrap_or(DEFAULT_TTL_SECS)
+    }
+}
+
+/// Hash of what determines a result: the request and, if given, the
+/// dataset's current version.
+pub fn key(db: &LocalDatabase, method: &str, endpoint: &str, data: Option<&Value>, options: &CacheOptions) -> Result<String> {
+    let dataset_version = match &options.dataset_uuid {
+        Some(uuid) => db.get_dataset(uuid)?.map(|dataset| format!("{}@{}", dataset.uuid, dataset.version)),
+        None => None,
+    };
+
+    let mut hasher = Sha256::new();
+    hasher.update(method.to_uppercase().as_bytes());
+    hasher.update([0]);
+    hasher.update(endpoint.trim_start_matches('/').as_bytes());
+    hasher.update([0]);
+    hasher.update(data.map(Value::to_string).unwrap_or_default().as_bytes());
+    hasher.update([0]);
+    hasher.update(dataset_version.unwrap_or_default().as_bytes());
+
+    Ok(hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+pub fn lookup(db: &LocalDatabase, key: &str) -> Result<Option<Value>> {
+    let Some(compressed) = db.get_cached_result(key)? else {
+        return Ok(None);
+    };
+
+    let mut json = Vec::new();
+    GzDecoder::new(compressed.as_slice()).read_to_end(&mut json)?;
+    Ok(Some(serde_json::from_slice(&json)?))
+}
+
+/// Compresses and stores a result, then trims the cache to its configured
+/// size. Does nothing when caching is turned off.
+pub fn store(db: &LocalDatabase, key: &str, endpoint: &str, value: &Value, ttl_secs: u64) -> Result<()> {
+    let max_bytes = settings::result_cache_max_bytes(db)?;
+    if max_bytes == 0 {
+        return Ok(());
+    }
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&serde_json::to_vec(value)?)?;
+    let compressed = encoder.finish()?;
+    if compressed.len() as i64 > max_bytes {
+        return Ok(());
+    }
+
+    db.put_cached_result(key, endpoint, &compressed, ttl_secs)?;
+    db.evict_result_cache(max_bytes)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_cached_results_round_trip() {
+        let db_path = std::env::temp_dir().join("test_novem_result_cache_store.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        let options = CacheOptions::default();
+        let body = json!({ "column": "amount" });
+        let stats = key(&db, "post", "/stats", Some(&body), &options).unwrap();
+        assert_eq!(stats, key(&db, "POST", "stats", Some(&body), &options).unwrap());
+        assert_ne!(stats, key(&db, "POST", "/stats", Some(&json!({ "column": "qty" })), &options).unwrap());
+
+        let result = json!({ "mean": 4.5, "rows": vec![1; 1000] });
+        store(&db, &stats, "/stats", &result, options.ttl_secs()).unwrap();
+        assert_eq!(lookup(&db, &stats).unwrap(), Some(result.clone()));
+        assert!(db.result_cache_stats().unwrap().size_bytes < result.to_string().len() as i64);
+
+        // A size of 0 turns the cache off
+        db.clear_result_cache().unwrap();
+        settings::store(&db, settings::RESULT_CACHE_SIZE_SETTING, &json!(0)).unwrap();
+        store(&db, &stats, "/stats", &result, options.ttl_secs()).unwrap();
+        assert_eq!(lookup(&db, &stats).unwrap(), None);
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
@@ -0,0 +1,198 @@
+use anyhow::Result;
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::database::{NewNotification, NotificationPriority, ScheduledTask};
+use crate::events::{self, AppEvent};
+use crate::i18n::tr;
+use crate::{notifications, queries, refresh, timestamps, AppState};
+
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(30);
+
+/// What a schedule does when it fires. Stored as the action name plus the
+/// remaining fields as the payload.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ScheduleAction {
+    /// Asks for a sync with the backend
+    Sync,
+    /// Re-imports a dataset from its refresh source
+    RefreshDataset { dataset_uuid: String },
+    /// Runs a saved SQL query from the query history again
+    RunQuery { query_id: i64 },
+}
+
+impl ScheduleAction {
+    pub fn from_parts(action: &str, payload: &serde_json::Value) -> Result<Self> {
+        let mut tagged = match payload {
+            serde_json::Value::Object(fields) => fields.clone(),
+            _ => serde_json::Map::new(),
+        };
+        tagged.insert("action".to_string(), action.into());
+        serde_json::from_value(tagged.into())
+            .map_err(|_| anyhow::anyhow!(tr!("schedule-action-invalid", action = action)))
+    }
+
+    /// The action name and its payload, as stored.
+    pub fn to_parts(&self) -> Result<(String, serde_json::Value)> {
+        let mut tagged = match serde_json::to_value(self)? {
+            serde_json::Value::Object(fields) => fields,
+            _ => unreachable!("actions serialize as objects"),
+        };
+        let action = tagged.remove("action").and_then(|a| a.as_str().map(str::to_string)).unwrap_or_default();
+        Ok((action, tagged.into()))
+    }
+}
+
+/// Parses a cron expression. The usual five fields (minute to weekday) are
+/// accepted as well as the six or seven with seconds and years.
+pub fn parse_cron(expr: &str) -> Result<cron::Schedule> {
+    let expr = expr.trim();
+    let full = match expr.split_whitespace().count() {
+        5 => format!("0 {}", expr),
+        _ => expr.to_string(),
+    };
+    cron::Schedule::from_str(&full).map_err(|_| anyhow::anyhow!(tr!("schedule-cron-invalid", cron = expr)))
+}
+
+/// When a cron expression next fires after `after`. Expressions are read
+/// in local time, so "0 2 * * *" means 2am where the user is.
+pub fn next_run(expr: &str, after: DateTime<Utc>) -> Result<Option<String>> {
+    let next = parse_cron(expr)?
+        .after(&after.with_timezone(&Local))
+        .next()
+        .map(|time| timestamps::format(time.with_timezone(&Utc)));
+    Ok(next)
+}
+
+async fn run_action(app: &AppHandle, action: &ScheduleAction) -> Result<()> {
+    let state = app.state::<AppState>();
+    match action {
+        ScheduleAction::Sync => {
+            events::publish(app, AppEvent::SyncRequested);
+            Ok(())
+        }
+        ScheduleAction::RefreshDataset { dataset_uuid } => refresh::run_refresh(app, dataset_uuid).await.map(|_| ()),
+        ScheduleAction::RunQuery { query_id } => {
+            let id = *query_id;
+            let entry = state
+                .with_db_async(move |db| db.get_query_history_entry(id)).await?
+                .ok_or_else(|| anyhow::anyhow!(tr!("query-not-found", id = id)))?;
+            if entry.kind != "sql" {
+                return Err(anyhow::anyhow!(tr!("query-not-rerunnable", kind = entry.kind.as_str())));
+            }
+            let request_id = uuid::Uuid::new_v4().to_string();
+            queries::run_sql(&state, &entry.query_text, entry.project_id, &request_id)
+                .await
+                .map(|_| ())
+        }
+    }
+}
+
+/// Runs a due schedule once and moves it on to its next run. Failures are
+/// kept on the schedule and raised in the notification inbox.
+async fn fire(app: &AppHandle, task: ScheduledTask) {
+    let state = app.state::<AppState>();
+
+    let next = next_run(&task.cron, Utc::now()).unwrap_or_else(|e| {
+        eprintln!("[ERROR] Schedule '{}' has an unusable cron expression: {}", task.name, e);
+        None
+    });
+    let uuid = task.uuid.clone();
+    if let Err(e) = state.with_db_async(move |db| db.mark_schedule_started(&uuid, next.as_deref())).await {
+        eprintln!("[ERROR] Failed to advance schedule '{}': {}", task.name, e);
+        return;
+    }
+
+    let action = ScheduleAction::from_parts(&task.action, &task.payload);
+    let outcome = match &action {
+        Ok(action) => run_action(app, action).await,
+        Err(e) => Err(anyhow::anyhow!(e.to_string())),
+    };
+
+    let error = outcome.as_ref().err().map(|e| format!("{:#}", e));
+    let uuid = task.uuid.clone();
+    let recorded = error.clone();
+    if let Err(e) = state.with_db_async(move |db| db.record_schedule_outcome(&uuid, recorded.as_deref())).await {
+        eprintln!("[ERROR] Failed to record outcome of schedule '{}': {}", task.name, e);
+    }
+
+    match error {
+        None => println!("[NOVEM] Ran scheduled task '{}' ({})", task.name, task.action),
+        Some(error) => {
+            eprintln!("[ERROR] Scheduled task '{}' failed: {}", task.name, error);
+            // Failed refreshes already raise their own notification
+            if !matches!(action, Ok(ScheduleAction::RefreshDataset { .. })) {
+                let title = tr!("notify-schedule-failed", name = task.name.as_str());
+                notifications::notify(
+                    app,
+                    NewNotification::new("schedule", NotificationPriority::Normal, title)
+                        .body(error)
+                        .about("schedule", &task.uuid),
+                )
+                .await;
+            }
+        }
+    }
+}
+
+/// Background loop firing schedules as they come due. A schedule missed
+/// while the app was closed fires once on the next pass, not once per
+/// missed occurrence.
+pub fn start_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+
+        loop {
+            tokio::time::sleep(SCHEDULER_INTERVAL).await;
+
+            // Scheduled tasks write; they wait for a normal session
+            if state.mode.is_guest() {
+                continue;
+            }
+
+            let due = state
+                .with_db_async(|db| db.get_due_schedules(&timestamps::now())).await
+                .unwrap_or_else(|e| {
+                    eprintln!("[ERROR] Failed to load due schedules: {}", e);
+                    Vec::new()
+                });
+
+            for task in due {
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move { fire(&app, task).await });
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Timelike;
+
+    #[test]
+    fn test_cron_and_actions() {
+        let now = Utc::now();
+        let next = timestamps::parse(&next_run("*/15 * * * *", now).unwrap().unwrap()).unwrap();
+        assert!(next > now && next <= now + chrono::Duration::minutes(15));
+        assert_eq!(next.minute() % 15, 0);
+        assert_eq!(next.second(), 0);
+
+        assert!(parse_cron("0 30 9 * * Mon-Fri").is_ok());
+        assert!(parse_cron("every day").is_err());
+
+        let action = ScheduleAction::RefreshDataset { dataset_uuid: "d1".to_string() };
+        let (name, payload) = action.to_parts().unwrap();
+        assert_eq!(name, "refresh_dataset");
+        assert_eq!(payload, serde_json::json!({ "dataset_uuid": "d1" }));
+        assert_eq!(ScheduleAction::from_parts(&name, &payload).unwrap(), action);
+
+        assert_eq!(ScheduleAction::from_parts("sync", &serde_json::json!({})).unwrap(), ScheduleAction::Sync);
+        assert!(ScheduleAction::from_parts("run_query", &serde_json::json!({})).is_err());
+        assert!(ScheduleAction::from_parts("reboot", &serde_json::json!({})).is_err());
+    }
+}
@@ -0,0 +1,479 @@
+use anyhow::{Context, Result};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::events::{AppEvent, EventBus};
+use crate::i18n::tr;
+use crate::engine::EngineStatus;
+use crate::AppState;
+
+/// Setting key holding the session pool sizes as JSON.
+pub const SESSION_POOL_SETTING: &str = "engine.session_pool";
+
+/// A new session imports pandas and numpy, slow on a cold disk.
+const SESSION_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Snapshots pickle whole dataframes.
+const SNAPSHOT_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionPoolConfig {
+    /// Idle sessions kept ready on each running engine
+    pub warm_sessions: usize,
+    /// Sessions per engine, leased and idle together
+    pub max_sessions: usize,
+}
+
+impl Default for SessionPoolConfig {
+    fn default() -> Self {
+        Self {
+            warm_sessions: 2,
+            max_sessions: 16,
+        }
+    }
+}
+
+impl SessionPoolConfig {
+    pub fn is_valid(&self) -> bool {
+        self.max_sessions > 0 && self.warm_sessions <= self.max_sessions
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionLease {
+    pub session_id: String,
+    pub project_id: Option<i64>,
+    pub notebook_uuid: String,
+    pub port: u16,
+    /// Taken from the pool rather than created for this lease
+    pub warm: bool,
+}
+
+/// A session an engine recreated from its snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestoredSession {
+    pub id: String,
+    pub saved_at: String,
+    pub variables: Vec<String>,
+    /// Variables that could not be unpickled, e.g. their library is gone
+    pub skipped: Vec<String>,
+    /// The notebook it was reattached to
+    #[serde(default)]
+    pub notebook_uuid: Option<String>,
+}
+
+/// What came back after an engine restart. Sessions no notebook held
+/// anymore are closed and not listed.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SessionRestore {
+    pub project_id: Option<i64>,
+    pub sessions: Vec<RestoredSession>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionPoolStats {
+    pub project_id: Option<i64>,
+    pub idle: usize,
+    pub leased: usize,
+    /// Being created to refill the pool
+    pub pending: usize,
+    pub warm_target: usize,
+    pub max_sessions: usize,
+    pub created: u64,
+    pub reused: u64,
+    /// Dropped because they could not be reset
+    pub discarded: u64,
+}
+
+/// Sessions of one engine process. Replaced when the engine stops, since
+/// its sessions die with it.
+#[derive(Default)]
+struct EnginePool {
+    /// Tells creations that outlived the engine to drop their session
+    generation: u64,
+    idle: Vec<String>,
+    leases: HashMap<String, String>, // notebook UUID -> session ID
+    pending: usize,
+    created: u64,
+    reused: u64,
+    discarded: u64,
+}
+
+impl EnginePool {
+    fn total(&self) -> usize {
+        self.idle.len() + self.leases.len() + self.pending
+    }
+}
+
+/// Warm engine sessions per engine, leased to notebooks so opening one
+/// doesn't wait for a fresh namespace and its imports.
+pub struct SessionPool {
+    config: Mutex<SessionPoolConfig>,
+    pools: Mutex<HashMap<Option<i64>, EnginePool>>,
+    /// Leases of engines that went down, kept so restored sessions can be
+    /// handed back to their notebooks
+    orphaned: Mutex<HashMap<Option<i64>, HashMap<String, String>>>,
+    generations: AtomicU64,
+}
+
+impl SessionPool {
+    pub fn new(config: SessionPoolConfig) -> Self {
+        Self {
+            config: Mutex::new(config),
+            pools: Mutex::new(HashMap::new()),
+            orphaned: Mutex::new(HashMap::new()),
+            generations: AtomicU64::new(0),
+        }
+    }
+
+    pub fn config(&self) -> SessionPoolConfig {
+        *self.config.lock().unwrap()
+    }
+
+    /// Applies to the next lease, release or refill.
+    pub fn set_config(&self, config: SessionPoolConfig) {
+        *self.config.lock().unwrap() = config;
+    }
+
+    pub fn stats(&self) -> Vec<SessionPoolStats> {
+        let config = self.config();
+        let mut stats: Vec<SessionPoolStats> = self
+            .pools
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(project_id, pool)| SessionPoolStats {
+                project_id: *project_id,
+                idle: pool.idle.len(),
+                leased: pool.leases.len(),
+                pending: pool.pending,
+                warm_target: config.warm_sessions,
+                max_sessions: config.max_sessions,
+                created: pool.created,
+                reused: pool.reused,
+                discarded: pool.discarded,
+            })
+            .collect();
+        stats.sort_by_key(|stats| stats.project_id);
+        stats
+    }
+
+    fn with_pool<T>(&self, project_id: Option<i64>, f: impl FnOnce(&mut EnginePool) -> T) -> T {
+        let mut pools = self.pools.lock().unwrap();
+        let pool = pools.entry(project_id).or_insert_with(|| EnginePool {
+            generation: self.generations.fetch_add(1, Ordering::Relaxed),
+            ..Default::default()
+        });
+        f(pool)
+    }
+
+    /// Whether `generation` is still the pool of the running engine.
+    fn is_current(&self, project_id: Option<i64>, generation: u64) -> bool {
+        self.pools
+            .lock()
+            .unwrap()
+            .get(&project_id)
+            .is_some_and(|pool| pool.generation == generation)
+    }
+
+    /// Forgets an engine's sessions once it stops; leases held by open
+    /// notebooks are gone too unless `restore` brings them back, and the
+    /// next lease starts over.
+    fn invalidate(&self, project_id: Option<i64>) {
+        let Some(pool) = self.pools.lock().unwrap().remove(&project_id) else {
+            return;
+        };
+        if !pool.leases.is_empty() {
+            self.orphaned.lock().unwrap().entry(project_id).or_default().extend(pool.leases);
+        }
+    }
+}
+
+async fn engine_call(state: &AppState, project_id: Option<i64>, method: Method, path: &str) -> Result<serde_json::Value> {
+    engine_call_with_timeout(state, project_id, method, path, SESSION_TIMEOUT).await
+}
+
+async fn engine_call_with_timeout(
+    state: &AppState,
+    project_id: Option<i64>,
+    method: Method,
+    path: &str,
+    timeout: Duration,
+) -> Result<serde_json::Value> {
+    let base_url = state.engines.base_url(project_id)?;
+
+    let value = state
+        .http
+        .engine()
+        .request(method, format!("{}{}", base_url, path))
+        .timeout(timeout)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .context(format!("Engine session request {} failed", path))?
+        .json()
+        .await?;
+    Ok(value)
+}
+
+async fn create_session(state: &AppState, project_id: Option<i64>) -> Result<String> {
+    let session = engine_call(state, project_id, Method::POST, "/sessions").await?;
+    session["id"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| anyhow::anyhow!("Engine returned a session without an ID"))
+}
+
+async fn delete_session(state: &AppState, project_id: Option<i64>, session_id: &str) {
+    let path = format!("/sessions/{}", session_id);
+    if let Err(e) = engine_call(state, project_id, Method::DELETE, &path).await {
+        eprintln!("[WARNING] Failed to close engine session {}: {:#}", session_id, e);
+    }
+}
+
+/// Gives `notebook_uuid` a session on the engine serving `project_id`: the
+/// one it already holds, a warm one, or a new one if the pool is empty.
+pub async fn lease(app: &AppHandle, project_id: Option<i64>, notebook_uuid: &str) -> Result<SessionLease> {
+    let state = app.state::<AppState>();
+    let port = state.engines.port(project_id)?;
+    let pool = &state.sessions;
+    let max_sessions = pool.config().max_sessions;
+
+    enum Taken {
+        Held(String),
+        Idle(String),
+        New(u64),
+    }
+
+    let taken = pool.with_pool(project_id, |engine_pool| {
+        if let Some(session_id) = engine_pool.leases.get(notebook_uuid) {
+            return Ok(Taken::Held(session_id.clone()));
+        }
+        if let Some(session_id) = engine_pool.idle.pop() {
+            engine_pool.leases.insert(notebook_uuid.to_string(), session_id.clone());
+            engine_pool.reused += 1;
+            return Ok(Taken::Idle(session_id));
+        }
+        if engine_pool.total() >= max_sessions {
+            return Err(anyhow::anyhow!(tr!("session-pool-exhausted", max = max_sessions)));
+        }
+        engine_pool.pending += 1;
+        Ok(Taken::New(engine_pool.generation))
+    })?;
+
+    let (session_id, warm) = match taken {
+        Taken::Held(session_id) | Taken::Idle(session_id) => (session_id, true),
+        Taken::New(generation) => {
+            let created = create_session(&state, project_id).await;
+            if !pool.is_current(project_id, generation) {
+                if let Ok(session_id) = &created {
+                    delete_session(&state, project_id, session_id).await;
+                }
+                return Err(anyhow::anyhow!(tr!("session-engine-restarted")));
+            }
+
+            let session_id = pool.with_pool(project_id, |engine_pool| {
+                engine_pool.pending -= 1;
+                let session_id = created?;
+                engine_pool.leases.insert(notebook_uuid.to_string(), session_id.clone());
+                engine_pool.created += 1;
+                Ok::<_, anyhow::Error>(session_id)
+            })?;
+            (session_id, false)
+        }
+    };
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move { refill(&app, project_id).await });
+
+    Ok(SessionLease {
+        session_id,
+        project_id,
+        notebook_uuid: notebook_uuid.to_string(),
+        port,
+        warm,
+    })
+}
+
+/// Takes a notebook's session back. It is reset and kept warm while the
+/// pool is short, otherwise closed. Returns false if none was leased.
+pub async fn release(app: &AppHandle, project_id: Option<i64>, notebook_uuid: &str) -> Result<bool> {
+    let state = app.state::<AppState>();
+    let pool = &state.sessions;
+    let warm_sessions = pool.config().warm_sessions;
+
+    let Some((session_id, keep, generation)) = pool.with_pool(project_id, |engine_pool| {
+        let session_id = engine_pool.leases.remove(notebook_uuid)?;
+        let keep = engine_pool.idle.len() + engine_pool.pending < warm_sessions;
+        Some((session_id, keep, engine_pool.generation))
+    }) else {
+        return Ok(false);
+    };
+
+    if !keep {
+        delete_session(&state, project_id, &session_id).await;
+        return Ok(true);
+    }
+
+    let path = format!("/sessions/{}/reset", session_id);
+    let reset = engine_call(&state, project_id, Method::POST, &path).await;
+    if !pool.is_current(project_id, generation) {
+        return Ok(true);
+    }
+
+    match reset {
+        Ok(_) => pool.with_pool(project_id, |engine_pool| engine_pool.idle.push(session_id)),
+        Err(e) => {
+            eprintln!("[WARNING] Discarding engine session {} that failed to reset: {:#}", session_id, e);
+            pool.with_pool(project_id, |engine_pool| engine_pool.discarded += 1);
+            delete_session(&state, project_id, &session_id).await;
+        }
+    }
+    Ok(true)
+}
+
+/// Has the engine snapshot every session that ran code, ahead of a restart.
+/// Returns how many were saved.
+pub async fn save(state: &AppState, project_id: Option<i64>) -> Result<usize> {
+    let saved = engine_call_with_timeout(state, project_id, Method::POST, "/sessions/save", SNAPSHOT_TIMEOUT).await?;
+    Ok(saved["sessions"].as_array().map_or(0, Vec::len))
+}
+
+/// Has a freshly started engine recreate sessions from their snapshots,
+/// either the ones `save` just wrote or the engine's last checkpoint after
+/// a crash, and hands each back to the notebook that held it.
+pub async fn restore(app: &AppHandle, project_id: Option<i64>) -> Result<SessionRestore> {
+    let state = app.state::<AppState>();
+    let pool = &state.sessions;
+
+    let restored = engine_call_with_timeout(&state, project_id, Method::POST, "/sessions/restore", SNAPSHOT_TIMEOUT).await?;
+    let restored: Vec<RestoredSession> =
+        serde_json::from_value(restored["sessions"].clone()).context("Invalid session restore response")?;
+
+    let orphaned = pool.orphaned.lock().unwrap().remove(&project_id).unwrap_or_default();
+    let mut report = SessionRestore { project_id, sessions: Vec::new() };
+
+    for mut session in restored {
+        let notebook_uuid = orphaned
+            .iter()
+            .find(|(_, session_id)| **session_id == session.id)
+            .map(|(notebook_uuid, _)| notebook_uuid.clone());
+
+        match notebook_uuid {
+            Some(notebook_uuid) => {
+                pool.with_pool(project_id, |engine_pool| {
+                    engine_pool.leases.insert(notebook_uuid.clone(), session.id.clone());
+                });
+                session.notebook_uuid = Some(notebook_uuid);
+                report.sessions.push(session);
+            }
+            // Its notebook was closed, or this is a checkpoint from a
+            // previous run of the app
+            None => delete_session(&state, project_id, &session.id).await,
+        }
+    }
+
+    println!(
+        "[NOVEM] Restored {} engine sessions for {:?}",
+        report.sessions.len(),
+        project_id
+    );
+    Ok(report)
+}
+
+/// Creates sessions until the engine has its warm target idle, one at a
+/// time so concurrent refills never overshoot, and closes any surplus.
+pub async fn refill(app: &AppHandle, project_id: Option<i64>) {
+    let state = app.state::<AppState>();
+    let pool = &state.sessions;
+
+    loop {
+        let config = pool.config();
+        let surplus = pool.with_pool(project_id, |engine_pool| {
+            let surplus = engine_pool.idle.len().saturating_sub(config.warm_sessions);
+            engine_pool.idle.split_off(engine_pool.idle.len() - surplus)
+        });
+        for session_id in surplus {
+            delete_session(&state, project_id, &session_id).await;
+        }
+
+        let generation = pool.with_pool(project_id, |engine_pool| {
+            let short = engine_pool.idle.len() + engine_pool.pending < config.warm_sessions;
+            if short && engine_pool.total() < config.max_sessions {
+                engine_pool.pending += 1;
+                Some(engine_pool.generation)
+            } else {
+                None
+            }
+        });
+        let Some(generation) = generation else {
+            return;
+        };
+
+        let created = create_session(&state, project_id).await;
+        if !pool.is_current(project_id, generation) {
+            if let Ok(session_id) = &created {
+                delete_session(&state, project_id, session_id).await;
+            }
+            return;
+        }
+
+        let failed = pool.with_pool(project_id, |engine_pool| {
+            engine_pool.pending -= 1;
+            match created {
+                Ok(session_id) => {
+                    engine_pool.idle.push(session_id);
+                    engine_pool.created += 1;
+                    None
+                }
+                Err(e) => Some(e),
+            }
+        });
+        if let Some(e) = failed {
+            eprintln!("[ERROR] Failed to warm an engine session: {:#}", e);
+            return;
+        }
+    }
+}
+
+/// Warms pools as engines come up and drops them when engines go down.
+/// The shared engine may already be up by the time this subscribes.
+pub fn start_keeper(app: AppHandle) {
+    let mut events = app.state::<EventBus>().subscribe();
+
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        if state.engines.status(None) == EngineStatus::Ready {
+            refill(&app, None).await;
+        }
+
+        loop {
+            let change = match events.recv().await {
+                Ok(record) => match record.event {
+                    AppEvent::EngineStatusChanged(change) => change,
+                    _ => continue,
+                },
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            };
+
+            match change.status {
+                EngineStatus::Ready if change.previous == EngineStatus::Starting => {
+                    let app = app.clone();
+                    tauri::async_runtime::spawn(async move { refill(&app, change.project_id).await });
+                }
+                EngineStatus::Starting | EngineStatus::Stopped | EngineStatus::Crashed | EngineStatus::NotFound => {
+                    state.sessions.invalidate(change.project_id);
+                }
+                _ => {}
+            }
+        }
+    });
+}
@@ -0,0 +1,227 @@
+use anyhow::Result;
+use jsonschema::JSONSchema;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::backend::DEFAULT_BACKEND_URL;
+use crate::database::{ConflictStrategy, LocalDatabase};
+use crate::i18n::tr;
+
+pub const QUERY_TIMEOUT_SETTING: &str = "engine.query_timeout_secs";
+pub const BACKEND_URL_SETTING: &str = "backend.url";
+pub const THEME_SETTING: &str = "app.theme";
+pub const SYNC_INTERVAL_SETTING: &str = "sync.interval_secs";
+pub const WEEKLY_MAINTENANCE_SETTING: &str = "db.weekly_maintenance";
+pub const CONFLICT_STRATEGIES_SETTING: &str = "sync.conflict_strategies";
+pub const RESULT_CACHE_SIZE_SETTING: &str = "cache.result_max_mb";
+
+/// A preference exposed through the generic settings commands, with the
+/// JSON Schema its value must satisfy. Other keys in the settings table are
+/// internal and changed through their own commands.
+pub struct KnownSetting {
+    pub key: &'static str,
+    schema: fn() -> Value,
+    default: fn() -> Value,
+}
+
+pub const KNOWN_SETTINGS: &[KnownSetting] = &[
+    KnownSetting {
+        key: QUERY_TIMEOUT_SETTING,
+        schema: || json!({ "type": "integer", "minimum": 5, "maximum": 86400 }),
+        default: || json!(300),
+    },
+    KnownSetting {
+        key: BACKEND_URL_SETTING,
+        schema: || json!({ "type": "string", "format": "uri", "pattern": "^https?://" }),
+        default: || json!(DEFAULT_BACKEND_URL),
+    },
+    KnownSetting {
+        key: THEME_SETTING,
+        schema: || json!({ "type": "string", "enum": ["system", "light", "dark"] }),
+        default: || json!("system"),
+    },
+    KnownSetting {
+        key: SYNC_INTERVAL_SETTING,
+        schema: || json!({ "type": "integer", "minimum": 5, "maximum": 3600 }),
+        default: || json!(30),
+    },
+    KnownSetting {
+        key: WEEKLY_MAINTENANCE_SETTING,
+        schema: || json!({ "type": "boolean" }),
+        default: || json!(false),
+    },
+    KnownSetting {
+        key: CONFLICT_STRATEGIES_SETTING,
+        schema: || {
+            let strategy = json!({ "type": "string", "enum": ["server_wins", "local_wins", "newest_wins", "manual"] });
+            json!({
+                "type": "object",
+                "properties": { "workspace": strategy, "project": strategy, "dataset": strategy },
+                "additionalProperties": false,
+            })
+        },
+        default: || json!({ "workspace": "manual", "project": "manual", "dataset": "manual" }),
+    },
+    KnownSetting {
+        key: RESULT_CACHE_SIZE_SETTING,
+        schema: || json!({ "type": "integer", "minimum": 0, "maximum": 10240 }),
+        default: || json!(256),
+    },
+];
+
+/// A known setting's current value, with what the UI needs to edit it.
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingValue {
+    pub key: String,
+    pub value: Value,
+    pub default: Value,
+    /// Nothing valid is stored, so `value` is the default
+    pub is_default: bool,
+    pub schema: Value,
+}
+
+pub fn known(key: &str) -> Result<&'static KnownSetting> {
+    KNOWN_SETTINGS
+        .iter()
+        .find(|setting| setting.key == key)
+        .ok_or_else(|| anyhow::anyhow!(tr!("setting-unknown", key = key)))
+}
+
+impl KnownSetting {
+    pub fn validate(&self, value: &Value) -> Result<()> {
+        let schema = (self.schema)();
+        let compiled = JSONSchema::options()
+            .should_validate_formats(true)
+            .compile(&schema)
+            .map_err(|e| anyhow::anyhow!("Invalid schema for setting {}: {}", self.key, e))?;
+
+        if let Err(errors) = compiled.validate(value) {
+            let reason = errors.map(|e| e.to_string()).collect::<Vec<_>>().join("; ");
+            return Err(anyhow::anyhow!(tr!("setting-invalid", key = self.key, reason = reason)));
+        }
+        Ok(())
+    }
+
+    fn with_value(&self, stored: Option<Value>) -> SettingValue {
+        let default = (self.default)();
+        SettingValue {
+            key: self.key.to_string(),
+            is_default: stored.is_none(),
+            value: stored.unwrap_or_else(|| default.clone()),
+            default,
+            schema: (self.schema)(),
+        }
+    }
+}
+
+/// The stored value, or the default if none is stored or it no longer
+/// passes the schema (e.g. written by an older version).
+pub fn load(db: &LocalDatabase, key: &str) -> Result<SettingValue> {
+    let setting = known(key)?;
+    let stored = db
+        .get_setting(key)?
+        .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+        .filter(|value| match setting.validate(value) {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!("[WARNING] Ignoring stored setting {}: {}", key, e);
+                false
+            }
+        });
+    Ok(setting.with_value(stored))
+}
+
+pub fn load_all(db: &LocalDatabase) -> Result<Vec<SettingValue>> {
+    KNOWN_SETTINGS.iter().map(|setting| load(db, setting.key)).collect()
+}
+
+/// Validates and persists a value; `null` goes back to the default.
+pub fn store(db: &LocalDatabase, key: &str, value: &Value) -> Result<SettingValue> {
+    let setting = known(key)?;
+    if value.is_null() {
+        db.delete_setting(key)?;
+        return Ok(setting.with_value(None));
+    }
+
+    setting.validate(value)?;
+    db.set_setting(key, &value.to_string())?;
+    Ok(setting.with_value(Some(value.clone())))
+}
+
+fn load_as<T: DeserializeOwned>(db: &LocalDatabase, key: &str) -> Result<T> {
+    Ok(serde_json::from_value(load(db, key)?.value)?)
+}
+
+/// How long a SQL query may run on an engine.
+pub fn query_timeout(db: &LocalDatabase) -> Result<Duration> {
+    load_as(db, QUERY_TIMEOUT_SETTING).map(Duration::from_secs)
+}
+
+pub fn backend_url(db: &LocalDatabase) -> Result<String> {
+    load_as(db, BACKEND_URL_SETTING)
+}
+
+/// How often pending uploads are retried against the backend.
+pub fn sync_interval(db: &LocalDatabase) -> Result<Duration> {
+    load_as(db, SYNC_INTERVAL_SETTING).map(Duration::from_secs)
+}
+
+/// Whether database maintenance runs on its own once a week.
+pub fn weekly_maintenance(db: &LocalDatabase) -> Result<bool> {
+    load_as(db, WEEKLY_MAINTENANCE_SETTING)
+}
+
+/// How sync conflicts on `entity_type` are settled; types left out of the
+/// setting wait for the user.
+pub fn conflict_strategy(db: &LocalDatabase, entity_type: &str) -> Result<ConflictStrategy> {
+    let strategies: BTreeMap<String, ConflictStrategy> = load_as(db, CONFLICT_STRATEGIES_SETTING)?;
+    Ok(strategies.get(entity_type).copied().unwrap_or(ConflictStrategy::Manual))
+}
+
+/// Space engine results may take in the local cache; 0 turns caching off.
+pub fn result_cache_max_bytes(db: &LocalDatabase) -> Result<i64> {
+    load_as::<i64>(db, RESULT_CACHE_SIZE_SETTING).map(|mb| mb * 1024 * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_settings_are_validated() {
+        let db_path = std::env::temp_dir().join("test_novem_settings.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        for setting in KNOWN_SETTINGS {
+            setting.validate(&(setting.default)()).unwrap();
+        }
+
+        assert_eq!(query_timeout(&db).unwrap(), Duration::from_secs(300));
+        assert!(store(&db, QUERY_TIMEOUT_SETTING, &json!(1)).is_err());
+        assert!(store(&db, QUERY_TIMEOUT_SETTING, &json!("60")).is_err());
+        store(&db, QUERY_TIMEOUT_SETTING, &json!(60)).unwrap();
+        assert_eq!(query_timeout(&db).unwrap(), Duration::from_secs(60));
+
+        assert!(store(&db, BACKEND_URL_SETTING, &json!("ftp://novem.example")).is_err());
+        assert!(store(&db, THEME_SETTING, &json!("sepia")).is_err());
+        assert!(store(&db, CONFLICT_STRATEGIES_SETTING, &json!({ "notebook": "manual" })).is_err());
+        store(&db, CONFLICT_STRATEGIES_SETTING, &json!({ "project": "newest_wins" })).unwrap();
+        assert_eq!(conflict_strategy(&db, "project").unwrap(), ConflictStrategy::NewestWins);
+        assert_eq!(conflict_strategy(&db, "dataset").unwrap(), ConflictStrategy::Manual);
+        assert!(store(&db, "engine.admission", &json!({})).is_err());
+
+        // A stored value that no longer validates reads as the default
+        db.set_setting(THEME_SETTING, "\"sepia\"").unwrap();
+        assert!(load(&db, THEME_SETTING).unwrap().is_default);
+
+        assert!(store(&db, QUERY_TIMEOUT_SETTING, &Value::Null).unwrap().is_default);
+        assert_eq!(load_all(&db).unwrap().len(), KNOWN_SETTINGS.len());
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+}
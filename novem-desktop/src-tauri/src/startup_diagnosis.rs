@@ -0,0 +1,164 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use crate::i18n::tr;
+
+/// How much of the engine's stderr is kept for a failed start; enough for
+/// a typical import traceback.
+const STDERR_TAIL_LINES: usize = 50;
+
+/// The last lines an engine process wrote to stderr, shared between the
+/// thread forwarding them and whoever diagnoses a failed start.
+#[derive(Debug, Clone, Default)]
+pub struct StderrTail(Arc<Mutex<VecDeque<String>>>);
+
+impl StderrTail {
+    pub fn push(&self, line: &str) {
+        let mut lines = self.0.lock().unwrap();
+        if lines.len() == STDERR_TAIL_LINES {
+            lines.pop_front();
+        }
+        lines.push_back(line.to_string());
+    }
+
+    pub fn clear(&self) {
+        self.0.lock().unwrap().clear();
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        self.0.lock().unwrap().iter().cloned().collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StartupFailure {
+    /// A package is missing from the engine's environment
+    MissingModule { module: String },
+    SyntaxError { location: Option<String> },
+    PortInUse { port: u16, owner: String },
+    PermissionDenied,
+    /// Still running, but never answered the readiness probe
+    Timeout { seconds: u64 },
+    /// Exited for a reason not recognised above
+    Exited,
+}
+
+/// Why an engine failed to start, returned by `restart_engine` in place of
+/// a bare error string.
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupDiagnosis {
+    pub project_id: Option<i64>,
+    #[serde(flatten)]
+    pub failure: StartupFailure,
+    pub message: String,
+    /// The final `SomeError: ...` line of the traceback, if any
+    pub exception: Option<String>,
+    pub stderr_tail: Vec<String>,
+}
+
+impl StartupDiagnosis {
+    pub fn new(project_id: Option<i64>, failure: StartupFailure, stderr_tail: Vec<String>) -> Self {
+        let message = match &failure {
+            StartupFailure::MissingModule { module } => tr!("engine-startup-missing-module", module = module.as_str()),
+            StartupFailure::SyntaxError { location } => tr!(
+                "engine-startup-syntax-error",
+                location = location.as_deref().unwrap_or("?")
+            ),
+            StartupFailure::PortInUse { port, owner } => tr!("engine-port-in-use", port = *port, owner = owner.as_str()),
+            StartupFailure::PermissionDenied => tr!("engine-startup-permission-denied"),
+            StartupFailure::Timeout { seconds } => tr!("engine-start-timeout", seconds = *seconds),
+            StartupFailure::Exited => tr!("engine-startup-exited"),
+        };
+
+        Self {
+            project_id,
+            failure,
+            message,
+            exception: exception_line(&stderr_tail),
+            stderr_tail,
+        }
+    }
+}
+
+/// The last line naming an exception, as Python prints it at the end of a
+/// traceback.
+fn exception_line(lines: &[String]) -> Option<String> {
+    lines
+        .iter()
+        .rev()
+        .find(|line| {
+            let Some((name, _)) = line.split_once(':') else {
+                return false;
+            };
+            !name.starts_with(char::is_whitespace)
+                && !name.contains(' ')
+                && (name.ends_with("Error") || name.ends_with("Exception"))
+        })
+        .cloned()
+}
+
+/// The `File "...", line N` frame Python prints before a syntax error.
+fn syntax_error_location(lines: &[String]) -> Option<String> {
+    lines.iter().rev().find_map(|line| {
+        let rest = line.trim().strip_prefix("File \"")?;
+        let (file, rest) = rest.split_once('"')?;
+        let line_number = rest.trim_start_matches(", line ").split(|c: char| !c.is_ascii_digit()).next()?;
+        Some(if line_number.is_empty() { file.to_string() } else { format!("{}:{}", file, line_number) })
+    })
+}
+
+/// Recognises the common ways uvicorn dies on startup from its stderr.
+/// Port conflicts are detected earlier and retried, so they aren't
+/// classified here.
+pub fn classify_exit(lines: &[String]) -> StartupFailure {
+    let exception = exception_line(lines).unwrap_or_default();
+
+    if let Some(message) = exception.strip_prefix("ModuleNotFoundError:") {
+        let module = message.split('\'').nth(1).unwrap_or(message.trim());
+        return StartupFailure::MissingModule { module: module.to_string() };
+    }
+    if exception.starts_with("SyntaxError:") || exception.starts_with("IndentationError:") {
+        return StartupFailure::SyntaxError { location: syntax_error_location(lines) };
+    }
+    if exception.starts_with("PermissionError:") {
+        return StartupFailure::PermissionDenied;
+    }
+    StartupFailure::Exited
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_classify_exit() {
+        let missing = lines(
+            "Traceback (most recent call last):\n  File \"/app/main.py\", line 3, in <module>\n    import duckdb\nModuleNotFoundError: No module named 'duckdb'",
+        );
+        assert_eq!(classify_exit(&missing), StartupFailure::MissingModule { module: "duckdb".to_string() });
+
+        let syntax = lines(
+            "  File \"/app/api/query.py\", line 41\n    def run(:\n           ^\nSyntaxError: invalid syntax",
+        );
+        assert_eq!(
+            classify_exit(&syntax),
+            StartupFailure::SyntaxError { location: Some("/app/api/query.py:41".to_string()) }
+        );
+        assert_eq!(exception_line(&syntax).as_deref(), Some("SyntaxError: invalid syntax"));
+
+        assert_eq!(classify_exit(&lines("INFO:     Started server process [42]")), StartupFailure::Exited);
+
+        let tail = StderrTail::default();
+        for i in 0..STDERR_TAIL_LINES + 5 {
+            tail.push(&i.to_string());
+        }
+        assert_eq!(tail.lines().len(), STDERR_TAIL_LINES);
+        assert_eq!(tail.lines()[0], "5");
+    }
+}
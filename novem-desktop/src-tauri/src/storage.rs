@@ -0,0 +1,169 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::Path;
+
+use crate::database::TableUsage;
+
+/// Directories under app data that grow with use, as (name, what's in it).
+const DATA_DIRS: &[(&str, &str)] = &[
+    ("datasets", "Imported datasets converted to Parquet"),
+    ("attachments", "Managed copies of project attachments"),
+    ("artifacts", "Published dashboards and exports"),
+    ("staging", "Uploads and downloads in progress"),
+    ("spill", "Engine responses too large for memory"),
+    ("engines", "Project engine scratch space and DuckDB files"),
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DirectoryUsage {
+    pub name: String,
+    pub description: String,
+    pub path: String,
+    pub bytes: u64,
+    pub files: u64,
+}
+
+/// One project engine's working directory.
+#[derive(Debug, Clone, Serialize)]
+pub struct EngineScratch {
+    pub project_id: i64,
+    pub bytes: u64,
+    pub files: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DatabaseUsage {
+    pub file_bytes: u64,
+    /// Write-ahead log not yet checkpointed into the file
+    pub wal_bytes: u64,
+    /// Space inside the file a vacuum would release
+    pub free_bytes: i64,
+    /// Largest first
+    pub tables: Vec<TableUsage>,
+}
+
+/// Where NOVEM's disk usage goes.
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageReport {
+    pub data_dir: String,
+    pub total_bytes: u64,
+    pub database: DatabaseUsage,
+    pub directories: Vec<DirectoryUsage>,
+    /// Largest first
+    pub engine_scratch: Vec<EngineScratch>,
+}
+
+/// Bytes and files under `path`, following no symlinks. Missing paths are
+/// empty; entries that vanish or can't be read mid-walk are skipped.
+fn usage(path: &Path) -> Result<(u64, u64)> {
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok((0, 0)),
+        Err(e) => return Err(e).context(format!("Failed to inspect {:?}", path)),
+    };
+    if !metadata.is_dir() {
+        return Ok((metadata.len(), 1));
+    }
+
+    let (mut bytes, mut files) = (0, 0);
+    for entry in std::fs::read_dir(path).context(format!("Failed to read {:?}", path))?.flatten() {
+        if let Ok((entry_bytes, entry_files)) = usage(&entry.path()) {
+            bytes += entry_bytes;
+            files += entry_files;
+        }
+    }
+    Ok((bytes, files))
+}
+
+fn file_size(path: &Path) -> u64 {
+    std::fs::metadata(path).map_or(0, |metadata| metadata.len())
+}
+
+fn engine_scratch(engines_dir: &Path) -> Result<Vec<EngineScratch>> {
+    let entries = match std::fs::read_dir(engines_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).context(format!("Failed to read {:?}", engines_dir)),
+    };
+
+    let mut scratch = Vec::new();
+    for entry in entries.flatten() {
+        // Work directories are named after the project ID
+        let Some(project_id) = entry.file_name().to_str().and_then(|name| name.parse().ok()) else {
+            continue;
+        };
+        let (bytes, files) = usage(&entry.path())?;
+        scratch.push(EngineScratch { project_id, bytes, files });
+    }
+    scratch.sort_by_key(|s| std::cmp::Reverse(s.bytes));
+    Ok(scratch)
+}
+
+/// Measures the database file and the data directories. `tables` and
+/// `free_bytes` come from the database itself; everything else is read
+/// from disk, so this can take a moment on a large data directory.
+pub fn report(data_dir: &Path, database_file: &str, tables: Vec<TableUsage>, free_bytes: i64) -> Result<StorageReport> {
+    let database = DatabaseUsage {
+        file_bytes: file_size(&data_dir.join(database_file)),
+        wal_bytes: file_size(&data_dir.join(format!("{}-wal", database_file))),
+        free_bytes,
+        tables,
+    };
+
+    let mut directories = Vec::new();
+    for (name, description) in DATA_DIRS {
+        let path = data_dir.join(name);
+        let (bytes, files) = usage(&path)?;
+        directories.push(DirectoryUsage {
+            name: name.to_string(),
+            description: description.to_string(),
+            path: path.display().to_string(),
+            bytes,
+            files,
+        });
+    }
+
+    let total_bytes =
+        database.file_bytes + database.wal_bytes + directories.iter().map(|dir| dir.bytes).sum::<u64>();
+
+    Ok(StorageReport {
+        data_dir: data_dir.display().to_string(),
+        total_bytes,
+        database,
+        directories,
+        engine_scratch: engine_scratch(&data_dir.join("engines"))?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_storage_report() {
+        let dir = std::env::temp_dir().join("test_novem_storage");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("engines").join("7").join("tmp")).unwrap();
+        std::fs::create_dir_all(dir.join("engines").join("lost+found")).unwrap();
+        std::fs::create_dir_all(dir.join("datasets")).unwrap();
+        std::fs::write(dir.join("novem.db"), vec![0; 4096]).unwrap();
+        std::fs::write(dir.join("datasets").join("a.parquet"), b"12345").unwrap();
+        std::fs::write(dir.join("engines").join("7").join("project.duckdb"), b"123").unwrap();
+        std::fs::write(dir.join("engines").join("7").join("tmp").join("spill.bin"), b"12").unwrap();
+
+        let report = report(&dir, "novem.db", Vec::new(), 0).unwrap();
+        assert_eq!(report.database.file_bytes, 4096);
+        assert_eq!(report.database.wal_bytes, 0);
+
+        let size = |name: &str| report.directories.iter().find(|d| d.name == name).map(|d| (d.bytes, d.files));
+        assert_eq!(size("datasets"), Some((5, 1)));
+        assert_eq!(size("engines"), Some((5, 2)));
+        assert_eq!(size("attachments"), Some((0, 0)));
+        assert_eq!(report.total_bytes, 4096 + 5 + 5);
+
+        assert_eq!(report.engine_scratch.len(), 1);
+        assert_eq!((report.engine_scratch[0].project_id, report.engine_scratch[0].bytes), (7, 5));
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
@@ -0,0 +1,752 @@
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::Notify;
+
+use crate::database::{
+    is_versioned, Conflict, LocalDatabase, NewNotification, NotificationPriority, ServerCopyResult, SyncQueue,
+    VersionCheck, SYNC_FAILED_PERMANENT,
+};
+use crate::events::{AppEvent, EventBus};
+use crate::i18n::tr;
+use crate::{notifications, settings, AppState};
+
+/// A push carries up to 100 items (see `get_pending_sync_items`).
+const PUSH_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Time of the backend's last answer to a pull, or of the last change
+/// applied before one that failed; changes since then are asked for next
+/// time.
+const PULL_CURSOR_SETTING: &str = "sync.pulled_until";
+
+/// Random id naming this install to the backend, so a pull can leave out
+/// what this device pushed itself.
+const DEVICE_ID_SETTING: &str = "sync.device_id";
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Controls the background sync worker. Sync runs from launch; `stop_sync`
+/// pauses it until `start_sync` or the next launch.
+pub struct SyncWorker {
+    enabled: AtomicBool,
+    wake: Notify,
+    /// Held for the length of a pass, so two never push the same items
+    pass: tokio::sync::Mutex<()>,
+}
+
+impl SyncWorker {
+    pub fn new() -> Self {
+        Self {
+            enabled: AtomicBool::new(true),
+            wake: Notify::new(),
+            pass: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::SeqCst)
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::SeqCst);
+        if enabled {
+            self.wake();
+        }
+    }
+
+    /// Cuts the worker's current wait short, e.g. after the interval changed.
+    pub fn wake(&self) {
+        self.wake.notify_one();
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncStatus {
+    pub enabled: bool,
+    /// Passes are skipped while the backend is unreachable
+    pub online: bool,
+    pub interval_secs: u64,
+    pub pending: i64,
+    /// Out of retries; see `get_failed_sync_items`
+    pub failed: i64,
+}
+
+pub fn status(state: &AppState) -> Result<SyncStatus> {
+    state.with_db(|db| {
+        Ok(SyncStatus {
+            enabled: state.sync.is_enabled(),
+            online: state.connectivity.is_online(),
+            interval_secs: settings::sync_interval(db)?.as_secs(),
+            pending: db.count_sync_items("pending")?,
+            failed: db.count_sync_items(SYNC_FAILED_PERMANENT)?,
+        })
+    })
+}
+
+#[derive(Debug, Serialize)]
+struct PushItem<'a> {
+    id: i64,
+    entity_type: &'a str,
+    entity_uuid: &'a str,
+    action: &'a str,
+    payload: serde_json::Value,
+    /// Set when `payload` is a delta holding only these fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    changed_fields: Option<&'a [String]>,
+    queued_at: &'a str,
+}
+
+/// The backend's verdict on one pushed item. Items it doesn't mention stay
+/// queued and use up a retry, as if rejected.
+#[derive(Debug, Clone, Deserialize)]
+struct ItemResult {
+    id: i64,
+    ok: bool,
+    #[serde(default)]
+    error: Option<String>,
+    /// Version the backend now holds, for versioned entities
+    #[serde(default)]
+    server_version: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PushResponse {
+    results: Vec<ItemResult>,
+}
+
+/// A row the backend changed since the last pull.
+#[derive(Debug, Clone, Deserialize)]
+struct ServerChange {
+    entity_type: String,
+    uuid: String,
+    version: i64,
+    data: serde_json::Value,
+    /// When the backend stored it; changes arrive oldest first
+    updated_at: String,
+}
+
+/// Both fields are required: an answer without them says nothing about
+/// what changed, so the cursor must not move past it.
+#[derive(Debug, Deserialize)]
+struct PullResponse {
+    changes: Vec<ServerChange>,
+    timestamp: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct PushTally {
+    pub pushed: usize,
+    pub failed: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+struct PullTally {
+    pulled: usize,
+    /// Recorded conflicts, settled or waiting on the user
+    conflicts: Vec<Conflict>,
+    failed: usize,
+    /// Changes for rows this device doesn't keep, like comments
+    skipped: usize,
+    /// Index of the first change that couldn't be applied
+    first_failed: Option<usize>,
+}
+
+/// What one push+pull cycle did, for the sync dialog.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SyncSummary {
+    pub pushed: usize,
+    pub pulled: usize,
+    pub conflicts: usize,
+    /// Pushed items the backend rejected plus pulled rows that couldn't be
+    /// applied
+    pub failed: usize,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncPhase {
+    Push,
+    Pull,
+    Done,
+}
+
+/// Emitted as `sync-progress` for each item a cycle handles, so the UI
+/// can show how far a long first sync has got.
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncProgress {
+    pub phase: SyncPhase,
+    pub entity_type: Option<String>,
+    pub processed: usize,
+    /// Known at the start of the phase; grows if items are queued meanwhile
+    pub total: usize,
+    pub current_item: Option<String>,
+}
+
+fn emit_progress(app: &AppHandle, progress: SyncProgress) {
+    let _ = app.emit("sync-progress", progress);
+}
+
+/// Why a request didn't reach the backend at all. Unlike a rejected item,
+/// neither costs the items a retry.
+#[derive(Debug)]
+enum SyncError {
+    Offline(reqwest::Error),
+    SignedOut,
+    Other(anyhow::Error),
+}
+
+impl SyncError {
+    fn into_anyhow(self) -> anyhow::Error {
+        match self {
+            SyncError::Offline(e) => anyhow::Error::new(e).context("Backend unreachable; pending changes stay queued"),
+            SyncError::SignedOut => anyhow::anyhow!("Not signed in; pending changes stay queued"),
+            SyncError::Other(e) => e,
+        }
+    }
+}
+
+/// Carries out what the backend accepting an item means locally.
+fn acknowledge(db: &LocalDatabase, item: &SyncQueue, server_version: Option<i64>) -> Result<()> {
+    db.update_sync_item_status(item.id, "completed", None)?;
+    match (item.entity_type.as_str(), item.action.as_str()) {
+        (_, "delete") => {
+            db.acknowledge_delete(&item.entity_type, &item.entity_uuid)?;
+        }
+        ("share_link", "create") => {
+            db.mark_share_link_synced(&item.entity_uuid)?;
+        }
+        _ => {}
+    }
+    if let Some(version) = server_version {
+        // The push itself landed; a version for an unversioned type is the
+        // backend's mistake, not a reason to push again
+        if let Err(e) = db.mark_version_synced(&item.entity_type, &item.entity_uuid, version) {
+            eprintln!("[WARNING] Failed to record synced version of {} {}: {}", item.entity_type, item.entity_uuid, e);
+        }
+    }
+    Ok(())
+}
+
+/// Records the backend's answer to a push: accepted items complete,
+/// rejected or unanswered ones use up a retry and stay queued with the
+/// error.
+fn apply_results(db: &LocalDatabase, items: &[SyncQueue], results: Vec<ItemResult>) -> Result<PushTally> {
+    let results: HashMap<i64, ItemResult> = results.into_iter().map(|result| (result.id, result)).collect();
+    let mut tally = PushTally::default();
+
+    db.atomically(|db| {
+        for item in items {
+            match results.get(&item.id) {
+                Some(ItemResult { ok: true, server_version, .. }) => {
+                    acknowledge(db, item, *server_version)?;
+                    tally.pushed += 1;
+                }
+                rejected => {
+                    let error = match rejected {
+                        Some(result) => result.error.as_deref().unwrap_or("Rejected by the backend"),
+                        None => "The backend didn't answer for this item",
+                    };
+                    db.increment_sync_retry(item.id)?;
+                    db.update_sync_item_status(item.id, "pending", Some(error))?;
+                    tally.failed += 1;
+                }
+            }
+        }
+        Ok(())
+    })?;
+
+    Ok(tally)
+}
+
+/// This install's device id, created on first use.
+fn device_id(db: &LocalDatabase) -> Result<String> {
+    if let Some(id) = db.get_setting(DEVICE_ID_SETTING)? {
+        return Ok(id);
+    }
+    let id = uuid::Uuid::new_v4().to_string();
+    db.set_setting(DEVICE_ID_SETTING, &id)?;
+    Ok(id)
+}
+
+async fn post<T: DeserializeOwned>(state: &AppState, body: &serde_json::Value) -> Result<T, SyncError> {
+    let (url, token) = {
+        let session = state.backend.lock().map_err(|e| SyncError::Other(anyhow::anyhow!("{}", e)))?;
+        (session.url("/api/auth/sync/"), session.access_token.clone())
+    };
+    let Some(token) = token else {
+        return Err(SyncError::SignedOut);
+    };
+
+    let response = state
+        .http
+        .backend()
+        .post(url)
+        .bearer_auth(token)
+        .timeout(PUSH_TIMEOUT)
+        .json(body)
+        .send()
+        .await
+        .map_err(|e| {
+            state.connectivity.recheck();
+            SyncError::Offline(e)
+        })?;
+
+    match response.status() {
+        status if status.is_success() => {
+            // Nothing counts as stored unless the backend says so
+            let text = response.text().await.map_err(SyncError::Offline)?;
+            serde_json::from_str(&text)
+                .map_err(|e| SyncError::Other(anyhow::anyhow!("Unexpected answer from the backend: {}", e)))
+        }
+        reqwest::StatusCode::UNAUTHORIZED => Err(SyncError::SignedOut),
+        status => {
+            let body = response.text().await.unwrap_or_default();
+            Err(SyncError::Other(anyhow::anyhow!("Backend answered {}: {}", status, body)))
+        }
+    }
+}
+
+fn push_body(device_id: &str, items: &[SyncQueue]) -> serde_json::Value {
+    let items: Vec<PushItem> = items
+        .iter()
+        .map(|item| PushItem {
+            id: item.id,
+            entity_type: &item.entity_type,
+            entity_uuid: &item.entity_uuid,
+            action: &item.action,
+            payload: serde_json::from_str(&item.payload).unwrap_or(serde_json::Value::Null),
+            changed_fields: item.changed_fields.as_deref(),
+            queued_at: &item.created_at,
+        })
+        .collect();
+    serde_json::json!({ "device_id": device_id, "items": items })
+}
+
+/// Pushes every pending item, a batch at a time, until the queue is empty
+/// or the backend stops answering.
+async fn push(app: &AppHandle, state: &AppState) -> Result<PushTally> {
+    let mut tally = PushTally::default();
+    let folded = state.with_db_async(|db| db.coalesce_sync_queue()).await?;
+    if folded > 0 {
+        println!("[NOVEM] Folded {} queued edits into deltas", folded);
+    }
+    let device = state.with_db_async(device_id).await?;
+    let mut total = state.with_db_async(|db| db.count_sync_items("pending")).await? as usize;
+    let mut processed = 0;
+
+    loop {
+        let items = state.with_db_async(|db| db.get_pending_sync_items()).await?;
+        if items.is_empty() {
+            break;
+        }
+
+        let results = match post::<PushResponse>(state, &push_body(&device, &items)).await {
+            Ok(response) => response.results,
+            // The whole batch failed; every item uses up a retry
+            Err(SyncError::Other(e)) => {
+                let message = format!("{:#}", e);
+                items
+                    .iter()
+                    .map(|item| ItemResult { id: item.id, ok: false, error: Some(message.clone()), server_version: None })
+                    .collect()
+            }
+            Err(e) => return Err(e.into_anyhow()),
+        };
+
+        let batch = items.len();
+        let handled: Vec<(String, String)> =
+            items.iter().map(|item| (item.entity_type.clone(), item.entity_uuid.clone())).collect();
+        let pushed = state.with_db_async(move |db| apply_results(db, &items, results)).await?;
+        tally.pushed += pushed.pushed;
+        tally.failed += pushed.failed;
+
+        // Results land per batch, in one transaction, so progress is
+        // reported once they are in
+        for (entity_type, entity_uuid) in handled {
+            processed += 1;
+            total = total.max(processed);
+            emit_progress(
+                app,
+                SyncProgress {
+                    phase: SyncPhase::Push,
+                    entity_type: Some(entity_type),
+                    processed,
+                    total,
+                    current_item: Some(entity_uuid),
+                },
+            );
+        }
+
+        // Rejected items are retried on the next pass, not in this one
+        if pushed.failed > 0 || batch < 100 {
+            break;
+        }
+    }
+
+    Ok(tally)
+}
+
+/// Offers one backend change to the local row, or stores it as a new row
+/// when this device doesn't have it yet. `None` if the row can't be
+/// created here.
+fn apply_change(db: &LocalDatabase, change: &ServerChange, owner_id: Option<i64>) -> Result<Option<ServerCopyResult>> {
+    if db.has_versioned_row(&change.entity_type, &change.uuid)? {
+        let strategy = settings::conflict_strategy(db, &change.entity_type)?;
+        return db
+            .apply_server_copy(&change.entity_type, &change.uuid, change.version, &change.data, strategy)
+            .map(Some);
+    }
+    let inserted = db.insert_server_copy(&change.entity_type, &change.uuid, change.version, &change.data, owner_id)?;
+    Ok(inserted.then_some(ServerCopyResult { check: VersionCheck::ServerAhead, applied: true, conflict: None }))
+}
+
+/// Offers each backend change to the local row, settling conflicts per
+/// the configured strategy. Rows new to this device are created, owned by
+/// `owner_id`; changes to entities that aren't versioned here are skipped.
+/// A row that can't take its change is counted rather than holding up the
+/// rest. `on_change` is told about each change as it is offered.
+fn apply_changes(
+    db: &LocalDatabase,
+    changes: &[ServerChange],
+    owner_id: Option<i64>,
+    mut on_change: impl FnMut(usize, &ServerChange),
+) -> Result<PullTally> {
+    let mut tally = PullTally::default();
+
+    for (index, change) in changes.iter().enumerate() {
+        on_change(index, change);
+        if !is_versioned(&change.entity_type) {
+            tally.skipped += 1;
+            continue;
+        }
+        match apply_change(db, change, owner_id) {
+            Ok(Some(result)) => {
+                if result.applied {
+                    tally.pulled += 1;
+                }
+                tally.conflicts.extend(result.conflict);
+            }
+            Ok(None) => tally.skipped += 1,
+            Err(e) => {
+                eprintln!("[WARNING] Could not apply server copy of {} {}: {}", change.entity_type, change.uuid, e);
+                tally.failed += 1;
+                tally.first_failed.get_or_insert(index);
+            }
+        }
+    }
+
+    Ok(tally)
+}
+
+/// Where the next pull should start. With every change applied that is
+/// the time of the backend's answer; otherwise the cursor stops short of
+/// the first failed change, and of anything stored at the same moment, so
+/// they are fetched again. `None` leaves the cursor where it was.
+fn next_cursor<'a>(changes: &'a [ServerChange], first_failed: Option<usize>, timestamp: &'a str) -> Option<&'a str> {
+    let Some(failed) = first_failed else {
+        return Some(timestamp);
+    };
+    let failed_at = changes[failed].updated_at.as_str();
+    changes[..failed].iter().rev().map(|change| change.updated_at.as_str()).find(|&at| at != failed_at)
+}
+
+/// Fetches and applies what changed on the backend since the last pull.
+async fn pull(app: &AppHandle, state: &AppState) -> Result<PullTally> {
+    let (device, since) = state
+        .with_db_async(|db| Ok((device_id(db)?, db.get_setting(PULL_CURSOR_SETTING)?)))
+        .await?;
+    let owner_id = state
+        .backend
+        .lock()
+        .map_err(|e| anyhow::anyhow!("Failed to lock backend session: {}", e))?
+        .user_id;
+    let body = serde_json::json!({ "device_id": device, "since": since });
+    let response: PullResponse = post(state, &body).await.map_err(SyncError::into_anyhow)?;
+
+    let PullResponse { changes, timestamp } = response;
+    let progress_app = app.clone();
+    let tally = state
+        .with_db_async(move |db| {
+            let total = changes.len();
+            let tally = apply_changes(db, &changes, owner_id, |index, change| {
+                emit_progress(
+                    &progress_app,
+                    SyncProgress {
+                        phase: SyncPhase::Pull,
+                        entity_type: Some(change.entity_type.clone()),
+                        processed: index + 1,
+                        total,
+                        current_item: Some(change.uuid.clone()),
+                    },
+                );
+            })?;
+            if let Some(cursor) = next_cursor(&changes, tally.first_failed, &timestamp) {
+                db.set_setting(PULL_CURSOR_SETTING, cursor)?;
+            }
+            Ok(tally)
+        })
+        .await?;
+
+    if tally.skipped > 0 {
+        println!("[NOVEM] Skipped {} pulled changes this device doesn't keep", tally.skipped);
+    }
+    for conflict in &tally.conflicts {
+        report_conflict(app, conflict).await;
+    }
+    Ok(tally)
+}
+
+/// Runs one full cycle, pushing local changes before pulling the
+/// backend's. Waits for a pass already underway rather than racing it.
+pub async fn run_cycle(app: &AppHandle, state: &AppState) -> Result<SyncSummary> {
+    let _pass = state.sync.pass.lock().await;
+    let started = Instant::now();
+
+    let result = async {
+        let pushed = push(app, state).await?;
+        let pulled = pull(app, state).await?;
+        Ok::<_, anyhow::Error>(SyncSummary {
+            pushed: pushed.pushed,
+            pulled: pulled.pulled,
+            conflicts: pulled.conflicts.len(),
+            failed: pushed.failed + pulled.failed,
+            duration_ms: started.elapsed().as_millis() as u64,
+        })
+    }
+    .await;
+
+    // Sent even when the cycle stopped early, so a progress bar never hangs
+    let handled = result
+        .as_ref()
+        .map(|summary| summary.pushed + summary.pulled + summary.conflicts + summary.failed)
+        .unwrap_or(0);
+    emit_progress(
+        app,
+        SyncProgress { phase: SyncPhase::Done, entity_type: None, processed: handled, total: handled, current_item: None },
+    );
+    result
+}
+
+/// Logs how a conflict was settled, or asks the user to settle it.
+pub async fn report_conflict(app: &AppHandle, conflict: &Conflict) {
+    match conflict.resolution {
+        Some(choice) => println!(
+            "[NOVEM] Sync conflict on {} {} settled for the {} copy",
+            conflict.entity_type, conflict.entity_uuid, choice.as_str()
+        ),
+        None => {
+            eprintln!(
+                "[WARNING] Sync conflict on {} {} needs to be resolved",
+                conflict.entity_type, conflict.entity_uuid
+            );
+            let body = tr!(
+                "notify-sync-conflict-body",
+                entity = conflict.entity_type.as_str(),
+                uuid = conflict.entity_uuid.as_str()
+            );
+            notifications::notify(
+                app,
+                NewNotification::new("sync", NotificationPriority::High, tr!("notify-sync-conflict"))
+                    .body(body)
+                    .about(&conflict.entity_type, &conflict.entity_uuid),
+            )
+            .await;
+        }
+    }
+}
+
+/// Background loop running a sync cycle every `sync.interval_secs`, or
+/// right away when a sync is requested. Skips passes while stopped,
+/// signed out, in guest mode or offline.
+pub fn start_worker(app: AppHandle) {
+    let mut events = app.state::<EventBus>().subscribe();
+
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+
+        loop {
+            let interval = state.with_db_async(settings::sync_interval).await.unwrap_or_else(|e| {
+                eprintln!("[ERROR] Failed to load sync interval: {}", e);
+                DEFAULT_INTERVAL
+            });
+
+            tokio::select! {
+                _ = tokio::time::sleep(interval) => {}
+                _ = state.sync.wake.notified() => {}
+                event = events.recv() => match event {
+                    Ok(record) if matches!(record.event, AppEvent::SyncRequested) => {}
+                    // Catch up on what queued while offline
+                    Ok(record) if matches!(&record.event, AppEvent::ConnectivityChanged(status) if status.online) => {}
+                    Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                },
+            }
+
+            if !state.sync.is_enabled() || state.mode.is_guest() {
+                continue;
+            }
+            let signed_in = state.backend.lock().map(|session| session.access_token.is_some()).unwrap_or(false);
+            if !signed_in || !state.connectivity.is_online() {
+                continue;
+            }
+
+            match run_cycle(&app, &state).await {
+                Ok(summary) if summary.pushed + summary.pulled + summary.conflicts + summary.failed == 0 => {}
+                Ok(summary) => println!(
+                    "[NOVEM] Sync pushed {}, pulled {}, {} conflicts, {} failed",
+                    summary.pushed, summary.pulled, summary.conflicts, summary.failed
+                ),
+                Err(e) => eprintln!("[WARNING] Sync pass stopped: {:#}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_results() {
+        let db_path = std::env::temp_dir().join("test_novem_sync_push.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        db.add_to_sync_queue("comment", "c1", "create", "{}").unwrap();
+        db.add_to_sync_queue("comment", "c2", "create", "{}").unwrap();
+        db.add_to_sync_queue("comment", "c3", "update", "{}").unwrap();
+        let items = db.get_pending_sync_items().unwrap();
+
+        // c3 goes unanswered
+        let results = vec![
+            ItemResult { id: items[0].id, ok: true, error: None, server_version: None },
+            ItemResult { id: items[1].id, ok: false, error: Some("invalid".to_string()), server_version: None },
+        ];
+        let tally = apply_results(&db, &items, results).unwrap();
+        assert_eq!(tally, PushTally { pushed: 1, failed: 2 });
+
+        // Rejected and unanswered items stay queued, backing off before
+        // their retry
+        assert_eq!(db.count_sync_items("pending").unwrap(), 2);
+        assert!(db.get_pending_sync_items().unwrap().is_empty());
+        let rejected = db.get_sync_item(items[1].id).unwrap().unwrap();
+        assert_eq!(rejected.retry_count, 1);
+        assert_eq!(rejected.error_message.as_deref(), Some("invalid"));
+        assert!(rejected.next_attempt_at.is_some());
+        let unanswered = db.get_sync_item(items[2].id).unwrap().unwrap();
+        assert_eq!(unanswered.retry_count, 1);
+        assert_eq!(db.count_sync_items("completed").unwrap(), 1);
+
+        // An answer without results acknowledges nothing
+        assert!(serde_json::from_str::<PushResponse>(r#"{"status": "synced"}"#).is_err());
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+
+    fn change(entity_type: &str, uuid: &str, version: i64, data: serde_json::Value) -> ServerChange {
+        ServerChange {
+            entity_type: entity_type.to_string(),
+            uuid: uuid.to_string(),
+            version,
+            data,
+            updated_at: "2026-01-01T00:00:00+00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_pull_changes() {
+        let db_path = std::env::temp_dir().join("test_novem_sync_pull.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        let workspace = db.create_workspace("Team", None, 1).unwrap();
+        db.mark_version_synced("workspace", &workspace.uuid, workspace.version).unwrap();
+
+        let renamed = serde_json::json!({ "name": "Team A" });
+        let changes = vec![
+            change("workspace", &workspace.uuid, workspace.version + 1, renamed.clone()),
+            change("comment", "c1", 1, renamed.clone()),
+            change("project", "p1", 1, serde_json::json!({ "workspace_uuid": "gone", "name": "Sales" })),
+        ];
+        let mut offered = Vec::new();
+        let tally =
+            apply_changes(&db, &changes, Some(1), |index, change| offered.push((index, change.uuid.clone()))).unwrap();
+        assert_eq!(offered.len(), 3);
+        assert_eq!(offered[1], (1, "c1".to_string()));
+        assert_eq!(tally.pulled, 1);
+        // Comments aren't versioned here and are skipped, not failed
+        assert_eq!(tally.skipped, 1);
+        assert_eq!(tally.failed, 1);
+        assert_eq!(tally.first_failed, Some(2));
+        assert!(tally.conflicts.is_empty());
+        assert_eq!(db.get_workspace_by_uuid(&workspace.uuid).unwrap().unwrap().name, "Team A");
+
+        // A push-only answer isn't mistaken for an empty pull
+        let pushed_only = r#"{"status": "synced", "results": [], "timestamp": "2026-01-01T00:00:00Z"}"#;
+        assert!(serde_json::from_str::<PullResponse>(pushed_only).is_err());
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+
+    #[test]
+    fn test_pull_new_rows() {
+        let db_path = std::env::temp_dir().join("test_novem_sync_pull_new.db");
+        let _ = std::fs::remove_file(&db_path);
+        let db = LocalDatabase::new(db_path.clone(), None).unwrap();
+
+        // Created on another device: neither row exists here yet
+        let changes = vec![
+            change("workspace", "ws-remote", 3, serde_json::json!({ "name": "Remote", "description": "Shared" })),
+            change("project", "p-remote", 2, serde_json::json!({ "workspace_uuid": "ws-remote", "name": "Sales" })),
+            change("dataset", "d-remote", 1, serde_json::json!({ "name": "sales.csv" })),
+        ];
+        let tally = apply_changes(&db, &changes, Some(1), |_, _| {}).unwrap();
+        assert_eq!(tally.pulled, 2);
+        assert_eq!(tally.failed, 0);
+        // Its file is on the other device
+        assert_eq!(tally.skipped, 1);
+
+        let workspace = db.get_workspace_by_uuid("ws-remote").unwrap().unwrap();
+        assert_eq!(workspace.name, "Remote");
+        assert_eq!(workspace.owner_id, 1);
+        assert_eq!(db.check_server_version("workspace", "ws-remote", 3).unwrap(), VersionCheck::InSync);
+        assert_eq!(db.check_server_version("project", "p-remote", 2).unwrap(), VersionCheck::InSync);
+        assert!(!db.has_versioned_row("dataset", "d-remote").unwrap());
+
+        // Pulling the same changes again leaves them alone
+        let tally = apply_changes(&db, &changes, Some(1), |_, _| {}).unwrap();
+        assert_eq!((tally.pulled, tally.failed), (0, 0));
+
+        drop(db);
+        std::fs::remove_file(db_path).ok();
+    }
+
+    #[test]
+    fn test_pull_cursor() {
+        let at = |uuid: &str, updated_at: &str| ServerChange {
+            updated_at: updated_at.to_string(),
+            ..change("workspace", uuid, 1, serde_json::Value::Null)
+        };
+        let changes = vec![
+            at("a", "2026-01-01T00:00:01+00:00"),
+            at("b", "2026-01-01T00:00:02+00:00"),
+            at("c", "2026-01-01T00:00:02+00:00"),
+            at("d", "2026-01-01T00:00:03+00:00"),
+        ];
+        let now = "2026-01-01T00:00:09+00:00";
+
+        assert_eq!(next_cursor(&changes, None, now), Some(now));
+        assert_eq!(next_cursor(&changes, Some(3), now), Some("2026-01-01T00:00:02+00:00"));
+        // "b" shares the failed change's time and is fetched again with it
+        assert_eq!(next_cursor(&changes, Some(2), now), Some("2026-01-01T00:00:01+00:00"));
+        assert_eq!(next_cursor(&changes, Some(0), now), None);
+    }
+}
@@ -0,0 +1,55 @@
+use chrono::{DateTime, NaiveDateTime, SecondsFormat, Utc};
+
+/// Formats an instant the way every stored timestamp looks: RFC3339 UTC with
+/// second precision (`2025-03-14T09:26:53Z`), so strings sort in time order
+/// on every machine. SQL writes the same shape with
+/// `strftime('%Y-%m-%dT%H:%M:%SZ', 'now')`.
+pub fn format(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+pub fn now() -> String {
+    format(Utc::now())
+}
+
+/// Parses RFC3339 with any offset, or SQLite's zone-less
+/// `YYYY-MM-DD HH:MM:SS`, which CURRENT_TIMESTAMP always wrote in UTC.
+pub fn parse(text: &str) -> Option<DateTime<Utc>> {
+    let text = text.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+        return Some(time.with_timezone(&Utc));
+    }
+
+    ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"]
+        .iter()
+        .find_map(|pattern| NaiveDateTime::parse_from_str(text, pattern).ok())
+        .map(|naive| naive.and_utc())
+}
+
+/// Rewrites a timestamp from elsewhere (the backend, the UI) into the stored
+/// form. Unparseable input is passed through unchanged.
+pub fn normalize(text: &str) -> String {
+    parse(text).map(format).unwrap_or_else(|| text.to_string())
+}
+
+pub fn normalize_opt(text: Option<&str>) -> Option<String> {
+    text.map(normalize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("2025-03-14 09:26:53"), "2025-03-14T09:26:53Z");
+        assert_eq!(normalize("2025-03-14T11:26:53.250+02:00"), "2025-03-14T09:26:53Z");
+        assert_eq!(normalize("2025-03-14T09:26:53Z"), "2025-03-14T09:26:53Z");
+        assert_eq!(normalize("yesterday"), "yesterday");
+
+        // Normalized strings sort chronologically regardless of source offset
+        let earlier = normalize("2025-03-14T23:30:00-05:00");
+        let later = normalize("2025-03-15T06:00:00+01:00");
+        assert!(earlier < later);
+    }
+}
@@ -0,0 +1,202 @@
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::io::{Read, Seek, SeekFrom};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::events::{self, AppEvent};
+use crate::settings;
+use crate::AppState;
+
+pub const DEFAULT_CHUNK_SIZE: i64 = 1024 * 1024;
+pub const MAX_TRANSFER_RETRIES: i64 = 5;
+const CHUNK_TIMEOUT: Duration = Duration::from_secs(60);
+/// Used if the configured sync interval can't be read.
+const DEFAULT_RESUME_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Tracks which transfers are currently being uploaded so the resume worker
+/// and manual retries never push the same file twice.
+pub struct TransferQueue {
+    active: Mutex<HashSet<String>>,
+}
+
+impl TransferQueue {
+    pub fn new() -> Self {
+        Self {
+            active: Mutex::new(HashSet::new()),
+        }
+    }
+
+    fn try_claim(&self, uuid: &str) -> bool {
+        self.active.lock().unwrap().insert(uuid.to_string())
+    }
+
+    fn release(&self, uuid: &str) {
+        self.active.lock().unwrap().remove(uuid);
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.active.lock().unwrap().is_empty()
+    }
+}
+
+/// Starts (or resumes) a transfer in the background.
+pub fn spawn_transfer(app: AppHandle, uuid: String) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = run_transfer(&app, &uuid).await {
+            eprintln!("[NOVEM] Transfer {} interrupted: {}", uuid, e);
+        }
+    });
+}
+
+/// Uploads the remaining chunks of a transfer, persisting progress after every
+/// chunk so an interrupted upload resumes from the last acknowledged byte.
+pub async fn run_transfer(app: &AppHandle, uuid: &str) -> Result<()> {
+    let state = app.state::<AppState>();
+
+    // Guests never upload; the transfer stays queued for a normal session
+    if state.mode.is_guest() || !state.transfers.try_claim(uuid) {
+        return Ok(());
+    }
+
+    let result = upload_chunks(app, &state, uuid).await;
+
+    if let Err(e) = &result {
+        let message = e.to_string();
+        let _ = state.with_db(|db| db.record_transfer_failure(uuid, &message, MAX_TRANSFER_RETRIES));
+        if let Ok(Some(transfer)) = state.with_db(|db| db.get_transfer(uuid)) {
+            publish_status(app, uuid, &transfer.status);
+        }
+    }
+
+    state.transfers.release(uuid);
+    result
+}
+
+fn publish_status(app: &AppHandle, uuid: &str, status: &str) {
+    events::publish(app, AppEvent::TransferUpdated { uuid: uuid.to_string(), status: status.to_string() });
+}
+
+async fn upload_chunks(app: &AppHandle, state: &AppState, uuid: &str) -> Result<()> {
+    let transfer = state
+        .with_db(|db| db.get_transfer(uuid))?
+        .ok_or_else(|| anyhow::anyhow!("Transfer {} not found", uuid))?;
+
+    if transfer.status != "pending" {
+        return Ok(());
+    }
+
+    state.with_db(|db| db.update_transfer_status(uuid, "uploading", None))?;
+    publish_status(app, uuid, "uploading");
+
+    let session = state
+        .backend
+        .lock()
+        .map_err(|e| anyhow::anyhow!("Failed to lock backend session: {}", e))?
+        .clone();
+
+    let url = session.url(&format!("/api/artifacts/uploads/{}/", transfer.uuid));
+
+    let mut file = std::fs::File::open(&transfer.file_path)
+        .context(format!("Failed to open {}", transfer.file_path))?;
+
+    let mut bytes_sent = transfer.bytes_sent;
+    let mut chunk_index = transfer.chunk_index;
+
+    println!(
+        "[NOVEM] Uploading {} ({} of {} bytes already sent)",
+        transfer.file_name, bytes_sent, transfer.total_bytes
+    );
+
+    while bytes_sent < transfer.total_bytes {
+        // Cancellation is recorded in the database; honour it between chunks
+        let status = state
+            .with_db(|db| db.get_transfer(uuid))?
+            .map(|t| t.status)
+            .unwrap_or_default();
+        if status == "cancelled" {
+            println!("[NOVEM] Transfer {} cancelled", uuid);
+            publish_status(app, uuid, "cancelled");
+            return Ok(());
+        }
+
+        let remaining = transfer.total_bytes - bytes_sent;
+        let mut chunk = vec![0u8; remaining.min(transfer.chunk_size) as usize];
+        file.seek(SeekFrom::Start(bytes_sent as u64))?;
+        file.read_exact(&mut chunk)
+            .context(format!("Failed to read {}", transfer.file_path))?;
+
+        let end = bytes_sent + chunk.len() as i64 - 1;
+        let request = state
+            .http
+            .backend()
+            .put(&url)
+            .timeout(CHUNK_TIMEOUT)
+            .header("Content-Range", format!("bytes {}-{}/{}", bytes_sent, end, transfer.total_bytes))
+            .header("X-Chunk-Index", chunk_index.to_string())
+            .header("X-Entity-Type", &transfer.entity_type)
+            .header("X-Entity-Uuid", &transfer.entity_uuid)
+            .header("X-File-Name", &transfer.file_name)
+            .body(chunk);
+
+        let response = session
+            .authorize(request)
+            .send()
+            .await
+            .context("Backend unreachable")?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("Backend rejected chunk {}: {}", chunk_index, response.status()));
+        }
+
+        bytes_sent = end + 1;
+        chunk_index += 1;
+        state.with_db(|db| db.update_transfer_progress(uuid, bytes_sent, chunk_index))?;
+    }
+
+    // A cancel may have landed while the last chunk was in flight
+    if !state.with_db(|db| db.complete_transfer(uuid))? {
+        println!("[NOVEM] Transfer {} cancelled", uuid);
+        publish_status(app, uuid, "cancelled");
+        return Ok(());
+    }
+    publish_status(app, uuid, "completed");
+    println!("[NOVEM] Transfer {} completed", uuid);
+
+    Ok(())
+}
+
+/// Background loop that resumes pending transfers whenever the backend is
+/// reachable (see `connectivity`).
+pub fn start_resume_worker(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+
+        match state.with_db(|db| db.reset_interrupted_transfers()) {
+            Ok(count) if count > 0 => println!("[NOVEM] Requeued {} interrupted transfers", count),
+            Ok(_) => {}
+            Err(e) => eprintln!("[ERROR] Failed to requeue interrupted transfers: {}", e),
+        }
+
+        loop {
+            let pending = state
+                .with_db(|db| db.get_resumable_transfers(MAX_TRANSFER_RETRIES))
+                .unwrap_or_default();
+
+            if !pending.is_empty() && !state.mode.is_guest() && state.connectivity.is_online() {
+                for transfer in pending {
+                    if let Err(e) = run_transfer(&app, &transfer.uuid).await {
+                        eprintln!("[NOVEM] Transfer {} interrupted: {}", transfer.uuid, e);
+                    }
+                }
+            }
+
+            let interval = state.with_db(settings::sync_interval).unwrap_or_else(|e| {
+                eprintln!("[ERROR] Failed to load sync interval: {}", e);
+                DEFAULT_RESUME_INTERVAL
+            });
+            tokio::time::sleep(interval).await;
+        }
+    });
+}
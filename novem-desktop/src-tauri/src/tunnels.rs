@@ -0,0 +1,471 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use ssh2::{CheckResult, KnownHostFileKind, Session};
+use std::collections::HashMap;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+use crate::events::{self, AppEvent};
+use crate::i18n::tr;
+use crate::engine::DEFAULT_ENGINE_PORT;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const IDLE_POLL: Duration = Duration::from_millis(10);
+const ACCEPT_POLL: Duration = Duration::from_millis(50);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// libssh2's "would block" code, returned by non-blocking session calls.
+const LIBSSH2_ERROR_EAGAIN: i32 = -37;
+
+#[derive(Clone, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum TunnelAuth {
+    Key { private_key_path: PathBuf, passphrase: Option<String> },
+    Password { password: String },
+}
+
+// Keeps secrets out of logs
+impl std::fmt::Debug for TunnelAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TunnelAuth::Key { private_key_path, .. } => write!(f, "Key({:?})", private_key_path),
+            TunnelAuth::Password { .. } => f.write_str("Password"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TunnelConfig {
+    pub host: String,
+    #[serde(default = "default_ssh_port")]
+    pub ssh_port: u16,
+    pub username: String,
+    pub auth: TunnelAuth,
+    /// Where the engine listens, as seen from the SSH host
+    #[serde(default = "default_remote_host")]
+    pub remote_host: String,
+    #[serde(default = "default_remote_port")]
+    pub remote_port: u16,
+    /// Local port to forward from; 0 picks a free one
+    #[serde(default)]
+    pub local_port: u16,
+    #[serde(default = "default_keepalive_secs")]
+    pub keepalive_secs: u32,
+    /// Connect to hosts missing from known_hosts. Changed keys are always
+    /// rejected.
+    #[serde(default)]
+    pub trust_unknown_host: bool,
+}
+
+fn default_ssh_port() -> u16 {
+    22
+}
+
+fn default_remote_host() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_remote_port() -> u16 {
+    DEFAULT_ENGINE_PORT
+}
+
+fn default_keepalive_secs() -> u32 {
+    30
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TunnelStatus {
+    Connected,
+    Reconnecting,
+    Closed,
+}
+
+/// Emitted as `engine-tunnel-status` whenever a tunnel connects, drops or
+/// closes.
+#[derive(Debug, Clone, Serialize)]
+pub struct TunnelInfo {
+    pub project_id: Option<i64>,
+    pub host: String,
+    pub local_port: u16,
+    pub remote_port: u16,
+    pub status: TunnelStatus,
+    pub reconnects: u32,
+    pub last_error: Option<String>,
+}
+
+struct Tunnel {
+    info: Arc<Mutex<TunnelInfo>>,
+    closed: Arc<AtomicBool>,
+}
+
+/// SSH tunnels to remote engines, keyed like engines by project ID (`None`
+/// is the shared engine). While a tunnel is open, requests for its engine
+/// go to the tunnel's local port instead of a local process.
+pub struct TunnelManager {
+    app: AppHandle,
+    tunnels: Mutex<HashMap<Option<i64>, Tunnel>>,
+}
+
+impl TunnelManager {
+    pub fn new(app: AppHandle) -> Self {
+        Self {
+            app,
+            tunnels: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Connects and starts forwarding, replacing any tunnel already open for
+    /// `project_id`. Blocks until the first connection is authenticated so
+    /// bad credentials fail here rather than in the background.
+    pub fn open(&self, project_id: Option<i64>, config: TunnelConfig) -> Result<TunnelInfo> {
+        self.close(project_id);
+
+        let session = connect(&config)?;
+        let listener = TcpListener::bind(("127.0.0.1", config.local_port))
+            .context(format!("Failed to bind local port {}", config.local_port))?;
+        listener.set_nonblocking(true)?;
+
+        let info = TunnelInfo {
+            project_id,
+            host: config.host.clone(),
+            local_port: listener.local_addr()?.port(),
+            remote_port: config.remote_port,
+            status: TunnelStatus::Connected,
+            reconnects: 0,
+            last_error: None,
+        };
+        println!(
+            "[NOVEM] Tunnel open: 127.0.0.1:{} -> {}:{} via {}",
+            info.local_port, config.remote_host, config.remote_port, config.host
+        );
+
+        let tunnel = Tunnel {
+            info: Arc::new(Mutex::new(info.clone())),
+            closed: Arc::new(AtomicBool::new(false)),
+        };
+        let forwarder = Forwarder {
+            app: self.app.clone(),
+            config,
+            listener,
+            info: Arc::clone(&tunnel.info),
+            closed: Arc::clone(&tunnel.closed),
+            broken: Arc::new(AtomicBool::new(false)),
+        };
+        std::thread::spawn(move || forwarder.run(session));
+
+        self.tunnels.lock().unwrap().insert(project_id, tunnel);
+        events::publish(&self.app, AppEvent::TunnelStatus(info.clone()));
+        Ok(info)
+    }
+
+    /// Stops forwarding; connections in flight are cut. Returns whether a
+    /// tunnel was open.
+    pub fn close(&self, project_id: Option<i64>) -> bool {
+        let Some(tunnel) = self.tunnels.lock().unwrap().remove(&project_id) else {
+            return false;
+        };
+
+        tunnel.closed.store(true, Ordering::Relaxed);
+        let info = {
+            let mut info = tunnel.info.lock().unwrap();
+            info.status = TunnelStatus::Closed;
+            info.clone()
+        };
+        println!("[NOVEM] Tunnel to {} closed", info.host);
+        events::publish(&self.app, AppEvent::TunnelStatus(info));
+        true
+    }
+
+    pub fn close_all(&self) {
+        let project_ids: Vec<Option<i64>> = self.tunnels.lock().unwrap().keys().copied().collect();
+        for project_id in project_ids {
+            self.close(project_id);
+        }
+    }
+
+    /// Local end of the tunnel for `project_id`, if one is open. The port
+    /// stays bound while reconnecting, so callers just see slow requests.
+    pub fn local_port(&self, project_id: Option<i64>) -> Option<u16> {
+        self.tunnels
+            .lock()
+            .unwrap()
+            .get(&project_id)
+            .map(|tunnel| tunnel.info.lock().unwrap().local_port)
+    }
+
+    pub fn list(&self) -> Vec<TunnelInfo> {
+        let mut tunnels: Vec<TunnelInfo> = self
+            .tunnels
+            .lock()
+            .unwrap()
+            .values()
+            .map(|tunnel| tunnel.info.lock().unwrap().clone())
+            .collect();
+        tunnels.sort_by_key(|info| info.project_id);
+        tunnels
+    }
+}
+
+fn is_eagain(e: &ssh2::Error) -> bool {
+    e.code() == ssh2::ErrorCode::Session(LIBSSH2_ERROR_EAGAIN)
+}
+
+fn known_hosts_path() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+    Some(PathBuf::from(home).join(".ssh").join("known_hosts"))
+}
+
+fn verify_host_key(session: &Session, config: &TunnelConfig) -> Result<()> {
+    let (key, _) = session
+        .host_key()
+        .ok_or_else(|| anyhow::anyhow!("{} sent no host key", config.host))?;
+
+    let mut known_hosts = session.known_hosts()?;
+    if let Some(path) = known_hosts_path().filter(|path| path.exists()) {
+        known_hosts.read_file(&path, KnownHostFileKind::OpenSSH)?;
+    }
+
+    match known_hosts.check_port(&config.host, config.ssh_port, key) {
+        CheckResult::Match => Ok(()),
+        CheckResult::NotFound if config.trust_unknown_host => {
+            eprintln!("[WARNING] Trusting unknown host key for {}", config.host);
+            Ok(())
+        }
+        CheckResult::NotFound => Err(anyhow::anyhow!(tr!("tunnel-host-unknown", host = config.host.as_str()))),
+        CheckResult::Mismatch => Err(anyhow::anyhow!(tr!("tunnel-host-key-changed", host = config.host.as_str()))),
+        CheckResult::Failure => Err(anyhow::anyhow!("Failed to check host key for {}", config.host)),
+    }
+}
+
+/// Opens an authenticated session, switched to non-blocking mode so one
+/// session can serve many forwarded connections.
+fn connect(config: &TunnelConfig) -> Result<Session> {
+    let address = (config.host.as_str(), config.ssh_port)
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("Could not resolve {}", config.host))?;
+    let tcp = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT)
+        .map_err(|e| anyhow::anyhow!(tr!("tunnel-unreachable", host = config.host.as_str(), error = e.to_string())))?;
+
+    let mut session = Session::new()?;
+    session.set_tcp_stream(tcp);
+    session.set_timeout(CONNECT_TIMEOUT.as_millis() as u32);
+    session.handshake()?;
+    verify_host_key(&session, config)?;
+
+    let auth = match &config.auth {
+        TunnelAuth::Key { private_key_path, passphrase } => {
+            session.userauth_pubkey_file(&config.username, None, private_key_path, passphrase.as_deref())
+        }
+        TunnelAuth::Password { password } => session.userauth_password(&config.username, password),
+    };
+    if auth.is_err() || !session.authenticated() {
+        return Err(anyhow::anyhow!(tr!(
+            "tunnel-auth-failed",
+            user = config.username.as_str(),
+            host = config.host.as_str()
+        )));
+    }
+
+    session.set_keepalive(true, config.keepalive_secs);
+    session.set_timeout(0);
+    session.set_blocking(false);
+    Ok(session)
+}
+
+struct Forwarder {
+    app: AppHandle,
+    config: TunnelConfig,
+    listener: TcpListener,
+    info: Arc<Mutex<TunnelInfo>>,
+    closed: Arc<AtomicBool>,
+    /// Set by connection threads when the session stops opening channels
+    broken: Arc<AtomicBool>,
+}
+
+impl Forwarder {
+    fn update(&self, f: impl FnOnce(&mut TunnelInfo)) {
+        if self.closed.load(Ordering::Relaxed) {
+            return;
+        }
+        let info = {
+            let mut info = self.info.lock().unwrap();
+            f(&mut info);
+            info.clone()
+        };
+        events::publish(&self.app, AppEvent::TunnelStatus(info));
+    }
+
+    fn run(self, session: Session) {
+        let mut session = Some(session);
+        let mut next_keepalive = Instant::now();
+        let mut delay = Duration::from_secs(1);
+
+        while !self.closed.load(Ordering::Relaxed) {
+            let Some(current) = session.clone() else {
+                match connect(&self.config) {
+                    Ok(new_session) => {
+                        println!("[NOVEM] Tunnel to {} reconnected", self.config.host);
+                        session = Some(new_session);
+                        next_keepalive = Instant::now();
+                        delay = Duration::from_secs(1);
+                        self.broken.store(false, Ordering::Relaxed);
+                        self.update(|info| {
+                            info.status = TunnelStatus::Connected;
+                            info.reconnects += 1;
+                        });
+                    }
+                    Err(e) => {
+                        eprintln!("[WARNING] Tunnel to {} still down: {}", self.config.host, e);
+                        self.update(|info| info.last_error = Some(e.to_string()));
+                        self.sleep(delay);
+                        delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+                    }
+                }
+                continue;
+            };
+
+            if Instant::now() >= next_keepalive {
+                match current.keepalive_send() {
+                    Ok(secs) => next_keepalive = Instant::now() + Duration::from_secs(u64::from(secs.max(1))),
+                    Err(e) if is_eagain(&e) => {}
+                    Err(e) => self.drop_session(&mut session, e.to_string()),
+                }
+            }
+            if self.broken.swap(false, Ordering::Relaxed) {
+                self.drop_session(&mut session, "SSH session stopped responding".to_string());
+                continue;
+            }
+
+            match self.listener.accept() {
+                Ok((stream, _)) => {
+                    let target = (self.config.remote_host.clone(), self.config.remote_port);
+                    let closed = Arc::clone(&self.closed);
+                    let broken = Arc::clone(&self.broken);
+                    std::thread::spawn(move || {
+                        if let Err(e) = forward(stream, &current, &target, &closed, &broken) {
+                            eprintln!("[WARNING] Tunnel connection ended: {}", e);
+                        }
+                    });
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => std::thread::sleep(ACCEPT_POLL),
+                Err(e) => eprintln!("[ERROR] Tunnel accept failed: {}", e),
+            }
+        }
+    }
+
+    fn drop_session(&self, session: &mut Option<Session>, error: String) {
+        eprintln!("[WARNING] Tunnel to {} dropped: {}", self.config.host, error);
+        *session = None;
+        self.update(|info| {
+            info.status = TunnelStatus::Reconnecting;
+            info.last_error = Some(error);
+        });
+    }
+
+    /// Sleeps in short steps so closing isn't held up by a long backoff.
+    fn sleep(&self, duration: Duration) {
+        let deadline = Instant::now() + duration;
+        while Instant::now() < deadline && !self.closed.load(Ordering::Relaxed) {
+            std::thread::sleep(ACCEPT_POLL);
+        }
+    }
+}
+
+/// Retries a non-blocking session call until it stops returning EAGAIN.
+fn retry<T>(mut f: impl FnMut() -> Result<T, ssh2::Error>) -> Result<T, ssh2::Error> {
+    loop {
+        match f() {
+            Err(e) if is_eagain(&e) => std::thread::sleep(IDLE_POLL),
+            result => return result,
+        }
+    }
+}
+
+fn write_all(writer: &mut impl Write, mut data: &[u8]) -> std::io::Result<()> {
+    while !data.is_empty() {
+        match writer.write(data) {
+            Ok(0) => return Err(ErrorKind::WriteZero.into()),
+            Ok(n) => data = &data[n..],
+            Err(e) if e.kind() == ErrorKind::WouldBlock => std::thread::sleep(IDLE_POLL),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+/// Relays one local connection over a direct-tcpip channel until either side
+/// closes.
+fn forward(
+    mut local: TcpStream,
+    session: &Session,
+    (host, port): &(String, u16),
+    closed: &AtomicBool,
+    broken: &AtomicBool,
+) -> Result<()> {
+    let mut channel = retry(|| session.channel_direct_tcpip(host, *port, None)).inspect_err(|_| {
+        broken.store(true, Ordering::Relaxed);
+    })?;
+    local.set_nonblocking(true)?;
+
+    let mut buf = [0u8; 16 * 1024];
+    while !closed.load(Ordering::Relaxed) {
+        let mut idle = true;
+
+        match local.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => {
+                write_all(&mut channel, &buf[..n])?;
+                idle = false;
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        match channel.read(&mut buf) {
+            Ok(0) if channel.eof() => break,
+            Ok(0) => {}
+            Ok(n) => {
+                write_all(&mut local, &buf[..n])?;
+                idle = false;
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        if idle {
+            std::thread::sleep(IDLE_POLL);
+        }
+    }
+
+    let _ = retry(|| channel.close());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_defaults_and_redaction() {
+        let config: TunnelConfig = serde_json::from_value(serde_json::json!({
+            "host": "gpu-box.internal",
+            "username": "ana",
+            "auth": { "method": "password", "password": "hunter2" },
+        }))
+        .unwrap();
+
+        assert_eq!(config.ssh_port, 22);
+        assert_eq!(config.remote_host, "127.0.0.1");
+        assert_eq!(config.remote_port, DEFAULT_ENGINE_PORT);
+        assert_eq!(config.local_port, 0);
+        assert!(!config.trust_unknown_host);
+        assert!(!format!("{:?}", config).contains("hunter2"));
+    }
+}
@@ -0,0 +1,233 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::i18n::tr;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct WatchdogConfig {
+    pub enabled: bool,
+    /// Per-process CPU can exceed 100 on multi-core machines
+    pub engine_cpu_percent: f64,
+    pub engine_memory_mb: f64,
+    pub engine_disk_write_mb_per_sec: f64,
+    pub app_cpu_percent: f64,
+    pub app_memory_mb: f64,
+    /// How long a threshold must stay exceeded before alerting
+    pub sustained_secs: i64,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            engine_cpu_percent: 200.0,
+            engine_memory_mb: 4096.0,
+            engine_disk_write_mb_per_sec: 50.0,
+            app_cpu_percent: 300.0,
+            app_memory_mb: 8192.0,
+            sustained_secs: 60,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Metric {
+    EngineCpu,
+    EngineMemory,
+    EngineDiskWrite,
+    AppCpu,
+    AppMemory,
+}
+
+impl Metric {
+    fn threshold(self, config: &WatchdogConfig) -> f64 {
+        match self {
+            Metric::EngineCpu => config.engine_cpu_percent,
+            Metric::EngineMemory => config.engine_memory_mb,
+            Metric::EngineDiskWrite => config.engine_disk_write_mb_per_sec,
+            Metric::AppCpu => config.app_cpu_percent,
+            Metric::AppMemory => config.app_memory_mb,
+        }
+    }
+
+    fn label(self) -> String {
+        match self {
+            Metric::EngineCpu => tr!("metric-engine-cpu"),
+            Metric::EngineMemory => tr!("metric-engine-memory"),
+            Metric::EngineDiskWrite => tr!("metric-engine-disk-write"),
+            Metric::AppCpu => tr!("metric-app-cpu"),
+            Metric::AppMemory => tr!("metric-app-memory"),
+        }
+    }
+
+    fn format_value(self, value: f64) -> String {
+        match self {
+            Metric::EngineCpu | Metric::AppCpu => format!("{:.0}%", value),
+            Metric::EngineMemory | Metric::AppMemory => format!("{:.0} MB", value),
+            Metric::EngineDiskWrite => format!("{:.1} MB/s", value),
+        }
+    }
+
+    /// Engine metrics are caused by jobs on that engine; app metrics by any.
+    pub fn is_engine(self) -> bool {
+        matches!(self, Metric::EngineCpu | Metric::EngineMemory | Metric::EngineDiskWrite)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertState {
+    Raised,
+    Cleared,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RunningJob {
+    pub project_id: Option<i64>,
+    pub request_id: String,
+}
+
+/// Emitted as `resource-alert` when a threshold has been exceeded for
+/// `sustained_secs`, and again with state `cleared` once it recovers.
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourceAlert {
+    pub metric: Metric,
+    pub state: AlertState,
+    pub value: f64,
+    pub peak: f64,
+    pub threshold: f64,
+    /// Unix seconds when the threshold was first exceeded
+    pub since: i64,
+    /// Requests running when the alert was raised, if any
+    pub jobs: Vec<RunningJob>,
+    /// Notification text in the current locale
+    pub message: String,
+}
+
+impl ResourceAlert {
+    fn describe(&self, now: i64) -> String {
+        let metric = self.metric;
+        match self.state {
+            AlertState::Raised => tr!(
+                "alert-raised",
+                metric = metric.label(),
+                threshold = metric.format_value(self.threshold),
+                seconds = now - self.since,
+                value = metric.format_value(self.value),
+            ),
+            AlertState::Cleared => tr!(
+                "alert-cleared",
+                metric = metric.label(),
+                threshold = metric.format_value(self.threshold),
+                peak = metric.format_value(self.peak),
+            ),
+        }
+    }
+}
+
+struct Breach {
+    since: i64,
+    peak: f64,
+    alerted: bool,
+}
+
+/// Tracks how long each metric has been over its threshold.
+#[derive(Default)]
+pub struct Watchdog {
+    breaches: HashMap<Metric, Breach>,
+}
+
+impl Watchdog {
+    /// Feeds one round of readings; missing metrics count as within limits.
+    /// Returns alerts to raise or clear, without `jobs` filled in.
+    pub fn observe(
+        &mut self,
+        config: &WatchdogConfig,
+        readings: &[(Metric, Option<f64>)],
+        now: i64,
+    ) -> Vec<ResourceAlert> {
+        let mut alerts = Vec::new();
+
+        for &(metric, value) in readings {
+            let threshold = metric.threshold(config);
+
+            match value.filter(|value| config.enabled && *value > threshold) {
+                Some(value) => {
+                    let breach = self.breaches.entry(metric).or_insert(Breach {
+                        since: now,
+                        peak: value,
+                        alerted: false,
+                    });
+                    breach.peak = breach.peak.max(value);
+
+                    if !breach.alerted && now - breach.since >= config.sustained_secs {
+                        breach.alerted = true;
+                        alerts.push(ResourceAlert {
+                            metric,
+                            state: AlertState::Raised,
+                            value,
+                            peak: breach.peak,
+                            threshold,
+                            since: breach.since,
+                            jobs: Vec::new(),
+                            message: String::new(),
+                        });
+                    }
+                }
+                None => {
+                    if let Some(breach) = self.breaches.remove(&metric) {
+                        if breach.alerted {
+                            alerts.push(ResourceAlert {
+                                metric,
+                                state: AlertState::Cleared,
+                                value: value.unwrap_or(0.0),
+                                peak: breach.peak,
+                                threshold,
+                                since: breach.since,
+                                jobs: Vec::new(),
+                                message: String::new(),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        for alert in &mut alerts {
+            alert.message = alert.describe(now);
+        }
+        alerts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alert_requires_sustained_breach() {
+        let config = WatchdogConfig { sustained_secs: 30, ..Default::default() };
+        let mut watchdog = Watchdog::default();
+        let memory = |mb: f64| [(Metric::EngineMemory, Some(mb))];
+
+        assert!(watchdog.observe(&config, &memory(5000.0), 0).is_empty());
+        // A dip resets the clock
+        assert!(watchdog.observe(&config, &memory(100.0), 10).is_empty());
+        assert!(watchdog.observe(&config, &memory(5000.0), 20).is_empty());
+        assert!(watchdog.observe(&config, &memory(6000.0), 40).is_empty());
+
+        let raised = watchdog.observe(&config, &memory(5500.0), 50);
+        assert_eq!(raised.len(), 1);
+        assert_eq!(raised[0].state, AlertState::Raised);
+        assert_eq!(raised[0].since, 20);
+        assert_eq!(raised[0].peak, 6000.0);
+
+        // Raised once per breach
+        assert!(watchdog.observe(&config, &memory(5500.0), 60).is_empty());
+
+        let cleared = watchdog.observe(&config, &[(Metric::EngineMemory, None)], 70);
+        assert_eq!(cleared.len(), 1);
+        assert_eq!(cleared[0].state, AlertState::Cleared);
+    }
+}
@@ -0,0 +1,150 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::database::{Project, Workspace, WorkspaceActivity};
+use crate::events::{self, AppEvent};
+use crate::i18n::tr;
+use crate::AppState;
+
+/// Setting key holding the UUID of the workspace to reopen on launch.
+pub const ACTIVE_WORKSPACE_SETTING: &str = "app.active_workspace";
+
+const ACTIVITY_LIMIT: i64 = 50;
+
+/// What the UI shows for a workspace, loaded in one go when it is activated.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceSnapshot {
+    pub workspace: Workspace,
+    pub projects: Vec<Project>,
+    pub recent_activity: Vec<WorkspaceActivity>,
+}
+
+#[derive(Default)]
+struct Selection {
+    workspace: Option<Workspace>,
+    /// Bumped on every switch so a slow preload can't overwrite a newer one
+    generation: u64,
+    snapshot: Option<WorkspaceSnapshot>,
+}
+
+/// The workspace the app is focused on, and its preloaded data once ready.
+#[derive(Default)]
+pub struct ActiveWorkspace {
+    selection: Mutex<Selection>,
+}
+
+impl ActiveWorkspace {
+    pub fn current(&self) -> Option<Workspace> {
+        self.selection.lock().unwrap().workspace.clone()
+    }
+
+    /// `None` until the active workspace has finished preloading.
+    pub fn snapshot(&self) -> Option<WorkspaceSnapshot> {
+        self.selection.lock().unwrap().snapshot.clone()
+    }
+
+    /// Drops the selection if it is `uuid` (any selection when `None`), for
+    /// workspaces removed from the device.
+    pub fn forget(&self, uuid: Option<&str>) {
+        let mut selection = self.selection.lock().unwrap();
+        let matches = match (&selection.workspace, uuid) {
+            (Some(current), Some(uuid)) => current.uuid == uuid,
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+        if matches {
+            selection.generation += 1;
+            selection.workspace = None;
+            selection.snapshot = None;
+        }
+    }
+
+    /// Returns the workspace switched away from and the new generation.
+    fn switch(&self, workspace: Workspace) -> (Option<Workspace>, u64) {
+        let mut selection = self.selection.lock().unwrap();
+        selection.generation += 1;
+        selection.snapshot = None;
+        let previous = selection.workspace.replace(workspace);
+        (previous, selection.generation)
+    }
+
+    fn finish(&self, generation: u64, snapshot: WorkspaceSnapshot) -> bool {
+        let mut selection = self.selection.lock().unwrap();
+        if selection.generation != generation {
+            return false;
+        }
+        selection.snapshot = Some(snapshot);
+        true
+    }
+}
+
+/// Makes a workspace active and returns right away. Its projects and recent
+/// activity load in the background, project engines of the previous
+/// workspace are stopped, and `workspace-activated` is emitted once with
+/// everything the UI needs.
+pub fn activate(app: &AppHandle, uuid: &str) -> Result<Workspace> {
+    let state = app.state::<AppState>();
+    let workspace = state
+        .with_db(|db| db.get_workspace_by_uuid(uuid))?
+        .ok_or_else(|| anyhow::anyhow!(tr!("workspace-not-found", uuid = uuid)))?;
+
+    state.with_db(|db| db.set_setting(ACTIVE_WORKSPACE_SETTING, &workspace.uuid))?;
+    let (previous, generation) = state.workspace.switch(workspace.clone());
+    println!("[NOVEM] Switching to workspace '{}'", workspace.name);
+
+    let app = app.clone();
+    let activated = workspace.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<AppState>();
+
+        if let Some(previous) = previous {
+            release(&state, &previous);
+        }
+
+        match preload(&state, activated) {
+            Ok(snapshot) => {
+                if state.workspace.finish(generation, snapshot.clone()) {
+                    events::publish(&app, AppEvent::WorkspaceActivated(snapshot));
+                }
+            }
+            Err(e) => eprintln!("[ERROR] Failed to preload workspace: {}", e),
+        }
+    });
+
+    Ok(workspace)
+}
+
+fn preload(state: &AppState, workspace: Workspace) -> Result<WorkspaceSnapshot> {
+    state.with_db(|db| {
+        Ok(WorkspaceSnapshot {
+            projects: db.get_workspace_projects(workspace.id)?,
+            recent_activity: db.get_workspace_activity(workspace.id, ACTIVITY_LIMIT)?,
+            workspace,
+        })
+    })
+}
+
+/// Stops the project engines and tunnels a workspace left running, unless
+/// the user has already switched back to it.
+fn release(state: &AppState, workspace: &Workspace) {
+    if state.workspace.current().map(|current| current.id) == Some(workspace.id) {
+        return;
+    }
+
+    let projects = match state.with_db(|db| db.get_workspace_projects(workspace.id)) {
+        Ok(projects) => projects,
+        Err(e) => {
+            eprintln!("[ERROR] Failed to list projects of workspace '{}': {}", workspace.name, e);
+            return;
+        }
+    };
+
+    for project in projects {
+        state.engines.tunnels().close(Some(project.id));
+        if let Err(e) = state.engines.stop_project(project.id) {
+            eprintln!("[ERROR] Failed to stop engine for project {}: {}", project.id, e);
+        }
+    }
+}
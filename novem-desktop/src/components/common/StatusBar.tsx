@@ -1,4 +1,5 @@
 import React, { useEffect, useState } from 'react';
+import { listen } from '@tauri-apps/api/event';
 import { Space, Typography, Tooltip, Progress } from 'antd';
 import {
   CheckCircleOutlined,
@@ -14,7 +15,7 @@ import {
 import { useTheme } from '../../contexts/ThemeContext';
 import { useAuth } from '../../contexts/AuthContext';
 import { colors } from '../../theme/config';
-import { tauriCommands, SystemResources, HealthResponse, isTauri } from '../../types/tauri';
+import { tauriCommands, SystemResources, HealthResponse, EngineHeartbeat, isTauri } from '../../types/tauri';
 import { backendAPI } from '../../services/api';
 
 const { Text } = Typography;
@@ -42,6 +43,7 @@ const StatusBar: React.FC = () => {
   const [systemResources, setSystemResources] = useState<SystemResources | null>(null);
   const [lastCheck, setLastCheck] = useState<Date>(new Date());
   const [isTauriEnv, setIsTauriEnv] = useState<boolean>(false);
+  const [heartbeat, setHeartbeat] = useState<EngineHeartbeat | null>(null);
 
   const isDark = theme === 'dark';
 
@@ -70,6 +72,29 @@ const StatusBar: React.FC = () => {
     return () => clearInterval(interval);
   }, [isTauriEnv]);
 
+  // The shared engine's heartbeat; Rust only emits when the miss streak changes
+  useEffect(() => {
+    if (!isTauri) return;
+
+    tauriCommands.getEngineHeartbeat().then(setHeartbeat).catch(() => setHeartbeat(null));
+
+    const unlisten = listen<EngineHeartbeat>('engine-heartbeat', (event) => {
+      if (event.payload.project_id === null) {
+        setHeartbeat(event.payload);
+      }
+    });
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, []);
+
+  const heartbeatColor = () => {
+    if (!heartbeat) return colors.textTertiary;
+    if (!heartbeat.alive) return colors.error;
+    if (heartbeat.consecutive_misses > 0) return colors.warning;
+    return colors.success;
+  };
+
   const checkAllServices = async () => {
     const checkTime = new Date();
     
@@ -366,6 +391,13 @@ const StatusBar: React.FC = () => {
                   Last check: {new Date(serviceHealth.computeEngine.data.timestamp).toLocaleTimeString()}
                 </div>
               )}
+              {heartbeat && (
+                <div style={{ color: 'rgba(255,255,255,0.65)', marginTop: '4px' }}>
+                  Heartbeat: {heartbeat.consecutive_misses > 0
+                    ? `${heartbeat.consecutive_misses} missed`
+                    : `${heartbeat.last_rtt_ms ?? '-'} ms`}
+                </div>
+              )}
             </div>
           }
           placement="top"
@@ -405,6 +437,16 @@ const StatusBar: React.FC = () => {
               FastAPI
             </Text>
             {getStatusIcon(serviceHealth.computeEngine.status)}
+            {heartbeat && (
+              <span
+                style={{
+                  width: '6px',
+                  height: '6px',
+                  borderRadius: '50%',
+                  backgroundColor: heartbeatColor(),
+                }}
+              />
+            )}
           </div>
         </Tooltip>
 
@@ -3,19 +3,33 @@ import { invoke } from '@tauri-apps/api/core';
 class EmbeddedComputeEngine {
   async checkHealth(): Promise<boolean> {
     try {
-      return await invoke<boolean>('get_engine_status');
+      const status = await invoke<string>('get_engine_status');
+      return status === 'ready';
     } catch (error) {
       console.error('Failed to check engine status:', error);
       return false;
     }
   }
 
-  async getWorkspaces(userId: number) {
-    return await invoke('get_workspaces', { userId });
+  // Paged, most recently updated first; pass a page's nextCursor back as cursor.
+  // Archived workspaces are listed only when archived is true.
+  async getWorkspaces(userId: number, page?: { cursor?: string; limit?: number }, archived?: boolean) {
+    return await invoke('get_workspaces', { userId, page, archived });
   }
 
-  async getProjects(workspaceId: number, userId: number) {
-    return await invoke('get_projects', { workspaceId, userId });
+  async getProjects(
+    workspaceId: number,
+    userId: number,
+    query?: {
+      sort?: 'name' | 'created_at' | 'updated_at';
+      direction?: 'asc' | 'desc';
+      name?: string;
+      sync_status?: string;
+      owner_id?: number;
+    },
+    page?: { cursor?: string; limit?: number }
+  ) {
+    return await invoke('get_projects', { workspaceId, userId, query, page });
   }
 
   async executePython(code: string): Promise<string> {
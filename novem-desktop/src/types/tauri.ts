@@ -16,6 +16,14 @@ export interface HealthResponse {
   database?: string;
 }
 
+export interface EngineHeartbeat {
+  project_id: number | null;
+  last_rtt_ms: number | null;
+  last_seen_at: string | null;
+  consecutive_misses: number;
+  alive: boolean;
+}
+
 export interface ServiceStatus {
   name: string;
   status: 'online' | 'offline' | 'checking';
@@ -71,6 +79,17 @@ export const tauriCommands = {
     }
   },
 
+  getEngineHeartbeat: async (projectId?: number): Promise<EngineHeartbeat> => {
+    try {
+      const result = await invoke<EngineHeartbeat>('get_engine_heartbeat', {
+        projectId: projectId ?? null,
+      });
+      return result;
+    } catch (error) {
+      throw new Error(error as string);
+    }
+  },
+
   callComputeEngine: async (
     endpoint: string,
     method: 'GET' | 'POST' | 'PUT' | 'DELETE' | 'PATCH',